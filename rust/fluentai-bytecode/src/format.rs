@@ -0,0 +1,832 @@
+//! Binary `.fabc` bytecode file format
+//!
+//! A `.fabc` file stores a complete [`Bytecode`] module: every chunk with its
+//! instructions, constant pool, line numbers and source map, the module-wide
+//! source map and the `extern` declarations. All integers are little-endian.
+//!
+//! ```text
+//! file       := "FABC" version:u16 main_chunk:u32 externs chunks module_map
+//! externs    := count:u32 (name:string arity:u32)*
+//! chunks     := count:u32 chunk*
+//! chunk      := name:option<string> arity:u32
+//!               count:u32 (opcode:u8 arg:u32)*
+//!               count:u32 value*
+//!               count:u32 line:u32*
+//!               option<source_map>
+//! module_map := option<count:u32 source_map*>
+//! source_map := filename:option<string> source:option<string>
+//!               count:u32 (offset:u32 start:u64 end:u64 line:option<u32> column:option<u32>)*
+//!               count:u32 (offset:u32 node:u32)*
+//! value      := tag:u8 payload
+//! string     := length:u32 utf8-bytes
+//! option<T>  := 0 | 1 T
+//! ```
+//!
+//! Opcodes are written with the fixed codes of [`Opcode::to_byte`], so adding
+//! opcodes to the enum does not change the meaning of existing files. Any other
+//! change to the layout must bump [`FORMAT_VERSION`]; readers reject files
+//! written with a different version.
+//!
+//! Loading a file only checks that it is well-formed. Use the VM's verifier
+//! before executing bytecode from an untrusted source.
+
+use crate::source_map::{ModuleSourceMap, SourceLocation, SourceMap};
+use crate::{Bytecode, BytecodeChunk, ExternDecl, Instruction, Opcode};
+use fluentai_core::ast::NodeId;
+use fluentai_core::value::{BigInt, Decimal, Value};
+use rustc_hash::FxHashMap;
+
+/// Magic bytes at the start of every `.fabc` file
+pub const MAGIC: &[u8; 4] = b"FABC";
+
+/// Version of the layout written by [`Bytecode::to_bytes`]
+pub const FORMAT_VERSION: u16 = 1;
+
+/// File extension for serialized bytecode modules
+pub const FILE_EXTENSION: &str = "fabc";
+
+/// Constants nested deeper than this are rejected when reading
+const MAX_VALUE_DEPTH: usize = 256;
+
+// Constant pool value tags
+const TAG_NIL: u8 = 0;
+const TAG_BOOLEAN: u8 = 1;
+const TAG_INTEGER: u8 = 2;
+const TAG_FLOAT: u8 = 3;
+const TAG_BIGINT: u8 = 4;
+const TAG_DECIMAL: u8 = 5;
+const TAG_STRING: u8 = 6;
+const TAG_BYTES: u8 = 7;
+const TAG_SYMBOL: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_TUPLE: u8 = 10;
+const TAG_VECTOR: u8 = 11;
+const TAG_MAP: u8 = 12;
+const TAG_TAGGED: u8 = 13;
+const TAG_STRUCT: u8 = 14;
+const TAG_FUNCTION: u8 = 15;
+const TAG_ERROR: u8 = 16;
+const TAG_MODULE: u8 = 17;
+
+/// Errors produced while reading or writing `.fabc` data
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FormatError {
+    /// The data does not start with [`MAGIC`]
+    #[error("not a FluentAi bytecode file")]
+    BadMagic,
+
+    /// The file was written with a different [`FORMAT_VERSION`]
+    #[error("unsupported bytecode format version {found} (expected {expected})")]
+    UnsupportedVersion { found: u16, expected: u16 },
+
+    /// The data ended in the middle of an item
+    #[error("unexpected end of bytecode data")]
+    UnexpectedEof,
+
+    /// An instruction uses an opcode code with no [`Opcode`]
+    #[error("unknown opcode {0}")]
+    UnknownOpcode(u8),
+
+    /// A constant uses an unknown value tag
+    #[error("unknown constant tag {0}")]
+    UnknownValueTag(u8),
+
+    /// A constant holds a runtime-only value such as a channel or native function
+    #[error("constants of type {0} cannot be serialized")]
+    UnsupportedValue(&'static str),
+
+    /// The data is structurally invalid
+    #[error("malformed bytecode data: {0}")]
+    Malformed(String),
+}
+
+/// Result type for `.fabc` reading and writing
+pub type FormatResult<T> = Result<T, FormatError>;
+
+/// Define the fixed wire code of every opcode
+macro_rules! opcode_codes {
+    ($($opcode:ident = $code:literal),* $(,)?) => {
+        impl Opcode {
+            /// The opcode's fixed code in the `.fabc` format
+            pub fn to_byte(self) -> u8 {
+                match self {
+                    $(Opcode::$opcode => $code,)*
+                }
+            }
+
+            /// Look up an opcode by its `.fabc` code
+            pub fn from_byte(code: u8) -> Option<Opcode> {
+                match code {
+                    $($code => Some(Opcode::$opcode),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+// New opcodes take the next free code; existing codes must never change
+opcode_codes! {
+    Push = 0, Pop = 1, Dup = 2, Swap = 3, Add = 4, Sub = 5, Mul = 6, Div = 7, Mod = 8,
+    Neg = 9, AddInt = 10, SubInt = 11, MulInt = 12, DivInt = 13, Eq = 14, Ne = 15, Lt = 16,
+    Le = 17, Gt = 18, Ge = 19, LtInt = 20, LeInt = 21, GtInt = 22, GeInt = 23, And = 24,
+    Or = 25, Not = 26, Jump = 27, JumpIf = 28, JumpIfNot = 29, Call = 30, Return = 31,
+    Load = 32, Store = 33, LoadGlobal = 34, StoreGlobal = 35, LoadLocal0 = 36,
+    LoadLocal1 = 37, LoadLocal2 = 38, LoadLocal3 = 39, StoreLocal0 = 40, StoreLocal1 = 41,
+    StoreLocal2 = 42, StoreLocal3 = 43, MakeFunc = 44, MakeEnv = 45, PopEnv = 46,
+    MakeList = 47, ListHead = 48, ListTail = 49, ListCons = 50, ListLen = 51,
+    ListEmpty = 52, StrLen = 53, StrConcat = 54, StrUpper = 55, StrLower = 56,
+    PushInt0 = 57, PushInt1 = 58, PushInt2 = 59, PushIntSmall = 60, PushTrue = 61,
+    PushFalse = 62, PushNil = 63, PushConst = 64, Effect = 65, EffectAsync = 66, Await = 67,
+    Spawn = 68, Channel = 69, ChannelWithCapacity = 70, Send = 71, Receive = 72,
+    TrySend = 73, TryReceive = 74, Halt = 75, Nop = 76, MakeClosure = 77, LoadCaptured = 78,
+    PopN = 79, MakeCell = 80, CellGet = 81, CellSet = 82, MakeTagged = 83, GetTag = 84,
+    GetTaggedField = 85, IsTagged = 86, LoadModule = 87, ImportBinding = 88,
+    LoadQualified = 89, BeginModule = 90, EndModule = 91, ExportBinding = 92, AddFloat = 93,
+    SubFloat = 94, MulFloat = 95, DivFloat = 96, MakeHandler = 97, InstallHandler = 98,
+    UninstallHandler = 99, ImportAll = 100, GcAlloc = 101, GcDeref = 102, GcSet = 103,
+    GcCollect = 104, TailCall = 105, TailReturn = 106, LoopStart = 107, LoopEnd = 108,
+    UpdateLocal = 109, Select = 110, CreateActor = 111, ActorSend = 112, ActorReceive = 113,
+    Become = 114, Try = 115, Catch = 116, Finally = 117, Throw = 118, PushHandler = 119,
+    PopHandler = 120, PromiseNew = 121, PromiseAll = 122, PromiseRace = 123,
+    WithTimeout = 124, MakeFuture = 125, PushFinally = 126, EndFinally = 127,
+    LoadLocal = 128, StoreLocal = 129, DefineGlobal = 130, LoadUpvalue = 131,
+    StoreUpvalue = 132, LoadCell = 133, StoreCell = 134, ListGet = 135, ListSet = 136,
+    MakeMap = 137, MapGet = 138, MapSet = 139, MakeChannel = 140, MakeActor = 141,
+    Perform = 142, Resume = 143, TryStart = 144, TryStartWithFinally = 145, TryEnd = 146,
+    FinallyStart = 147, FinallyEnd = 148, MakeStruct = 149, GetField = 150, SetField = 151,
+    DefineMethod = 152, CallMethod = 153, Parallel = 154, MatchError = 155, MakeTuple = 156,
+    TupleGet = 157, IsTuple = 158,
+}
+
+impl Bytecode {
+    /// Serialize the module in the `.fabc` format
+    pub fn to_bytes(&self) -> FormatResult<Vec<u8>> {
+        let mut w = Writer::default();
+        w.buf.extend_from_slice(MAGIC);
+        w.u16(FORMAT_VERSION);
+        w.len(self.main_chunk)?;
+
+        w.len(self.externs.len())?;
+        for decl in &self.externs {
+            w.string(&decl.name)?;
+            w.len(decl.arity)?;
+        }
+
+        w.len(self.chunks.len())?;
+        for chunk in &self.chunks {
+            w.chunk(chunk)?;
+        }
+
+        match &self.module_source_map {
+            Some(map) => {
+                w.u8(1);
+                w.len(map.chunk_maps.len())?;
+                for chunk_map in &map.chunk_maps {
+                    w.source_map(chunk_map)?;
+                }
+            }
+            None => w.u8(0),
+        }
+
+        Ok(w.buf)
+    }
+
+    /// Deserialize a module written by [`Bytecode::to_bytes`]
+    pub fn from_bytes(bytes: &[u8]) -> FormatResult<Self> {
+        let mut r = Reader { bytes, pos: 0 };
+        if r.take(MAGIC.len()).map_err(|_| FormatError::BadMagic)? != MAGIC {
+            return Err(FormatError::BadMagic);
+        }
+        let version = r.u16()?;
+        if version != FORMAT_VERSION {
+            return Err(FormatError::UnsupportedVersion {
+                found: version,
+                expected: FORMAT_VERSION,
+            });
+        }
+
+        let mut bytecode = Bytecode::new();
+        bytecode.main_chunk = r.len()?;
+
+        for _ in 0..r.len()? {
+            let name = r.string()?;
+            let arity = r.len()?;
+            bytecode.externs.push(ExternDecl { name, arity });
+        }
+
+        for _ in 0..r.len()? {
+            let chunk = r.chunk()?;
+            bytecode.chunks.push(chunk);
+        }
+
+        if r.flag()? {
+            let mut map = ModuleSourceMap::new();
+            for _ in 0..r.len()? {
+                map.add_chunk_map(r.source_map()?);
+            }
+            bytecode.module_source_map = Some(map);
+        }
+
+        if r.pos != bytes.len() {
+            return Err(FormatError::Malformed(format!(
+                "{} trailing bytes",
+                bytes.len() - r.pos
+            )));
+        }
+        Ok(bytecode)
+    }
+}
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn u8(&mut self, n: u8) {
+        self.buf.push(n);
+    }
+
+    fn u16(&mut self, n: u16) {
+        self.buf.extend_from_slice(&n.to_le_bytes());
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.buf.extend_from_slice(&n.to_le_bytes());
+    }
+
+    fn u64(&mut self, n: u64) {
+        self.buf.extend_from_slice(&n.to_le_bytes());
+    }
+
+    /// Write a count, index or offset as a u32
+    fn len(&mut self, n: usize) -> FormatResult<()> {
+        let n = u32::try_from(n)
+            .map_err(|_| FormatError::Malformed(format!("{} does not fit in 32 bits", n)))?;
+        self.u32(n);
+        Ok(())
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) -> FormatResult<()> {
+        self.len(bytes.len())?;
+        self.buf.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    fn string(&mut self, s: &str) -> FormatResult<()> {
+        self.bytes(s.as_bytes())
+    }
+
+    fn opt_string(&mut self, s: Option<&str>) -> FormatResult<()> {
+        match s {
+            Some(s) => {
+                self.u8(1);
+                self.string(s)
+            }
+            None => {
+                self.u8(0);
+                Ok(())
+            }
+        }
+    }
+
+    fn opt_u32(&mut self, n: Option<u32>) {
+        match n {
+            Some(n) => {
+                self.u8(1);
+                self.u32(n);
+            }
+            None => self.u8(0),
+        }
+    }
+
+    fn chunk(&mut self, chunk: &BytecodeChunk) -> FormatResult<()> {
+        self.opt_string(chunk.name.as_deref())?;
+        self.len(chunk.arity)?;
+
+        self.len(chunk.instructions.len())?;
+        for instruction in &chunk.instructions {
+            self.u8(instruction.opcode.to_byte());
+            self.u32(instruction.arg);
+        }
+
+        self.values(&chunk.constants)?;
+
+        self.len(chunk.line_numbers.len())?;
+        for line in &chunk.line_numbers {
+            self.u32(*line);
+        }
+
+        match &chunk.source_map {
+            Some(map) => {
+                self.u8(1);
+                self.source_map(map)
+            }
+            None => {
+                self.u8(0);
+                Ok(())
+            }
+        }
+    }
+
+    fn source_map(&mut self, map: &SourceMap) -> FormatResult<()> {
+        self.opt_string(map.filename.as_deref())?;
+        self.opt_string(map.source_text.as_deref())?;
+
+        let locations = map.locations();
+        self.len(locations.len())?;
+        for (offset, location) in locations {
+            self.len(offset)?;
+            self.u64(location.start as u64);
+            self.u64(location.end as u64);
+            self.opt_u32(location.line);
+            self.opt_u32(location.column);
+        }
+
+        let nodes = map.nodes();
+        self.len(nodes.len())?;
+        for (offset, node) in nodes {
+            self.len(offset)?;
+            self.u32(node.get());
+        }
+        Ok(())
+    }
+
+    fn values(&mut self, values: &[Value]) -> FormatResult<()> {
+        self.len(values.len())?;
+        for value in values {
+            self.value(value)?;
+        }
+        Ok(())
+    }
+
+    /// Write map entries sorted by key so the output is deterministic
+    fn map(&mut self, map: &FxHashMap<String, Value>) -> FormatResult<()> {
+        let mut entries: Vec<_> = map.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        self.len(entries.len())?;
+        for (key, value) in entries {
+            self.string(key)?;
+            self.value(value)?;
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, value: &Value) -> FormatResult<()> {
+        match value {
+            Value::Nil => self.u8(TAG_NIL),
+            Value::Boolean(b) => {
+                self.u8(TAG_BOOLEAN);
+                self.u8(*b as u8);
+            }
+            Value::Integer(n) => {
+                self.u8(TAG_INTEGER);
+                self.u64(*n as u64);
+            }
+            Value::Float(f) => {
+                self.u8(TAG_FLOAT);
+                self.u64(f.to_bits());
+            }
+            Value::BigInt(n) => {
+                self.u8(TAG_BIGINT);
+                self.bytes(&n.to_signed_bytes_le())?;
+            }
+            Value::Decimal(d) => {
+                self.u8(TAG_DECIMAL);
+                self.buf.extend_from_slice(&d.serialize());
+            }
+            Value::String(s) => {
+                self.u8(TAG_STRING);
+                self.string(s)?;
+            }
+            Value::Bytes(b) => {
+                self.u8(TAG_BYTES);
+                self.bytes(b)?;
+            }
+            Value::Symbol(s) => {
+                self.u8(TAG_SYMBOL);
+                self.string(s)?;
+            }
+            Value::List(items) => {
+                self.u8(TAG_LIST);
+                self.values(items)?;
+            }
+            Value::Tuple(items) => {
+                self.u8(TAG_TUPLE);
+                self.values(items)?;
+            }
+            Value::Vector(items) => {
+                self.u8(TAG_VECTOR);
+                self.values(items)?;
+            }
+            Value::Map(map) => {
+                self.u8(TAG_MAP);
+                self.map(map)?;
+            }
+            Value::Tagged { tag, values } => {
+                self.u8(TAG_TAGGED);
+                self.string(tag)?;
+                self.values(values)?;
+            }
+            Value::Struct { name, fields } => {
+                self.u8(TAG_STRUCT);
+                self.string(name)?;
+                self.len(fields.len())?;
+                for (field, value) in fields {
+                    self.string(field)?;
+                    self.value(value)?;
+                }
+            }
+            Value::Function { chunk_id, env } => {
+                self.u8(TAG_FUNCTION);
+                self.len(*chunk_id)?;
+                self.values(env)?;
+            }
+            Value::Error {
+                kind,
+                message,
+                stack_trace,
+            } => {
+                self.u8(TAG_ERROR);
+                self.string(kind)?;
+                self.string(message)?;
+                match stack_trace {
+                    Some(frames) => {
+                        self.u8(1);
+                        self.len(frames.len())?;
+                        for frame in frames {
+                            self.string(frame)?;
+                        }
+                    }
+                    None => self.u8(0),
+                }
+            }
+            Value::Module { name, exports } => {
+                self.u8(TAG_MODULE);
+                self.string(name)?;
+                self.map(exports)?;
+            }
+            Value::Procedure(_)
+            | Value::NativeFunction { .. }
+            | Value::Promise(_)
+            | Value::Future { .. }
+            | Value::Channel(_)
+            | Value::Actor(_)
+            | Value::Cell(_)
+            | Value::GcHandle(_) => return Err(FormatError::UnsupportedValue(value.type_name())),
+        }
+        Ok(())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> FormatResult<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(FormatError::UnexpectedEof)?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> FormatResult<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn u8(&mut self) -> FormatResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> FormatResult<u16> {
+        self.array().map(u16::from_le_bytes)
+    }
+
+    fn u32(&mut self) -> FormatResult<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> FormatResult<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn len(&mut self) -> FormatResult<usize> {
+        self.u32().map(|n| n as usize)
+    }
+
+    fn flag(&mut self) -> FormatResult<bool> {
+        match self.u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            n => Err(FormatError::Malformed(format!("invalid flag byte {}", n))),
+        }
+    }
+
+    fn bytes(&mut self) -> FormatResult<Vec<u8>> {
+        let len = self.len()?;
+        Ok(self.take(len)?.to_vec())
+    }
+
+    fn string(&mut self) -> FormatResult<String> {
+        String::from_utf8(self.bytes()?)
+            .map_err(|_| FormatError::Malformed("string is not valid UTF-8".to_string()))
+    }
+
+    fn opt_string(&mut self) -> FormatResult<Option<String>> {
+        if self.flag()? {
+            self.string().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn opt_u32(&mut self) -> FormatResult<Option<u32>> {
+        if self.flag()? {
+            self.u32().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn chunk(&mut self) -> FormatResult<BytecodeChunk> {
+        let mut chunk = BytecodeChunk::new(self.opt_string()?);
+        chunk.arity = self.len()?;
+
+        for _ in 0..self.len()? {
+            let code = self.u8()?;
+            let opcode = Opcode::from_byte(code).ok_or(FormatError::UnknownOpcode(code))?;
+            let arg = self.u32()?;
+            chunk.instructions.push(Instruction { opcode, arg });
+        }
+
+        chunk.constants = self.values(0)?;
+
+        for _ in 0..self.len()? {
+            let line = self.u32()?;
+            chunk.line_numbers.push(line);
+        }
+
+        if self.flag()? {
+            chunk.source_map = Some(self.source_map()?);
+        }
+        Ok(chunk)
+    }
+
+    fn source_map(&mut self) -> FormatResult<SourceMap> {
+        let mut map = SourceMap::new();
+        map.filename = self.opt_string()?;
+        map.source_text = self.opt_string()?;
+
+        for _ in 0..self.len()? {
+            let offset = self.len()?;
+            let location = SourceLocation {
+                start: self.u64()? as usize,
+                end: self.u64()? as usize,
+                line: self.opt_u32()?,
+                column: self.opt_u32()?,
+            };
+            map.add_instruction_location(offset, location);
+        }
+
+        for _ in 0..self.len()? {
+            let offset = self.len()?;
+            let node = NodeId::new(self.u32()?)
+                .ok_or_else(|| FormatError::Malformed("node id 0 in source map".to_string()))?;
+            map.add_instruction_node(offset, node);
+        }
+        Ok(map)
+    }
+
+    fn values(&mut self, depth: usize) -> FormatResult<Vec<Value>> {
+        // Counts come from untrusted data, so grow the vector as items are read
+        let mut values = Vec::new();
+        for _ in 0..self.len()? {
+            values.push(self.value(depth)?);
+        }
+        Ok(values)
+    }
+
+    fn map(&mut self, depth: usize) -> FormatResult<FxHashMap<String, Value>> {
+        let mut map = FxHashMap::default();
+        for _ in 0..self.len()? {
+            let key = self.string()?;
+            let value = self.value(depth)?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+
+    fn value(&mut self, depth: usize) -> FormatResult<Value> {
+        if depth > MAX_VALUE_DEPTH {
+            return Err(FormatError::Malformed(
+                "constant is nested too deeply".to_string(),
+            ));
+        }
+        let depth = depth + 1;
+
+        Ok(match self.u8()? {
+            TAG_NIL => Value::Nil,
+            TAG_BOOLEAN => Value::Boolean(self.flag()?),
+            TAG_INTEGER => Value::Integer(self.u64()? as i64),
+            TAG_FLOAT => Value::Float(f64::from_bits(self.u64()?)),
+            TAG_BIGINT => Value::BigInt(BigInt::from_signed_bytes_le(&self.bytes()?)),
+            TAG_DECIMAL => Value::Decimal(Decimal::deserialize(self.array()?)),
+            TAG_STRING => Value::String(self.string()?),
+            TAG_BYTES => Value::Bytes(self.bytes()?),
+            TAG_SYMBOL => Value::Symbol(self.string()?),
+            TAG_LIST => Value::List(self.values(depth)?),
+            TAG_TUPLE => Value::Tuple(self.values(depth)?),
+            TAG_VECTOR => Value::Vector(self.values(depth)?),
+            TAG_MAP => Value::Map(self.map(depth)?),
+            TAG_TAGGED => Value::Tagged {
+                tag: self.string()?,
+                values: self.values(depth)?,
+            },
+            TAG_STRUCT => {
+                let name = self.string()?;
+                let mut fields = Vec::new();
+                for _ in 0..self.len()? {
+                    let field = self.string()?;
+                    fields.push((field, self.value(depth)?));
+                }
+                Value::Struct { name, fields }
+            }
+            TAG_FUNCTION => Value::Function {
+                chunk_id: self.len()?,
+                env: self.values(depth)?,
+            },
+            TAG_ERROR => {
+                let kind = self.string()?;
+                let message = self.string()?;
+                let stack_trace = if self.flag()? {
+                    let mut frames = Vec::new();
+                    for _ in 0..self.len()? {
+                        frames.push(self.string()?);
+                    }
+                    Some(frames)
+                } else {
+                    None
+                };
+                Value::Error {
+                    kind,
+                    message,
+                    stack_trace,
+                }
+            }
+            TAG_MODULE => Value::Module {
+                name: self.string()?,
+                exports: self.map(depth)?,
+            },
+            tag => return Err(FormatError::UnknownValueTag(tag)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn sample() -> Bytecode {
+        let mut bytecode = Bytecode::new();
+
+        let mut main = BytecodeChunk::new(Some("main".to_string()));
+        main.add_constant(Value::BigInt(BigInt::from(u64::MAX) * 3));
+        main.add_constant(Value::Decimal(Decimal::from_str("-12.345").unwrap()));
+        main.add_constant(Value::Tuple(vec![
+            Value::Float(1.5),
+            Value::Bytes(vec![0, 255]),
+            Value::Symbol("ok".to_string()),
+        ]));
+        let mut map = FxHashMap::default();
+        map.insert("b".to_string(), Value::Nil);
+        map.insert("a".to_string(), Value::List(vec![Value::Integer(-1)]));
+        main.add_constant(Value::Map(map));
+        main.add_constant(Value::Struct {
+            name: "Point".to_string(),
+            fields: vec![("x".to_string(), Value::Integer(1))],
+        });
+        main.add_instruction(Instruction::with_arg(Opcode::PushConst, 0));
+        main.add_instruction(Instruction::with_arg(Opcode::MakeFunc, 1));
+        main.add_instruction(Instruction::with_arg(Opcode::Call, 0));
+        main.add_instruction(Instruction::new(Opcode::Halt));
+        main.add_line(3);
+
+        let mut source_map = SourceMap::with_filename("main.flc".to_string());
+        source_map.add_instruction_location(0, SourceLocation::with_line_col(0, 4, 1, 1));
+        source_map.add_instruction_node(0, NodeId::new(7).unwrap());
+        main.source_map = Some(source_map);
+        bytecode.add_chunk(main);
+
+        let mut lambda = BytecodeChunk::new(None);
+        lambda.arity = 2;
+        lambda.add_instruction(Instruction::new(Opcode::LoadLocal0));
+        lambda.add_instruction(Instruction::new(Opcode::Return));
+        bytecode.add_chunk(lambda);
+
+        bytecode.externs.push(ExternDecl {
+            name: "host_log".to_string(),
+            arity: 1,
+        });
+        bytecode
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let original = sample();
+        let bytes = original.to_bytes().unwrap();
+        assert_eq!(&bytes[..4], MAGIC);
+
+        let loaded = Bytecode::from_bytes(&bytes).unwrap();
+        assert_eq!(loaded.main_chunk, original.main_chunk);
+        assert_eq!(loaded.externs, original.externs);
+        assert_eq!(loaded.chunks.len(), 2);
+        for (a, b) in loaded.chunks.iter().zip(&original.chunks) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.arity, b.arity);
+            assert_eq!(a.instructions, b.instructions);
+            assert_eq!(a.constants, b.constants);
+            assert_eq!(a.line_numbers, b.line_numbers);
+        }
+
+        let map = loaded.chunks[0].source_map.as_ref().unwrap();
+        assert_eq!(map.filename.as_deref(), Some("main.flc"));
+        assert_eq!(
+            map.get_location(0),
+            Some(&SourceLocation::with_line_col(0, 4, 1, 1))
+        );
+        assert_eq!(map.get_node(0), NodeId::new(7));
+
+        // Writing is deterministic even with hash map constants
+        assert_eq!(loaded.to_bytes().unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_opcode_codes_round_trip() {
+        for code in 0..=u8::MAX {
+            if let Some(opcode) = Opcode::from_byte(code) {
+                assert_eq!(opcode.to_byte(), code);
+            }
+        }
+        assert_eq!(Opcode::Push.to_byte(), 0);
+        assert_eq!(Opcode::from_byte(u8::MAX), None);
+    }
+
+    #[test]
+    fn test_rejects_bad_header() {
+        assert_eq!(
+            Bytecode::from_bytes(b"ELF\x7f").unwrap_err(),
+            FormatError::BadMagic
+        );
+        assert_eq!(
+            Bytecode::from_bytes(b"FA").unwrap_err(),
+            FormatError::BadMagic
+        );
+
+        let mut bytes = sample().to_bytes().unwrap();
+        bytes[4] = 99;
+        assert_eq!(
+            Bytecode::from_bytes(&bytes).unwrap_err(),
+            FormatError::UnsupportedVersion {
+                found: 99,
+                expected: FORMAT_VERSION
+            }
+        );
+    }
+
+    #[test]
+    fn test_rejects_truncated_and_trailing_data() {
+        let bytes = sample().to_bytes().unwrap();
+        for len in 6..bytes.len() {
+            assert!(Bytecode::from_bytes(&bytes[..len]).is_err());
+        }
+
+        let mut padded = bytes.clone();
+        padded.push(0);
+        assert!(matches!(
+            Bytecode::from_bytes(&padded),
+            Err(FormatError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_runtime_values_are_not_serializable() {
+        let mut bytecode = Bytecode::new();
+        let mut chunk = BytecodeChunk::new(None);
+        chunk.add_constant(Value::Channel(1));
+        bytecode.add_chunk(chunk);
+        assert_eq!(
+            bytecode.to_bytes().unwrap_err(),
+            FormatError::UnsupportedValue("channel")
+        );
+    }
+}
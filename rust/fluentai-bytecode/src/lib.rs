@@ -4,6 +4,7 @@
 
 #![warn(missing_docs)]
 
+pub mod format;
 pub mod source_map;
 
 use fluentai_core::value::Value;
@@ -105,6 +106,11 @@ pub enum Opcode {
     ListLen,
     ListEmpty,
 
+    // Tuples
+    MakeTuple, // Create tuple from N stack values
+    TupleGet,  // Get element N of a tuple
+    IsTuple,   // Check if value is a tuple of arity N (leaves the value beneath)
+
     // Maps
     MakeMap,
     MapGet,
@@ -169,6 +175,7 @@ pub enum Opcode {
     PromiseAll,   // Wait for all promises
     PromiseRace,  // Race multiple promises
     WithTimeout,  // Add timeout to promise
+    Parallel,     // Run N closures concurrently, producing the list of their results
 
     // Effect handlers
     MakeHandler,      // Create handler table from stack values
@@ -187,6 +194,16 @@ pub enum Opcode {
     GetTag,         // Get tag from tagged value
     GetTaggedField, // Get field N from tagged value
     IsTagged,       // Check if value is tagged with specific tag
+    MatchError,     // Raise an error for a value no pattern matched
+
+    // Structs (records)
+    MakeStruct, // Create struct: layout const (upper 16 bits) and field count (lower 16 bits)
+    GetField,   // Get named field (string const) from struct or map
+    SetField,   // Copy struct with named field (string const) replaced
+
+    // Traits
+    DefineMethod, // Register method impl: type name const (upper 16 bits), method name const (lower 16 bits)
+    CallMethod,   // Dispatch on receiver type: method name const (upper 16 bits), arg count incl. receiver (lower 16 bits)
 
     // Module operations
     LoadModule,    // Load module by name (string const)
@@ -215,7 +232,7 @@ pub enum Opcode {
     Nop,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Instruction {
     pub opcode: Opcode,
     pub arg: u32,
@@ -240,6 +257,8 @@ pub struct BytecodeChunk {
     pub line_numbers: Vec<u32>,
     /// Optional source map for this chunk
     pub source_map: Option<source_map::SourceMap>,
+    /// Number of arguments on the stack when the chunk is entered
+    pub arity: usize,
 }
 
 impl BytecodeChunk {
@@ -250,6 +269,7 @@ impl BytecodeChunk {
             name,
             line_numbers: Vec::new(),
             source_map: None,
+            arity: 0,
         }
     }
 
@@ -274,12 +294,21 @@ impl BytecodeChunk {
     }
 }
 
+/// A foreign function the program expects the host to provide
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternDecl {
+    pub name: String,
+    pub arity: usize,
+}
+
 #[derive(Debug, Clone)]
 pub struct Bytecode {
     pub chunks: Vec<BytecodeChunk>,
     pub main_chunk: usize,
     /// Optional module-wide source map
     pub module_source_map: Option<source_map::ModuleSourceMap>,
+    /// `extern` declarations, bound to host functions before the program runs
+    pub externs: Vec<ExternDecl>,
 }
 
 impl Bytecode {
@@ -288,6 +317,7 @@ impl Bytecode {
             chunks: Vec::new(),
             main_chunk: 0,
             module_source_map: None,
+            externs: Vec::new(),
         }
     }
 
@@ -88,6 +88,20 @@ impl SourceMap {
         self.node_map.get(&offset).copied()
     }
     
+    /// All instruction locations, ordered by instruction offset
+    pub fn locations(&self) -> Vec<(usize, SourceLocation)> {
+        let mut locations: Vec<_> = self.instruction_map.iter().map(|(k, v)| (*k, *v)).collect();
+        locations.sort_by_key(|(offset, _)| *offset);
+        locations
+    }
+    
+    /// All instruction nodes, ordered by instruction offset
+    pub fn nodes(&self) -> Vec<(usize, NodeId)> {
+        let mut nodes: Vec<_> = self.node_map.iter().map(|(k, v)| (*k, *v)).collect();
+        nodes.sort_by_key(|(offset, _)| *offset);
+        nodes
+    }
+    
     /// Format an error message with source location
     pub fn format_error(&self, offset: usize, message: &str) -> String {
         if let Some(location) = self.get_location(offset) {
@@ -2,6 +2,7 @@
 
 use anyhow::{Context, Result};
 use colored::*;
+use fluentai_core_lib::aot::{AotCompiler, AotOptions, OutputFormat};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -209,8 +210,9 @@ struct ModuleMetadata {
 fn compile_file(source_file: &Path, config: &BuildConfig) -> Result<CompiledModule> {
     let source = fs::read_to_string(source_file)?;
 
-    // Parse
-    let ast = fluentai_parser::parse(&source).context("Failed to parse source file")?;
+    // Parse and expand macros
+    let mut ast = fluentai_parser::parse(&source).context("Failed to parse source file")?;
+    fluentai_metaprogramming::expand_macros(&mut ast).context("Failed to expand macros")?;
 
     // Optimize
     let ast = if config.optimization_level > 0 {
@@ -233,7 +235,7 @@ fn compile_file(source_file: &Path, config: &BuildConfig) -> Result<CompiledModu
 
     Ok(CompiledModule {
         name: module_name,
-        bytecode: serialize_bytecode(&bytecode),
+        bytecode: serialize_bytecode(&bytecode)?,
         metadata: ModuleMetadata {
             exports: Vec::new(), // TODO: Extract from AST
             imports: Vec::new(), // TODO: Extract from AST
@@ -242,9 +244,10 @@ fn compile_file(source_file: &Path, config: &BuildConfig) -> Result<CompiledModu
 }
 
 /// Serialize bytecode for storage
-fn serialize_bytecode(bytecode: &fluentai_bytecode::Bytecode) -> Vec<u8> {
-    // TODO: Implement proper serialization
-    vec![]
+fn serialize_bytecode(bytecode: &fluentai_bytecode::Bytecode) -> Result<Vec<u8>> {
+    bytecode
+        .to_bytes()
+        .context("Failed to serialize bytecode")
 }
 
 /// Link compiled modules into final output
@@ -269,6 +272,17 @@ fn link_modules(
         BuildTarget::WebAssembly => output_dir.join(format!("{}.wasm", project_name)),
     };
 
+    // Write each module's bytecode next to the linked output
+    for module in modules {
+        let module_file = output_dir.join(format!(
+            "{}.{}",
+            module.name,
+            fluentai_bytecode::format::FILE_EXTENSION
+        ));
+        fs::write(&module_file, &module.bytecode)
+            .with_context(|| format!("Failed to write {}", module_file.display()))?;
+    }
+
     match config.target {
         BuildTarget::Executable => {
             // Create an embedded application
@@ -288,19 +302,19 @@ fn link_modules(
 }
 
 /// Create a self-contained executable with embedded runtime
+///
+/// Release builds compile the entry module ahead of time and link it against the
+/// core runtime library. Debug builds write a launcher for the `.fabc` modules.
 fn create_embedded_executable(
     modules: &[CompiledModule],
     project_name: &str,
     output_file: &Path,
-    _config: &BuildConfig,
+    config: &BuildConfig,
 ) -> Result<()> {
-    // For now, create a placeholder
-    // In a real implementation, this would:
-    // 1. Create a Rust project that embeds the bytecode
-    // 2. Link with fluentai-core-lib
-    // 3. Compile to native executable
+    if config.configuration == "Release" {
+        return compile_native(modules, output_file, OutputFormat::Executable, config);
+    }
 
-    // Temporary: just write a marker file
     let content = format!(
         "#!/usr/bin/env fluentai\n# FluentAI Embedded Application: {}\n# Modules: {}\n",
         project_name,
@@ -323,14 +337,47 @@ fn create_embedded_executable(
 /// Create a static library
 fn create_static_library(
     modules: &[CompiledModule],
-    project_name: &str,
+    _project_name: &str,
     output_file: &Path,
-    _config: &BuildConfig,
+    config: &BuildConfig,
 ) -> Result<()> {
-    // TODO: Implement static library creation
-    let content = format!("FluentAI Static Library: {}\n", project_name);
-    fs::write(output_file, content)?;
-    Ok(())
+    compile_native(modules, output_file, OutputFormat::StaticLib, config)
+}
+
+/// Compile the project's module ahead of time to native code
+///
+/// Modules are compiled to separate bytecode and native code cannot link them
+/// yet, so a native build takes exactly one source file.
+fn compile_native(
+    modules: &[CompiledModule],
+    output_file: &Path,
+    format: OutputFormat,
+    config: &BuildConfig,
+) -> Result<()> {
+    let entry = match modules {
+        [] => anyhow::bail!("No source files to compile"),
+        [entry] => entry,
+        _ => {
+            let names: Vec<&str> = modules.iter().map(|module| module.name.as_str()).collect();
+            anyhow::bail!(
+                "Native builds support a single source file, but the project has {} modules: {}",
+                modules.len(),
+                names.join(", ")
+            )
+        }
+    };
+    let bytecode = fluentai_bytecode::Bytecode::from_bytes(&entry.bytecode)
+        .with_context(|| format!("Failed to read bytecode of {}", entry.name))?;
+
+    let options = AotOptions {
+        format,
+        opt_level: config.optimization_level,
+        debug_info: config.configuration != "Release",
+        ..Default::default()
+    };
+    AotCompiler::new(options)?
+        .compile_bytecode(&bytecode, output_file)
+        .with_context(|| format!("Failed to compile {} to native code", entry.name))
 }
 
 /// Compile to WebAssembly
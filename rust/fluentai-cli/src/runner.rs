@@ -1,6 +1,7 @@
 //! Core execution logic for running FluentAi programs
 
 use anyhow::Result;
+use fluentai_metaprogramming::expand_macros;
 use fluentai_optimizer::OptimizationLevel;
 use fluentai_parser::parse;
 use fluentai_vm::{Compiler, CompilerOptions, Value, VM};
@@ -27,8 +28,9 @@ pub fn run_code(code: &str) -> Result<Value> {
 
 /// Run FluentAi code with specific optimization level
 pub fn run_code_with_options(code: &str, opt_level: OptimizationLevel) -> Result<Value> {
-    // Parse
-    let ast = parse(code)?;
+    // Parse and expand macros
+    let mut ast = parse(code)?;
+    expand_macros(&mut ast)?;
 
     // Compile with optimization
     let options = CompilerOptions {
@@ -78,7 +80,8 @@ pub async fn run_with_visualization(
     use std::path::PathBuf;
 
     // Parse first to get AST
-    let ast = parse(code)?;
+    let mut ast = parse(code)?;
+    expand_macros(&mut ast)?;
 
     // Create channels
     let (vm_debug_tx, mut vm_debug_rx) = mpsc::unbounded_channel::<VMDebugEvent>();
@@ -69,6 +69,7 @@ impl<'a> ConditionEvaluator<'a> {
                 else_branch,
             } => self.evaluate_if(*condition, *then_branch, *else_branch),
             Node::List(elements) => self.evaluate_list(elements),
+            Node::Tuple(elements) => self.evaluate_tuple(elements),
             _ => Err(ContractError::VerificationError(format!(
                 "Unsupported node type in contract condition: {:?}",
                 node
@@ -80,7 +81,10 @@ impl<'a> ConditionEvaluator<'a> {
         Ok(match lit {
             Literal::Integer(i) => Value::Integer(*i),
             Literal::Float(f) => Value::Float(*f),
+            Literal::BigInt(n) => Value::BigInt(n.clone()),
+            Literal::Decimal(d) => Value::Decimal(*d),
             Literal::String(s) => Value::String(s.clone()),
+            Literal::Bytes(b) => Value::Bytes(b.clone()),
             Literal::Symbol(s) => Value::Symbol(s.clone()),
             Literal::Boolean(b) => Value::Boolean(*b),
             Literal::Nil => Value::Nil,
@@ -172,6 +176,11 @@ impl<'a> ConditionEvaluator<'a> {
         Ok(Value::List(values?))
     }
 
+    fn evaluate_tuple(&self, elements: &[NodeId]) -> ContractResult<Value> {
+        let values: Result<Vec<_>, _> = elements.iter().map(|&elem| self.evaluate(elem)).collect();
+        Ok(Value::Tuple(values?))
+    }
+
     // Built-in predicate implementations
 
     fn builtin_equal(&self, args: &[Value]) -> ContractResult<Value> {
@@ -154,12 +154,12 @@ impl<'a> IncrementalVerifier<'a> {
             Node::Lambda { body, .. } => {
                 self.collect_function_refs(*body, refs)?;
             }
-            Node::List(elements) => {
+            Node::List(elements) | Node::Tuple(elements) => {
                 for elem in elements {
                     self.collect_function_refs(*elem, refs)?;
                 }
             }
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 self.collect_function_refs(*expr, refs)?;
                 for (_, body) in branches {
                     self.collect_function_refs(*body, refs)?;
@@ -343,12 +343,12 @@ impl<'a> DependencyAnalyzer<'a> {
                 Node::Lambda { body, .. } => {
                     self.collect_calls(*body, calls);
                 }
-                Node::List(elements) => {
+                Node::List(elements) | Node::Tuple(elements) => {
                     for elem in elements {
                         self.collect_calls(*elem, calls);
                     }
                 }
-                Node::Match { expr, branches } => {
+                Node::Match { expr, branches, .. } => {
                     self.collect_calls(*expr, calls);
                     for (_, body) in branches {
                         self.collect_calls(*body, calls);
@@ -237,8 +237,8 @@ impl<'a> PurityChecker<'a> {
             // Note: FluentAi doesn't have a Sequence node type
             // Sequential execution would be done through let bindings or function applications
 
-            // Lists are pure if all elements are pure
-            Node::List(elements) => {
+            // Lists and tuples are pure if all elements are pure
+            Node::List(elements) | Node::Tuple(elements) => {
                 for elem in elements {
                     if !self.is_pure(*elem)? {
                         return Ok(false);
@@ -248,7 +248,7 @@ impl<'a> PurityChecker<'a> {
             }
 
             // Pattern matching is pure if scrutinee and all bodies are pure
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 if !self.is_pure(*expr)? {
                     return Ok(false);
                 }
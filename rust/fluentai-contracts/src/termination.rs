@@ -233,7 +233,7 @@ impl<'a> TerminationChecker<'a> {
                     }
                 }
             }
-            Node::Match { expr: _, branches } => {
+            Node::Match { expr: _, branches, .. } => {
                 // Pattern matching often indicates structural recursion
                 for (pattern, _body) in branches {
                     if self.is_structural_pattern_check(pattern)? {
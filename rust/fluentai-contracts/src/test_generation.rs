@@ -416,7 +416,10 @@ fn format_literal(lit: &Literal) -> String {
     match lit {
         Literal::Integer(n) => n.to_string(),
         Literal::Float(f) => f.to_string(),
+        Literal::BigInt(n) => format!("{}n", n),
+        Literal::Decimal(d) => format!("{}d", d),
         Literal::String(s) => format!("\"{}\"", s),
+        Literal::Bytes(b) => fluentai_core::value::format_bytes(b),
         Literal::Symbol(s) => format!("'{}", s),
         Literal::Boolean(b) => if *b { "#t" } else { "#f" }.to_string(),
         Literal::Nil => "nil".to_string(),
@@ -428,7 +431,10 @@ fn format_literal_rust(lit: &Literal) -> String {
     match lit {
         Literal::Integer(n) => format!("{}i64", n),
         Literal::Float(f) => format!("{}f64", f),
+        Literal::BigInt(n) => format!("BigInt::from_str(\"{}\").unwrap()", n),
+        Literal::Decimal(d) => format!("Decimal::from_str(\"{}\").unwrap()", d),
         Literal::String(s) => format!("\"{}\"", s),
+        Literal::Bytes(b) => format!("{}.to_vec()", fluentai_core::value::format_bytes(b)),
         Literal::Symbol(s) => format!("Symbol(\"{}\")", s),
         Literal::Boolean(b) => b.to_string(),
         Literal::Nil => "None".to_string(),
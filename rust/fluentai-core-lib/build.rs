@@ -0,0 +1,117 @@
+//! Find the system libraries that executables linked against the core library's
+//! static build need, for the AOT linker command
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+
+    let mut libs = Vec::new();
+    if cfg!(feature = "aot") {
+        let target = env::var("TARGET").expect("TARGET is set by cargo");
+        libs.extend(openssl_libs());
+        libs.extend(std_native_libs(&out_dir, &target));
+    }
+
+    let source = format!(
+        "/// System libraries the core runtime library needs: OpenSSL where native-tls\n\
+         /// links it, then those `rustc --print native-static-libs` lists for the standard\n\
+         /// library. Empty without the `aot` feature.\n\
+         const SYSTEM_LIBS: &[&str] = &{:?};\n",
+        libs
+    );
+    fs::write(out_dir.join("system_libs.rs"), source).expect("failed to write system_libs.rs");
+}
+
+/// The OpenSSL libraries openssl-sys linked, if it is a dependency on this target
+///
+/// openssl-sys only publishes its include directory (and its root when vendored),
+/// so the library names are resolved the way it resolves them: `OPENSSL_LIBS`,
+/// or `ssl` and `crypto`.
+fn openssl_libs() -> Vec<String> {
+    println!("cargo:rerun-if-env-changed=OPENSSL_LIBS");
+    println!("cargo:rerun-if-env-changed=OPENSSL_LIB_DIR");
+
+    if env::var_os("DEP_OPENSSL_INCLUDE").is_none() {
+        return Vec::new();
+    }
+
+    let mut libs = Vec::new();
+    if let Some(root) = env::var_os("DEP_OPENSSL_ROOT") {
+        libs.push(format!("-L{}", Path::new(&root).join("lib").display()));
+    } else if let Some(dir) = env::var_os("OPENSSL_LIB_DIR") {
+        libs.push(format!("-L{}", Path::new(&dir).display()));
+    }
+    let names = env::var("OPENSSL_LIBS").unwrap_or_else(|_| "ssl:crypto".to_string());
+    libs.extend(
+        names
+            .split(':')
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("-l{}", name)),
+    );
+    libs
+}
+
+/// Ask rustc which native libraries the standard library links on `target`, by
+/// building an empty static library
+///
+/// Falls back to no libraries, with a warning, if rustc cannot tell.
+fn std_native_libs(out_dir: &Path, target: &str) -> Vec<String> {
+    let probe = out_dir.join("native_libs_probe.rs");
+    if let Err(e) = fs::write(&probe, "") {
+        println!(
+            "cargo:warning=could not write the native library probe: {}",
+            e
+        );
+        return Vec::new();
+    }
+
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let output = Command::new(rustc)
+        .args([
+            "--crate-type",
+            "staticlib",
+            "--crate-name",
+            "native_libs_probe",
+        ])
+        .args([
+            "--print",
+            "native-static-libs",
+            "--target",
+            target,
+            "--out-dir",
+        ])
+        .arg(out_dir)
+        .arg(&probe)
+        .output();
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            println!(
+                "cargo:warning=rustc failed to build the native library probe, so AOT executables \
+                 may not link: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return Vec::new();
+        }
+        Err(e) => {
+            println!(
+                "cargo:warning=could not run rustc for the native library probe, so AOT \
+                 executables may not link: {}",
+                e
+            );
+            return Vec::new();
+        }
+    };
+
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .find_map(|line| line.split("native-static-libs:").nth(1))
+        .map(|libs| libs.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
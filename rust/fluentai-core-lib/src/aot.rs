@@ -2,15 +2,38 @@
 //!
 //! This module provides functionality for compiling FluentAI programs
 //! to native executables that statically link the core library.
+//!
+//! Programs are compiled to bytecode, and every function chunk the Cranelift
+//! backend supports is compiled to native code in an object file. The object also
+//! embeds the bytecode and defines `fluentai_program_run`, which starts the core
+//! runtime through [`fluentai_aot_main`]. Executables add a C `main` and are linked
+//! against the core library's static build, `libfluentai_core_lib.a`.
 
-use crate::error::Result;
+use crate::error::{Result, RuntimeError};
+#[cfg(feature = "aot")]
+use crate::{ExecutionMode, RuntimeConfig, RuntimeEngine, Value};
+use fluentai_bytecode::Bytecode;
 use fluentai_core::ast::Graph;
-use std::path::Path;
+#[cfg(feature = "aot")]
+use fluentai_jit::object::{compile_object, host_target, NativeFunction, ObjectOptions};
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{Compiler, CompilerOptions};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable overriding where the core runtime library is found
+pub const RUNTIME_LIB_ENV: &str = "FLUENTAI_RUNTIME_LIB";
+
+/// File name of the core runtime library executables are linked against
+pub const RUNTIME_LIB_NAME: &str = "libfluentai_core_lib.a";
+
+// Defines `SYSTEM_LIBS`, found by the build script
+include!(concat!(env!("OUT_DIR"), "/system_libs.rs"));
 
 /// AOT compilation options
 #[derive(Debug, Clone)]
 pub struct AotOptions {
-    /// Target triple (e.g., "x86_64-unknown-linux-gnu")
+    /// Target triple (e.g., "x86_64-unknown-linux-gnu"); only the host is supported
     pub target: Option<String>,
     /// Optimization level (0-3)
     pub opt_level: u8,
@@ -22,6 +45,8 @@ pub struct AotOptions {
     pub static_link: bool,
     /// Output format
     pub format: OutputFormat,
+    /// Core runtime library to link executables against, found automatically if unset
+    pub runtime_lib: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -45,6 +70,7 @@ impl Default for AotOptions {
             lto: true,
             static_link: true,
             format: OutputFormat::Executable,
+            runtime_lib: None,
         }
     }
 }
@@ -62,87 +88,214 @@ impl AotCompiler {
 
     /// Compile a program to native code
     pub fn compile(&mut self, graph: &Graph, output_path: &Path) -> Result<()> {
-        // For now, we'll use the JIT engine to generate code
-        // In a real implementation, this would:
-        // 1. Generate all functions using Cranelift
-        // 2. Link with the core library
-        // 3. Create the final executable
+        let options = CompilerOptions {
+            optimization_level: match self.options.opt_level {
+                0 => OptimizationLevel::None,
+                1 => OptimizationLevel::Basic,
+                2 => OptimizationLevel::Standard,
+                _ => OptimizationLevel::Aggressive,
+            },
+            debug_info: self.options.debug_info,
+        };
+        let bytecode = Compiler::with_options(options)
+            .compile(graph)
+            .map_err(|e| RuntimeError::other(format!("Compilation error: {}", e)))?;
 
-        // TODO: Implement actual AOT compilation
-        // This is a placeholder that shows the intended API
+        self.compile_bytecode(&bytecode, output_path)
+    }
 
+    /// Compile a program already compiled to bytecode to native code
+    pub fn compile_bytecode(&mut self, bytecode: &Bytecode, output_path: &Path) -> Result<()> {
         match self.options.format {
-            OutputFormat::Executable => {
-                self.compile_executable(graph, output_path)?;
-            }
-            OutputFormat::Object => {
-                self.compile_object(graph, output_path)?;
-            }
-            OutputFormat::StaticLib => {
-                self.compile_static_lib(graph, output_path)?;
-            }
-            OutputFormat::DynamicLib => {
-                self.compile_dynamic_lib(graph, output_path)?;
+            OutputFormat::Executable => self.compile_executable(bytecode, output_path),
+            OutputFormat::Object => self.compile_object(bytecode, output_path),
+            OutputFormat::StaticLib => self.compile_static_lib(bytecode, output_path),
+            OutputFormat::DynamicLib => Err(RuntimeError::not_implemented(
+                "Dynamic library output is not supported; use a static library",
+            )),
+        }
+    }
+
+    fn compile_executable(&mut self, bytecode: &Bytecode, output_path: &Path) -> Result<()> {
+        let runtime_lib = self.runtime_library()?;
+        let object_path = output_path.with_extension("o");
+        self.write_object(bytecode, &object_path, true)?;
+
+        let linker = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+        let mut command = Command::new(&linker);
+        command.arg(&object_path).arg(&runtime_lib);
+        if self.options.static_link {
+            // libgcc_s only exists as a shared library; libgcc_eh is its static part
+            command.arg("-static").args(SYSTEM_LIBS.iter().map(|&lib| {
+                if lib == "-lgcc_s" {
+                    "-lgcc_eh"
+                } else {
+                    lib
+                }
+            }));
+        } else {
+            command.args(SYSTEM_LIBS);
+        }
+        command.arg("-o").arg(output_path);
+        if !self.options.debug_info {
+            command.arg("-s");
+        }
+        let result = run_tool(&mut command, &linker);
+
+        // The object only exists to be linked
+        let _ = std::fs::remove_file(&object_path);
+        result
+    }
+
+    fn compile_object(&mut self, bytecode: &Bytecode, output_path: &Path) -> Result<()> {
+        self.write_object(bytecode, output_path, false)
+    }
+
+    fn compile_static_lib(&mut self, bytecode: &Bytecode, output_path: &Path) -> Result<()> {
+        let object_path = output_path.with_extension("o");
+        self.write_object(bytecode, &object_path, false)?;
+
+        // `ar` replaces existing members, so start from an empty archive
+        if output_path.exists() {
+            std::fs::remove_file(output_path)?;
+        }
+        let archiver = std::env::var("AR").unwrap_or_else(|_| "ar".to_string());
+        let result = run_tool(
+            Command::new(&archiver)
+                .arg("crs")
+                .arg(output_path)
+                .arg(&object_path),
+            &archiver,
+        );
+
+        let _ = std::fs::remove_file(&object_path);
+        result
+    }
+
+    /// Write the program's object file, with a C `main` if `define_main` is set
+    #[cfg(feature = "aot")]
+    fn write_object(&self, bytecode: &Bytecode, path: &Path, define_main: bool) -> Result<()> {
+        let host = host_target()?;
+        if let Some(target) = &self.options.target {
+            if *target != host {
+                return Err(RuntimeError::not_implemented(format!(
+                    "Cross compilation to {} (host is {})",
+                    target, host
+                )));
             }
         }
 
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("fluentai_program")
+            .to_string();
+        let object = compile_object(
+            bytecode,
+            &ObjectOptions {
+                name,
+                opt_level: self.options.opt_level,
+                define_main,
+            },
+        )?;
+        for (chunk_id, reason) in &object.interpreted_chunks {
+            tracing::debug!("Chunk {} will be interpreted: {}", chunk_id, reason);
+        }
+
+        std::fs::write(path, &object.bytes)?;
         Ok(())
     }
 
-    fn compile_executable(&mut self, graph: &Graph, output_path: &Path) -> Result<()> {
-        // Generate main entry point
-        // Link with core library
-        // Create executable
-        todo!("Implement executable compilation")
+    #[cfg(not(feature = "aot"))]
+    fn write_object(&self, _bytecode: &Bytecode, _path: &Path, _define_main: bool) -> Result<()> {
+        Err(RuntimeError::not_implemented(
+            "AOT compilation requires the `aot` feature",
+        ))
     }
 
-    fn compile_object(&mut self, graph: &Graph, output_path: &Path) -> Result<()> {
-        // Generate object file
-        todo!("Implement object file compilation")
-    }
+    /// Find the core runtime library: the configured path, then the
+    /// [`RUNTIME_LIB_ENV`] variable, then next to the running executable
+    fn runtime_library(&self) -> Result<PathBuf> {
+        if let Some(path) = &self.options.runtime_lib {
+            return Ok(path.clone());
+        }
+        if let Some(path) = std::env::var_os(RUNTIME_LIB_ENV) {
+            return Ok(PathBuf::from(path));
+        }
 
-    fn compile_static_lib(&mut self, graph: &Graph, output_path: &Path) -> Result<()> {
-        // Generate static library
-        todo!("Implement static library compilation")
+        // Cargo puts the library beside the binaries of the same build, and one
+        // level above its test binaries
+        let exe = std::env::current_exe()?;
+        for dir in exe.ancestors().skip(1).take(2) {
+            let path = dir.join(RUNTIME_LIB_NAME);
+            if path.exists() {
+                return Ok(path);
+            }
+        }
+        Err(RuntimeError::config(format!(
+            "{} not found; build it with `cargo build -p fluentai-core-lib` or set {}",
+            RUNTIME_LIB_NAME, RUNTIME_LIB_ENV
+        )))
     }
+}
 
-    fn compile_dynamic_lib(&mut self, graph: &Graph, output_path: &Path) -> Result<()> {
-        // Generate dynamic library
-        todo!("Implement dynamic library compilation")
+/// Run an external tool, failing with its output if it does not succeed
+fn run_tool(command: &mut Command, name: &str) -> Result<()> {
+    let output = command
+        .output()
+        .map_err(|e| RuntimeError::other(format!("Failed to run {}: {}", name, e)))?;
+    if !output.status.success() {
+        return Err(RuntimeError::other(format!(
+            "{} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr)
+        )));
     }
+    Ok(())
 }
 
-/// Generate a main function wrapper for standalone executables
-pub fn generate_main_wrapper(entry_point: &str) -> String {
-    format!(
-        r#"
-// Auto-generated main wrapper for FluentAI
-extern crate fluentai_core_lib;
-
-use fluentai_core_lib::{{Value, RuntimeEngine}};
-
-fn main() {{
-    // Initialize core library
-    fluentai_core_lib::init();
-    
-    // Create engine for dynamic features
-    let engine = RuntimeEngine::new_minimal();
-    
-    // Call the FluentAI entry point
-    let result = {entry_point}(&engine);
-    
-    // Handle result
-    match result {{
-        Ok(Value::Integer(code)) => std::process::exit(code as i32),
-        Ok(_) => std::process::exit(0),
-        Err(e) => {{
-            eprintln!("Error: {{}}", e);
-            std::process::exit(1);
-        }}
-    }}
-}}
-"#
-    )
+/// Entry point of AOT-compiled programs, called by their `fluentai_program_run`
+///
+/// Runs the program and returns its exit code: its result if that is an integer
+/// that fits an `i32`, 0 for any other result and 1 if it fails or its integer
+/// result is out of range.
+///
+/// # Safety
+///
+/// The arguments must be the bytecode and function table of an object written by
+/// [`AotCompiler`].
+#[cfg(feature = "aot")]
+#[no_mangle]
+pub unsafe extern "C" fn fluentai_aot_main(
+    bytecode: *const u8,
+    bytecode_len: usize,
+    functions: *const NativeFunction,
+    function_count: usize,
+) -> i32 {
+    init();
+
+    let bytecode = std::slice::from_raw_parts(bytecode, bytecode_len);
+    let functions = if function_count == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(functions, function_count)
+    };
+
+    let mut engine = RuntimeEngine::new(RuntimeConfig {
+        execution_mode: ExecutionMode::AOT,
+        ..RuntimeConfig::production()
+    });
+    match engine.execute_native(bytecode, functions) {
+        Ok(Value::Integer(code)) => i32::try_from(code).unwrap_or_else(|_| {
+            eprintln!("Error: exit code {} is out of range", code);
+            1
+        }),
+        Ok(_) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
 }
 
 /// Initialize the core library
@@ -155,3 +308,113 @@ pub fn init() {
     // - Registering signal handlers
     // - Setting up thread pools
 }
+
+#[cfg(all(test, feature = "aot", target_os = "linux", target_arch = "x86_64"))]
+mod tests {
+    use super::*;
+    use fluentai_parser::parse;
+
+    const PROGRAM: &str = r#"
+private function add(a, b) { a + b }
+private function scale(x) { x * 1.5 }
+private function first_plus_rest(xs) { head(xs) + length(tail(xs)) }
+add(first_plus_rest([40, 0, 0]), 0) + add(0, 0)"#;
+
+    fn compile_to(format: OutputFormat, output_path: &Path) -> Result<()> {
+        compile_linked(format, false, output_path)
+    }
+
+    fn compile_linked(format: OutputFormat, static_link: bool, output_path: &Path) -> Result<()> {
+        let mut compiler = AotCompiler::new(AotOptions {
+            format,
+            opt_level: 0,
+            static_link,
+            ..Default::default()
+        })?;
+        compiler.compile(&parse(PROGRAM).unwrap(), output_path)
+    }
+
+    #[test]
+    fn test_object_and_static_library_output() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let object_path = dir.path().join("program.o");
+        compile_to(OutputFormat::Object, &object_path).unwrap();
+        let object = std::fs::read(&object_path).unwrap();
+        assert_eq!(&object[..4], b"\x7fELF");
+
+        let lib_path = dir.path().join("libprogram.a");
+        compile_to(OutputFormat::StaticLib, &lib_path).unwrap();
+        let lib = std::fs::read(&lib_path).unwrap();
+        assert_eq!(&lib[..8], b"!<arch>\n");
+        // Only the archive is left behind
+        assert!(!dir.path().join("libprogram.o").exists());
+
+        let result = compile_to(OutputFormat::DynamicLib, &dir.path().join("libprogram.so"));
+        assert!(matches!(result, Err(RuntimeError::NotImplemented(_))));
+    }
+
+    #[test]
+    fn test_cross_compilation_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut compiler = AotCompiler::new(AotOptions {
+            target: Some("riscv64gc-unknown-linux-gnu".to_string()),
+            opt_level: 0,
+            format: OutputFormat::Object,
+            ..Default::default()
+        })
+        .unwrap();
+        let result = compiler.compile(&parse(PROGRAM).unwrap(), &dir.path().join("program.o"));
+        assert!(matches!(result, Err(RuntimeError::NotImplemented(_))));
+    }
+
+    /// Run a program through the AOT entry point, with every chunk interpreted
+    fn aot_main_exit_code(code: &str) -> i32 {
+        let bytecode = Compiler::new().compile(&parse(code).unwrap()).unwrap();
+        let bytes = bytecode.to_bytes().unwrap();
+        unsafe { fluentai_aot_main(bytes.as_ptr(), bytes.len(), std::ptr::null(), 0) }
+    }
+
+    #[test]
+    fn test_aot_main_exit_code() {
+        assert_eq!(aot_main_exit_code("40 + 2"), 42);
+        assert_eq!(aot_main_exit_code("-3"), -3);
+        assert_eq!(aot_main_exit_code(r#""done""#), 0);
+        assert_eq!(aot_main_exit_code("1 / 0"), 1);
+        // Wider values are not truncated into an unrelated code
+        assert_eq!(aot_main_exit_code("4294967338"), 1);
+        assert_eq!(aot_main_exit_code("-2147483649"), 1);
+        assert_eq!(aot_main_exit_code("2147483647"), i32::MAX);
+    }
+
+    /// Build the core library's static archive the linked executables need, the
+    /// way `cargo build -p fluentai-core-lib` would
+    fn build_runtime_library() {
+        let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let mut command = Command::new(cargo);
+        command
+            .args(["build", "--lib", "--manifest-path"])
+            .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"));
+        if !cfg!(debug_assertions) {
+            command.arg("--release");
+        }
+        run_tool(&mut command, "cargo").unwrap();
+    }
+
+    #[test]
+    fn test_executable_exits_with_program_result() {
+        build_runtime_library();
+        let compiler = AotCompiler::new(AotOptions::default()).unwrap();
+        if let Err(e) = compiler.runtime_library() {
+            panic!("{}", e);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        for static_link in [false, true] {
+            let exe_path = dir.path().join(format!("program_{}", static_link));
+            compile_linked(OutputFormat::Executable, static_link, &exe_path).unwrap();
+            let status = Command::new(&exe_path).status().unwrap();
+            assert_eq!(status.code(), Some(42));
+        }
+    }
+}
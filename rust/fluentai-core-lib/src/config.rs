@@ -14,7 +14,7 @@ pub enum ExecutionMode {
         /// Threshold for JIT compilation
         threshold: u32,
     },
-    /// Ahead-of-time compilation (future)
+    /// Run native code compiled ahead of time, interpreting chunks without any
     #[cfg(feature = "aot")]
     AOT,
 }
@@ -86,6 +86,9 @@ pub struct SecurityConfig {
     pub allow_process: bool,
     /// Maximum execution time in milliseconds (0 = unlimited)
     pub max_execution_time: u64,
+    /// Host functions a sandboxed program may call
+    #[serde(default)]
+    pub allowed_host_functions: Vec<String>,
 }
 
 impl Default for SecurityConfig {
@@ -96,6 +99,7 @@ impl Default for SecurityConfig {
             allow_network: true,
             allow_process: true,
             max_execution_time: 0,
+            allowed_host_functions: Vec::new(),
         }
     }
 }
@@ -196,6 +200,7 @@ impl RuntimeConfig {
                 allow_network: false,
                 allow_process: false,
                 max_execution_time: 30000, // 30 seconds
+                allowed_host_functions: Vec::new(),
             },
             ..Default::default()
         }
@@ -1,7 +1,7 @@
 //! Runtime context and state management
 
 use fluentai_core::value::Value;
-use fluentai_vm::VM;
+use fluentai_vm::{Capability, SecurityManager, SecurityPolicy, VM};
 use parking_lot::RwLock;
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -152,10 +152,19 @@ impl RuntimeContext {
     /// Create a VM instance for this context
     pub fn create_vm(&self, bytecode: fluentai_bytecode::Bytecode) -> Result<VM> {
         // Create VM with bytecode
-        let vm = VM::new(bytecode);
-
-        // TODO: Add configuration support when VM builder is available
-        // For now, just return the basic VM
+        let mut vm = VM::new(bytecode);
+
+        // A sandboxed VM runs under the sandbox policy and may only bind the
+        // host functions the configuration allows
+        if self.config.security.enable_sandbox {
+            let security = SecurityManager::new(SecurityPolicy::sandbox());
+            for name in &self.config.security.allowed_host_functions {
+                security.context.grant_capability(Capability::HostFunction {
+                    names: vec![name.clone()],
+                });
+            }
+            vm.set_security_manager(Arc::new(security));
+        }
 
         Ok(vm)
     }
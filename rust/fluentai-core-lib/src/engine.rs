@@ -104,32 +104,55 @@ impl RuntimeEngine {
         self.execute_compiled_module(&module)
     }
 
+    /// Execute a program compiled ahead of time
+    ///
+    /// `bytecode` is the program's `.fabc` bytecode, and `functions` the native code
+    /// compiled for some of its chunks; the VM interprets the other chunks.
+    ///
+    /// # Safety
+    ///
+    /// Every function must be a chunk of `bytecode` compiled by
+    /// [`AotCompiler`](crate::AotCompiler), and must stay loaded while this runs.
+    #[cfg(feature = "aot")]
+    pub unsafe fn execute_native(
+        &mut self,
+        bytecode: &[u8],
+        functions: &[fluentai_jit::object::NativeFunction],
+    ) -> Result<Value> {
+        info!("Executing native program");
+
+        let module = self.context.loader().load_from_bytecode("__main__", bytecode)?;
+        let mut vm = self.create_module_vm(&module)?;
+        for function in functions {
+            vm.register_native_function(function.chunk_id as usize, function.code)?;
+        }
+        self.run_module_vm(&mut vm, &module)
+    }
+
     /// Execute a compiled module
     fn execute_compiled_module(&mut self, module: &CompiledModule) -> Result<Value> {
+        let mut vm = self.create_module_vm(module)?;
+        self.run_module_vm(&mut vm, module)
+    }
+
+    /// Create a VM for a module, with host functions, globals and externs bound
+    fn create_module_vm(&self, module: &CompiledModule) -> Result<fluentai_vm::VM> {
         // Check state
         if self.context.state() == RuntimeState::Running {
             return Err(RuntimeError::other("Runtime is already executing"));
         }
 
-        // Mark start
-        self.context.mark_start();
-        let start_time = Instant::now();
-
         // Create VM with bytecode
         let mut vm = self.context.create_vm(module.bytecode.as_ref().clone())?;
 
-        // Register host functions with VM
+        // Register host functions with VM. They can be called by name without an
+        // `extern` declaration, so a sandbox only gets those its policy grants.
         for func in self.context.host_registry().all() {
-            // Create a NativeFunction value that wraps the host function
-            let host_func = func.clone();
-            let native_func = Value::NativeFunction {
-                name: func.name.clone(),
-                arity: func.arity,
-                function: Arc::new(move |args| {
-                    host_func.call(args).map_err(|e| fluentai_core::value::ValueError::InvalidOperation(e.to_string()))
-                }),
-            };
-            vm.set_global(func.name.clone(), native_func);
+            if vm.check_host_function(&func.name).is_err() {
+                continue;
+            }
+            let arity = func.arity;
+            vm.set_global(func.name.clone(), native_function(func, arity));
         }
 
         // Set globals in VM
@@ -137,23 +160,46 @@ impl RuntimeEngine {
             vm.set_global(name.clone(), value.clone());
         }
 
+        // Bind `extern` declarations last so nothing shadows them; an unresolved
+        // declaration fails here, before any code runs
+        let registry = self.context.host_registry();
+        vm.link_externs(|decl| {
+            registry.get(&decl.name).map(|func| {
+                // A variadic host function takes the arity it was declared with
+                let arity = if func.variadic && decl.arity >= func.arity {
+                    decl.arity
+                } else {
+                    func.arity
+                };
+                native_function(func, arity)
+            })
+        })?;
+
+        Ok(vm)
+    }
+
+    /// Run a module's VM in the configured execution mode
+    fn run_module_vm(&mut self, vm: &mut fluentai_vm::VM, module: &CompiledModule) -> Result<Value> {
+        // Mark start once setup has succeeded, so a failed load leaves the runtime idle
+        self.context.mark_start();
+        let start_time = Instant::now();
+
         // Execute based on execution mode
         let result = match self.context.config().execution_mode {
             ExecutionMode::Interpreted => {
                 debug!("Executing in interpreted mode");
-                self.execute_interpreted(&mut vm, &module.bytecode)
+                self.execute_interpreted(vm, &module.bytecode)
             }
             #[cfg(feature = "jit")]
             ExecutionMode::JIT { threshold } => {
                 debug!("Executing in JIT mode (threshold: {})", threshold);
-                self.execute_jit(&mut vm, &module.bytecode, threshold)
+                self.execute_jit(vm, &module.bytecode, threshold)
             }
             #[cfg(feature = "aot")]
             ExecutionMode::AOT => {
+                // Chunks with native code registered on the VM run it; the rest is interpreted
                 debug!("Executing in AOT mode");
-                Err(RuntimeError::not_implemented(
-                    "AOT execution not yet implemented",
-                ))
+                self.execute_interpreted(vm, &module.bytecode)
             }
         };
 
@@ -232,6 +278,17 @@ impl RuntimeEngine {
     }
 }
 
+/// Wrap a host function in a VM native function value of the given arity
+fn native_function(func: HostFunction, arity: usize) -> Value {
+    Value::NativeFunction {
+        name: func.name.clone(),
+        arity,
+        function: Arc::new(move |args| {
+            func.call(args).map_err(|e| fluentai_core::value::ValueError::InvalidOperation(e.to_string()))
+        }),
+    }
+}
+
 /// Engine builder
 pub struct RuntimeEngineBuilder {
     config: RuntimeConfig,
@@ -269,6 +326,12 @@ impl RuntimeEngineBuilder {
         self
     }
 
+    /// Let a sandboxed program call a host function
+    pub fn allow_host_function(mut self, name: impl Into<String>) -> Self {
+        self.config.security.allowed_host_functions.push(name.into());
+        self
+    }
+
     /// Set memory limits
     pub fn memory_limit(mut self, limit: usize) -> Self {
         self.config.memory.max_heap_size = limit;
@@ -324,6 +387,88 @@ mod tests {
         assert_eq!(result, Value::Integer(3));
     }
 
+    #[test]
+    fn test_extern_binds_host_function() {
+        let mut engine = RuntimeEngine::default();
+        let shout = HostFunction::new("shout", 1, |args| match &args[0] {
+            Value::String(s) => Ok(Value::String(s.to_uppercase())),
+            _ => Err(RuntimeError::host("Expected a string")),
+        });
+        engine.register_function(shout).unwrap();
+
+        let result = engine
+            .execute("extern function shout(text: String) -> String\nshout(\"hi\")")
+            .unwrap();
+        assert_eq!(result, Value::String("HI".to_string()));
+    }
+
+    #[test]
+    fn test_unresolved_extern_fails_before_execution() {
+        let mut engine = RuntimeEngine::default();
+        let err = engine
+            .execute("extern function sha256(data: String) -> String\nsha256(\"abc\")")
+            .unwrap_err();
+        assert!(err.to_string().contains("Unresolved extern function 'sha256'"));
+
+        // The failed load leaves the runtime free to execute again
+        assert_eq!(engine.execute("1 + 2").unwrap(), Value::Integer(3));
+    }
+
+    #[test]
+    fn test_sandbox_denies_extern_without_capability() {
+        let source = "extern function shout(text: String) -> String\nshout(\"hi\")";
+        let shout = || {
+            HostFunction::new("shout", 1, |args| match &args[0] {
+                Value::String(s) => Ok(Value::String(s.to_uppercase())),
+                _ => Err(RuntimeError::host("Expected a string")),
+            })
+        };
+
+        let mut engine = RuntimeEngine::sandboxed();
+        engine.register_function(shout()).unwrap();
+        let err = engine.execute(source).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("No capability to call host function 'shout'"));
+
+        // Allowing the host function lets the declaration bind
+        let mut engine = RuntimeEngineBuilder::new()
+            .sandboxed(true)
+            .allow_host_function("shout")
+            .build();
+        engine.register_function(shout()).unwrap();
+        assert_eq!(
+            engine.execute(source).unwrap(),
+            Value::String("HI".to_string())
+        );
+    }
+
+    #[test]
+    fn test_sandbox_hides_host_functions_without_capability() {
+        // Calling a host function by name, without an `extern` declaration
+        let source = "shout(\"hi\")";
+        let shout = || {
+            HostFunction::new("shout", 1, |args| match &args[0] {
+                Value::String(s) => Ok(Value::String(s.to_uppercase())),
+                _ => Err(RuntimeError::host("Expected a string")),
+            })
+        };
+
+        let mut engine = RuntimeEngine::sandboxed();
+        engine.register_function(shout()).unwrap();
+        assert!(engine.execute(source).is_err());
+
+        let mut engine = RuntimeEngineBuilder::new()
+            .sandboxed(true)
+            .allow_host_function("shout")
+            .build();
+        engine.register_function(shout()).unwrap();
+        assert_eq!(
+            engine.execute(source).unwrap(),
+            Value::String("HI".to_string())
+        );
+    }
+
     #[test]
     fn test_engine_builder() {
         let engine = RuntimeEngineBuilder::new()
@@ -33,6 +33,14 @@ pub enum RuntimeError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    /// Malformed `.fabc` bytecode file
+    #[error("Bytecode format error: {0}")]
+    BytecodeFormat(#[from] fluentai_bytecode::format::FormatError),
+
+    /// Bytecode rejected by the verifier
+    #[error("Bytecode verification failed: {0}")]
+    Verification(#[from] fluentai_vm::verifier::VerifyError),
+
     /// Timeout error
     #[error("Execution timeout exceeded")]
     Timeout,
@@ -1,9 +1,11 @@
 //! Module loading and compilation
 
 use dashmap::DashMap;
+use fluentai_bytecode::{format::FILE_EXTENSION, Bytecode};
 use fluentai_optimizer::GraphOptimizer;
 use fluentai_parser::parse;
 use fluentai_vm::compiler::Compiler;
+use fluentai_vm::verifier::verify;
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
@@ -72,6 +74,16 @@ impl ModuleLoader {
 
     /// Load a module from a file path
     pub fn load_from_path(&self, path: &Path) -> Result<CompiledModule> {
+        if path.extension().and_then(|ext| ext.to_str()) == Some(FILE_EXTENSION) {
+            let bytes = std::fs::read(path)
+                .map_err(|e| RuntimeError::module(format!("Failed to read module: {}", e)))?;
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown");
+            return self.bytecode_module(name, &bytes);
+        }
+
         let source = load_module_source(path)?;
         let metadata = parse_module_metadata(&source)?;
 
@@ -105,6 +117,40 @@ impl ModuleLoader {
         Ok(module)
     }
 
+    /// Load a precompiled `.fabc` module
+    ///
+    /// The bytecode is verified before it is made available for execution.
+    pub fn load_from_bytecode(&self, name: &str, bytes: &[u8]) -> Result<Arc<CompiledModule>> {
+        let module = Arc::new(self.bytecode_module(name, bytes)?);
+        self.modules.insert(name.to_string(), module.clone());
+        Ok(module)
+    }
+
+    /// Deserialize and verify a `.fabc` module
+    fn bytecode_module(&self, name: &str, bytes: &[u8]) -> Result<CompiledModule> {
+        let bytecode = Bytecode::from_bytes(bytes)?;
+        verify(&bytecode)?;
+
+        if self.config.debug.dump_bytecode {
+            eprintln!("=== Bytecode for {} ===", name);
+            eprintln!("{:#?}", bytecode);
+        }
+
+        Ok(CompiledModule {
+            metadata: ModuleMetadata {
+                name: name.to_string(),
+                version: None,
+                description: None,
+                dependencies: Vec::new(),
+                exports: Vec::new(),
+            },
+            bytecode: Arc::new(bytecode),
+            ast: None,
+            source_map: None,
+            exports: HashMap::new(),
+        })
+    }
+
     /// Compile a source module
     fn compile_module(&self, source: SourceModule) -> Result<CompiledModule> {
         // Load dependencies first
@@ -265,6 +311,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_load_from_bytecode() {
+        let graph = parse("let f = (x) => x * 2; f(21)").unwrap();
+        let bytes = Compiler::new().compile(&graph).unwrap().to_bytes().unwrap();
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("double.fabc"), &bytes).unwrap();
+        let mut config = RuntimeConfig::default();
+        config
+            .modules
+            .search_paths
+            .push(temp_dir.path().to_path_buf());
+        let loader = ModuleLoader::new(Arc::new(config));
+
+        let module = loader.load("double").unwrap();
+        assert_eq!(module.metadata.name, "double");
+        assert!(module.ast.is_none());
+
+        // A jump out of the chunk is caught by the verifier
+        let mut bytecode = Bytecode::from_bytes(&bytes).unwrap();
+        let main = bytecode.main_chunk;
+        bytecode.chunks[main].instructions[0] = fluentai_bytecode::Instruction::with_arg(
+            fluentai_bytecode::Opcode::Jump,
+            u32::MAX,
+        );
+        let result = loader.load_from_bytecode("broken", &bytecode.to_bytes().unwrap());
+        assert!(matches!(result, Err(RuntimeError::Verification(_))));
+
+        let result = loader.load_from_bytecode("garbage", b"not bytecode");
+        assert!(matches!(result, Err(RuntimeError::BytecodeFormat(_))));
+    }
+
     #[test]
     fn test_load_from_source() {
         let config = RuntimeConfig::default();
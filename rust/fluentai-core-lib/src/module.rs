@@ -214,6 +214,16 @@ pub fn resolve_module_path(name: &str, search_paths: &[PathBuf]) -> Option<PathB
         if ai_path.exists() && ai_path.is_file() {
             return Some(ai_path);
         }
+
+        // Finally try a precompiled bytecode module
+        let bytecode_path = search_path.join(format!(
+            "{}.{}",
+            name.replace('/', std::path::MAIN_SEPARATOR_STR),
+            fluentai_bytecode::format::FILE_EXTENSION
+        ));
+        if bytecode_path.exists() && bytecode_path.is_file() {
+            return Some(bytecode_path);
+        }
     }
 
     None
@@ -3,6 +3,7 @@
 use crate::documentation::{
     Documentation, DocumentationCategory, DocumentationVisibility, DocumentedNode,
 };
+use crate::source::{LineCol, LineIndex};
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -45,10 +46,12 @@ impl fmt::Display for NodeId {
 /// Node metadata for analysis and optimization
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NodeMetadata {
-    /// Source location information
+    /// Source location: start and end byte offsets in the parsed source
     pub span: Option<(usize, usize)>,
     /// Inferred type information
     pub type_info: Option<String>,
+    /// Declared type from a source annotation
+    pub type_annotation: Option<TypeExpr>,
     /// Purity flag for optimization
     pub is_pure: Option<bool>,
     /// Custom annotations
@@ -184,6 +187,9 @@ pub struct Graph {
     pub metadata: AstHashMap<NodeId, NodeMetadata>,
     /// Graph-level metadata (e.g., module name)
     pub graph_metadata: AstHashMap<String, String>,
+    /// Line index of the source the graph was parsed from, for resolving node spans
+    #[serde(default)]
+    pub line_index: Option<LineIndex>,
 }
 
 impl Default for Graph {
@@ -200,6 +206,7 @@ impl Graph {
             next_id: 1, // Start at 1 since 0 is reserved for null
             metadata: AstHashMap::default(),
             graph_metadata: AstHashMap::default(),
+            line_index: None,
         }
     }
 
@@ -216,6 +223,20 @@ impl Graph {
         Ok(id)
     }
 
+    /// ID the next added node will take
+    pub fn next_node_id(&self) -> u32 {
+        self.next_id
+    }
+
+    /// Remove the nodes added since `next_node_id` returned `from`, as when a
+    /// speculative parse is abandoned. Their IDs are not reused.
+    pub fn remove_nodes_since(&mut self, from: u32) {
+        for id in (from..self.next_id).filter_map(NodeId::new) {
+            self.nodes.remove(&id);
+            self.metadata.remove(&id);
+        }
+    }
+
     pub fn get_node(&self, id: NodeId) -> Option<&Node> {
         self.nodes.get(&id)
     }
@@ -240,6 +261,18 @@ impl Graph {
         self.metadata.insert(id, metadata);
     }
 
+    /// Byte range of a node in the source it was parsed from
+    pub fn span(&self, id: NodeId) -> Option<(usize, usize)> {
+        self.metadata.get(&id)?.span
+    }
+
+    /// Start and end line/column of a node in the source it was parsed from
+    pub fn location(&self, id: NodeId) -> Option<(LineCol, LineCol)> {
+        let (start, end) = self.span(id)?;
+        let index = self.line_index.as_ref()?;
+        Some((index.line_col(start), index.line_col(end)))
+    }
+
     /// Sets documentation ID for a node
     pub fn set_documentation(&mut self, id: NodeId, doc_id: String) {
         self.metadata_mut(id).documentation_id = Some(doc_id);
@@ -337,13 +370,13 @@ impl Graph {
                         stack.push(*then_branch);
                         stack.push(*condition);
                     }
-                    Node::Match { expr, branches } => {
+                    Node::Match { expr, branches, .. } => {
                         for (_, branch) in branches.iter().rev() {
                             stack.push(*branch);
                         }
                         stack.push(*expr);
                     }
-                    Node::List(items) => {
+                    Node::List(items) | Node::Tuple(items) => {
                         for item in items.iter().rev() {
                             stack.push(*item);
                         }
@@ -441,6 +474,11 @@ impl Graph {
                     Node::Await { expr } | Node::Spawn { expr } => {
                         stack.push(*expr);
                     }
+                    Node::Parallel { branches } => {
+                        for branch in branches.iter().rev() {
+                            stack.push(*branch);
+                        }
+                    }
                     Node::Module { body, .. } => {
                         stack.push(*body);
                     }
@@ -454,6 +492,45 @@ impl Graph {
                         stack.push(*target);
                         stack.push(*value);
                     }
+                    Node::While { condition, body } => {
+                        stack.push(*body);
+                        stack.push(*condition);
+                    }
+                    Node::For { iterable, body, .. } => {
+                        stack.push(*body);
+                        stack.push(*iterable);
+                    }
+                    Node::Range { start, end, .. } => {
+                        stack.push(*end);
+                        stack.push(*start);
+                    }
+                    Node::Break { value: Some(value) } => {
+                        stack.push(*value);
+                    }
+                    Node::StructConstruct { fields, .. } => {
+                        for (_, value) in fields.iter().rev() {
+                            stack.push(*value);
+                        }
+                    }
+                    Node::FieldAccess { object, .. } => {
+                        stack.push(*object);
+                    }
+                    Node::StructUpdate { base, fields, .. } => {
+                        for (_, value) in fields.iter().rev() {
+                            stack.push(*value);
+                        }
+                        stack.push(*base);
+                    }
+                    Node::TraitImpl { methods, .. } => {
+                        for (_, method) in methods.iter().rev() {
+                            stack.push(*method);
+                        }
+                    }
+                    Node::MethodCall { args, .. } => {
+                        for arg in args.iter().rev() {
+                            stack.push(*arg);
+                        }
+                    }
                     _ => {} // Leaf nodes
                 }
             }
@@ -506,13 +583,13 @@ impl Graph {
                     self.dfs_helper(*then_branch, visited, visitor);
                     self.dfs_helper(*else_branch, visited, visitor);
                 }
-                Node::Match { expr, branches } => {
+                Node::Match { expr, branches, .. } => {
                     self.dfs_helper(*expr, visited, visitor);
                     for (_, branch) in branches {
                         self.dfs_helper(*branch, visited, visitor);
                     }
                 }
-                Node::List(items) => {
+                Node::List(items) | Node::Tuple(items) => {
                     for item in items {
                         self.dfs_helper(*item, visited, visitor);
                     }
@@ -537,6 +614,45 @@ impl Graph {
                     self.dfs_helper(*target, visited, visitor);
                     self.dfs_helper(*value, visited, visitor);
                 }
+                Node::While { condition, body } => {
+                    self.dfs_helper(*condition, visited, visitor);
+                    self.dfs_helper(*body, visited, visitor);
+                }
+                Node::For { iterable, body, .. } => {
+                    self.dfs_helper(*iterable, visited, visitor);
+                    self.dfs_helper(*body, visited, visitor);
+                }
+                Node::Range { start, end, .. } => {
+                    self.dfs_helper(*start, visited, visitor);
+                    self.dfs_helper(*end, visited, visitor);
+                }
+                Node::Break { value: Some(value) } => {
+                    self.dfs_helper(*value, visited, visitor);
+                }
+                Node::StructConstruct { fields, .. } => {
+                    for (_, value) in fields {
+                        self.dfs_helper(*value, visited, visitor);
+                    }
+                }
+                Node::FieldAccess { object, .. } => {
+                    self.dfs_helper(*object, visited, visitor);
+                }
+                Node::StructUpdate { base, fields, .. } => {
+                    self.dfs_helper(*base, visited, visitor);
+                    for (_, value) in fields {
+                        self.dfs_helper(*value, visited, visitor);
+                    }
+                }
+                Node::TraitImpl { methods, .. } => {
+                    for (_, method) in methods {
+                        self.dfs_helper(*method, visited, visitor);
+                    }
+                }
+                Node::MethodCall { args, .. } => {
+                    for arg in args {
+                        self.dfs_helper(*arg, visited, visitor);
+                    }
+                }
                 _ => {} // Leaf nodes
             }
         }
@@ -567,11 +683,11 @@ impl Graph {
                     children.push(*then_branch);
                     children.push(*else_branch);
                 }
-                Node::Match { expr, branches } => {
+                Node::Match { expr, branches, .. } => {
                     children.push(*expr);
                     children.extend(branches.iter().map(|(_, b)| b));
                 }
-                Node::List(items) => {
+                Node::List(items) | Node::Tuple(items) => {
                     children.extend(items);
                 }
                 Node::Effect { args, .. } => {
@@ -657,6 +773,9 @@ impl Graph {
                 Node::Await { expr } | Node::Spawn { expr } => {
                     children.push(*expr);
                 }
+                Node::Parallel { branches } => {
+                    children.extend(branches);
+                }
                 Node::Module { body, .. } => {
                     children.push(*body);
                 }
@@ -667,6 +786,37 @@ impl Graph {
                     children.push(*target);
                     children.push(*value);
                 }
+                Node::While { condition, body } => {
+                    children.push(*condition);
+                    children.push(*body);
+                }
+                Node::For { iterable, body, .. } => {
+                    children.push(*iterable);
+                    children.push(*body);
+                }
+                Node::Range { start, end, .. } => {
+                    children.push(*start);
+                    children.push(*end);
+                }
+                Node::Break { value: Some(value) } => {
+                    children.push(*value);
+                }
+                Node::StructConstruct { fields, .. } => {
+                    children.extend(fields.iter().map(|(_, v)| v));
+                }
+                Node::FieldAccess { object, .. } => {
+                    children.push(*object);
+                }
+                Node::StructUpdate { base, fields, .. } => {
+                    children.push(*base);
+                    children.extend(fields.iter().map(|(_, v)| v));
+                }
+                Node::TraitImpl { methods, .. } => {
+                    children.extend(methods.iter().map(|(_, m)| m));
+                }
+                Node::MethodCall { args, .. } => {
+                    children.extend(args);
+                }
                 _ => {} // Leaf nodes have no children
             }
         }
@@ -826,6 +976,7 @@ impl Graph {
                     cons_result,
                 ),
             ],
+            must_match: false,
         })
     }
 
@@ -845,6 +996,7 @@ impl Graph {
         self.add_node(Node::Match {
             expr: value,
             branches,
+            must_match: false,
         })
     }
 }
@@ -878,6 +1030,29 @@ pub enum Node {
         then_branch: NodeId,
         else_branch: NodeId,
     },
+    /// `while condition { body }`, evaluating to the value given to `break`, or nil
+    While {
+        condition: NodeId,
+        body: NodeId,
+    },
+    /// `for pattern in iterable { body }` over a list or a range
+    For {
+        pattern: Pattern,
+        iterable: NodeId,
+        body: NodeId,
+    },
+    /// Integer range `start..end`, or `start..=end` when inclusive
+    Range {
+        start: NodeId,
+        end: NodeId,
+        inclusive: bool,
+    },
+    /// Leave the innermost loop, optionally with its result
+    Break {
+        value: Option<NodeId>,
+    },
+    /// Skip to the next iteration of the innermost loop
+    Continue,
 
     // Function application
     Application {
@@ -901,11 +1076,17 @@ pub enum Node {
 
     // Data structures
     List(Vec<NodeId>),
+    /// `(a, b, c)`, a fixed-size tuple of the evaluated elements
+    Tuple(Vec<NodeId>),
 
     // Pattern matching
     Match {
         expr: NodeId,
         branches: Vec<(Pattern, NodeId)>,
+        /// Raise a match error, rather than produce nil, when no branch matches;
+        /// set for the patterns of let bindings and parameters
+        #[serde(default)]
+        must_match: bool,
     },
 
     // Module system
@@ -954,6 +1135,11 @@ pub enum Node {
     Spawn {
         expr: NodeId,
     },
+    /// `parallel { a; b; c }`, running each branch concurrently and
+    /// evaluating to the list of their results
+    Parallel {
+        branches: Vec<NodeId>,
+    },
     Channel {
         capacity: Option<NodeId>, // Optional capacity expression
     },
@@ -1026,13 +1212,192 @@ pub enum Node {
         complexity: Option<String>,
         pure: bool,
     },
+
+    // Records
+    /// Struct definition with typed fields
+    Struct {
+        name: String,
+        /// Generic parameters, such as `T` in `struct Box<T>`
+        #[serde(default)]
+        type_params: Vec<String>,
+        fields: Vec<StructField>,
+        derives: Vec<String>,
+    },
+    /// Struct construction: `Name { field: value, ... }`
+    StructConstruct {
+        name: String,
+        fields: Vec<(String, NodeId)>,
+    },
+    /// Field access: `record.field`
+    FieldAccess {
+        object: NodeId,
+        field: String,
+    },
+    /// Functional update: `Name { field: value, ..base }` - returns a copy of base
+    StructUpdate {
+        name: String,
+        base: NodeId,
+        fields: Vec<(String, NodeId)>,
+    },
+    /// Enum definition with its variants
+    Enum {
+        name: String,
+        /// Generic parameters, such as `T` in `enum Option<T>`
+        #[serde(default)]
+        type_params: Vec<String>,
+        variants: Vec<EnumVariant>,
+        derives: Vec<String>,
+    },
+
+    // Traits
+    /// Trait declaration with method signatures
+    Trait {
+        name: String,
+        methods: Vec<TraitMethod>,
+    },
+    /// Trait implementation: `Type as Trait { ... }`
+    TraitImpl {
+        type_name: String,
+        trait_name: String,
+        methods: Vec<(String, NodeId)>, // Method name -> lambda
+    },
+    /// Trait method call: `receiver.method(args)`, dispatched on the receiver's type
+    MethodCall {
+        method: String,
+        args: Vec<NodeId>, // Receiver first
+    },
+
+    // User-defined effects
+    /// Effect declaration: `effect Logger { function log(msg: string); }`
+    EffectDef {
+        name: String,
+        operations: Vec<EffectOperation>,
+    },
+
+    // Foreign functions
+    /// Foreign function declaration: `extern function sha256(data: String) -> String`,
+    /// bound to a host function when the program is loaded
+    Extern {
+        name: String,
+        params: Vec<(String, TypeExpr)>,
+        return_type: TypeExpr, // `Unit` when omitted
+        effects: Vec<String>,
+    },
+
+    // Macros
+    /// Macro definition: `macro name(params) { template }`, removed by macro expansion
+    MacroDef {
+        name: String,
+        params: Vec<String>,
+        body: String, // Template source text
+    },
+
+    // Parse recovery
+    /// Placeholder for source that failed to parse, produced by the recovering parser
+    Error {
+        message: String,
+    },
+}
+
+/// A field in a struct definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructField {
+    pub name: String,
+    pub field_type: TypeExpr,
+    pub is_public: bool,
+}
+
+/// A variant in an enum definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnumVariant {
+    pub name: String,
+    pub fields: Vec<TypeExpr>, // Field types, in order
+}
+
+/// A method signature in a trait declaration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraitMethod {
+    pub name: String,
+    pub params: Vec<(String, TypeExpr)>, // Including `self`; `TypeExpr::Infer` when unannotated
+    pub return_type: Option<TypeExpr>,
+}
+
+/// An operation signature in an effect declaration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EffectOperation {
+    pub name: String,
+    pub params: Vec<(String, TypeExpr)>, // `TypeExpr::Infer` when unannotated
+    pub return_type: Option<TypeExpr>,
+}
+
+/// A type expression as written in a source annotation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TypeExpr {
+    /// Named type with optional arguments: `Int`, `List<T>`, `Map<String, Int>`
+    Named { name: String, args: Vec<TypeExpr> },
+    /// Type variable: `a`
+    Variable(String),
+    /// Function type with its effect row: `(Int, String) -> Bool with(IO)`
+    Function {
+        params: Vec<TypeExpr>,
+        result: Box<TypeExpr>,
+        effects: Vec<String>,
+    },
+    /// Tuple type: `(Int, String)`
+    Tuple(Vec<TypeExpr>),
+    /// Placeholder left to inference: `_` or an omitted annotation
+    Infer,
+}
+
+impl TypeExpr {
+    /// Named type without arguments
+    pub fn named(name: impl Into<String>) -> Self {
+        TypeExpr::Named {
+            name: name.into(),
+            args: vec![],
+        }
+    }
+}
+
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn join(types: &[TypeExpr]) -> String {
+            types
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        match self {
+            TypeExpr::Named { name, args } if args.is_empty() => write!(f, "{name}"),
+            TypeExpr::Named { name, args } => write!(f, "{name}<{}>", join(args)),
+            TypeExpr::Variable(name) => write!(f, "{name}"),
+            TypeExpr::Function {
+                params,
+                result,
+                effects,
+            } => {
+                write!(f, "({}) -> {result}", join(params))?;
+                if !effects.is_empty() {
+                    write!(f, " with({})", effects.join(", "))?;
+                }
+                Ok(())
+            }
+            TypeExpr::Tuple(elements) => write!(f, "({})", join(elements)),
+            TypeExpr::Infer => write!(f, "_"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Literal {
     Integer(i64),
     Float(f64),
+    BigInt(crate::value::BigInt),
+    Decimal(crate::value::Decimal),
     String(String),
+    Bytes(Vec<u8>),
     Symbol(String),
     Boolean(bool),
     Nil,
@@ -1043,7 +1408,10 @@ impl fmt::Display for Literal {
         match self {
             Literal::Integer(i) => write!(f, "{i}"),
             Literal::Float(fl) => write!(f, "{fl}"),
+            Literal::BigInt(n) => write!(f, "{n}n"),
+            Literal::Decimal(d) => write!(f, "{d}d"),
             Literal::String(s) => write!(f, "\"{s}\""),
+            Literal::Bytes(b) => write!(f, "{}", crate::value::format_bytes(b)),
             Literal::Symbol(s) => write!(f, "{s}"),
             Literal::Boolean(b) => write!(f, "{b}"),
             Literal::Nil => write!(f, "nil"),
@@ -1066,6 +1434,8 @@ pub enum Pattern {
         name: String,
         patterns: Vec<Pattern>,
     },
+    /// Tuple pattern: matches a tuple of the same arity element by element
+    Tuple(Vec<Pattern>),
     Wildcard,
 
     // Complex pattern extensions
@@ -1145,6 +1515,11 @@ impl Pattern {
         }
     }
 
+    /// Creates a tuple pattern
+    pub fn tuple(patterns: Vec<Pattern>) -> Self {
+        Pattern::Tuple(patterns)
+    }
+
     /// Creates a wildcard pattern that matches anything
     pub fn wildcard() -> Self {
         Pattern::Wildcard
@@ -1212,6 +1587,38 @@ impl Pattern {
             pattern: Box::new(pattern),
         }
     }
+
+    /// Names bound by this pattern, in left-to-right order
+    pub fn bound_variables(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.collect_bound_variables(&mut names);
+        names
+    }
+
+    fn collect_bound_variables(&self, names: &mut Vec<String>) {
+        match self {
+            Pattern::Variable(name) => names.push(name.clone()),
+            Pattern::Constructor { patterns, .. } | Pattern::Tuple(patterns) => {
+                for pattern in patterns {
+                    pattern.collect_bound_variables(names);
+                }
+            }
+            Pattern::Guard { pattern, .. } | Pattern::View { pattern, .. } => {
+                pattern.collect_bound_variables(names)
+            }
+            Pattern::As { binding, pattern } => {
+                names.push(binding.clone());
+                pattern.collect_bound_variables(names);
+            }
+            // Every alternative binds the same names
+            Pattern::Or(patterns) => {
+                if let Some(first) = patterns.first() {
+                    first.collect_bound_variables(names);
+                }
+            }
+            Pattern::Literal(_) | Pattern::Wildcard | Pattern::Range(_) => {}
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -1226,6 +1633,78 @@ pub enum EffectType {
     Dom,
     Async,
     Concurrent,
+    /// An effect declared in source with `effect Name { ... }`
+    Custom(EffectName),
+}
+
+impl EffectType {
+    /// The effects built into the runtime
+    const BUILTINS: [EffectType; 10] = [
+        EffectType::Pure,
+        EffectType::IO,
+        EffectType::State,
+        EffectType::Error,
+        EffectType::Time,
+        EffectType::Network,
+        EffectType::Random,
+        EffectType::Dom,
+        EffectType::Async,
+        EffectType::Concurrent,
+    ];
+
+    /// The built-in effect with this name, or a user-declared effect otherwise
+    ///
+    /// Names that do not start with an uppercase letter, or that differ from a built-in
+    /// effect only by case or a single typo, are rejected rather than taken as new effects.
+    /// Use [`EffectType::from_declared_name`] when the declared effects are known.
+    pub fn from_name(name: &str) -> crate::error::Result<Self> {
+        Self::from_declared_name(name, |_| false)
+    }
+
+    /// Like [`EffectType::from_name`], but a name `is_declared` accepts is always the
+    /// user-declared effect, even if it looks like a misspelled built-in one
+    pub fn from_declared_name(
+        name: &str,
+        is_declared: impl Fn(&str) -> bool,
+    ) -> crate::error::Result<Self> {
+        let unknown = |reason: String| crate::error::Error::UnknownEffect(format!("{} ({})", name, reason));
+
+        if let Some(builtin) = Self::BUILTINS.iter().find(|e| e.to_string() == name) {
+            return Ok(*builtin);
+        }
+        if is_declared(name) {
+            return EffectName::new(name).map(EffectType::Custom);
+        }
+        if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return Err(unknown("effect names start with an uppercase letter".to_string()));
+        }
+        for builtin in Self::BUILTINS {
+            let builtin = builtin.to_string();
+            let near_miss = builtin.eq_ignore_ascii_case(name)
+                || (builtin.len() >= 4 && edit_distance(&builtin, name) == 1);
+            if near_miss {
+                return Err(unknown(format!("did you mean {}?", builtin)));
+            }
+        }
+        EffectName::new(name).map(EffectType::Custom)
+    }
+
+    /// A user-declared effect
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name cannot be interned; see [`EffectName::new`].
+    pub fn custom(name: &str) -> Self {
+        match EffectName::new(name) {
+            Ok(name) => EffectType::Custom(name),
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Whether this is one of the effects built into the runtime
+    pub fn is_builtin(&self) -> bool {
+        !matches!(self, EffectType::Custom(_))
+    }
 }
 
 impl fmt::Display for EffectType {
@@ -1241,7 +1720,127 @@ impl fmt::Display for EffectType {
             EffectType::Dom => write!(f, "Dom"),
             EffectType::Async => write!(f, "Async"),
             EffectType::Concurrent => write!(f, "Concurrent"),
+            EffectType::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// The number of edits (insertions, deletions, substitutions, swaps of adjacent
+/// characters) turning `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Name of a user-declared effect
+///
+/// Names are interned so that [`EffectType`] stays `Copy`; two `EffectName`s are equal
+/// exactly when their strings are. Interned names live for the rest of the process, so
+/// the table is bounded by [`EffectName::MAX_NAMES`] and [`EffectName::MAX_LEN`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EffectName(u32);
+
+/// Interned effect names, indexed by `EffectName`
+struct EffectNames {
+    names: Vec<&'static str>,
+    ids: FxHashMap<&'static str, u32>,
+}
+
+fn effect_names() -> &'static parking_lot::RwLock<EffectNames> {
+    static NAMES: std::sync::OnceLock<parking_lot::RwLock<EffectNames>> =
+        std::sync::OnceLock::new();
+    NAMES.get_or_init(|| {
+        parking_lot::RwLock::new(EffectNames {
+            names: Vec::new(),
+            ids: FxHashMap::default(),
+        })
+    })
+}
+
+impl EffectName {
+    /// Maximum number of distinct effect names in a process
+    pub const MAX_NAMES: usize = 4096;
+    /// Maximum length of an effect name, in bytes
+    pub const MAX_LEN: usize = 128;
+
+    /// Intern an effect name
+    ///
+    /// Fails when the name is empty, longer than [`EffectName::MAX_LEN`], or new while
+    /// [`EffectName::MAX_NAMES`] names are already interned.
+    pub fn new(name: &str) -> crate::error::Result<Self> {
+        if let Some(&id) = effect_names().read().ids.get(name) {
+            return Ok(EffectName(id));
         }
+        if name.is_empty() || name.len() > Self::MAX_LEN {
+            return Err(crate::error::Error::UnknownEffect(format!(
+                "effect names must be 1 to {} bytes long",
+                Self::MAX_LEN
+            )));
+        }
+        let mut table = effect_names().write();
+        if let Some(&id) = table.ids.get(name) {
+            return Ok(EffectName(id));
+        }
+        if table.names.len() >= Self::MAX_NAMES {
+            return Err(crate::error::Error::UnknownEffect(format!(
+                "{} (more than {} effect names)",
+                name,
+                Self::MAX_NAMES
+            )));
+        }
+        // The bounds above cap what is leaked over the life of the process
+        let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+        let id = table.names.len() as u32;
+        table.names.push(name);
+        table.ids.insert(name, id);
+        Ok(EffectName(id))
+    }
+
+    /// The name as written in source
+    pub fn as_str(&self) -> &'static str {
+        effect_names().read().names[self.0 as usize]
+    }
+}
+
+impl fmt::Display for EffectName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for EffectName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl Serialize for EffectName {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for EffectName {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        EffectName::new(&name).map_err(serde::de::Error::custom)
     }
 }
 
@@ -1369,6 +1968,24 @@ impl Node {
                     see_also: vec![],
                     visibility: DocumentationVisibility::Public,
                 },
+                Literal::BigInt(_) => Documentation {
+                    name: "BigInt".to_string(),
+                    syntax: "<integer>n".to_string(),
+                    description: "Arbitrary-precision integer literals. Integer arithmetic that overflows 64 bits is promoted to a BigInt automatically.".to_string(),
+                    examples: vec!["123n".to_string(), "-9223372036854775809n".to_string()],
+                    category: DocumentationCategory::Literal,
+                    see_also: vec!["Integer".to_string(), "Decimal".to_string()],
+                    visibility: DocumentationVisibility::Public,
+                },
+                Literal::Decimal(_) => Documentation {
+                    name: "Decimal".to_string(),
+                    syntax: "<number>d".to_string(),
+                    description: "Exact base-10 decimal literals for arithmetic that must not lose precision, such as currency.".to_string(),
+                    examples: vec!["19.99d".to_string(), "0.1d".to_string(), "100d".to_string()],
+                    category: DocumentationCategory::Literal,
+                    see_also: vec!["Float".to_string(), "BigInt".to_string()],
+                    visibility: DocumentationVisibility::Public,
+                },
                 Literal::String(_) => Documentation {
                     name: "String".to_string(),
                     syntax: "\"<text>\"".to_string(),
@@ -1378,6 +1995,15 @@ impl Node {
                     see_also: vec![],
                     visibility: DocumentationVisibility::Public,
                 },
+                Literal::Bytes(_) => Documentation {
+                    name: "Bytes".to_string(),
+                    syntax: "b\"<bytes>\"".to_string(),
+                    description: "Byte-string literals hold raw binary data. Printable ASCII is written as-is and other bytes use \\xHH escapes.".to_string(),
+                    examples: vec!["b\"GIF89a\"".to_string(), "b\"\\x00\\xff\\r\\n\"".to_string()],
+                    category: DocumentationCategory::Literal,
+                    see_also: vec!["String".to_string()],
+                    visibility: DocumentationVisibility::Public,
+                },
                 Literal::Boolean(_) => Documentation {
                     name: "Boolean".to_string(),
                     syntax: "true | false".to_string(),
@@ -1490,6 +2116,67 @@ impl Node {
                 see_also: vec!["cons".to_string(), "car".to_string(), "cdr".to_string()],
                 visibility: DocumentationVisibility::Public,
             },
+            Node::Tuple(_) => Documentation {
+                name: "Tuple".to_string(),
+                syntax: "(<expr1>, <expr2>, ...)".to_string(),
+                description: "Tuple literal. Creates a fixed-size tuple whose elements may have different types. A one-element tuple needs a trailing comma.".to_string(),
+                examples: vec!["(1, \"one\")".to_string(), "(x, y, z)".to_string(), "(42,)".to_string()],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["List".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::While { .. } => Documentation {
+                name: "While".to_string(),
+                syntax: "while <condition> { <body> }".to_string(),
+                description: "Evaluates the body as long as the condition is true. The loop evaluates to nil, or to the value given to `break`.".to_string(),
+                examples: vec![
+                    "while i < 10 { i = i + 1 }".to_string(),
+                    "while true { if (done()) { break result } }".to_string()
+                ],
+                category: DocumentationCategory::ControlFlow,
+                see_also: vec!["For".to_string(), "Break".to_string(), "Continue".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::For { .. } => Documentation {
+                name: "For".to_string(),
+                syntax: "for <pattern> in <list or range> { <body> }".to_string(),
+                description: "Evaluates the body for each element of a list or each integer of a range. Each element is matched against the pattern, and one that does not match raises a match error. The loop evaluates to nil, or to the value given to `break`.".to_string(),
+                examples: vec![
+                    "for x in [1, 2, 3] { $(x).print() }".to_string(),
+                    "for i in 0..=10 { total = total + i }".to_string(),
+                    "for Some(x) in options { $(x).print() }".to_string()
+                ],
+                category: DocumentationCategory::ControlFlow,
+                see_also: vec!["While".to_string(), "Range".to_string(), "Break".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::Range { .. } => Documentation {
+                name: "Range".to_string(),
+                syntax: "<start>..<end> | <start>..=<end>".to_string(),
+                description: "The integers from start up to end, excluding end unless the range is inclusive. Iterated directly by `for`; elsewhere it evaluates to a list.".to_string(),
+                examples: vec!["for i in 0..n { ... }".to_string(), "1..=3".to_string()],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["For".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::Break { .. } => Documentation {
+                name: "Break".to_string(),
+                syntax: "break | break <value>".to_string(),
+                description: "Leaves the innermost loop. The loop evaluates to the given value, or to nil.".to_string(),
+                examples: vec!["while true { break 42 }".to_string()],
+                category: DocumentationCategory::ControlFlow,
+                see_also: vec!["Continue".to_string(), "While".to_string(), "For".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::Continue => Documentation {
+                name: "Continue".to_string(),
+                syntax: "continue".to_string(),
+                description: "Skips the rest of the loop body and starts the next iteration of the innermost loop.".to_string(),
+                examples: vec!["for x in xs { if (x < 0) { continue } else { nil }; total = total + x }".to_string()],
+                category: DocumentationCategory::ControlFlow,
+                see_also: vec!["Break".to_string(), "While".to_string(), "For".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
             Node::Match { .. } => Documentation {
                 name: "Match".to_string(),
                 syntax: "(match <expr> (<pattern> <body>) ...)".to_string(),
@@ -1587,6 +2274,15 @@ impl Node {
                 see_also: vec!["Async".to_string(), "Channel".to_string()],
                 visibility: DocumentationVisibility::Public,
             },
+            Node::Parallel { .. } => Documentation {
+                name: "Parallel".to_string(),
+                syntax: "parallel { <expr>; <expr>; ... }".to_string(),
+                description: "Evaluates each branch concurrently in its own task and returns the list of their results in branch order. If any branch fails, the remaining branches are cancelled and the error is raised.".to_string(),
+                examples: vec!["parallel { fetch_user(id); fetch_posts(id) }".to_string()],
+                category: DocumentationCategory::Async,
+                see_also: vec!["Spawn".to_string(), "PromiseAll".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
             Node::Channel { .. } => Documentation {
                 name: "Channel".to_string(),
                 syntax: "(chan) or (chan capacity)".to_string(),
@@ -1779,6 +2475,143 @@ impl Node {
                 see_also: vec!["Let".to_string(), "Define".to_string()],
                 visibility: DocumentationVisibility::Public,
             },
+            Node::Struct { .. } => Documentation {
+                name: "Struct".to_string(),
+                syntax: "struct <Name> { <field>: <Type>, ... }".to_string(),
+                description: "Defines a record type with named, typed fields.".to_string(),
+                examples: vec![
+                    "struct Point { x: int, y: int }".to_string(),
+                    "public struct User { public name: string, age: int }".to_string()
+                ],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["StructConstruct".to_string(), "FieldAccess".to_string(), "StructUpdate".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::StructConstruct { .. } => Documentation {
+                name: "StructConstruct".to_string(),
+                syntax: "<Name> { <field>: <expr>, ... }".to_string(),
+                description: "Creates a new record value. Fields are stored in declaration order.".to_string(),
+                examples: vec![
+                    "Point { x: 1, y: 2 }".to_string()
+                ],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["Struct".to_string(), "StructUpdate".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::FieldAccess { .. } => Documentation {
+                name: "FieldAccess".to_string(),
+                syntax: "<expr>.<field>".to_string(),
+                description: "Reads a field from a record. Also works on maps with string keys.".to_string(),
+                examples: vec![
+                    "p.x".to_string(),
+                    "user.address.city".to_string()
+                ],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["Struct".to_string(), "StructUpdate".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::StructUpdate { .. } => Documentation {
+                name: "StructUpdate".to_string(),
+                syntax: "<Name> { <field>: <expr>, ..<base> }".to_string(),
+                description: "Returns a copy of a record with some fields replaced. The original record is unchanged.".to_string(),
+                examples: vec![
+                    "Point { x: 10, ..p }".to_string()
+                ],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["Struct".to_string(), "StructConstruct".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::Enum { .. } => Documentation {
+                name: "Enum".to_string(),
+                syntax: "enum <Name> { <Variant>, <Variant>(<Type>, ...), ... }".to_string(),
+                description: "Defines a sum type. Variants are constructed like functions and matched with patterns.".to_string(),
+                examples: vec![
+                    "enum Shape { Circle(float), Square(float) }".to_string(),
+                    "public enum Color { Red, Green, Blue }".to_string()
+                ],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["Struct".to_string(), "Match".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::Trait { .. } => Documentation {
+                name: "Trait".to_string(),
+                syntax: "trait <Name> { function <method>(self, ...) -> <Type>; ... }".to_string(),
+                description: "Declares a set of method signatures that types can implement.".to_string(),
+                examples: vec![
+                    "trait Area { function area(self) -> float; }".to_string()
+                ],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["TraitImpl".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::TraitImpl { .. } => Documentation {
+                name: "TraitImpl".to_string(),
+                syntax: "<Type> as <Trait> { private function <method>(self, ...) { ... } }".to_string(),
+                description: "Implements a trait for a type. Method calls on a value dispatch on its runtime type.".to_string(),
+                examples: vec![
+                    "Circle as Area { private function area(self) { 3.14 * self.r * self.r } }".to_string()
+                ],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["Trait".to_string(), "Struct".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::MethodCall { .. } => Documentation {
+                name: "MethodCall".to_string(),
+                syntax: "<expr>.<method>(<args>)".to_string(),
+                description: "Calls a trait method, using the implementation for the receiver's struct name, variant tag or enum. Falls back to a function of the same name.".to_string(),
+                examples: vec![
+                    "c.area()".to_string(),
+                    "shape.scale(2)".to_string()
+                ],
+                category: DocumentationCategory::DataStructure,
+                see_also: vec!["Trait".to_string(), "TraitImpl".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::EffectDef { .. } => Documentation {
+                name: "EffectDef".to_string(),
+                syntax: "effect <Name> { function <operation>(<param>: <Type>, ...) -> <Type>; ... }".to_string(),
+                description: "Declares a user-defined effect and the signatures of its operations. Operations are performed with `perform` and intercepted with `handle`.".to_string(),
+                examples: vec![
+                    "effect Logger { function log(msg: string) -> unit; }".to_string(),
+                    "handle { perform Logger.log(\"hi\") } with { Logger.log(msg) => nil }".to_string()
+                ],
+                category: DocumentationCategory::Effect,
+                see_also: vec!["Effect".to_string(), "Handler".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::Extern { .. } => Documentation {
+                name: "Extern".to_string(),
+                syntax: "extern function <name>(<param>: <Type>, ...) -> <Type> with(<Effect>, ...)".to_string(),
+                description: "Declares a function implemented by the host. The declaration is bound to the host function of the same name when the program is loaded, and loading fails if no such function is registered, its arity differs, or the security policy does not grant it.".to_string(),
+                examples: vec![
+                    "extern function sha256(data: String) -> String".to_string(),
+                    "extern function log_line(line: String) with(IO)".to_string()
+                ],
+                category: DocumentationCategory::Function,
+                see_also: vec!["Lambda".to_string(), "Define".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::MacroDef { .. } => Documentation {
+                name: "MacroDef".to_string(),
+                syntax: "macro <name>(<param>, ...) { <template> }".to_string(),
+                description: "Defines a macro. Calls to the macro are replaced by its template before compilation, with each parameter standing for the corresponding argument expression. Names bound inside the template are renamed so they cannot capture the caller's variables.".to_string(),
+                examples: vec![
+                    "macro swap_args(f, a, b) { f(b, a) }".to_string(),
+                    "macro unless(cond, body) { if (cond) { nil } else { body } }".to_string()
+                ],
+                category: DocumentationCategory::Keyword,
+                see_also: vec!["Lambda".to_string()],
+                visibility: DocumentationVisibility::Public,
+            },
+            Node::Error { .. } => Documentation {
+                name: "Error".to_string(),
+                syntax: "<unparseable source>".to_string(),
+                description: "Stands in for a statement or definition that failed to parse, so the rest of the file can still be analyzed.".to_string(),
+                examples: vec![],
+                category: DocumentationCategory::Keyword,
+                see_also: vec![],
+                visibility: DocumentationVisibility::Internal,
+            },
         }
     }
 }
@@ -1857,6 +2690,7 @@ impl<'a> MatchBuilder<'a> {
         self.graph.add_node(Node::Match {
             expr,
             branches: self.branches,
+            must_match: false,
         })
     }
 }
@@ -353,6 +353,7 @@ mod tests {
                     (Pattern::Literal(Literal::Integer(5)), branch1),
                     (Pattern::Wildcard, branch2),
                 ],
+                must_match: false,
             })
             .unwrap();
         visited.clear();
@@ -493,6 +494,58 @@ mod tests {
         assert_ne!(EffectType::Pure, EffectType::IO);
     }
 
+    #[test]
+    fn test_effect_type_from_name() {
+        assert_eq!(EffectType::from_name("IO").unwrap(), EffectType::IO);
+        assert_eq!(EffectType::from_name("Concurrent").unwrap(), EffectType::Concurrent);
+        assert_eq!(EffectType::from_name("Logger").unwrap(), EffectType::custom("Logger"));
+
+        // Lowercase names and near misses of built-in effects are not new effects
+        for name in ["io", "logger", "Io", "STATE", "Netwrok", "Stat", "Asyncs"] {
+            assert!(
+                matches!(EffectType::from_name(name), Err(crate::error::Error::UnknownEffect(_))),
+                "{} should be rejected",
+                name
+            );
+        }
+        let message = EffectType::from_name("Netwrk").unwrap_err().to_string();
+        assert!(message.contains("did you mean Network?"), "{}", message);
+    }
+
+    #[test]
+    fn test_declared_effect_names_are_not_near_misses() {
+        let declared = |name: &str| matches!(name, "Timer" | "Stats");
+        assert_eq!(
+            EffectType::from_declared_name("Timer", declared).unwrap(),
+            EffectType::custom("Timer")
+        );
+        assert_eq!(
+            EffectType::from_declared_name("Stats", declared).unwrap(),
+            EffectType::custom("Stats")
+        );
+        assert_eq!(EffectType::from_declared_name("Time", declared).unwrap(), EffectType::Time);
+
+        // Undeclared near misses still get a suggestion
+        let message = EffectType::from_declared_name("Networks", declared)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("did you mean Network?"), "{}", message);
+    }
+
+    #[test]
+    fn test_effect_names_are_bounded() {
+        let long = "L".repeat(EffectName::MAX_LEN + 1);
+        assert!(EffectName::new(&long).is_err());
+        assert!(EffectName::new("").is_err());
+
+        // Deserializing untrusted input is held to the same bounds
+        use serde::de::value::{Error, StrDeserializer};
+        use serde::Deserialize;
+        let name = EffectName::deserialize(StrDeserializer::<Error>::new("Logger")).unwrap();
+        assert_eq!(EffectType::Custom(name), EffectType::custom("Logger"));
+        assert!(EffectName::deserialize(StrDeserializer::<Error>::new(&long)).is_err());
+    }
+
     // ===== Node Type Tests =====
 
     #[test]
@@ -585,10 +638,11 @@ mod tests {
                     (Pattern::Literal(Literal::Integer(42)), branch1),
                     (Pattern::Wildcard, branch2),
                 ],
+                must_match: false,
             })
             .unwrap();
 
-        if let Some(Node::Match { expr: e, branches }) = graph.get_node(match_node) {
+        if let Some(Node::Match { expr: e, branches, .. }) = graph.get_node(match_node) {
             assert_eq!(*e, expr);
             assert_eq!(branches.len(), 2);
 
@@ -1249,6 +1303,7 @@ mod tests {
                     (Pattern::Literal(Literal::Integer(5)), branch1),
                     (Pattern::Wildcard, branch2),
                 ],
+                must_match: false,
             })
             .unwrap();
 
@@ -1408,6 +1463,7 @@ mod tests {
         let match_node = Node::Match {
             expr: NodeId::new(1).unwrap(),
             branches: vec![],
+            must_match: false,
         };
         let match_docs = match_node.get_node_docs();
         assert_eq!(match_docs.name, "Match");
@@ -2101,6 +2157,7 @@ mod tests {
                     (Pattern::Literal(Literal::Integer(1)), one_branch),
                     (Pattern::Wildcard, other_branch),
                 ],
+                must_match: false,
             })
             .unwrap();
 
@@ -2306,6 +2363,7 @@ mod tests {
             .add_node(Node::Match {
                 expr: var_node,
                 branches: vec![],
+                must_match: false,
             })
             .unwrap();
         let _module_node = graph
@@ -5,15 +5,18 @@
 //! - AST representation
 //! - Value representation
 //! - Error types
+//! - Source positions
 //! - Documentation system
 
 pub mod ast;
 pub mod documentation;
 pub mod error;
+pub mod source;
 pub mod thread_pool;
 pub mod traits;
 pub mod value;
 
 pub use ast::{AstHashMap, AstHashSet};
 pub use error::{Error, Result};
+pub use source::{LineCol, LineIndex};
 pub use thread_pool::{ThreadPool, ThreadPoolBuilder, ThreadPoolConfig};
@@ -0,0 +1,146 @@
+//! Source positions
+//!
+//! Node spans are byte ranges into the parsed source. A [`LineIndex`] built from the
+//! same source turns those offsets into the 1-based line and column numbers used in
+//! error messages, diagnostics and source maps.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A 1-based line and column in a source text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LineCol {
+    /// Line number, starting at 1
+    pub line: u32,
+    /// Column number in characters, starting at 1
+    pub column: u32,
+}
+
+impl fmt::Display for LineCol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Line/column lookup for byte offsets into a source text
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineIndex {
+    /// Byte offset at which each line starts
+    line_starts: Vec<usize>,
+    /// Byte offsets of multi-byte characters, with their encoded length
+    wide_chars: Vec<(usize, usize)>,
+    /// Length of the source in bytes
+    len: usize,
+}
+
+impl LineIndex {
+    /// Index the lines of a source text
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut wide_chars = Vec::new();
+        for (offset, ch) in source.char_indices() {
+            if ch == '\n' {
+                line_starts.push(offset + 1);
+            } else if ch.len_utf8() > 1 {
+                wide_chars.push((offset, ch.len_utf8()));
+            }
+        }
+        Self {
+            line_starts,
+            wide_chars,
+            len: source.len(),
+        }
+    }
+
+    /// Number of lines in the source
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Line and column of a byte offset; offsets past the end map to the end of the source
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        let offset = offset.min(self.len);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        // Columns count characters, so multi-byte characters take one column each
+        let first = self.wide_chars.partition_point(|&(at, _)| at < line_start);
+        let extra: usize = self.wide_chars[first..]
+            .iter()
+            .take_while(|&&(at, _)| at < offset)
+            .map(|&(_, width)| width - 1)
+            .sum();
+
+        LineCol {
+            line: line as u32 + 1,
+            column: (offset - line_start - extra) as u32 + 1,
+        }
+    }
+
+    /// Byte offset of a line and column, if the line exists
+    pub fn offset(&self, position: LineCol) -> Option<usize> {
+        let line = (position.line as usize).checked_sub(1)?;
+        let line_start = *self.line_starts.get(line)?;
+        let line_end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.len, |next| next - 1);
+
+        let mut offset = line_start;
+        let mut column = 1;
+        let mut wide = self.wide_chars[self.wide_chars.partition_point(|&(at, _)| at < line_start)..]
+            .iter()
+            .peekable();
+        while column < position.column && offset < line_end {
+            offset += match wide.peek() {
+                Some(&&(at, width)) if at == offset => {
+                    wide.next();
+                    width
+                }
+                _ => 1,
+            };
+            column += 1;
+        }
+        Some(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let index = LineIndex::new("let x = 1;\nx + 2\n\nend");
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_col(0), LineCol { line: 1, column: 1 });
+        assert_eq!(index.line_col(4), LineCol { line: 1, column: 5 });
+        assert_eq!(index.line_col(11), LineCol { line: 2, column: 1 });
+        assert_eq!(index.line_col(15), LineCol { line: 2, column: 5 });
+        assert_eq!(index.line_col(17), LineCol { line: 3, column: 1 });
+        assert_eq!(index.line_col(18), LineCol { line: 4, column: 1 });
+        assert_eq!(index.line_col(100), LineCol { line: 4, column: 4 });
+    }
+
+    #[test]
+    fn test_columns_count_characters() {
+        let source = "\"héllo\" + x\n\"日本\" + y";
+        let index = LineIndex::new(source);
+        let x = source.find('x').unwrap();
+        let y = source.find('y').unwrap();
+        assert_eq!(index.line_col(x), LineCol { line: 1, column: 11 });
+        assert_eq!(index.line_col(y), LineCol { line: 2, column: 8 });
+        assert_eq!(index.offset(LineCol { line: 1, column: 11 }), Some(x));
+        assert_eq!(index.offset(LineCol { line: 2, column: 8 }), Some(y));
+    }
+
+    #[test]
+    fn test_offset_round_trip() {
+        let source = "a\nbc\n\ndef";
+        let index = LineIndex::new(source);
+        for offset in 0..=source.len() {
+            assert_eq!(index.offset(index.line_col(offset)), Some(offset));
+        }
+        assert_eq!(index.offset(LineCol { line: 9, column: 1 }), None);
+    }
+}
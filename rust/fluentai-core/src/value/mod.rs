@@ -1,8 +1,11 @@
 //! Runtime value representation
 
 pub mod error;
+pub mod numeric;
 
 pub use error::{ValueError, ValueResult};
+pub use num_bigint::BigInt;
+pub use rust_decimal::Decimal;
 
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
@@ -16,9 +19,18 @@ pub enum Value {
     /// Floating point value
     Float(f64),
 
+    /// Arbitrary-precision integer
+    BigInt(BigInt),
+
+    /// Exact base-10 decimal
+    Decimal(Decimal),
+
     /// String value
     String(String),
 
+    /// Raw byte string
+    Bytes(Vec<u8>),
+
     /// Symbol value
     Symbol(String),
 
@@ -31,6 +43,9 @@ pub enum Value {
     /// List of values
     List(Vec<Value>),
 
+    /// Fixed-size tuple of values
+    Tuple(Vec<Value>),
+
     /// Procedure (closure)
     Procedure(Arc<Procedure>),
 
@@ -50,6 +65,12 @@ pub enum Value {
     /// Tagged value for ADTs
     Tagged { tag: String, values: Vec<Value> },
 
+    /// Struct instance with named fields in declaration order
+    Struct {
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+
     /// VM function (bytecode chunk with environment)
     Function { chunk_id: usize, env: Vec<Value> },
 
@@ -117,13 +138,32 @@ impl Value {
     }
 
     pub fn is_number(&self) -> bool {
-        matches!(self, Value::Integer(_) | Value::Float(_))
+        matches!(
+            self,
+            Value::Integer(_) | Value::Float(_) | Value::BigInt(_) | Value::Decimal(_)
+        )
+    }
+
+    pub fn is_exact_number(&self) -> bool {
+        matches!(self, Value::Integer(_) | Value::BigInt(_) | Value::Decimal(_))
+    }
+
+    pub fn is_bigint(&self) -> bool {
+        matches!(self, Value::BigInt(_))
+    }
+
+    pub fn is_decimal(&self) -> bool {
+        matches!(self, Value::Decimal(_))
     }
 
     pub fn is_string(&self) -> bool {
         matches!(self, Value::String(_))
     }
 
+    pub fn is_bytes(&self) -> bool {
+        matches!(self, Value::Bytes(_))
+    }
+
     pub fn is_symbol(&self) -> bool {
         matches!(self, Value::Symbol(_))
     }
@@ -140,6 +180,10 @@ impl Value {
         matches!(self, Value::List(_))
     }
 
+    pub fn is_tuple(&self) -> bool {
+        matches!(self, Value::Tuple(_))
+    }
+
     pub fn is_vector(&self) -> bool {
         matches!(self, Value::Vector(_))
     }
@@ -163,6 +207,10 @@ impl Value {
         matches!(self, Value::Tagged { .. })
     }
 
+    pub fn is_struct(&self) -> bool {
+        matches!(self, Value::Struct { .. })
+    }
+
     pub fn is_function(&self) -> bool {
         matches!(self, Value::Function { .. })
     }
@@ -209,11 +257,27 @@ impl Value {
     }
 
     pub fn as_number(&self) -> ValueResult<f64> {
+        numeric::to_f64(self).ok_or(ValueError::TypeError {
+            expected: "number",
+            actual: self.type_name(),
+        })
+    }
+
+    pub fn as_bigint(&self) -> ValueResult<&BigInt> {
         match self {
-            Value::Integer(n) => Ok(*n as f64),
-            Value::Float(f) => Ok(*f),
+            Value::BigInt(n) => Ok(n),
             _ => Err(ValueError::TypeError {
-                expected: "number",
+                expected: "bigint",
+                actual: self.type_name(),
+            }),
+        }
+    }
+
+    pub fn as_decimal(&self) -> ValueResult<Decimal> {
+        match self {
+            Value::Decimal(d) => Ok(*d),
+            _ => Err(ValueError::TypeError {
+                expected: "decimal",
                 actual: self.type_name(),
             }),
         }
@@ -229,6 +293,16 @@ impl Value {
         }
     }
 
+    pub fn as_bytes(&self) -> ValueResult<&[u8]> {
+        match self {
+            Value::Bytes(b) => Ok(b),
+            _ => Err(ValueError::TypeError {
+                expected: "bytes",
+                actual: self.type_name(),
+            }),
+        }
+    }
+
     pub fn as_symbol(&self) -> ValueResult<&str> {
         match self {
             Value::Symbol(s) => Ok(s),
@@ -259,6 +333,16 @@ impl Value {
         }
     }
 
+    pub fn as_tuple(&self) -> ValueResult<&[Value]> {
+        match self {
+            Value::Tuple(items) => Ok(items),
+            _ => Err(ValueError::TypeError {
+                expected: "tuple",
+                actual: self.type_name(),
+            }),
+        }
+    }
+
     pub fn as_vector(&self) -> ValueResult<&[Value]> {
         match self {
             Value::Vector(items) => Ok(items),
@@ -349,21 +433,69 @@ impl Value {
         }
     }
 
+    /// Look up a field on a struct value
+    pub fn get_field(&self, field: &str) -> ValueResult<&Value> {
+        match self {
+            Value::Struct { name, fields } => fields
+                .iter()
+                .find(|(field_name, _)| field_name == field)
+                .map(|(_, value)| value)
+                .ok_or_else(|| {
+                    ValueError::InvalidOperation(format!("struct {} has no field '{}'", name, field))
+                }),
+            _ => Err(ValueError::TypeError {
+                expected: "struct",
+                actual: self.type_name(),
+            }),
+        }
+    }
+
+    /// Return a copy of a struct value with one field replaced
+    pub fn with_field(&self, field: &str, value: Value) -> ValueResult<Value> {
+        match self {
+            Value::Struct { name, fields } => {
+                let mut fields = fields.clone();
+                match fields.iter_mut().find(|(field_name, _)| field_name == field) {
+                    Some(slot) => slot.1 = value,
+                    None => {
+                        return Err(ValueError::InvalidOperation(format!(
+                            "struct {} has no field '{}'",
+                            name, field
+                        )))
+                    }
+                }
+                Ok(Value::Struct {
+                    name: name.clone(),
+                    fields,
+                })
+            }
+            _ => Err(ValueError::TypeError {
+                expected: "struct",
+                actual: self.type_name(),
+            }),
+        }
+    }
+
     /// Get type name for error messages
     pub fn type_name(&self) -> &'static str {
         match self {
             Value::Integer(_) => "integer",
             Value::Float(_) => "float",
+            Value::BigInt(_) => "bigint",
+            Value::Decimal(_) => "decimal",
             Value::String(_) => "string",
+            Value::Bytes(_) => "bytes",
             Value::Symbol(_) => "symbol",
             Value::Boolean(_) => "boolean",
             Value::Nil => "nil",
             Value::List(_) => "list",
+            Value::Tuple(_) => "tuple",
             Value::Procedure(_) => "procedure",
             Value::Vector(_) => "vector",
             Value::Map(_) => "map",
             Value::NativeFunction { .. } => "native-function",
             Value::Tagged { .. } => "tagged",
+            Value::Struct { .. } => "struct",
             Value::Function { .. } => "function",
             Value::Promise(_) => "promise",
             Value::Future { .. } => "future",
@@ -401,10 +533,14 @@ impl Value {
             (Value::Nil, Value::Nil) => true,
             (Value::Integer(a), Value::Integer(b)) => a == b,
             (Value::Float(a), Value::Float(b)) => (a - b).abs() < f64::EPSILON,
+            (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                numeric::compare(a, b) == Some(std::cmp::Ordering::Equal)
+            }
             (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
             (Value::Symbol(a), Value::Symbol(b)) => a == b,
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
-            (Value::List(a), Value::List(b)) => {
+            (Value::List(a), Value::List(b)) | (Value::Tuple(a), Value::Tuple(b)) => {
                 a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.deep_eq(y))
             }
             (Value::Vector(a), Value::Vector(b)) => {
@@ -430,6 +566,23 @@ impl Value {
                     && vals_a.len() == vals_b.len()
                     && vals_a.iter().zip(vals_b.iter()).all(|(x, y)| x.deep_eq(y))
             }
+            (
+                Value::Struct {
+                    name: name_a,
+                    fields: fields_a,
+                },
+                Value::Struct {
+                    name: name_b,
+                    fields: fields_b,
+                },
+            ) => {
+                name_a == name_b
+                    && fields_a.len() == fields_b.len()
+                    && fields_a
+                        .iter()
+                        .zip(fields_b.iter())
+                        .all(|((ka, va), (kb, vb))| ka == kb && va.deep_eq(vb))
+            }
             (
                 Value::Function {
                     chunk_id: id_a,
@@ -477,6 +630,9 @@ impl Value {
 
         match (self, other) {
             (Value::Integer(a), Value::Integer(b)) => Ok(a.cmp(b)),
+            (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                Ok(numeric::compare(a, b).unwrap_or(Ordering::Equal))
+            }
             (Value::Float(a), Value::Float(b)) => {
                 if a < b {
                     Ok(Ordering::Less)
@@ -520,11 +676,21 @@ impl std::fmt::Debug for Value {
         match self {
             Value::Integer(n) => write!(f, "Integer({})", n),
             Value::Float(x) => write!(f, "Float({})", x),
+            Value::BigInt(n) => write!(f, "BigInt({})", n),
+            Value::Decimal(d) => write!(f, "Decimal({})", d),
             Value::String(s) => write!(f, "String({:?})", s),
+            Value::Bytes(b) => write!(f, "Bytes({})", format_bytes(b)),
             Value::Symbol(s) => write!(f, "Symbol({:?})", s),
             Value::Boolean(b) => write!(f, "Boolean({})", b),
             Value::Nil => write!(f, "Nil"),
             Value::List(items) => f.debug_list().entries(items).finish(),
+            Value::Tuple(items) => {
+                let mut tuple = f.debug_tuple("Tuple");
+                for item in items {
+                    tuple.field(item);
+                }
+                tuple.finish()
+            }
             Value::Procedure(proc) => f.debug_struct("Procedure").field("proc", proc).finish(),
             Value::Vector(items) => f.debug_struct("Vector").field("items", items).finish(),
             Value::Map(map) => f.debug_struct("Map").field("map", map).finish(),
@@ -538,6 +704,11 @@ impl std::fmt::Debug for Value {
                 .field("tag", tag)
                 .field("values", values)
                 .finish(),
+            Value::Struct { name, fields } => f
+                .debug_struct("Struct")
+                .field("name", name)
+                .field("fields", fields)
+                .finish(),
             Value::Function { chunk_id, env } => f
                 .debug_struct("Function")
                 .field("chunk_id", chunk_id)
@@ -570,9 +741,14 @@ impl PartialEq for Value {
             (Value::Boolean(a), Value::Boolean(b)) => a == b,
             (Value::Integer(a), Value::Integer(b)) => a == b,
             (Value::Float(a), Value::Float(b)) => (a - b).abs() < f64::EPSILON,
+            (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                numeric::compare(a, b) == Some(std::cmp::Ordering::Equal)
+            }
             (Value::String(a), Value::String(b)) => a == b,
+            (Value::Bytes(a), Value::Bytes(b)) => a == b,
             (Value::Symbol(a), Value::Symbol(b)) => a == b,
             (Value::List(a), Value::List(b)) => a == b,
+            (Value::Tuple(a), Value::Tuple(b)) => a == b,
             (Value::Vector(a), Value::Vector(b)) => a == b,
             (Value::Map(a), Value::Map(b)) => a == b,
             (Value::Procedure(a), Value::Procedure(b)) => Arc::ptr_eq(a, b),
@@ -598,6 +774,16 @@ impl PartialEq for Value {
                     values: v2,
                 },
             ) => t1 == t2 && v1 == v2,
+            (
+                Value::Struct {
+                    name: n1,
+                    fields: f1,
+                },
+                Value::Struct {
+                    name: n2,
+                    fields: f2,
+                },
+            ) => n1 == n2 && f1 == f2,
             (
                 Value::Function {
                     chunk_id: id1,
@@ -650,12 +836,34 @@ impl PartialEq for Value {
     }
 }
 
+/// Render bytes as a `b"..."` literal, escaping anything that is not printable ASCII
+pub fn format_bytes(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 3);
+    out.push_str("b\"");
+    for &byte in bytes {
+        match byte {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            0x20..=0x7e => out.push(byte as char),
+            _ => out.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    out.push('"');
+    out
+}
+
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Value::Integer(n) => write!(f, "{}", n),
             Value::Float(x) => write!(f, "{}", x),
+            Value::BigInt(n) => write!(f, "{}", n),
+            Value::Decimal(d) => write!(f, "{}", d),
             Value::String(s) => write!(f, "\"{}\"", s),
+            Value::Bytes(b) => write!(f, "{}", format_bytes(b)),
             Value::Symbol(s) => write!(f, "{}", s),
             Value::Boolean(b) => write!(f, "{}", if *b { "#t" } else { "#f" }),
             Value::Nil => write!(f, "nil"),
@@ -669,6 +877,20 @@ impl std::fmt::Display for Value {
                 }
                 write!(f, ")")
             }
+            Value::Tuple(items) => {
+                write!(f, "(")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                // A trailing comma tells a 1-tuple apart from a parenthesised value
+                if items.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
             Value::Procedure(_) => write!(f, "#<procedure>"),
             Value::Vector(items) => {
                 write!(f, "#(")?;
@@ -692,6 +914,16 @@ impl std::fmt::Display for Value {
                 }
                 write!(f, ")")
             }
+            Value::Struct { name, fields } => {
+                write!(f, "{} {{", name)?;
+                for (i, (field, val)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, " {}: {}", field, val)?;
+                }
+                write!(f, " }}")
+            }
             Value::Function { .. } => write!(f, "#<function>"),
             Value::Promise(id) => write!(f, "#<promise:{}>", id),
             Value::Future { .. } => write!(f, "#<future>"),
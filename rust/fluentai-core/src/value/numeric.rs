@@ -0,0 +1,262 @@
+//! Arithmetic over the exact numeric tower
+//!
+//! `Integer` values are fixed-width until an operation overflows, at which
+//! point the result is promoted to a `BigInt`. Once a computation involves a
+//! `BigInt` its result stays a `BigInt`. `Decimal` values are exact base-10
+//! numbers; combining a `Decimal` with an integer produces a `Decimal`.
+//! Floats are never mixed with exact numbers implicitly.
+
+use super::{Value, ValueError, ValueResult};
+use num_bigint::BigInt;
+use num_traits::{ToPrimitive, Zero};
+use rust_decimal::Decimal;
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// Binary arithmetic operations supported on exact numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    /// Addition
+    Add,
+    /// Subtraction
+    Sub,
+    /// Multiplication
+    Mul,
+    /// Truncating division
+    Div,
+    /// Remainder
+    Rem,
+}
+
+impl ArithOp {
+    fn name(self) -> &'static str {
+        match self {
+            ArithOp::Add => "add",
+            ArithOp::Sub => "sub",
+            ArithOp::Mul => "mul",
+            ArithOp::Div => "div",
+            ArithOp::Rem => "mod",
+        }
+    }
+}
+
+/// Convert an integer or big integer to a `BigInt`
+fn to_bigint(value: &Value) -> Option<BigInt> {
+    match value {
+        Value::Integer(n) => Some(BigInt::from(*n)),
+        Value::BigInt(n) => Some(n.clone()),
+        _ => None,
+    }
+}
+
+/// Convert any exact number to a `Decimal`
+fn to_decimal(value: &Value) -> ValueResult<Decimal> {
+    match value {
+        Value::Integer(n) => Ok(Decimal::from(*n)),
+        Value::BigInt(n) => {
+            Decimal::from_str(&n.to_string()).map_err(|e| ValueError::ConversionError {
+                from: "bigint",
+                to: "decimal",
+                reason: e.to_string(),
+            })
+        }
+        Value::Decimal(d) => Ok(*d),
+        _ => Err(ValueError::TypeError {
+            expected: "exact number",
+            actual: value.type_name(),
+        }),
+    }
+}
+
+fn int_op(op: ArithOp, x: i64, y: i64) -> ValueResult<Value> {
+    if y == 0 && matches!(op, ArithOp::Div | ArithOp::Rem) {
+        return Err(ValueError::DivisionByZero);
+    }
+    let result = match op {
+        ArithOp::Add => x.checked_add(y),
+        ArithOp::Sub => x.checked_sub(y),
+        ArithOp::Mul => x.checked_mul(y),
+        ArithOp::Div => x.checked_div(y),
+        ArithOp::Rem => Some(x.checked_rem(y).unwrap_or(0)),
+    };
+    match result {
+        Some(n) => Ok(Value::Integer(n)),
+        None => bigint_op(op, BigInt::from(x), BigInt::from(y)),
+    }
+}
+
+fn bigint_op(op: ArithOp, x: BigInt, y: BigInt) -> ValueResult<Value> {
+    if y.is_zero() && matches!(op, ArithOp::Div | ArithOp::Rem) {
+        return Err(ValueError::DivisionByZero);
+    }
+    Ok(Value::BigInt(match op {
+        ArithOp::Add => x + y,
+        ArithOp::Sub => x - y,
+        ArithOp::Mul => x * y,
+        ArithOp::Div => x / y,
+        ArithOp::Rem => x % y,
+    }))
+}
+
+fn decimal_op(op: ArithOp, x: Decimal, y: Decimal) -> ValueResult<Value> {
+    if y.is_zero() && matches!(op, ArithOp::Div | ArithOp::Rem) {
+        return Err(ValueError::DivisionByZero);
+    }
+    let result = match op {
+        ArithOp::Add => x.checked_add(y),
+        ArithOp::Sub => x.checked_sub(y),
+        ArithOp::Mul => x.checked_mul(y),
+        ArithOp::Div => x.checked_div(y),
+        ArithOp::Rem => x.checked_rem(y),
+    };
+    result
+        .map(Value::Decimal)
+        .ok_or_else(|| ValueError::InvalidOperation(format!("decimal overflow in {}", op.name())))
+}
+
+/// Apply `op` to two exact numbers, promoting the result as needed
+pub fn arith(op: ArithOp, a: &Value, b: &Value) -> ValueResult<Value> {
+    match (a, b) {
+        (Value::Integer(x), Value::Integer(y)) => int_op(op, *x, *y),
+        (Value::Decimal(_), _) | (_, Value::Decimal(_)) => {
+            decimal_op(op, to_decimal(a)?, to_decimal(b)?)
+        }
+        _ => match (to_bigint(a), to_bigint(b)) {
+            (Some(x), Some(y)) => bigint_op(op, x, y),
+            _ => Err(ValueError::TypeError {
+                expected: "exact number",
+                actual: if a.is_exact_number() {
+                    b.type_name()
+                } else {
+                    a.type_name()
+                },
+            }),
+        },
+    }
+}
+
+/// Negate an exact number, promoting `i64::MIN` to a `BigInt`
+pub fn negate(value: &Value) -> ValueResult<Value> {
+    match value {
+        Value::Integer(n) => Ok(n
+            .checked_neg()
+            .map_or_else(|| Value::BigInt(-BigInt::from(*n)), Value::Integer)),
+        Value::BigInt(n) => Ok(Value::BigInt(-n)),
+        Value::Decimal(d) => Ok(Value::Decimal(-*d)),
+        _ => Err(ValueError::TypeError {
+            expected: "exact number",
+            actual: value.type_name(),
+        }),
+    }
+}
+
+/// Compare two exact numbers, returning `None` if either is not exact
+pub fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Integer(x), Value::Integer(y)) => Some(x.cmp(y)),
+        (Value::Decimal(_), _) | (_, Value::Decimal(_)) => {
+            if !a.is_exact_number() || !b.is_exact_number() {
+                return None;
+            }
+            match (to_decimal(a), to_decimal(b)) {
+                (Ok(x), Ok(y)) => Some(x.cmp(&y)),
+                // A big integer too large for a decimal dominates it
+                (Err(_), _) => to_bigint(a).map(|n| n.sign().cmp(&num_bigint::Sign::NoSign)),
+                (_, Err(_)) => to_bigint(b).map(|n| num_bigint::Sign::NoSign.cmp(&n.sign())),
+            }
+        }
+        _ => Some(to_bigint(a)?.cmp(&to_bigint(b)?)),
+    }
+}
+
+/// Convert an exact number to the nearest `f64`
+pub fn to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(n) => Some(*n as f64),
+        Value::BigInt(n) => n.to_f64(),
+        Value::Decimal(d) => d.to_f64(),
+        Value::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_overflow_promotes() {
+        let result = arith(ArithOp::Add, &Value::Integer(i64::MAX), &Value::Integer(1)).unwrap();
+        assert_eq!(result, Value::BigInt(BigInt::from(i64::MAX) + 1));
+
+        let result = arith(ArithOp::Mul, &Value::Integer(i64::MAX), &Value::Integer(2)).unwrap();
+        assert_eq!(result, Value::BigInt(BigInt::from(i64::MAX) * 2));
+
+        assert_eq!(
+            negate(&Value::Integer(i64::MIN)).unwrap(),
+            Value::BigInt(-BigInt::from(i64::MIN))
+        );
+    }
+
+    #[test]
+    fn test_small_results_stay_integers() {
+        let result = arith(ArithOp::Sub, &Value::Integer(10), &Value::Integer(3)).unwrap();
+        assert_eq!(result, Value::Integer(7));
+        assert_eq!(
+            arith(ArithOp::Rem, &Value::Integer(i64::MIN), &Value::Integer(-1)).unwrap(),
+            Value::Integer(0)
+        );
+    }
+
+    #[test]
+    fn test_decimal_arithmetic_is_exact() {
+        let a = Value::Decimal(Decimal::from_str("0.1").unwrap());
+        let b = Value::Decimal(Decimal::from_str("0.2").unwrap());
+        let sum = arith(ArithOp::Add, &a, &b).unwrap();
+        assert_eq!(sum, Value::Decimal(Decimal::from_str("0.3").unwrap()));
+
+        let scaled = arith(ArithOp::Mul, &a, &Value::Integer(3)).unwrap();
+        assert_eq!(scaled, Value::Decimal(Decimal::from_str("0.3").unwrap()));
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let zero = Value::Decimal(Decimal::ZERO);
+        assert_eq!(
+            arith(ArithOp::Div, &Value::Integer(1), &zero),
+            Err(ValueError::DivisionByZero)
+        );
+        assert_eq!(
+            arith(
+                ArithOp::Rem,
+                &Value::BigInt(BigInt::from(5)),
+                &Value::Integer(0)
+            ),
+            Err(ValueError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn test_compare_across_representations() {
+        let big = Value::BigInt(BigInt::from(i64::MAX) * 4);
+        assert_eq!(compare(&Value::Integer(1), &big), Some(Ordering::Less));
+        assert_eq!(
+            compare(
+                &Value::Decimal(Decimal::from_str("2.00").unwrap()),
+                &Value::Integer(2)
+            ),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(compare(&Value::Float(1.0), &Value::Integer(1)), None);
+    }
+
+    #[test]
+    fn test_mixing_float_is_rejected() {
+        assert!(arith(
+            ArithOp::Add,
+            &Value::Float(1.0),
+            &Value::BigInt(BigInt::from(1))
+        )
+        .is_err());
+    }
+}
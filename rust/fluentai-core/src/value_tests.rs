@@ -599,6 +599,44 @@ mod tests {
         assert!(!some1.deep_eq(&some3));
     }
 
+    // ===== Struct Value Tests =====
+
+    #[test]
+    fn test_struct_values() {
+        let point = Value::Struct {
+            name: "Point".to_string(),
+            fields: vec![
+                ("x".to_string(), Value::Integer(1)),
+                ("y".to_string(), Value::Integer(2)),
+            ],
+        };
+
+        assert!(point.is_struct());
+        assert_eq!(point.type_name(), "struct");
+        assert_eq!(point.get_field("y").unwrap(), &Value::Integer(2));
+        assert!(point.get_field("z").is_err());
+        assert!(Value::Integer(1).get_field("x").is_err());
+        assert_eq!(point.to_string(), "Point { x: 1, y: 2 }");
+    }
+
+    #[test]
+    fn test_struct_with_field() {
+        let point = Value::Struct {
+            name: "Point".to_string(),
+            fields: vec![
+                ("x".to_string(), Value::Integer(1)),
+                ("y".to_string(), Value::Integer(2)),
+            ],
+        };
+
+        let moved = point.with_field("x", Value::Integer(10)).unwrap();
+        assert_eq!(moved.get_field("x").unwrap(), &Value::Integer(10));
+        // The original is untouched
+        assert_eq!(point.get_field("x").unwrap(), &Value::Integer(1));
+        assert_ne!(point, moved);
+        assert!(point.with_field("z", Value::Nil).is_err());
+    }
+
     // ===== Error Tests =====
 
     #[test]
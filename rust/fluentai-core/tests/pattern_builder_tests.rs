@@ -84,7 +84,7 @@ fn test_match_builder_simple() -> Result<()> {
 
     // Verify the match node was created
     assert!(graph.get_node(match_node).is_some());
-    if let Some(Node::Match { expr, branches }) = graph.get_node(match_node) {
+    if let Some(Node::Match { expr, branches, .. }) = graph.get_node(match_node) {
         assert_eq!(*expr, value);
         assert_eq!(branches.len(), 2);
         assert!(matches!(
@@ -204,7 +204,7 @@ fn test_match_value_helper() -> Result<()> {
         default,
     )?;
 
-    if let Some(Node::Match { expr, branches }) = graph.get_node(match_node) {
+    if let Some(Node::Match { expr, branches, .. }) = graph.get_node(match_node) {
         assert_eq!(*expr, value);
         assert_eq!(branches.len(), 4); // 3 cases + default
         assert!(matches!(branches[3].0, Pattern::Wildcard));
@@ -231,7 +231,7 @@ fn test_match_list_helper() -> Result<()> {
         Ok(head)
     })?;
 
-    if let Some(Node::Match { expr, branches }) = graph.get_node(match_node) {
+    if let Some(Node::Match { expr, branches, .. }) = graph.get_node(match_node) {
         assert_eq!(*expr, list);
         assert_eq!(branches.len(), 2);
         // First branch should be nil pattern
@@ -292,6 +292,7 @@ fn test_old_vs_new_api_comparison() -> Result<()> {
             (Pattern::Literal(Literal::Integer(42)), result1_1),
             (Pattern::Wildcard, result2_1),
         ],
+        must_match: false,
     })?;
 
     // New way using builder API
@@ -56,14 +56,36 @@ impl EffectHandler for IOHandler {
                     )))
                 }
             }
-            "write_file" => {
+            "read_file_bytes" => {
+                if let Some(Value::String(path)) = args.first() {
+                    std::fs::read(path)
+                        .map(Value::Bytes)
+                        .map_err(|e| Error::Runtime(format!("Failed to read file: {}", e)))
+                } else {
+                    Err(Error::Runtime(format_effect_error(
+                        "IO",
+                        operation,
+                        "requires a string path",
+                    )))
+                }
+            }
+            "write_file" | "write_file_bytes" => {
                 if args.len() >= 2 {
                     if let (Some(Value::String(path)), Some(content)) = (args.get(0), args.get(1)) {
-                        let content_str = match content {
-                            Value::String(s) => s.clone(),
-                            other => other.to_string(),
+                        // Only the text variant stringifies non-byte content
+                        let content = match (operation, content) {
+                            (_, Value::Bytes(b)) => b.clone(),
+                            ("write_file", Value::String(s)) => s.clone().into_bytes(),
+                            ("write_file", other) => other.to_string().into_bytes(),
+                            _ => {
+                                return Err(Error::Runtime(format_effect_error(
+                                    "IO",
+                                    operation,
+                                    "requires bytes content",
+                                )))
+                            }
                         };
-                        std::fs::write(path, content_str)
+                        std::fs::write(path, content)
                             .map(|_| Value::Nil)
                             .map_err(|e| Error::Runtime(format!("Failed to write file: {}", e)))
                     } else {
@@ -2,7 +2,8 @@
 //!
 //! This module provides a comprehensive effect handling system that supports
 //! all effect types including IO, State, Error, Time, Network, Random, Dom,
-//! Async, and Concurrent effects.
+//! Async, and Concurrent effects, as well as user-declared effects, which are
+//! registered like any other handler under [`EffectType::Custom`].
 
 #![warn(missing_docs)]
 
@@ -39,7 +40,7 @@ pub trait EffectHandler: Send + Sync {
     /// Handle a synchronous effect operation
     fn handle_sync(&self, operation: &str, _args: &[Value]) -> EffectResult {
         Err(Error::Runtime(format!(
-            "Synchronous operation '{}' not supported for {:?}",
+            "Synchronous operation '{}' not supported for {}",
             operation,
             self.effect_type()
         )))
@@ -86,7 +87,7 @@ impl EffectContext {
         match self.handlers.get(&effect_type) {
             Some(handler) => handler.handle_sync(operation, args),
             None => Err(Error::Runtime(format!(
-                "No handler registered for effect type {:?}",
+                "No handler registered for effect type {}",
                 effect_type
             ))),
         }
@@ -102,7 +103,7 @@ impl EffectContext {
         match self.handlers.get(&effect_type) {
             Some(handler) => handler.handle_async(operation, args).await,
             None => Err(Error::Runtime(format!(
-                "No handler registered for effect type {:?}",
+                "No handler registered for effect type {}",
                 effect_type
             ))),
         }
@@ -141,7 +142,7 @@ impl fmt::Display for EffectContext {
         let mut handlers: Vec<String> = self
             .handlers
             .iter()
-            .map(|entry| format!("{:?}", entry.key()))
+            .map(|entry| entry.key().to_string())
             .collect();
         handlers.sort();
 
@@ -300,4 +301,46 @@ mod tests {
         assert!(debug.contains("EffectContext"));
         assert!(debug.contains("handler_count"));
     }
+
+    struct LoggerHandler;
+
+    impl EffectHandler for LoggerHandler {
+        fn effect_type(&self) -> EffectType {
+            EffectType::custom("Logger")
+        }
+
+        fn handle_sync(&self, operation: &str, args: &[Value]) -> EffectResult {
+            match (operation, args) {
+                ("log", [Value::String(msg)]) => Ok(Value::String(format!("logged: {}", msg))),
+                _ => Err(Error::Runtime(format_effect_error(
+                    "Logger",
+                    operation,
+                    "operation not supported",
+                ))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_custom_effect_handler_dispatch() {
+        let context = EffectContext::default();
+        context.register_handler(Arc::new(LoggerHandler));
+
+        let result = context.perform_sync(
+            EffectType::from_name("Logger").unwrap(),
+            "log",
+            &[Value::String("hi".to_string())],
+        );
+        assert_eq!(result.unwrap(), Value::String("logged: hi".to_string()));
+        assert!(context.to_string().contains("Logger"));
+
+        // Custom effects are keyed by name
+        let result = context.perform_sync(EffectType::custom("Metrics"), "inc", &[]);
+        match result {
+            Err(Error::Runtime(msg)) => {
+                assert!(msg.contains("No handler registered for effect type Metrics"))
+            }
+            _ => panic!("Expected Runtime error for missing handler"),
+        }
+    }
 }
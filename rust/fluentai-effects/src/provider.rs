@@ -78,7 +78,7 @@ impl EffectHandlerProvider {
         }
 
         Err(Error::Runtime(format!(
-            "No handler registered for effect type {:?}",
+            "No handler registered for effect type {}",
             effect_type
         )))
     }
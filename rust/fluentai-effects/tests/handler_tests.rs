@@ -59,6 +59,36 @@ fn test_io_handler_file_operations() {
     let _ = std::fs::remove_file(test_file);
 }
 
+#[test]
+fn test_io_handler_binary_file_operations() {
+    let handler = IOHandler::new();
+    let test_file = "/tmp/fluentai_test_io.bin";
+    let content = vec![0x00, 0x9f, 0xff, b'\n'];
+
+    let write_result = handler.handle_sync(
+        "write_file_bytes",
+        &[
+            Value::String(test_file.to_string()),
+            Value::Bytes(content.clone()),
+        ],
+    );
+    assert!(write_result.is_ok());
+
+    let read_result =
+        handler.handle_sync("read_file_bytes", &[Value::String(test_file.to_string())]);
+    assert_eq!(read_result.unwrap(), Value::Bytes(content));
+
+    // The binary variant does not stringify other values
+    let write_result = handler.handle_sync(
+        "write_file_bytes",
+        &[Value::String(test_file.to_string()), Value::Integer(1)],
+    );
+    assert!(write_result.is_err());
+
+    // Cleanup
+    let _ = std::fs::remove_file(test_file);
+}
+
 #[test]
 fn test_io_handler_invalid_operations() {
     let handler = IOHandler::new();
@@ -158,6 +158,7 @@ async fn test_generate_with_full_context() {
         metadata: Some(NodeMetadata {
             span: None,
             type_info: None,
+            type_annotation: None,
             is_pure: Some(true),
             annotations: vec![],
             documentation_id: Some("test_doc".to_string()),
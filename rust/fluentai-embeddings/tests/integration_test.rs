@@ -330,6 +330,7 @@ async fn test_metadata_enriched_embeddings() {
     let metadata = NodeMetadata {
         span: Some((10, 15)),
         type_info: Some("Integer".to_string()),
+        type_annotation: None,
         is_pure: Some(true),
         annotations: vec![],
         documentation_id: Some("doc_important_constant".to_string()),
@@ -451,6 +452,7 @@ fn create_complex_graph() -> Graph {
         NodeMetadata {
             span: Some((1, 10)),
             type_info: Some("Integer".to_string()),
+            type_annotation: None,
             is_pure: Some(true),
             annotations: vec![],
             documentation_id: Some("constant_42".to_string()),
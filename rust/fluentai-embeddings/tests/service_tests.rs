@@ -285,6 +285,7 @@ async fn test_extract_context_with_metadata() {
     let metadata = NodeMetadata {
         span: Some((10, 15)),
         type_info: Some("Integer".to_string()),
+        type_annotation: None,
         is_pure: Some(true),
         annotations: vec![],
         documentation_id: Some("doc_123".to_string()),
@@ -98,6 +98,10 @@ pub struct Interpreter {
     start_time: Instant,
     /// Cache for evaluated nodes
     node_cache: RefCell<FxHashMap<NodeId, Value>>,
+    /// Trait method implementations keyed by (type name, method name)
+    methods: FxHashMap<(String, String), Value>,
+    /// Enums declaring each variant tag
+    variant_enums: FxHashMap<String, Vec<String>>,
 }
 
 impl Interpreter {
@@ -130,6 +134,8 @@ impl Interpreter {
             recursion_depth: RefCell::new(0),
             start_time: Instant::now(),
             node_cache: RefCell::new(FxHashMap::default()),
+            methods: FxHashMap::default(),
+            variant_enums: FxHashMap::default(),
         };
 
         // Initialize standard library
@@ -208,6 +214,19 @@ impl Interpreter {
         self.start_time = Instant::now();
         self.node_cache.borrow_mut().clear();
 
+        // Register enum variants before evaluation, so impls on an enum
+        // apply to its variants wherever they are constructed
+        for node in graph.nodes.values() {
+            if let Node::Enum { name, variants, .. } = node {
+                for variant in variants {
+                    let enums = self.variant_enums.entry(variant.name.clone()).or_default();
+                    if !enums.contains(name) {
+                        enums.push(name.clone());
+                    }
+                }
+            }
+        }
+
         if let Some(root_id) = graph.root_id {
             self.eval_node(root_id, graph, &self.global_env.clone())
         } else {
@@ -285,6 +304,7 @@ impl Interpreter {
                 else_branch,
             } => self.eval_if(*condition, *then_branch, *else_branch, graph, env),
             Node::List(elements) => self.eval_list(elements, graph, env),
+            Node::Tuple(elements) => self.eval_tuple(elements, graph, env),
             // TODO: Handle map nodes when available
             // Node::Map(pairs) => self.eval_map(pairs, graph, env),
             Node::Async { body } => self.eval_async(*body, graph, env),
@@ -295,6 +315,40 @@ impl Interpreter {
             Node::Receive { channel } => self.eval_receive(*channel, graph, env),
             Node::TrySend { channel, value } => self.eval_try_send(*channel, *value, graph, env),
             Node::TryReceive { channel } => self.eval_try_receive(*channel, graph, env),
+            Node::Begin { exprs } => self.eval_begin(exprs, graph, env),
+            Node::Struct { .. }
+            | Node::Enum { .. }
+            | Node::Trait { .. }
+            | Node::EffectDef { .. }
+            | Node::MacroDef { .. } => {
+                // Declarations have no runtime representation
+                Ok(Value::new(ValueData::Nil))
+            }
+            Node::StructConstruct { name, fields } => {
+                let mut values = Vec::with_capacity(fields.len());
+                for (field, value_id) in fields {
+                    values.push((field.clone(), self.eval_node(*value_id, graph, env)?));
+                }
+                Ok(Value::new(ValueData::Struct {
+                    name: name.clone(),
+                    fields: values,
+                }))
+            }
+            Node::QualifiedVariable {
+                module_name,
+                variable_name,
+            } => match env.lookup(module_name) {
+                // `record.field` where `record` is a local binding
+                Some(object) => self.eval_field(&object, variable_name),
+                None => Err(InterpreterError::NameError(format!(
+                    "Undefined variable: {}",
+                    module_name
+                ))),
+            },
+            Node::TraitImpl {
+                type_name, methods, ..
+            } => self.eval_trait_impl(type_name, methods, graph, env),
+            Node::MethodCall { method, args } => self.eval_method_call(method, args, graph, env),
             _ => Err(InterpreterError::InvalidOperation(format!(
                 "Cannot evaluate node type: {:?}",
                 node
@@ -307,8 +361,10 @@ impl Interpreter {
             result = result.with_provenance(node_id);
         }
 
-        // Cache result
-        self.node_cache.borrow_mut().insert(node_id, result.clone());
+        // Cache result; only literals evaluate the same in every environment
+        if matches!(node, Node::Literal(_)) {
+            self.node_cache.borrow_mut().insert(node_id, result.clone());
+        }
 
         // Debug event
         if let Some(debugger) = &mut self.debugger {
@@ -347,6 +403,20 @@ impl Interpreter {
         graph: &Graph,
         env: &Environment,
     ) -> InterpreterResult<Value> {
+        if let Some(Node::Variable { name }) = graph.get_node(function_id) {
+            // Unbound capitalized names construct tagged values
+            if name.starts_with(char::is_uppercase) && env.lookup(name).is_none() {
+                let mut values = Vec::with_capacity(args.len());
+                for arg_id in args {
+                    values.push(self.eval_node(*arg_id, graph, env)?);
+                }
+                return Ok(Value::new(ValueData::Tagged {
+                    tag: name.clone(),
+                    values,
+                }));
+            }
+        }
+
         let func_val = self.eval_node(function_id, graph, env)?;
 
         match &func_val.data {
@@ -568,13 +638,19 @@ impl Interpreter {
             ValueData::Boolean(b) => CoreValue::Boolean(*b),
             ValueData::Integer(i) => CoreValue::Integer(*i),
             ValueData::Float(f) => CoreValue::Float(*f),
+            ValueData::BigInt(n) => CoreValue::BigInt(n.clone()),
+            ValueData::Decimal(d) => CoreValue::Decimal(*d),
             ValueData::String(s) => CoreValue::String(s.clone()),
+            ValueData::Bytes(b) => CoreValue::Bytes(b.clone()),
             ValueData::Symbol(s) => CoreValue::Symbol(s.clone()),
             ValueData::List(items) => {
                 let core_items: Vec<CoreValue> =
                     items.iter().map(|v| self.value_to_core(v)).collect();
                 CoreValue::List(core_items)
             }
+            ValueData::Tuple(items) => {
+                CoreValue::Tuple(items.iter().map(|v| self.value_to_core(v)).collect())
+            }
             _ => CoreValue::Nil, // TODO: Handle other types
         }
     }
@@ -587,12 +663,18 @@ impl Interpreter {
             CoreValue::Boolean(b) => ValueData::Boolean(*b),
             CoreValue::Integer(i) => ValueData::Integer(*i),
             CoreValue::Float(f) => ValueData::Float(*f),
+            CoreValue::BigInt(n) => ValueData::BigInt(n.clone()),
+            CoreValue::Decimal(d) => ValueData::Decimal(*d),
             CoreValue::String(s) => ValueData::String(s.clone()),
+            CoreValue::Bytes(b) => ValueData::Bytes(b.clone()),
             CoreValue::Symbol(s) => ValueData::Symbol(s.clone()),
             CoreValue::List(items) => {
                 let values: Vec<Value> = items.iter().map(|v| self.core_to_value(v)).collect();
                 ValueData::List(values)
             }
+            CoreValue::Tuple(items) => {
+                ValueData::Tuple(items.iter().map(|v| self.core_to_value(v)).collect())
+            }
             _ => ValueData::Nil, // TODO: Handle other types
         };
         Value::new(data)
@@ -614,12 +696,33 @@ impl Interpreter {
             });
         }
 
+        let mut arg_values = Vec::with_capacity(args.len());
+        for arg_id in args {
+            arg_values.push(self.eval_node(*arg_id, graph, env)?);
+        }
+
+        self.apply_closure(&closure, arg_values, graph)
+    }
+
+    /// Call a closure with already evaluated arguments
+    fn apply_closure(
+        &mut self,
+        closure: &Closure,
+        args: Vec<Value>,
+        graph: &Graph,
+    ) -> InterpreterResult<Value> {
+        if args.len() != closure.params.len() {
+            return Err(InterpreterError::ArityError {
+                expected: closure.params.len(),
+                actual: args.len(),
+            });
+        }
+
         // Create new environment for the function
         let func_env = closure.env.extend();
 
         // Bind parameters
-        for (param, arg_id) in closure.params.iter().zip(args) {
-            let arg_val = self.eval_node(*arg_id, graph, env)?;
+        for (param, arg_val) in closure.params.iter().zip(args) {
             func_env.bind(param.clone(), arg_val)?;
         }
 
@@ -627,6 +730,112 @@ impl Interpreter {
         self.eval_node(closure.body, graph, &func_env)
     }
 
+    /// Evaluate a sequence of expressions, returning the last value
+    fn eval_begin(
+        &mut self,
+        exprs: &[NodeId],
+        graph: &Graph,
+        env: &Environment,
+    ) -> InterpreterResult<Value> {
+        let mut result = Value::new(ValueData::Nil);
+        for expr in exprs {
+            result = self.eval_node(*expr, graph, env)?;
+        }
+        Ok(result)
+    }
+
+    /// Read a field from a struct value
+    fn eval_field(&self, object: &Value, field: &str) -> InterpreterResult<Value> {
+        match &object.data {
+            ValueData::Struct { name, .. } => object.get_field(field).cloned().ok_or_else(|| {
+                InterpreterError::KeyNotFound(format!("struct {} has no field '{}'", name, field))
+            }),
+            ValueData::Map(map) => map
+                .get(field)
+                .cloned()
+                .ok_or_else(|| InterpreterError::KeyNotFound(field.to_string())),
+            _ => Err(InterpreterError::TypeError(format!(
+                "Cannot access field '{}' of {}",
+                field, object
+            ))),
+        }
+    }
+
+    /// Register the methods of `Type as Trait { ... }`
+    ///
+    /// An impl on an enum is keyed by the enum name and applies to its variants.
+    fn eval_trait_impl(
+        &mut self,
+        type_name: &str,
+        methods: &[(String, NodeId)],
+        graph: &Graph,
+        env: &Environment,
+    ) -> InterpreterResult<Value> {
+        for (method, lambda) in methods {
+            let func = self.eval_node(*lambda, graph, env)?;
+            self.methods
+                .insert((type_name.to_string(), method.clone()), func);
+        }
+
+        Ok(Value::new(ValueData::Nil))
+    }
+
+    /// Find the trait method implementation for a receiver
+    ///
+    /// An impl on a variant's own tag takes precedence over one on its enum.
+    fn lookup_method(&self, receiver: &Value, method: &str) -> Option<&Value> {
+        let type_name = receiver.method_type_name();
+        if let Some(func) = self.methods.get(&(type_name.to_string(), method.to_string())) {
+            return Some(func);
+        }
+        match &receiver.data {
+            ValueData::Tagged { tag, .. } => self
+                .variant_enums
+                .get(tag)?
+                .iter()
+                .find_map(|enum_name| self.methods.get(&(enum_name.clone(), method.to_string()))),
+            _ => None,
+        }
+    }
+
+    /// Call a trait method, dispatching on the type of the receiver
+    fn eval_method_call(
+        &mut self,
+        method: &str,
+        args: &[NodeId],
+        graph: &Graph,
+        env: &Environment,
+    ) -> InterpreterResult<Value> {
+        let mut arg_values = Vec::with_capacity(args.len());
+        for arg_id in args {
+            arg_values.push(self.eval_node(*arg_id, graph, env)?);
+        }
+
+        let receiver = arg_values.first().ok_or_else(|| {
+            InterpreterError::InvalidOperation(format!("Method '{}' called without a receiver", method))
+        })?;
+        // Fall back to a plain function of the same name
+        let func = match self.lookup_method(receiver, method) {
+            Some(func) => func.clone(),
+            None => env.lookup(method).ok_or_else(|| {
+                InterpreterError::InvalidOperation(format!(
+                    "No implementation of method '{}' for type {}",
+                    method,
+                    receiver.method_type_name()
+                ))
+            })?,
+        };
+
+        match &func.data {
+            ValueData::Closure(closure) => self.apply_closure(&closure.clone(), arg_values, graph),
+            ValueData::BuiltinFunction { name, .. } => self.call_builtin(name, arg_values),
+            _ => Err(InterpreterError::TypeError(format!(
+                "Cannot call non-function value: {}",
+                func
+            ))),
+        }
+    }
+
     /// Evaluate a lambda expression
     fn eval_lambda(
         &self,
@@ -694,6 +903,20 @@ impl Interpreter {
         Ok(Value::new(ValueData::List(values)))
     }
 
+    /// Evaluate a tuple expression
+    fn eval_tuple(
+        &mut self,
+        elements: &[NodeId],
+        graph: &Graph,
+        env: &Environment,
+    ) -> InterpreterResult<Value> {
+        let mut values = Vec::with_capacity(elements.len());
+        for elem_id in elements {
+            values.push(self.eval_node(*elem_id, graph, env)?);
+        }
+        Ok(Value::new(ValueData::Tuple(values)))
+    }
+
     // TODO: Implement eval_map when Map node type is available
     // /// Evaluate a map expression
     // fn eval_map(
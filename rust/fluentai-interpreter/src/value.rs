@@ -6,6 +6,7 @@ use std::fmt;
 use std::rc::Rc;
 
 use fluentai_core::ast::{Literal, NodeId};
+use fluentai_core::value::{BigInt, Decimal};
 use fluentai_types::types::Type;
 
 use crate::environment::Environment;
@@ -36,12 +37,20 @@ pub enum ValueData {
     Integer(i64),
     /// Float value
     Float(f64),
+    /// Arbitrary-precision integer
+    BigInt(BigInt),
+    /// Exact decimal
+    Decimal(Decimal),
     /// String value
     String(String),
+    /// Raw byte string
+    Bytes(Vec<u8>),
     /// Symbol value
     Symbol(String),
     /// List of values
     List(Vec<Value>),
+    /// Fixed-size tuple of values
+    Tuple(Vec<Value>),
     /// Map/dictionary
     Map(FxHashMap<String, Value>),
     /// Function closure
@@ -62,6 +71,13 @@ pub enum ValueData {
     // Promise(Rc<RefCell<PromiseState>>),
     /// Channel for concurrency
     Channel(Rc<RefCell<ChannelState>>),
+    /// Record value with named fields
+    Struct {
+        name: String,
+        fields: Vec<(String, Value)>,
+    },
+    /// Constructor application, e.g. an enum variant
+    Tagged { tag: String, values: Vec<Value> },
 }
 
 /// Function closure
@@ -116,7 +132,10 @@ impl Value {
             Literal::Boolean(b) => ValueData::Boolean(*b),
             Literal::Integer(i) => ValueData::Integer(*i),
             Literal::Float(f) => ValueData::Float(*f),
+            Literal::BigInt(n) => ValueData::BigInt(n.clone()),
+            Literal::Decimal(d) => ValueData::Decimal(*d),
             Literal::String(s) => ValueData::String(s.clone()),
+            Literal::Bytes(b) => ValueData::Bytes(b.clone()),
             Literal::Symbol(s) => ValueData::Symbol(s.clone()),
         };
         Self::new(data)
@@ -190,12 +209,20 @@ impl Value {
             ValueData::Boolean(b) => b.to_string(),
             ValueData::Integer(i) => i.to_string(),
             ValueData::Float(f) => f.to_string(),
+            ValueData::BigInt(n) => n.to_string(),
+            ValueData::Decimal(d) => d.to_string(),
             ValueData::String(s) => s.clone(),
+            ValueData::Bytes(b) => fluentai_core::value::format_bytes(b),
             ValueData::Symbol(s) => format!(":{}", s),
             ValueData::List(items) => {
                 let strs: Vec<String> = items.iter().map(|v| v.to_string()).collect();
                 format!("[{}]", strs.join(", "))
             }
+            ValueData::Tuple(items) if items.len() == 1 => format!("({},)", items[0].to_string()),
+            ValueData::Tuple(items) => {
+                let strs: Vec<String> = items.iter().map(|v| v.to_string()).collect();
+                format!("({})", strs.join(", "))
+            }
             ValueData::Map(m) => {
                 let pairs: Vec<String> = m
                     .iter()
@@ -209,6 +236,18 @@ impl Value {
             // #[cfg(feature = "async")]
             // ValueData::Promise(_) => "<promise>".to_string(),
             ValueData::Channel(_) => "<channel>".to_string(),
+            ValueData::Struct { name, fields } => {
+                let strs: Vec<String> = fields
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", k, v.to_string()))
+                    .collect();
+                format!("{} {{ {} }}", name, strs.join(", "))
+            }
+            ValueData::Tagged { tag, values } if values.is_empty() => tag.clone(),
+            ValueData::Tagged { tag, values } => {
+                let strs: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                format!("{}({})", tag, strs.join(", "))
+            }
         }
     }
 
@@ -220,6 +259,38 @@ impl Value {
         }
     }
 
+    /// Get a struct field by name
+    pub fn get_field(&self, field: &str) -> Option<&Value> {
+        match &self.data {
+            ValueData::Struct { fields, .. } => {
+                fields.iter().find(|(name, _)| name == field).map(|(_, v)| v)
+            }
+            _ => None,
+        }
+    }
+
+    /// Type name used to dispatch trait methods on this value
+    pub fn method_type_name(&self) -> &str {
+        match &self.data {
+            ValueData::Struct { name, .. } => name,
+            ValueData::Tagged { tag, .. } => tag,
+            ValueData::Nil => "Nil",
+            ValueData::Boolean(_) => "Bool",
+            ValueData::Integer(_) | ValueData::BigInt(_) => "Int",
+            ValueData::Float(_) => "Float",
+            ValueData::Decimal(_) => "Decimal",
+            ValueData::String(_) => "String",
+            ValueData::Bytes(_) => "Bytes",
+            ValueData::Symbol(_) => "Symbol",
+            ValueData::List(_) => "List",
+            ValueData::Tuple(_) => "Tuple",
+            ValueData::Map(_) => "Map",
+            ValueData::Closure(_) | ValueData::BuiltinFunction { .. } => "Function",
+            ValueData::Module { .. } => "Module",
+            ValueData::Channel(_) => "Channel",
+        }
+    }
+
     /// Convert to list if possible
     pub fn to_list(&self) -> Option<&Vec<Value>> {
         match &self.data {
@@ -242,10 +313,22 @@ impl PartialEq for Value {
             (ValueData::Boolean(a), ValueData::Boolean(b)) => a == b,
             (ValueData::Integer(a), ValueData::Integer(b)) => a == b,
             (ValueData::Float(a), ValueData::Float(b)) => (a - b).abs() < f64::EPSILON,
+            (ValueData::BigInt(a), ValueData::BigInt(b)) => a == b,
+            (ValueData::Decimal(a), ValueData::Decimal(b)) => a == b,
             (ValueData::String(a), ValueData::String(b)) => a == b,
+            (ValueData::Bytes(a), ValueData::Bytes(b)) => a == b,
             (ValueData::Symbol(a), ValueData::Symbol(b)) => a == b,
             (ValueData::List(a), ValueData::List(b)) => a == b,
+            (ValueData::Tuple(a), ValueData::Tuple(b)) => a == b,
             (ValueData::Map(a), ValueData::Map(b)) => a == b,
+            (
+                ValueData::Struct { name: n1, fields: f1 },
+                ValueData::Struct { name: n2, fields: f2 },
+            ) => n1 == n2 && f1 == f2,
+            (
+                ValueData::Tagged { tag: t1, values: v1 },
+                ValueData::Tagged { tag: t2, values: v2 },
+            ) => t1 == t2 && v1 == v2,
             _ => false,
         }
     }
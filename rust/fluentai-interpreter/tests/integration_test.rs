@@ -160,3 +160,63 @@ fn test_error_handling() {
     let result = interpreter.interpret(&graph);
     assert!(result.is_err());
 }
+
+#[test]
+fn test_trait_method_dispatch() {
+    let mut interpreter = Interpreter::new(InterpreterOptions::default());
+
+    let code = r#"
+private struct Circle { r: int }
+private struct Rect { w: int, h: int }
+private trait Area { function area(self) -> int; }
+Circle as Area { private function area(self) { 3 * self.r * self.r } }
+Rect as Area { private function area(self) { self.w * self.h } }
+Circle { r: 1 }.area() + Circle { r: 2 }.area() + Rect { w: 2, h: 5 }.area()
+"#;
+    let graph = parse(code).unwrap();
+    let result = interpreter.interpret(&graph).unwrap();
+    assert_eq!(result.to_integer(), Some(25));
+}
+
+#[test]
+fn test_trait_method_dispatch_on_tags() {
+    let mut interpreter = Interpreter::new(InterpreterOptions::default());
+
+    let code = r#"
+private enum Shape { Square(int), Triangle(int) }
+private trait Sides { function sides(self) -> int; }
+private trait Named { function kind(self) -> string; }
+Square as Sides { private function sides(self) { 4 } }
+Triangle as Sides { private function sides(self) { 3 } }
+Shape as Named { private function kind(self) { "shape" } }
+list(Square(1).sides(), Triangle(1).sides(), Triangle(2).kind())
+"#;
+    let graph = parse(code).unwrap();
+    let result = interpreter.interpret(&graph).unwrap();
+    let values = result.to_list().unwrap();
+    assert_eq!(values[0].to_integer(), Some(4));
+    assert_eq!(values[1].to_integer(), Some(3));
+    assert_eq!(values[2].to_string(), "shape");
+
+    // No impl for Int
+    let graph = parse("private trait Sides { function sides(self); }\n(1).sides()").unwrap();
+    assert!(interpreter.interpret(&graph).is_err());
+}
+
+#[test]
+fn test_trait_method_dispatch_prefers_variant_impl() {
+    let mut interpreter = Interpreter::new(InterpreterOptions::default());
+
+    let code = r#"
+private enum Shape { Square(int), Triangle(int) }
+private trait Named { function name(self) -> string; }
+Square as Named { private function name(self) { "square" } }
+Shape as Named { private function name(self) { "shape" } }
+list(Square(1).name(), Triangle(1).name())
+"#;
+    let graph = parse(code).unwrap();
+    let result = interpreter.interpret(&graph).unwrap();
+    let values = result.to_list().unwrap();
+    assert_eq!(values[0].to_string(), "square");
+    assert_eq!(values[1].to_string(), "shape");
+}
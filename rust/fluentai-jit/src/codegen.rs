@@ -4,10 +4,12 @@ use cranelift::prelude::*;
 use cranelift_codegen::ir::MemFlags;
 
 use anyhow::{anyhow, Result};
-use fluentai_bytecode::{BytecodeChunk, Opcode};
+use fluentai_bytecode::{BytecodeChunk, Instruction, Opcode};
 use fluentai_core::value::Value as ClValue;
 use std::collections::HashMap;
-use crate::value::{TaggedValue, value_to_tagged};
+use crate::feedback::{TypeFeedback, TypeProfile};
+use crate::runtime::{encode_instruction, CALL_FAILED};
+use crate::value::{TaggedValue, ValueTag, value_to_tagged};
 
 /// Represents a value on the JIT's compile-time stack, tracking both
 /// the Cranelift value and its type.
@@ -45,17 +47,498 @@ impl JitValue {
     
     /// Create a tagged boolean value
     fn make_tagged_bool(builder: &mut FunctionBuilder, b: Value) -> Self {
-        // For simplicity, represent booleans as tagged integers (0 or 1)
+        // True is false with bit 3 set
         let extended = builder.ins().uextend(types::I64, b);
-        Self::make_tagged_int(builder, extended)
+        let bit = builder.ins().ishl_imm(extended, 3);
+        let val = builder.ins().iadd_imm(bit, TaggedValue::FALSE.0 as i64);
+        JitValue {
+            val,
+            ty: types::I64,
+        }
+    }
+}
+
+/// Get the stack variable for slot `index`, declaring it on first use
+fn stack_slot(builder: &mut FunctionBuilder, slots: &mut Vec<Variable>, index: usize) -> Variable {
+    while slots.len() <= index {
+        let var = Variable::new(slots.len());
+        builder.declare_var(var, types::I64);
+        slots.push(var);
+    }
+    slots[index]
+}
+
+/// Write the compile-time stack into stack variables before leaving a block
+fn spill_stack(builder: &mut FunctionBuilder, slots: &mut Vec<Variable>, value_stack: &[JitValue]) {
+    for (i, value) in value_stack.iter().enumerate() {
+        let var = stack_slot(builder, slots, i);
+        builder.def_var(var, value.val);
+    }
+}
+
+/// Store tagged values in a stack-allocated array and return its address
+fn store_array(builder: &mut FunctionBuilder, values: &[JitValue]) -> Value {
+    let size = (values.len().max(1) * 8) as u32;
+    let slot = builder.create_sized_stack_slot(StackSlotData::new(StackSlotKind::ExplicitSlot, size));
+    for (i, value) in values.iter().enumerate() {
+        builder.ins().stack_store(value.val, slot, (i * 8) as i32);
+    }
+    builder.ins().stack_addr(types::I64, slot, 0)
+}
+
+/// Import a runtime function taking `param_count` i64 parameters and returning an i64
+fn runtime_function(
+    builder: &mut FunctionBuilder,
+    module: &mut dyn cranelift_module::Module,
+    name: &str,
+    param_count: usize,
+) -> Result<codegen::ir::FuncRef> {
+    let mut sig = module.make_signature();
+    for _ in 0..param_count {
+        sig.params.push(AbiParam::new(types::I64));
+    }
+    sig.returns.push(AbiParam::new(types::I64));
+    let func = module.declare_function(name, cranelift_module::Linkage::Import, &sig)?;
+    Ok(module.declare_func_in_func(func, builder.func))
+}
+
+/// Return [`CALL_FAILED`] straight away if a runtime call failed
+fn return_if_failed(builder: &mut FunctionBuilder, result: Value) {
+    let failed = builder.ins().icmp_imm(IntCC::Equal, result, CALL_FAILED);
+    let fail_block = builder.create_block();
+    let continue_block = builder.create_block();
+    builder.set_cold_block(fail_block);
+    builder.ins().brif(failed, fail_block, &[], continue_block, &[]);
+
+    builder.switch_to_block(fail_block);
+    builder.seal_block(fail_block);
+    builder.ins().return_(&[result]);
+
+    builder.switch_to_block(continue_block);
+    builder.seal_block(continue_block);
+}
+
+/// Stack effect `(pop, push)` of opcodes that run through the interpreter stub
+///
+/// Only opcodes that work on their operands and VM-wide state (globals, cells,
+/// effects, channels, ...) are listed; anything touching frames, locals or the
+/// instruction pointer cannot run outside the interpreter loop. A push of 2 means
+/// the instruction leaves its operand in place and pushes a result above it.
+fn interpreted_effect(instruction: &Instruction) -> Option<(usize, usize)> {
+    use Opcode::*;
+
+    let arg = instruction.arg as usize;
+    let effect = match instruction.opcode {
+        LoadGlobal | LoadQualified | Channel | MakeChannel | GcCollect => (0, 1),
+        StoreGlobal | DefineGlobal | MatchError => (1, 0),
+        MakeCell | CellGet | LoadCell | GcAlloc | GcDeref => (1, 1),
+        CellSet | GcSet => (2, 1),
+        StoreCell => (2, 0),
+        Effect | EffectAsync | Perform => (arg + 2, 1),
+        AddFloat | SubFloat | MulFloat | DivFloat => (2, 1),
+        MakeList => (arg, 1),
+        ListHead | ListTail | ListLen | ListEmpty => (1, 1),
+        ListGet | ListCons => (2, 1),
+        ListSet | MapSet => (3, 1),
+        MakeTuple => (arg, 1),
+        TupleGet | GetField => (1, 1),
+        IsTuple => (1, 2),
+        MakeStruct => (arg & 0xFFFF, 1),
+        SetField => (2, 1),
+        ChannelWithCapacity | Receive | TryReceive => (1, 1),
+        Send | TrySend | MakeActor | ActorSend => (2, 1),
+        _ => return None,
+    };
+    Some(effect)
+}
+
+/// Run `instruction` through the interpreter stub, see [`crate::runtime::jit_runtime_interpret`]
+fn interpret(
+    builder: &mut FunctionBuilder,
+    module: &mut dyn cranelift_module::Module,
+    ctx_ptr: Value,
+    chunk_id: usize,
+    instruction: &Instruction,
+    operands: &[JitValue],
+) -> Result<Value> {
+    let operands_array = store_array(builder, operands);
+    let chunk_val = builder.ins().iconst(types::I64, chunk_id as i64);
+    let encoded = builder.ins().iconst(types::I64, encode_instruction(instruction));
+    let count = builder.ins().iconst(types::I64, operands.len() as i64);
+
+    let interpret_func = runtime_function(builder, module, "jit_runtime_interpret", 5)?;
+    let call = builder
+        .ins()
+        .call(interpret_func, &[ctx_ptr, chunk_val, encoded, count, operands_array]);
+    let result = builder.inst_results(call)[0];
+    return_if_failed(builder, result);
+    Ok(result)
+}
+
+/// The instruction being compiled, for deoptimizing back to the interpreter
+struct Site<'a> {
+    ctx_ptr: Value,
+    pc: usize,
+    /// The stack before the instruction pops its operands
+    stack: &'a [JitValue],
+}
+
+impl Site<'_> {
+    /// Deoptimize if `failed` is non-zero: hand the stack to the runtime so the
+    /// interpreter can run this instruction, then return
+    fn deopt_if(
+        &self,
+        builder: &mut FunctionBuilder,
+        module: &mut dyn cranelift_module::Module,
+        failed: Value,
+    ) -> Result<()> {
+        let deopt_block = builder.create_block();
+        let continue_block = builder.create_block();
+        builder.set_cold_block(deopt_block);
+        builder.ins().brif(failed, deopt_block, &[], continue_block, &[]);
+
+        builder.switch_to_block(deopt_block);
+        builder.seal_block(deopt_block);
+        let stack_array = store_array(builder, self.stack);
+        let ip = builder.ins().iconst(types::I64, self.pc as i64);
+        let count = builder.ins().iconst(types::I64, self.stack.len() as i64);
+        let deopt = runtime_function(builder, module, "jit_runtime_deopt", 4)?;
+        let call = builder.ins().call(deopt, &[self.ctx_ptr, ip, count, stack_array]);
+        let result = builder.inst_results(call)[0];
+        builder.ins().return_(&[result]);
+
+        builder.switch_to_block(continue_block);
+        builder.seal_block(continue_block);
+        Ok(())
+    }
+
+    /// Deoptimize unless all `values` are tagged integers
+    fn deopt_unless_int(
+        &self,
+        builder: &mut FunctionBuilder,
+        module: &mut dyn cranelift_module::Module,
+        values: &[&JitValue],
+    ) -> Result<()> {
+        // The integer tag is zero, so it survives OR-ing only if every tag is zero
+        let mut bits = values[0].val;
+        for value in &values[1..] {
+            bits = builder.ins().bor(bits, value.val);
+        }
+        let tag = builder.ins().band_imm(bits, 0b111);
+        let failed = builder.ins().icmp_imm(IntCC::NotEqual, tag, ValueTag::Integer as i64);
+        self.deopt_if(builder, module, failed)
+    }
+
+    /// Deoptimize unless all `values` are booleans
+    fn deopt_unless_bool(
+        &self,
+        builder: &mut FunctionBuilder,
+        module: &mut dyn cranelift_module::Module,
+        values: &[&JitValue],
+    ) -> Result<()> {
+        let mut failed = None;
+        for value in values {
+            // Setting bit 3 turns false into true and leaves true as it is
+            let bits = builder.ins().bor_imm(value.val, 0b1000);
+            let not_bool = builder.ins().icmp_imm(IntCC::NotEqual, bits, TaggedValue::TRUE.0 as i64);
+            failed = Some(match failed {
+                Some(failed) => builder.ins().bor(failed, not_bool),
+                None => not_bool,
+            });
+        }
+        let failed = failed.ok_or_else(|| anyhow!("No values to check"))?;
+        self.deopt_if(builder, module, failed)
+    }
+
+    /// Deoptimize unless all `values` are tagged floats
+    fn deopt_unless_float(
+        &self,
+        builder: &mut FunctionBuilder,
+        module: &mut dyn cranelift_module::Module,
+        values: &[&JitValue],
+    ) -> Result<()> {
+        let mut failed = None;
+        for value in values {
+            let tag = builder.ins().band_imm(value.val, 0b111);
+            let not_float = builder.ins().icmp_imm(IntCC::NotEqual, tag, ValueTag::Float as i64);
+            failed = Some(match failed {
+                Some(failed) => builder.ins().bor(failed, not_float),
+                None => not_float,
+            });
+        }
+        let failed = failed.ok_or_else(|| anyhow!("No values to check"))?;
+        self.deopt_if(builder, module, failed)
+    }
+}
+
+/// Pick the code to emit for an arithmetic or comparison instruction
+///
+/// The `*Int` opcodes are always compiled for integers. Float remainder has no
+/// native code, so it runs in the interpreter like mixed operands.
+fn specialization(opcode: Opcode, profile: TypeProfile) -> TypeProfile {
+    use Opcode::*;
+
+    match (opcode, profile) {
+        (AddInt | SubInt | MulInt | DivInt | LtInt | LeInt | GtInt | GeInt, _) => TypeProfile::Integer,
+        (Mod, TypeProfile::Float) => TypeProfile::Mixed,
+        (_, profile) => profile,
+    }
+}
+
+/// Integer code for a binary instruction
+///
+/// Deoptimizes on non-integer operands, on overflow (the interpreter promotes to a
+/// big integer) and on division by zero (the interpreter raises the error).
+fn int_binary_op(
+    builder: &mut FunctionBuilder,
+    module: &mut dyn cranelift_module::Module,
+    site: &Site,
+    opcode: Opcode,
+    l: &JitValue,
+    r: &JitValue,
+) -> Result<Value> {
+    use Opcode::*;
+
+    site.deopt_unless_int(builder, module, &[l, r])?;
+    // Tagged integers are shifted left by 3, which sums, differences and
+    // comparisons can ignore
+    let result = match opcode {
+        Add | AddInt => {
+            let (sum, overflow) = builder.ins().sadd_overflow(l.val, r.val);
+            site.deopt_if(builder, module, overflow)?;
+            sum
+        }
+        Sub | SubInt => {
+            let (diff, overflow) = builder.ins().ssub_overflow(l.val, r.val);
+            site.deopt_if(builder, module, overflow)?;
+            diff
+        }
+        Mul | MulInt => {
+            let l_int = l.untag_int(builder);
+            let (product, overflow) = builder.ins().smul_overflow(l_int, r.val);
+            site.deopt_if(builder, module, overflow)?;
+            product
+        }
+        Div | DivInt | Mod => {
+            let l_int = l.untag_int(builder);
+            let r_int = r.untag_int(builder);
+            let is_zero = builder.ins().icmp_imm(IntCC::Equal, r_int, 0);
+            site.deopt_if(builder, module, is_zero)?;
+            let n = if opcode == Mod {
+                builder.ins().srem(l_int, r_int)
+            } else {
+                let quotient = builder.ins().sdiv(l_int, r_int);
+                // Dividing the smallest tagged integer by -1 leaves the tagged range
+                let shifted = builder.ins().ishl_imm(quotient, 3);
+                let restored = builder.ins().sshr_imm(shifted, 3);
+                let overflow = builder.ins().icmp(IntCC::NotEqual, restored, quotient);
+                site.deopt_if(builder, module, overflow)?;
+                quotient
+            };
+            JitValue::make_tagged_int(builder, n).val
+        }
+        _ => {
+            let cc = match opcode {
+                Lt | LtInt => IntCC::SignedLessThan,
+                Le | LeInt => IntCC::SignedLessThanOrEqual,
+                Gt | GtInt => IntCC::SignedGreaterThan,
+                _ => IntCC::SignedGreaterThanOrEqual,
+            };
+            let cmp = builder.ins().icmp(cc, l.val, r.val);
+            JitValue::make_tagged_bool(builder, cmp).val
+        }
+    };
+    Ok(result)
+}
+
+/// Float code for a binary instruction, deoptimizing on non-float operands
+fn float_binary_op(
+    builder: &mut FunctionBuilder,
+    module: &mut dyn cranelift_module::Module,
+    site: &Site,
+    opcode: Opcode,
+    l: &JitValue,
+    r: &JitValue,
+) -> Result<Value> {
+    use Opcode::*;
+
+    site.deopt_unless_float(builder, module, &[l, r])?;
+    let lf = untag_float(builder, l);
+    let rf = untag_float(builder, r);
+    let result = match opcode {
+        Add => builder.ins().fadd(lf, rf),
+        Sub => builder.ins().fsub(lf, rf),
+        Mul => builder.ins().fmul(lf, rf),
+        Div => builder.ins().fdiv(lf, rf),
+        _ => {
+            let cc = match opcode {
+                Lt => FloatCC::LessThan,
+                Le => FloatCC::LessThanOrEqual,
+                Gt => FloatCC::GreaterThan,
+                _ => FloatCC::GreaterThanOrEqual,
+            };
+            let cmp = builder.ins().fcmp(cc, lf, rf);
+            return Ok(JitValue::make_tagged_bool(builder, cmp).val);
+        }
+    };
+    box_float(builder, module, result)
+}
+
+/// Emit constant `index` of `chunk`
+///
+/// JIT code embeds the tagged value, which points into this process's heap.
+/// Relocatable code only embeds integers and symbols, and loads other constants
+/// through the runtime when it runs.
+fn constant(
+    builder: &mut FunctionBuilder,
+    module: &mut dyn cranelift_module::Module,
+    ctx_ptr: Value,
+    chunk_id: usize,
+    chunk: &BytecodeChunk,
+    index: u32,
+    relocatable: bool,
+) -> Result<Value> {
+    let value = chunk
+        .constants
+        .get(index as usize)
+        .ok_or_else(|| anyhow!("Invalid constant index"))?;
+
+    // Boxed values live on the heap of the process that compiled them
+    let immediate = match value {
+        ClValue::Integer(n) => TaggedValue::fits_integer(*n),
+        ClValue::Symbol(_) | ClValue::Boolean(_) | ClValue::Nil => true,
+        _ => false,
+    };
+    if relocatable && !immediate {
+        let chunk_id = builder.ins().iconst(types::I64, chunk_id as i64);
+        let index = builder.ins().iconst(types::I64, index as i64);
+        let load = runtime_function(builder, module, "jit_runtime_constant", 3)?;
+        let call = builder.ins().call(load, &[ctx_ptr, chunk_id, index]);
+        let result = builder.inst_results(call)[0];
+        return_if_failed(builder, result);
+        return Ok(result);
     }
+    Ok(builder.ins().iconst(types::I64, value_to_tagged(value).0 as i64))
+}
+
+/// Emit a nil value
+fn nil(builder: &mut FunctionBuilder) -> Value {
+    builder.ins().iconst(types::I64, TaggedValue::NIL.0 as i64)
+}
+
+/// Decide whether a branch on `value` is taken, as an i8 that is 1 if it is
+///
+/// Booleans and nil are decided inline; other values go to the runtime, so that
+/// compiled branches agree with the VM's truthiness.
+fn truthy(
+    builder: &mut FunctionBuilder,
+    module: &mut dyn cranelift_module::Module,
+    ctx_ptr: Value,
+    value: &JitValue,
+) -> Result<Value> {
+    // Setting bits 3 and 4 turns nil, false and true into true, and nothing else
+    let bits = builder.ins().bor_imm(value.val, 0b11000);
+    let is_immediate = builder.ins().icmp_imm(IntCC::Equal, bits, TaggedValue::TRUE.0 as i64);
+    let immediate_block = builder.create_block();
+    let runtime_block = builder.create_block();
+    let merge_block = builder.create_block();
+    builder.append_block_param(merge_block, types::I8);
+    builder.ins().brif(is_immediate, immediate_block, &[], runtime_block, &[]);
+
+    builder.switch_to_block(immediate_block);
+    builder.seal_block(immediate_block);
+    let is_true = builder.ins().icmp_imm(IntCC::Equal, value.val, TaggedValue::TRUE.0 as i64);
+    builder.ins().jump(merge_block, &[is_true]);
+
+    builder.switch_to_block(runtime_block);
+    builder.seal_block(runtime_block);
+    let is_truthy = runtime_function(builder, module, "jit_runtime_is_truthy", 2)?;
+    let call = builder.ins().call(is_truthy, &[ctx_ptr, value.val]);
+    let result = builder.inst_results(call)[0];
+    let is_true = builder.ins().icmp_imm(IntCC::NotEqual, result, 0);
+    builder.ins().jump(merge_block, &[is_true]);
+
+    builder.switch_to_block(merge_block);
+    builder.seal_block(merge_block);
+    Ok(builder.block_params(merge_block)[0])
+}
+
+/// Code for `Eq` and `Ne`
+///
+/// Tagged integers are equal only if their bits are; any other operands, boxed
+/// integers among them, are compared by the interpreter stub.
+fn equality(
+    builder: &mut FunctionBuilder,
+    module: &mut dyn cranelift_module::Module,
+    ctx_ptr: Value,
+    chunk_id: usize,
+    instruction: &Instruction,
+    l: &JitValue,
+    r: &JitValue,
+) -> Result<Value> {
+    let bits = builder.ins().bor(l.val, r.val);
+    let tag = builder.ins().band_imm(bits, 0b111);
+    let both_int = builder.ins().icmp_imm(IntCC::Equal, tag, ValueTag::Integer as i64);
+    let int_block = builder.create_block();
+    let stub_block = builder.create_block();
+    let merge_block = builder.create_block();
+    builder.append_block_param(merge_block, types::I64);
+    builder.ins().brif(both_int, int_block, &[], stub_block, &[]);
+
+    builder.switch_to_block(int_block);
+    builder.seal_block(int_block);
+    let cc = if instruction.opcode == Opcode::Eq {
+        IntCC::Equal
+    } else {
+        IntCC::NotEqual
+    };
+    let cmp = builder.ins().icmp(cc, l.val, r.val);
+    let result = JitValue::make_tagged_bool(builder, cmp).val;
+    builder.ins().jump(merge_block, &[result]);
+
+    builder.switch_to_block(stub_block);
+    builder.seal_block(stub_block);
+    let result = interpret(builder, module, ctx_ptr, chunk_id, instruction, &[l.clone(), r.clone()])?;
+    builder.ins().jump(merge_block, &[result]);
+
+    builder.switch_to_block(merge_block);
+    builder.seal_block(merge_block);
+    Ok(builder.block_params(merge_block)[0])
+}
+
+/// Load the f64 a tagged float points to
+fn untag_float(builder: &mut FunctionBuilder, value: &JitValue) -> Value {
+    let ptr = builder.ins().iadd_imm(value.val, -(ValueTag::Float as i64));
+    builder.ins().load(types::F64, MemFlags::trusted(), ptr, 0)
+}
+
+/// Box an f64 as a tagged float
+fn box_float(
+    builder: &mut FunctionBuilder,
+    module: &mut dyn cranelift_module::Module,
+    value: Value,
+) -> Result<Value> {
+    let bits = builder.ins().bitcast(types::I64, MemFlags::new(), value);
+    let box_func = runtime_function(builder, module, "jit_runtime_box_float", 1)?;
+    let call = builder.ins().call(box_func, &[bits]);
+    Ok(builder.inst_results(call)[0])
 }
 
 /// Build a complete function from bytecode, translating it into Cranelift IR.
+///
+/// The function takes a runtime context pointer, a pointer to its arguments and a
+/// pointer to its captured environment, all as i64. The VM stack is modelled at
+/// compile time; at block boundaries it is passed through one variable per slot.
+/// Opcodes without native code call back into the VM through an interpreter stub.
+/// Arithmetic is specialized on `feedback` behind type guards that deoptimize.
+/// `relocatable` code embeds no heap pointers, so it can be written to an object file.
 pub fn build_function(
     func: &mut codegen::ir::Function,
     func_ctx: &mut FunctionBuilderContext,
+    chunk_id: usize,
     chunk: &BytecodeChunk,
+    feedback: &TypeFeedback,
+    relocatable: bool,
     module: &mut dyn cranelift_module::Module,
 ) -> Result<()> {
     let mut builder = FunctionBuilder::new(func, func_ctx);
@@ -65,13 +548,25 @@ pub fn build_function(
     builder.append_block_params_for_function_params(entry_block);
     builder.switch_to_block(entry_block);
     // Note: Sealing the entry block immediately is fine as it has no predecessors.
+    builder.seal_block(entry_block);
+    let params = builder.block_params(entry_block).to_vec();
+    let (ctx_ptr, args_ptr, env_ptr) = (params[0], params[1], params[2]);
 
     // --- Pass 1: Discover all jump targets and create blocks for them ---
+    // The entry block loads the arguments, so a jump back to pc=0 gets its own block
     let mut blocks = HashMap::new();
-    blocks.insert(0, entry_block); // The entry point is at pc=0
+    // Captured values the code reads, which a self tail call must leave unchanged
+    let mut captures_read = 0;
 
     for (pc, instruction) in chunk.instructions.iter().enumerate() {
         match instruction.opcode {
+            Opcode::LoadCaptured => {
+                captures_read = captures_read.max(instruction.arg as usize + 1);
+            }
+            Opcode::TailCall if instruction.arg as usize == chunk.arity => {
+                // A self tail call jumps back to the start
+                blocks.entry(0).or_insert_with(|| builder.create_block());
+            }
             Opcode::Jump => {
                 let target = instruction.arg as usize;
                 if !blocks.contains_key(&target) {
@@ -98,22 +593,17 @@ pub fn build_function(
         }
     }
 
-    // --- Pass 2: Declare all local variables ---
-    let mut locals = HashMap::new();
-    // Assuming locals are dense from 0 to N. A more robust method might scan
-    // all Load/Store opcodes to find the max index.
-    let num_locals = 32; // Placeholder for actual local count from bytecode info
-    for i in 0..num_locals {
-        // We declare all locals as I64 and will bitcast if we need to store floats.
-        let var = Variable::new(i);
-        builder.declare_var(var, types::I64);
-        locals.insert(i as u32, var);
-        let zero = builder.ins().iconst(types::I64, 0);
-        builder.def_var(var, zero); // Initialize to 0
+    // --- Pass 2: Load the arguments ---
+    // Locals live in stack slots, with the arguments in the first `arity` slots
+    let mut value_stack: Vec<JitValue> = Vec::with_capacity(chunk.arity);
+    for i in 0..chunk.arity {
+        let val = builder.ins().load(types::I64, MemFlags::trusted(), args_ptr, (i * 8) as i32);
+        value_stack.push(JitValue { val, ty: types::I64 });
     }
 
     // --- Pass 3: Translate instructions ---
-    let mut value_stack: Vec<JitValue> = Vec::new();
+    let mut slots: Vec<Variable> = Vec::new();
+    let mut block_depths: HashMap<usize, usize> = HashMap::new();
     let mut pc = 0;
     let mut block_terminated = false;
     
@@ -121,26 +611,35 @@ pub fn build_function(
         // If the current PC is a jump target, switch to its block.
         if let Some(&block) = blocks.get(&pc) {
             if !block_terminated {
-                 builder.ins().jump(block, &[]);
+                spill_stack(&mut builder, &mut slots, &value_stack);
+                block_depths.insert(pc, value_stack.len());
+                builder.ins().jump(block, &[]);
             }
             builder.switch_to_block(block);
             block_terminated = false;
+
+            // Reload the stack from the slot variables written by each predecessor
+            let depth = block_depths.get(&pc).copied().unwrap_or(0);
+            value_stack.clear();
+            for i in 0..depth {
+                let var = stack_slot(&mut builder, &mut slots, i);
+                let val = builder.use_var(var);
+                value_stack.push(JitValue { val, ty: types::I64 });
+            }
+        } else if block_terminated {
+            // Unreachable code after a return or jump
+            pc += 1;
+            continue;
         }
 
         let instruction = &chunk.instructions[pc];
 
         match instruction.opcode {
             // --- Stack Operations ---
-            Opcode::Push => {
-                let constant = chunk
-                    .constants
-                    .get(instruction.arg as usize)
-                    .ok_or_else(|| anyhow!("Invalid constant index"))?;
-
-                // Convert the constant to a tagged value
-                let tagged = value_to_tagged(constant);
+            Opcode::Push | Opcode::PushConst => {
+                let val = constant(&mut builder, module, ctx_ptr, chunk_id, chunk, instruction.arg, relocatable)?;
                 let jit_val = JitValue {
-                    val: builder.ins().iconst(types::I64, tagged.0 as i64),
+                    val,
                     ty: types::I64, // All values are represented as tagged i64
                 };
                 value_stack.push(jit_val);
@@ -157,6 +656,20 @@ pub fn build_function(
                 value_stack.push(val);
             }
             
+            Opcode::PopN => {
+                // Pop N values below the top of the stack, keeping the top
+                let n = instruction.arg as usize;
+                if n > 0 {
+                    let top = value_stack.pop().ok_or_else(|| anyhow!("JIT Error: Stack underflow on POPN"))?;
+                    let len = value_stack.len();
+                    if len < n {
+                        return Err(anyhow!("JIT Error: Stack underflow on POPN"));
+                    }
+                    value_stack.truncate(len - n);
+                    value_stack.push(top);
+                }
+            }
+            
             Opcode::Swap => {
                 let len = value_stack.len();
                 if len < 2 {
@@ -204,7 +717,7 @@ pub fn build_function(
             }
             
             Opcode::PushTrue => {
-                let true_val = TaggedValue::from_integer(1);
+                let true_val = TaggedValue::TRUE;
                 let jit_val = JitValue {
                     val: builder.ins().iconst(types::I64, true_val.0 as i64),
                     ty: types::I64,
@@ -213,7 +726,7 @@ pub fn build_function(
             }
             
             Opcode::PushFalse => {
-                let false_val = TaggedValue::from_integer(0);
+                let false_val = TaggedValue::FALSE;
                 let jit_val = JitValue {
                     val: builder.ins().iconst(types::I64, false_val.0 as i64),
                     ty: types::I64,
@@ -222,314 +735,283 @@ pub fn build_function(
             }
             
             Opcode::PushNil => {
-                // Use a special tagged value for nil
-                let nil_val = value_to_tagged(&ClValue::Nil);
                 let jit_val = JitValue {
-                    val: builder.ins().iconst(types::I64, nil_val.0 as i64),
+                    val: nil(&mut builder),
                     ty: types::I64,
                 };
                 value_stack.push(jit_val);
             }
 
-            // --- Arithmetic Operations ---
-            Opcode::Add => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                // For now, assume both values are tagged integers
-                // TODO: Add runtime type checking and support for other types
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let sum = builder.ins().iadd(l_int, r_int);
-                let result = JitValue::make_tagged_int(&mut builder, sum);
-                value_stack.push(result);
-            }
+            // --- Arithmetic and Comparison Operations ---
+            Opcode::Add
+            | Opcode::AddInt
+            | Opcode::Sub
+            | Opcode::SubInt
+            | Opcode::Mul
+            | Opcode::MulInt
+            | Opcode::Div
+            | Opcode::DivInt
+            | Opcode::Mod
+            | Opcode::Lt
+            | Opcode::LtInt
+            | Opcode::Le
+            | Opcode::LeInt
+            | Opcode::Gt
+            | Opcode::GtInt
+            | Opcode::Ge
+            | Opcode::GeInt => {
+                let len = value_stack.len();
+                if len < 2 {
+                    return Err(anyhow!("Stack underflow"));
+                }
+                let (l, r) = (value_stack[len - 2].clone(), value_stack[len - 1].clone());
+                let site = Site { ctx_ptr, pc, stack: &value_stack };
 
-            Opcode::Sub => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                // For now, assume both values are tagged integers
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let diff = builder.ins().isub(l_int, r_int);
-                let result = JitValue::make_tagged_int(&mut builder, diff);
-                value_stack.push(result);
-            }
-            
-            Opcode::Mul => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let product = builder.ins().imul(l_int, r_int);
-                let result = JitValue::make_tagged_int(&mut builder, product);
-                value_stack.push(result);
-            }
-            
-            Opcode::Div => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let quotient = builder.ins().sdiv(l_int, r_int);
-                let result = JitValue::make_tagged_int(&mut builder, quotient);
-                value_stack.push(result);
-            }
-            
-            Opcode::Mod => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let remainder = builder.ins().srem(l_int, r_int);
-                let result = JitValue::make_tagged_int(&mut builder, remainder);
-                value_stack.push(result);
+                let result = match specialization(instruction.opcode, feedback.profile(chunk_id, pc)) {
+                    TypeProfile::Float => float_binary_op(&mut builder, module, &site, instruction.opcode, &l, &r)?,
+                    TypeProfile::Mixed => {
+                        interpret(&mut builder, module, ctx_ptr, chunk_id, instruction, &[l, r])?
+                    }
+                    _ => int_binary_op(&mut builder, module, &site, instruction.opcode, &l, &r)?,
+                };
+                value_stack.truncate(len - 2);
+                value_stack.push(JitValue { val: result, ty: types::I64 });
             }
-            
+
             Opcode::Neg => {
-                let val = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let int_val = val.untag_int(&mut builder);
-                let negated = builder.ins().ineg(int_val);
-                let result = JitValue::make_tagged_int(&mut builder, negated);
-                value_stack.push(result);
-            }
-            
-            // --- Comparison Operations ---
-            Opcode::Eq => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                // For tagged values, we can compare directly
-                let eq = builder.ins().icmp(IntCC::Equal, l.val, r.val);
-                let result = JitValue::make_tagged_bool(&mut builder, eq);
-                value_stack.push(result);
-            }
-            
-            Opcode::Ne => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let ne = builder.ins().icmp(IntCC::NotEqual, l.val, r.val);
-                let result = JitValue::make_tagged_bool(&mut builder, ne);
-                value_stack.push(result);
-            }
-            
-            Opcode::Lt => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let lt = builder.ins().icmp(IntCC::SignedLessThan, l_int, r_int);
-                let result = JitValue::make_tagged_bool(&mut builder, lt);
-                value_stack.push(result);
-            }
-            
-            Opcode::Le => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let le = builder.ins().icmp(IntCC::SignedLessThanOrEqual, l_int, r_int);
-                let result = JitValue::make_tagged_bool(&mut builder, le);
-                value_stack.push(result);
-            }
-            
-            Opcode::Gt => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let gt = builder.ins().icmp(IntCC::SignedGreaterThan, l_int, r_int);
-                let result = JitValue::make_tagged_bool(&mut builder, gt);
-                value_stack.push(result);
+                let val = value_stack.last().ok_or_else(|| anyhow!("Stack underflow"))?.clone();
+                let site = Site { ctx_ptr, pc, stack: &value_stack };
+
+                let result = match feedback.profile(chunk_id, pc) {
+                    TypeProfile::Float => {
+                        site.deopt_unless_float(&mut builder, module, &[&val])?;
+                        let f = untag_float(&mut builder, &val);
+                        let negated = builder.ins().fneg(f);
+                        box_float(&mut builder, module, negated)?
+                    }
+                    TypeProfile::Mixed => {
+                        interpret(&mut builder, module, ctx_ptr, chunk_id, instruction, &[val])?
+                    }
+                    _ => {
+                        // Negating the smallest integer overflows
+                        site.deopt_unless_int(&mut builder, module, &[&val])?;
+                        let zero = builder.ins().iconst(types::I64, 0);
+                        let (negated, overflow) = builder.ins().ssub_overflow(zero, val.val);
+                        site.deopt_if(&mut builder, module, overflow)?;
+                        negated
+                    }
+                };
+                value_stack.pop();
+                value_stack.push(JitValue { val: result, ty: types::I64 });
             }
-            
-            Opcode::Ge => {
+
+            Opcode::Eq | Opcode::Ne => {
                 let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
                 let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let ge = builder.ins().icmp(IntCC::SignedGreaterThanOrEqual, l_int, r_int);
-                let result = JitValue::make_tagged_bool(&mut builder, ge);
-                value_stack.push(result);
+                let result = equality(&mut builder, module, ctx_ptr, chunk_id, instruction, &l, &r)?;
+                value_stack.push(JitValue { val: result, ty: types::I64 });
             }
             
             // --- Boolean Operations ---
-            Opcode::And => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                // For tagged integers used as bools, we need to check if both are non-zero
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let zero = builder.ins().iconst(types::I64, 0);
-                let l_bool = builder.ins().icmp(IntCC::NotEqual, l_int, zero);
-                let r_bool = builder.ins().icmp(IntCC::NotEqual, r_int, zero);
-                let and_result = builder.ins().band(l_bool, r_bool);
-                let result = JitValue::make_tagged_bool(&mut builder, and_result);
-                value_stack.push(result);
-            }
-            
-            Opcode::Or => {
-                let r = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let l_int = l.untag_int(&mut builder);
-                let r_int = r.untag_int(&mut builder);
-                let zero = builder.ins().iconst(types::I64, 0);
-                let l_bool = builder.ins().icmp(IntCC::NotEqual, l_int, zero);
-                let r_bool = builder.ins().icmp(IntCC::NotEqual, r_int, zero);
-                let or_result = builder.ins().bor(l_bool, r_bool);
-                let result = JitValue::make_tagged_bool(&mut builder, or_result);
-                value_stack.push(result);
+            // Like the VM these take booleans only; anything else deoptimizes and raises there.
+            // True and false differ only in bit 3, so the encodings combine bitwise.
+            Opcode::And | Opcode::Or => {
+                let len = value_stack.len();
+                if len < 2 {
+                    return Err(anyhow!("Stack underflow"));
+                }
+                let (l, r) = (value_stack[len - 2].clone(), value_stack[len - 1].clone());
+                let site = Site { ctx_ptr, pc, stack: &value_stack };
+                site.deopt_unless_bool(&mut builder, module, &[&l, &r])?;
+                let val = if instruction.opcode == Opcode::And {
+                    builder.ins().band(l.val, r.val)
+                } else {
+                    builder.ins().bor(l.val, r.val)
+                };
+                value_stack.truncate(len - 2);
+                value_stack.push(JitValue { val, ty: types::I64 });
             }
             
             Opcode::Not => {
-                let val = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let int_val = val.untag_int(&mut builder);
-                let zero = builder.ins().iconst(types::I64, 0);
-                let is_zero = builder.ins().icmp(IntCC::Equal, int_val, zero);
-                let result = JitValue::make_tagged_bool(&mut builder, is_zero);
-                value_stack.push(result);
+                let val = value_stack.last().ok_or_else(|| anyhow!("Stack underflow"))?.clone();
+                let site = Site { ctx_ptr, pc, stack: &value_stack };
+                site.deopt_unless_bool(&mut builder, module, &[&val])?;
+                let negated = builder.ins().bxor_imm(val.val, 0b1000);
+                value_stack.pop();
+                value_stack.push(JitValue { val: negated, ty: types::I64 });
             }
 
              // --- Local Variable Operations ---
-            Opcode::Load => {
-                let var = locals.get(&instruction.arg).ok_or_else(|| anyhow!("Invalid local"))?;
-                let val = builder.use_var(*var);
-                // Here we assume the type based on context, a more advanced JIT might store types of locals
-                value_stack.push(JitValue { val, ty: types::I64 });
+            Opcode::Load
+            | Opcode::LoadLocal
+            | Opcode::LoadLocal0
+            | Opcode::LoadLocal1
+            | Opcode::LoadLocal2
+            | Opcode::LoadLocal3 => {
+                let index = match instruction.opcode {
+                    Opcode::LoadLocal0 => 0,
+                    Opcode::LoadLocal1 => 1,
+                    Opcode::LoadLocal2 => 2,
+                    Opcode::LoadLocal3 => 3,
+                    _ => instruction.arg as usize,
+                };
+                let val = value_stack
+                    .get(index)
+                    .ok_or_else(|| anyhow!("Invalid local"))?
+                    .clone();
+                value_stack.push(val);
             }
 
-            Opcode::Store => {
-                let jit_val = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let var = locals.get(&instruction.arg).ok_or_else(|| anyhow!("Invalid local"))?;
-                // If storing a float, bitcast it to I64 to fit in the variable slot
-                let val_to_store = if jit_val.ty == types::F64 {
-                    builder.ins().bitcast(types::I64, MemFlags::new(), jit_val.val)
-                } else {
-                    jit_val.val
+            Opcode::Store
+            | Opcode::StoreLocal
+            | Opcode::StoreLocal0
+            | Opcode::StoreLocal1
+            | Opcode::StoreLocal2
+            | Opcode::StoreLocal3 => {
+                let index = match instruction.opcode {
+                    Opcode::StoreLocal0 => 0,
+                    Opcode::StoreLocal1 => 1,
+                    Opcode::StoreLocal2 => 2,
+                    Opcode::StoreLocal3 => 3,
+                    _ => instruction.arg as usize,
                 };
-                builder.def_var(*var, val_to_store);
+                // Like the VM, store the top of the stack without popping it
+                let val = value_stack.last().ok_or_else(|| anyhow!("Stack underflow"))?.clone();
+                let local = value_stack
+                    .get_mut(index)
+                    .ok_or_else(|| anyhow!("Invalid local"))?;
+                *local = val;
             }
 
             // --- Control Flow ---
             Opcode::Jump => {
-                let target_block = blocks[&(instruction.arg as usize)];
+                let target = instruction.arg as usize;
+                let target_block = blocks[&target];
+                spill_stack(&mut builder, &mut slots, &value_stack);
+                block_depths.insert(target, value_stack.len());
                 builder.ins().jump(target_block, &[]);
                 block_terminated = true;
             }
 
             Opcode::JumpIf => {
                 let cond = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let cond_int = cond.untag_int(&mut builder);
-                let zero = builder.ins().iconst(types::I64, 0);
-                let is_truthy = builder.ins().icmp(IntCC::NotEqual, cond_int, zero);
-                let target_block = blocks[&(instruction.arg as usize)];
+                let is_truthy = truthy(&mut builder, module, ctx_ptr, &cond)?;
+                let target = instruction.arg as usize;
+                let target_block = blocks[&target];
                 let fallthrough_block = blocks[&(pc + 1)];
+                spill_stack(&mut builder, &mut slots, &value_stack);
+                block_depths.insert(target, value_stack.len());
+                block_depths.insert(pc + 1, value_stack.len());
                 builder.ins().brif(is_truthy, target_block, &[], fallthrough_block, &[]);
                 block_terminated = true;
             }
             
             Opcode::JumpIfNot => {
                 let cond = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let cond_int = cond.untag_int(&mut builder);
-                let zero = builder.ins().iconst(types::I64, 0);
-                let is_falsy = builder.ins().icmp(IntCC::Equal, cond_int, zero);
-                let target_block = blocks[&(instruction.arg as usize)];
+                let is_truthy = truthy(&mut builder, module, ctx_ptr, &cond)?;
+                let target = instruction.arg as usize;
+                let target_block = blocks[&target];
                 let fallthrough_block = blocks[&(pc + 1)];
-                builder.ins().brif(is_falsy, target_block, &[], fallthrough_block, &[]);
+                spill_stack(&mut builder, &mut slots, &value_stack);
+                block_depths.insert(target, value_stack.len());
+                block_depths.insert(pc + 1, value_stack.len());
+                builder.ins().brif(is_truthy, fallthrough_block, &[], target_block, &[]);
                 block_terminated = true;
             }
 
-            Opcode::Return => {
-                let result = value_stack.pop().unwrap_or({
-                    // Return tagged nil if stack is empty
-                    let nil_tagged = TaggedValue::from_integer(0); // Using 0 as nil for now
-                    JitValue {
-                        val: builder.ins().iconst(types::I64, nil_tagged.0 as i64),
-                        ty: types::I64,
-                    }
-                });
-                builder.ins().return_(&[result.val]);
+            Opcode::Return | Opcode::TailReturn => {
+                // Return nil if the stack is empty
+                let result = match value_stack.pop() {
+                    Some(value) => value.val,
+                    None => nil(&mut builder),
+                };
+                builder.ins().return_(&[result]);
                 block_terminated = true;
             }
             
             // --- Function Calls ---
-            Opcode::Call => {
-                // For now, implement a simplified version that just returns a dummy value
-                // Full implementation with runtime dispatch is complex and needs more infrastructure
+            // A tail call to the running function jumps back to its start, so tail recursion
+            // runs in constant space. Other tail calls run as ordinary calls, and the Return
+            // after them passes the result on.
+            Opcode::Call | Opcode::TailCall => {
+                // The function is on top of the stack with its arguments below it
                 let arg_count = instruction.arg as usize;
-                
-                // Pop the function value
-                let _func_val = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow for function"))?;
-                
-                // Pop arguments
-                for _ in 0..arg_count {
-                    value_stack.pop().ok_or_else(|| anyhow!("Stack underflow for argument"))?;
+                let func_val = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow for function"))?;
+                if value_stack.len() < arg_count {
+                    return Err(anyhow!("Stack underflow for argument"));
                 }
-                
-                // For now, just push a dummy result
-                // TODO: Implement proper function call dispatch
-                let dummy_result = TaggedValue::from_integer(42);
+                let args = value_stack.split_off(value_stack.len() - arg_count);
+
+                if instruction.opcode == Opcode::TailCall && arg_count == chunk.arity {
+                    let chunk_val = builder.ins().iconst(types::I64, chunk_id as i64);
+                    let captures_val = builder.ins().iconst(types::I64, captures_read as i64);
+                    let is_self_call = runtime_function(&mut builder, module, "jit_runtime_is_self_call", 4)?;
+                    let call = builder.ins().call(is_self_call, &[func_val.val, chunk_val, captures_val, env_ptr]);
+                    let is_self = builder.inst_results(call)[0];
+
+                    let loop_block = builder.create_block();
+                    let call_block = builder.create_block();
+                    builder.ins().brif(is_self, loop_block, &[], call_block, &[]);
+
+                    // The arguments become the stack at the start of the function
+                    builder.switch_to_block(loop_block);
+                    builder.seal_block(loop_block);
+                    spill_stack(&mut builder, &mut slots, &args);
+                    block_depths.insert(0, arg_count);
+                    builder.ins().jump(blocks[&0], &[]);
+
+                    builder.switch_to_block(call_block);
+                    builder.seal_block(call_block);
+                }
+                let args_array = store_array(&mut builder, &args);
+                let count = builder.ins().iconst(types::I64, arg_count as i64);
+
+                // Dispatch through the runtime, which calls back into the VM for bytecode functions
+                let call_func = runtime_function(&mut builder, module, "jit_runtime_call", 4)?;
+                let call = builder.ins().call(call_func, &[ctx_ptr, func_val.val, count, args_array]);
+                let result = builder.inst_results(call)[0];
+                return_if_failed(&mut builder, result);
+
                 value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, dummy_result.0 as i64),
+                    val: result,
                     ty: types::I64,
                 });
             }
 
 
             // --- Function Operations ---
-            Opcode::MakeFunc => {
-                // MakeFunc creates a function without captures
-                let chunk_id = instruction.arg as usize;
-                
-                // Create a tagged value representing the function
-                let func_tag = TaggedValue::from_integer(chunk_id as i64);
-                value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, func_tag.0 as i64),
-                    ty: types::I64,
-                });
-            }
-            
-            // --- Closure Operations ---
-            Opcode::MakeClosure => {
-                // MakeClosure: creates a closure capturing N values from stack
+            Opcode::MakeFunc | Opcode::MakeClosure => {
+                // MakeFunc creates a function without captures. For MakeClosure,
                 // instruction.arg contains packed data: (chunk_id << 16) | capture_count
-                let chunk_id = (instruction.arg >> 16) as usize;
-                let capture_count = (instruction.arg & 0xFFFF) as usize;
-                
-                // Pop captured values
-                let mut captures = Vec::with_capacity(capture_count);
-                for _ in 0..capture_count {
-                    captures.push(value_stack.pop().ok_or_else(|| anyhow!("Stack underflow for capture"))?);
+                let (chunk_id, capture_count) = if instruction.opcode == Opcode::MakeFunc {
+                    (instruction.arg as usize, 0)
+                } else {
+                    ((instruction.arg >> 16) as usize, (instruction.arg & 0xFFFF) as usize)
+                };
+                if value_stack.len() < capture_count {
+                    return Err(anyhow!("Stack underflow for capture"));
                 }
-                captures.reverse();
-                
-                // For now, create a tagged value representing the closure
-                // In a real implementation, we'd allocate a closure object on the heap
-                // containing the chunk_id and captured environment
-                
-                // Use chunk_id as a simple closure identifier for now
-                let closure_tag = TaggedValue::from_integer(chunk_id as i64);
+
+                // Captured values become the function's environment
+                let captures = value_stack.split_off(value_stack.len() - capture_count);
+                let captures_array = store_array(&mut builder, &captures);
+                let chunk_val = builder.ins().iconst(types::I64, chunk_id as i64);
+                let count = builder.ins().iconst(types::I64, capture_count as i64);
+
+                let make_func = runtime_function(&mut builder, module, "jit_runtime_make_closure", 3)?;
+                let call = builder.ins().call(make_func, &[chunk_val, count, captures_array]);
                 value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, closure_tag.0 as i64),
+                    val: builder.inst_results(call)[0],
                     ty: types::I64,
                 });
             }
             
+            // --- Closure Operations ---
             Opcode::LoadCaptured => {
-                // Load a value from the captured environment
-                // instruction.arg is the index into the captured environment
-                let _index = instruction.arg as usize;
-                
-                // For now, just push a dummy value
-                // In a real implementation, we'd load from the closure's environment
-                let dummy = TaggedValue::from_integer(0);
-                value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, dummy.0 as i64),
-                    ty: types::I64,
-                });
+                // Load a value from the captured environment passed in by the caller
+                let index = instruction.arg as usize;
+                let val = builder.ins().load(types::I64, MemFlags::trusted(), env_ptr, (index * 8) as i32);
+                value_stack.push(JitValue { val, ty: types::I64 });
             }
             
             // --- String Operations ---
@@ -639,99 +1121,122 @@ pub fn build_function(
                 });
             }
             
-            // --- List Operations ---
-            Opcode::MakeList => {
-                // Pop N elements from stack and create a list
+            // --- Map Operations ---
+            Opcode::MakeMap => {
+                // Keys and values alternate on the stack, key first
                 let count = instruction.arg as usize;
-                let mut items = Vec::with_capacity(count);
-                
-                // Pop items in reverse order
-                for _ in 0..count {
-                    items.push(value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?);
+                if value_stack.len() < 2 * count {
+                    return Err(anyhow!("Stack underflow"));
                 }
-                items.reverse();
-                
-                // For now, create a dummy list value
-                // In a real implementation, we'd allocate a list on the heap
-                let list_id = items.len() as i64; // Use length as a simple ID
-                let list_val = TaggedValue::from_integer(list_id);
-                value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, list_val.0 as i64),
-                    ty: types::I64,
-                });
+                let entries = value_stack.split_off(value_stack.len() - 2 * count);
+                let entries_array = store_array(&mut builder, &entries);
+                let count_val = builder.ins().iconst(types::I64, count as i64);
+
+                let make_map = runtime_function(&mut builder, module, "jit_runtime_make_map", 3)?;
+                let call = builder.ins().call(make_map, &[ctx_ptr, count_val, entries_array]);
+                let result = builder.inst_results(call)[0];
+                return_if_failed(&mut builder, result);
+                value_stack.push(JitValue { val: result, ty: types::I64 });
             }
-            
-            Opcode::ListHead => {
-                // Get the first element of a list
-                let _list = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                // For now, return a dummy value
-                // In a real implementation, we'd call a runtime function
-                let dummy = TaggedValue::from_integer(0);
-                value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, dummy.0 as i64),
-                    ty: types::I64,
-                });
+
+            Opcode::MapGet => {
+                let key = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
+                let map = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
+
+                let map_get = runtime_function(&mut builder, module, "jit_runtime_map_get", 3)?;
+                let call = builder.ins().call(map_get, &[ctx_ptr, map.val, key.val]);
+                let result = builder.inst_results(call)[0];
+                return_if_failed(&mut builder, result);
+                value_stack.push(JitValue { val: result, ty: types::I64 });
             }
-            
-            Opcode::ListTail => {
-                // Get all elements except the first
-                let _list = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                // For now, return a dummy list
-                let dummy = TaggedValue::from_integer(0);
-                value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, dummy.0 as i64),
-                    ty: types::I64,
-                });
+
+            // --- Tagged Value Operations ---
+            Opcode::MakeTagged => {
+                // The tag is below the field values
+                let count = instruction.arg as usize;
+                if value_stack.len() < count + 1 {
+                    return Err(anyhow!("Stack underflow"));
+                }
+                let values = value_stack.split_off(value_stack.len() - count - 1);
+                let values_array = store_array(&mut builder, &values);
+                let count_val = builder.ins().iconst(types::I64, count as i64);
+
+                let make_tagged = runtime_function(&mut builder, module, "jit_runtime_make_tagged", 3)?;
+                let call = builder.ins().call(make_tagged, &[ctx_ptr, count_val, values_array]);
+                let result = builder.inst_results(call)[0];
+                return_if_failed(&mut builder, result);
+                value_stack.push(JitValue { val: result, ty: types::I64 });
             }
-            
-            Opcode::ListCons => {
-                // Prepend an element to a list
-                let _list = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                let _elem = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                // For now, return a dummy list
-                let dummy = TaggedValue::from_integer(1);
-                value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, dummy.0 as i64),
-                    ty: types::I64,
-                });
+
+            Opcode::GetTag => {
+                let value = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
+
+                let get_tag = runtime_function(&mut builder, module, "jit_runtime_get_tag", 2)?;
+                let call = builder.ins().call(get_tag, &[ctx_ptr, value.val]);
+                let result = builder.inst_results(call)[0];
+                return_if_failed(&mut builder, result);
+                value_stack.push(JitValue { val: result, ty: types::I64 });
             }
-            
-            Opcode::ListLen => {
-                // Get the length of a list
-                let _list = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                // For now, return a dummy length
-                let dummy_len = TaggedValue::from_integer(0);
-                value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, dummy_len.0 as i64),
-                    ty: types::I64,
-                });
+
+            Opcode::GetTaggedField => {
+                let value = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
+                let index = builder.ins().iconst(types::I64, instruction.arg as i64);
+
+                let get_field = runtime_function(&mut builder, module, "jit_runtime_get_tagged_field", 3)?;
+                let call = builder.ins().call(get_field, &[ctx_ptr, value.val, index]);
+                let result = builder.inst_results(call)[0];
+                return_if_failed(&mut builder, result);
+                value_stack.push(JitValue { val: result, ty: types::I64 });
             }
-            
-            Opcode::ListEmpty => {
-                // Check if a list is empty
-                let _list = value_stack.pop().ok_or_else(|| anyhow!("Stack underflow"))?;
-                
-                // For now, return true (1)
-                let true_val = TaggedValue::from_integer(1);
+
+            Opcode::IsTagged => {
+                // Leaves the value on the stack and pushes whether it has the expected tag
+                let value = value_stack.last().ok_or_else(|| anyhow!("Stack underflow"))?.clone();
+                let tag_val = constant(&mut builder, module, ctx_ptr, chunk_id, chunk, instruction.arg, relocatable)?;
+
+                let is_tagged = runtime_function(&mut builder, module, "jit_runtime_is_tagged", 2)?;
+                let call = builder.ins().call(is_tagged, &[value.val, tag_val]);
                 value_stack.push(JitValue {
-                    val: builder.ins().iconst(types::I64, true_val.0 as i64),
+                    val: builder.inst_results(call)[0],
                     ty: types::I64,
                 });
             }
-            
+
             // --- Halt and Unimplemented Opcodes ---
+            Opcode::LoopStart => {
+                // Marker for optimization passes
+            }
+
             Opcode::Halt => {
-                let zero = builder.ins().iconst(types::I64, 0);
-                builder.ins().return_(&[zero]);
+                // The main chunk's result is left on top of the stack
+                let result = match value_stack.pop() {
+                    Some(value) => value.val,
+                    None => nil(&mut builder),
+                };
+                builder.ins().return_(&[result]);
                 block_terminated = true;
             }
 
-            // All other opcodes are explicitly not supported yet
-            _ => return Err(anyhow!("JIT Error: Unsupported opcode {:?}", instruction.opcode)),
+            // Other opcodes run in the interpreter if they can, otherwise compilation fails
+            _ => {
+                let (pop, push) = interpreted_effect(instruction)
+                    .ok_or_else(|| anyhow!("JIT Error: Unsupported opcode {:?}", instruction.opcode))?;
+                if value_stack.len() < pop {
+                    return Err(anyhow!("Stack underflow"));
+                }
+                let operands = value_stack.split_off(value_stack.len() - pop);
+                let result = interpret(&mut builder, module, ctx_ptr, chunk_id, instruction, &operands)?;
+                if instruction.opcode == Opcode::MatchError {
+                    // Always raises, so nothing after it on this path is reachable
+                    builder.ins().return_(&[result]);
+                    block_terminated = true;
+                } else if push == 2 {
+                    value_stack.extend(operands);
+                }
+                if push > 0 {
+                    value_stack.push(JitValue { val: result, ty: types::I64 });
+                }
+            }
         }
 
         pc += 1;
@@ -743,7 +1248,7 @@ pub fn build_function(
          let result = if let Some(value) = value_stack.pop() {
             value.val
         } else {
-            builder.ins().iconst(types::I64, 0) // Default return
+            nil(&mut builder) // Default return
         };
         builder.ins().return_(&[result]);
     }
@@ -0,0 +1,103 @@
+//! Type feedback collected by the interpreter
+//!
+//! The VM records the operand types it sees at arithmetic and comparison
+//! instructions. The code generator uses them to emit integer or float code for
+//! an instruction, guarded by type checks that deoptimize when they fail.
+
+use fluentai_bytecode::Opcode;
+use fluentai_core::value::Value;
+use rustc_hash::FxHashMap;
+
+/// Number of stack operands to profile for an instruction, or `None` if the code
+/// generator does not specialize it
+pub fn profiled_operands(opcode: Opcode) -> Option<usize> {
+    use Opcode::*;
+
+    match opcode {
+        Add | Sub | Mul | Div | Mod | Lt | Le | Gt | Ge => Some(2),
+        Neg => Some(1),
+        _ => None,
+    }
+}
+
+/// Operand types observed at one instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TypeProfile {
+    /// The instruction has not run in the interpreter
+    #[default]
+    Unseen,
+    /// All operands were integers
+    Integer,
+    /// All operands were floats
+    Float,
+    /// Operands had other or differing types
+    Mixed,
+}
+
+impl TypeProfile {
+    /// Profile of a single operand
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Integer(_) => TypeProfile::Integer,
+            Value::Float(_) => TypeProfile::Float,
+            _ => TypeProfile::Mixed,
+        }
+    }
+
+    /// Combine two observations
+    pub fn merge(self, other: TypeProfile) -> Self {
+        match (self, other) {
+            (TypeProfile::Unseen, profile) | (profile, TypeProfile::Unseen) => profile,
+            (a, b) if a == b => a,
+            _ => TypeProfile::Mixed,
+        }
+    }
+}
+
+/// Observed operand types, keyed by chunk and instruction pointer
+#[derive(Debug, Clone, Default)]
+pub struct TypeFeedback {
+    sites: FxHashMap<(usize, usize), TypeProfile>,
+}
+
+impl TypeFeedback {
+    /// Create empty feedback, under which every instruction is [`TypeProfile::Unseen`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the operands of the instruction at `ip` in `chunk_id`
+    pub fn record(&mut self, chunk_id: usize, ip: usize, operands: &[Value]) {
+        let observed = operands
+            .iter()
+            .fold(TypeProfile::Unseen, |profile, value| profile.merge(TypeProfile::of(value)));
+        let site = self.sites.entry((chunk_id, ip)).or_default();
+        *site = site.merge(observed);
+    }
+
+    /// Get the profile of the instruction at `ip` in `chunk_id`
+    pub fn profile(&self, chunk_id: usize, ip: usize) -> TypeProfile {
+        self.sites.get(&(chunk_id, ip)).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profiles_merge_towards_mixed() {
+        let mut feedback = TypeFeedback::new();
+        assert_eq!(feedback.profile(0, 3), TypeProfile::Unseen);
+
+        feedback.record(0, 3, &[Value::Integer(1), Value::Integer(2)]);
+        assert_eq!(feedback.profile(0, 3), TypeProfile::Integer);
+
+        feedback.record(0, 4, &[Value::Float(1.0), Value::Float(2.0)]);
+        assert_eq!(feedback.profile(0, 4), TypeProfile::Float);
+
+        feedback.record(0, 3, &[Value::Float(1.0), Value::Integer(2)]);
+        assert_eq!(feedback.profile(0, 3), TypeProfile::Mixed);
+        assert_eq!(feedback.profile(1, 3), TypeProfile::Unseen);
+    }
+}
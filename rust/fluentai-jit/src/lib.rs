@@ -13,15 +13,17 @@ use anyhow::{anyhow, Result};
 use fluentai_bytecode::Bytecode;
 use fluentai_core::value::Value;
 use std::collections::HashMap;
-use std::mem;
 
 pub mod codegen;
+pub mod feedback;
 pub mod value;
 pub mod function_registry;
+pub mod object;
 pub mod runtime;
 
-use value::TaggedValue;
+use feedback::TypeFeedback;
 use function_registry::FunctionRegistry;
+use runtime::{JitCallback, JitOutcome};
 
 // The ABI is now defined in the value module using 3-bit tags
 
@@ -35,9 +37,91 @@ pub struct JitStats {
 }
 
 /// A handle to a compiled function, containing its signature and memory location.
+#[derive(Clone)]
 pub struct CompiledFunction {
     pub signature: Signature,
     pub code_ptr: *const u8,
+    /// Number of arguments the function expects
+    pub arity: usize,
+}
+
+// SAFETY: the code pointer refers to finalized machine code that is never written
+// again, so it can be shared and called from any thread.
+unsafe impl Send for CompiledFunction {}
+unsafe impl Sync for CompiledFunction {}
+
+impl CompiledFunction {
+    /// Calls the function, passing closure captures as its environment.
+    ///
+    /// Calls to bytecode functions made by the compiled code go through `host`.
+    /// A failed type guard is an error here, as there is no interpreter to resume in.
+    pub fn call(&self, host: &mut dyn JitCallback, args: &[Value], env: &[Value]) -> Result<Value> {
+        match self.call_or_deoptimize(host, args, env)? {
+            JitOutcome::Returned(value) => Ok(value),
+            JitOutcome::Deoptimized(deopt) => Err(anyhow!(
+                "Compiled function deoptimized at instruction {}",
+                deopt.ip
+            )),
+        }
+    }
+
+    /// Wraps code compiled ahead of time that takes `arity` arguments.
+    ///
+    /// # Safety
+    ///
+    /// `code_ptr` must point to a function with the [`runtime::JitFunction`] signature,
+    /// such as a chunk from an [`object`] file, that stays loaded while this is used.
+    pub unsafe fn from_code(code_ptr: *const u8, arity: usize) -> Result<Self> {
+        let isa_builder = cranelift_native::builder()
+            .map_err(|e| anyhow!("Failed to create ISA builder: {}", e))?;
+        let mut signature = Signature::new(isa::CallConv::triple_default(isa_builder.triple()));
+        for _ in 0..3 {
+            signature.params.push(AbiParam::new(types::I64));
+        }
+        signature.returns.push(AbiParam::new(types::I64));
+
+        Ok(Self {
+            signature,
+            code_ptr,
+            arity,
+        })
+    }
+
+    /// Calls the function, returning the interpreter state if a type guard fails.
+    pub fn call_or_deoptimize(
+        &self,
+        host: &mut dyn JitCallback,
+        args: &[Value],
+        env: &[Value],
+    ) -> Result<JitOutcome> {
+        if args.len() != self.arity {
+            return Err(anyhow!(
+                "Expected {} arguments, got {}",
+                self.arity,
+                args.len()
+            ));
+        }
+        // The signature and arity were fixed when the function was compiled
+        unsafe { runtime::invoke(self.code_ptr, host, args, env) }
+    }
+}
+
+/// Runs the bytecode functions called from JIT code by compiling them as well
+struct JitHost<'a> {
+    compiler: &'a mut JitCompiler,
+    bytecode: &'a Bytecode,
+}
+
+impl JitCallback for JitHost<'_> {
+    fn call_function(&mut self, func: &Value, args: &[Value]) -> Result<Value> {
+        match func {
+            Value::Function { chunk_id, env } => {
+                let compiled = self.compiler.compile(self.bytecode, *chunk_id)?.clone();
+                compiled.call(self, args, env)
+            }
+            _ => Err(anyhow!("Cannot call non-function value")),
+        }
+    }
 }
 
 /// Main JIT compiler interface
@@ -55,6 +139,11 @@ pub struct JitCompiler {
     stats: JitStats,
 }
 
+// SAFETY: the JIT module owns the code it holds pointers to and has no thread
+// affinity; its interior mutability is only reached through `&mut self`. It is
+// not `Sync`, so callers sharing a compiler must lock it.
+unsafe impl Send for JitCompiler {}
+
 impl JitCompiler {
     /// Creates a new JIT compiler.
     pub fn new() -> Result<Self> {
@@ -77,6 +166,13 @@ impl JitCompiler {
         
         // Register runtime functions
         builder.symbol("jit_runtime_call", runtime::jit_runtime_call as *const u8);
+        builder.symbol("jit_runtime_interpret", runtime::jit_runtime_interpret as *const u8);
+        builder.symbol("jit_runtime_is_self_call", runtime::jit_runtime_is_self_call as *const u8);
+        builder.symbol("jit_runtime_constant", runtime::jit_runtime_constant as *const u8);
+        builder.symbol("jit_runtime_is_truthy", runtime::jit_runtime_is_truthy as *const u8);
+        builder.symbol("jit_runtime_deopt", runtime::jit_runtime_deopt as *const u8);
+        builder.symbol("jit_runtime_box_float", runtime::jit_runtime_box_float as *const u8);
+        builder.symbol("jit_runtime_make_closure", runtime::jit_runtime_make_closure as *const u8);
         builder.symbol("jit_runtime_add_checked", runtime::jit_runtime_add_checked as *const u8);
         builder.symbol("jit_runtime_string_concat", runtime::jit_runtime_string_concat as *const u8);
         builder.symbol("jit_runtime_string_len", runtime::jit_runtime_string_len as *const u8);
@@ -88,6 +184,12 @@ impl JitCompiler {
         builder.symbol("jit_runtime_list_head", runtime::jit_runtime_list_head as *const u8);
         builder.symbol("jit_runtime_list_tail", runtime::jit_runtime_list_tail as *const u8);
         builder.symbol("jit_runtime_list_cons", runtime::jit_runtime_list_cons as *const u8);
+        builder.symbol("jit_runtime_make_map", runtime::jit_runtime_make_map as *const u8);
+        builder.symbol("jit_runtime_map_get", runtime::jit_runtime_map_get as *const u8);
+        builder.symbol("jit_runtime_make_tagged", runtime::jit_runtime_make_tagged as *const u8);
+        builder.symbol("jit_runtime_get_tag", runtime::jit_runtime_get_tag as *const u8);
+        builder.symbol("jit_runtime_get_tagged_field", runtime::jit_runtime_get_tagged_field as *const u8);
+        builder.symbol("jit_runtime_is_tagged", runtime::jit_runtime_is_tagged as *const u8);
         
         let module = JITModule::new(builder);
 
@@ -104,6 +206,19 @@ impl JitCompiler {
     /// Returns a `CompiledFunction` handle, which includes the function's signature
     /// and a raw pointer to the executable code.
     pub fn compile(&mut self, bytecode: &Bytecode, chunk_id: usize) -> Result<&CompiledFunction> {
+        self.compile_with_feedback(bytecode, chunk_id, &TypeFeedback::new())
+    }
+
+    /// Compiles a bytecode chunk, specializing arithmetic on the observed operand types.
+    ///
+    /// Instructions without feedback are compiled for integers. Every specialization
+    /// is guarded, and the compiled function deoptimizes when a guard fails.
+    pub fn compile_with_feedback(
+        &mut self,
+        bytecode: &Bytecode,
+        chunk_id: usize,
+        feedback: &TypeFeedback,
+    ) -> Result<&CompiledFunction> {
         // Return the cached function if it's already compiled.
         if self.function_cache.contains_key(&chunk_id) {
             return Ok(&self.function_cache[&chunk_id]);
@@ -119,22 +234,26 @@ impl JitCompiler {
         // Create a new context for this function.
         let mut ctx = self.module.make_context();
 
-        // Define the function signature. For now, all functions are `() -> i64`.
+        // Define the function signature: `(context, args, env) -> i64`, see `runtime::JitFunction`.
         // The returned i64 is a tagged value according to our ABI.
+        for _ in 0..3 {
+            ctx.func.signature.params.push(AbiParam::new(types::I64));
+        }
         ctx.func.signature.returns.push(AbiParam::new(types::I64));
 
         // Build the function IR using the codegen module.
         let mut func_ctx = FunctionBuilderContext::new();
-        codegen::build_function(&mut ctx.func, &mut func_ctx, chunk, &mut self.module)?;
+        codegen::build_function(&mut ctx.func, &mut func_ctx, chunk_id, chunk, feedback, false, &mut self.module)?;
         self.stats.codegen_time_ms += start_time.elapsed().as_secs_f64() * 1000.0;
 
-        // Define the function within the JIT module.
-        let func_name = format!("fluentai_func_{}", chunk_id);
+        // Define the function within the JIT module. A chunk compiled again after
+        // invalidation gets a new name, as module functions cannot be redefined.
+        let func_name = format!("fluentai_func_{}_{}", chunk_id, self.stats.functions_compiled);
         let func_id = self
             .module
             .declare_function(&func_name, Linkage::Local, &ctx.func.signature)?;
 
-        // Define the function - cranelift-jit handles redefinition internally
+        // Define the function body
         self.module.define_function(func_id, &mut ctx)?;
 
         // Finalize definitions, which triggers optimization and code emission.
@@ -149,6 +268,7 @@ impl JitCompiler {
         let compiled_func = CompiledFunction {
             signature: ctx.func.signature.clone(),
             code_ptr,
+            arity: chunk.arity,
         };
         self.function_cache.insert(chunk_id, compiled_func);
         self.stats.functions_compiled += 1;
@@ -157,43 +277,35 @@ impl JitCompiler {
         Ok(&self.function_cache[&chunk_id])
     }
 
-    /// Executes a compiled function and decodes the result.
-    ///
-    /// # Safety
-    /// The caller must ensure that the signature used to transmute the function pointer
-    /// matches the `compiled_func.signature`.
-    pub fn execute(&self, compiled_func: &CompiledFunction) -> Result<Value> {
-        // Transmute the raw pointer to a safe, callable function pointer.
-        // This is the primary unsafe block, relying on the correctness of the signature.
-        let func_ptr: fn() -> i64 = unsafe { mem::transmute(compiled_func.code_ptr) };
-
-        // Execute the native code.
-        let result = func_ptr();
-
-        // Decode the tagged result according to our ABI.
-        self.decode_result(result)
+    /// Returns a previously compiled function without compiling anything.
+    pub fn get_compiled(&self, chunk_id: usize) -> Option<&CompiledFunction> {
+        self.function_cache.get(&chunk_id)
     }
 
-    /// Decodes an i64 result from the JIT into a VM Value based on the ABI.
-    fn decode_result(&self, result: i64) -> Result<Value> {
-        let tagged = TaggedValue(result as u64);
-        Ok(tagged.to_value())
+    /// Executes a compiled function and decodes the result.
+    ///
+    /// Bytecode functions called from the compiled code run through `host`,
+    /// which is normally the VM that owns this compiler.
+    pub fn execute(
+        &self,
+        compiled_func: &CompiledFunction,
+        host: &mut dyn JitCallback,
+        args: &[Value],
+        env: &[Value],
+    ) -> Result<Value> {
+        compiled_func.call(host, args, env)
     }
 
     /// Compiles and executes the main bytecode chunk in one step.
+    ///
+    /// Functions called from the main chunk are compiled on first call.
     pub fn compile_and_run(&mut self, bytecode: &Bytecode) -> Result<Value> {
-        // First compile the function
-        self.compile(bytecode, bytecode.main_chunk)?;
-        
-        // Then get it from the cache and execute it
-        let func = &self.function_cache[&bytecode.main_chunk];
-        let func_ptr: fn() -> i64 = unsafe { mem::transmute(func.code_ptr) };
-        
-        // Execute the JIT'd function
-        let result = func_ptr();
-        
-        // Decode the tagged result
-        self.decode_result(result)
+        let main = self.compile(bytecode, bytecode.main_chunk)?.clone();
+        let mut host = JitHost {
+            compiler: self,
+            bytecode,
+        };
+        main.call(&mut host, &[], &[])
     }
 
     /// Returns statistics about the JIT compilation process.
@@ -201,6 +313,11 @@ impl JitCompiler {
         &self.stats
     }
 
+    /// Forgets the compiled code for one chunk, so the next compile generates it again.
+    pub fn invalidate(&mut self, chunk_id: usize) {
+        self.function_cache.remove(&chunk_id);
+    }
+
     /// Clears the function cache, forcing recompilation on the next call.
     pub fn clear_cache(&mut self) {
         self.function_cache.clear();
@@ -0,0 +1,267 @@
+//! Ahead-of-time compilation of bytecode to object files
+//!
+//! Chunks are compiled by the same code generator as the JIT, in relocatable mode.
+//! The object also holds the serialized bytecode and a table of the compiled chunks,
+//! and defines [`RUN_SYMBOL`], which hands both to the core runtime's
+//! [`RUNTIME_ENTRY_SYMBOL`]. The main chunk and chunks the code generator cannot
+//! compile are interpreted.
+
+use crate::codegen;
+use crate::feedback::TypeFeedback;
+use anyhow::{anyhow, Result};
+use cranelift::prelude::*;
+use cranelift_module::{DataDescription, DataId, FuncId, Linkage, Module};
+use cranelift_object::{ObjectBuilder, ObjectModule};
+use fluentai_bytecode::Bytecode;
+
+/// Function defined by every object: `fluentai_program_run() -> i32`
+///
+/// It runs the program and returns its exit code.
+pub const RUN_SYMBOL: &str = "fluentai_program_run";
+
+/// Core runtime function called by [`RUN_SYMBOL`]:
+/// `fluentai_aot_main(bytecode, bytecode_len, functions, function_count) -> i32`
+pub const RUNTIME_ENTRY_SYMBOL: &str = "fluentai_aot_main";
+
+/// Entry in an object's table of compiled chunks
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct NativeFunction {
+    /// Chunk the code was compiled from
+    pub chunk_id: u64,
+    /// Code with the [`JitFunction`](crate::runtime::JitFunction) signature
+    pub code: *const u8,
+}
+
+/// Options for [`compile_object`]
+#[derive(Debug, Clone)]
+pub struct ObjectOptions {
+    /// Name recorded in the object file
+    pub name: String,
+    /// 0 to skip Cranelift's optimizations, otherwise optimize for speed
+    pub opt_level: u8,
+    /// Also define a C `main` calling [`RUN_SYMBOL`], for linking an executable
+    pub define_main: bool,
+}
+
+impl Default for ObjectOptions {
+    fn default() -> Self {
+        Self {
+            name: "fluentai_program".to_string(),
+            opt_level: 2,
+            define_main: false,
+        }
+    }
+}
+
+/// An object file produced by [`compile_object`]
+#[derive(Debug)]
+pub struct ObjectFile {
+    /// Contents of the object file
+    pub bytes: Vec<u8>,
+    /// Chunks compiled to native code
+    pub compiled_chunks: Vec<usize>,
+    /// Chunks left to the interpreter, with the reason
+    pub interpreted_chunks: Vec<(usize, String)>,
+}
+
+/// Target triple of the host, which objects are compiled for
+pub fn host_target() -> Result<String> {
+    let isa_builder =
+        cranelift_native::builder().map_err(|e| anyhow!("Failed to create ISA builder: {}", e))?;
+    Ok(isa_builder.triple().to_string())
+}
+
+/// Compile a program to an object file for the host
+pub fn compile_object(bytecode: &Bytecode, options: &ObjectOptions) -> Result<ObjectFile> {
+    let mut module = object_module(options)?;
+    let mut ctx = module.make_context();
+
+    // Nothing has been profiled yet, so arithmetic is compiled for integers and
+    // deoptimizes to the interpreter on anything else
+    let feedback = TypeFeedback::new();
+    let mut functions = Vec::new();
+    let mut interpreted_chunks = Vec::new();
+    for (chunk_id, chunk) in bytecode.chunks.iter().enumerate() {
+        // The VM interprets the main chunk, which defines the program's globals
+        if chunk_id == bytecode.main_chunk {
+            continue;
+        }
+
+        module.clear_context(&mut ctx);
+        for _ in 0..3 {
+            ctx.func.signature.params.push(AbiParam::new(types::I64));
+        }
+        ctx.func.signature.returns.push(AbiParam::new(types::I64));
+
+        let mut func_ctx = FunctionBuilderContext::new();
+        if let Err(e) = codegen::build_function(
+            &mut ctx.func,
+            &mut func_ctx,
+            chunk_id,
+            chunk,
+            &feedback,
+            true,
+            &mut module,
+        ) {
+            interpreted_chunks.push((chunk_id, e.to_string()));
+            continue;
+        }
+
+        let name = format!("fluentai_chunk_{}", chunk_id);
+        let func_id = module.declare_function(&name, Linkage::Local, &ctx.func.signature)?;
+        module.define_function(func_id, &mut ctx)?;
+        functions.push((chunk_id, func_id));
+    }
+
+    let serialized = bytecode
+        .to_bytes()
+        .map_err(|e| anyhow!("Failed to serialize bytecode: {}", e))?;
+    let bytecode_len = serialized.len();
+    let bytecode_id =
+        module.declare_data("fluentai_program_bytecode", Linkage::Local, false, false)?;
+    let mut data = DataDescription::new();
+    data.define(serialized.into_boxed_slice());
+    module.define_data(bytecode_id, &data)?;
+
+    let table_id = define_function_table(&mut module, &functions)?;
+
+    // fluentai_program_run() -> i32
+    let mut run_sig = module.make_signature();
+    run_sig.returns.push(AbiParam::new(types::I32));
+    let run_id = module.declare_function(RUN_SYMBOL, Linkage::Export, &run_sig)?;
+
+    let mut entry_sig = module.make_signature();
+    for _ in 0..4 {
+        entry_sig.params.push(AbiParam::new(types::I64));
+    }
+    entry_sig.returns.push(AbiParam::new(types::I32));
+    let entry_id = module.declare_function(RUNTIME_ENTRY_SYMBOL, Linkage::Import, &entry_sig)?;
+
+    define_forwarder(
+        &mut module,
+        &mut ctx,
+        run_id,
+        entry_id,
+        |builder, module| {
+            let bytecode_ptr = data_address(builder, module, bytecode_id);
+            let bytecode_len = builder.ins().iconst(types::I64, bytecode_len as i64);
+            let table_ptr = data_address(builder, module, table_id);
+            let function_count = builder.ins().iconst(types::I64, functions.len() as i64);
+            vec![bytecode_ptr, bytecode_len, table_ptr, function_count]
+        },
+    )?;
+
+    if options.define_main {
+        // main(argc, argv) -> i32; the runtime reads the arguments from the OS
+        let mut main_sig = module.make_signature();
+        main_sig.params.push(AbiParam::new(types::I32));
+        main_sig.params.push(AbiParam::new(types::I64));
+        main_sig.returns.push(AbiParam::new(types::I32));
+        let main_id = module.declare_function("main", Linkage::Export, &main_sig)?;
+        define_forwarder(&mut module, &mut ctx, main_id, run_id, |_, _| Vec::new())?;
+    }
+
+    let bytes = module
+        .finish()
+        .emit()
+        .map_err(|e| anyhow!("Failed to write object file: {}", e))?;
+    Ok(ObjectFile {
+        bytes,
+        compiled_chunks: functions.iter().map(|&(chunk_id, _)| chunk_id).collect(),
+        interpreted_chunks,
+    })
+}
+
+/// Create an object module for the host with position independent code
+fn object_module(options: &ObjectOptions) -> Result<ObjectModule> {
+    let mut flag_builder = settings::builder();
+    flag_builder
+        .set("is_pic", "true")
+        .map_err(|e| anyhow!("Failed to set is_pic flag: {}", e))?;
+    let opt_level = if options.opt_level == 0 {
+        "none"
+    } else {
+        "speed"
+    };
+    flag_builder
+        .set("opt_level", opt_level)
+        .map_err(|e| anyhow!("Failed to set opt_level flag: {}", e))?;
+
+    let isa = cranelift_native::builder()
+        .map_err(|e| anyhow!("Failed to create ISA builder: {}", e))?
+        .finish(settings::Flags::new(flag_builder))?;
+    let builder = ObjectBuilder::new(
+        isa,
+        options.name.as_str(),
+        cranelift_module::default_libcall_names(),
+    )?;
+    Ok(ObjectModule::new(builder))
+}
+
+/// Define the table of compiled chunks, laid out as an array of [`NativeFunction`]
+fn define_function_table(
+    module: &mut ObjectModule,
+    functions: &[(usize, FuncId)],
+) -> Result<DataId> {
+    const ENTRY_SIZE: usize = std::mem::size_of::<NativeFunction>();
+
+    let table_id =
+        module.declare_data("fluentai_program_functions", Linkage::Local, false, false)?;
+    let mut data = DataDescription::new();
+    let mut table = vec![0u8; functions.len() * ENTRY_SIZE];
+    for (entry, &(chunk_id, _)) in table.chunks_mut(ENTRY_SIZE).zip(functions) {
+        entry[..8].copy_from_slice(&(chunk_id as u64).to_ne_bytes());
+    }
+    data.define(table.into_boxed_slice());
+    data.set_align(8);
+    for (i, &(_, func_id)) in functions.iter().enumerate() {
+        let func_ref = module.declare_func_in_data(func_id, &mut data);
+        data.write_function_addr((i * ENTRY_SIZE + 8) as u32, func_ref);
+    }
+    module.define_data(table_id, &data)?;
+    Ok(table_id)
+}
+
+/// Define `func_id` as a call to `callee` with the given arguments, returning its result
+fn define_forwarder(
+    module: &mut ObjectModule,
+    ctx: &mut cranelift_codegen::Context,
+    func_id: FuncId,
+    callee: FuncId,
+    args: impl FnOnce(&mut FunctionBuilder, &mut ObjectModule) -> Vec<Value>,
+) -> Result<()> {
+    module.clear_context(ctx);
+    ctx.func.signature = module
+        .declarations()
+        .get_function_decl(func_id)
+        .signature
+        .clone();
+
+    let mut func_ctx = FunctionBuilderContext::new();
+    let mut builder = FunctionBuilder::new(&mut ctx.func, &mut func_ctx);
+    let block = builder.create_block();
+    builder.append_block_params_for_function_params(block);
+    builder.switch_to_block(block);
+    builder.seal_block(block);
+
+    let args = args(&mut builder, module);
+    let callee = module.declare_func_in_func(callee, builder.func);
+    let call = builder.ins().call(callee, &args);
+    let result = builder.inst_results(call)[0];
+    builder.ins().return_(&[result]);
+    builder.finalize();
+
+    module.define_function(func_id, ctx)?;
+    Ok(())
+}
+
+/// Address of a data object defined in the module
+fn data_address(
+    builder: &mut FunctionBuilder,
+    module: &mut ObjectModule,
+    data_id: DataId,
+) -> Value {
+    let global = module.declare_data_in_func(data_id, builder.func);
+    builder.ins().symbol_value(types::I64, global)
+}
@@ -3,21 +3,134 @@
 //! This module provides runtime functions that JIT code can call for complex operations
 //! that are easier to implement in Rust than in generated machine code.
 
-use crate::value::{TaggedValue, value_to_tagged};
+use crate::value::{TaggedValue, ValueTag, value_to_tagged};
+use anyhow::{anyhow, Result};
+use fluentai_bytecode::{Instruction, Opcode};
 use fluentai_core::value::Value;
+use rustc_hash::FxHashMap;
 use std::slice;
 
+/// Returned by runtime calls that failed; the error is left in the [`JitContext`].
+///
+/// This is a null pointer with the `Other` tag, which no real value uses. Compiled
+/// code also returns it after deoptimizing.
+pub const CALL_FAILED: i64 = ValueTag::Other as i64;
+
+/// Signature of a compiled chunk: `(context, arguments, captured environment) -> result`
+pub type JitFunction = extern "C" fn(*mut JitContext<'_>, *const i64, *const i64) -> i64;
+
+/// Callback for running functions that JIT code cannot call directly
+///
+/// The VM implements this so compiled code can call interpreted closures, which
+/// may in turn call back into compiled code.
+pub trait JitCallback {
+    /// Call a function value with the given arguments
+    fn call_function(&mut self, func: &Value, args: &[Value]) -> Result<Value>;
+
+    /// Execute an instruction the JIT has no native code for
+    ///
+    /// `operands` are the values the instruction pops, bottom of the stack first.
+    /// Returns the value it pushes, or nil if it pushes nothing.
+    fn execute_instruction(
+        &mut self,
+        chunk_id: usize,
+        instruction: &Instruction,
+        operands: &[Value],
+    ) -> Result<Value> {
+        let _ = (chunk_id, operands);
+        Err(anyhow!("{:?} requires the interpreter", instruction.opcode))
+    }
+
+    /// Get constant `index` of chunk `chunk_id`
+    ///
+    /// Code compiled ahead of time cannot embed constants, so it loads them here.
+    fn constant(&mut self, chunk_id: usize, index: usize) -> Result<Value> {
+        Err(anyhow!("Constant {} of chunk {} is not available", index, chunk_id))
+    }
+
+    /// Decide whether a branch on `value` is taken
+    ///
+    /// Compiled code decides booleans and nil itself. The VM overrides this with
+    /// its own rules, so compiled branches agree with interpreted ones.
+    fn is_truthy(&mut self, value: &Value) -> bool {
+        value.is_truthy()
+    }
+}
+
+/// State shared between an executing JIT function and the runtime helpers it calls
+pub struct JitContext<'a> {
+    host: &'a mut dyn JitCallback,
+    error: Option<anyhow::Error>,
+    deopt: Option<Deoptimization>,
+}
+
+/// Interpreter state for resuming a compiled function whose type guard failed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deoptimization {
+    /// Instruction to resume at; it has not run yet
+    pub ip: usize,
+    /// The function's stack slots relative to its frame, arguments first
+    pub stack: Vec<Value>,
+}
+
+/// How a compiled function finished
+#[derive(Debug, Clone, PartialEq)]
+pub enum JitOutcome {
+    /// The function ran to completion
+    Returned(Value),
+    /// A type guard failed; the interpreter must finish the call
+    Deoptimized(Deoptimization),
+}
+
+/// Run a compiled function with its arguments and captured environment
+///
+/// # Safety
+/// `code_ptr` must point to a function produced by [`crate::JitCompiler::compile`]
+/// whose chunk takes exactly `args.len()` arguments and captures at least
+/// `env.len()` values.
+pub unsafe fn invoke(
+    code_ptr: *const u8,
+    host: &mut dyn JitCallback,
+    args: &[Value],
+    env: &[Value],
+) -> Result<JitOutcome> {
+    let func: JitFunction = std::mem::transmute(code_ptr);
+    let args: Vec<i64> = args.iter().map(|v| value_to_tagged(v).0 as i64).collect();
+    let env: Vec<i64> = env.iter().map(|v| value_to_tagged(v).0 as i64).collect();
+
+    let mut context = JitContext {
+        host,
+        error: None,
+        deopt: None,
+    };
+    let result = func(&mut context, args.as_ptr(), env.as_ptr());
+
+    if let Some(e) = context.error.take() {
+        return Err(e);
+    }
+    match context.deopt.take() {
+        Some(deopt) => Ok(JitOutcome::Deoptimized(deopt)),
+        None if result == CALL_FAILED => Err(anyhow!("JIT function failed without an error")),
+        None => Ok(JitOutcome::Returned(TaggedValue(result as u64).to_value())),
+    }
+}
+
 /// Runtime function for handling dynamic function calls
 /// 
 /// This function is called from JIT code when a CALL instruction is executed.
-/// It handles the dispatch to the appropriate function type (VM function, native function, etc.)
+/// Native functions are called directly; VM functions go back through the
+/// context's [`JitCallback`], so they run on the VM that owns this JIT code.
+/// Returns [`CALL_FAILED`] and records the error in the context on failure.
 #[no_mangle]
 pub extern "C" fn jit_runtime_call(
+    ctx: *mut JitContext<'_>,
     func_tagged: i64,
     arg_count: i64,
     args_ptr: *const i64,
 ) -> i64 {
     unsafe {
+        let ctx = &mut *ctx;
+
         // Convert arguments to slice
         let args = if arg_count > 0 {
             slice::from_raw_parts(args_ptr, arg_count as usize)
@@ -37,50 +150,172 @@ pub extern "C" fn jit_runtime_call(
         }
         
         // Dispatch based on function type
-        match func {
-            Value::Function { chunk_id, env } => {
-                // For VM functions, we need to call back into the VM
-                // This is complex and would require a VM instance reference
-                // For now, return an error value
-                let error = Value::Error {
-                    kind: "JIT".to_string(),
-                    message: "VM function calls from JIT not yet implemented".to_string(),
-                    stack_trace: None,
-                };
-                value_to_tagged(&error).0 as i64
-            }
+        let result = match func {
+            Value::Function { .. } => ctx.host.call_function(&func, &arg_values),
             Value::NativeFunction { function, .. } => {
-                // Call the native function
-                match function(&arg_values) {
-                    Ok(result) => value_to_tagged(&result).0 as i64,
-                    Err(e) => {
-                        let error = Value::Error {
-                            kind: "Native".to_string(),
-                            message: e.to_string(),
-                            stack_trace: None,
-                        };
-                        value_to_tagged(&error).0 as i64
-                    }
-                }
+                function(&arg_values).map_err(|e| anyhow!("Native function error: {}", e))
             }
-            _ => {
-                // Not a callable value
-                let error = Value::Error {
-                    kind: "TypeError".to_string(),
-                    message: format!("Cannot call non-function value"),
-                    stack_trace: None,
-                };
-                value_to_tagged(&error).0 as i64
+            _ => Err(anyhow!("Cannot call non-function value")),
+        };
+
+        match result {
+            Ok(value) => value_to_tagged(&value).0 as i64,
+            Err(e) => {
+                ctx.error = Some(e);
+                CALL_FAILED
             }
         }
     }
 }
 
+/// Record a failed runtime call in the context and return [`CALL_FAILED`]
+fn fail(ctx: *mut JitContext<'_>, error: anyhow::Error) -> i64 {
+    unsafe { (*ctx).error = Some(error) };
+    CALL_FAILED
+}
+
+/// Runtime helper for checking whether a tail call calls the running function again
+///
+/// The running function is chunk `chunk_id`, and its code reads the first
+/// `capture_count` values of its environment at `env_ptr`. Returns 1 untagged if
+/// `func_tagged` is that chunk with the same values there, 0 otherwise.
+#[no_mangle]
+pub extern "C" fn jit_runtime_is_self_call(
+    func_tagged: i64,
+    chunk_id: i64,
+    capture_count: i64,
+    env_ptr: *const i64,
+) -> i64 {
+    match TaggedValue(func_tagged as u64).to_value() {
+        Value::Function { chunk_id: callee, env }
+            if callee == chunk_id as usize && env.len() >= capture_count as usize =>
+        {
+            let captures = unsafe { decode_values(capture_count, env_ptr) };
+            (env[..captures.len()] == captures[..]) as i64
+        }
+        _ => 0,
+    }
+}
+
+/// Decode `count` tagged values starting at `ptr`
+unsafe fn decode_values(count: i64, ptr: *const i64) -> Vec<Value> {
+    if count <= 0 {
+        return Vec::new();
+    }
+    slice::from_raw_parts(ptr, count as usize)
+        .iter()
+        .map(|&v| TaggedValue(v as u64).to_value())
+        .collect()
+}
+
+/// Pack an instruction into the single i64 passed to [`jit_runtime_interpret`]
+pub fn encode_instruction(instruction: &Instruction) -> i64 {
+    ((instruction.opcode.to_byte() as i64) << 32) | instruction.arg as i64
+}
+
+/// Interpreter stub for opcodes without native code
+///
+/// Hands the instruction and its `operand_count` popped operands to the context's
+/// [`JitCallback`], which runs it on the owning VM.
+#[no_mangle]
+pub extern "C" fn jit_runtime_interpret(
+    ctx: *mut JitContext<'_>,
+    chunk_id: i64,
+    instruction: i64,
+    operand_count: i64,
+    operands_ptr: *const i64,
+) -> i64 {
+    let opcode = match Opcode::from_byte((instruction >> 32) as u8) {
+        Some(opcode) => opcode,
+        None => return fail(ctx, anyhow!("Invalid opcode in JIT code")),
+    };
+    let instruction = Instruction::with_arg(opcode, instruction as u32);
+    let operands = unsafe { decode_values(operand_count, operands_ptr) };
+
+    let host = unsafe { &mut (*ctx).host };
+    match host.execute_instruction(chunk_id as usize, &instruction, &operands) {
+        Ok(value) => value_to_tagged(&value).0 as i64,
+        Err(e) => fail(ctx, e),
+    }
+}
+
+/// Runtime helper for loading a constant through the context's [`JitCallback`]
+#[no_mangle]
+pub extern "C" fn jit_runtime_constant(ctx: *mut JitContext<'_>, chunk_id: i64, index: i64) -> i64 {
+    let host = unsafe { &mut (*ctx).host };
+    match host.constant(chunk_id as usize, index as usize) {
+        Ok(value) => value_to_tagged(&value).0 as i64,
+        Err(e) => fail(ctx, e),
+    }
+}
+
+/// Runtime helper for the truthiness of a value that is not a boolean or nil
+///
+/// Returns 1 or 0 untagged, as decided by the context's [`JitCallback`].
+#[no_mangle]
+pub extern "C" fn jit_runtime_is_truthy(ctx: *mut JitContext<'_>, value_tagged: i64) -> i64 {
+    let value = TaggedValue(value_tagged as u64).to_value();
+    let host = unsafe { &mut (*ctx).host };
+    host.is_truthy(&value) as i64
+}
+
+/// Record a deoptimization at `ip` with `count` stack values and return [`CALL_FAILED`]
+#[no_mangle]
+pub extern "C" fn jit_runtime_deopt(
+    ctx: *mut JitContext<'_>,
+    ip: i64,
+    count: i64,
+    stack_ptr: *const i64,
+) -> i64 {
+    let stack = unsafe { decode_values(count, stack_ptr) };
+    unsafe {
+        (*ctx).deopt = Some(Deoptimization {
+            ip: ip as usize,
+            stack,
+        })
+    };
+    CALL_FAILED
+}
+
+/// Runtime helper for boxing a float given as its bit pattern
+#[no_mangle]
+pub extern "C" fn jit_runtime_box_float(bits: i64) -> i64 {
+    value_to_tagged(&Value::Float(f64::from_bits(bits as u64))).0 as i64
+}
+
+/// Runtime helper for building a function value
+///
+/// `capture_count` tagged values at `captures_ptr` become the closure's environment.
+#[no_mangle]
+pub extern "C" fn jit_runtime_make_closure(
+    chunk_id: i64,
+    capture_count: i64,
+    captures_ptr: *const i64,
+) -> i64 {
+    unsafe {
+        let captures = if capture_count > 0 {
+            slice::from_raw_parts(captures_ptr, capture_count as usize)
+        } else {
+            &[]
+        };
+
+        let env = captures
+            .iter()
+            .map(|&capture| TaggedValue(capture as u64).to_value())
+            .collect();
+        let func = Value::Function {
+            chunk_id: chunk_id as usize,
+            env,
+        };
+        value_to_tagged(&func).0 as i64
+    }
+}
+
 /// Runtime helper for integer addition with overflow checking
 #[no_mangle]
 pub extern "C" fn jit_runtime_add_checked(a: i64, b: i64) -> i64 {
     match a.checked_add(b) {
-        Some(result) => TaggedValue::from_integer(result).0 as i64,
+        Some(result) => value_to_tagged(&Value::Integer(result)).0 as i64,
         None => {
             // Return an error for overflow
             let error = Value::Error {
@@ -222,7 +457,7 @@ pub extern "C" fn jit_runtime_list_empty(list_tagged: i64) -> i64 {
     let list = TaggedValue(list_tagged as u64).to_value();
     
     match list {
-        Value::List(ref l) => TaggedValue::from_integer(if l.is_empty() { 1 } else { 0 }).0 as i64,
+        Value::List(ref l) => TaggedValue::from_bool(l.is_empty()).0 as i64,
         _ => {
             let error = Value::Error {
                 kind: "TypeError".to_string(),
@@ -317,10 +552,106 @@ pub extern "C" fn jit_runtime_list_cons(elem_tagged: i64, list_tagged: i64) -> i
     }
 }
 
+/// Runtime helper for creating a map from `count` key/value pairs
+#[no_mangle]
+pub extern "C" fn jit_runtime_make_map(
+    ctx: *mut JitContext<'_>,
+    count: i64,
+    entries_ptr: *const i64,
+) -> i64 {
+    let entries = unsafe { decode_values(count * 2, entries_ptr) };
+    let mut map = FxHashMap::default();
+    for pair in entries.chunks(2) {
+        match &pair[0] {
+            Value::String(key) => {
+                map.insert(key.clone(), pair[1].clone());
+            }
+            other => return fail(ctx, anyhow!("Map keys must be strings, got {}", other)),
+        }
+    }
+    value_to_tagged(&Value::Map(map)).0 as i64
+}
+
+/// Runtime helper for map lookup, returning nil for missing keys
+#[no_mangle]
+pub extern "C" fn jit_runtime_map_get(ctx: *mut JitContext<'_>, map_tagged: i64, key_tagged: i64) -> i64 {
+    let map = TaggedValue(map_tagged as u64).to_value();
+    let key = TaggedValue(key_tagged as u64).to_value();
+
+    match (&map, &key) {
+        (Value::Map(m), Value::String(k)) => value_to_tagged(m.get(k).unwrap_or(&Value::Nil)).0 as i64,
+        _ => fail(ctx, anyhow!("Map lookup requires a map and a string key")),
+    }
+}
+
+/// Runtime helper for creating a tagged value
+///
+/// `values_ptr` holds the tag followed by `count` field values.
+#[no_mangle]
+pub extern "C" fn jit_runtime_make_tagged(
+    ctx: *mut JitContext<'_>,
+    count: i64,
+    values_ptr: *const i64,
+) -> i64 {
+    let mut values = unsafe { decode_values(count + 1, values_ptr) };
+    match values.remove(0) {
+        Value::String(tag) => value_to_tagged(&Value::Tagged { tag, values }).0 as i64,
+        other => fail(ctx, anyhow!("Tag must be a string, got {}", other)),
+    }
+}
+
+/// Runtime helper for getting the tag of a tagged value
+#[no_mangle]
+pub extern "C" fn jit_runtime_get_tag(ctx: *mut JitContext<'_>, value_tagged: i64) -> i64 {
+    match TaggedValue(value_tagged as u64).to_value() {
+        Value::Tagged { tag, .. } => value_to_tagged(&Value::String(tag)).0 as i64,
+        other => fail(ctx, anyhow!("Expected a tagged value, got {}", other)),
+    }
+}
+
+/// Runtime helper for reading field `index` of a tagged value
+#[no_mangle]
+pub extern "C" fn jit_runtime_get_tagged_field(
+    ctx: *mut JitContext<'_>,
+    value_tagged: i64,
+    index: i64,
+) -> i64 {
+    match TaggedValue(value_tagged as u64).to_value() {
+        Value::Tagged { values, .. } => match values.get(index as usize) {
+            Some(field) => value_to_tagged(field).0 as i64,
+            None => fail(
+                ctx,
+                anyhow!("Tagged field index {} out of bounds (size: {})", index, values.len()),
+            ),
+        },
+        other => fail(ctx, anyhow!("Expected a tagged value, got {}", other)),
+    }
+}
+
+/// Runtime helper for checking a value's tag
+#[no_mangle]
+pub extern "C" fn jit_runtime_is_tagged(value_tagged: i64, tag_tagged: i64) -> i64 {
+    let value = TaggedValue(value_tagged as u64).to_value();
+    let expected = TaggedValue(tag_tagged as u64).to_value();
+
+    let is_match = match (value, expected) {
+        (Value::Tagged { tag, .. }, Value::String(expected)) => tag == expected,
+        _ => false,
+    };
+    TaggedValue::from_bool(is_match).0 as i64
+}
+
 /// Get the address of a runtime function by name
 pub fn get_runtime_function(name: &str) -> Option<*const u8> {
     match name {
         "jit_runtime_call" => Some(jit_runtime_call as *const u8),
+        "jit_runtime_interpret" => Some(jit_runtime_interpret as *const u8),
+        "jit_runtime_is_self_call" => Some(jit_runtime_is_self_call as *const u8),
+        "jit_runtime_constant" => Some(jit_runtime_constant as *const u8),
+        "jit_runtime_is_truthy" => Some(jit_runtime_is_truthy as *const u8),
+        "jit_runtime_deopt" => Some(jit_runtime_deopt as *const u8),
+        "jit_runtime_box_float" => Some(jit_runtime_box_float as *const u8),
+        "jit_runtime_make_closure" => Some(jit_runtime_make_closure as *const u8),
         "jit_runtime_add_checked" => Some(jit_runtime_add_checked as *const u8),
         "jit_runtime_string_concat" => Some(jit_runtime_string_concat as *const u8),
         "jit_runtime_string_len" => Some(jit_runtime_string_len as *const u8),
@@ -332,6 +663,12 @@ pub fn get_runtime_function(name: &str) -> Option<*const u8> {
         "jit_runtime_list_head" => Some(jit_runtime_list_head as *const u8),
         "jit_runtime_list_tail" => Some(jit_runtime_list_tail as *const u8),
         "jit_runtime_list_cons" => Some(jit_runtime_list_cons as *const u8),
+        "jit_runtime_make_map" => Some(jit_runtime_make_map as *const u8),
+        "jit_runtime_map_get" => Some(jit_runtime_map_get as *const u8),
+        "jit_runtime_make_tagged" => Some(jit_runtime_make_tagged as *const u8),
+        "jit_runtime_get_tag" => Some(jit_runtime_get_tag as *const u8),
+        "jit_runtime_get_tagged_field" => Some(jit_runtime_get_tagged_field as *const u8),
+        "jit_runtime_is_tagged" => Some(jit_runtime_is_tagged as *const u8),
         _ => None,
     }
 }
\ No newline at end of file
@@ -13,22 +13,52 @@ pub enum ValueTag {
     Float = 0b001,    // Pointer to heap-allocated f64
     String = 0b010,   // Pointer to heap-allocated String
     List = 0b011,     // Pointer to heap-allocated List
-    Closure = 0b100,  // Pointer to heap-allocated Closure
+    Closure = 0b100,  // Pointer to heap-allocated function Value
     Symbol = 0b101,   // Symbol ID (shifted left 3)
     Tagged = 0b110,   // Pointer to Tagged value
-    Other = 0b111,    // Other heap objects (Boolean, Nil, etc.)
+    Other = 0b111,    // Nil, booleans, or other heap objects (Tuple, Error, etc.)
 }
 
 const TAG_MASK: u64 = 0b111;
 const VALUE_MASK: u64 = !TAG_MASK;
 
 /// A tagged value that can be passed through JIT code as a single u64
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct TaggedValue(pub u64);
 
 impl TaggedValue {
+    /// Nil, tagged `Other` with a payload too small to be a heap pointer
+    pub const NIL: TaggedValue = TaggedValue(0b01_111);
+    /// False, tagged like [`TaggedValue::NIL`]
+    pub const FALSE: TaggedValue = TaggedValue(0b10_111);
+    /// True, which differs from [`TaggedValue::FALSE`] only in bit 3
+    pub const TRUE: TaggedValue = TaggedValue(0b11_111);
+
+    /// Create a tagged boolean value
+    pub fn from_bool(b: bool) -> Self {
+        if b {
+            Self::TRUE
+        } else {
+            Self::FALSE
+        }
+    }
+
+    /// Smallest integer that fits in a tagged integer
+    pub const MIN_INTEGER: i64 = i64::MIN >> 3;
+    /// Largest integer that fits in a tagged integer
+    pub const MAX_INTEGER: i64 = i64::MAX >> 3;
+
+    /// Whether `n` fits in the 61 bits of a tagged integer
+    pub fn fits_integer(n: i64) -> bool {
+        (Self::MIN_INTEGER..=Self::MAX_INTEGER).contains(&n)
+    }
+
     /// Create a tagged integer value
+    ///
+    /// `n` must [fit](TaggedValue::fits_integer); [`value_to_tagged`] boxes
+    /// integers that don't.
     pub fn from_integer(n: i64) -> Self {
+        debug_assert!(Self::fits_integer(n), "{} does not fit in a tagged integer", n);
         // Shift left by 3 bits and set tag to Integer
         TaggedValue(((n as u64) << 3) | ValueTag::Integer as u64)
     }
@@ -58,7 +88,8 @@ impl TaggedValue {
     /// Extract an integer value (panics if not an integer)
     pub fn to_integer(&self) -> i64 {
         debug_assert_eq!(self.tag() as u8, ValueTag::Integer as u8);
-        (self.untagged() >> 3) as i64
+        // An arithmetic shift keeps the sign
+        (self.0 as i64) >> 3
     }
 
     /// Extract a symbol ID (panics if not a symbol)
@@ -95,25 +126,31 @@ impl TaggedValue {
                 let ptr = self.to_ptr::<Vec<Value>>();
                 unsafe { Value::List((*ptr).clone()) }
             }
+            ValueTag::Closure | ValueTag::Tagged => {
+                let ptr = self.to_ptr::<Value>();
+                unsafe { (*ptr).clone() }
+            }
             ValueTag::Other => {
+                match *self {
+                    Self::NIL => return Value::Nil,
+                    Self::FALSE => return Value::Boolean(false),
+                    Self::TRUE => return Value::Boolean(true),
+                    _ => {}
+                }
                 // Check the heap object type
                 let ptr = self.to_ptr::<HeapValue>();
                 unsafe {
                     match &*ptr {
-                        HeapValue::Boolean(b) => Value::Boolean(*b),
-                        HeapValue::Nil => Value::Nil,
+                        HeapValue::Tuple(items) => Value::Tuple(items.clone()),
                         HeapValue::Error { kind, message, stack_trace } => Value::Error {
                             kind: kind.clone(),
                             message: message.clone(),
                             stack_trace: stack_trace.clone(),
                         },
+                        HeapValue::Value(value) => value.clone(),
                     }
                 }
             }
-            _ => {
-                // For now, return Nil for unimplemented types
-                Value::Nil
-            }
         }
     }
 }
@@ -121,15 +158,16 @@ impl TaggedValue {
 /// Heap-allocated values that don't fit in a tagged pointer
 #[derive(Debug)]
 pub enum HeapValue {
-    Boolean(bool),
-    Nil,
+    Tuple(Vec<Value>),
     Error { kind: String, message: String, stack_trace: Option<Vec<String>> },
+    /// Any other value (maps, cells, integers too big to tag, ...), kept as is
+    Value(Value),
 }
 
 /// Convert a VM Value to a tagged value
 pub fn value_to_tagged(value: &Value) -> TaggedValue {
     match value {
-        Value::Integer(n) => TaggedValue::from_integer(*n),
+        Value::Integer(n) if TaggedValue::fits_integer(*n) => TaggedValue::from_integer(*n),
         Value::Symbol(s) => {
             // For now, use a simple hash as the symbol ID
             // In real implementation, we'd use a proper intern table
@@ -148,12 +186,18 @@ pub fn value_to_tagged(value: &Value) -> TaggedValue {
             let boxed = Box::new(l.clone());
             TaggedValue::from_ptr(Box::into_raw(boxed), ValueTag::List)
         }
-        Value::Boolean(b) => {
-            let heap_val = Box::new(HeapValue::Boolean(*b));
-            TaggedValue::from_ptr(Box::into_raw(heap_val), ValueTag::Other)
+        Value::Function { .. } | Value::NativeFunction { .. } => {
+            let boxed = Box::new(value.clone());
+            TaggedValue::from_ptr(Box::into_raw(boxed), ValueTag::Closure)
         }
-        Value::Nil => {
-            let heap_val = Box::new(HeapValue::Nil);
+        Value::Tagged { .. } => {
+            let boxed = Box::new(value.clone());
+            TaggedValue::from_ptr(Box::into_raw(boxed), ValueTag::Tagged)
+        }
+        Value::Boolean(b) => TaggedValue::from_bool(*b),
+        Value::Nil => TaggedValue::NIL,
+        Value::Tuple(items) => {
+            let heap_val = Box::new(HeapValue::Tuple(items.clone()));
             TaggedValue::from_ptr(Box::into_raw(heap_val), ValueTag::Other)
         }
         Value::Error { kind, message, stack_trace } => {
@@ -165,8 +209,7 @@ pub fn value_to_tagged(value: &Value) -> TaggedValue {
             TaggedValue::from_ptr(Box::into_raw(heap_val), ValueTag::Other)
         }
         _ => {
-            // For unimplemented types, return a nil value
-            let heap_val = Box::new(HeapValue::Nil);
+            let heap_val = Box::new(HeapValue::Value(value.clone()));
             TaggedValue::from_ptr(Box::into_raw(heap_val), ValueTag::Other)
         }
     }
@@ -189,6 +232,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_big_integers_are_boxed() {
+        for n in [TaggedValue::MIN_INTEGER, -1, TaggedValue::MAX_INTEGER] {
+            let tagged = value_to_tagged(&Value::Integer(n));
+            assert_eq!(tagged.tag() as u8, ValueTag::Integer as u8);
+            assert_eq!(tagged.to_value(), Value::Integer(n));
+        }
+        for n in [TaggedValue::MAX_INTEGER + 1, TaggedValue::MIN_INTEGER - 1, i64::MAX, i64::MIN] {
+            let tagged = value_to_tagged(&Value::Integer(n));
+            assert_eq!(tagged.tag() as u8, ValueTag::Other as u8);
+            assert_eq!(tagged.to_value(), Value::Integer(n));
+        }
+    }
+
     #[test]
     fn test_symbol_tagging() {
         let tagged = TaggedValue::from_symbol_id(123);
@@ -208,4 +265,51 @@ mod tests {
             _ => panic!("Expected Float"),
         }
     }
+
+    #[test]
+    fn test_closure_tagging() {
+        let value = Value::Function {
+            chunk_id: 3,
+            env: vec![Value::Integer(10)],
+        };
+        let tagged = value_to_tagged(&value);
+        assert_eq!(tagged.tag() as u8, ValueTag::Closure as u8);
+        assert_eq!(tagged.to_value(), value);
+    }
+
+    #[test]
+    fn test_tagged_and_map_tagging() {
+        let value = Value::Tagged {
+            tag: "Some".to_string(),
+            values: vec![Value::Integer(1)],
+        };
+        let tagged = value_to_tagged(&value);
+        assert_eq!(tagged.tag() as u8, ValueTag::Tagged as u8);
+        assert_eq!(tagged.to_value(), value);
+
+        let mut map = rustc_hash::FxHashMap::default();
+        map.insert("a".to_string(), Value::Integer(1));
+        let value = Value::Map(map);
+        assert_eq!(value_to_tagged(&value).to_value(), value);
+        assert_eq!(value_to_tagged(&Value::Cell(3)).to_value(), Value::Cell(3));
+    }
+
+    #[test]
+    fn test_boolean_and_nil_tagging() {
+        for value in [Value::Boolean(true), Value::Boolean(false), Value::Nil] {
+            let tagged = value_to_tagged(&value);
+            assert_eq!(tagged.tag() as u8, ValueTag::Other as u8);
+            assert_eq!(tagged.to_value(), value);
+        }
+        assert_eq!(value_to_tagged(&Value::Boolean(true)), TaggedValue::TRUE);
+        assert_eq!(value_to_tagged(&Value::Nil), TaggedValue::NIL);
+    }
+
+    #[test]
+    fn test_tuple_tagging() {
+        let value = Value::Tuple(vec![Value::Integer(1), Value::String("a".to_string())]);
+        let tagged = value_to_tagged(&value);
+        assert_eq!(tagged.tag() as u8, ValueTag::Other as u8);
+        assert_eq!(tagged.to_value(), value);
+    }
 }
\ No newline at end of file
@@ -48,8 +48,8 @@ fn test_runtime_list_empty() {
     let result = TaggedValue(result_tagged as u64).to_value();
     
     match result {
-        Value::Integer(n) => assert_eq!(n, 1, "Expected 1 (true) for empty list"),
-        _ => panic!("Expected integer result, got {:?}", result),
+        Value::Boolean(b) => assert!(b, "Expected true for empty list"),
+        _ => panic!("Expected boolean result, got {:?}", result),
     }
     
     // Test non-empty list
@@ -59,8 +59,8 @@ fn test_runtime_list_empty() {
     let result = TaggedValue(result_tagged as u64).to_value();
     
     match result {
-        Value::Integer(n) => assert_eq!(n, 0, "Expected 0 (false) for non-empty list"),
-        _ => panic!("Expected integer result, got {:?}", result),
+        Value::Boolean(b) => assert!(!b, "Expected false for non-empty list"),
+        _ => panic!("Expected boolean result, got {:?}", result),
     }
 }
 
@@ -0,0 +1,272 @@
+//! Tests for opcodes compiled to native code or run through the interpreter stub
+
+use fluentai_bytecode::{Bytecode, BytecodeChunk, Instruction, Opcode};
+use fluentai_core::value::Value;
+use fluentai_jit::feedback::TypeFeedback;
+use fluentai_jit::runtime::{Deoptimization, JitCallback, JitOutcome};
+use fluentai_jit::JitCompiler;
+
+/// Build a single-chunk program from the given constants and instructions
+fn program(constants: Vec<Value>, instructions: Vec<Instruction>) -> Bytecode {
+    let mut chunk = BytecodeChunk::new(Some("main".to_string()));
+    for constant in constants {
+        chunk.add_constant(constant);
+    }
+    for instruction in instructions {
+        chunk.add_instruction(instruction);
+    }
+    let mut bytecode = Bytecode::new();
+    bytecode.main_chunk = bytecode.add_chunk(chunk);
+    bytecode
+}
+
+fn run(constants: Vec<Value>, instructions: Vec<Instruction>) -> anyhow::Result<Value> {
+    let mut jit = JitCompiler::new().unwrap();
+    jit.compile_and_run(&program(constants, instructions))
+}
+
+#[test]
+fn test_specialized_int_ops() {
+    use Opcode::*;
+    // (7 + 5) * 2 - 4 / 2
+    let result = run(
+        vec![],
+        vec![
+            Instruction::with_arg(PushIntSmall, 7),
+            Instruction::with_arg(PushIntSmall, 5),
+            Instruction::new(AddInt),
+            Instruction::new(PushInt2),
+            Instruction::new(MulInt),
+            Instruction::with_arg(PushIntSmall, 4),
+            Instruction::new(PushInt2),
+            Instruction::new(DivInt),
+            Instruction::new(SubInt),
+            Instruction::new(Halt),
+        ],
+    );
+    assert_eq!(result.unwrap(), Value::Integer(22));
+
+    let result = run(
+        vec![],
+        vec![
+            Instruction::new(PushInt1),
+            Instruction::new(PushInt2),
+            Instruction::new(LtInt),
+            Instruction::new(Halt),
+        ],
+    );
+    assert_eq!(result.unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn test_map_operations() {
+    use Opcode::*;
+    let map_program = |key: &str| {
+        run(
+            vec![
+                Value::String("a".to_string()),
+                Value::String("b".to_string()),
+                Value::String(key.to_string()),
+            ],
+            vec![
+                Instruction::with_arg(PushConst, 0),
+                Instruction::new(PushInt1),
+                Instruction::with_arg(PushConst, 1),
+                Instruction::new(PushInt2),
+                Instruction::with_arg(MakeMap, 2),
+                Instruction::with_arg(PushConst, 2),
+                Instruction::new(MapGet),
+                Instruction::new(Halt),
+            ],
+        )
+    };
+    assert_eq!(map_program("b").unwrap(), Value::Integer(2));
+    assert_eq!(map_program("c").unwrap(), Value::Nil);
+
+    // Non-string keys raise an error instead of producing a value
+    let result = run(
+        vec![],
+        vec![
+            Instruction::new(PushInt1),
+            Instruction::new(PushInt2),
+            Instruction::with_arg(MakeMap, 1),
+            Instruction::new(Halt),
+        ],
+    );
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Map keys must be strings"));
+}
+
+#[test]
+fn test_tagged_value_operations() {
+    use Opcode::*;
+    let constants = vec![Value::String("Some".to_string())];
+    let make_some = vec![
+        Instruction::with_arg(Push, 0),
+        Instruction::with_arg(PushIntSmall, 5),
+        Instruction::with_arg(MakeTagged, 1),
+    ];
+
+    let mut instructions = make_some.clone();
+    instructions.push(Instruction::new(Halt));
+    assert_eq!(
+        run(constants.clone(), instructions).unwrap(),
+        Value::Tagged {
+            tag: "Some".to_string(),
+            values: vec![Value::Integer(5)],
+        }
+    );
+
+    let mut instructions = make_some.clone();
+    instructions.extend([Instruction::new(GetTag), Instruction::new(Halt)]);
+    assert_eq!(
+        run(constants.clone(), instructions).unwrap(),
+        Value::String("Some".to_string())
+    );
+
+    // IsTagged keeps the value below its result
+    let mut instructions = make_some.clone();
+    instructions.extend([
+        Instruction::with_arg(IsTagged, 0),
+        Instruction::new(Pop),
+        Instruction::with_arg(GetTaggedField, 0),
+        Instruction::new(Halt),
+    ]);
+    assert_eq!(
+        run(constants.clone(), instructions).unwrap(),
+        Value::Integer(5)
+    );
+
+    let mut instructions = make_some;
+    instructions.extend([
+        Instruction::with_arg(GetTaggedField, 1),
+        Instruction::new(Halt),
+    ]);
+    assert!(run(constants, instructions)
+        .unwrap_err()
+        .to_string()
+        .contains("out of bounds"));
+}
+
+#[test]
+fn test_unsupported_opcodes_use_the_interpreter_stub() {
+    use Opcode::*;
+    // LoadGlobal has no native code; it compiles, but running it needs a VM
+    let bytecode = program(
+        vec![Value::String("x".to_string())],
+        vec![Instruction::with_arg(LoadGlobal, 0), Instruction::new(Halt)],
+    );
+    let mut jit = JitCompiler::new().unwrap();
+    assert!(jit.compile(&bytecode, 0).is_ok());
+
+    let err = jit.compile_and_run(&bytecode).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("LoadGlobal requires the interpreter"),
+        "{}",
+        err
+    );
+
+    // Opcodes that need an interpreter frame still fail to compile
+    let bytecode = program(
+        vec![],
+        vec![Instruction::new(PushHandler), Instruction::new(Halt)],
+    );
+    let mut jit = JitCompiler::new().unwrap();
+    assert!(jit.compile(&bytecode, 0).is_err());
+}
+
+/// Host for functions that make no calls
+struct NoCalls;
+
+impl JitCallback for NoCalls {
+    fn call_function(&mut self, _func: &Value, _args: &[Value]) -> anyhow::Result<Value> {
+        Err(anyhow::anyhow!("unexpected call"))
+    }
+}
+
+#[test]
+fn test_float_feedback_guards_operands() {
+    use Opcode::*;
+    // 1.5 + x, where the addition has only seen floats
+    let bytecode = |x: Value| {
+        program(
+            vec![Value::Float(1.5), x],
+            vec![
+                Instruction::with_arg(PushConst, 0),
+                Instruction::with_arg(PushConst, 1),
+                Instruction::new(Add),
+                Instruction::new(Halt),
+            ],
+        )
+    };
+    let mut feedback = TypeFeedback::new();
+    feedback.record(0, 2, &[Value::Float(1.5), Value::Float(0.5)]);
+
+    let float_program = bytecode(Value::Float(0.25));
+    let mut jit = JitCompiler::new().unwrap();
+    let func = jit
+        .compile_with_feedback(&float_program, float_program.main_chunk, &feedback)
+        .unwrap()
+        .clone();
+    let outcome = func.call_or_deoptimize(&mut NoCalls, &[], &[]).unwrap();
+    assert_eq!(outcome, JitOutcome::Returned(Value::Float(1.75)));
+
+    // An integer operand fails the guard, handing back the stack before the addition
+    let int_program = bytecode(Value::Integer(2));
+    let mut jit = JitCompiler::new().unwrap();
+    let func = jit
+        .compile_with_feedback(&int_program, int_program.main_chunk, &feedback)
+        .unwrap()
+        .clone();
+    let outcome = func.call_or_deoptimize(&mut NoCalls, &[], &[]).unwrap();
+    assert_eq!(
+        outcome,
+        JitOutcome::Deoptimized(Deoptimization {
+            ip: 2,
+            stack: vec![Value::Float(1.5), Value::Integer(2)],
+        })
+    );
+    assert!(func.call(&mut NoCalls, &[], &[]).is_err());
+}
+
+#[test]
+fn test_boolean_opcodes_guard_operands() {
+    use Opcode::*;
+    let result = run(
+        vec![],
+        vec![
+            Instruction::new(PushTrue),
+            Instruction::new(PushFalse),
+            Instruction::new(Or),
+            Instruction::new(PushTrue),
+            Instruction::new(And),
+            Instruction::new(Not),
+            Instruction::new(Halt),
+        ],
+    );
+    assert_eq!(result.unwrap(), Value::Boolean(false));
+
+    // The VM rejects non-booleans, so compiled code hands them back to it
+    let bytecode = program(
+        vec![],
+        vec![
+            Instruction::new(PushTrue),
+            Instruction::new(PushInt1),
+            Instruction::new(And),
+            Instruction::new(Halt),
+        ],
+    );
+    let mut jit = JitCompiler::new().unwrap();
+    let func = jit.compile(&bytecode, bytecode.main_chunk).unwrap().clone();
+    let outcome = func.call_or_deoptimize(&mut NoCalls, &[], &[]).unwrap();
+    assert_eq!(
+        outcome,
+        JitOutcome::Deoptimized(Deoptimization {
+            ip: 2,
+            stack: vec![Value::Boolean(true), Value::Integer(1)],
+        })
+    );
+}
@@ -1,6 +1,6 @@
 //! Diagnostic types and utilities for the linter
 
-use fluentai_core::ast::NodeId;
+use fluentai_core::ast::{Graph, NodeId};
 use miette::{Diagnostic, SourceSpan};
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -156,6 +156,23 @@ impl Location {
         }
     }
 
+    /// Create from the source span of a node
+    pub fn from_node(graph: &Graph, node_id: NodeId) -> Self {
+        let mut location = Self::unknown();
+        location.node_id = Some(node_id);
+        if let Some((start, end)) = graph.span(node_id) {
+            location.start.offset = start;
+            location.end.offset = end;
+        }
+        if let Some((start, end)) = graph.location(node_id) {
+            location.start.line = start.line as usize;
+            location.start.column = start.column as usize;
+            location.end.line = end.line as usize;
+            location.end.column = end.column as usize;
+        }
+        location
+    }
+
     /// Convert to miette source span
     pub fn to_source_span(&self) -> SourceSpan {
         SourceSpan::from((self.start.offset, self.end.offset - self.start.offset))
@@ -1,6 +1,6 @@
 //! Correctness-related lint rules
 
-use crate::diagnostic::{LintDiagnostic, Suggestion};
+use crate::diagnostic::{LintDiagnostic, Location, Suggestion};
 use crate::impl_rule;
 use crate::rules::{DiagnosticCollector, Rule, RuleCategory};
 use crate::visitor::Visitor;
@@ -47,16 +47,17 @@ impl Visitor for UnusedVariablesVisitor {
                     .bindings
                     .iter()
                     .filter(|(_, _, s, used)| *s == scope && !used)
-                    .map(|(name, _, _, _)| name.clone())
+                    .map(|(name, id, _, _)| (name.clone(), *id))
                     .collect();
 
-                for name in unused {
+                for (name, id) in unused {
                     if !name.starts_with('_') {
                         self.collector.add_diagnostic(
                             LintDiagnostic::warning(
                                 "unused-variables",
                                 format!("Variable '{}' is never used", name)
                             )
+                            .with_location(Location::from_node(graph, id))
                             .with_suggestion(Suggestion {
                                 message: format!("Prefix with underscore to indicate it's intentionally unused: _{}", name),
                                 replacements: vec![],
@@ -91,15 +92,18 @@ impl Visitor for UnusedVariablesVisitor {
                     .bindings
                     .iter()
                     .filter(|(_, _, s, used)| *s == scope && !used)
-                    .map(|(name, _, _, _)| name.clone())
+                    .map(|(name, id, _, _)| (name.clone(), *id))
                     .collect();
 
-                for name in unused {
+                for (name, id) in unused {
                     if !name.starts_with('_') {
-                        self.collector.add_diagnostic(LintDiagnostic::warning(
-                            "unused-variables",
-                            format!("Parameter '{}' is never used", name),
-                        ));
+                        self.collector.add_diagnostic(
+                            LintDiagnostic::warning(
+                                "unused-variables",
+                                format!("Parameter '{}' is never used", name),
+                            )
+                            .with_location(Location::from_node(graph, id)),
+                        );
                     }
                 }
 
@@ -123,7 +127,7 @@ impl Visitor for UnusedVariablesVisitor {
                         self.visit_node_id(graph, *then_branch);
                         self.visit_node_id(graph, *else_branch);
                     }
-                    Node::List(items) => {
+                    Node::List(items) | Node::Tuple(items) => {
                         for item in items {
                             self.visit_node_id(graph, *item);
                         }
@@ -177,16 +181,22 @@ impl Visitor for UnreachableCodeVisitor {
                 if let Some(Node::Literal(lit)) = graph.get_node(*condition) {
                     match lit {
                         fluentai_core::ast::Literal::Boolean(true) => {
-                            self.collector.add_diagnostic(LintDiagnostic::warning(
-                                "unreachable-code",
-                                "Else branch is unreachable due to constant true condition",
-                            ));
+                            self.collector.add_diagnostic(
+                                LintDiagnostic::warning(
+                                    "unreachable-code",
+                                    "Else branch is unreachable due to constant true condition",
+                                )
+                                .with_location(Location::from_node(graph, *else_branch)),
+                            );
                         }
                         fluentai_core::ast::Literal::Boolean(false) => {
-                            self.collector.add_diagnostic(LintDiagnostic::warning(
-                                "unreachable-code",
-                                "Then branch is unreachable due to constant false condition",
-                            ));
+                            self.collector.add_diagnostic(
+                                LintDiagnostic::warning(
+                                    "unreachable-code",
+                                    "Then branch is unreachable due to constant false condition",
+                                )
+                                .with_location(Location::from_node(graph, *then_branch)),
+                            );
                         }
                         _ => {}
                     }
@@ -260,11 +270,14 @@ impl Visitor for TypeMismatchVisitor {
                 // Check for obvious mismatches like calling a non-function
                 if let Some(func_node) = graph.get_node(*function) {
                     match func_node {
-                        Node::Literal(_) | Node::List(_) => {
-                            self.collector.add_diagnostic(LintDiagnostic::error(
-                                "type-mismatch",
-                                "Attempting to call a non-function value",
-                            ));
+                        Node::Literal(_) | Node::List(_) | Node::Tuple(_) => {
+                            self.collector.add_diagnostic(
+                                LintDiagnostic::error(
+                                    "type-mismatch",
+                                    "Attempting to call a non-function value",
+                                )
+                                .with_location(Location::from_node(graph, *function)),
+                            );
                         }
                         _ => {}
                     }
@@ -1,6 +1,6 @@
 //! Performance-related lint rules
 
-use crate::diagnostic::LintDiagnostic;
+use crate::diagnostic::{LintDiagnostic, Location};
 use crate::impl_rule;
 use crate::rules::{DiagnosticCollector, Rule, RuleCategory};
 use crate::visitor::Visitor;
@@ -82,7 +82,7 @@ impl UnusedComputation {
 
 struct UnusedComputationVisitor {
     collector: DiagnosticCollector,
-    pure_expressions: Vec<NodeId>,
+    pure_expressions: Vec<Location>,
 }
 
 impl Visitor for UnusedComputationVisitor {
@@ -94,7 +94,7 @@ impl Visitor for UnusedComputationVisitor {
                 if let Some(func_node) = graph.get_node(*function) {
                     if is_likely_pure(func_node) {
                         // This is a heuristic - would need effect analysis for accuracy
-                        self.pure_expressions.push(node_id);
+                        self.pure_expressions.push(Location::from_node(graph, node_id));
                     }
                 }
 
@@ -150,9 +150,10 @@ fn is_likely_pure(node: &Node) -> bool {
 impl UnusedComputationVisitor {
     fn finish(mut self) -> Vec<LintDiagnostic> {
         // Report pure expressions whose results are ignored
-        for _expr_id in self.pure_expressions {
+        for location in self.pure_expressions {
             self.collector.add_diagnostic(
                 LintDiagnostic::warning("unused-computation", "Pure computation result is ignored")
+                    .with_location(location)
                     .with_note("Consider removing this computation or using its result"),
             );
         }
@@ -1,6 +1,6 @@
 //! Security-related lint rules
 
-use crate::diagnostic::LintDiagnostic;
+use crate::diagnostic::{LintDiagnostic, Location};
 use crate::impl_rule;
 use crate::rules::{DiagnosticCollector, Rule, RuleCategory};
 use crate::visitor::Visitor;
@@ -23,7 +23,7 @@ struct UnsafeEffectsVisitor {
 }
 
 impl Visitor for UnsafeEffectsVisitor {
-    fn visit_node(&mut self, graph: &Graph, _node_id: NodeId, node: &Node) {
+    fn visit_node(&mut self, graph: &Graph, node_id: NodeId, node: &Node) {
         match node {
             Node::Effect {
                 effect_type,
@@ -39,6 +39,7 @@ impl Visitor for UnsafeEffectsVisitor {
                                     "unsafe-effects",
                                     format!("Potentially dangerous IO operation: {}", operation),
                                 )
+                                .with_location(Location::from_node(graph, node_id))
                                 .with_note("System command execution can be a security risk"),
                             );
                         }
@@ -46,10 +47,13 @@ impl Visitor for UnsafeEffectsVisitor {
                     EffectType::Network => {
                         // Check for unvalidated network operations
                         if args.is_empty() {
-                            self.collector.add_diagnostic(LintDiagnostic::warning(
-                                "unsafe-effects",
-                                "Network operation without explicit parameters",
-                            ));
+                            self.collector.add_diagnostic(
+                                LintDiagnostic::warning(
+                                    "unsafe-effects",
+                                    "Network operation without explicit parameters",
+                                )
+                                .with_location(Location::from_node(graph, node_id)),
+                            );
                         }
                     }
                     _ => {}
@@ -147,6 +151,7 @@ impl Visitor for UnvalidatedInputVisitor {
                             "unvalidated-input",
                             "Ensure user input is validated before use in effects",
                         )
+                        .with_location(Location::from_node(graph, node_id))
                         .with_note("Consider adding input validation"),
                     );
                 }
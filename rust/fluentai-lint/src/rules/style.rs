@@ -1,6 +1,6 @@
 //! Style-related lint rules
 
-use crate::diagnostic::LintDiagnostic;
+use crate::diagnostic::{LintDiagnostic, Location};
 use crate::impl_rule;
 use crate::rules::{DiagnosticCollector, Rule, RuleCategory};
 use crate::visitor::Visitor;
@@ -32,7 +32,7 @@ impl NamingConventions {
 }
 
 impl Visitor for NamingVisitor {
-    fn visit_node(&mut self, graph: &Graph, _node_id: NodeId, node: &Node) {
+    fn visit_node(&mut self, graph: &Graph, node_id: NodeId, node: &Node) {
         match node {
             Node::Variable { name } => {
                 if !is_valid_name(name, self.allow_snake_case, self.allow_kebab_case) {
@@ -41,6 +41,7 @@ impl Visitor for NamingVisitor {
                             "naming-conventions",
                             format!("Variable '{}' does not follow naming conventions", name),
                         )
+                        .with_location(Location::from_node(graph, node_id))
                         .with_note("FluentAi uses kebab-case for identifiers by default"),
                     );
                 }
@@ -48,10 +49,13 @@ impl Visitor for NamingVisitor {
             Node::Lambda { params, body } => {
                 for param in params {
                     if !is_valid_name(param, self.allow_snake_case, self.allow_kebab_case) {
-                        self.collector.add_diagnostic(LintDiagnostic::warning(
-                            "naming-conventions",
-                            format!("Parameter '{}' does not follow naming conventions", param),
-                        ));
+                        self.collector.add_diagnostic(
+                            LintDiagnostic::warning(
+                                "naming-conventions",
+                                format!("Parameter '{}' does not follow naming conventions", param),
+                            )
+                            .with_location(Location::from_node(graph, node_id)),
+                        );
                     }
                 }
                 self.visit_node_id(graph, *body);
@@ -68,13 +72,16 @@ impl Visitor for NamingVisitor {
                     Node::Let { bindings, body } => {
                         for (name, value) in bindings {
                             if !is_valid_name(name, self.allow_snake_case, self.allow_kebab_case) {
-                                self.collector.add_diagnostic(LintDiagnostic::warning(
-                                    "naming-conventions",
-                                    format!(
-                                        "Binding '{}' does not follow naming conventions",
-                                        name
-                                    ),
-                                ));
+                                self.collector.add_diagnostic(
+                                    LintDiagnostic::warning(
+                                        "naming-conventions",
+                                        format!(
+                                            "Binding '{}' does not follow naming conventions",
+                                            name
+                                        ),
+                                    )
+                                    .with_location(Location::from_node(graph, *value)),
+                                );
                             }
                             self.visit_node_id(graph, *value);
                         }
@@ -183,7 +190,7 @@ impl UnusedImports {
 
 struct UnusedImportsVisitor {
     collector: DiagnosticCollector,
-    imports: Vec<(NodeId, Vec<String>)>,
+    imports: Vec<(Location, Vec<String>)>,
     used_names: Vec<String>,
 }
 
@@ -195,7 +202,7 @@ impl Visitor for UnusedImportsVisitor {
                     .iter()
                     .map(|item| item.alias.as_ref().unwrap_or(&item.name).clone())
                     .collect();
-                self.imports.push((node_id, names));
+                self.imports.push((Location::from_node(graph, node_id), names));
             }
             Node::Variable { name } => {
                 self.used_names.push(name.clone());
@@ -229,7 +236,7 @@ impl Visitor for UnusedImportsVisitor {
                 self.visit_node_id(graph, *then_branch);
                 self.visit_node_id(graph, *else_branch);
             }
-            Node::List(items) => {
+            Node::List(items) | Node::Tuple(items) => {
                 for item in items {
                     self.visit_node_id(graph, *item);
                 }
@@ -242,13 +249,13 @@ impl Visitor for UnusedImportsVisitor {
 impl UnusedImportsVisitor {
     fn finish(mut self) -> Vec<LintDiagnostic> {
         // Check which imports are unused
-        for (_node_id, import_names) in self.imports {
+        for (location, import_names) in self.imports {
             for name in import_names {
                 if !self.used_names.contains(&name) {
-                    self.collector.add_diagnostic(LintDiagnostic::warning(
-                        "unused-imports",
-                        format!("Unused import: '{}'", name),
-                    ));
+                    self.collector.add_diagnostic(
+                        LintDiagnostic::warning("unused-imports", format!("Unused import: '{}'", name))
+                            .with_location(location.clone()),
+                    );
                 }
             }
         }
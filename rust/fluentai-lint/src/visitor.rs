@@ -33,12 +33,12 @@ pub trait Visitor {
                 self.visit_node_id(graph, *then_branch);
                 self.visit_node_id(graph, *else_branch);
             }
-            Node::List(items) => {
+            Node::List(items) | Node::Tuple(items) => {
                 for item in items {
                     self.visit_node_id(graph, *item);
                 }
             }
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 self.visit_node_id(graph, *expr);
                 for (_pattern, body) in branches {
                     self.visit_node_id(graph, *body);
@@ -61,6 +61,11 @@ pub trait Visitor {
             Node::Spawn { expr } => {
                 self.visit_node_id(graph, *expr);
             }
+            Node::Parallel { branches } => {
+                for branch in branches {
+                    self.visit_node_id(graph, *branch);
+                }
+            }
             Node::Send { channel, value } => {
                 self.visit_node_id(graph, *channel);
                 self.visit_node_id(graph, *value);
@@ -169,12 +174,12 @@ where
                 self.visit_node_id(graph, *then_branch);
                 self.visit_node_id(graph, *else_branch);
             }
-            Node::List(items) => {
+            Node::List(items) | Node::Tuple(items) => {
                 for item in items {
                     self.visit_node_id(graph, *item);
                 }
             }
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 self.visit_node_id(graph, *expr);
                 for (_pattern, body) in branches {
                     self.visit_node_id(graph, *body);
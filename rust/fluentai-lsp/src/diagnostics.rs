@@ -1,6 +1,8 @@
 //! Fast diagnostic computation for FluentAi
 
 use fluentai_core::ast::{Graph, Node, NodeId};
+use fluentai_core::source::LineCol;
+use fluentai_parser::ParseError;
 use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
 
 /// Compute diagnostics for an AST
@@ -25,6 +27,29 @@ pub fn compute_diagnostics(ast: &Graph, _content: &str) -> Vec<Diagnostic> {
     diagnostics
 }
 
+/// Convert the syntax errors of a recovering parse into diagnostics
+pub fn parse_error_diagnostics(ast: &Graph, errors: &[ParseError]) -> Vec<Diagnostic> {
+    errors
+        .iter()
+        .map(|error| {
+            let position = error
+                .position()
+                .zip(ast.line_index.as_ref())
+                .map(|(offset, index)| lsp_position(index.line_col(offset)))
+                .unwrap_or_default();
+            Diagnostic {
+                range: Range {
+                    start: position,
+                    end: position,
+                },
+                severity: Some(DiagnosticSeverity::ERROR),
+                message: format!("Parse error: {}", error),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
 fn check_node(graph: &Graph, node_id: NodeId, diagnostics: &mut Vec<Diagnostic>) {
     if let Some(node) = graph.get_node(node_id) {
         match node {
@@ -32,16 +57,7 @@ fn check_node(graph: &Graph, node_id: NodeId, diagnostics: &mut Vec<Diagnostic>)
                 // Check for undefined variables
                 if is_undefined_variable(name) {
                     diagnostics.push(Diagnostic {
-                        range: Range {
-                            start: Position {
-                                line: 0,
-                                character: 0,
-                            },
-                            end: Position {
-                                line: 0,
-                                character: 0,
-                            },
-                        },
+                        range: node_range(graph, node_id),
                         severity: Some(DiagnosticSeverity::ERROR),
                         message: format!("Undefined variable: {}", name),
                         ..Default::default()
@@ -60,16 +76,7 @@ fn check_node(graph: &Graph, node_id: NodeId, diagnostics: &mut Vec<Diagnostic>)
                 for param in params {
                     if !seen.insert(param) {
                         diagnostics.push(Diagnostic {
-                            range: Range {
-                                start: Position {
-                                    line: 0,
-                                    character: 0,
-                                },
-                                end: Position {
-                                    line: 0,
-                                    character: 0,
-                                },
-                            },
+                            range: node_range(graph, node_id),
                             severity: Some(DiagnosticSeverity::ERROR),
                             message: format!("Duplicate parameter: {}", param),
                             ..Default::default()
@@ -94,7 +101,7 @@ fn check_node(graph: &Graph, node_id: NodeId, diagnostics: &mut Vec<Diagnostic>)
                 check_node(graph, *then_branch, diagnostics);
                 check_node(graph, *else_branch, diagnostics);
             }
-            Node::List(items) => {
+            Node::List(items) | Node::Tuple(items) => {
                 for item in items {
                     check_node(graph, *item, diagnostics);
                 }
@@ -104,6 +111,25 @@ fn check_node(graph: &Graph, node_id: NodeId, diagnostics: &mut Vec<Diagnostic>)
     }
 }
 
+/// Editor range of a node, or the start of the document if it has no span
+fn node_range(graph: &Graph, node_id: NodeId) -> Range {
+    graph
+        .location(node_id)
+        .map(|(start, end)| Range {
+            start: lsp_position(start),
+            end: lsp_position(end),
+        })
+        .unwrap_or_default()
+}
+
+/// Convert a 1-based line/column into a 0-based editor position
+fn lsp_position(position: LineCol) -> Position {
+    Position {
+        line: position.line.saturating_sub(1),
+        character: position.column.saturating_sub(1),
+    }
+}
+
 fn is_undefined_variable(name: &str) -> bool {
     // Built-in functions and special forms are always defined
     !matches!(
@@ -157,4 +183,24 @@ mod tests {
         assert_eq!(diagnostics.len(), 1);
         assert!(diagnostics[0].message.contains("Undefined variable"));
     }
+
+    #[test]
+    fn test_diagnostic_range_points_at_node() {
+        let code = "1 + 2 *\n    undefined_var";
+        let ast = parse(code).unwrap();
+        let diagnostics = compute_diagnostics(&ast, code);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start, Position { line: 1, character: 4 });
+        assert_eq!(diagnostics[0].range.end, Position { line: 1, character: 17 });
+    }
+
+    #[test]
+    fn test_parse_error_diagnostics_for_each_error() {
+        let code = "private function f() {\n    let x = ;\n    1\n}\nlet = 2;\nf()";
+        let (ast, errors) = fluentai_parser::parse_with_recovery(code);
+        let diagnostics = parse_error_diagnostics(&ast, &errors);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].range.start, Position { line: 1, character: 12 });
+        assert_eq!(diagnostics[1].range.start.line, 4);
+    }
 }
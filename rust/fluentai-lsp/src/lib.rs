@@ -10,7 +10,7 @@
 use anyhow::Result;
 use dashmap::DashMap;
 use fluentai_core::ast::Graph;
-use fluentai_parser::parse;
+use fluentai_parser::parse_with_recovery;
 use ropey::Rope;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -25,7 +25,7 @@ mod documentation_service;
 mod hover;
 
 use completion::compute_completions;
-use diagnostics::compute_diagnostics;
+use diagnostics::{compute_diagnostics, parse_error_diagnostics};
 use hover::compute_hover;
 
 /// Document state maintained by the LSP server
@@ -33,7 +33,7 @@ use hover::compute_hover;
 struct Document {
     /// The document content as a rope for efficient editing
     rope: Rope,
-    /// Parsed AST (if successful)
+    /// Parsed AST, possibly partial with `Error` nodes where parsing failed
     ast: Option<Graph>,
     /// Version number for synchronization
     version: i32,
@@ -56,49 +56,28 @@ impl FluentAiServer {
     }
 
     /// Parse a document and update its AST
+    ///
+    /// Syntax errors do not discard the document: the parser recovers at the next
+    /// statement or definition, so features keep working on the rest of the file.
     async fn parse_document(&self, uri: &Url, content: &str) -> Option<Graph> {
         let start = std::time::Instant::now();
 
-        match parse(content) {
-            Ok(ast) => {
-                let elapsed = start.elapsed();
-                debug!("Parsed {} in {:?}", uri, elapsed);
+        let (ast, errors) = parse_with_recovery(content);
+        let elapsed = start.elapsed();
+        debug!("Parsed {} in {:?}", uri, elapsed);
 
-                // Send diagnostics
-                let diagnostics = compute_diagnostics(&ast, content);
-                self.client
-                    .publish_diagnostics(uri.clone(), diagnostics, None)
-                    .await;
-
-                Some(ast)
-            }
-            Err(e) => {
-                error!("Parse error in {}: {}", uri, e);
-
-                // Send error diagnostic
-                let diagnostic = Diagnostic {
-                    range: Range {
-                        start: Position {
-                            line: 0,
-                            character: 0,
-                        },
-                        end: Position {
-                            line: 0,
-                            character: 0,
-                        },
-                    },
-                    severity: Some(DiagnosticSeverity::ERROR),
-                    message: format!("Parse error: {}", e),
-                    ..Default::default()
-                };
+        for e in &errors {
+            error!("Parse error in {}: {}", uri, e);
+        }
 
-                self.client
-                    .publish_diagnostics(uri.clone(), vec![diagnostic], None)
-                    .await;
+        // Send diagnostics
+        let mut diagnostics = parse_error_diagnostics(&ast, &errors);
+        diagnostics.extend(compute_diagnostics(&ast, content));
+        self.client
+            .publish_diagnostics(uri.clone(), diagnostics, None)
+            .await;
 
-                None
-            }
-        }
+        Some(ast)
     }
 }
 
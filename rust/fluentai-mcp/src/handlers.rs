@@ -240,13 +240,24 @@ fn format_value(value: &Value) -> String {
     match value {
         Value::Integer(n) => n.to_string(),
         Value::Float(f) => f.to_string(),
+        Value::BigInt(n) => n.to_string(),
+        Value::Decimal(d) => d.to_string(),
         Value::String(s) => format!("\"{}\"", s),
+        Value::Bytes(b) => fluentai_core::value::format_bytes(b),
         Value::Boolean(b) => b.to_string(),
         Value::Nil => "nil".to_string(),
         Value::List(items) => {
             let items_str: Vec<_> = items.iter().map(format_value).collect();
             format!("[{}]", items_str.join(" "))
         }
+        Value::Tuple(items) => {
+            let items_str: Vec<_> = items.iter().map(format_value).collect();
+            if items_str.len() == 1 {
+                format!("({},)", items_str[0])
+            } else {
+                format!("({})", items_str.join(", "))
+            }
+        }
         Value::Map(map) => {
             let pairs: Vec<_> = map
                 .iter()
@@ -267,6 +278,13 @@ fn format_value(value: &Value) -> String {
                 format!("{}({})", tag, values_str.join(", "))
             }
         }
+        Value::Struct { name, fields } => {
+            let fields_str: Vec<_> = fields
+                .iter()
+                .map(|(k, v)| format!("{}: {}", k, format_value(v)))
+                .collect();
+            format!("{} {{ {} }}", name, fields_str.join(", "))
+        }
         Value::Module { name, .. } => format!("<module:{}>", name),
         Value::GcHandle(_) => "<gc-handle>".to_string(),
         Value::Symbol(s) => format!(":{}", s),
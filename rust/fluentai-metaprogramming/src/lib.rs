@@ -14,7 +14,7 @@ pub mod template;
 pub mod transform;
 
 pub use error::{MetaprogrammingError, Result};
-pub use macros::{MacroDefinition, MacroExpander};
+pub use macros::{expand_macros, MacroDefinition, MacroExpander};
 pub use patterns::Pattern;
 pub use query::{GraphQuery, QueryResult};
 pub use template::{Template, TemplateEngine};
@@ -2,10 +2,13 @@
 
 use crate::error::{MetaprogrammingError, Result};
 use crate::patterns::{Pattern, PatternMatcher};
-use fluentai_core::ast::{Graph, Node, NodeId};
-use fluentai_parser::parse;
-use rustc_hash::FxHashMap;
+use fluentai_core::ast::{Graph, Literal, Node, NodeId, Pattern as AstPattern};
+use fluentai_parser::flc_lexer::{Lexer, Token};
+use fluentai_parser::parse_block_into;
+use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::ops::Range;
 
 /// A macro definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -56,6 +59,10 @@ pub struct MacroExpander {
     macros: FxHashMap<String, MacroDefinition>,
     /// Expansion depth limit to prevent infinite recursion
     max_depth: usize,
+    /// Symbols generated so far, so names stay unique across expansions
+    gensym_counter: Cell<usize>,
+    /// Nodes that held the definitions collected from a graph
+    definition_sites: FxHashMap<String, NodeId>,
 }
 
 impl MacroExpander {
@@ -63,6 +70,8 @@ impl MacroExpander {
         Self {
             macros: FxHashMap::default(),
             max_depth: 100,
+            gensym_counter: Cell::new(0),
+            definition_sites: FxHashMap::default(),
         }
     }
 
@@ -110,6 +119,38 @@ impl MacroExpander {
         });
     }
 
+    /// Register the macros defined in a graph
+    ///
+    /// Each `macro` definition is registered and its node is replaced with `nil`, so
+    /// the graph can be compiled once its calls are expanded. Later definitions of
+    /// the same name replace earlier ones. Returns the number of definitions found.
+    pub fn collect_definitions(&mut self, graph: &mut Graph) -> usize {
+        let mut definitions: Vec<NodeId> = graph
+            .nodes
+            .iter()
+            .filter(|(_, node)| matches!(node, Node::MacroDef { .. }))
+            .map(|(&id, _)| id)
+            .collect();
+        definitions.sort_by_key(|id| id.0);
+
+        for &id in &definitions {
+            if let Some(Node::MacroDef { name, params, body }) =
+                graph.nodes.insert(id, Node::Literal(Literal::Nil))
+            {
+                self.definition_sites.insert(name.clone(), id);
+                self.register_macro(MacroDefinition {
+                    name,
+                    params,
+                    pattern: None,
+                    body,
+                    hygenic: true,
+                });
+            }
+        }
+
+        definitions.len()
+    }
+
     /// Expand macros in a graph
     pub fn expand_graph(&self, graph: &mut Graph) -> Result<()> {
         self.expand_graph_recursive(graph, 0)
@@ -125,11 +166,23 @@ impl MacroExpander {
         // Collect macro calls
         let macro_calls = self.find_macro_calls(graph)?;
 
-        // Expand each macro call
+        // Expand each macro call in place, so references to the call see the expansion
         for (node_id, macro_name, args) in macro_calls {
-            let expanded = self.expand_macro_call(graph, &macro_name, args)?;
+            let before = graph.nodes.keys().map(|id| id.0).max();
+            let expanded = self.expand_macro_call(graph, node_id, &macro_name, args)?;
+
+            // Expanded code reports errors at the call site
+            let span = graph.span(node_id);
+            let new_nodes: Vec<NodeId> = graph
+                .nodes
+                .keys()
+                .filter(|id| Some(id.0) > before)
+                .copied()
+                .collect();
+            for id in new_nodes {
+                graph.metadata_mut(id).span = span;
+            }
 
-            // Replace the macro call with expanded code
             self.replace_node(graph, node_id, expanded)?;
         }
 
@@ -142,6 +195,9 @@ impl MacroExpander {
     }
 
     /// Find macro calls in the graph
+    ///
+    /// Calls are returned in creation order, so arguments are expanded before the
+    /// calls that contain them.
     fn find_macro_calls(&self, graph: &Graph) -> Result<Vec<(NodeId, String, Vec<NodeId>)>> {
         let mut calls = Vec::new();
 
@@ -154,6 +210,7 @@ impl MacroExpander {
                 }
             }
         }
+        calls.sort_by_key(|(node_id, _, _)| node_id.0);
 
         Ok(calls)
     }
@@ -172,13 +229,14 @@ impl MacroExpander {
         Ok(false)
     }
 
-    /// Expand a single macro call
+    /// Expand a single macro call, returning the root of the expansion
     fn expand_macro_call(
         &self,
-        graph: &Graph,
+        graph: &mut Graph,
+        call: NodeId,
         macro_name: &str,
         args: Vec<NodeId>,
-    ) -> Result<Graph> {
+    ) -> Result<NodeId> {
         let macro_def = self
             .macros
             .get(macro_name)
@@ -194,8 +252,9 @@ impl MacroExpander {
             )));
         }
 
-        // Create expansion context
+        // Create expansion context, continuing the symbol numbering of earlier expansions
         let mut context = ExpansionContext::new();
+        context.gensym_counter = self.gensym_counter.get();
 
         // Bind arguments
         for (param, &arg_id) in macro_def.params.iter().zip(args.iter()) {
@@ -213,53 +272,139 @@ impl MacroExpander {
         }
 
         // Special handling for built-in macros
-        match macro_def.body.as_str() {
+        let expanded = match macro_def.body.as_str() {
             "cond-expansion" => self.expand_cond(&context, graph, &args),
             "let*-expansion" => self.expand_let_star(&context, graph, &args),
             _ => {
                 // Template-based expansion
-                self.expand_template(&macro_def.body, &context, graph)
+                self.expand_template(macro_def, call, &mut context, graph)
             }
-        }
+        };
+
+        self.gensym_counter.set(context.gensym_counter);
+        expanded
     }
 
-    /// Expand template with substitutions
+    /// Instantiate a template in the graph
+    ///
+    /// The template is parsed into new nodes. Its parameters are replaced by the
+    /// argument expressions and, for hygienic macros, every name the template binds
+    /// is renamed to a fresh symbol so it cannot capture the caller's variables,
+    /// and the caller's bindings are renamed so they cannot capture the template's.
     fn expand_template(
         &self,
-        template: &str,
-        context: &ExpansionContext,
-        graph: &Graph,
-    ) -> Result<Graph> {
-        // Parse template
-        let mut expanded = template.to_string();
+        macro_def: &MacroDefinition,
+        call: NodeId,
+        context: &mut ExpansionContext,
+        graph: &mut Graph,
+    ) -> Result<NodeId> {
+        let hygenic = macro_def.hygenic;
+        let before = graph.nodes.keys().map(|id| id.0).max();
+        let root = parse_block_into(&template_source(&macro_def.body, &macro_def.params), graph)
+            .map_err(|e| MetaprogrammingError::ParseError(e.to_string()))?;
+        let template_nodes: Vec<NodeId> = graph
+            .nodes
+            .keys()
+            .filter(|id| Some(id.0) > before)
+            .copied()
+            .collect();
+
+        let substitutions: FxHashMap<String, NodeId> = context
+            .arguments
+            .iter()
+            .chain(context.bindings.iter())
+            .map(|(name, &id)| (name.clone(), id))
+            .collect();
+
+        // A parameter in binding position names the variable passed as its argument
+        let mut renames = FxHashMap::default();
+        for (name, &arg_id) in &substitutions {
+            if let Some(Node::Variable { name: arg_name }) = graph.get_node(arg_id) {
+                renames.insert(name.clone(), arg_name.clone());
+            }
+        }
+
+        // Every other name bound by the template gets a fresh symbol
+        for &id in &template_nodes {
+            for binder in binders(&graph.nodes[&id]) {
+                if renames.contains_key(&binder) {
+                    continue;
+                }
+                if substitutions.contains_key(&binder) {
+                    return Err(MetaprogrammingError::MacroExpansionError(format!(
+                        "Macro parameter '{}' is bound in the template but its argument is not a variable",
+                        binder
+                    )));
+                }
+                if hygenic {
+                    let fresh = context.gensym(&binder);
+                    renames.insert(binder, fresh);
+                }
+            }
+        }
 
-        // Substitute arguments
-        for (param, &node_id) in &context.arguments {
-            let node_str = self.node_to_string(graph, node_id)?;
-            expanded = expanded.replace(&format!("${}", param), &node_str);
+        if hygenic {
+            let free: FxHashSet<String> = template_nodes
+                .iter()
+                .filter_map(|id| match &graph.nodes[id] {
+                    Node::Variable { name }
+                        if !substitutions.contains_key(name) && !renames.contains_key(name) =>
+                    {
+                        Some(name.clone())
+                    }
+                    _ => None,
+                })
+                .collect();
+            self.unshadow(graph, &macro_def.name, call, &free, context);
         }
 
-        // Substitute pattern bindings
-        for (binding, &node_id) in &context.bindings {
-            let node_str = self.node_to_string(graph, node_id)?;
-            expanded = expanded.replace(&format!("${{{}}}", binding), &node_str);
+        for &id in &template_nodes {
+            // Parameters are replaced by their arguments rather than renamed
+            if let Some(Node::Variable { name }) = graph.get_node(id) {
+                if let Some(&arg_id) = substitutions.get(name) {
+                    let arg = graph.nodes[&arg_id].clone();
+                    let metadata = graph.get_metadata(arg_id).cloned().unwrap_or_default();
+                    graph.nodes.insert(id, arg);
+                    graph.set_metadata(id, metadata);
+                    continue;
+                }
+            }
+            if let Some(node) = graph.get_node_mut(id) {
+                rename_node(node, &renames);
+            }
         }
 
-        // Parse expanded code
-        parse(&expanded).map_err(|e| MetaprogrammingError::ParseError(e.to_string()))
+        Ok(root)
     }
 
-    /// Convert node to string representation
-    fn node_to_string(&self, graph: &Graph, node_id: NodeId) -> Result<String> {
-        // Simplified - would need proper serialization
-        if let Some(node) = graph.get_node(node_id) {
-            match node {
-                Node::Variable { name } => Ok(name.clone()),
-                Node::Literal(lit) => Ok(format!("{}", lit)),
-                _ => Ok(format!("<node:{:?}>", node_id)),
+    /// Rename the bindings around a call that would capture the template's free names
+    ///
+    /// Free names refer to the macro's definition scope, so bindings in scope at the
+    /// call but not at the definition get fresh symbols, along with their references.
+    /// Macros without a definition site are defined at the top level.
+    fn unshadow(
+        &self,
+        graph: &mut Graph,
+        macro_name: &str,
+        call: NodeId,
+        free: &FxHashSet<String>,
+        context: &mut ExpansionContext,
+    ) {
+        let Some(root) = graph.root_id else {
+            return;
+        };
+        let definition_scope = self
+            .definition_sites
+            .get(macro_name)
+            .and_then(|&site| scope_at(graph, root, site, &mut Vec::new()))
+            .unwrap_or_default();
+        let call_scope = scope_at(graph, root, call, &mut Vec::new()).unwrap_or_default();
+
+        for (name, binder) in call_scope {
+            if free.contains(&name) && !definition_scope.contains(&(name.clone(), binder)) {
+                let fresh = context.gensym(&name);
+                rename_binding(graph, binder, &name, &fresh);
             }
-        } else {
-            Err(MetaprogrammingError::NodeNotFound(node_id))
         }
     }
 
@@ -267,37 +412,35 @@ impl MacroExpander {
     fn expand_cond(
         &self,
         _context: &ExpansionContext,
-        graph: &Graph,
+        graph: &mut Graph,
         args: &[NodeId],
-    ) -> Result<Graph> {
-        if args.is_empty() {
-            return parse("nil").map_err(|e| MetaprogrammingError::ParseError(e.to_string()));
-        }
-
-        // Build nested if expressions
-        let mut result = "nil".to_string();
+    ) -> Result<NodeId> {
+        // Build nested ifs from the last clause outwards
+        let mut result = graph.add_node(Node::Literal(Literal::Nil))?;
 
-        // Process clauses in reverse order
         for &clause_id in args.iter().rev() {
             if let Some(Node::List(items)) = graph.get_node(clause_id) {
                 if items.len() >= 2 {
-                    let test = self.node_to_string(graph, items[0])?;
-                    let body = self.node_to_string(graph, items[1])?;
-                    result = format!("(if {} {} {})", test, body, result);
+                    let (test, body) = (items[0], items[1]);
+                    result = graph.add_node(Node::If {
+                        condition: test,
+                        then_branch: body,
+                        else_branch: result,
+                    })?;
                 }
             }
         }
 
-        parse(&result).map_err(|e| MetaprogrammingError::ParseError(e.to_string()))
+        Ok(result)
     }
 
     /// Expand let* macro
     fn expand_let_star(
         &self,
         _context: &ExpansionContext,
-        graph: &Graph,
+        graph: &mut Graph,
         args: &[NodeId],
-    ) -> Result<Graph> {
+    ) -> Result<NodeId> {
         if args.len() != 2 {
             return Err(MetaprogrammingError::MacroExpansionError(
                 "let* requires exactly 2 arguments".to_string(),
@@ -309,109 +452,316 @@ impl MacroExpander {
 
         // Get bindings
         let bindings = if let Some(Node::List(items)) = graph.get_node(bindings_id) {
-            items
+            items.clone()
         } else {
             return Err(MetaprogrammingError::MacroExpansionError(
                 "let* bindings must be a list".to_string(),
             ));
         };
 
-        // Build nested let expressions
-        let body = self.node_to_string(graph, body_id)?;
-        let mut result = body;
-
-        // Process bindings in reverse order
+        // Build nested let expressions, innermost binding first
+        let mut result = body_id;
         for &binding_id in bindings.iter().rev() {
             if let Some(Node::List(pair)) = graph.get_node(binding_id) {
-                if pair.len() == 2 {
-                    let var = self.node_to_string(graph, pair[0])?;
-                    let val = self.node_to_string(graph, pair[1])?;
-                    result = format!("(let (({} {})) {})", var, val, result);
+                if let [var, value] = pair[..] {
+                    if let Some(Node::Variable { name }) = graph.get_node(var) {
+                        let name = name.clone();
+                        result = graph.add_node(Node::Let {
+                            bindings: vec![(name, value)],
+                            body: result,
+                        })?;
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Replace a macro call with its expansion
+    ///
+    /// The call node takes on the expansion's root node, so every reference to the
+    /// call now refers to the expanded code. The call keeps its own source span.
+    fn replace_node(&self, graph: &mut Graph, old_id: NodeId, new_root: NodeId) -> Result<()> {
+        let node = graph
+            .get_node(new_root)
+            .cloned()
+            .ok_or(MetaprogrammingError::NodeNotFound(new_root))?;
+        let mut metadata = graph.get_metadata(new_root).cloned().unwrap_or_default();
+        metadata.span = graph.span(old_id);
+
+        graph.nodes.insert(old_id, node);
+        graph.set_metadata(old_id, metadata);
+
+        Ok(())
+    }
+}
+
+impl Default for MacroExpander {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Expand the macros defined in a graph
+///
+/// Registers the graph's `macro` definitions and expands every call to them.
+/// Graphs without macro definitions are left unchanged.
+pub fn expand_macros(graph: &mut Graph) -> Result<()> {
+    let mut expander = MacroExpander::new();
+    if expander.collect_definitions(graph) > 0 {
+        expander.expand_graph(graph)?;
+    }
+    Ok(())
+}
+
+/// Template source with the `$name` and `${name}` markers of its parameters removed
+///
+/// Parameters are plain identifiers once the template is parsed; the markers are
+/// accepted so templates can make substitutions stand out. Markers are found among
+/// the template's tokens, so a `$` inside a string literal is left alone.
+fn template_source(template: &str, params: &[String]) -> String {
+    let mut lexer = Lexer::new(template);
+    let mut tokens = Vec::new();
+    while let Some(token) = lexer.next_token() {
+        tokens.push((token, lexer.span()));
+    }
+
+    let is_param =
+        |span: &Range<usize>| params.iter().any(|param| *param == template[span.clone()]);
+    // The `$` must touch what it marks
+    let attached = |i: usize| {
+        tokens
+            .get(i + 1)
+            .filter(|(_, span)| span.start == tokens[i].1.end)
+    };
+
+    let mut markers = Vec::new();
+    for (i, (token, dollar)) in tokens.iter().enumerate() {
+        if !matches!(token, Token::Dollar) {
+            continue;
+        }
+        match attached(i) {
+            Some((_, name)) if is_param(name) => markers.push(dollar.clone()),
+            Some((Token::LBrace, open)) => {
+                if let (Some((_, name)), Some((Token::RBrace, close))) =
+                    (tokens.get(i + 2), tokens.get(i + 3))
+                {
+                    if is_param(name) {
+                        markers.push(dollar.start..open.end);
+                        markers.push(close.clone());
+                    }
                 }
             }
+            _ => {}
         }
+    }
 
-        parse(&result).map_err(|e| MetaprogrammingError::ParseError(e.to_string()))
+    let mut source = String::with_capacity(template.len());
+    let mut copied = 0;
+    for marker in markers {
+        source.push_str(&template[copied..marker.start]);
+        copied = marker.end;
     }
+    source.push_str(&template[copied..]);
+    source
+}
 
-    /// Replace a node in the graph
-    fn replace_node(&self, graph: &mut Graph, old_id: NodeId, new_graph: Graph) -> Result<()> {
-        // Merge new graph into existing graph
-        for (new_id, node) in new_graph.nodes {
-            graph.nodes.insert(new_id, node);
+/// Names bound by a node
+fn binders(node: &Node) -> Vec<String> {
+    match node {
+        Node::Let { bindings, .. } | Node::Letrec { bindings, .. } => {
+            bindings.iter().map(|(name, _)| name.clone()).collect()
+        }
+        Node::Lambda { params, .. } => params.clone(),
+        Node::Match { branches, .. } => {
+            let mut names = Vec::new();
+            for (pattern, _) in branches {
+                pattern_binders(pattern, &mut names);
+            }
+            names
         }
+        Node::For { pattern, .. } => {
+            let mut names = Vec::new();
+            pattern_binders(pattern, &mut names);
+            names
+        }
+        _ => Vec::new(),
+    }
+}
 
-        // Update references to old node
-        if let Some(new_root) = new_graph.root_id {
-            // Replace all references to old_id with new_root
-            for node in graph.nodes.values_mut() {
-                self.update_node_references(node, old_id, new_root);
+fn pattern_binders(pattern: &AstPattern, names: &mut Vec<String>) {
+    match pattern {
+        AstPattern::Variable(name) => names.push(name.clone()),
+        AstPattern::As { binding, pattern } => {
+            names.push(binding.clone());
+            pattern_binders(pattern, names);
+        }
+        AstPattern::Constructor { patterns, .. }
+        | AstPattern::Tuple(patterns)
+        | AstPattern::Or(patterns) => {
+            for pattern in patterns {
+                pattern_binders(pattern, names);
             }
+        }
+        AstPattern::Guard { pattern, .. } | AstPattern::View { pattern, .. } => {
+            pattern_binders(pattern, names);
+        }
+        AstPattern::Literal(_) | AstPattern::Wildcard | AstPattern::Range(_) => {}
+    }
+}
 
-            // Update root if necessary
-            if graph.root_id == Some(old_id) {
-                graph.root_id = Some(new_root);
+/// Children of a node, each with the names the node binds for it
+fn scoped_children(graph: &Graph, id: NodeId) -> Vec<(NodeId, Vec<String>)> {
+    match graph.get_node(id) {
+        Some(Node::Let { bindings, body }) => {
+            // Each binding is in scope for the later ones
+            let mut names = Vec::new();
+            let mut children = Vec::new();
+            for (name, value) in bindings {
+                children.push((*value, names.clone()));
+                names.push(name.clone());
+            }
+            children.push((*body, names));
+            children
+        }
+        Some(node @ Node::Letrec { bindings, body }) => {
+            let names = binders(node);
+            bindings
+                .iter()
+                .map(|(_, value)| *value)
+                .chain([*body])
+                .map(|child| (child, names.clone()))
+                .collect()
+        }
+        Some(Node::Lambda { params, body }) => vec![(*body, params.clone())],
+        Some(Node::Match { expr, branches, .. }) => {
+            let mut children = vec![(*expr, Vec::new())];
+            for (pattern, branch) in branches {
+                let mut names = Vec::new();
+                pattern_binders(pattern, &mut names);
+                children.push((*branch, names));
             }
+            children
         }
+        Some(Node::For {
+            pattern,
+            iterable,
+            body,
+        }) => {
+            let mut names = Vec::new();
+            pattern_binders(pattern, &mut names);
+            vec![(*iterable, Vec::new()), (*body, names)]
+        }
+        Some(Node::Define { value, .. }) => vec![(*value, Vec::new())],
+        _ => graph
+            .children(id)
+            .into_iter()
+            .map(|child| (child, Vec::new()))
+            .collect(),
+    }
+}
 
-        // Remove old node
-        graph.nodes.remove(&old_id);
+/// Names in scope at `target`, each with the node that binds it, outermost first
+fn scope_at(
+    graph: &Graph,
+    id: NodeId,
+    target: NodeId,
+    scope: &mut Vec<(String, NodeId)>,
+) -> Option<Vec<(String, NodeId)>> {
+    if id == target {
+        return Some(scope.clone());
+    }
+    for (child, names) in scoped_children(graph, id) {
+        let depth = scope.len();
+        scope.extend(names.into_iter().map(|name| (name, id)));
+        let found = scope_at(graph, child, target, scope);
+        scope.truncate(depth);
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
 
-        Ok(())
+/// Rename a name bound by `binder` and the references to it
+fn rename_binding(graph: &mut Graph, binder: NodeId, name: &str, fresh: &str) {
+    let renames = FxHashMap::from_iter([(name.to_string(), fresh.to_string())]);
+    let in_scope: Vec<NodeId> = scoped_children(graph, binder)
+        .into_iter()
+        .filter(|(_, names)| names.iter().any(|bound| bound == name))
+        .map(|(child, _)| child)
+        .collect();
+    if let Some(node) = graph.get_node_mut(binder) {
+        rename_node(node, &renames);
     }
+    for child in in_scope {
+        rename_references(graph, child, &renames);
+    }
+}
 
-    /// Update references in a node
-    fn update_node_references(&self, node: &mut Node, old_id: NodeId, new_id: NodeId) {
-        match node {
-            Node::Lambda { body, .. } => {
-                if *body == old_id {
-                    *body = new_id;
-                }
+/// Rename references below a node, up to bindings that shadow them
+fn rename_references(graph: &mut Graph, id: NodeId, renames: &FxHashMap<String, String>) {
+    if let Some(node @ Node::Variable { .. }) = graph.get_node_mut(id) {
+        rename_node(node, renames);
+    }
+    for (child, names) in scoped_children(graph, id) {
+        if !names.iter().any(|name| renames.contains_key(name)) {
+            rename_references(graph, child, renames);
+        }
+    }
+}
+
+/// Apply renames to the names a node binds or refers to
+fn rename_node(node: &mut Node, renames: &FxHashMap<String, String>) {
+    let rename = |name: &mut String| {
+        if let Some(new_name) = renames.get(name) {
+            *name = new_name.clone();
+        }
+    };
+    match node {
+        Node::Variable { name } | Node::Define { name, .. } => rename(name),
+        Node::Let { bindings, .. } | Node::Letrec { bindings, .. } => {
+            for (name, _) in bindings {
+                rename(name);
             }
-            Node::Application { function, args } => {
-                if *function == old_id {
-                    *function = new_id;
-                }
-                for arg in args {
-                    if *arg == old_id {
-                        *arg = new_id;
-                    }
-                }
+        }
+        Node::Lambda { params, .. } => {
+            for param in params {
+                rename(param);
             }
-            Node::Let { bindings, body } => {
-                for (_, value) in bindings {
-                    if *value == old_id {
-                        *value = new_id;
-                    }
-                }
-                if *body == old_id {
-                    *body = new_id;
-                }
+        }
+        Node::Match { branches, .. } => {
+            for (pattern, _) in branches {
+                rename_pattern(pattern, renames);
             }
-            Node::If {
-                condition,
-                then_branch,
-                else_branch,
-            } => {
-                if *condition == old_id {
-                    *condition = new_id;
-                }
-                if *then_branch == old_id {
-                    *then_branch = new_id;
-                }
-                if *else_branch == old_id {
-                    *else_branch = new_id;
-                }
+        }
+        Node::For { pattern, .. } => rename_pattern(pattern, renames),
+        _ => {}
+    }
+}
+
+fn rename_pattern(pattern: &mut AstPattern, renames: &FxHashMap<String, String>) {
+    match pattern {
+        AstPattern::Variable(name) | AstPattern::As { binding: name, .. } => {
+            if let Some(new_name) = renames.get(name) {
+                *name = new_name.clone();
             }
-            Node::List(items) => {
-                for item in items {
-                    if *item == old_id {
-                        *item = new_id;
-                    }
-                }
+        }
+        _ => {}
+    }
+    match pattern {
+        AstPattern::As { pattern, .. }
+        | AstPattern::Guard { pattern, .. }
+        | AstPattern::View { pattern, .. } => rename_pattern(pattern, renames),
+        AstPattern::Constructor { patterns, .. }
+        | AstPattern::Tuple(patterns)
+        | AstPattern::Or(patterns) => {
+            for pattern in patterns {
+                rename_pattern(pattern, renames);
             }
-            _ => {}
         }
+        _ => {}
     }
 }
 
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     
-    use crate::macros::{ExpansionContext, MacroDefinition, MacroExpander};
+    use crate::macros::{template_source, ExpansionContext, MacroDefinition, MacroExpander};
     use fluentai_core::ast::Node;
     use fluentai_parser::parse;
 
@@ -218,6 +218,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_template_markers_outside_strings_are_stripped() {
+        let params = ["x".to_string(), "y".to_string()];
+        assert_eq!(template_source(r#""$" + $x"#, &params), r#""$" + x"#);
+        assert_eq!(
+            template_source(r#""${x} $y" + ${x} + ${ y }"#, &params),
+            r#""${x} $y" + x +  y "#
+        );
+        // Only parameters are markers, and `$(...)` is a printable value
+        assert_eq!(template_source("$z + $(x)", &params), "$z + $(x)");
+    }
+
     #[test]
     #[ignore = "Macro expansion not fully implemented"]
     fn test_expand_nested_macros() {
@@ -237,7 +249,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "Recursive macro expansion not implemented"]
     fn test_max_depth_limit() {
         let mut expander = MacroExpander::new();
         expander.max_depth = 3;
@@ -265,7 +276,6 @@ mod tests {
     }
 
     #[test]
-    #[ignore = "Hygenic macro expansion not implemented"]
     fn test_hygenic_expansion() {
         let mut expander = MacroExpander::new();
 
@@ -286,9 +296,68 @@ mod tests {
         let result = expander.expand_graph(&mut graph);
         assert!(result.is_ok());
 
-        // The 'x' in the body should be renamed to avoid capture
-        // TODO: Verify that variable renaming actually happened
-        assert!(graph.root_id.is_some());
+        // The binding takes the caller's name, so the caller's body can refer to it
+        let root = graph.root_id.unwrap();
+        match graph.get_node(root) {
+            Some(Node::Let { bindings, .. }) => assert_eq!(bindings[0].0, "x"),
+            other => panic!("expected let, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_template_bindings_are_renamed() {
+        let mut expander = MacroExpander::new();
+        expander.register_macro(MacroDefinition {
+            name: "add_tmp".to_string(),
+            params: vec!["x".to_string()],
+            pattern: None,
+            body: "let tmp = 1; tmp + $x".to_string(),
+            hygenic: true,
+        });
+
+        let mut graph = parse("add_tmp(tmp)").unwrap();
+        expander.expand_graph(&mut graph).unwrap();
+
+        let root = graph.root_id.unwrap();
+        let (binding, body) = match graph.get_node(root) {
+            Some(Node::Let { bindings, body }) => (bindings[0].0.clone(), *body),
+            other => panic!("expected let, got {:?}", other),
+        };
+        assert!(binding.starts_with("tmp#"), "binding not renamed: {}", binding);
+
+        let args = match graph.get_node(body) {
+            Some(Node::Application { args, .. }) => args.clone(),
+            other => panic!("expected application, got {:?}", other),
+        };
+        let names: Vec<_> = args
+            .iter()
+            .map(|arg| match graph.get_node(*arg) {
+                Some(Node::Variable { name }) => name.clone(),
+                other => panic!("expected variable, got {:?}", other),
+            })
+            .collect();
+        assert_eq!(names, vec![binding, "tmp".to_string()]);
+    }
+
+    #[test]
+    fn test_collect_definitions() {
+        let mut graph = parse("macro twice(x) { x + x }\ntwice(2)").unwrap();
+
+        let mut expander = MacroExpander::new();
+        assert_eq!(expander.collect_definitions(&mut graph), 1);
+        assert_eq!(expander.macros["twice"].params, vec!["x"]);
+        assert_eq!(expander.macros["twice"].body, "x + x");
+        assert!(!graph
+            .nodes
+            .values()
+            .any(|node| matches!(node, Node::MacroDef { .. })));
+
+        expander.expand_graph(&mut graph).unwrap();
+        assert!(!graph.nodes.values().any(|node| matches!(
+            node,
+            Node::Application { function, .. }
+                if matches!(graph.get_node(*function), Some(Node::Variable { name }) if name == "twice")
+        )));
     }
 
     #[test]
@@ -262,7 +262,7 @@ impl<'a> QueryExecutor<'a> {
             } => {
                 vec![*condition, *then_branch, *else_branch]
             }
-            Node::List(items) => items.clone(),
+            Node::List(items) | Node::Tuple(items) => items.clone(),
             _ => vec![],
         }
     }
@@ -1,6 +1,7 @@
 //! Module caching system
 
 use crate::{ModuleInfo, Result};
+use fluentai_types::ModuleInterface;
 use rustc_hash::FxHashMap;
 use std::sync::{Arc, RwLock};
 use tracing::{debug, trace};
@@ -14,6 +15,7 @@ pub struct ModuleCache {
 #[derive(Debug)]
 struct ModuleCacheInner {
     modules: FxHashMap<String, Arc<ModuleInfo>>,
+    interfaces: FxHashMap<String, Arc<ModuleInterface>>,
     max_size: usize,
     access_order: Vec<String>,
 }
@@ -24,6 +26,7 @@ impl ModuleCache {
         Self {
             inner: Arc::new(RwLock::new(ModuleCacheInner {
                 modules: FxHashMap::default(),
+                interfaces: FxHashMap::default(),
                 max_size,
                 access_order: Vec::new(),
             })),
@@ -61,6 +64,7 @@ impl ModuleCache {
         if cache.modules.len() >= cache.max_size && cache.max_size > 0 {
             if let Some(oldest) = cache.access_order.first().cloned() {
                 cache.modules.remove(&oldest);
+                cache.interfaces.remove(&oldest);
                 cache.access_order.remove(0);
                 debug!("Evicted module from cache: {}", oldest);
             }
@@ -78,13 +82,30 @@ impl ModuleCache {
     pub fn remove(&self, id: &str) -> Option<Arc<ModuleInfo>> {
         let mut cache = self.inner.write().unwrap();
         cache.access_order.retain(|x| x != id);
+        cache.interfaces.remove(id);
         cache.modules.remove(id)
     }
 
+    /// Get the inferred interface of a cached module
+    pub fn get_interface(&self, id: &str) -> Option<Arc<ModuleInterface>> {
+        self.inner.read().unwrap().interfaces.get(id).cloned()
+    }
+
+    /// Store the inferred interface of a cached module
+    pub fn insert_interface(&self, id: &str, interface: Arc<ModuleInterface>) {
+        let mut cache = self.inner.write().unwrap();
+        // Interfaces live only as long as their module stays cached
+        if cache.modules.contains_key(id) {
+            cache.interfaces.insert(id.to_string(), interface);
+            trace!("Cached interface for module: {}", id);
+        }
+    }
+
     /// Clear all cached modules
     pub fn clear(&self) {
         let mut cache = self.inner.write().unwrap();
         cache.modules.clear();
+        cache.interfaces.clear();
         cache.access_order.clear();
         debug!("Cleared module cache");
     }
@@ -163,8 +184,12 @@ mod tests {
         cache.insert(create_test_module("module1")).unwrap();
         cache.insert(create_test_module("module2")).unwrap();
 
+        cache.insert_interface("module1", Arc::new(ModuleInterface::new("module1")));
+        assert!(cache.get_interface("module1").is_some());
+
         cache.clear();
         assert_eq!(cache.size(), 0);
+        assert!(cache.get_interface("module1").is_none());
         assert!(!cache.contains("module1"));
         assert!(!cache.contains("module2"));
     }
@@ -47,4 +47,7 @@ pub enum ModuleError {
 
     #[error("Module cache error: {message}")]
     CacheError { message: String },
+
+    #[error("Type inference failed in module {module}: {message}")]
+    TypeCheckFailed { module: String, message: String },
 }
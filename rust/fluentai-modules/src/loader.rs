@@ -3,6 +3,7 @@
 use crate::{ModuleCache, ModuleConfig, ModuleError, ModuleInfo, Result};
 use fluentai_core::ast::{Graph, Literal, Node};
 use fluentai_parser::parse;
+use fluentai_types::{ModuleInterface, TypeError, TypeInferencer};
 use rustc_hash::FxHashSet;
 use std::fs;
 use std::path::{Component, Path, PathBuf};
@@ -13,7 +14,10 @@ use tracing::{info, trace, warn};
 pub struct ModuleLoader {
     config: ModuleConfig,
     cache: ModuleCache,
-    loading: FxHashSet<String>,
+    /// Modules being loaded, outermost first
+    loading: Vec<String>,
+    /// Modules whose interfaces are being inferred, outermost first
+    inferring: Vec<String>,
 }
 
 impl ModuleLoader {
@@ -23,7 +27,8 @@ impl ModuleLoader {
         Self {
             config,
             cache,
-            loading: FxHashSet::default(),
+            loading: Vec::new(),
+            inferring: Vec::new(),
         }
     }
 
@@ -44,27 +49,20 @@ impl ModuleLoader {
         }
 
         // Check for circular dependencies
-        if self.loading.contains(&module_id) {
+        if let Some(cycle) = Self::cycle(&self.loading, &module_id) {
             if !self.config.allow_circular {
-                return Err(ModuleError::CircularDependency {
-                    cycle: self
-                        .loading
-                        .iter()
-                        .cloned()
-                        .collect::<Vec<_>>()
-                        .join(" -> "),
-                });
+                return Err(ModuleError::CircularDependency { cycle });
             }
         }
 
         // Mark as loading
-        self.loading.insert(module_id.clone());
+        self.loading.push(module_id.clone());
 
         // Load and parse the module
         let result = self.load_module_from_path(&path);
 
-        // Remove from loading set
-        self.loading.remove(&module_id);
+        // Remove from loading stack
+        self.loading.pop();
 
         match result {
             Ok(module) => {
@@ -322,6 +320,91 @@ impl ModuleLoader {
         Ok(loaded)
     }
 
+    /// Infer the types a module exports, reusing the cached interface when present
+    pub fn load_interface(&mut self, module_ref: &str) -> Result<Arc<ModuleInterface>> {
+        let module = self.load_module(module_ref)?;
+        if let Some(interface) = self.cache.get_interface(&module.id) {
+            return Ok(interface);
+        }
+
+        let (interface, _) = self.infer_module(&module)?;
+        interface
+    }
+
+    /// Type check a module against the interfaces of the modules it imports
+    pub fn type_check_module(&mut self, module_ref: &str) -> Result<Vec<TypeError>> {
+        let module = self.load_module(module_ref)?;
+        let (_, errors) = self.infer_module(&module)?;
+        Ok(errors)
+    }
+
+    /// Infer a module with its dependencies' interfaces in scope, caching its own interface
+    ///
+    /// The interface is only available, and only cached, when the module has no type errors.
+    fn infer_module(
+        &mut self,
+        module: &ModuleInfo,
+    ) -> Result<(Result<Arc<ModuleInterface>>, Vec<TypeError>)> {
+        if let Some(cycle) = Self::cycle(&self.inferring, &module.id) {
+            return Err(ModuleError::CircularDependency { cycle });
+        }
+        self.inferring.push(module.id.clone());
+
+        let mut inferencer = TypeInferencer::new();
+        let mut result = Ok(());
+        for dependency in &module.dependencies {
+            match self.load_interface(dependency) {
+                Ok(interface) => {
+                    inferencer.add_module_interface(dependency.clone(), (*interface).clone())
+                }
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        self.inferring.pop();
+        result?;
+
+        let inferred = inferencer.infer_interface(&module.graph);
+        let errors = inferencer.errors().to_vec();
+        let interface = match inferred {
+            Ok(interface) if errors.is_empty() => {
+                let interface = Arc::new(interface);
+                if self.config.enable_cache {
+                    self.cache.insert_interface(&module.id, interface.clone());
+                }
+                Ok(interface)
+            }
+            Ok(_) => Err(Self::type_check_failed(module, &errors)),
+            Err(e) if errors.is_empty() => Err(ModuleError::TypeCheckFailed {
+                module: module.name.clone(),
+                message: e.to_string(),
+            }),
+            Err(_) => Err(Self::type_check_failed(module, &errors)),
+        };
+        Ok((interface, errors))
+    }
+
+    /// The import chain from `module_id`'s first entry on `stack` back to itself, if it is there
+    fn cycle(stack: &[String], module_id: &str) -> Option<String> {
+        let start = stack.iter().position(|id| id == module_id)?;
+        let mut cycle = stack[start..].to_vec();
+        cycle.push(module_id.to_string());
+        Some(cycle.join(" -> "))
+    }
+
+    fn type_check_failed(module: &ModuleInfo, errors: &[TypeError]) -> ModuleError {
+        ModuleError::TypeCheckFailed {
+            module: module.name.clone(),
+            message: errors
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("; "),
+        }
+    }
+
     /// Get the module cache
     pub fn cache(&self) -> &ModuleCache {
         &self.cache
@@ -275,3 +275,137 @@ fn test_nested_module_access() {
         }
     }
 }
+
+fn typed_project() -> (TempDir, ModuleLoader) {
+    let temp_dir = TempDir::new().unwrap();
+    let files = [
+        (
+            "math",
+            r#"
+mod math {
+    private function add(x: Int, y: Int) -> Int { x + y }
+    private function shout(s: String) -> String { s }
+    export { add, shout as exclaim };
+}
+"#,
+        ),
+        ("ok", "use math::{add};\nadd(1, 2)"),
+        ("bad_arg", "use math::{add};\nadd(1, \"two\")"),
+        ("missing", "use math::{subtract};\nsubtract(1, 2)"),
+        ("qualified", "use math;\nmath.exclaim(3)"),
+    ];
+    for (name, source) in files {
+        fs::write(temp_dir.path().join(format!("{}.ai", name)), source).unwrap();
+    }
+
+    let config = ModuleConfig {
+        search_paths: vec![temp_dir.path().to_path_buf()],
+        ..Default::default()
+    };
+    (temp_dir, ModuleLoader::new(config))
+}
+
+#[test]
+fn test_module_interface_is_inferred_and_cached() {
+    use std::sync::Arc;
+
+    let (_dir, mut loader) = typed_project();
+    let interface = loader.load_interface("math").unwrap();
+
+    assert_eq!(interface.name, "math");
+    assert_eq!(interface.get("add").unwrap().to_string(), "Int → Int → Int");
+    assert_eq!(
+        interface.get("exclaim").unwrap().to_string(),
+        "String → String"
+    );
+    assert!(interface.get("shout").is_none());
+
+    let again = loader.load_interface("math").unwrap();
+    assert!(Arc::ptr_eq(&interface, &again));
+}
+
+#[test]
+fn test_imports_are_checked_against_interfaces() {
+    use fluentai_types::TypeError;
+
+    let (_dir, mut loader) = typed_project();
+
+    let errors = loader.type_check_module("ok").unwrap();
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+    let errors = loader.type_check_module("bad_arg").unwrap();
+    assert!(!errors.is_empty());
+
+    let errors = loader.type_check_module("missing").unwrap();
+    assert!(errors.iter().any(|e| matches!(
+        e,
+        TypeError::UnknownExport { module, name } if module == "math" && name == "subtract"
+    )));
+
+    let errors = loader.type_check_module("qualified").unwrap();
+    assert!(!errors.is_empty());
+}
+
+#[test]
+fn test_interface_cycle_is_reported_in_import_order() {
+    let temp_dir = TempDir::new().unwrap();
+    for (name, import) in [("a", "b"), ("b", "c"), ("c", "a")] {
+        let source = format!("use {}::{{f}};\nf(1)", import);
+        fs::write(temp_dir.path().join(format!("{}.ai", name)), source).unwrap();
+    }
+    let config = ModuleConfig {
+        search_paths: vec![temp_dir.path().to_path_buf()],
+        ..Default::default()
+    };
+    let mut loader = ModuleLoader::new(config);
+
+    match loader.load_interface("a") {
+        Err(fluentai_modules::ModuleError::CircularDependency { cycle }) => {
+            let names: Vec<_> = cycle
+                .split(" -> ")
+                .map(|id| PathBuf::from(id).file_stem().unwrap().to_string_lossy().to_string())
+                .collect();
+            assert_eq!(names, ["a", "b", "c", "a"]);
+        }
+        other => panic!("Expected CircularDependency error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_dependency_type_errors_are_propagated() {
+    let temp_dir = TempDir::new().unwrap();
+    let files = [
+        (
+            "broken",
+            r#"
+mod broken {
+    private function add(x: Int, y: Int) -> Int { x + "y" }
+    export { add };
+}
+"#,
+        ),
+        ("user", "use broken::{add};\nadd(1, 2)"),
+    ];
+    for (name, source) in files {
+        fs::write(temp_dir.path().join(format!("{}.ai", name)), source).unwrap();
+    }
+    let config = ModuleConfig {
+        search_paths: vec![temp_dir.path().to_path_buf()],
+        ..Default::default()
+    };
+    let mut loader = ModuleLoader::new(config);
+
+    for _ in 0..2 {
+        match loader.load_interface("broken") {
+            Err(fluentai_modules::ModuleError::TypeCheckFailed { module, message }) => {
+                assert_eq!(module, "broken");
+                assert!(!message.is_empty());
+            }
+            other => panic!("Expected TypeCheckFailed error, got {:?}", other),
+        }
+    }
+    assert!(matches!(
+        loader.type_check_module("user"),
+        Err(fluentai_modules::ModuleError::TypeCheckFailed { .. })
+    ));
+}
@@ -255,12 +255,32 @@ impl AdvancedOptimizer {
                                 stack.push(WorkItem::Process(*value_id));
                             }
                         }
-                        Node::List(elements) => {
+                        Node::While { condition, body } => {
+                            stack.push(WorkItem::Process(*condition));
+                            stack.push(WorkItem::Process(*body));
+                        }
+                        Node::For { iterable, body, .. } => {
+                            stack.push(WorkItem::Process(*iterable));
+                            stack.push(WorkItem::Process(*body));
+                        }
+                        Node::Range { start, end, .. } => {
+                            stack.push(WorkItem::Process(*start));
+                            stack.push(WorkItem::Process(*end));
+                        }
+                        Node::Break { value } => {
+                            if let Some(value) = value {
+                                stack.push(WorkItem::Process(*value));
+                            }
+                        }
+                        Node::Continue => {
+                            // No children to process
+                        }
+                        Node::List(elements) | Node::Tuple(elements) => {
                             for elem in elements {
                                 stack.push(WorkItem::Process(*elem));
                             }
                         }
-                        Node::Match { expr, branches } => {
+                        Node::Match { expr, branches, .. } => {
                             stack.push(WorkItem::Process(*expr));
                             for (_, body) in branches {
                                 stack.push(WorkItem::Process(*body));
@@ -389,6 +409,11 @@ impl AdvancedOptimizer {
                                 stack.push(WorkItem::Process(*promise));
                             }
                         }
+                        Node::Parallel { branches } => {
+                            for branch in branches.iter().rev() {
+                                stack.push(WorkItem::Process(*branch));
+                            }
+                        }
                         Node::Timeout { duration, promise, default } => {
                             stack.push(WorkItem::Process(*duration));
                             stack.push(WorkItem::Process(*promise));
@@ -401,6 +426,37 @@ impl AdvancedOptimizer {
                             stack.push(WorkItem::Process(*target));
                             stack.push(WorkItem::Process(*value));
                         }
+                        Node::Struct { .. } => {
+                            // Leaf node - no children to process
+                        }
+                        Node::StructConstruct { fields, .. } => {
+                            for (_, value) in fields.iter().rev() {
+                                stack.push(WorkItem::Process(*value));
+                            }
+                        }
+                        Node::FieldAccess { object, .. } => {
+                            stack.push(WorkItem::Process(*object));
+                        }
+                        Node::StructUpdate { base, fields, .. } => {
+                            for (_, value) in fields.iter().rev() {
+                                stack.push(WorkItem::Process(*value));
+                            }
+                            stack.push(WorkItem::Process(*base));
+                        }
+                        Node::Enum { .. } | Node::Trait { .. } | Node::EffectDef { .. } | Node::Extern { .. }
+                        | Node::MacroDef { .. } | Node::Error { .. } => {
+                            // Leaf nodes - no children to process
+                        }
+                        Node::TraitImpl { methods, .. } => {
+                            for (_, method) in methods.iter().rev() {
+                                stack.push(WorkItem::Process(*method));
+                            }
+                        }
+                        Node::MethodCall { args, .. } => {
+                            for arg in args.iter().rev() {
+                                stack.push(WorkItem::Process(*arg));
+                            }
+                        }
                     }
                 }
                 WorkItem::Complete(node_id, placeholder_id) => {
@@ -839,6 +895,15 @@ impl AdvancedOptimizer {
                 }
                 Node::List(opt_items)
             }
+            Node::Tuple(items) => {
+                let mut opt_items = Vec::new();
+                for item in items {
+                    if let Some(opt_item) = self.optimize_node(item)? {
+                        opt_items.push(opt_item);
+                    }
+                }
+                Node::Tuple(opt_items)
+            }
             Node::Letrec { bindings, body } => {
                 let mut opt_bindings = Vec::new();
                 for (name, value_id) in bindings {
@@ -856,7 +921,63 @@ impl AdvancedOptimizer {
                     return Ok(None);
                 }
             }
-            Node::Match { expr, branches } => {
+            Node::While { condition, body } => {
+                if let (Some(opt_cond), Some(opt_body)) =
+                    (self.optimize_node(condition)?, self.optimize_node(body)?)
+                {
+                    Node::While {
+                        condition: opt_cond,
+                        body: opt_body,
+                    }
+                } else {
+                    return Ok(None);
+                }
+            }
+            Node::For {
+                pattern,
+                iterable,
+                body,
+            } => {
+                if let (Some(opt_iterable), Some(opt_body)) =
+                    (self.optimize_node(iterable)?, self.optimize_node(body)?)
+                {
+                    Node::For {
+                        pattern,
+                        iterable: opt_iterable,
+                        body: opt_body,
+                    }
+                } else {
+                    return Ok(None);
+                }
+            }
+            Node::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                if let (Some(opt_start), Some(opt_end)) =
+                    (self.optimize_node(start)?, self.optimize_node(end)?)
+                {
+                    Node::Range {
+                        start: opt_start,
+                        end: opt_end,
+                        inclusive,
+                    }
+                } else {
+                    return Ok(None);
+                }
+            }
+            Node::Break { value } => {
+                let opt_value = match value {
+                    Some(value) => match self.optimize_node(value)? {
+                        Some(opt_value) => Some(opt_value),
+                        None => return Ok(None),
+                    },
+                    None => None,
+                };
+                Node::Break { value: opt_value }
+            }
+            Node::Match { expr, branches, must_match } => {
                 if let Some(opt_expr) = self.optimize_node(expr)? {
                     let mut opt_branches = Vec::new();
                     for (pattern, branch_body) in branches {
@@ -867,6 +988,7 @@ impl AdvancedOptimizer {
                     Node::Match {
                         expr: opt_expr,
                         branches: opt_branches,
+                        must_match,
                     }
                 } else {
                     return Ok(None);
@@ -908,6 +1030,15 @@ impl AdvancedOptimizer {
                     return Ok(None);
                 }
             }
+            Node::Parallel { branches } => {
+                let mut opt_branches = Vec::new();
+                for branch in branches {
+                    if let Some(opt_branch) = self.optimize_node(branch)? {
+                        opt_branches.push(opt_branch);
+                    }
+                }
+                Node::Parallel { branches: opt_branches }
+            }
             Node::Send { channel, value } => {
                 if let (Some(opt_channel), Some(opt_value)) =
                     (self.optimize_node(channel)?, self.optimize_node(value)?)
@@ -1244,6 +1375,75 @@ impl AdvancedOptimizer {
                     return Ok(None);
                 }
             }
+            Node::StructConstruct { name, fields } => {
+                let mut opt_fields = Vec::new();
+                for (field, value) in fields {
+                    match self.optimize_node(value)? {
+                        Some(opt_value) => opt_fields.push((field, opt_value)),
+                        None => return Ok(None),
+                    }
+                }
+                Node::StructConstruct {
+                    name,
+                    fields: opt_fields,
+                }
+            }
+            Node::FieldAccess { object, field } => match self.optimize_node(object)? {
+                Some(opt_object) => Node::FieldAccess {
+                    object: opt_object,
+                    field,
+                },
+                None => return Ok(None),
+            },
+            Node::StructUpdate { name, base, fields } => {
+                let opt_base = match self.optimize_node(base)? {
+                    Some(opt_base) => opt_base,
+                    None => return Ok(None),
+                };
+                let mut opt_fields = Vec::new();
+                for (field, value) in fields {
+                    match self.optimize_node(value)? {
+                        Some(opt_value) => opt_fields.push((field, opt_value)),
+                        None => return Ok(None),
+                    }
+                }
+                Node::StructUpdate {
+                    name,
+                    base: opt_base,
+                    fields: opt_fields,
+                }
+            }
+            Node::TraitImpl {
+                type_name,
+                trait_name,
+                methods,
+            } => {
+                let mut opt_methods = Vec::new();
+                for (name, method) in methods {
+                    match self.optimize_node(method)? {
+                        Some(opt_method) => opt_methods.push((name, opt_method)),
+                        None => return Ok(None),
+                    }
+                }
+                Node::TraitImpl {
+                    type_name,
+                    trait_name,
+                    methods: opt_methods,
+                }
+            }
+            Node::MethodCall { method, args } => {
+                let mut opt_args = Vec::new();
+                for arg in args {
+                    match self.optimize_node(arg)? {
+                        Some(opt_arg) => opt_args.push(opt_arg),
+                        None => return Ok(None),
+                    }
+                }
+                Node::MethodCall {
+                    method,
+                    args: opt_args,
+                }
+            }
             _ => node.clone(),
         };
 
@@ -1313,15 +1513,30 @@ impl AdvancedOptimizer {
                         queue.push(*then_branch);
                         queue.push(*else_branch);
                     }
-                    Node::List(items) => {
+                    Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                         queue.extend(items);
                     }
-                    Node::Match { expr, branches } => {
+                    Node::Match { expr, branches, .. } => {
                         queue.push(*expr);
                         for (_, branch) in branches {
                             queue.push(*branch);
                         }
                     }
+                    Node::While { condition, body } => {
+                        queue.push(*condition);
+                        queue.push(*body);
+                    }
+                    Node::For { iterable, body, .. } => {
+                        queue.push(*iterable);
+                        queue.push(*body);
+                    }
+                    Node::Range { start, end, .. } => {
+                        queue.push(*start);
+                        queue.push(*end);
+                    }
+                    Node::Break { value: Some(value) } => {
+                        queue.push(*value);
+                    }
                     Node::Handler { handlers, body } => {
                         queue.push(*body);
                         for (_, _, handler_fn) in handlers {
@@ -1497,6 +1712,30 @@ impl AdvancedOptimizer {
                     let new_node = Node::List(new_items);
                     Some(self.optimized.add_node(new_node).ok()?)
                 }
+                Node::Tuple(items) => {
+                    let mut new_items = Vec::new();
+                    for item in items {
+                        if let Some(new_item) =
+                            self.deep_copy_with_substitution(item, substitutions)
+                        {
+                            new_items.push(new_item);
+                        }
+                    }
+                    let new_node = Node::Tuple(new_items);
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
+                Node::Parallel { branches } => {
+                    let mut new_branches = Vec::new();
+                    for branch in branches {
+                        if let Some(new_branch) =
+                            self.deep_copy_with_substitution(branch, substitutions)
+                        {
+                            new_branches.push(new_branch);
+                        }
+                    }
+                    let new_node = Node::Parallel { branches: new_branches };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
                 Node::Letrec { bindings, body } => {
                     // For letrec, all bindings are in scope for all values
                     let mut new_substitutions = substitutions.clone();
@@ -1520,6 +1759,55 @@ impl AdvancedOptimizer {
                     };
                     Some(self.optimized.add_node(new_node).ok()?)
                 }
+                Node::While { condition, body } => {
+                    let new_condition = self.deep_copy_with_substitution(condition, substitutions)?;
+                    let new_body = self.deep_copy_with_substitution(body, substitutions)?;
+                    let new_node = Node::While {
+                        condition: new_condition,
+                        body: new_body,
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
+                Node::For {
+                    pattern,
+                    iterable,
+                    body,
+                } => {
+                    let new_iterable = self.deep_copy_with_substitution(iterable, substitutions)?;
+                    // Names bound by the pattern shadow substitutions in the body
+                    let mut new_substitutions = substitutions.clone();
+                    for name in pattern.bound_variables() {
+                        new_substitutions.remove(&name);
+                    }
+                    let new_body = self.deep_copy_with_substitution(body, &new_substitutions)?;
+                    let new_node = Node::For {
+                        pattern,
+                        iterable: new_iterable,
+                        body: new_body,
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
+                Node::Range {
+                    start,
+                    end,
+                    inclusive,
+                } => {
+                    let new_start = self.deep_copy_with_substitution(start, substitutions)?;
+                    let new_end = self.deep_copy_with_substitution(end, substitutions)?;
+                    let new_node = Node::Range {
+                        start: new_start,
+                        end: new_end,
+                        inclusive,
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
+                Node::Break { value: Some(value) } => {
+                    let new_value = self.deep_copy_with_substitution(value, substitutions)?;
+                    let new_node = Node::Break {
+                        value: Some(new_value),
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
                 Node::Handler { handlers, body } => {
                     let mut new_handlers = Vec::new();
                     for (effect_type, op_filter, handler_fn) in handlers {
@@ -1537,6 +1825,83 @@ impl AdvancedOptimizer {
                     };
                     Some(self.optimized.add_node(new_node).ok()?)
                 }
+                Node::QualifiedVariable {
+                    ref module_name,
+                    ref variable_name,
+                } => {
+                    if let Some(subst_id) = substitutions.get(module_name) {
+                        // `p.x` where `p` is substituted becomes a field access
+                        let new_node = Node::FieldAccess {
+                            object: *subst_id,
+                            field: variable_name.clone(),
+                        };
+                        Some(self.optimized.add_node(new_node).ok()?)
+                    } else {
+                        Some(self.optimized.add_node(node.clone()).ok()?)
+                    }
+                }
+                Node::StructConstruct { name, fields } => {
+                    let mut new_fields = Vec::new();
+                    for (field, value_id) in fields {
+                        let new_value = self.deep_copy_with_substitution(value_id, substitutions)?;
+                        new_fields.push((field, new_value));
+                    }
+                    let new_node = Node::StructConstruct {
+                        name,
+                        fields: new_fields,
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
+                Node::FieldAccess { object, field } => {
+                    let new_object = self.deep_copy_with_substitution(object, substitutions)?;
+                    let new_node = Node::FieldAccess {
+                        object: new_object,
+                        field,
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
+                Node::StructUpdate { name, base, fields } => {
+                    let new_base = self.deep_copy_with_substitution(base, substitutions)?;
+                    let mut new_fields = Vec::new();
+                    for (field, value_id) in fields {
+                        let new_value = self.deep_copy_with_substitution(value_id, substitutions)?;
+                        new_fields.push((field, new_value));
+                    }
+                    let new_node = Node::StructUpdate {
+                        name,
+                        base: new_base,
+                        fields: new_fields,
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
+                Node::TraitImpl {
+                    type_name,
+                    trait_name,
+                    methods,
+                } => {
+                    let mut new_methods = Vec::new();
+                    for (name, method_id) in methods {
+                        let new_method = self.deep_copy_with_substitution(method_id, substitutions)?;
+                        new_methods.push((name, new_method));
+                    }
+                    let new_node = Node::TraitImpl {
+                        type_name,
+                        trait_name,
+                        methods: new_methods,
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
+                Node::MethodCall { method, args } => {
+                    let mut new_args = Vec::new();
+                    for arg_id in args {
+                        new_args.push(self.deep_copy_with_substitution(arg_id, substitutions)?);
+                    }
+                    let new_node = Node::MethodCall {
+                        method,
+                        args: new_args,
+                    };
+                    Some(self.optimized.add_node(new_node).ok()?)
+                }
                 _ => {
                     // For other node types (Literal, Effect, etc.), create a simple copy
                     Some(self.optimized.add_node(node).ok()?)
@@ -1938,8 +2303,8 @@ impl AdvancedOptimizer {
                                                 }
                                             }
                                         }
-                                        // Spawn and Await nodes have implicit effects
-                                        Node::Spawn { .. } | Node::Await { .. } => {
+                                        // Spawn, Parallel and Await nodes have implicit effects
+                                        Node::Spawn { .. } | Node::Parallel { .. } | Node::Await { .. } => {
                                             has_effect = true;
                                         }
                                         _ => {}
@@ -1986,15 +2351,30 @@ impl AdvancedOptimizer {
                         stack.push(*then_branch);
                         stack.push(*else_branch);
                     }
-                    Node::List(items) => {
+                    Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                         stack.extend(items);
                     }
-                    Node::Match { expr, branches } => {
+                    Node::Match { expr, branches, .. } => {
                         stack.push(*expr);
                         for (_, branch) in branches {
                             stack.push(*branch);
                         }
                     }
+                    Node::While { condition, body } => {
+                        stack.push(*condition);
+                        stack.push(*body);
+                    }
+                    Node::For { iterable, body, .. } => {
+                        stack.push(*iterable);
+                        stack.push(*body);
+                    }
+                    Node::Range { start, end, .. } => {
+                        stack.push(*start);
+                        stack.push(*end);
+                    }
+                    Node::Break { value: Some(value) } => {
+                        stack.push(*value);
+                    }
                     Node::Module { body, .. } => {
                         stack.push(*body);
                     }
@@ -2050,6 +2430,30 @@ impl AdvancedOptimizer {
                         stack.push(*target);
                         stack.push(*value);
                     }
+                    Node::StructConstruct { fields, .. } => {
+                        for (_, value_id) in fields {
+                            stack.push(*value_id);
+                        }
+                    }
+                    Node::FieldAccess { object, .. } => {
+                        stack.push(*object);
+                    }
+                    Node::StructUpdate { base, fields, .. } => {
+                        stack.push(*base);
+                        for (_, value_id) in fields {
+                            stack.push(*value_id);
+                        }
+                    }
+                    Node::TraitImpl { methods, .. } => {
+                        for (_, method_id) in methods {
+                            stack.push(*method_id);
+                        }
+                    }
+                    Node::MethodCall { args, .. } => {
+                        for arg_id in args {
+                            stack.push(*arg_id);
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -2111,15 +2515,30 @@ impl AdvancedOptimizer {
                         work_stack.push(*then_branch);
                         work_stack.push(*else_branch);
                     }
-                    Node::List(items) => {
+                    Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                         work_stack.extend(items);
                     }
-                    Node::Match { expr, branches } => {
+                    Node::Match { expr, branches, .. } => {
                         work_stack.push(*expr);
                         for (_, branch_body) in branches {
                             work_stack.push(*branch_body);
                         }
                     }
+                    Node::While { condition, body } => {
+                        work_stack.push(*condition);
+                        work_stack.push(*body);
+                    }
+                    Node::For { iterable, body, .. } => {
+                        work_stack.push(*iterable);
+                        work_stack.push(*body);
+                    }
+                    Node::Range { start, end, .. } => {
+                        work_stack.push(*start);
+                        work_stack.push(*end);
+                    }
+                    Node::Break { value: Some(value) } => {
+                        work_stack.push(*value);
+                    }
                     Node::Handler { handlers, body } => {
                         work_stack.push(*body);
                         for (_, _, handler_fn) in handlers {
@@ -2131,6 +2550,35 @@ impl AdvancedOptimizer {
                         work_stack.push(*target);
                         work_stack.push(*value);
                     }
+                    Node::QualifiedVariable { module_name, .. } => {
+                        // The receiver may be a local record (`point.x`)
+                        used_vars.insert(module_name.clone());
+                    }
+                    Node::StructConstruct { fields, .. } => {
+                        for (_, value_id) in fields {
+                            work_stack.push(*value_id);
+                        }
+                    }
+                    Node::FieldAccess { object, .. } => {
+                        work_stack.push(*object);
+                    }
+                    Node::StructUpdate { base, fields, .. } => {
+                        work_stack.push(*base);
+                        for (_, value_id) in fields {
+                            work_stack.push(*value_id);
+                        }
+                    }
+                    Node::TraitImpl { methods, .. } => {
+                        for (_, method_id) in methods {
+                            work_stack.push(*method_id);
+                        }
+                    }
+                    Node::MethodCall { method, args } => {
+                        used_vars.insert(method.clone());
+                        for arg_id in args {
+                            work_stack.push(*arg_id);
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -2182,11 +2630,12 @@ fn evaluate_primitive(func_name: &str, args: &[Literal]) -> Result<Option<Litera
 
     let result = match (func_name, args) {
         // Arithmetic
-        ("+", [Integer(a), Integer(b)]) => Integer(a + b),
-        ("-", [Integer(a), Integer(b)]) => Integer(a - b),
-        ("*", [Integer(a), Integer(b)]) => Integer(a * b),
-        ("/", [Integer(a), Integer(b)]) if *b != 0 => Integer(a / b),
-        ("mod", [Integer(a), Integer(b)]) if *b != 0 => Integer(a % b),
+        // Overflowing results are left to the runtime, which promotes them to big integers
+        ("+", [Integer(a), Integer(b)]) if a.checked_add(*b).is_some() => Integer(a + b),
+        ("-", [Integer(a), Integer(b)]) if a.checked_sub(*b).is_some() => Integer(a - b),
+        ("*", [Integer(a), Integer(b)]) if a.checked_mul(*b).is_some() => Integer(a * b),
+        ("/", [Integer(a), Integer(b)]) if a.checked_div(*b).is_some() => Integer(a / b),
+        ("mod", [Integer(a), Integer(b)]) if a.checked_rem(*b).is_some() => Integer(a % b),
 
         // Floating point
         ("+", [Float(a), Float(b)]) => Float(a + b),
@@ -88,14 +88,22 @@ impl ControlFlowGraph {
                     Node::Lambda { body, .. } => {
                         work_stack.push((*body, Some(current_id)));
                     }
-                    Node::Match { expr, branches } => {
+                    Node::While { condition, body } => {
+                        work_stack.push((*body, Some(current_id)));
+                        work_stack.push((*condition, Some(current_id)));
+                    }
+                    Node::For { iterable, body, .. } => {
+                        work_stack.push((*body, Some(current_id)));
+                        work_stack.push((*iterable, Some(current_id)));
+                    }
+                    Node::Match { expr, branches, .. } => {
                         // Add branches in reverse order, then expression
                         for (_, branch_body) in branches.iter().rev() {
                             work_stack.push((*branch_body, Some(current_id)));
                         }
                         work_stack.push((*expr, Some(current_id)));
                     }
-                    Node::List(items) => {
+                    Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                         // Add items in reverse order
                         for item in items.iter().rev() {
                             work_stack.push((*item, Some(current_id)));
@@ -434,7 +442,22 @@ impl EffectAnalysis {
                 effects.extend(analyze_child(*then_branch));
                 effects.extend(analyze_child(*else_branch));
             }
-            Node::List(items) => {
+            Node::While { condition, body } => {
+                effects.extend(analyze_child(*condition));
+                effects.extend(analyze_child(*body));
+            }
+            Node::For { iterable, body, .. } => {
+                effects.extend(analyze_child(*iterable));
+                effects.extend(analyze_child(*body));
+            }
+            Node::Range { start, end, .. } => {
+                effects.extend(analyze_child(*start));
+                effects.extend(analyze_child(*end));
+            }
+            Node::Break { value: Some(value) } => {
+                effects.extend(analyze_child(*value));
+            }
+            Node::List(items) | Node::Tuple(items) => {
                 // Lists have no effects, but analyze contained items
                 for item in items {
                     effects.extend(analyze_child(*item));
@@ -493,6 +516,12 @@ impl EffectAnalysis {
                 effects.insert(EffectType::Concurrent);
                 effects.extend(analyze_child(*expr));
             }
+            Node::Parallel { branches } => {
+                effects.insert(EffectType::Concurrent);
+                for branch in branches {
+                    effects.extend(analyze_child(*branch));
+                }
+            }
             Node::Async { body } => {
                 effects.insert(EffectType::Async);
                 effects.extend(analyze_child(*body));
@@ -501,6 +530,30 @@ impl EffectAnalysis {
                 effects.insert(EffectType::Async);
                 effects.extend(analyze_child(*expr));
             }
+            Node::StructConstruct { fields, .. } => {
+                for (_, value) in fields {
+                    effects.extend(analyze_child(*value));
+                }
+            }
+            Node::FieldAccess { object, .. } => {
+                effects.extend(analyze_child(*object));
+            }
+            Node::StructUpdate { base, fields, .. } => {
+                effects.extend(analyze_child(*base));
+                for (_, value) in fields {
+                    effects.extend(analyze_child(*value));
+                }
+            }
+            Node::TraitImpl { methods, .. } => {
+                for (_, method) in methods {
+                    effects.extend(analyze_child(*method));
+                }
+            }
+            Node::MethodCall { args, .. } => {
+                for arg in args {
+                    effects.extend(analyze_child(*arg));
+                }
+            }
             _ => {
                 // Default to no effects for other nodes
             }
@@ -731,13 +784,21 @@ fn calculate_node_size_helper(
                 calculate_node_size_helper(graph, *then_branch, size, visited);
                 calculate_node_size_helper(graph, *else_branch, size, visited);
             }
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 calculate_node_size_helper(graph, *expr, size, visited);
                 for (_, branch) in branches {
                     calculate_node_size_helper(graph, *branch, size, visited);
                 }
             }
-            Node::List(items) => {
+            Node::While { condition, body } => {
+                calculate_node_size_helper(graph, *condition, size, visited);
+                calculate_node_size_helper(graph, *body, size, visited);
+            }
+            Node::For { iterable, body, .. } => {
+                calculate_node_size_helper(graph, *iterable, size, visited);
+                calculate_node_size_helper(graph, *body, size, visited);
+            }
+            Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                 // Count nodes within the list
                 for item in items {
                     calculate_node_size_helper(graph, *item, size, visited);
@@ -804,7 +865,7 @@ fn contains_reference_to(
                     || contains_reference_to(graph, *then_branch, target_id, visited)
                     || contains_reference_to(graph, *else_branch, target_id, visited);
             }
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 if contains_reference_to(graph, *expr, target_id, visited) {
                     return true;
                 }
@@ -814,7 +875,15 @@ fn contains_reference_to(
                     }
                 }
             }
-            Node::List(items) => {
+            Node::While { condition, body } => {
+                return contains_reference_to(graph, *condition, target_id, visited)
+                    || contains_reference_to(graph, *body, target_id, visited);
+            }
+            Node::For { iterable, body, .. } => {
+                return contains_reference_to(graph, *iterable, target_id, visited)
+                    || contains_reference_to(graph, *body, target_id, visited);
+            }
+            Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                 // Check for references within the list
                 for item in items {
                     if contains_reference_to(graph, *item, target_id, visited) {
@@ -903,7 +972,9 @@ impl TypeAnalysis {
                     Boolean(_) => ConcreteType::Boolean,
                     String(_) => ConcreteType::String,
                     Symbol(_) => ConcreteType::String, // Treat symbols like strings for type analysis
-                    Nil => return Some(TypeInfo::Unknown),
+                    // Not fixed-width, so never eligible for int/float specialization
+                    BigInt(_) | Decimal(_) => return Some(TypeInfo::Unknown),
+                    Bytes(_) | Nil => return Some(TypeInfo::Unknown),
                 };
                 Some(TypeInfo::Concrete(concrete_type))
             }
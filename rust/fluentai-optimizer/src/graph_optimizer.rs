@@ -293,6 +293,13 @@ impl GraphOptimizer {
                         }
                     }
                 }
+                Node::MethodCall { args, .. } => {
+                    for arg in args {
+                        if self.check_for_effects(graph, *arg, visited) {
+                            return true;
+                        }
+                    }
+                }
                 Node::Lambda { body, .. } => {
                     return self.check_for_effects(graph, *body, visited);
                 }
@@ -313,7 +320,7 @@ impl GraphOptimizer {
                         || self.check_for_effects(graph, *then_branch, visited)
                         || self.check_for_effects(graph, *else_branch, visited);
                 }
-                Node::List(items) => {
+                Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                     for item in items {
                         if self.check_for_effects(graph, *item, visited) {
                             return true;
@@ -405,12 +412,12 @@ impl GraphOptimizer {
                     self.collect_used_variables(graph, *then_branch, used, visited);
                     self.collect_used_variables(graph, *else_branch, used, visited);
                 }
-                Node::List(items) => {
+                Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                     for item in items {
                         self.collect_used_variables(graph, *item, used, visited);
                     }
                 }
-                Node::Match { expr, branches } => {
+                Node::Match { expr, branches, .. } => {
                     self.collect_used_variables(graph, *expr, used, visited);
                     for (_, branch_body) in branches {
                         self.collect_used_variables(graph, *branch_body, used, visited);
@@ -463,6 +470,55 @@ impl GraphOptimizer {
                         self.collect_used_variables(graph, *cap_id, used, visited);
                     }
                 }
+                Node::QualifiedVariable { module_name, .. } => {
+                    // The receiver may be a local record (`point.x`)
+                    used.insert(module_name.clone());
+                }
+                Node::StructConstruct { fields, .. } => {
+                    for (_, value) in fields {
+                        self.collect_used_variables(graph, *value, used, visited);
+                    }
+                }
+                Node::FieldAccess { object, .. } => {
+                    self.collect_used_variables(graph, *object, used, visited);
+                }
+                Node::StructUpdate { base, fields, .. } => {
+                    self.collect_used_variables(graph, *base, used, visited);
+                    for (_, value) in fields {
+                        self.collect_used_variables(graph, *value, used, visited);
+                    }
+                }
+                Node::TraitImpl { methods, .. } => {
+                    for (_, method) in methods {
+                        self.collect_used_variables(graph, *method, used, visited);
+                    }
+                }
+                Node::MethodCall { method, args } => {
+                    // A call with no impl for its receiver falls back to a function of the same name
+                    used.insert(method.clone());
+                    for arg in args {
+                        self.collect_used_variables(graph, *arg, used, visited);
+                    }
+                }
+                Node::While { condition, body } => {
+                    self.collect_used_variables(graph, *condition, used, visited);
+                    self.collect_used_variables(graph, *body, used, visited);
+                }
+                Node::For { iterable, body, .. } => {
+                    self.collect_used_variables(graph, *iterable, used, visited);
+                    self.collect_used_variables(graph, *body, used, visited);
+                }
+                Node::Range { start, end, .. } => {
+                    self.collect_used_variables(graph, *start, used, visited);
+                    self.collect_used_variables(graph, *end, used, visited);
+                }
+                Node::Break { value: Some(value) } => {
+                    self.collect_used_variables(graph, *value, used, visited);
+                }
+                Node::Assignment { target, value } => {
+                    self.collect_used_variables(graph, *target, used, visited);
+                    self.collect_used_variables(graph, *value, used, visited);
+                }
                 _ => {}
             }
         }
@@ -522,12 +578,12 @@ impl GraphOptimizer {
                     self.mark_reachable(graph, *then_branch, reachable);
                     self.mark_reachable(graph, *else_branch, reachable);
                 }
-                Node::List(items) => {
+                Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                     for item in items {
                         self.mark_reachable(graph, *item, reachable);
                     }
                 }
-                Node::Match { expr, branches } => {
+                Node::Match { expr, branches, .. } => {
                     self.mark_reachable(graph, *expr, reachable);
                     for (_, branch_body) in branches {
                         self.mark_reachable(graph, *branch_body, reachable);
@@ -582,6 +638,49 @@ impl GraphOptimizer {
                         self.mark_reachable(graph, *cap_id, reachable);
                     }
                 }
+                Node::StructConstruct { fields, .. } => {
+                    for (_, value) in fields {
+                        self.mark_reachable(graph, *value, reachable);
+                    }
+                }
+                Node::FieldAccess { object, .. } => {
+                    self.mark_reachable(graph, *object, reachable);
+                }
+                Node::StructUpdate { base, fields, .. } => {
+                    self.mark_reachable(graph, *base, reachable);
+                    for (_, value) in fields {
+                        self.mark_reachable(graph, *value, reachable);
+                    }
+                }
+                Node::TraitImpl { methods, .. } => {
+                    for (_, method) in methods {
+                        self.mark_reachable(graph, *method, reachable);
+                    }
+                }
+                Node::MethodCall { args, .. } => {
+                    for arg in args {
+                        self.mark_reachable(graph, *arg, reachable);
+                    }
+                }
+                Node::While { condition, body } => {
+                    self.mark_reachable(graph, *condition, reachable);
+                    self.mark_reachable(graph, *body, reachable);
+                }
+                Node::For { iterable, body, .. } => {
+                    self.mark_reachable(graph, *iterable, reachable);
+                    self.mark_reachable(graph, *body, reachable);
+                }
+                Node::Range { start, end, .. } => {
+                    self.mark_reachable(graph, *start, reachable);
+                    self.mark_reachable(graph, *end, reachable);
+                }
+                Node::Break { value: Some(value) } => {
+                    self.mark_reachable(graph, *value, reachable);
+                }
+                Node::Assignment { target, value } => {
+                    self.mark_reachable(graph, *target, reachable);
+                    self.mark_reachable(graph, *value, reachable);
+                }
                 _ => {}
             }
         }
@@ -677,36 +776,38 @@ impl GraphOptimizer {
             }
         }
 
-        // Second pass: Process with CSE
-        for node_id in nodes {
-            if let Some(node) = graph.get_node(node_id) {
+        // Second pass: Map pure duplicates to their first occurrence. Keys use the
+        // original ids, so every merge is known before any node is copied.
+        for node_id in &nodes {
+            if let Some(node) = graph.get_node(*node_id) {
                 if self
                     .effect_analysis
                     .as_ref()
-                    .map_or(false, |ea| ea.pure_nodes.contains(&node_id))
+                    .map_or(false, |ea| ea.pure_nodes.contains(node_id))
                 {
                     let expr_key = self.node_to_key(node);
 
                     if let Some(existing_id) = expr_cache.get(&expr_key) {
                         // Reuse existing node - update mapping
-                        if let Some(old_mapping) = node_mapping.get(&node_id) {
+                        if let Some(old_mapping) = node_mapping.get(node_id) {
                             // Remove the placeholder node we created
                             optimized.nodes.remove(old_mapping);
                         }
-                        node_mapping.insert(node_id, *existing_id);
+                        node_mapping.insert(*node_id, *existing_id);
                         self.stats.cse_eliminated += 1;
-                    } else {
-                        // Update the node with proper references
-                        let new_node = self.copy_with_mapping(node, &node_mapping);
-                        if let Some(new_id) = node_mapping.get(&node_id) {
-                            optimized.nodes.insert(*new_id, new_node);
-                            expr_cache.insert(expr_key, *new_id);
-                        }
+                    } else if let Some(new_id) = node_mapping.get(node_id) {
+                        expr_cache.insert(expr_key, *new_id);
                     }
-                } else {
-                    // Non-pure nodes can't be eliminated
-                    let new_node = self.copy_with_mapping(node, &node_mapping);
-                    if let Some(new_id) = node_mapping.get(&node_id) {
+                }
+            }
+        }
+
+        // Third pass: Copy the remaining nodes with the final mapping
+        for node_id in nodes {
+            if let Some(node) = graph.get_node(node_id) {
+                if let Some(new_id) = node_mapping.get(&node_id) {
+                    if optimized.nodes.contains_key(new_id) {
+                        let new_node = self.copy_with_mapping(node, &node_mapping);
                         optimized.nodes.insert(*new_id, new_node);
                     }
                 }
@@ -790,12 +891,16 @@ impl GraphOptimizer {
             Node::List(items) => {
                 Node::List(items.iter().map(map_node_id).collect::<Result<Vec<_>>>()?)
             }
-            Node::Match { expr, branches } => Node::Match {
+            Node::Tuple(items) => {
+                Node::Tuple(items.iter().map(map_node_id).collect::<Result<Vec<_>>>()?)
+            }
+            Node::Match { expr, branches, must_match } => Node::Match {
                 expr: map_node_id(expr)?,
                 branches: branches
                     .iter()
                     .map(|(pattern, body)| Ok((pattern.clone(), map_node_id(body)?)))
                     .collect::<Result<Vec<_>>>()?,
+                must_match: *must_match,
             },
             Node::Effect {
                 effect_type,
@@ -815,6 +920,9 @@ impl GraphOptimizer {
             Node::Spawn { expr } => Node::Spawn {
                 expr: map_node_id(expr)?,
             },
+            Node::Parallel { branches } => Node::Parallel {
+                branches: branches.iter().map(map_node_id).collect::<Result<Vec<_>>>()?,
+            },
             Node::Send { channel, value } => Node::Send {
                 channel: map_node_id(channel)?,
                 value: map_node_id(value)?,
@@ -856,10 +964,58 @@ impl GraphOptimizer {
             Node::Channel { capacity } => Node::Channel {
                 capacity: capacity.as_ref().map(map_node_id).transpose()?,
             },
+            Node::While { condition, body } => Node::While {
+                condition: map_node_id(condition)?,
+                body: map_node_id(body)?,
+            },
+            Node::For { pattern, iterable, body } => Node::For {
+                pattern: pattern.clone(),
+                iterable: map_node_id(iterable)?,
+                body: map_node_id(body)?,
+            },
+            Node::Range { start, end, inclusive } => Node::Range {
+                start: map_node_id(start)?,
+                end: map_node_id(end)?,
+                inclusive: *inclusive,
+            },
+            Node::Break { value } => Node::Break {
+                value: value.as_ref().map(map_node_id).transpose()?,
+            },
             Node::Assignment { target, value } => Node::Assignment {
                 target: map_node_id(target)?,
                 value: map_node_id(value)?,
             },
+            Node::StructConstruct { name, fields } => Node::StructConstruct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, value)| Ok((field.clone(), map_node_id(value)?)))
+                    .collect::<Result<Vec<_>>>()?,
+            },
+            Node::FieldAccess { object, field } => Node::FieldAccess {
+                object: map_node_id(object)?,
+                field: field.clone(),
+            },
+            Node::StructUpdate { name, base, fields } => Node::StructUpdate {
+                name: name.clone(),
+                base: map_node_id(base)?,
+                fields: fields
+                    .iter()
+                    .map(|(field, value)| Ok((field.clone(), map_node_id(value)?)))
+                    .collect::<Result<Vec<_>>>()?,
+            },
+            Node::TraitImpl { type_name, trait_name, methods } => Node::TraitImpl {
+                type_name: type_name.clone(),
+                trait_name: trait_name.clone(),
+                methods: methods
+                    .iter()
+                    .map(|(method, id)| Ok((method.clone(), map_node_id(id)?)))
+                    .collect::<Result<Vec<_>>>()?,
+            },
+            Node::MethodCall { method, args } => Node::MethodCall {
+                method: method.clone(),
+                args: args.iter().map(map_node_id).collect::<Result<Vec<_>>>()?,
+            },
             _ => node.clone(),
         };
 
@@ -919,7 +1075,13 @@ impl GraphOptimizer {
                     .map(|id| mapping.get(id).copied().unwrap_or(*id))
                     .collect(),
             ),
-            Node::Match { expr, branches } => Node::Match {
+            Node::Tuple(items) => Node::Tuple(
+                items
+                    .iter()
+                    .map(|id| mapping.get(id).copied().unwrap_or(*id))
+                    .collect(),
+            ),
+            Node::Match { expr, branches, must_match } => Node::Match {
                 expr: mapping.get(expr).copied().unwrap_or(*expr),
                 branches: branches
                     .iter()
@@ -927,6 +1089,7 @@ impl GraphOptimizer {
                         (pattern.clone(), mapping.get(body).copied().unwrap_or(*body))
                     })
                     .collect(),
+                must_match: *must_match,
             },
             Node::Effect {
                 effect_type,
@@ -949,6 +1112,12 @@ impl GraphOptimizer {
             Node::Spawn { expr } => Node::Spawn {
                 expr: mapping.get(expr).copied().unwrap_or(*expr),
             },
+            Node::Parallel { branches } => Node::Parallel {
+                branches: branches
+                    .iter()
+                    .map(|id| mapping.get(id).copied().unwrap_or(*id))
+                    .collect(),
+            },
             Node::Send { channel, value } => Node::Send {
                 channel: mapping.get(channel).copied().unwrap_or(*channel),
                 value: mapping.get(value).copied().unwrap_or(*value),
@@ -993,10 +1162,67 @@ impl GraphOptimizer {
             Node::Channel { capacity } => Node::Channel {
                 capacity: capacity.map(|id| mapping.get(&id).copied().unwrap_or(id)),
             },
+            Node::While { condition, body } => Node::While {
+                condition: mapping.get(condition).copied().unwrap_or(*condition),
+                body: mapping.get(body).copied().unwrap_or(*body),
+            },
+            Node::For { pattern, iterable, body } => Node::For {
+                pattern: pattern.clone(),
+                iterable: mapping.get(iterable).copied().unwrap_or(*iterable),
+                body: mapping.get(body).copied().unwrap_or(*body),
+            },
+            Node::Range { start, end, inclusive } => Node::Range {
+                start: mapping.get(start).copied().unwrap_or(*start),
+                end: mapping.get(end).copied().unwrap_or(*end),
+                inclusive: *inclusive,
+            },
+            Node::Break { value } => Node::Break {
+                value: value.map(|id| mapping.get(&id).copied().unwrap_or(id)),
+            },
             Node::Assignment { target, value } => Node::Assignment {
                 target: mapping.get(target).copied().unwrap_or(*target),
                 value: mapping.get(value).copied().unwrap_or(*value),
             },
+            Node::StructConstruct { name, fields } => Node::StructConstruct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, value)| {
+                        (field.clone(), mapping.get(value).copied().unwrap_or(*value))
+                    })
+                    .collect(),
+            },
+            Node::FieldAccess { object, field } => Node::FieldAccess {
+                object: mapping.get(object).copied().unwrap_or(*object),
+                field: field.clone(),
+            },
+            Node::StructUpdate { name, base, fields } => Node::StructUpdate {
+                name: name.clone(),
+                base: mapping.get(base).copied().unwrap_or(*base),
+                fields: fields
+                    .iter()
+                    .map(|(field, value)| {
+                        (field.clone(), mapping.get(value).copied().unwrap_or(*value))
+                    })
+                    .collect(),
+            },
+            Node::TraitImpl { type_name, trait_name, methods } => Node::TraitImpl {
+                type_name: type_name.clone(),
+                trait_name: trait_name.clone(),
+                methods: methods
+                    .iter()
+                    .map(|(method, id)| {
+                        (method.clone(), mapping.get(id).copied().unwrap_or(*id))
+                    })
+                    .collect(),
+            },
+            Node::MethodCall { method, args } => Node::MethodCall {
+                method: method.clone(),
+                args: args
+                    .iter()
+                    .map(|id| mapping.get(id).copied().unwrap_or(*id))
+                    .collect(),
+            },
             _ => node.clone(),
         }
     }
@@ -1056,11 +1282,12 @@ fn evaluate_primitive(func_name: &str, args: &[Literal]) -> Option<Node> {
 
     let result = match (func_name, args) {
         // Arithmetic
-        ("+", [Integer(a), Integer(b)]) => Integer(a + b),
-        ("-", [Integer(a), Integer(b)]) => Integer(a - b),
-        ("*", [Integer(a), Integer(b)]) => Integer(a * b),
-        ("/", [Integer(a), Integer(b)]) if *b != 0 => Integer(a / b),
-        ("mod", [Integer(a), Integer(b)]) if *b != 0 => Integer(a % b),
+        // Overflowing results are left to the runtime, which promotes them to big integers
+        ("+", [Integer(a), Integer(b)]) if a.checked_add(*b).is_some() => Integer(a + b),
+        ("-", [Integer(a), Integer(b)]) if a.checked_sub(*b).is_some() => Integer(a - b),
+        ("*", [Integer(a), Integer(b)]) if a.checked_mul(*b).is_some() => Integer(a * b),
+        ("/", [Integer(a), Integer(b)]) if a.checked_div(*b).is_some() => Integer(a / b),
+        ("mod", [Integer(a), Integer(b)]) if a.checked_rem(*b).is_some() => Integer(a % b),
 
         // Floating point
         ("+", [Float(a), Float(b)]) => Float(a + b),
@@ -382,7 +382,7 @@ impl MLOptimizationHints {
             } => {
                 vec![*condition, *then_branch, *else_branch]
             }
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 let mut children = vec![*expr];
                 children.extend(branches.iter().map(|(_, b)| *b));
                 children
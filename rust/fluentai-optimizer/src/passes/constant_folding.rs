@@ -98,11 +98,12 @@ fn evaluate_builtin(name: &str, args: &[Literal]) -> Option<Node> {
 
     let result = match (name, args) {
         // Arithmetic
-        ("+", [Integer(a), Integer(b)]) => Integer(a + b),
-        ("-", [Integer(a), Integer(b)]) => Integer(a - b),
-        ("*", [Integer(a), Integer(b)]) => Integer(a * b),
-        ("/", [Integer(a), Integer(b)]) if *b != 0 => Integer(a / b),
-        ("mod", [Integer(a), Integer(b)]) if *b != 0 => Integer(a % b),
+        // Overflowing results are left to the runtime, which promotes them to big integers
+        ("+", [Integer(a), Integer(b)]) if a.checked_add(*b).is_some() => Integer(a + b),
+        ("-", [Integer(a), Integer(b)]) if a.checked_sub(*b).is_some() => Integer(a - b),
+        ("*", [Integer(a), Integer(b)]) if a.checked_mul(*b).is_some() => Integer(a * b),
+        ("/", [Integer(a), Integer(b)]) if a.checked_div(*b).is_some() => Integer(a / b),
+        ("mod", [Integer(a), Integer(b)]) if a.checked_rem(*b).is_some() => Integer(a % b),
 
         // Comparison
         ("<", [Integer(a), Integer(b)]) => Boolean(a < b),
@@ -246,6 +246,57 @@ impl ContextAwarePass {
                     .collect(),
                 body: mapping.get(body).copied().unwrap_or(*body),
             },
+            Node::While { condition, body } => Node::While {
+                condition: mapping.get(condition).copied().unwrap_or(*condition),
+                body: mapping.get(body).copied().unwrap_or(*body),
+            },
+            Node::For { pattern, iterable, body } => Node::For {
+                pattern: pattern.clone(),
+                iterable: mapping.get(iterable).copied().unwrap_or(*iterable),
+                body: mapping.get(body).copied().unwrap_or(*body),
+            },
+            Node::Range { start, end, inclusive } => Node::Range {
+                start: mapping.get(start).copied().unwrap_or(*start),
+                end: mapping.get(end).copied().unwrap_or(*end),
+                inclusive: *inclusive,
+            },
+            Node::Break { value } => Node::Break {
+                value: value.map(|id| mapping.get(&id).copied().unwrap_or(id)),
+            },
+            Node::StructConstruct { name, fields } => Node::StructConstruct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, id)| (field.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                    .collect(),
+            },
+            Node::FieldAccess { object, field } => Node::FieldAccess {
+                object: mapping.get(object).copied().unwrap_or(*object),
+                field: field.clone(),
+            },
+            Node::StructUpdate { name, base, fields } => Node::StructUpdate {
+                name: name.clone(),
+                base: mapping.get(base).copied().unwrap_or(*base),
+                fields: fields
+                    .iter()
+                    .map(|(field, id)| (field.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                    .collect(),
+            },
+            Node::TraitImpl { type_name, trait_name, methods } => Node::TraitImpl {
+                type_name: type_name.clone(),
+                trait_name: trait_name.clone(),
+                methods: methods
+                    .iter()
+                    .map(|(method, id)| (method.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                    .collect(),
+            },
+            Node::MethodCall { method, args } => Node::MethodCall {
+                method: method.clone(),
+                args: args
+                    .iter()
+                    .map(|id| mapping.get(id).copied().unwrap_or(*id))
+                    .collect(),
+            },
             _ => node.clone(), // For literals, variables, etc. that don't contain NodeIds
         }
     }
@@ -76,10 +76,22 @@ impl CommonSubexpressionEliminationPass {
                         "float".hash(hasher);
                         f.to_bits().hash(hasher);
                     }
+                    Literal::BigInt(n) => {
+                        "bigint".hash(hasher);
+                        n.hash(hasher);
+                    }
+                    Literal::Decimal(d) => {
+                        "decimal".hash(hasher);
+                        d.hash(hasher);
+                    }
                     Literal::String(s) => {
                         "string".hash(hasher);
                         s.hash(hasher);
                     }
+                    Literal::Bytes(b) => {
+                        "bytes".hash(hasher);
+                        b.hash(hasher);
+                    }
                     Literal::Symbol(s) => {
                         "symbol".hash(hasher);
                         s.hash(hasher);
@@ -114,6 +126,13 @@ impl CommonSubexpressionEliminationPass {
                     hash_node_ref(*item, hasher, visited);
                 }
             }
+            Node::Tuple(items) => {
+                "tuple".hash(hasher);
+                items.len().hash(hasher);
+                for item in items {
+                    hash_node_ref(*item, hasher, visited);
+                }
+            }
             _ => {
                 // For other node types, just use a unique identifier
                 format!("{:?}", node).hash(hasher);
@@ -224,7 +243,7 @@ impl CommonSubexpressionEliminationPass {
                 }
                 true
             }
-            (Node::List(i1), Node::List(i2)) => {
+            (Node::List(i1), Node::List(i2)) | (Node::Tuple(i1), Node::Tuple(i2)) => {
                 if i1.len() != i2.len() {
                     return false;
                 }
@@ -292,7 +311,9 @@ impl OptimizationPass for CommonSubexpressionEliminationPass {
                         node_mapping.contains_key(function)
                             && args.iter().all(|arg| node_mapping.contains_key(arg))
                     }
-                    Node::List(items) => items.iter().all(|item| node_mapping.contains_key(item)),
+                    Node::List(items) | Node::Tuple(items) | Node::MethodCall { args: items, .. } => {
+                        items.iter().all(|item| node_mapping.contains_key(item))
+                    }
                     Node::Lambda { body, .. } => node_mapping.contains_key(body),
                     Node::Let { bindings, body } | Node::Letrec { bindings, body } => {
                         bindings
@@ -435,6 +456,69 @@ fn map_node_refs(node: &Node, mapping: &FxHashMap<NodeId, NodeId>) -> Node {
                 .map(|id| mapping.get(id).copied().unwrap_or(*id))
                 .collect(),
         ),
+        Node::Tuple(items) => Node::Tuple(
+            items
+                .iter()
+                .map(|id| mapping.get(id).copied().unwrap_or(*id))
+                .collect(),
+        ),
+        Node::Parallel { branches } => Node::Parallel {
+            branches: branches
+                .iter()
+                .map(|id| mapping.get(id).copied().unwrap_or(*id))
+                .collect(),
+        },
+        Node::While { condition, body } => Node::While {
+            condition: mapping.get(condition).copied().unwrap_or(*condition),
+            body: mapping.get(body).copied().unwrap_or(*body),
+        },
+        Node::For { pattern, iterable, body } => Node::For {
+            pattern: pattern.clone(),
+            iterable: mapping.get(iterable).copied().unwrap_or(*iterable),
+            body: mapping.get(body).copied().unwrap_or(*body),
+        },
+        Node::Range { start, end, inclusive } => Node::Range {
+            start: mapping.get(start).copied().unwrap_or(*start),
+            end: mapping.get(end).copied().unwrap_or(*end),
+            inclusive: *inclusive,
+        },
+        Node::Break { value } => Node::Break {
+            value: value.map(|id| mapping.get(&id).copied().unwrap_or(id)),
+        },
+        Node::StructConstruct { name, fields } => Node::StructConstruct {
+            name: name.clone(),
+            fields: fields
+                .iter()
+                .map(|(field, id)| (field.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                .collect(),
+        },
+        Node::FieldAccess { object, field } => Node::FieldAccess {
+            object: mapping.get(object).copied().unwrap_or(*object),
+            field: field.clone(),
+        },
+        Node::StructUpdate { name, base, fields } => Node::StructUpdate {
+            name: name.clone(),
+            base: mapping.get(base).copied().unwrap_or(*base),
+            fields: fields
+                .iter()
+                .map(|(field, id)| (field.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                .collect(),
+        },
+        Node::TraitImpl { type_name, trait_name, methods } => Node::TraitImpl {
+            type_name: type_name.clone(),
+            trait_name: trait_name.clone(),
+            methods: methods
+                .iter()
+                .map(|(method, id)| (method.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                .collect(),
+        },
+        Node::MethodCall { method, args } => Node::MethodCall {
+            method: method.clone(),
+            args: args
+                .iter()
+                .map(|id| mapping.get(id).copied().unwrap_or(*id))
+                .collect(),
+        },
         _ => node.clone(),
     }
 }
@@ -67,12 +67,29 @@ impl DeadCodeEliminationPass {
                     self.mark_reachable(graph, *then_branch, reachable);
                     self.mark_reachable(graph, *else_branch, reachable);
                 }
-                Node::List(items) => {
+                Node::While { condition, body } => {
+                    self.mark_reachable(graph, *condition, reachable);
+                    self.mark_reachable(graph, *body, reachable);
+                }
+                Node::For { iterable, body, .. } => {
+                    self.mark_reachable(graph, *iterable, reachable);
+                    self.mark_reachable(graph, *body, reachable);
+                }
+                Node::Range { start, end, .. } => {
+                    self.mark_reachable(graph, *start, reachable);
+                    self.mark_reachable(graph, *end, reachable);
+                }
+                Node::Break { value } => {
+                    if let Some(value) = value {
+                        self.mark_reachable(graph, *value, reachable);
+                    }
+                }
+                Node::List(items) | Node::Tuple(items) => {
                     for item in items {
                         self.mark_reachable(graph, *item, reachable);
                     }
                 }
-                Node::Match { expr, branches } => {
+                Node::Match { expr, branches, .. } => {
                     self.mark_reachable(graph, *expr, reachable);
                     for (_, body) in branches {
                         self.mark_reachable(graph, *body, reachable);
@@ -178,6 +195,11 @@ impl DeadCodeEliminationPass {
                         self.mark_reachable(graph, *promise, reachable);
                     }
                 }
+                Node::Parallel { branches } => {
+                    for branch in branches {
+                        self.mark_reachable(graph, *branch, reachable);
+                    }
+                }
                 Node::Timeout { duration, promise, default } => {
                     self.mark_reachable(graph, *duration, reachable);
                     self.mark_reachable(graph, *promise, reachable);
@@ -188,8 +210,34 @@ impl DeadCodeEliminationPass {
                 Node::Contract { .. } => {
                     // Contract nodes have no child nodes to traverse
                 }
+                Node::StructConstruct { fields, .. } => {
+                    for (_, value) in fields {
+                        self.mark_reachable(graph, *value, reachable);
+                    }
+                }
+                Node::FieldAccess { object, .. } => {
+                    self.mark_reachable(graph, *object, reachable);
+                }
+                Node::StructUpdate { base, fields, .. } => {
+                    self.mark_reachable(graph, *base, reachable);
+                    for (_, value) in fields {
+                        self.mark_reachable(graph, *value, reachable);
+                    }
+                }
+                Node::TraitImpl { methods, .. } => {
+                    for (_, method) in methods {
+                        self.mark_reachable(graph, *method, reachable);
+                    }
+                }
+                Node::MethodCall { args, .. } => {
+                    for arg in args {
+                        self.mark_reachable(graph, *arg, reachable);
+                    }
+                }
                 // Leaf nodes - no children to traverse
-                Node::Literal(_) | Node::Variable { .. } => {}
+                Node::Literal(_) | Node::Variable { .. } | Node::Struct { .. }
+                | Node::Enum { .. } | Node::Trait { .. } | Node::EffectDef { .. } | Node::Extern { .. }
+                | Node::MacroDef { .. } | Node::Error { .. } | Node::Continue => {}
             }
         }
     }
@@ -244,13 +292,30 @@ impl DeadCodeEliminationPass {
                     self.collect_vars_from_node(graph, *then_branch, used);
                     self.collect_vars_from_node(graph, *else_branch, used);
                 }
-                Node::Match { expr, branches } => {
+                Node::Match { expr, branches, .. } => {
                     self.collect_vars_from_node(graph, *expr, used);
                     for (_, branch) in branches {
                         self.collect_vars_from_node(graph, *branch, used);
                     }
                 }
-                Node::List(items) => {
+                Node::While { condition, body } => {
+                    self.collect_vars_from_node(graph, *condition, used);
+                    self.collect_vars_from_node(graph, *body, used);
+                }
+                Node::For { iterable, body, .. } => {
+                    self.collect_vars_from_node(graph, *iterable, used);
+                    self.collect_vars_from_node(graph, *body, used);
+                }
+                Node::Range { start, end, .. } => {
+                    self.collect_vars_from_node(graph, *start, used);
+                    self.collect_vars_from_node(graph, *end, used);
+                }
+                Node::Break { value } => {
+                    if let Some(value) = value {
+                        self.collect_vars_from_node(graph, *value, used);
+                    }
+                }
+                Node::List(items) | Node::Tuple(items) => {
                     for item in items {
                         self.collect_vars_from_node(graph, *item, used);
                     }
@@ -352,6 +417,11 @@ impl DeadCodeEliminationPass {
                         self.collect_vars_from_node(graph, *promise, used);
                     }
                 }
+                Node::Parallel { branches } => {
+                    for branch in branches {
+                        self.collect_vars_from_node(graph, *branch, used);
+                    }
+                }
                 Node::Timeout { duration, promise, default } => {
                     self.collect_vars_from_node(graph, *duration, used);
                     self.collect_vars_from_node(graph, *promise, used);
@@ -359,12 +429,43 @@ impl DeadCodeEliminationPass {
                         self.collect_vars_from_node(graph, *default_value, used);
                     }
                 }
-                Node::QualifiedVariable { variable_name, .. } => {
+                Node::QualifiedVariable { module_name, variable_name } => {
                     // For qualified variables, we track the variable name
                     used.insert(variable_name.clone());
+                    // The receiver may be a local record (`point.x`)
+                    used.insert(module_name.clone());
+                }
+                Node::StructConstruct { fields, .. } => {
+                    for (_, value) in fields {
+                        self.collect_vars_from_node(graph, *value, used);
+                    }
+                }
+                Node::FieldAccess { object, .. } => {
+                    self.collect_vars_from_node(graph, *object, used);
+                }
+                Node::StructUpdate { base, fields, .. } => {
+                    self.collect_vars_from_node(graph, *base, used);
+                    for (_, value) in fields {
+                        self.collect_vars_from_node(graph, *value, used);
+                    }
+                }
+                Node::TraitImpl { methods, .. } => {
+                    for (_, method) in methods {
+                        self.collect_vars_from_node(graph, *method, used);
+                    }
+                }
+                Node::MethodCall { method, args } => {
+                    // A call with no impl for its receiver falls back to a function of the same name
+                    used.insert(method.clone());
+                    for arg in args {
+                        self.collect_vars_from_node(graph, *arg, used);
+                    }
                 }
                 // Leaf nodes - no variables to collect
-                Node::Literal(_) | Node::Import { .. } | Node::Export { .. } | Node::Contract { .. } => {}
+                Node::Literal(_) | Node::Import { .. } | Node::Export { .. } | Node::Contract { .. }
+                | Node::Struct { .. } | Node::Enum { .. } | Node::Trait { .. }
+                | Node::EffectDef { .. } | Node::Extern { .. } | Node::MacroDef { .. } | Node::Error { .. }
+                | Node::Continue => {}
             }
         }
     }
@@ -391,11 +492,15 @@ impl DeadCodeEliminationPass {
                 // If nodes contain NodeIds, not inline nodes
                 // This will be handled by the graph traversal in find_used_variables
             }
+            Node::While { .. } | Node::For { .. } | Node::Range { .. } | Node::Break { .. } => {
+                // Loop nodes contain NodeIds, not inline nodes
+                // This will be handled by the graph traversal in find_used_variables
+            }
             Node::Match { .. } => {
                 // Match nodes contain NodeIds, not inline nodes
                 // This will be handled by the graph traversal in find_used_variables
             }
-            Node::List(_) => {
+            Node::List(_) | Node::Tuple(_) => {
                 // List nodes contain NodeIds, not inline nodes
                 // This will be handled by the graph traversal in find_used_variables
             }
@@ -415,7 +520,7 @@ impl DeadCodeEliminationPass {
                 // Begin nodes contain NodeIds, not inline nodes
                 // This will be handled by the graph traversal in find_used_variables
             }
-            Node::Async { .. } | Node::Await { .. } | Node::Spawn { .. } => {
+            Node::Async { .. } | Node::Await { .. } | Node::Spawn { .. } | Node::Parallel { .. } => {
                 // Async nodes contain NodeIds, not inline nodes
                 // This will be handled by the graph traversal in find_used_variables
             }
@@ -463,12 +568,20 @@ impl DeadCodeEliminationPass {
                 // Timeout nodes contain NodeIds, not inline nodes
                 // This will be handled by the graph traversal in find_used_variables
             }
-            Node::QualifiedVariable { .. } => {
-                // QualifiedVariable has module and name, we should collect the name
-                // But we need to handle it properly based on the actual structure
+            Node::QualifiedVariable { module_name, .. } => {
+                // The receiver may be a local record (`point.x`)
+                used.insert(module_name.clone());
+            }
+            Node::StructConstruct { .. } | Node::FieldAccess { .. } | Node::StructUpdate { .. }
+            | Node::TraitImpl { .. } | Node::MethodCall { .. } => {
+                // Record and impl nodes contain NodeIds, not inline nodes
+                // This will be handled by the graph traversal in find_used_variables
             }
             // Leaf nodes - these don't contain variable references
-            Node::Literal(_) | Node::Import { .. } | Node::Export { .. } | Node::Contract { .. } => {
+            Node::Literal(_) | Node::Import { .. } | Node::Export { .. } | Node::Contract { .. }
+            | Node::Struct { .. } | Node::Enum { .. } | Node::Trait { .. }
+            | Node::EffectDef { .. } | Node::Extern { .. } | Node::MacroDef { .. } | Node::Error { .. }
+            | Node::Continue => {
                 // These nodes don't contain variable references we need to track
             }
         }
@@ -102,7 +102,7 @@ impl InlinePass {
                     self.collect_free_variables(graph, *then_branch, bound_vars, free_vars);
                     self.collect_free_variables(graph, *else_branch, bound_vars, free_vars);
                 }
-                Node::List(items) => {
+                Node::List(items) | Node::Tuple(items) | Node::Parallel { branches: items } => {
                     for item in items {
                         self.collect_free_variables(graph, *item, bound_vars, free_vars);
                     }
@@ -286,6 +286,195 @@ impl InlinePass {
                     value: new_value,
                 }
             }
+            Node::QualifiedVariable {
+                module_name,
+                variable_name,
+            } => {
+                // `p.x` where `p` is a substituted parameter becomes a field access
+                if let Some(&arg_id) = substitutions.get(module_name) {
+                    Node::FieldAccess {
+                        object: node_mapping.get(&arg_id).copied().unwrap_or(arg_id),
+                        field: variable_name.clone(),
+                    }
+                } else {
+                    node.clone()
+                }
+            }
+            Node::While { condition, body } => {
+                let new_condition = self.copy_with_substitution(
+                    graph,
+                    *condition,
+                    substitutions,
+                    node_mapping,
+                    optimized,
+                )?;
+                let new_body = self.copy_with_substitution(
+                    graph,
+                    *body,
+                    substitutions,
+                    node_mapping,
+                    optimized,
+                )?;
+                Node::While {
+                    condition: new_condition,
+                    body: new_body,
+                }
+            }
+            Node::For {
+                pattern,
+                iterable,
+                body,
+            } => {
+                let new_iterable = self.copy_with_substitution(
+                    graph,
+                    *iterable,
+                    substitutions,
+                    node_mapping,
+                    optimized,
+                )?;
+                // Names bound by the pattern shadow substitutions in the body
+                let mut new_subs = substitutions.clone();
+                for name in pattern.bound_variables() {
+                    new_subs.remove(&name);
+                }
+                let new_body =
+                    self.copy_with_substitution(graph, *body, &new_subs, node_mapping, optimized)?;
+                Node::For {
+                    pattern: pattern.clone(),
+                    iterable: new_iterable,
+                    body: new_body,
+                }
+            }
+            Node::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let new_start = self.copy_with_substitution(
+                    graph,
+                    *start,
+                    substitutions,
+                    node_mapping,
+                    optimized,
+                )?;
+                let new_end = self.copy_with_substitution(
+                    graph,
+                    *end,
+                    substitutions,
+                    node_mapping,
+                    optimized,
+                )?;
+                Node::Range {
+                    start: new_start,
+                    end: new_end,
+                    inclusive: *inclusive,
+                }
+            }
+            Node::Break { value: Some(value) } => {
+                let new_value = self.copy_with_substitution(
+                    graph,
+                    *value,
+                    substitutions,
+                    node_mapping,
+                    optimized,
+                )?;
+                Node::Break {
+                    value: Some(new_value),
+                }
+            }
+            Node::StructConstruct { name, fields } => {
+                let mut new_fields = Vec::new();
+                for (field, value) in fields {
+                    let new_value = self.copy_with_substitution(
+                        graph,
+                        *value,
+                        substitutions,
+                        node_mapping,
+                        optimized,
+                    )?;
+                    new_fields.push((field.clone(), new_value));
+                }
+                Node::StructConstruct {
+                    name: name.clone(),
+                    fields: new_fields,
+                }
+            }
+            Node::FieldAccess { object, field } => {
+                let new_object = self.copy_with_substitution(
+                    graph,
+                    *object,
+                    substitutions,
+                    node_mapping,
+                    optimized,
+                )?;
+                Node::FieldAccess {
+                    object: new_object,
+                    field: field.clone(),
+                }
+            }
+            Node::StructUpdate { name, base, fields } => {
+                let new_base = self.copy_with_substitution(
+                    graph,
+                    *base,
+                    substitutions,
+                    node_mapping,
+                    optimized,
+                )?;
+                let mut new_fields = Vec::new();
+                for (field, value) in fields {
+                    let new_value = self.copy_with_substitution(
+                        graph,
+                        *value,
+                        substitutions,
+                        node_mapping,
+                        optimized,
+                    )?;
+                    new_fields.push((field.clone(), new_value));
+                }
+                Node::StructUpdate {
+                    name: name.clone(),
+                    base: new_base,
+                    fields: new_fields,
+                }
+            }
+            Node::TraitImpl {
+                type_name,
+                trait_name,
+                methods,
+            } => {
+                let mut new_methods = Vec::new();
+                for (method, lambda) in methods {
+                    let new_lambda = self.copy_with_substitution(
+                        graph,
+                        *lambda,
+                        substitutions,
+                        node_mapping,
+                        optimized,
+                    )?;
+                    new_methods.push((method.clone(), new_lambda));
+                }
+                Node::TraitImpl {
+                    type_name: type_name.clone(),
+                    trait_name: trait_name.clone(),
+                    methods: new_methods,
+                }
+            }
+            Node::MethodCall { method, args } => {
+                let mut new_args = Vec::new();
+                for arg in args {
+                    new_args.push(self.copy_with_substitution(
+                        graph,
+                        *arg,
+                        substitutions,
+                        node_mapping,
+                        optimized,
+                    )?);
+                }
+                Node::MethodCall {
+                    method: method.clone(),
+                    args: new_args,
+                }
+            }
             _ => node.clone(),
         };
 
@@ -458,6 +647,57 @@ impl InlinePass {
                     value: new_value,
                 }
             }
+            Node::While { condition, body } => Node::While {
+                condition: mapping.get(condition).copied().unwrap_or(*condition),
+                body: mapping.get(body).copied().unwrap_or(*body),
+            },
+            Node::For { pattern, iterable, body } => Node::For {
+                pattern: pattern.clone(),
+                iterable: mapping.get(iterable).copied().unwrap_or(*iterable),
+                body: mapping.get(body).copied().unwrap_or(*body),
+            },
+            Node::Range { start, end, inclusive } => Node::Range {
+                start: mapping.get(start).copied().unwrap_or(*start),
+                end: mapping.get(end).copied().unwrap_or(*end),
+                inclusive: *inclusive,
+            },
+            Node::Break { value } => Node::Break {
+                value: value.map(|id| mapping.get(&id).copied().unwrap_or(id)),
+            },
+            Node::StructConstruct { name, fields } => Node::StructConstruct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(field, id)| (field.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                    .collect(),
+            },
+            Node::FieldAccess { object, field } => Node::FieldAccess {
+                object: mapping.get(object).copied().unwrap_or(*object),
+                field: field.clone(),
+            },
+            Node::StructUpdate { name, base, fields } => Node::StructUpdate {
+                name: name.clone(),
+                base: mapping.get(base).copied().unwrap_or(*base),
+                fields: fields
+                    .iter()
+                    .map(|(field, id)| (field.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                    .collect(),
+            },
+            Node::TraitImpl { type_name, trait_name, methods } => Node::TraitImpl {
+                type_name: type_name.clone(),
+                trait_name: trait_name.clone(),
+                methods: methods
+                    .iter()
+                    .map(|(method, id)| (method.clone(), mapping.get(id).copied().unwrap_or(*id)))
+                    .collect(),
+            },
+            Node::MethodCall { method, args } => Node::MethodCall {
+                method: method.clone(),
+                args: args
+                    .iter()
+                    .map(|id| mapping.get(id).copied().unwrap_or(*id))
+                    .collect(),
+            },
             _ => node.clone(),
         };
 
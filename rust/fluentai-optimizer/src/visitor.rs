@@ -27,7 +27,7 @@ pub trait NodeVisitor {
                     else_branch,
                 } => self.visit_if(graph, node_id, *condition, *then_branch, *else_branch),
                 Node::List(elements) => self.visit_list(graph, node_id, elements),
-                Node::Match { expr, branches } => self.visit_match(graph, node_id, *expr, branches),
+                Node::Match { expr, branches, .. } => self.visit_match(graph, node_id, *expr, branches),
                 Node::Effect {
                     effect_type,
                     operation,
@@ -730,6 +730,7 @@ mod tests {
                     (Pattern::Literal(Literal::Integer(0)), zero_lit),
                     (Pattern::Literal(Literal::Integer(1)), one_lit),
                 ],
+                must_match: false,
             })
             .expect("Failed to add node");
         graph.root_id = Some(match_node);
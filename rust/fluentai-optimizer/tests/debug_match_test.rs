@@ -76,6 +76,7 @@ fn test_debug_match_optimization() -> Result<()> {
                 ),
                 (Pattern::Wildcard, zero),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
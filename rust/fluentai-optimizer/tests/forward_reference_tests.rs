@@ -49,6 +49,7 @@ fn test_optimizer_handles_forward_references_in_match() -> Result<()> {
                 ), // Forward reference to x_var
                 (Pattern::Wildcard, zero),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
 
@@ -115,6 +116,7 @@ fn test_optimizer_handles_complex_forward_references() -> Result<()> {
         .add_node(Node::Match {
             expr: outer_list,
             branches: vec![(Pattern::Variable("lst".to_string()), app)],
+            must_match: false,
         })
         .expect("Failed to add node");
 
@@ -339,6 +341,7 @@ fn test_all_optimization_passes_handle_forward_references() -> Result<()> {
                 },
                 var_ref,
             )],
+            must_match: false,
         })
         .expect("Failed to add node");
 
@@ -42,6 +42,7 @@ fn test_match_optimization_simple() -> Result<()> {
                 ),
                 (Pattern::Wildcard, zero),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -78,7 +79,7 @@ fn check_node_references(graph: &Graph, node_id: NodeId) -> Result<()> {
         .ok_or_else(|| anyhow::anyhow!("Invalid node ID: {:?}", node_id))?;
 
     match node {
-        Node::Match { expr, branches } => {
+        Node::Match { expr, branches, .. } => {
             // Check expr reference
             if graph.get_node(*expr).is_none() {
                 return Err(anyhow::anyhow!(
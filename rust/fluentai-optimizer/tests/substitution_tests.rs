@@ -245,7 +245,7 @@ fn verify_node_references_recursive(graph: &Graph, node_id: NodeId, node: &Node)
                 );
             }
         }
-        Node::Match { expr, branches } => {
+        Node::Match { expr, branches, .. } => {
             assert!(
                 graph.get_node(*expr).is_some(),
                 "Node {:?} has dangling expr reference {:?}",
@@ -404,7 +404,7 @@ fn collect_node_references(node: &Node, stack: &mut Vec<NodeId>) {
             stack.push(*else_branch);
         }
         Node::List(items) => stack.extend(items),
-        Node::Match { expr, branches } => {
+        Node::Match { expr, branches, .. } => {
             stack.push(*expr);
             for (_, branch) in branches {
                 stack.push(*branch);
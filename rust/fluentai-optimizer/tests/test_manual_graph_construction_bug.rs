@@ -212,7 +212,7 @@ fn verify_node_references(graph: &Graph, node: &Node) {
                 );
             }
         }
-        Node::Match { expr, branches } => {
+        Node::Match { expr, branches, .. } => {
             assert!(
                 graph.get_node(*expr).is_some(),
                 "Match expr node {:?} doesn't exist",
@@ -53,6 +53,14 @@ impl ParseError {
             ParseError::MaxDepthExceeded { .. } => ErrorKind::MaxDepthExceeded,
         }
     }
+
+    /// Byte offset in the source at which the error was found, if known
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            ParseError::UnexpectedToken { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
 }
 
 impl From<fluentai_core::error::Error> for ParseError {
@@ -39,8 +39,11 @@ fn format_token(token: &Token) -> String {
         Token::ConstIdent(name) => format!("constant identifier '{}'", name),
         Token::Integer(n) => format!("integer {}", n),
         Token::Float(f) => format!("float {}", f),
+        Token::BigInt(n) => format!("integer {}n", n),
+        Token::Decimal(d) => format!("decimal {}d", d),
         Token::String(s) => format!("string \"{}\"", s),
         Token::FString(s) => format!("f-string \"{}\"", s),
+        Token::Bytes(b) => format!("byte string {}", fluentai_core::value::format_bytes(b)),
         Token::True => "boolean 'true'".to_string(),
         Token::False => "boolean 'false'".to_string(),
         Token::LParen => "'('".to_string(),
@@ -97,6 +100,9 @@ fn extract_expected(message: &str) -> String {
         "expression".to_string()
     } else if message.contains("method") {
         "method name".to_string()
+    } else if let Some(rest) = message.strip_prefix("Expected ") {
+        // "Expected <token>, found <token>" from a failed consume
+        rest.split(", found").next().unwrap_or(rest).to_string()
     } else {
         "valid syntax".to_string()
     }
@@ -1,6 +1,8 @@
 //! Lexer for FLC (Fluent Lambda Chain) syntax using logos
 
 use logos::{Lexer as LogosLexer, Logos};
+use fluentai_core::value::{BigInt, Decimal};
+use std::str::FromStr;
 
 #[derive(Logos, Debug, PartialEq, Clone)]
 pub enum Token<'a> {
@@ -46,6 +48,10 @@ pub enum Token<'a> {
     For,
     #[token("while", priority = 10)]
     While,
+    #[token("break", priority = 10)]
+    Break,
+    #[token("continue", priority = 10)]
+    Continue,
     #[token("when", priority = 10)]
     When,
     #[token("in", priority = 10)]
@@ -212,6 +218,19 @@ pub enum Token<'a> {
     #[regex(r"-?[0-9]+\.[0-9]+([eE][+-]?[0-9]+)?", priority = 5, callback = |lex| lex.slice().parse::<f64>().ok())]
     Float(f64),
     
+    // Exact numeric literals: `123n` is a big integer, `19.99d` a decimal
+    #[regex(r"-?[0-9]+n", priority = 5, callback = |lex| {
+        let s = lex.slice();
+        s[..s.len() - 1].parse::<BigInt>().ok()
+    })]
+    BigInt(BigInt),
+    
+    #[regex(r"-?[0-9]+(\.[0-9]+)?d", priority = 5, callback = |lex| {
+        let s = lex.slice();
+        Decimal::from_str(&s[..s.len() - 1]).ok()
+    })]
+    Decimal(Decimal),
+    
     // String literals - regular strings
     #[regex(r#""([^"\\]|\\.)*""#, priority = 5, callback = |lex| {
         let s = lex.slice();
@@ -220,6 +239,13 @@ pub enum Token<'a> {
     })]
     String(String),
     
+    // Byte-string literals - b"..." with \xHH escapes
+    #[regex(r#"b"([^"\\]|\\.)*""#, priority = 6, callback = |lex| {
+        let s = lex.slice();
+        process_byte_escapes(&s[2..s.len()-1])
+    })]
+    Bytes(Vec<u8>),
+    
     // Symbol literals - start with single quote
     #[regex(r"'[a-zA-Z_][a-zA-Z0-9_-]*", priority = 5, callback = |lex| {
         let s = lex.slice();
@@ -288,6 +314,43 @@ pub fn process_string_escapes(s: &str) -> String {
     result
 }
 
+/// Process escape sequences in byte strings, returning `None` for a malformed `\x` escape
+pub fn process_byte_escapes(s: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::with_capacity(s.len());
+    let mut chars = s.chars();
+    
+    while let Some(ch) = chars.next() {
+        if ch == '\\' {
+            match chars.next() {
+                Some('n') => result.push(b'\n'),
+                Some('t') => result.push(b'\t'),
+                Some('r') => result.push(b'\r'),
+                Some('\\') => result.push(b'\\'),
+                Some('"') => result.push(b'"'),
+                Some('0') => result.push(0),
+                Some('x') => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    if hex.len() != 2 {
+                        return None;
+                    }
+                    result.push(u8::from_str_radix(&hex, 16).ok()?);
+                }
+                Some(c) => {
+                    result.push(b'\\');
+                    let mut buf = [0; 4];
+                    result.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                None => result.push(b'\\'),
+            }
+        } else {
+            let mut buf = [0; 4];
+            result.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+    
+    Some(result)
+}
+
 #[derive(Clone)]
 pub struct Lexer<'a> {
     inner: LogosLexer<'a, Token<'a>>,
@@ -302,6 +365,11 @@ impl<'a> Lexer<'a> {
         }
     }
     
+    /// The source text being tokenized
+    pub fn source(&self) -> &'a str {
+        self.inner.source()
+    }
+    
     pub fn next_token(&mut self) -> Option<Token<'a>> {
         if let Some((token, _)) = self.peeked.take() {
             Some(token)
@@ -367,6 +435,25 @@ mod tests {
         assert_eq!(lexer.next_token(), Some(Token::FString("Hello {name}")));
     }
     
+    #[test]
+    fn test_exact_numeric_literals() {
+        let mut lexer = Lexer::new("123n -5n 19.99d 100d");
+        assert_eq!(lexer.next_token(), Some(Token::BigInt(BigInt::from(123))));
+        assert_eq!(lexer.next_token(), Some(Token::BigInt(BigInt::from(-5))));
+        assert_eq!(lexer.next_token(), Some(Token::Decimal(Decimal::new(1999, 2))));
+        assert_eq!(lexer.next_token(), Some(Token::Decimal(Decimal::from(100))));
+    }
+    
+    #[test]
+    fn test_byte_string_literals() {
+        let mut lexer = Lexer::new(r#"b"GIF" b"\x00\xffa\n" b"" bytes"#);
+        assert_eq!(lexer.next_token(), Some(Token::Bytes(b"GIF".to_vec())));
+        assert_eq!(lexer.next_token(), Some(Token::Bytes(vec![0x00, 0xff, b'a', b'\n'])));
+        assert_eq!(lexer.next_token(), Some(Token::Bytes(vec![])));
+        assert_eq!(lexer.next_token(), Some(Token::LowerIdent("bytes")));
+        assert_eq!(process_byte_escapes(r"\xZZ"), None);
+    }
+    
     #[test]
     fn test_operators() {
         let mut lexer = Lexer::new(". |> + == && = :=");
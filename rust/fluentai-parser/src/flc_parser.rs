@@ -2,11 +2,30 @@
 
 use anyhow::{anyhow, Result};
 use fluentai_core::ast::{
-    EffectType, ExportItem, Graph, ImportItem, Literal, Node, NodeId, Pattern, RangePattern,
+    EffectOperation, EffectType, ExportItem, Graph, ImportItem, Literal, Node, NodeId, Pattern,
+    RangePattern, EnumVariant, StructField, TraitMethod, TypeExpr,
 };
+use fluentai_core::source::LineIndex;
+use rustc_hash::FxHashSet;
 
+use crate::error::ParseError;
+use crate::flc_error::flc_error;
 use crate::flc_lexer::{Lexer, Token};
 
+/// Names following the `effect` keyword anywhere in the source, so effects can be
+/// used before their declaration
+fn declared_effects(mut lexer: Lexer<'_>) -> FxHashSet<String> {
+    let mut names = FxHashSet::default();
+    while let Some(token) = lexer.next_token() {
+        if matches!(token, Token::Effect) {
+            if let Some(Token::UpperIdent(name)) = lexer.peek_token() {
+                names.insert(name.to_string());
+            }
+        }
+    }
+    names
+}
+
 // Helper function to build module path from components
 fn build_module_path(path_components: &[String], relative_prefix: Option<&str>) -> String {
     if let Some(prefix) = relative_prefix {
@@ -30,34 +49,107 @@ pub struct Parser<'a> {
     lexer: Lexer<'a>,
     graph: Graph,
     current: Option<Token<'a>>,
+    /// End offset of the last consumed token
     position: usize,
+    /// Start offset of the last consumed token
+    last_start: usize,
     /// Module name if declared at the top of the file
     module_name: Option<String>,
+    /// Whether an actor handler body is being parsed (`self` is the actor state)
+    in_actor_handler: bool,
+    /// Whether to recover from syntax errors instead of stopping at the first one
+    recovering: bool,
+    /// Syntax errors recovered from so far
+    errors: Vec<ParseError>,
+    /// `x.m(...)` calls and their method variable, made trait method calls once
+    /// every trait in the source is known
+    method_calls: Vec<(NodeId, NodeId)>,
+    /// Names of the effects the source declares, which are never taken for
+    /// misspelled built-in effects
+    declared_effects: FxHashSet<String>,
 }
 
 impl<'a> Parser<'a> {
     pub fn new(source: &'a str) -> Self {
         let mut lexer = Lexer::new(source);
+        let declared_effects = declared_effects(lexer.clone());
         let current = lexer.next_token();
+        let mut graph = Graph::new();
+        graph.line_index = Some(LineIndex::new(source));
         Self {
             lexer,
-            graph: Graph::new(),
+            graph,
             current,
             position: 0,
+            last_start: 0,
             module_name: None,
+            in_actor_handler: false,
+            recovering: false,
+            errors: Vec::new(),
+            method_calls: Vec::new(),
+            declared_effects,
+        }
+    }
+    
+    /// Parse the whole source, recovering from syntax errors
+    ///
+    /// Statements and definitions that fail to parse are replaced by `Error` nodes
+    /// and parsing resumes at the next `;`, `}` or `private`/`public` boundary.
+    /// Returns the partial graph together with every error encountered.
+    pub fn parse_recovering(mut self) -> (Graph, Vec<ParseError>) {
+        self.recovering = true;
+        if let Err(error) = self.parse_items() {
+            // Only node id exhaustion gets here; keep what was parsed
+            self.errors.push(ParseError::InvalidSyntax(error.to_string()));
         }
+        (self.graph, self.errors)
     }
     
     pub fn parse(mut self) -> Result<Graph> {
-        // Check for optional module declaration at the top
-        if matches!(self.current, Some(Token::Mod)) {
-            self.parse_module_declaration()?;
+        self.parse_items()?;
+        Ok(self.graph)
+    }
+    
+    /// Parse the source as the contents of a block, adding its nodes to `graph`
+    ///
+    /// The graph's root and line index are left as they were, so the returned node is
+    /// the only way to reach what was parsed. Used to instantiate macro templates.
+    pub fn parse_block_into(mut self, graph: &mut Graph) -> Result<NodeId> {
+        let line_index = graph.line_index.take();
+        self.graph = std::mem::take(graph);
+        // The template can use effects declared by the program it expands into
+        for node in self.graph.nodes.values() {
+            if let Node::EffectDef { name, .. } = node {
+                self.declared_effects.insert(name.clone());
+            }
+        }
+        let result = self.parse_block().and_then(|body| match &self.current {
+            Some(token) => Err(anyhow!("Unexpected {:?} after end of block", token)),
+            None => Ok(body),
+        });
+        self.resolve_method_calls();
+        *graph = std::mem::take(&mut self.graph);
+        graph.line_index = line_index;
+        result
+    }
+    
+    fn parse_items(&mut self) -> Result<()> {
+        // Check for optional module declaration at the top; `mod name { ... }` is a module definition
+        if matches!(self.current, Some(Token::Mod)) && !self.peek_ahead_for_module_body() {
+            let start = self.token_start();
+            if let Err(error) = self.parse_module_declaration() {
+                self.recover(error, start, false)?;
+            }
         }
         
         let mut items = vec![];
         
         while self.current.is_some() {
-            items.push(self.parse_top_level()?);
+            let start = self.token_start();
+            match self.parse_top_level() {
+                Ok(item) => items.push(item),
+                Err(error) => items.push(self.recover(error, start, false)?),
+            }
         }
         
         if items.is_empty() {
@@ -68,20 +160,109 @@ impl<'a> Parser<'a> {
             self.graph.root_id = Some(self.add_node(Node::Begin { exprs: items })?);
         }
         
+        self.resolve_method_calls();
+        
         // Store module name in graph metadata if present
-        if let Some(module_name) = self.module_name {
+        if let Some(module_name) = self.module_name.take() {
             self.graph.graph_metadata.insert("module_name".to_string(), module_name);
         }
         
-        Ok(self.graph)
+        Ok(())
+    }
+    
+    /// Turn `x.m(...)` calls into trait method calls where `m` is a trait method
+    ///
+    /// Other method-call syntax stays a plain application of `m` to the receiver,
+    /// as do direct calls `m(x)`.
+    fn resolve_method_calls(&mut self) {
+        let mut trait_methods = FxHashSet::default();
+        for node in self.graph.nodes.values() {
+            match node {
+                Node::Trait { methods, .. } => {
+                    trait_methods.extend(methods.iter().map(|m| m.name.clone()));
+                }
+                Node::TraitImpl { methods, .. } => {
+                    trait_methods.extend(methods.iter().map(|(name, _)| name.clone()));
+                }
+                _ => {}
+            }
+        }
+        
+        for (call, method) in std::mem::take(&mut self.method_calls) {
+            let name = match self.graph.get_node(method) {
+                Some(Node::Variable { name }) if trait_methods.contains(name) => name.clone(),
+                _ => continue,
+            };
+            if let Some(Node::Application { args, .. }) = self.graph.get_node(call).cloned() {
+                self.graph.nodes.insert(call, Node::MethodCall { method: name, args });
+                self.graph.nodes.remove(&method);
+                self.graph.metadata.remove(&method);
+            }
+        }
+    }
+    
+    /// Record a syntax error and skip to the next statement or definition
+    ///
+    /// Outside recovery mode the error is returned unchanged. Otherwise an `Error`
+    /// node covering the skipped source is returned in place of the failed item.
+    fn recover(&mut self, error: anyhow::Error, start: usize, in_block: bool) -> Result<NodeId> {
+        if !self.recovering {
+            return Err(error);
+        }
+        
+        let message = error.to_string();
+        self.errors.push(flc_error(message.clone(), self.token_start(), self.current.as_ref()));
+        
+        let before = self.position;
+        self.synchronize(in_block);
+        // Always make progress, but leave a block's closing brace to the block
+        if self.position == before
+            && self.current.is_some()
+            && !(in_block && matches!(self.current, Some(Token::RBrace)))
+        {
+            self.advance();
+        }
+        
+        self.add_node_at(Node::Error { message }, (start, self.position.max(start)))
+    }
+    
+    /// Skip tokens up to the next statement or definition boundary
+    ///
+    /// Stops after a `;` or a closing `}` at the starting nesting level, or before
+    /// `private`/`public`. Inside a block, the brace that closes it is not consumed.
+    fn synchronize(&mut self, in_block: bool) {
+        let mut depth = 0usize;
+        while let Some(token) = &self.current {
+            match token {
+                Token::LBrace | Token::LParen | Token::LBracket => depth += 1,
+                Token::RBrace if depth == 0 && in_block => return,
+                Token::RBrace | Token::RParen | Token::RBracket if depth > 0 => {
+                    depth -= 1;
+                    if depth == 0 && matches!(token, Token::RBrace) {
+                        self.advance();
+                        return;
+                    }
+                }
+                Token::Semicolon if depth == 0 => {
+                    self.advance();
+                    return;
+                }
+                Token::Private | Token::Public if depth == 0 => return,
+                _ => {}
+            }
+            self.advance();
+        }
     }
     
     fn parse_top_level(&mut self) -> Result<NodeId> {
+        let start = self.token_start();
         let node = match self.current {
             Some(Token::Use) => self.parse_use_statement(),
             Some(Token::Mod) => self.parse_module(),
             Some(Token::Export) => self.parse_export_statement(),
             Some(Token::At) | Some(Token::Private) | Some(Token::Public) => self.parse_definition(),
+            Some(Token::Macro) => self.parse_macro_definition(),
+            Some(Token::Extern) => self.parse_extern_declaration(),
             Some(Token::UpperIdent(_)) => {
                 // Check if this is a trait implementation (Type as Trait)
                 if self.peek_ahead_for_as() {
@@ -92,6 +273,7 @@ impl<'a> Parser<'a> {
             }
             _ => self.parse_statement(),
         }?;
+        self.extend_span(node, start, self.position);
         
         // Consume optional semicolon at top level
         if matches!(self.current, Some(Token::Semicolon)) {
@@ -130,6 +312,8 @@ impl<'a> Parser<'a> {
             Some(Token::Type) => self.parse_type_alias(is_public),
             Some(Token::Actor) => self.parse_actor_definition(is_public),
             Some(Token::Effect) => self.parse_effect_definition(is_public),
+            Some(Token::Macro) => self.parse_macro_definition(),
+            Some(Token::Extern) => self.parse_extern_declaration(),
             Some(Token::UpperIdent(_)) => self.parse_trait_impl(is_public),
             Some(Token::LowerIdent(_)) => self.parse_value_definition(is_public),
             _ => Err(anyhow!("Expected definition after visibility modifier")),
@@ -139,6 +323,7 @@ impl<'a> Parser<'a> {
     }
     
     fn parse_function_definition(&mut self, _is_public: bool, contract_info: Option<ContractInfo>) -> Result<NodeId> {
+        let start = self.token_start();
         self.consume(Token::Function)?;
         
         let name = match self.current {
@@ -151,21 +336,34 @@ impl<'a> Parser<'a> {
         
         self.consume(Token::LParen)?;
         let mut params = vec![];
+        let mut param_types = vec![];
+        let mut param_patterns = vec![];
         
         while !matches!(self.current, Some(Token::RParen)) {
-            let param_name = match self.current {
-                Some(Token::LowerIdent(param)) => param.to_string(),
-                Some(Token::Self_) => "self".to_string(),
-                _ => return Err(anyhow!("Expected parameter name")),
-            };
-            
-            params.push(param_name);
-            self.advance();
+            match self.current {
+                Some(Token::LowerIdent(param)) => {
+                    params.push(param.to_string());
+                    self.advance();
+                }
+                Some(Token::Self_) => {
+                    params.push("self".to_string());
+                    self.advance();
+                }
+                _ => {
+                    // Destructuring parameter, matched against the argument on entry
+                    let pattern = self.parse_pattern()?;
+                    let param = format!("_param{}", params.len());
+                    param_patterns.push((param.clone(), pattern));
+                    params.push(param);
+                }
+            }
             
             // Optional type annotation
             if matches!(self.current, Some(Token::Colon)) {
                 self.advance();
-                self.parse_type()?;
+                param_types.push(Some(self.parse_type()?));
+            } else {
+                param_types.push(None);
             }
             
             if matches!(self.current, Some(Token::Comma)) {
@@ -176,13 +374,16 @@ impl<'a> Parser<'a> {
         self.consume(Token::RParen)?;
         
         // Optional return type
-        if matches!(self.current, Some(Token::Arrow)) {
+        let return_type = if matches!(self.current, Some(Token::Arrow)) {
             self.advance();
-            self.parse_type()?;
-        }
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        let mut effects = vec![];
         
-        // Optional effect annotation with .with(EffectName)
-        let body = if matches!(self.current, Some(Token::Dot)) {
+        // Optional effect annotation with .with(EffectName); it is part of the signature only
+        if matches!(self.current, Some(Token::Dot)) {
             self.advance();
             
             // Expect "with"
@@ -197,7 +398,7 @@ impl<'a> Parser<'a> {
             
             // Parse effect name
             let effect_name = match self.current {
-                Some(Token::UpperIdent(name)) => {
+                Some(Token::UpperIdent(name)) | Some(Token::ConstIdent(name)) => {
                     let name = name.to_string();
                     self.advance();
                     name
@@ -207,41 +408,22 @@ impl<'a> Parser<'a> {
             
             self.consume(Token::RParen)?;
             
-            // Now parse the function body
-            self.consume(Token::LBrace)?;
-            let inner_body = self.parse_block_expression()?;
-            self.consume(Token::RBrace)?;
-            
-            // Create an Effect node that wraps the body
-            // Map effect name to EffectType
             let effect_type = match effect_name.as_str() {
                 "Database" => EffectType::IO,  // Database operations are IO effects
-                "Network" => EffectType::Network,
-                "State" => EffectType::State,
-                "Error" => EffectType::Error,
-                "Time" => EffectType::Time,
-                "Random" => EffectType::Random,
-                "Dom" => EffectType::Dom,
-                "Async" => EffectType::Async,
-                "Concurrent" => EffectType::Concurrent,
-                _ => EffectType::IO,  // Default to IO for user-defined effects
+                name => self.effect_type(name)?,
             };
-            
-            // Create an effect node
-            self.add_node(Node::Effect {
-                effect_type,
-                operation: format!("{}_{}", effect_name, name),
-                args: vec![inner_body],
-            })?
-        } else {
-            // No effect annotation, parse body normally
-            self.consume(Token::LBrace)?;
-            let body = self.parse_block_expression()?;
-            self.consume(Token::RBrace)?;
-            body
-        };
+            effects.push(effect_type.to_string());
+        }
+        
+        self.consume(Token::LBrace)?;
+        // `self` in a function body is its own parameter, even inside an actor handler
+        let body = self.with_actor_handler(false, Self::parse_block_expression)?;
+        self.consume(Token::RBrace)?;
+        let body = self.match_pattern_params(param_patterns, body)?;
         
         let lambda = self.add_node(Node::Lambda { params, body })?;
+        self.extend_span(lambda, start, self.position);
+        self.annotate_lambda(lambda, param_types, return_type, effects);
         let define_node = self.add_node(Node::Define { name: name.clone(), value: lambda })?;
         
         // If we have contract annotations, create a Contract node
@@ -264,6 +446,69 @@ impl<'a> Parser<'a> {
         }
     }
     
+    fn parse_extern_declaration(&mut self) -> Result<NodeId> {
+        // extern function name(param: Type, ...) -> Type with(Effect, ...)
+        self.consume(Token::Extern)?;
+        self.consume(Token::Function)?;
+        
+        let name = match self.current {
+            Some(Token::LowerIdent(n)) => {
+                self.advance();
+                n.to_string()
+            }
+            _ => return Err(anyhow!("Expected function name after 'extern function'")),
+        };
+        
+        self.consume(Token::LParen)?;
+        let mut params = vec![];
+        while !matches!(self.current, Some(Token::RParen)) {
+            let param = match self.current {
+                Some(Token::LowerIdent(param)) => param.to_string(),
+                _ => return Err(anyhow!("Expected parameter name")),
+            };
+            self.advance();
+            
+            // There is no body to infer from, so every parameter must be typed
+            if !matches!(self.current, Some(Token::Colon)) {
+                return Err(anyhow!(
+                    "Parameter '{}' of extern function '{}' needs a type annotation",
+                    param, name
+                ));
+            }
+            self.advance();
+            params.push((param, self.parse_type()?));
+            
+            if matches!(self.current, Some(Token::Comma)) {
+                self.advance();
+            } else if !matches!(self.current, Some(Token::RParen)) {
+                return Err(anyhow!("Expected ',' or ')' in parameters of extern function '{}'", name));
+            }
+        }
+        self.consume(Token::RParen)?;
+        
+        let return_type = if matches!(self.current, Some(Token::Arrow)) {
+            self.advance();
+            self.parse_type()?
+        } else {
+            TypeExpr::named("Unit")
+        };
+        let effects = if matches!(self.current, Some(Token::With)) {
+            self.parse_effect_row()?
+        } else {
+            vec![]
+        };
+        if matches!(self.current, Some(Token::LBrace)) {
+            return Err(anyhow!("Extern function '{}' cannot have a body", name));
+        }
+        
+        self.add_node(Node::Extern {
+            name,
+            params,
+            return_type,
+            effects,
+        })
+    }
+    
     fn parse_handler_definition(&mut self, _is_public: bool) -> Result<NodeId> {
         // handle MessageType(param1: Type, param2: Type) { ... }
         self.consume(Token::Handle)?;
@@ -279,6 +524,7 @@ impl<'a> Parser<'a> {
         
         self.consume(Token::LParen)?;
         let mut params = vec![];
+        let mut param_types = vec![];
         
         while !matches!(self.current, Some(Token::RParen)) {
             let param_name = match self.current {
@@ -292,7 +538,9 @@ impl<'a> Parser<'a> {
             // Optional type annotation
             if matches!(self.current, Some(Token::Colon)) {
                 self.advance();
-                self.parse_type()?;
+                param_types.push(Some(self.parse_type()?));
+            } else {
+                param_types.push(None);
             }
             
             params.push(param_name);
@@ -304,10 +552,12 @@ impl<'a> Parser<'a> {
         self.consume(Token::RParen)?;
         
         // Parse optional return type
-        if matches!(self.current, Some(Token::Arrow)) {
+        let return_type = if matches!(self.current, Some(Token::Arrow)) {
             self.advance();
-            self.parse_type()?;
-        }
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
         
         self.consume(Token::LBrace)?;
         let body = self.parse_block_expression()?;
@@ -316,6 +566,7 @@ impl<'a> Parser<'a> {
         // Create handler as a special function
         let handler_name = format!("handle_{}", message_type);
         let lambda = self.add_node(Node::Lambda { params, body })?;
+        self.annotate_lambda(lambda, param_types, return_type, vec![]);
         self.add_node(Node::Define { 
             name: handler_name, 
             value: lambda 
@@ -422,9 +673,10 @@ impl<'a> Parser<'a> {
         let mut left = self.parse_and_expression()?;
         
         while matches!(self.current, Some(Token::OrOr)) {
+            let op_span = self.token_span();
             self.advance();
             let right = self.parse_and_expression()?;
-            let op = self.add_node(Node::Variable { name: "or".to_string() })?;
+            let op = self.add_node_at(Node::Variable { name: "or".to_string() }, op_span)?;
             left = self.add_node(Node::Application { 
                 function: op, 
                 args: vec![left, right] 
@@ -438,9 +690,10 @@ impl<'a> Parser<'a> {
         let mut left = self.parse_equality_expression()?;
         
         while matches!(self.current, Some(Token::AndAnd)) {
+            let op_span = self.token_span();
             self.advance();
             let right = self.parse_equality_expression()?;
-            let op = self.add_node(Node::Variable { name: "and".to_string() })?;
+            let op = self.add_node_at(Node::Variable { name: "and".to_string() }, op_span)?;
             left = self.add_node(Node::Application { 
                 function: op, 
                 args: vec![left, right] 
@@ -460,9 +713,10 @@ impl<'a> Parser<'a> {
                 _ => break,
             };
             
+            let op_span = self.token_span();
             self.advance();
             let right = self.parse_comparison_expression()?;
-            let op = self.add_node(Node::Variable { name: op_name.to_string() })?;
+            let op = self.add_node_at(Node::Variable { name: op_name.to_string() }, op_span)?;
             left = self.add_node(Node::Application { 
                 function: op, 
                 args: vec![left, right] 
@@ -484,9 +738,10 @@ impl<'a> Parser<'a> {
                 _ => break,
             };
             
+            let op_span = self.token_span();
             self.advance();
             let right = self.parse_additive_expression()?;
-            let op = self.add_node(Node::Variable { name: op_name.to_string() })?;
+            let op = self.add_node_at(Node::Variable { name: op_name.to_string() }, op_span)?;
             left = self.add_node(Node::Application { 
                 function: op, 
                 args: vec![left, right] 
@@ -506,9 +761,10 @@ impl<'a> Parser<'a> {
                 _ => break,
             };
             
+            let op_span = self.token_span();
             self.advance();
             let right = self.parse_multiplicative_expression()?;
-            let op = self.add_node(Node::Variable { name: op_name.to_string() })?;
+            let op = self.add_node_at(Node::Variable { name: op_name.to_string() }, op_span)?;
             left = self.add_node(Node::Application { 
                 function: op, 
                 args: vec![left, right] 
@@ -529,9 +785,10 @@ impl<'a> Parser<'a> {
                 _ => break,
             };
             
+            let op_span = self.token_span();
             self.advance();
             let right = self.parse_unary_expression()?;
-            let op = self.add_node(Node::Variable { name: op_name.to_string() })?;
+            let op = self.add_node_at(Node::Variable { name: op_name.to_string() }, op_span)?;
             left = self.add_node(Node::Application { 
                 function: op, 
                 args: vec![left, right] 
@@ -544,18 +801,20 @@ impl<'a> Parser<'a> {
     fn parse_unary_expression(&mut self) -> Result<NodeId> {
         match self.current {
             Some(Token::Bang) => {
+                let op_span = self.token_span();
                 self.advance();
                 let expr = self.parse_unary_expression()?;
-                let not_op = self.add_node(Node::Variable { name: "not".to_string() })?;
+                let not_op = self.add_node_at(Node::Variable { name: "not".to_string() }, op_span)?;
                 self.add_node(Node::Application { 
                     function: not_op, 
                     args: vec![expr] 
                 })
             }
             Some(Token::Minus) => {
+                let op_span = self.token_span();
                 self.advance();
                 let expr = self.parse_unary_expression()?;
-                let neg_op = self.add_node(Node::Variable { name: "neg".to_string() })?;
+                let neg_op = self.add_node_at(Node::Variable { name: "neg".to_string() }, op_span)?;
                 self.add_node(Node::Application { 
                     function: neg_op, 
                     args: vec![expr] 
@@ -566,7 +825,7 @@ impl<'a> Parser<'a> {
     }
     
     fn parse_postfix_expression(&mut self) -> Result<NodeId> {
-        let mut expr = self.parse_primary_expression()?;
+        let mut expr = self.spanned(Self::parse_primary_expression)?;
         
         loop {
             match self.current {
@@ -643,6 +902,10 @@ impl<'a> Parser<'a> {
                             self.advance();
                             "case".to_string()
                         }
+                        Some(Token::Receive) => {
+                            self.advance();
+                            "receive".to_string()
+                        }
                         _ => return Err(anyhow!("Expected method name after '.'"))
                     };
                     
@@ -734,6 +997,7 @@ impl<'a> Parser<'a> {
                                         function: method, 
                                         args 
                                     })?;
+                                    self.method_calls.push((expr, method));
                                 }
                     } else {
                         // Check if the expr is a simple variable and this could be a qualified variable
@@ -743,13 +1007,19 @@ impl<'a> Parser<'a> {
                                 module_name: module_name.clone(),
                                 variable_name: method_name,
                             })?;
-                        } else {
-                            // Property access or method without parens
+                        } else if matches!(method_name.as_str(), "match" | "await" | "case") {
+                            // Keyword method without parens
                             let method = self.add_node(Node::Variable { name: method_name })?;
                             expr = self.add_node(Node::Application { 
                                 function: method, 
                                 args: vec![expr] 
                             })?;
+                        } else {
+                            // Field access on an arbitrary expression: a.b.c, make_point().x
+                            expr = self.add_node(Node::FieldAccess {
+                                object: expr,
+                                field: method_name,
+                            })?;
                         }
                     }
                 }
@@ -803,10 +1073,22 @@ impl<'a> Parser<'a> {
                 self.advance();
                 self.add_node(Node::Literal(Literal::Float(f)))
             }
+            Some(Token::BigInt(n)) => {
+                self.advance();
+                self.add_node(Node::Literal(Literal::BigInt(n)))
+            }
+            Some(Token::Decimal(d)) => {
+                self.advance();
+                self.add_node(Node::Literal(Literal::Decimal(d)))
+            }
             Some(Token::String(s)) => {
                 self.advance();
                 self.add_node(Node::Literal(Literal::String(s.to_string())))
             }
+            Some(Token::Bytes(b)) => {
+                self.advance();
+                self.add_node(Node::Literal(Literal::Bytes(b)))
+            }
             Some(Token::Symbol(s)) => {
                 self.advance();
                 self.add_node(Node::Literal(Literal::Symbol(s.to_string())))
@@ -860,7 +1142,9 @@ impl<'a> Parser<'a> {
             }
             Some(Token::Self_) => {
                 self.advance();
-                self.add_node(Node::Variable { name: "self".to_string() })
+                // Inside actor handlers `self` refers to the actor's state
+                let name = if self.in_actor_handler { "state" } else { "self" };
+                self.add_node(Node::Variable { name: name.to_string() })
             }
             Some(Token::UpperIdent(name)) => {
                 let name = name.to_string();
@@ -917,61 +1201,110 @@ impl<'a> Parser<'a> {
                 // Try to parse as a lambda parameter list
                 // We'll use a more careful approach that doesn't consume tokens unnecessarily
                 let checkpoint = self.position;
+                let checkpoint_start = self.last_start;
                 let checkpoint_lexer = self.lexer.clone();
                 let checkpoint_current = self.current.clone();
+                let checkpoint_node = self.graph.next_node_id();
                 
                 // Try to collect parameter names
                 let mut params = vec![];
+                let mut param_types = vec![];
+                let mut param_patterns = vec![];
                 let mut could_be_lambda = true;
                 
                 // First, check if we have a valid parameter list
                 loop {
-                    match self.current {
+                    let is_param = match self.current {
                         Some(Token::LowerIdent(name)) => {
                             params.push(name.to_string());
                             self.advance();
-                            
-                            match self.current {
-                                Some(Token::Comma) => {
-                                    self.advance(); // continue to next parameter
-                                }
-                                Some(Token::RParen) => {
-                                    self.advance();
-                                    // Check if followed by =>
-                                    if matches!(self.current, Some(Token::FatArrow)) {
-                                        // It's definitely a lambda!
-                                        self.advance(); // consume =>
-                                        let body = self.parse_expression()?;
-                                        return self.add_node(Node::Lambda { params, body });
-                                    } else {
-                                        // Not a lambda, break and restore
-                                        could_be_lambda = false;
-                                        break;
-                                    }
-                                }
-                                _ => {
-                                    // Not a valid parameter list
+                            true
+                        }
+                        // Destructuring parameter, matched against the argument on entry
+                        _ => match self.parse_pattern() {
+                            Ok(pattern) => {
+                                let param = format!("_param{}", params.len());
+                                param_patterns.push((param.clone(), pattern));
+                                params.push(param);
+                                true
+                            }
+                            Err(_) => false,
+                        },
+                    };
+                    if is_param {
+                        // Optional parameter annotation
+                        if matches!(self.current, Some(Token::Colon)) {
+                            self.advance();
+                            match self.parse_type() {
+                                Ok(ty) => param_types.push(Some(ty)),
+                                Err(_) => {
                                     could_be_lambda = false;
                                     break;
                                 }
                             }
+                        } else {
+                            param_types.push(None);
                         }
-                        _ => {
-                            // Not starting with an identifier, can't be lambda params
-                            could_be_lambda = false;
-                            break;
+                        
+                        match self.current {
+                            Some(Token::Comma) => {
+                                self.advance(); // continue to next parameter
+                            }
+                            Some(Token::RParen) => {
+                                self.advance();
+                                // Check if followed by =>
+                                if matches!(self.current, Some(Token::FatArrow)) {
+                                    // It's definitely a lambda!
+                                    self.advance(); // consume =>
+                                    let body = self.parse_expression()?;
+                                    let body = self.match_pattern_params(param_patterns, body)?;
+                                    let lambda = self.add_node(Node::Lambda { params, body })?;
+                                    self.annotate_lambda(lambda, param_types, None, vec![]);
+                                    return Ok(lambda);
+                                } else {
+                                    // Not a lambda, break and restore
+                                    could_be_lambda = false;
+                                    break;
+                                }
+                            }
+                            _ => {
+                                // Not a valid parameter list
+                                could_be_lambda = false;
+                                break;
+                            }
                         }
+                    } else {
+                        // Not starting with a parameter, can't be lambda params
+                        could_be_lambda = false;
+                        break;
                     }
                 }
                 
                 // If it wasn't a lambda, restore and parse as regular expression
                 self.position = checkpoint;
+                self.last_start = checkpoint_start;
                 self.lexer = checkpoint_lexer;
                 self.current = checkpoint_current;
+                // Drop the nodes of any guard parsed as part of a parameter pattern
+                self.graph.remove_nodes_since(checkpoint_node);
                 
                 let expr = self.parse_expression()?;
+                if !matches!(self.current, Some(Token::Comma)) {
+                    self.consume(Token::RParen)?;
+                    return Ok(expr);
+                }
+                
+                // A comma makes it a tuple: (a, b, c), or (a,) for a single element
+                let mut elements = vec![expr];
+                while matches!(self.current, Some(Token::Comma)) {
+                    self.advance();
+                    if matches!(self.current, Some(Token::RParen)) {
+                        break;
+                    }
+                    elements.push(self.parse_expression()?);
+                }
                 self.consume(Token::RParen)?;
-                Ok(expr)
+                self.add_node(Node::Tuple(elements))
             }
             Some(Token::LBrace) => {
                 // Could be a block expression or a map literal
@@ -1096,8 +1429,14 @@ impl<'a> Parser<'a> {
             Some(Token::Match) => self.parse_match_expression(),
             Some(Token::For) => self.parse_for_expression(),
             Some(Token::While) => self.parse_while_expression(),
+            Some(Token::Break) => self.parse_break_expression(),
+            Some(Token::Continue) => {
+                self.advance();
+                self.add_node(Node::Continue)
+            }
             Some(Token::Try) => self.parse_try_expression(),
             Some(Token::Spawn) => self.parse_spawn_expression(),
+            Some(Token::Parallel) => self.parse_parallel_expression(),
             Some(Token::Perform) => self.parse_perform_expression(),
             Some(Token::Handle) => self.parse_handle_expression(),
             Some(Token::Receive) => self.parse_receive_expression(),
@@ -1135,41 +1474,34 @@ impl<'a> Parser<'a> {
     }
     
     fn parse_for_expression(&mut self) -> Result<NodeId> {
-        // for item in collection { body }
+        // for pattern in collection { body }
+        // for pattern in start..end { body }
+        // for pattern in start..=end { body }
         self.consume(Token::For)?;
         
-        // Parse the loop variable (pattern)
-        let var_name = match self.current {
-            Some(Token::LowerIdent(name)) => {
-                let name = name.to_string();
-                self.advance();
-                name
-            }
-            _ => return Err(anyhow!("Expected variable name after 'for'")),
-        };
+        let pattern = self.parse_pattern()?;
         
         self.consume(Token::In)?;
         
-        // Parse the collection to iterate over
-        let collection = self.parse_expression()?;
+        // Parse the collection to iterate over, or the start of a range
+        let mut iterable = self.parse_expression()?;
+        if matches!(self.current, Some(Token::DotDot)) {
+            self.advance(); // consume ..
+            let inclusive = if matches!(self.current, Some(Token::Eq)) {
+                self.advance(); // consume =
+                true
+            } else {
+                false
+            };
+            let end = self.parse_expression()?;
+            iterable = self.add_node(Node::Range { start: iterable, end, inclusive })?;
+        }
         
         self.consume(Token::LBrace)?;
         let body = self.parse_block_expression()?;
         self.consume(Token::RBrace)?;
         
-        // Convert to: collection.for_each(var_name => body)
-        let for_each_fn = self.add_node(Node::Variable { name: "for_each".to_string() })?;
-        let lambda = self.add_node(Node::Lambda {
-            params: vec![var_name],
-            body,
-        })?;
-        
-        let method_call = self.add_node(Node::Application {
-            function: for_each_fn,
-            args: vec![collection, lambda],
-        })?;
-        
-        Ok(method_call)
+        self.add_node(Node::For { pattern, iterable, body })
     }
     
     fn parse_while_expression(&mut self) -> Result<NodeId> {
@@ -1182,50 +1514,20 @@ impl<'a> Parser<'a> {
         let body = self.parse_block_expression()?;
         self.consume(Token::RBrace)?;
         
-        // Convert to a recursive function
-        // let loop_fn = rec (unit) => if (condition) { body; loop_fn(unit) } else { nil }
-        // loop_fn(unit)
-        
-        let unit = self.add_node(Node::Literal(Literal::Nil))?;
-        let loop_var = format!("_while_{}", self.graph.nodes.len());
-        
-        // Create the recursive call: loop_fn(unit)
-        let loop_call_inner = self.add_node(Node::Variable { name: loop_var.clone() })?;
-        let recursive_call = self.add_node(Node::Application {
-            function: loop_call_inner,
-            args: vec![unit],
-        })?;
-        
-        // Create body sequence: body; loop_fn(unit)
-        let body_with_recursion = self.add_node(Node::Begin {
-            exprs: vec![body, recursive_call],
-        })?;
-        
-        // Create the if expression
-        let if_expr = self.add_node(Node::If {
-            condition,
-            then_branch: body_with_recursion,
-            else_branch: unit,
-        })?;
-        
-        // Create the recursive lambda
-        let loop_lambda = self.add_node(Node::Lambda {
-            params: vec!["_".to_string()],
-            body: if_expr,
-        })?;
-        
-        // Create the let-rec binding and call
-        let binding = (loop_var.clone(), loop_lambda);
-        let loop_call = self.add_node(Node::Variable { name: loop_var })?;
-        let final_call = self.add_node(Node::Application {
-            function: loop_call,
-            args: vec![unit],
-        })?;
+        self.add_node(Node::While { condition, body })
+    }
+    
+    fn parse_break_expression(&mut self) -> Result<NodeId> {
+        // break
+        // break value
+        self.consume(Token::Break)?;
+        
+        let value = match self.current {
+            None | Some(Token::Semicolon) | Some(Token::RBrace) | Some(Token::RParen) | Some(Token::Comma) => None,
+            _ => Some(self.parse_expression()?),
+        };
         
-        self.add_node(Node::Letrec {
-            bindings: vec![binding],
-            body: final_call,
-        })
+        self.add_node(Node::Break { value })
     }
     
     fn parse_let_expression(&mut self) -> Result<NodeId> {
@@ -1239,75 +1541,34 @@ impl<'a> Parser<'a> {
             self.advance();
         }
         
+        // Any other pattern matches the value against it: let [x, ...rest] = expr
+        if !is_rec && self.at_let_pattern() {
+            let (pattern, value) = self.parse_let_pattern_binding()?;
+            self.consume(Token::Semicolon)?;
+            let body = self.parse_expression()?;
+            return self.add_node(Node::Match { expr: value, branches: vec![(pattern, body)], must_match: true });
+        }
+        
         // Check if this is a destructuring pattern
         if matches!(self.current, Some(Token::LBrace)) {
             // Parse destructuring pattern: let {x, y} = expr
-            self.advance(); // consume {
-            
-            let mut field_names = vec![];
-            while !matches!(self.current, Some(Token::RBrace)) {
-                match self.current {
-                    Some(Token::LowerIdent(name)) => {
-                        field_names.push(name.to_string());
-                        self.advance();
-                    }
-                    _ => return Err(anyhow!("Expected field name in destructuring pattern")),
-                }
-                
-                if matches!(self.current, Some(Token::Comma)) {
-                    self.advance();
-                }
-            }
-            self.consume(Token::RBrace)?;
-            self.consume(Token::Eq)?;
-            let value = self.parse_expression()?;
-            
-            // Generate a temporary variable for the struct value
-            let temp_var = format!("_struct{}", self.graph.nodes.len());
-            bindings.push((temp_var.clone(), value));
-            
-            // Create field access for each destructured field
-            for field_name in field_names {
-                let struct_var = self.add_node(Node::Variable { name: temp_var.clone() })?;
-                let getter_fn = self.add_node(Node::Variable { name: format!("get_{}", field_name) })?;
-                let field_access = self.add_node(Node::Application {
-                    function: getter_fn,
-                    args: vec![struct_var],
-                })?;
-                bindings.push((field_name, field_access));
-            }
+            bindings.extend(self.parse_record_destructuring()?);
         } else {
             // Parse regular binding
-            let name = match self.current {
-                Some(Token::LowerIdent(n)) => {
-                    self.advance();
-                    n.to_string()
-                }
-                _ => return Err(anyhow!("Expected variable name after 'let'")),
-            };
-            
-            self.consume(Token::Eq)?;
-            let value = self.parse_expression()?;
-            bindings.push((name, value));
+            bindings.push(self.parse_let_binding()?);
         }
         
         self.consume(Token::Semicolon)?;
         
-        // Parse additional bindings for regular let
-        while !is_rec && matches!(self.current, Some(Token::Let)) {
+        // Parse additional bindings for regular let; a pattern let starts the body
+        while !is_rec && matches!(self.current, Some(Token::Let)) && !self.peek_ahead_for_let_pattern() {
             self.advance();
             
-            let name = match self.current {
-                Some(Token::LowerIdent(n)) => {
-                    self.advance();
-                    n.to_string()
-                }
-                _ => return Err(anyhow!("Expected variable name after 'let'")),
-            };
-            
-            self.consume(Token::Eq)?;
-            let value = self.parse_expression()?;
-            bindings.push((name, value));
+            if matches!(self.current, Some(Token::LBrace)) {
+                bindings.extend(self.parse_record_destructuring()?);
+            } else {
+                bindings.push(self.parse_let_binding()?);
+            }
             
             self.consume(Token::Semicolon)?;
         }
@@ -1322,48 +1583,121 @@ impl<'a> Parser<'a> {
         }
     }
     
-    fn parse_block(&mut self) -> Result<NodeId> {
-        let mut let_bindings = vec![];
-        let mut exprs = vec![];
+    /// Match destructuring parameters against their arguments before the body runs
+    fn match_pattern_params(&mut self, params: Vec<(String, Pattern)>, body: NodeId) -> Result<NodeId> {
+        let mut body = body;
+        for (param, pattern) in params.into_iter().rev() {
+            let argument = self.add_node(Node::Variable { name: param })?;
+            body = self.add_node(Node::Match { expr: argument, branches: vec![(pattern, body)], must_match: true })?;
+        }
+        Ok(body)
+    }
+    
+    /// Parse `name = expr` or `name: Type = expr` after `let`
+    fn parse_let_binding(&mut self) -> Result<(String, NodeId)> {
+        let name = match self.current {
+            Some(Token::LowerIdent(n)) => {
+                self.advance();
+                n.to_string()
+            }
+            _ => return Err(anyhow!("Expected variable name after 'let'")),
+        };
         
-        while !matches!(self.current, Some(Token::RBrace)) && self.current.is_some() {
+        let annotation = self.parse_binding_annotation()?;
+        self.consume(Token::Eq)?;
+        let value = self.parse_expression()?;
+        let value = match annotation {
+            Some(ty) => self.annotate_binding(value, ty)?,
+            None => value,
+        };
+        Ok((name, value))
+    }
+    
+    /// Parse `{x, y} = expr` after `let`, binding each name to the field of the same name
+    fn parse_record_destructuring(&mut self) -> Result<Vec<(String, NodeId)>> {
+        self.consume(Token::LBrace)?;
+        
+        let mut field_names = vec![];
+        while !matches!(self.current, Some(Token::RBrace)) {
+            match self.current {
+                Some(Token::LowerIdent(name)) => {
+                    field_names.push(name.to_string());
+                    self.advance();
+                }
+                _ => return Err(anyhow!("Expected field name in destructuring pattern")),
+            }
+            
+            if matches!(self.current, Some(Token::Comma)) {
+                self.advance();
+            }
+        }
+        self.consume(Token::RBrace)?;
+        self.consume(Token::Eq)?;
+        let value = self.parse_expression()?;
+        
+        // Generate a temporary variable for the struct value
+        let temp_var = format!("_struct{}", self.graph.nodes.len());
+        let mut bindings = vec![(temp_var.clone(), value)];
+        
+        // Create field access for each destructured field
+        for field_name in field_names {
+            let object = self.add_node(Node::Variable { name: temp_var.clone() })?;
+            let field_access = self.add_node(Node::FieldAccess {
+                object,
+                field: field_name.clone(),
+            })?;
+            bindings.push((field_name, field_access));
+        }
+        Ok(bindings)
+    }
+    
+    /// Parse `pattern = expr` after `let`
+    fn parse_let_pattern_binding(&mut self) -> Result<(Pattern, NodeId)> {
+        let pattern = self.parse_pattern()?;
+        self.consume(Token::Eq)?;
+        let value = self.parse_expression()?;
+        Ok((pattern, value))
+    }
+    
+    /// Whether the binding after `let` is a pattern other than a plain name or record
+    fn at_let_pattern(&self) -> bool {
+        !matches!(
+            self.current,
+            Some(Token::LowerIdent(_)) | Some(Token::LBrace) | Some(Token::Rec)
+        )
+    }
+    
+    fn peek_ahead_for_let_pattern(&mut self) -> bool {
+        // Look past `let` for the start of a destructuring pattern
+        let saved_lexer = self.lexer.clone();
+        let saved_current = self.current.clone();
+        let saved_span = (self.last_start, self.position);
+        
+        self.advance();
+        let is_pattern = self.at_let_pattern();
+        
+        self.lexer = saved_lexer;
+        self.current = saved_current;
+        (self.last_start, self.position) = saved_span;
+        
+        is_pattern
+    }
+    
+    fn parse_block(&mut self) -> Result<NodeId> {
+        let mut let_bindings = vec![];
+        let mut exprs = vec![];
+        
+        while !matches!(self.current, Some(Token::RBrace)) && self.current.is_some() {
             // Debug: print current token before parsing statement
             #[cfg(test)]
             if let Some(ref token) = self.current {
                 eprintln!("parse_block: about to parse statement, current token: {:?}", token);
             }
             
-            // Check if this is a let binding
-            if matches!(self.current, Some(Token::Let)) {
-                // Parse the let binding directly here instead of creating a Let node
-                self.advance(); // consume 'let'
-                
-                let name = match self.current {
-                    Some(Token::LowerIdent(n)) => {
-                        self.advance();
-                        n.to_string()
-                    }
-                    _ => return Err(anyhow!("Expected variable name after 'let'")),
-                };
-                
-                self.consume(Token::Eq)?;
-                let value = self.parse_expression()?;
-                
-                let_bindings.push((name, value));
-                
-                // Optional semicolon after let binding
-                if matches!(self.current, Some(Token::Semicolon)) {
-                    self.advance();
-                }
-            } else {
-                // Parse as regular statement/expression
-                let expr = self.parse_statement()?;
-                exprs.push(expr);
-                
-                // Optional semicolon (already consumed by parse_statement for statements)
-                if matches!(self.current, Some(Token::Semicolon)) {
-                    self.advance();
-                }
+            let start = self.token_start();
+            if let Err(error) = self.parse_block_item(&mut let_bindings, &mut exprs) {
+                let error_node = self.recover(error, start, true)?;
+                exprs.push(error_node);
             }
         }
         
@@ -1384,6 +1718,52 @@ impl<'a> Parser<'a> {
         }
     }
     
+    /// Parse one let binding or statement of a block
+    fn parse_block_item(
+        &mut self,
+        let_bindings: &mut Vec<(String, NodeId)>,
+        exprs: &mut Vec<NodeId>,
+    ) -> Result<()> {
+        // Check if this is a let binding
+        if matches!(self.current, Some(Token::Let)) {
+            // Parse the let binding directly here instead of creating a Let node
+            self.advance(); // consume 'let'
+            
+            if self.at_let_pattern() {
+                // The rest of the block runs inside a match on the pattern
+                let (pattern, value) = self.parse_let_pattern_binding()?;
+                if matches!(self.current, Some(Token::Semicolon)) {
+                    self.advance();
+                }
+                let rest = self.parse_block()?;
+                exprs.push(self.add_node(Node::Match { expr: value, branches: vec![(pattern, rest)], must_match: true })?);
+                return Ok(());
+            }
+            
+            if matches!(self.current, Some(Token::LBrace)) {
+                let_bindings.extend(self.parse_record_destructuring()?);
+            } else {
+                let_bindings.push(self.parse_let_binding()?);
+            }
+            
+            // Optional semicolon after let binding
+            if matches!(self.current, Some(Token::Semicolon)) {
+                self.advance();
+            }
+        } else {
+            // Parse as regular statement/expression
+            let expr = self.parse_statement()?;
+            exprs.push(expr);
+            
+            // Optional semicolon (already consumed by parse_statement for statements)
+            if matches!(self.current, Some(Token::Semicolon)) {
+                self.advance();
+            }
+        }
+        
+        Ok(())
+    }
+    
     fn parse_block_expression(&mut self) -> Result<NodeId> {
         // For backward compatibility, delegate to parse_block
         self.parse_block()
@@ -1410,8 +1790,13 @@ impl<'a> Parser<'a> {
             _ => return Err(anyhow!("Expected variable name after 'let'")),
         };
         
+        let annotation = self.parse_binding_annotation()?;
         self.consume(Token::Eq)?;
         let value = self.parse_expression()?;
+        let value = match annotation {
+            Some(ty) => self.annotate_binding(value, ty)?,
+            None => value,
+        };
         
         // Create a let node with the binding and nil as body
         // This effectively makes it a statement that binds the variable
@@ -1703,7 +2088,7 @@ impl<'a> Parser<'a> {
         while !matches!(self.current, Some(Token::RBrace)) {
             match self.current {
                 Some(Token::Private) | Some(Token::Public) => {
-                    let def = self.parse_definition()?;
+                    let def = self.spanned(Self::parse_definition)?;
                     definitions.push(def);
                 }
                 Some(Token::Use) => {
@@ -1817,14 +2202,15 @@ impl<'a> Parser<'a> {
             }
             _ => return Err(anyhow!("Expected struct name after 'struct'")),
         };
+        let type_params = self.parse_type_params()?;
         
         self.consume(Token::LBrace)?;
         
         let mut fields = vec![];
         while !matches!(self.current, Some(Token::RBrace)) {
             // Check for pub modifier
-            let _field_public = matches!(self.current, Some(Token::Public));
-            if _field_public {
+            let is_public = matches!(self.current, Some(Token::Public));
+            if is_public {
                 self.advance();
             }
             
@@ -1839,9 +2225,16 @@ impl<'a> Parser<'a> {
             };
             
             self.consume(Token::Colon)?;
-            let _field_type = self.parse_type()?;
+            let field_type = self.parse_type()?;
             
-            fields.push(field_name);
+            if fields.iter().any(|f: &StructField| f.name == field_name) {
+                return Err(anyhow!("Duplicate field '{}' in struct {}", field_name, struct_name));
+            }
+            fields.push(StructField {
+                name: field_name,
+                field_type,
+                is_public,
+            });
             
             if matches!(self.current, Some(Token::Comma)) {
                 self.advance();
@@ -1879,24 +2272,44 @@ impl<'a> Parser<'a> {
             }
         }
         
-        // For now, create a define node with a special struct value
-        // In a full implementation, we'd have a Node::Struct variant
-        let struct_value = if derive_traits.is_empty() {
-            self.add_node(Node::List(vec![]))?
-        } else {
-            // Add derive info as metadata
-            let derive_list = derive_traits.into_iter()
-                .map(|t| self.add_node(Node::Literal(Literal::Symbol(t))))
-                .collect::<Result<Vec<_>>>()?;
-            self.add_node(Node::List(derive_list))?
-        };
-        
-        self.add_node(Node::Define {
+        self.add_node(Node::Struct {
             name: struct_name,
-            value: struct_value,
+            type_params,
+            fields,
+            derives: derive_traits,
         })
     }
     
+    /// Parse the optional `<T, U>` type parameters of a struct or enum declaration
+    fn parse_type_params(&mut self) -> Result<Vec<String>> {
+        let mut params = vec![];
+        if !matches!(self.current, Some(Token::Less)) {
+            return Ok(params);
+        }
+        self.advance();
+        
+        while !matches!(self.current, Some(Token::Greater)) {
+            match self.current {
+                Some(Token::UpperIdent(name)) | Some(Token::ConstIdent(name)) => {
+                    if params.iter().any(|p| p == name) {
+                        return Err(anyhow!("Duplicate type parameter '{}'", name));
+                    }
+                    params.push(name.to_string());
+                    self.advance();
+                }
+                _ => return Err(anyhow!("Expected type parameter name")),
+            }
+            if matches!(self.current, Some(Token::Comma)) {
+                self.advance();
+            } else if !matches!(self.current, Some(Token::Greater)) {
+                return Err(anyhow!("Expected ',' or '>' in type parameters"));
+            }
+        }
+        self.consume(Token::Greater)?;
+        
+        Ok(params)
+    }
+    
     fn parse_enum_definition(&mut self, _is_public: bool) -> Result<NodeId> {
         // enum EnumName { Variant1, Variant2(Type), Variant3 { field: Type } }
         self.consume(Token::Enum)?;
@@ -1909,6 +2322,7 @@ impl<'a> Parser<'a> {
             }
             _ => return Err(anyhow!("Expected enum name after 'enum'")),
         };
+        let type_params = self.parse_type_params()?;
         
         self.consume(Token::LBrace)?;
         
@@ -1924,12 +2338,13 @@ impl<'a> Parser<'a> {
             };
             
             // Check for variant data
+            let mut variant_fields = vec![];
             match self.current {
                 Some(Token::LParen) => {
                     // Tuple variant
                     self.advance();
                     while !matches!(self.current, Some(Token::RParen)) {
-                        self.parse_type()?;
+                        variant_fields.push(self.parse_type()?);
                         if matches!(self.current, Some(Token::Comma)) {
                             self.advance();
                         }
@@ -1944,7 +2359,7 @@ impl<'a> Parser<'a> {
                             Some(Token::LowerIdent(_)) => {
                                 self.advance();
                                 self.consume(Token::Colon)?;
-                                self.parse_type()?;
+                                variant_fields.push(self.parse_type()?);
                                 if matches!(self.current, Some(Token::Comma)) {
                                     self.advance();
                                 }
@@ -1959,7 +2374,13 @@ impl<'a> Parser<'a> {
                 }
             }
             
-            variants.push(variant_name);
+            if variants.iter().any(|v: &EnumVariant| v.name == variant_name) {
+                return Err(anyhow!("Duplicate variant '{}' in enum {}", variant_name, enum_name));
+            }
+            variants.push(EnumVariant {
+                name: variant_name,
+                fields: variant_fields,
+            });
             
             if matches!(self.current, Some(Token::Comma)) {
                 self.advance();
@@ -1997,25 +2418,16 @@ impl<'a> Parser<'a> {
             }
         }
         
-        // Create a define node for the enum
-        let enum_value = if derive_traits.is_empty() {
-            self.add_node(Node::List(vec![]))?
-        } else {
-            // Add derive info as metadata
-            let derive_list = derive_traits.into_iter()
-                .map(|t| self.add_node(Node::Literal(Literal::Symbol(t))))
-                .collect::<Result<Vec<_>>>()?;
-            self.add_node(Node::List(derive_list))?
-        };
-        
-        self.add_node(Node::Define {
+        self.add_node(Node::Enum {
             name: enum_name,
-            value: enum_value,
+            type_params,
+            variants,
+            derives: derive_traits,
         })
     }
     
     fn parse_trait_definition(&mut self, _is_public: bool) -> Result<NodeId> {
-        // trait TraitName { def method(self, args) -> Type; }
+        // trait TraitName { function method(self, args) -> Type; }
         self.consume(Token::Trait)?;
         
         let trait_name = match self.current {
@@ -2031,68 +2443,94 @@ impl<'a> Parser<'a> {
         
         let mut methods = vec![];
         while !matches!(self.current, Some(Token::RBrace)) {
-            // Parse method signature
-            if matches!(self.current, Some(Token::Private)) || matches!(self.current, Some(Token::Public)) {
+            // Parse method signature; visibility is optional
+            if matches!(self.current, Some(Token::Private) | Some(Token::Public)) {
                 self.advance();
-                
-                if matches!(self.current, Some(Token::Function)) {
+            }
+            self.consume(Token::Function)?;
+            
+            let method_name = match self.current {
+                Some(Token::LowerIdent(n)) => {
+                    let name = n.to_string();
                     self.advance();
+                    name
                 }
-                
-                let method_name = match self.current {
-                    Some(Token::LowerIdent(n)) => {
-                        let name = n.to_string();
-                        self.advance();
-                        name
-                    }
-                    _ => return Err(anyhow!("Expected method name in trait")),
+                _ => return Err(anyhow!("Expected method name in trait {}", trait_name)),
+            };
+            
+            // Parse parameters
+            let mut params = vec![];
+            self.consume(Token::LParen)?;
+            while !matches!(self.current, Some(Token::RParen)) {
+                let param_name = match self.current {
+                    Some(Token::LowerIdent(n)) => n.to_string(),
+                    Some(Token::Self_) => "self".to_string(),
+                    _ => return Err(anyhow!("Expected parameter name")),
                 };
+                self.advance();
+                let ty = if matches!(self.current, Some(Token::Colon)) {
+                    self.advance();
+                    self.parse_type()?
+                } else {
+                    TypeExpr::Infer
+                };
+                params.push((param_name, ty));
                 
-                // Parse parameters
-                self.consume(Token::LParen)?;
-                while !matches!(self.current, Some(Token::RParen)) {
-                    match self.current {
-                        Some(Token::LowerIdent(_)) => {
-                            self.advance();
-                            if matches!(self.current, Some(Token::Colon)) {
-                                self.advance();
-                                self.parse_type()?;
-                            }
-                        }
-                        _ => return Err(anyhow!("Expected parameter name")),
-                    }
-                    
-                    if matches!(self.current, Some(Token::Comma)) {
-                        self.advance();
-                    }
-                }
-                self.consume(Token::RParen)?;
-                
-                // Skip return type if present
-                if matches!(self.current, Some(Token::Arrow)) {
+                if matches!(self.current, Some(Token::Comma)) {
                     self.advance();
-                    self.parse_type()?;
                 }
-                
-                self.consume(Token::Semicolon)?;
-                methods.push(method_name);
             }
+            self.consume(Token::RParen)?;
+            
+            let return_type = if matches!(self.current, Some(Token::Arrow)) {
+                self.advance();
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+            
+            self.consume(Token::Semicolon)?;
+            
+            if methods.iter().any(|m: &TraitMethod| m.name == method_name) {
+                return Err(anyhow!("Duplicate method '{}' in trait {}", method_name, trait_name));
+            }
+            methods.push(TraitMethod {
+                name: method_name,
+                params,
+                return_type,
+            });
         }
         
         self.consume(Token::RBrace)?;
         
-        // Create a define node for the trait
-        let trait_value = self.add_node(Node::List(vec![]))?;
-        self.add_node(Node::Define {
+        self.add_node(Node::Trait {
             name: trait_name,
-            value: trait_value,
+            methods,
         })
     }
     
+    fn peek_ahead_for_module_body(&mut self) -> bool {
+        // Look past `mod name` for the opening brace of a module definition
+        let saved_lexer = self.lexer.clone();
+        let saved_current = self.current.clone();
+        let saved_span = (self.last_start, self.position);
+        
+        self.advance();
+        self.advance();
+        let has_body = matches!(self.current, Some(Token::LBrace));
+        
+        self.lexer = saved_lexer;
+        self.current = saved_current;
+        (self.last_start, self.position) = saved_span;
+        
+        has_body
+    }
+    
     fn peek_ahead_for_as(&mut self) -> bool {
         // Save current position
         let saved_lexer = self.lexer.clone();
         let saved_current = self.current.clone();
+        let saved_span = (self.last_start, self.position);
         
         // Advance past the type name
         self.advance();
@@ -2103,6 +2541,7 @@ impl<'a> Parser<'a> {
         // Restore position
         self.lexer = saved_lexer;
         self.current = saved_current;
+        (self.last_start, self.position) = saved_span;
         
         is_as
     }
@@ -2131,29 +2570,37 @@ impl<'a> Parser<'a> {
         
         self.consume(Token::LBrace)?;
         
-        let mut definitions = vec![];
+        let mut methods = vec![];
         while !matches!(self.current, Some(Token::RBrace)) {
-            // Parse method implementations
-            let def = self.parse_definition()?;
-            definitions.push(def);
+            // Parse method implementations; each must be a function definition
+            let def = self.spanned(Self::parse_definition)?;
+            match self.graph.get_node(def) {
+                Some(Node::Define { name, value })
+                    if matches!(self.graph.get_node(*value), Some(Node::Lambda { .. })) =>
+                {
+                    if methods.iter().any(|(m, _): &(String, NodeId)| m == name) {
+                        return Err(anyhow!(
+                            "Duplicate method '{}' in {} as {}",
+                            name, type_name, trait_name
+                        ));
+                    }
+                    methods.push((name.clone(), *value));
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "Expected function definition in {} as {}",
+                        type_name, trait_name
+                    ))
+                }
+            }
         }
         
         self.consume(Token::RBrace)?;
         
-        // For now, just return a placeholder - in a real implementation
-        // we'd need a proper AST node for trait implementations
-        let impl_body = if definitions.is_empty() {
-            self.add_node(Node::Literal(Literal::Nil))?
-        } else if definitions.len() == 1 {
-            definitions[0]
-        } else {
-            self.add_node(Node::Begin { exprs: definitions })?
-        };
-        
-        // Create a define node with special naming for trait impl
-        self.add_node(Node::Define {
-            name: format!("{}@{}", type_name, trait_name),
-            value: impl_body,
+        self.add_node(Node::TraitImpl {
+            type_name,
+            trait_name,
+            methods,
         })
     }
     
@@ -2172,10 +2619,11 @@ impl<'a> Parser<'a> {
         
         self.consume(Token::Eq)?;
         
-        let type_name = self.parse_type()?;
+        let aliased = self.parse_type()?;
         
-        // Create a define node for the type alias
-        let type_value = self.add_node(Node::Variable { name: type_name })?;
+        // Create a define node for the type alias; the full type rides along as its annotation
+        let type_value = self.add_node(Node::Variable { name: aliased.to_string() })?;
+        self.annotate(type_value, aliased);
         self.add_node(Node::Define {
             name: alias_name,
             value: type_value,
@@ -2270,7 +2718,7 @@ impl<'a> Parser<'a> {
                         
                         // Parse handler body
                         self.consume(Token::LBrace)?;
-                        let body = self.parse_block_expression()?;
+                        let body = self.with_actor_handler(true, Self::parse_block_expression)?;
                         self.consume(Token::RBrace)?; // Consume closing brace of handler method
                         
                         // Create lambda with (state, msg) parameters
@@ -2306,7 +2754,7 @@ impl<'a> Parser<'a> {
     }
     
     fn parse_effect_definition(&mut self, _is_public: bool) -> Result<NodeId> {
-        // effect EffectName { def operation(args) -> Type; }
+        // effect EffectName { function operation(arg: Type) -> Type; }
         self.consume(Token::Effect)?;
         
         let effect_name = match self.current {
@@ -2322,104 +2770,305 @@ impl<'a> Parser<'a> {
         
         let mut operations = vec![];
         while !matches!(self.current, Some(Token::RBrace)) {
-            if matches!(self.current, Some(Token::Private)) || matches!(self.current, Some(Token::Public)) {
+            if matches!(self.current, Some(Token::Private) | Some(Token::Public)) {
                 self.advance();
-                
-                if matches!(self.current, Some(Token::Function)) {
+            }
+            if matches!(self.current, Some(Token::Function)) {
+                self.advance();
+            }
+            
+            let op_name = match self.current {
+                Some(Token::LowerIdent(n)) => {
+                    let name = n.to_string();
                     self.advance();
+                    name
                 }
-                
-                let op_name = match self.current {
-                    Some(Token::LowerIdent(n)) => {
-                        let name = n.to_string();
-                        self.advance();
-                        name
-                    }
-                    _ => return Err(anyhow!("Expected operation name in effect")),
-                };
-                
-                // Parse parameters
-                self.consume(Token::LParen)?;
-                while !matches!(self.current, Some(Token::RParen)) {
-                    match self.current {
-                        Some(Token::LowerIdent(_)) => {
-                            self.advance();
-                            if matches!(self.current, Some(Token::Colon)) {
-                                self.advance();
-                                self.parse_type()?;
-                            }
-                        }
-                        _ => return Err(anyhow!("Expected parameter name")),
-                    }
-                    
-                    if matches!(self.current, Some(Token::Comma)) {
+                _ => return Err(anyhow!("Expected operation name in effect")),
+            };
+            
+            // Parse parameters
+            self.consume(Token::LParen)?;
+            let mut params = vec![];
+            while !matches!(self.current, Some(Token::RParen)) {
+                match self.current {
+                    Some(Token::LowerIdent(name)) => {
+                        let name = name.to_string();
                         self.advance();
+                        let ty = self.parse_binding_annotation()?.unwrap_or(TypeExpr::Infer);
+                        params.push((name, ty));
                     }
+                    _ => return Err(anyhow!("Expected parameter name")),
                 }
-                self.consume(Token::RParen)?;
                 
-                // Skip return type if present
-                if matches!(self.current, Some(Token::Arrow)) {
+                if matches!(self.current, Some(Token::Comma)) {
                     self.advance();
-                    self.parse_type()?;
                 }
-                
-                self.consume(Token::Semicolon)?;
-                operations.push(op_name);
             }
+            self.consume(Token::RParen)?;
+            
+            let return_type = if matches!(self.current, Some(Token::Arrow)) {
+                self.advance();
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+            
+            self.consume(Token::Semicolon)?;
+            operations.push(EffectOperation {
+                name: op_name,
+                params,
+                return_type,
+            });
         }
         
         self.consume(Token::RBrace)?;
         
-        // Create a define node for the effect
-        let effect_value = self.add_node(Node::List(vec![]))?;
-        self.add_node(Node::Define {
+        self.add_node(Node::EffectDef {
             name: effect_name,
-            value: effect_value,
+            operations,
         })
     }
     
-    fn parse_type(&mut self) -> Result<String> {
-        // Simple type parsing for now - just identifiers
-        // Full implementation would handle generics, function types, etc.
+    fn parse_macro_definition(&mut self) -> Result<NodeId> {
+        // macro name(param, ...) { template }
+        self.consume(Token::Macro)?;
+        
+        let name = match self.current {
+            Some(Token::LowerIdent(name)) => {
+                let name = name.to_string();
+                self.advance();
+                name
+            }
+            _ => return Err(anyhow!("Expected macro name after 'macro'")),
+        };
+        
+        self.consume(Token::LParen)?;
+        let mut params = vec![];
+        while !matches!(self.current, Some(Token::RParen)) {
+            match self.current {
+                Some(Token::LowerIdent(param)) => {
+                    params.push(param.to_string());
+                    self.advance();
+                }
+                _ => return Err(anyhow!("Expected parameter name in macro '{}'", name)),
+            }
+            
+            if matches!(self.current, Some(Token::Comma)) {
+                self.advance();
+            }
+        }
+        self.consume(Token::RParen)?;
+        self.consume(Token::LBrace)?;
+        
+        // The template is kept as source text and instantiated at each call site
+        let body_start = self.position;
+        let mut depth = 0usize;
+        loop {
+            match self.current {
+                Some(Token::LBrace) => depth += 1,
+                Some(Token::RBrace) if depth == 0 => break,
+                Some(Token::RBrace) => depth -= 1,
+                Some(_) => {}
+                None => return Err(anyhow!("Unterminated body in macro '{}'", name)),
+            }
+            self.advance();
+        }
+        let body = self.lexer.source()[body_start..self.token_start()].trim().to_string();
+        self.consume(Token::RBrace)?;
+        
+        // Report template syntax errors at the definition rather than at each use
+        Parser::new(&body)
+            .parse_block_into(&mut Graph::new())
+            .map_err(|e| anyhow!("Invalid body in macro '{}': {}", name, e))?;
+        
+        self.add_node(Node::MacroDef { name, params, body })
+    }
+    
+    fn parse_type(&mut self) -> Result<TypeExpr> {
+        // type := base ('->' type ('with' '(' Effect, ... ')')?)?
+        let base = self.parse_base_type()?;
+        
+        if matches!(self.current, Some(Token::Arrow)) {
+            self.advance();
+            let params = match base {
+                // `(A, B) -> R` takes two parameters, `() -> R` none
+                TypeExpr::Tuple(elements) => elements,
+                other => vec![other],
+            };
+            let result = self.parse_type()?;
+            
+            // A nested function type already claimed the effect row
+            let effects = if matches!(self.current, Some(Token::With)) {
+                self.parse_effect_row()?
+            } else {
+                vec![]
+            };
+            
+            return Ok(TypeExpr::Function {
+                params,
+                result: Box::new(result),
+                effects,
+            });
+        }
+        
+        Ok(base)
+    }
+    
+    fn parse_base_type(&mut self) -> Result<TypeExpr> {
         match self.current {
-            Some(Token::UpperIdent(name)) => {
+            Some(Token::UpperIdent(name)) | Some(Token::ConstIdent(name)) => {
                 let name = name.to_string();
                 self.advance();
                 
-                // Check for generic type parameters
+                let mut args = vec![];
                 if matches!(self.current, Some(Token::Less)) {
-                    // Skip generic parameters for now
-                    let mut depth = 1;
                     self.advance();
-                    while depth > 0 && self.current.is_some() {
-                        match self.current {
-                            Some(Token::Less) => depth += 1,
-                            Some(Token::Greater) => depth -= 1,
-                            _ => {},
+                    while !matches!(self.current, Some(Token::Greater)) {
+                        args.push(self.parse_type()?);
+                        if matches!(self.current, Some(Token::Comma)) {
+                            self.advance();
+                        } else if !matches!(self.current, Some(Token::Greater)) {
+                            return Err(anyhow!("Expected ',' or '>' in type arguments of {}", name));
                         }
-                        self.advance();
                     }
+                    self.consume(Token::Greater)?;
                 }
                 
-                Ok(name)
+                Ok(TypeExpr::Named { name, args })
             }
-            Some(Token::LowerIdent(name)) if matches!(name, "string" | "int" | "float" | "bool" | "any") => {
-                // Allow primitive types as lowercase
+            Some(Token::LowerIdent(name)) => {
                 let name = name.to_string();
                 self.advance();
-                Ok(name)
+                
+                // Primitive types may be written lowercase; anything else is a type variable
+                if matches!(name.as_str(), "string" | "int" | "float" | "bool" | "any") {
+                    Ok(TypeExpr::named(name))
+                } else {
+                    Ok(TypeExpr::Variable(name))
+                }
+            }
+            Some(Token::Nil) => {
+                self.advance();
+                Ok(TypeExpr::named("Unit"))
+            }
+            Some(Token::Underscore) => {
+                self.advance();
+                Ok(TypeExpr::Infer)
+            }
+            Some(Token::LParen) => {
+                // Parenthesized type, tuple, or function parameter list
+                self.advance();
+                let mut elements = vec![];
+                while !matches!(self.current, Some(Token::RParen)) {
+                    elements.push(self.parse_type()?);
+                    if matches!(self.current, Some(Token::Comma)) {
+                        self.advance();
+                    } else if !matches!(self.current, Some(Token::RParen)) {
+                        return Err(anyhow!("Expected ',' or ')' in type"));
+                    }
+                }
+                self.consume(Token::RParen)?;
+                
+                // A parameter list stays a list when it is followed by `->`
+                let is_params = matches!(self.current, Some(Token::Arrow));
+                match elements.len() {
+                    0 if !is_params => Ok(TypeExpr::named("Unit")),
+                    1 if !is_params => Ok(elements.remove(0)),
+                    _ => Ok(TypeExpr::Tuple(elements)),
+                }
             }
             _ => Err(anyhow!("Expected type name")),
         }
     }
     
+    fn parse_effect_row(&mut self) -> Result<Vec<String>> {
+        // with(IO, State)
+        self.consume(Token::With)?;
+        self.consume(Token::LParen)?;
+        
+        let mut effects = vec![];
+        while !matches!(self.current, Some(Token::RParen)) {
+            match self.current {
+                Some(Token::UpperIdent(name)) | Some(Token::ConstIdent(name)) => {
+                    effects.push(name.to_string());
+                    self.advance();
+                }
+                _ => return Err(anyhow!("Expected effect name in effect row")),
+            }
+            if matches!(self.current, Some(Token::Comma)) {
+                self.advance();
+            } else if !matches!(self.current, Some(Token::RParen)) {
+                return Err(anyhow!("Expected ',' or ')' in effect row"));
+            }
+        }
+        self.consume(Token::RParen)?;
+        
+        Ok(effects)
+    }
+    
+    /// Parse an optional `: Type` after a binding name
+    fn parse_binding_annotation(&mut self) -> Result<Option<TypeExpr>> {
+        if matches!(self.current, Some(Token::Colon)) {
+            self.advance();
+            Ok(Some(self.parse_type()?))
+        } else {
+            Ok(None)
+        }
+    }
+    
+    /// Record a declared type on a node for the type checker
+    fn annotate(&mut self, node: NodeId, ty: TypeExpr) {
+        self.graph.metadata_mut(node).type_annotation = Some(ty);
+    }
+    
+    /// Record parameter and return annotations on a lambda, if any were written
+    fn annotate_lambda(
+        &mut self,
+        lambda: NodeId,
+        param_types: Vec<Option<TypeExpr>>,
+        return_type: Option<TypeExpr>,
+        effects: Vec<String>,
+    ) {
+        if param_types.iter().all(Option::is_none) && return_type.is_none() && effects.is_empty() {
+            return;
+        }
+        let ty = TypeExpr::Function {
+            params: param_types.into_iter().map(|t| t.unwrap_or(TypeExpr::Infer)).collect(),
+            result: Box::new(return_type.unwrap_or(TypeExpr::Infer)),
+            effects,
+        };
+        self.annotate(lambda, ty);
+    }
+    
+    /// Attach a `let` annotation to a bound value, keeping any annotation the value carries itself
+    fn annotate_binding(&mut self, value: NodeId, ty: TypeExpr) -> Result<NodeId> {
+        let value = if self.graph.get_metadata(value).and_then(|m| m.type_annotation.as_ref()).is_some() {
+            self.add_node(Node::Begin { exprs: vec![value] })?
+        } else {
+            value
+        };
+        self.annotate(value, ty);
+        Ok(value)
+    }
+    
     fn parse_struct_construction(&mut self, name: String) -> Result<NodeId> {
         // StructName { field1: value1, field2: value2 }
+        // StructName { field1: value1, ..base }
         self.consume(Token::LBrace)?;
         
         let mut fields = vec![];
+        let mut base = None;
         while !matches!(self.current, Some(Token::RBrace)) {
+            if matches!(self.current, Some(Token::DotDot)) {
+                // Functional update: remaining fields come from base
+                self.advance();
+                base = Some(self.parse_expression()?);
+                if matches!(self.current, Some(Token::Comma)) {
+                    self.advance();
+                }
+                continue;
+            }
+            
             let field_name = match self.current {
                 Some(Token::LowerIdent(n)) => {
                     let name = n.to_string();
@@ -2440,13 +3089,10 @@ impl<'a> Parser<'a> {
         
         self.consume(Token::RBrace)?;
         
-        // For now, represent struct construction as a function call
-        let constructor = self.add_node(Node::Variable { name })?;
-        let field_values: Vec<NodeId> = fields.into_iter().map(|(_, v)| v).collect();
-        self.add_node(Node::Application {
-            function: constructor,
-            args: field_values,
-        })
+        match base {
+            Some(base) => self.add_node(Node::StructUpdate { name, base, fields }),
+            None => self.add_node(Node::StructConstruct { name, fields }),
+        }
     }
     
     fn parse_match_expression(&mut self) -> Result<NodeId> {
@@ -2471,7 +3117,7 @@ impl<'a> Parser<'a> {
         
         self.consume(Token::RBrace)?;
         
-        self.add_node(Node::Match { expr, branches })
+        self.add_node(Node::Match { expr, branches, must_match: false })
     }
     
     fn parse_pattern(&mut self) -> Result<Pattern> {
@@ -2534,6 +3180,11 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Ok(Pattern::Wildcard)
             }
+            // A lone `_` can also lex as an identifier
+            Some(Token::LowerIdent("_")) => {
+                self.advance();
+                Ok(Pattern::Wildcard)
+            }
             Some(Token::LowerIdent(name)) => {
                 let name = name.to_string();
                 self.advance();
@@ -2594,11 +3245,76 @@ impl<'a> Parser<'a> {
                 self.advance();
                 Ok(Pattern::Literal(Literal::Float(f)))
             }
+            Some(Token::BigInt(n)) => {
+                let n = n.clone();
+                self.advance();
+                Ok(Pattern::Literal(Literal::BigInt(n)))
+            }
+            Some(Token::Decimal(d)) => {
+                let d = *d;
+                self.advance();
+                Ok(Pattern::Literal(Literal::Decimal(d)))
+            }
+            Some(Token::LParen) => {
+                // Tuple pattern: (first, second), or (only,) for a single element
+                // Without a comma the parentheses just group a pattern
+                self.advance();
+                let first = self.parse_pattern()?;
+                if !matches!(self.current, Some(Token::Comma)) {
+                    self.consume(Token::RParen)?;
+                    return Ok(first);
+                }
+                let mut elements = vec![first];
+                while matches!(self.current, Some(Token::Comma)) {
+                    self.advance();
+                    if matches!(self.current, Some(Token::RParen)) {
+                        break;
+                    }
+                    elements.push(self.parse_pattern()?);
+                }
+                self.consume(Token::RParen)?;
+                Ok(Pattern::Tuple(elements))
+            }
+            Some(Token::LBracket) => {
+                // List pattern: [first, second, ...rest]
+                self.advance();
+                let mut elements = vec![];
+                let mut rest = None;
+                while !matches!(self.current, Some(Token::RBracket)) {
+                    if matches!(self.current, Some(Token::DotDotDot)) {
+                        self.advance();
+                        rest = Some(self.parse_single_pattern()?);
+                        break;
+                    }
+                    elements.push(self.parse_pattern()?);
+                    if matches!(self.current, Some(Token::Comma)) {
+                        self.advance();
+                    } else if !matches!(self.current, Some(Token::RBracket)) {
+                        return Err(anyhow!("Expected ',' or ']' in list pattern"));
+                    }
+                }
+                self.consume(Token::RBracket)?;
+                
+                // Lists are matched as Cons cells ending in Nil, or in the rest pattern
+                let tail = rest.unwrap_or_else(|| Pattern::Constructor {
+                    name: "Nil".to_string(),
+                    patterns: vec![],
+                });
+                Ok(elements.into_iter().rev().fold(tail, |tail, head| Pattern::Constructor {
+                    name: "Cons".to_string(),
+                    patterns: vec![head, tail],
+                }))
+            }
             Some(Token::String(s)) => {
                 let s = s.to_string();
                 self.advance();
                 Ok(Pattern::Literal(Literal::String(s)))
             }
+            Some(Token::Bytes(b)) => {
+                let b = b.clone();
+                self.advance();
+                Ok(Pattern::Literal(Literal::Bytes(b)))
+            }
             Some(Token::Symbol(s)) => {
                 let s = s.to_string();
                 self.advance();
@@ -2637,6 +3353,13 @@ impl<'a> Parser<'a> {
         })
     }
     
+    /// The effect a name in `perform`, `handle` or `.with()` refers to
+    fn effect_type(&self, name: &str) -> Result<EffectType> {
+        Ok(EffectType::from_declared_name(name, |name| {
+            self.declared_effects.contains(name)
+        })?)
+    }
+    
     fn parse_perform_expression(&mut self) -> Result<NodeId> {
         // perform IO.print("Hello")
         self.consume(Token::Perform)?;
@@ -2650,19 +3373,8 @@ impl<'a> Parser<'a> {
                 let effect_name = name.to_string();
                 self.advance();
                 
-                // Convert string to EffectType
-                match effect_name.as_str() {
-                    "IO" => EffectType::IO,
-                    "State" => EffectType::State,
-                    "Error" => EffectType::Error,
-                    "Async" => EffectType::Async,
-                    "Time" => EffectType::Time,
-                    "Network" => EffectType::Network,
-                    "Random" => EffectType::Random,
-                    "Dom" => EffectType::Dom,
-                    "Concurrent" => EffectType::Concurrent,
-                    _ => return Err(anyhow!("Unknown effect type: {}", effect_name)),
-                }
+                // Names other than the built-in effects refer to user-declared effects
+                self.effect_type(&effect_name)?
             }
             _ => return Err(anyhow!("Expected effect type after 'perform'")),
         };
@@ -2721,18 +3433,7 @@ impl<'a> Parser<'a> {
                     let effect_name = name.to_string();
                     self.advance();
                     
-                    match effect_name.as_str() {
-                        "IO" => EffectType::IO,
-                        "State" => EffectType::State,
-                        "Error" => EffectType::Error,
-                        "Async" => EffectType::Async,
-                        "Time" => EffectType::Time,
-                        "Network" => EffectType::Network,
-                        "Random" => EffectType::Random,
-                        "Dom" => EffectType::Dom,
-                        "Concurrent" => EffectType::Concurrent,
-                        _ => return Err(anyhow!("Unknown effect type: {}", effect_name)),
-                    }
+                    self.effect_type(&effect_name)?
                 }
                 _ => return Err(anyhow!("Expected effect type in handler")),
             };
@@ -2849,13 +3550,54 @@ impl<'a> Parser<'a> {
         self.add_node(Node::Spawn { expr })
     }
     
+    fn parse_parallel_expression(&mut self) -> Result<NodeId> {
+        // parallel { branch; branch; ... }
+        self.consume(Token::Parallel)?;
+        self.consume(Token::LBrace)?;
+        
+        let mut branches = vec![];
+        while !matches!(self.current, Some(Token::RBrace)) && self.current.is_some() {
+            branches.push(self.parse_expression()?);
+            
+            if matches!(self.current, Some(Token::Semicolon)) {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        
+        self.consume(Token::RBrace)?;
+        self.add_node(Node::Parallel { branches })
+    }
+    
     // Helper methods
     
     fn advance(&mut self) {
-        self.position = self.lexer.span().end;
+        let span = self.lexer.span();
+        self.last_start = span.start;
+        self.position = span.end;
         self.current = self.lexer.next_token();
     }
     
+    /// Start offset of the current token, or the end of input
+    fn token_start(&self) -> usize {
+        if self.current.is_some() {
+            self.lexer.span().start
+        } else {
+            self.position
+        }
+    }
+    
+    /// Span of the current token, or an empty span at the end of input
+    fn token_span(&self) -> (usize, usize) {
+        if self.current.is_some() {
+            let span = self.lexer.span();
+            (span.start, span.end)
+        } else {
+            (self.position, self.position)
+        }
+    }
+    
     fn consume(&mut self, expected: Token) -> Result<()> {
         if self.current.as_ref().map(|t| std::mem::discriminant(t)) == Some(std::mem::discriminant(&expected)) {
             self.advance();
@@ -3003,7 +3745,55 @@ impl<'a> Parser<'a> {
     }
     
     fn add_node(&mut self, node: Node) -> Result<NodeId> {
-        self.graph.add_node(node).map_err(|e| anyhow!("{}", e))
+        let id = self.graph.add_node(node).map_err(|e| anyhow!("{}", e))?;
+        
+        // A node covers the token just consumed and all of its children
+        let (mut start, mut end) = (self.last_start, self.position);
+        for child in self.graph.children(id) {
+            if let Some((child_start, child_end)) = self.graph.span(child) {
+                start = start.min(child_start);
+                end = end.max(child_end);
+            }
+        }
+        self.graph.metadata_mut(id).span = Some((start, end));
+        Ok(id)
+    }
+    
+    /// Add a node that covers exactly the given span
+    fn add_node_at(&mut self, node: Node, span: (usize, usize)) -> Result<NodeId> {
+        let id = self.graph.add_node(node).map_err(|e| anyhow!("{}", e))?;
+        self.graph.metadata_mut(id).span = Some(span);
+        Ok(id)
+    }
+    
+    /// Widen a node's span to include `start..end`
+    fn extend_span(&mut self, node: NodeId, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+        let span = self.graph.metadata_mut(node).span.get_or_insert((start, end));
+        span.0 = span.0.min(start);
+        span.1 = span.1.max(end);
+    }
+    
+    /// Run a parse function with `self` meaning the actor state or not, restoring the outer context
+    fn with_actor_handler(
+        &mut self,
+        in_handler: bool,
+        parse: impl FnOnce(&mut Self) -> Result<NodeId>,
+    ) -> Result<NodeId> {
+        let saved = std::mem::replace(&mut self.in_actor_handler, in_handler);
+        let result = parse(self);
+        self.in_actor_handler = saved;
+        result
+    }
+    
+    /// Run a parse function and widen the resulting node's span to every token it consumed
+    fn spanned(&mut self, parse: impl FnOnce(&mut Self) -> Result<NodeId>) -> Result<NodeId> {
+        let start = self.token_start();
+        let node = parse(self)?;
+        self.extend_span(node, start, self.position);
+        Ok(node)
     }
     
     fn pattern_to_expression(&mut self, pattern: Pattern) -> Result<NodeId> {
@@ -3021,6 +3811,13 @@ impl<'a> Parser<'a> {
                 }
                 self.add_node(Node::Application { function: constructor, args })
             }
+            Pattern::Tuple(patterns) => {
+                let mut elements = vec![];
+                for p in patterns {
+                    elements.push(self.pattern_to_expression(p)?);
+                }
+                self.add_node(Node::Tuple(elements))
+            }
             Pattern::Or(patterns) => {
                 // For or patterns, we'll create a special "OrPattern" node
                 // This is a simplification - in a real implementation, we'd need
@@ -3239,6 +4036,13 @@ impl<'a> Parser<'a> {
                     }
                     self.add_node(Node::List(new_items))
                 }
+                Node::Tuple(items) => {
+                    let mut new_items = Vec::new();
+                    for item in items {
+                        new_items.push(self.import_subgraph_node(sub_graph, *item)?);
+                    }
+                    self.add_node(Node::Tuple(new_items))
+                }
                 Node::QualifiedVariable { module_name, variable_name } => {
                     self.add_node(Node::QualifiedVariable {
                         module_name: module_name.clone(),
@@ -13,7 +13,7 @@ pub mod flc_error;
 
 pub use error::{ErrorKind, ParseError};
 
-use fluentai_core::ast::Graph;
+use fluentai_core::ast::{Graph, NodeId};
 
 /// Parse FluentAi source code into an AST graph
 pub fn parse(source: &str) -> Result<Graph, ParseError> {
@@ -26,6 +26,27 @@ pub fn parse_flc(source: &str) -> Result<Graph, ParseError> {
     parser.parse().map_err(|e| ParseError::InvalidSyntax(e.to_string()))
 }
 
+/// Parse FLC syntax, recovering from syntax errors
+///
+/// Unlike [`parse_flc`], this does not stop at the first error: statements and
+/// definitions that fail to parse become `Error` nodes and parsing resumes at the
+/// next statement or definition. Returns the partial graph and all errors found,
+/// which is empty when the source parsed cleanly.
+pub fn parse_with_recovery(source: &str) -> (Graph, Vec<ParseError>) {
+    flc_parser::Parser::new(source).parse_recovering()
+}
+
+/// Parse the contents of a block into an existing graph
+///
+/// The statements are parsed as if they appeared between `{` and `}`, and their
+/// nodes are added to `graph` without changing its root. Returns the id of the
+/// block's value.
+pub fn parse_block_into(source: &str, graph: &mut Graph) -> Result<NodeId, ParseError> {
+    flc_parser::Parser::new(source)
+        .parse_block_into(graph)
+        .map_err(|e| ParseError::InvalidSyntax(e.to_string()))
+}
+
 /// Parse with custom allocator for better performance
 pub fn parse_with_arena<'a>(
     source: &'a str,
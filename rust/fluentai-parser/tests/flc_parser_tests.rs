@@ -352,8 +352,357 @@ User as Serializable {
 }
 "#;
         
-        let result = parse_flc(input);
-        assert!(result.is_ok(), "Failed to parse trait implementation: {:?}", result);
+        let graph = parse_flc(input).expect("Failed to parse trait implementation");
+        let impl_node = graph.nodes.values().find_map(|node| match node {
+            fluentai_core::ast::Node::TraitImpl { type_name, trait_name, methods } => {
+                Some((type_name.clone(), trait_name.clone(), methods.clone()))
+            }
+            _ => None,
+        });
+        let (type_name, trait_name, methods) = impl_node.expect("Expected TraitImpl node");
+        assert_eq!(type_name, "User");
+        assert_eq!(trait_name, "Serializable");
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].0, "to_json");
+        assert!(matches!(
+            graph.get_node(methods[0].1),
+            Some(fluentai_core::ast::Node::Lambda { params, .. }) if params == &vec!["self".to_string()]
+        ));
+    }
+    
+    #[test]
+    fn test_parse_trait_declaration() {
+        let input = r#"
+public trait Shape {
+    function area(self) -> float;
+    private function scale(self, factor: float) -> Shape;
+    function name(self);
+}
+"#;
+        
+        let graph = parse_flc(input).expect("Failed to parse trait declaration");
+        match graph.get_node(graph.root_id.unwrap()) {
+            Some(fluentai_core::ast::Node::Trait { name, methods }) => {
+                assert_eq!(name, "Shape");
+                let names: Vec<_> = methods.iter().map(|m| m.name.as_str()).collect();
+                assert_eq!(names, vec!["area", "scale", "name"]);
+                assert_eq!(
+                    methods[1].params,
+                    vec![
+                        ("self".to_string(), fluentai_core::ast::TypeExpr::Infer),
+                        ("factor".to_string(), fluentai_core::ast::TypeExpr::named("float")),
+                    ]
+                );
+                assert_eq!(methods[0].return_type, Some(fluentai_core::ast::TypeExpr::named("float")));
+                assert_eq!(methods[2].return_type, None);
+            }
+            other => panic!("Expected Trait node, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_parse_trait_method_calls() {
+        // Only method-call syntax on a trait method dispatches; the call may come first
+        let input = r#"
+[p.area(), area(p), p.map(f)];
+private trait Area { function area(self) -> int; }
+"#;
+        
+        let graph = parse_flc(input).expect("Failed to parse method calls");
+        let method_calls: Vec<_> = graph.nodes.values().filter_map(|node| match node {
+            fluentai_core::ast::Node::MethodCall { method, args } => Some((method.clone(), args.len())),
+            _ => None,
+        }).collect();
+        assert_eq!(method_calls, vec![("area".to_string(), 1)]);
+        
+        // `area(p)` and `p.map(f)` stay plain applications, and only their
+        // callees remain as variables named after the methods
+        let callees: Vec<_> = graph.nodes.values().filter_map(|node| match node {
+            fluentai_core::ast::Node::Variable { name } if name == "area" || name == "map" => {
+                Some(name.clone())
+            }
+            _ => None,
+        }).collect();
+        assert_eq!(callees.len(), 2);
+    }
+    
+    #[test]
+    fn test_parse_enum_definition() {
+        let input = "private enum Shape { Circle(float), Rect(float, float), Empty }";
+        
+        let graph = parse_flc(input).expect("Failed to parse enum");
+        match graph.get_node(graph.root_id.unwrap()) {
+            Some(fluentai_core::ast::Node::Enum { name, variants, .. }) => {
+                assert_eq!(name, "Shape");
+                let names: Vec<_> = variants.iter().map(|v| v.name.as_str()).collect();
+                assert_eq!(names, vec!["Circle", "Rect", "Empty"]);
+                assert_eq!(variants[1].fields, vec![
+                    fluentai_core::ast::TypeExpr::named("float"),
+                    fluentai_core::ast::TypeExpr::named("float")
+                ]);
+                assert!(variants[2].fields.is_empty());
+            }
+            other => panic!("Expected Enum node, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_parse_effect_definition() {
+        use fluentai_core::ast::{EffectType, Node, TypeExpr};
+        
+        let input = r#"
+private effect Logger {
+    function log(msg: string) -> int;
+    flush();
+}
+perform Logger.log("hi")
+"#;
+        
+        let graph = parse_flc(input).expect("Failed to parse effect definition");
+        let operations = graph.nodes.values().find_map(|node| match node {
+            Node::EffectDef { name, operations } if name == "Logger" => Some(operations.clone()),
+            _ => None,
+        });
+        let operations = operations.expect("Expected EffectDef node");
+        let names: Vec<_> = operations.iter().map(|op| op.name.as_str()).collect();
+        assert_eq!(names, vec!["log", "flush"]);
+        assert_eq!(operations[0].params, vec![("msg".to_string(), TypeExpr::named("string"))]);
+        assert_eq!(operations[0].return_type, Some(TypeExpr::named("int")));
+        assert_eq!(operations[1].return_type, None);
+        
+        assert!(graph.nodes.values().any(|node| matches!(
+            node,
+            Node::Effect { effect_type, operation, .. }
+                if *effect_type == EffectType::custom("Logger") && operation == "log"
+        )));
+    }
+    
+    #[test]
+    fn test_parse_perform_rejects_misspelled_builtin_effects() {
+        assert!(parse_flc("perform IO.print(\"hi\")").is_ok());
+        let error = parse_flc("perform Io.print(\"hi\")").unwrap_err().to_string();
+        assert!(error.contains("did you mean IO?"), "{}", error);
+        assert!(parse_flc("handle { 1 } with { State.get() => 0 }").is_ok());
+        assert!(parse_flc("handle { 1 } with { Stat.get() => 0 }").is_err());
+    }
+    
+    #[test]
+    fn test_parse_macro_definition() {
+        use fluentai_core::ast::Node;
+
+        let input = r#"
+macro unless(cond, body) {
+    if (cond) { nil } else { { body } }
+}
+private macro twice(x) { x + x }
+unless(false, twice(1))
+"#;
+
+        let graph = parse_flc(input).expect("Failed to parse macro definitions");
+        let mut macros: Vec<_> = graph.nodes.values().filter_map(|node| match node {
+            Node::MacroDef { name, params, body } => Some((name.clone(), params.clone(), body.clone())),
+            _ => None,
+        }).collect();
+        macros.sort();
+
+        assert_eq!(macros, vec![
+            ("twice".to_string(), vec!["x".to_string()], "x + x".to_string()),
+            (
+                "unless".to_string(),
+                vec!["cond".to_string(), "body".to_string()],
+                "if (cond) { nil } else { { body } }".to_string(),
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_parse_macro_definition_errors() {
+        assert!(parse_flc("macro broken(x) { x + }").is_err());
+        assert!(parse_flc("macro unterminated(x) { x").is_err());
+        assert!(parse_flc("macro (x) { x }").is_err());
+    }
+
+    #[test]
+    fn test_parse_loops() {
+        use fluentai_core::ast::{Node, Pattern};
+
+        let graph = parse_flc("while (x > 0) { x := x - 1 }").unwrap();
+        assert!(graph.nodes.values().any(|node| matches!(node, Node::While { .. })));
+
+        let graph = parse_flc("for Cons(head, tail) in lists { head }").unwrap();
+        assert!(graph.nodes.values().any(|node| matches!(
+            node,
+            Node::For { pattern: Pattern::Constructor { .. }, .. }
+        )));
+
+        let graph = parse_flc("for i in 0..=n { i }").unwrap();
+        assert!(graph.nodes.values().any(|node| matches!(
+            node,
+            Node::Range { inclusive: true, .. }
+        )));
+
+        let graph = parse_flc("while true { if (done) { break 42 } else { continue } }").unwrap();
+        assert!(graph.nodes.values().any(|node| matches!(node, Node::Break { value: Some(_) })));
+        assert!(graph.nodes.values().any(|node| matches!(node, Node::Continue)));
+
+        let graph = parse_flc("while true { break }").unwrap();
+        assert!(graph.nodes.values().any(|node| matches!(node, Node::Break { value: None })));
+    }
+
+    #[test]
+    fn test_parse_parallel_block() {
+        use fluentai_core::ast::Node;
+
+        let graph = parse_flc("parallel { fetch(1); fetch(2); fetch(3); }").unwrap();
+        let branch_count = graph.nodes.values().find_map(|node| match node {
+            Node::Parallel { branches } => Some(branches.len()),
+            _ => None,
+        });
+        assert_eq!(branch_count, Some(3));
+
+        assert!(parse_flc("parallel { a b }").is_err());
+    }
+
+    #[test]
+    fn test_parse_extern_declaration() {
+        use fluentai_core::ast::{Node, TypeExpr};
+
+        let input = r#"
+extern function sha256(data: String) -> String
+private extern function log_line(line: String, level: Int) with(IO)
+sha256("abc")
+"#;
+        let graph = parse_flc(input).expect("Failed to parse extern declarations");
+        let mut externs: Vec<_> = graph
+            .nodes
+            .values()
+            .filter_map(|node| match node {
+                Node::Extern { name, params, return_type, effects } => {
+                    Some((name.clone(), params.clone(), return_type.clone(), effects.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        externs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(externs.len(), 2);
+        let (name, params, return_type, effects) = &externs[0];
+        assert_eq!(name, "log_line");
+        assert_eq!(
+            params,
+            &vec![
+                ("line".to_string(), TypeExpr::named("String")),
+                ("level".to_string(), TypeExpr::named("Int")),
+            ]
+        );
+        assert_eq!(return_type, &TypeExpr::named("Unit"));
+        assert_eq!(effects, &vec!["IO".to_string()]);
+        assert_eq!(externs[1].2, TypeExpr::named("String"));
+
+        // Without a body there is nothing to infer an unannotated parameter from
+        assert!(parse_flc("extern function f(x) -> Int").is_err());
+        assert!(parse_flc("extern function f(x: Int) { x }").is_err());
+    }
+
+    #[test]
+    fn test_parse_destructuring_let() {
+        use fluentai_core::ast::{Node, Pattern};
+
+        let graph = parse_flc("let [first, ...rest] = items; first").expect("Failed to parse list pattern");
+        let branches = graph
+            .nodes
+            .values()
+            .find_map(|node| match node {
+                Node::Match { branches, must_match, .. } => {
+                    assert!(must_match, "a let pattern that does not match must raise");
+                    Some(branches.clone())
+                }
+                _ => None,
+            })
+            .expect("Destructuring let should produce a match");
+        assert_eq!(branches.len(), 1);
+        assert_eq!(
+            branches[0].0,
+            Pattern::Constructor {
+                name: "Cons".to_string(),
+                patterns: vec![Pattern::Variable("first".to_string()), Pattern::Variable("rest".to_string())],
+            }
+        );
+
+        // Record destructuring reads fields instead of calling getter functions
+        let graph = parse_flc("let {x, y} = point; x").expect("Failed to parse record destructuring");
+        let fields: Vec<_> = graph
+            .nodes
+            .values()
+            .filter_map(|node| match node {
+                Node::FieldAccess { field, .. } => Some(field.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fields.len(), 2);
+        assert!(graph.nodes.values().all(|node| !matches!(node, Node::Variable { name } if name.starts_with("get_"))));
+    }
+
+    #[test]
+    fn test_parse_tuples() {
+        use fluentai_core::ast::{Node, Pattern};
+
+        let graph = parse_flc("(1, (2, 3),)").expect("Failed to parse tuple");
+        let root = graph.root_id.unwrap();
+        match graph.get_node(root) {
+            Some(Node::Tuple(items)) => {
+                assert_eq!(items.len(), 2);
+                assert!(matches!(graph.get_node(items[1]), Some(Node::Tuple(inner)) if inner.len() == 2));
+            }
+            other => panic!("Expected tuple, got {:?}", other),
+        }
+
+        // A single parenthesized expression is grouping, a trailing comma makes a 1-tuple
+        let graph = parse_flc("(42)").unwrap();
+        assert!(!matches!(graph.get_node(graph.root_id.unwrap()), Some(Node::Tuple(_))));
+        let graph = parse_flc("(42,)").unwrap();
+        assert!(matches!(graph.get_node(graph.root_id.unwrap()), Some(Node::Tuple(items)) if items.len() == 1));
+
+        let graph = parse_flc("match(p) { (x, _) => x }").expect("Failed to parse tuple pattern");
+        let pattern = graph
+            .nodes
+            .values()
+            .find_map(|node| match node {
+                Node::Match { branches, .. } => Some(branches[0].0.clone()),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(
+            pattern,
+            Pattern::Tuple(vec![Pattern::Variable("x".to_string()), Pattern::Wildcard])
+        );
+    }
+
+    #[test]
+    fn test_parse_abandoned_parameter_pattern_leaves_no_nodes() {
+        use fluentai_core::ast::Node;
+        use fluentai_parser::parse_with_recovery;
+
+        // The guard is parsed while trying `(...)` as a parameter list
+        let (graph, errors) = parse_with_recovery("let a = (Some(x) when limit > 0);\n1");
+        assert_eq!(errors.len(), 1, "errors: {:?}", errors);
+        assert!(graph
+            .nodes
+            .values()
+            .all(|node| !matches!(node, Node::Variable { name } if name == "limit")));
+
+        // A match expression keeps producing nil when no branch matches
+        let graph = parse_flc("match(x) { 1 => 2 }").unwrap();
+        assert!(graph
+            .nodes
+            .values()
+            .any(|node| matches!(node, Node::Match { must_match: false, .. })));
+    }
+
+    #[test]
+    fn test_parse_trait_impl_rejects_non_function_members() {
+        let input = "Point as Show { private const x = 1; }";
+        assert!(parse_flc(input).is_err());
     }
     
     #[test]
@@ -415,6 +764,32 @@ private actor Counter {
         assert!(result.is_ok(), "Failed to parse actor handlers: {:?}", result);
     }
     
+    #[test]
+    fn test_parse_self_in_actor_handler() {
+        // `self` is the actor state in the handler, but a function's own parameter outside it
+        let input = r#"
+private actor Counter {
+    state: int = 0;
+    private handle message(amount: int) {
+        self + amount
+    }
+}
+private function bump(self) { self + 1 }
+"#;
+        
+        let graph = parse_flc(input).expect("Failed to parse actor handler");
+        let variables: Vec<_> = graph
+            .nodes
+            .values()
+            .filter_map(|node| match node {
+                fluentai_core::ast::Node::Variable { name } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(variables.iter().filter(|&&name| name == "self").count(), 1);
+        assert!(variables.contains(&"state"));
+    }
+    
     #[test]
     fn test_parse_handler_expressions() {
         let cases = vec![
@@ -481,4 +856,270 @@ private actor Counter {
             assert!(result.is_ok(), "Failed to parse expression {}: {:?}", desc, result);
         }
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_parse_struct_definitions() {
+        use fluentai_core::ast::{Node, TypeExpr};
+
+        let input = "private struct Point { public x: int, y: float }";
+        let graph = parse_flc(input).unwrap();
+        let fields = graph
+            .nodes
+            .values()
+            .find_map(|node| match node {
+                Node::Struct { name, fields, .. } if name == "Point" => Some(fields.clone()),
+                _ => None,
+            })
+            .expect("struct definition node");
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].name, "x");
+        assert_eq!(fields[0].field_type, TypeExpr::named("int"));
+        assert!(fields[0].is_public);
+        assert_eq!(fields[1].name, "y");
+        assert!(!fields[1].is_public);
+
+        let result = parse_flc("private struct P { x: int, x: int }");
+        assert!(result.is_err(), "Duplicate fields should be rejected");
+    }
+    
+    #[test]
+    fn test_parse_struct_construction_and_field_access() {
+        use fluentai_core::ast::Node;
+
+        let graph = parse_flc("Point { x: 1, y: 2 }").unwrap();
+        assert!(graph
+            .nodes
+            .values()
+            .any(|node| matches!(node, Node::StructConstruct { name, fields } if name == "Point" && fields.len() == 2)));
+
+        let graph = parse_flc("let p = Point { x: 1, y: 2 }; Point { x: 3, ..p }").unwrap();
+        assert!(graph
+            .nodes
+            .values()
+            .any(|node| matches!(node, Node::StructUpdate { fields, .. } if fields.len() == 1)));
+
+        let graph = parse_flc("make_line().start.x").unwrap();
+        let accessed: Vec<String> = graph
+            .nodes
+            .values()
+            .filter_map(|node| match node {
+                Node::FieldAccess { field, .. } => Some(field.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(accessed.len(), 2);
+        assert!(accessed.contains(&"start".to_string()));
+        assert!(accessed.contains(&"x".to_string()));
+    }
+    
+    #[test]
+    fn test_parse_function_type_annotations() {
+        use fluentai_core::ast::{Node, TypeExpr};
+
+        let input = "private function apply(f: (Int, String) -> Bool with(IO), xs: List<Option<T>>) -> (Int, a) { xs }";
+        let graph = parse_flc(input).unwrap();
+        let (lambda, _) = graph
+            .nodes
+            .iter()
+            .find(|(_, node)| matches!(node, Node::Lambda { .. }))
+            .expect("lambda node");
+        let annotation = graph
+            .get_metadata(*lambda)
+            .and_then(|m| m.type_annotation.clone())
+            .expect("lambda annotation");
+
+        match annotation {
+            TypeExpr::Function { params, result, effects } => {
+                assert!(effects.is_empty());
+                assert_eq!(
+                    params[0],
+                    TypeExpr::Function {
+                        params: vec![TypeExpr::named("Int"), TypeExpr::named("String")],
+                        result: Box::new(TypeExpr::named("Bool")),
+                        effects: vec!["IO".to_string()],
+                    }
+                );
+                assert_eq!(params[1].to_string(), "List<Option<T>>");
+                assert_eq!(
+                    *result,
+                    TypeExpr::Tuple(vec![TypeExpr::named("Int"), TypeExpr::Variable("a".to_string())])
+                );
+            }
+            other => panic!("Expected function annotation, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_parse_effect_row_requires_commas() {
+        assert!(parse_flc("private function run(g: () -> Int with(IO, State)) { g() }").is_ok());
+        assert!(parse_flc("private function run(g: () -> Int with(IO State)) { g() }").is_err());
+    }
+    
+    #[test]
+    fn test_parse_generic_declarations() {
+        use fluentai_core::ast::Node;
+
+        let graph = parse_flc("private struct Pair<A, B> { first: A, second: B }\nprivate enum Maybe<T> { Just(T), Nothing }").unwrap();
+        assert!(graph.nodes.values().any(|node| matches!(
+            node,
+            Node::Struct { name, type_params, .. } if name == "Pair" && type_params == &["A", "B"]
+        )));
+        assert!(graph.nodes.values().any(|node| matches!(
+            node,
+            Node::Enum { name, type_params, .. } if name == "Maybe" && type_params == &["T"]
+        )));
+
+        assert!(parse_flc("private struct Pair<A, A> { first: A }").is_err());
+    }
+    
+    #[test]
+    fn test_parse_let_and_lambda_annotations() {
+        use fluentai_core::ast::{Node, TypeExpr};
+
+        let graph = parse_flc("{ let n: Int = 1; let f = (x: Int, y) => x + y; f(n, 2) }").unwrap();
+        let annotations: Vec<String> = graph
+            .metadata
+            .values()
+            .filter_map(|m| m.type_annotation.as_ref().map(|t| t.to_string()))
+            .collect();
+        assert_eq!(annotations.len(), 2);
+        assert!(annotations.contains(&"Int".to_string()));
+        assert!(annotations.contains(&"(Int, _) -> _".to_string()));
+
+        // An annotated lambda bound with its own annotation keeps both
+        let graph = parse_flc("{ let g: (Int) -> Int = (x: Int) => x; g(1) }").unwrap();
+        let wrapped = graph.nodes.iter().any(|(id, node)| {
+            matches!(node, Node::Begin { exprs } if exprs.len() == 1)
+                && graph.get_metadata(*id).and_then(|m| m.type_annotation.as_ref())
+                    == Some(&TypeExpr::Function {
+                        params: vec![TypeExpr::named("Int")],
+                        result: Box::new(TypeExpr::named("Int")),
+                        effects: vec![],
+                    })
+        });
+        assert!(wrapped);
+    }
+    
+    #[test]
+    fn test_parse_invalid_type_annotation() {
+        assert!(parse_flc("private function f(x: List<Int) { x }").is_err());
+        assert!(parse_flc("{ let x: = 1; x }").is_err());
+    }
+    
+    #[test]
+    fn test_parse_records_spans() {
+        use fluentai_core::ast::{Literal, Node};
+        use fluentai_core::source::LineCol;
+
+        let source = "private function f(x) {\n    x * 2\n}\nf(10) + 1";
+        let graph = parse_flc(source).unwrap();
+        let text = |id| {
+            let (start, end) = graph.span(id).expect("node without span");
+            &source[start..end]
+        };
+
+        // Every node the parser creates has a span
+        for id in graph.nodes.keys() {
+            assert!(graph.span(*id).is_some(), "node {:?} has no span", graph.nodes[id]);
+        }
+
+        let find = |pred: &dyn Fn(&Node) -> bool| {
+            *graph.nodes.iter().find(|(_, node)| pred(node)).unwrap().0
+        };
+        let lambda = find(&|n| matches!(n, Node::Lambda { .. }));
+        let define = find(&|n| matches!(n, Node::Define { .. }));
+        let ten = find(&|n| matches!(n, Node::Literal(Literal::Integer(10))));
+        let times = find(&|n| matches!(n, Node::Variable { name } if name == "*"));
+        let sum = find(&|n| {
+            matches!(n, Node::Application { args, .. }
+                if args.len() == 2
+                    && matches!(graph.nodes[&args[1]], Node::Literal(Literal::Integer(1))))
+        });
+
+        assert_eq!(text(define), "private function f(x) {\n    x * 2\n}");
+        assert_eq!(text(lambda), "function f(x) {\n    x * 2\n}");
+        assert_eq!(text(ten), "10");
+        assert_eq!(text(times), "*");
+        assert_eq!(text(sum), "f(10) + 1");
+
+        let (start, end) = graph.location(times).unwrap();
+        assert_eq!(start, LineCol { line: 2, column: 7 });
+        assert_eq!(end, LineCol { line: 2, column: 8 });
+        assert_eq!(graph.location(sum).unwrap().0, LineCol { line: 4, column: 1 });
+    }
+    
+    #[test]
+    fn test_recovery_reports_every_error() {
+        use fluentai_core::ast::Node;
+        use fluentai_parser::{parse_with_recovery, ParseError};
+
+        let source = "private function a() { 1 }\n\
+                      private function b( { 2 }\n\
+                      private function c() { 3 }\n\
+                      let = 4;\n\
+                      c()";
+        let (graph, errors) = parse_with_recovery(source);
+        assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+        assert!(matches!(errors[0], ParseError::UnexpectedToken { .. }));
+
+        let defined: Vec<&str> = graph
+            .nodes
+            .values()
+            .filter_map(|node| match node {
+                Node::Define { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(defined.contains(&"a"));
+        assert!(defined.contains(&"c"));
+
+        let error_nodes = graph
+            .nodes
+            .values()
+            .filter(|node| matches!(node, Node::Error { .. }))
+            .count();
+        assert_eq!(error_nodes, 2);
+        assert!(parse_flc(source).is_err());
+    }
+    
+    #[test]
+    fn test_recovery_inside_blocks() {
+        use fluentai_core::ast::Node;
+        use fluentai_parser::parse_with_recovery;
+
+        let source = "private function f() {\n    let x = ;\n    foo(1,;\n    2\n}\nf()";
+        let (graph, errors) = parse_with_recovery(source);
+        assert_eq!(errors.len(), 2, "errors: {:?}", errors);
+        assert_eq!(errors[0].position(), Some(source.find(';').unwrap()));
+
+        // The function survives with its valid statements
+        let define = graph
+            .nodes
+            .values()
+            .find(|node| matches!(node, Node::Define { name, .. } if name == "f"));
+        assert!(define.is_some());
+        let root = graph.root_id.unwrap();
+        assert!(matches!(&graph.nodes[&root], Node::Begin { exprs } if exprs.len() == 2));
+
+        // Error nodes cover the skipped source
+        let (start, end) = graph
+            .nodes
+            .iter()
+            .find(|(_, node)| matches!(node, Node::Error { .. }))
+            .and_then(|(id, _)| graph.span(*id))
+            .unwrap();
+        assert!(source[start..end].contains(';'));
+    }
+    
+    #[test]
+    fn test_recovery_on_valid_source() {
+        use fluentai_core::ast::Node;
+        use fluentai_parser::parse_with_recovery;
+
+        let source = "private function f(x) { x + 1 }\nf(2)";
+        let (graph, errors) = parse_with_recovery(source);
+        assert!(errors.is_empty());
+        assert_eq!(graph.nodes.len(), parse_flc(source).unwrap().nodes.len());
+        assert!(!graph.nodes.values().any(|node| matches!(node, Node::Error { .. })));
+    }
+}
@@ -5,7 +5,7 @@ use fluentai_parser::parse as rust_parse;
 use fluentai_vm::{compiler::Compiler, vm::VM, Value};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList};
+use pyo3::types::{PyBytes, PyDict, PyList, PyTuple};
 use std::collections::HashMap;
 
 /// Python wrapper for AST Graph
@@ -58,7 +58,10 @@ impl PyNode {
                         match lit {
                             Literal::Integer(_) => "int",
                             Literal::Float(_) => "float",
+                            Literal::BigInt(_) => "bigint",
+                            Literal::Decimal(_) => "decimal",
                             Literal::String(_) => "string",
+                            Literal::Bytes(_) => "bytes",
                             Literal::Symbol(_) => "symbol",
                             Literal::Boolean(_) => "bool",
                             Literal::Nil => "nil",
@@ -71,7 +74,10 @@ impl PyNode {
                         match lit {
                             Literal::Integer(n) => n.to_object(py),
                             Literal::Float(f) => f.to_object(py),
+                            Literal::BigInt(n) => n.to_string().to_object(py),
+                            Literal::Decimal(d) => d.to_string().to_object(py),
                             Literal::String(s) => s.to_object(py),
+                            Literal::Bytes(b) => PyBytes::new(py, b).to_object(py),
                             Literal::Symbol(s) => s.to_object(py),
                             Literal::Boolean(b) => b.to_object(py),
                             Literal::Nil => py.None(),
@@ -159,10 +165,52 @@ impl PyNode {
                     );
                     "List"
                 }
-                Node::Match { expr, branches } => {
+                Node::Tuple(elements) => {
+                    data.insert(
+                        "elements".to_string(),
+                        elements
+                            .iter()
+                            .map(|id| id.to_string())
+                            .collect::<Vec<_>>()
+                            .to_object(py),
+                    );
+                    "Tuple"
+                }
+                Node::While { condition, body } => {
+                    data.insert(
+                        "condition_id".to_string(),
+                        condition.to_string().to_object(py),
+                    );
+                    data.insert("body_id".to_string(), body.to_string().to_object(py));
+                    "While"
+                }
+                Node::For { iterable, body, .. } => {
+                    data.insert("iterable_id".to_string(), iterable.to_string().to_object(py));
+                    data.insert("body_id".to_string(), body.to_string().to_object(py));
+                    "For"
+                }
+                Node::Range {
+                    start,
+                    end,
+                    inclusive,
+                } => {
+                    data.insert("start_id".to_string(), start.to_string().to_object(py));
+                    data.insert("end_id".to_string(), end.to_string().to_object(py));
+                    data.insert("inclusive".to_string(), inclusive.to_object(py));
+                    "Range"
+                }
+                Node::Break { value } => {
+                    if let Some(value) = value {
+                        data.insert("value_id".to_string(), value.to_string().to_object(py));
+                    }
+                    "Break"
+                }
+                Node::Continue => "Continue",
+                Node::Match { expr, branches, must_match } => {
                     data.insert("expr_id".to_string(), expr.to_string().to_object(py));
                     // Simplified pattern representation for now
                     data.insert("branches".to_string(), branches.len().to_object(py));
+                    data.insert("must_match".to_string(), must_match.to_object(py));
                     "Match"
                 }
                 Node::Async { body } => {
@@ -177,6 +225,10 @@ impl PyNode {
                     data.insert("expr_id".to_string(), expr.to_string().to_object(py));
                     "Spawn"
                 }
+                Node::Parallel { branches } => {
+                    data.insert("branch_count".to_string(), branches.len().to_object(py));
+                    "Parallel"
+                }
                 Node::Channel { .. } => "Channel",
                 Node::Send { channel, value } => {
                     data.insert("channel_id".to_string(), channel.to_string().to_object(py));
@@ -279,7 +331,7 @@ impl PyNode {
                     data.insert("handler_count".to_string(), handlers.len().to_object(py));
                     let handler_types: Vec<String> = handlers
                         .iter()
-                        .map(|(effect_type, _, _)| format!("{:?}", effect_type))
+                        .map(|(effect_type, _, _)| effect_type.to_string())
                         .collect();
                     data.insert("effect_types".to_string(), handler_types.to_object(py));
                     "Handler"
@@ -357,6 +409,106 @@ impl PyNode {
                     data.insert("value".to_string(), value.get().to_object(py));
                     "Assignment"
                 }
+                Node::Struct { name, type_params, fields, derives } => {
+                    let field_names: Vec<String> = fields.iter().map(|f| f.name.clone()).collect();
+                    data.insert("name".to_string(), name.to_object(py));
+                    data.insert("fields".to_string(), field_names.to_object(py));
+                    data.insert("type_params".to_string(), type_params.to_object(py));
+                    data.insert("derives".to_string(), derives.to_object(py));
+                    "Struct"
+                }
+                Node::StructConstruct { name, fields } => {
+                    let py_fields: HashMap<String, String> = fields
+                        .iter()
+                        .map(|(field, id)| (field.clone(), id.to_string()))
+                        .collect();
+                    data.insert("name".to_string(), name.to_object(py));
+                    data.insert("fields".to_string(), py_fields.to_object(py));
+                    "StructConstruct"
+                }
+                Node::FieldAccess { object, field } => {
+                    data.insert("object".to_string(), object.to_string().to_object(py));
+                    data.insert("field".to_string(), field.to_object(py));
+                    "FieldAccess"
+                }
+                Node::StructUpdate { name, base, fields } => {
+                    let py_fields: HashMap<String, String> = fields
+                        .iter()
+                        .map(|(field, id)| (field.clone(), id.to_string()))
+                        .collect();
+                    data.insert("name".to_string(), name.to_object(py));
+                    data.insert("base".to_string(), base.to_string().to_object(py));
+                    data.insert("fields".to_string(), py_fields.to_object(py));
+                    "StructUpdate"
+                }
+                Node::Enum { name, type_params, variants, derives } => {
+                    let variant_names: Vec<String> =
+                        variants.iter().map(|v| v.name.clone()).collect();
+                    data.insert("name".to_string(), name.to_object(py));
+                    data.insert("variants".to_string(), variant_names.to_object(py));
+                    data.insert("type_params".to_string(), type_params.to_object(py));
+                    data.insert("derives".to_string(), derives.to_object(py));
+                    "Enum"
+                }
+                Node::Trait { name, methods } => {
+                    let method_names: Vec<String> =
+                        methods.iter().map(|m| m.name.clone()).collect();
+                    data.insert("name".to_string(), name.to_object(py));
+                    data.insert("methods".to_string(), method_names.to_object(py));
+                    "Trait"
+                }
+                Node::TraitImpl { type_name, trait_name, methods } => {
+                    let py_methods: HashMap<String, String> = methods
+                        .iter()
+                        .map(|(method, id)| (method.clone(), id.to_string()))
+                        .collect();
+                    data.insert("type_name".to_string(), type_name.to_object(py));
+                    data.insert("trait_name".to_string(), trait_name.to_object(py));
+                    data.insert("methods".to_string(), py_methods.to_object(py));
+                    "TraitImpl"
+                }
+                Node::MethodCall { method, args } => {
+                    data.insert("method".to_string(), method.to_object(py));
+                    data.insert(
+                        "argument_ids".to_string(),
+                        args.iter()
+                            .map(|id| id.to_string())
+                            .collect::<Vec<_>>()
+                            .to_object(py),
+                    );
+                    "MethodCall"
+                }
+                Node::EffectDef { name, operations } => {
+                    let operation_names: Vec<String> =
+                        operations.iter().map(|op| op.name.clone()).collect();
+                    data.insert("name".to_string(), name.to_object(py));
+                    data.insert("operations".to_string(), operation_names.to_object(py));
+                    "EffectDef"
+                }
+                Node::Extern {
+                    name,
+                    params,
+                    return_type,
+                    effects,
+                } => {
+                    let param_names: Vec<String> =
+                        params.iter().map(|(param, _)| param.clone()).collect();
+                    data.insert("name".to_string(), name.to_object(py));
+                    data.insert("params".to_string(), param_names.to_object(py));
+                    data.insert("return_type".to_string(), return_type.to_string().to_object(py));
+                    data.insert("effects".to_string(), effects.to_object(py));
+                    "Extern"
+                }
+                Node::MacroDef { name, params, body } => {
+                    data.insert("name".to_string(), name.to_object(py));
+                    data.insert("params".to_string(), params.to_object(py));
+                    data.insert("body".to_string(), body.to_object(py));
+                    "MacroDef"
+                }
+                Node::Error { message } => {
+                    data.insert("message".to_string(), message.to_object(py));
+                    "Error"
+                }
             };
 
             Self {
@@ -432,16 +584,10 @@ fn compile(source: &str) -> PyResult<Vec<u8>> {
         .compile(&graph)
         .map_err(|e| PyValueError::new_err(format!("Compilation error: {}", e)))?;
 
-    // Serialize bytecode (simplified - just return opcode bytes)
-    let mut bytes = Vec::new();
-    for chunk in &bytecode.chunks {
-        for instruction in &chunk.instructions {
-            bytes.push(opcode_to_u8(&instruction.opcode));
-            bytes.extend_from_slice(&instruction.arg.to_le_bytes());
-        }
-    }
-
-    Ok(bytes)
+    // Serialize to the .fabc format
+    bytecode
+        .to_bytes()
+        .map_err(|e| PyValueError::new_err(format!("Serialization error: {}", e)))
 }
 
 /// Convert Rust Value to Python object
@@ -449,7 +595,18 @@ fn value_to_python(py: Python, value: &Value) -> PyResult<PyObject> {
     match value {
         Value::Integer(n) => Ok(n.to_object(py)),
         Value::Float(f) => Ok(f.to_object(py)),
+        Value::BigInt(n) => Ok(py
+            .import("builtins")?
+            .getattr("int")?
+            .call1((n.to_string(),))?
+            .to_object(py)),
+        Value::Decimal(d) => Ok(py
+            .import("decimal")?
+            .getattr("Decimal")?
+            .call1((d.to_string(),))?
+            .to_object(py)),
         Value::String(s) => Ok(s.to_object(py)),
+        Value::Bytes(b) => Ok(PyBytes::new(py, b).to_object(py)),
         Value::Boolean(b) => Ok(b.to_object(py)),
         Value::Nil => Ok(py.None()),
         Value::List(elements) => {
@@ -459,6 +616,13 @@ fn value_to_python(py: Python, value: &Value) -> PyResult<PyObject> {
             }
             Ok(py_list.to_object(py))
         }
+        Value::Tuple(elements) => {
+            let items = elements
+                .iter()
+                .map(|elem| value_to_python(py, elem))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(PyTuple::new(py, items).to_object(py))
+        }
         Value::Function { .. } => Ok("<function>".to_object(py)),
         Value::Map(map) => {
             let py_dict = PyDict::new(py);
@@ -481,6 +645,14 @@ fn value_to_python(py: Python, value: &Value) -> PyResult<PyObject> {
             py_dict.set_item("values", py_values)?;
             Ok(py_dict.to_object(py))
         }
+        Value::Struct { name, fields } => {
+            let py_dict = PyDict::new(py);
+            py_dict.set_item("__struct__", name)?;
+            for (field, val) in fields {
+                py_dict.set_item(field, value_to_python(py, val)?)?;
+            }
+            Ok(py_dict.to_object(py))
+        }
         Value::Module { name, exports } => {
             let py_dict = PyDict::new(py);
             py_dict.set_item("name", name)?;
@@ -511,162 +683,6 @@ fn value_to_python(py: Python, value: &Value) -> PyResult<PyObject> {
     }
 }
 
-/// Convert opcode to u8 representation
-fn opcode_to_u8(opcode: &fluentai_bytecode::Opcode) -> u8 {
-    use fluentai_bytecode::Opcode::*;
-    match opcode {
-        Push => 0,
-        Pop => 1,
-        Dup => 2,
-        Swap => 3,
-        Add => 4,
-        Sub => 5,
-        Mul => 6,
-        Div => 7,
-        Mod => 8,
-        Neg => 9,
-        AddInt => 10,
-        SubInt => 11,
-        MulInt => 12,
-        DivInt => 13,
-        Eq => 14,
-        Ne => 15,
-        Lt => 16,
-        Le => 17,
-        Gt => 18,
-        Ge => 19,
-        LtInt => 20,
-        LeInt => 21,
-        GtInt => 22,
-        GeInt => 23,
-        And => 24,
-        Or => 25,
-        Not => 26,
-        Jump => 27,
-        JumpIf => 28,
-        JumpIfNot => 29,
-        Call => 30,
-        Return => 31,
-        Load => 32,
-        Store => 33,
-        LoadGlobal => 34,
-        StoreGlobal => 35,
-        LoadLocal0 => 36,
-        LoadLocal1 => 37,
-        LoadLocal2 => 38,
-        LoadLocal3 => 39,
-        StoreLocal0 => 40,
-        StoreLocal1 => 41,
-        StoreLocal2 => 42,
-        StoreLocal3 => 43,
-        MakeFunc => 44,
-        MakeEnv => 45,
-        PopEnv => 46,
-        MakeList => 47,
-        ListHead => 48,
-        ListTail => 49,
-        ListCons => 50,
-        ListLen => 51,
-        ListEmpty => 52,
-        StrLen => 53,
-        StrConcat => 54,
-        StrUpper => 55,
-        StrLower => 56,
-        PushInt0 => 57,
-        PushInt1 => 58,
-        PushInt2 => 59,
-        PushIntSmall => 60,
-        PushTrue => 61,
-        PushFalse => 62,
-        PushNil => 63,
-        PushConst => 64,
-        Effect => 65,
-        EffectAsync => 66,
-        Await => 67,
-        Spawn => 68,
-        Channel => 69,
-        ChannelWithCapacity => 70,
-        Send => 71,
-        Receive => 72,
-        TrySend => 73,
-        TryReceive => 74,
-        Halt => 75,
-        Nop => 76,
-        MakeClosure => 77,
-        LoadCaptured => 78,
-        PopN => 79,
-        MakeCell => 80,
-        CellGet => 81,
-        CellSet => 82,
-        MakeTagged => 83,
-        GetTag => 84,
-        GetTaggedField => 85,
-        IsTagged => 86,
-        LoadModule => 87,
-        ImportBinding => 88,
-        LoadQualified => 89,
-        BeginModule => 90,
-        EndModule => 91,
-        ExportBinding => 92,
-        AddFloat => 93,
-        SubFloat => 94,
-        MulFloat => 95,
-        DivFloat => 96,
-        MakeHandler => 97,
-        InstallHandler => 98,
-        UninstallHandler => 99,
-        ImportAll => 100,
-        GcAlloc => 101,
-        GcDeref => 102,
-        GcSet => 103,
-        GcCollect => 104,
-        TailCall => 105,
-        TailReturn => 106,
-        LoopStart => 107,
-        LoopEnd => 108,
-        UpdateLocal => 109,
-        Select => 110,
-        CreateActor => 111,
-        ActorSend => 112,
-        ActorReceive => 113,
-        Become => 114,
-        Try => 115,
-        Catch => 116,
-        Finally => 117,
-        Throw => 118,
-        PushHandler => 119,
-        PopHandler => 120,
-        PromiseNew => 121,
-        PromiseAll => 122,
-        PromiseRace => 123,
-        WithTimeout => 124,
-        MakeFuture => 125,
-        PushFinally => 126,
-        EndFinally => 127,
-        LoadLocal => 128,
-        StoreLocal => 129,
-        DefineGlobal => 130,
-        LoadUpvalue => 131,
-        StoreUpvalue => 132,
-        LoadCell => 133,
-        StoreCell => 134,
-        ListGet => 135,
-        ListSet => 136,
-        MakeMap => 137,
-        MapGet => 138,
-        MapSet => 139,
-        MakeChannel => 140,
-        MakeActor => 141,
-        Perform => 142,
-        Resume => 143,
-        TryStart => 144,
-        TryStartWithFinally => 145,
-        TryEnd => 146,
-        FinallyStart => 147,
-        FinallyEnd => 148,
-    }
-}
-
 /// Benchmark function to measure parser performance
 #[pyfunction]
 fn benchmark_parser(source: &str, iterations: usize) -> PyResult<f64> {
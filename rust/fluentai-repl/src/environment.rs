@@ -5,6 +5,7 @@ use fluentai_core::ast::Graph;
 use fluentai_interpreter::{
     ExecutionMode as InterpreterExecutionMode, Interpreter, InterpreterOptions,
 };
+use fluentai_metaprogramming::MacroExpander;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -37,6 +38,8 @@ pub struct ReplEnvironment {
     mode: ExecutionMode,
     /// Tree-walking interpreter
     interpreter: Interpreter,
+    /// Macros defined so far in the session
+    macros: MacroExpander,
     /// Current working directory
     cwd: PathBuf,
     /// User-defined variables for inspection
@@ -57,6 +60,7 @@ impl ReplEnvironment {
         Ok(Self {
             mode: ExecutionMode::TreeWalking,
             interpreter,
+            macros: MacroExpander::new(),
             cwd,
             user_vars: HashMap::new(),
             debug_enabled: false,
@@ -82,7 +86,14 @@ impl ReplEnvironment {
     }
 
     /// Execute code in the current mode
+    ///
+    /// Macros defined by the code stay available to later inputs.
     pub fn execute(&mut self, graph: &Graph) -> ReplResult<String> {
+        let mut graph = graph.clone();
+        self.macros.collect_definitions(&mut graph);
+        self.macros.expand_graph(&mut graph)?;
+        let graph = &graph;
+
         match self.mode {
             ExecutionMode::TreeWalking => {
                 let value = self.interpreter.interpret(graph)?;
@@ -172,6 +183,7 @@ impl ReplEnvironment {
     /// Reset the environment
     pub fn reset(&mut self) -> ReplResult<()> {
         self.user_vars.clear();
+        self.macros = MacroExpander::new();
         let options = InterpreterOptions {
             mode: self.mode.into(),
             debug_mode: fluentai_interpreter::DebugMode {
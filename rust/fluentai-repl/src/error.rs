@@ -1,6 +1,7 @@
 //! Error types for the REPL
 
 use fluentai_interpreter::InterpreterError;
+use fluentai_metaprogramming::MetaprogrammingError;
 use fluentai_parser::ParseError;
 use rustyline::error::ReadlineError;
 use std::io;
@@ -24,6 +25,10 @@ pub enum ReplError {
     #[error("Interpreter error: {0}")]
     Interpreter(#[from] InterpreterError),
 
+    /// Macro expansion error
+    #[error("Macro error: {0}")]
+    Macro(#[from] MetaprogrammingError),
+
     /// Command error
     #[error("Command error: {0}")]
     Command(String),
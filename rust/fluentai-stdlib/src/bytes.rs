@@ -0,0 +1,370 @@
+//! Binary data functions
+//!
+//! This module provides operations on byte strings including:
+//! - construction and inspection (bytes?, bytes-length, bytes-ref)
+//! - slicing and concatenation
+//! - hex and base64 encoding
+//! - UTF-8 conversion to and from strings
+//! - endian-aware integer packing
+
+use crate::registry::{StdlibFunction, StdlibRegistry};
+use crate::value::Value;
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use fluentai_core::value::BigInt;
+
+/// Register all byte string functions
+pub fn register(registry: &mut StdlibRegistry) {
+    registry.register_all(vec![
+        // Construction and inspection
+        StdlibFunction::pure(
+            "bytes?",
+            is_bytes,
+            1,
+            Some(1),
+            "Check if value is a byte string",
+        ),
+        StdlibFunction::pure(
+            "bytes-length",
+            bytes_length,
+            1,
+            Some(1),
+            "Get the number of bytes",
+        ),
+        StdlibFunction::pure(
+            "bytes-ref",
+            bytes_ref,
+            2,
+            Some(2),
+            "Get the byte at an index as an integer",
+        ),
+        StdlibFunction::pure(
+            "list->bytes",
+            list_to_bytes,
+            1,
+            Some(1),
+            "Convert a list of integers 0-255 to bytes",
+        ),
+        StdlibFunction::pure(
+            "bytes->list",
+            bytes_to_list,
+            1,
+            Some(1),
+            "Convert bytes to a list of integers",
+        ),
+        // Slicing and concatenation
+        StdlibFunction::pure(
+            "bytes-slice",
+            bytes_slice,
+            2,
+            Some(3),
+            "Extract a slice of bytes",
+        ),
+        StdlibFunction::pure(
+            "bytes-concat",
+            bytes_concat,
+            1,
+            None,
+            "Concatenate byte strings",
+        ),
+        // Encoding
+        StdlibFunction::pure(
+            "bytes->hex",
+            bytes_to_hex,
+            1,
+            Some(1),
+            "Encode bytes as lowercase hex",
+        ),
+        StdlibFunction::pure(
+            "hex->bytes",
+            hex_to_bytes,
+            1,
+            Some(1),
+            "Decode a hex string to bytes",
+        ),
+        StdlibFunction::pure(
+            "bytes->base64",
+            bytes_to_base64,
+            1,
+            Some(1),
+            "Encode bytes as standard base64",
+        ),
+        StdlibFunction::pure(
+            "base64->bytes",
+            base64_to_bytes,
+            1,
+            Some(1),
+            "Decode a standard base64 string to bytes",
+        ),
+        // UTF-8 conversion
+        StdlibFunction::pure(
+            "string->bytes",
+            string_to_bytes,
+            1,
+            Some(1),
+            "Encode a string as UTF-8 bytes",
+        ),
+        StdlibFunction::pure(
+            "bytes->string",
+            bytes_to_string,
+            1,
+            Some(1),
+            "Decode UTF-8 bytes to a string",
+        ),
+        // Integer packing
+        StdlibFunction::pure(
+            "int->bytes",
+            int_to_bytes,
+            3,
+            Some(3),
+            "Pack an integer into width bytes with the given endianness",
+        ),
+        StdlibFunction::pure(
+            "bytes->int",
+            bytes_to_int,
+            3,
+            Some(4),
+            "Read a signed integer of width bytes at an offset",
+        ),
+        StdlibFunction::pure(
+            "bytes->uint",
+            bytes_to_uint,
+            3,
+            Some(4),
+            "Read an unsigned integer of width bytes at an offset",
+        ),
+    ]);
+}
+
+fn expect_bytes<'a>(name: &str, value: &'a Value) -> Result<&'a [u8]> {
+    match value {
+        Value::Bytes(b) => Ok(b),
+        _ => Err(anyhow!("{}: expected bytes", name)),
+    }
+}
+
+fn expect_index(name: &str, what: &str, value: &Value) -> Result<usize> {
+    match value {
+        Value::Integer(i) if *i >= 0 => Ok(*i as usize),
+        _ => Err(anyhow!("{}: expected non-negative integer {}", name, what)),
+    }
+}
+
+// Construction and inspection
+
+fn is_bytes(args: &[Value]) -> Result<Value> {
+    Ok(Value::Boolean(matches!(&args[0], Value::Bytes(_))))
+}
+
+fn bytes_length(args: &[Value]) -> Result<Value> {
+    let bytes = expect_bytes("bytes-length", &args[0])?;
+    Ok(Value::Integer(bytes.len() as i64))
+}
+
+fn bytes_ref(args: &[Value]) -> Result<Value> {
+    let bytes = expect_bytes("bytes-ref", &args[0])?;
+    let index = expect_index("bytes-ref", "index", &args[1])?;
+
+    bytes
+        .get(index)
+        .map(|byte| Value::Integer(*byte as i64))
+        .ok_or_else(|| anyhow!("bytes-ref: index out of bounds"))
+}
+
+fn list_to_bytes(args: &[Value]) -> Result<Value> {
+    let items = match &args[0] {
+        Value::List(items) => items,
+        _ => return Err(anyhow!("list->bytes: expected list")),
+    };
+
+    items
+        .iter()
+        .map(|item| match item {
+            Value::Integer(n) => {
+                u8::try_from(*n).map_err(|_| anyhow!("list->bytes: {} is not a byte", n))
+            }
+            _ => Err(anyhow!("list->bytes: expected list of integers")),
+        })
+        .collect::<Result<Vec<u8>>>()
+        .map(Value::Bytes)
+}
+
+fn bytes_to_list(args: &[Value]) -> Result<Value> {
+    let bytes = expect_bytes("bytes->list", &args[0])?;
+    Ok(Value::List(
+        bytes
+            .iter()
+            .map(|byte| Value::Integer(*byte as i64))
+            .collect(),
+    ))
+}
+
+// Slicing and concatenation
+
+fn bytes_slice(args: &[Value]) -> Result<Value> {
+    let bytes = expect_bytes("bytes-slice", &args[0])?;
+    let start = expect_index("bytes-slice", "start index", &args[1])?;
+    let end = if args.len() > 2 {
+        expect_index("bytes-slice", "end index", &args[2])?
+    } else {
+        bytes.len()
+    };
+
+    if start > end || end > bytes.len() {
+        return Err(anyhow!("bytes-slice: invalid indices"));
+    }
+
+    Ok(Value::Bytes(bytes[start..end].to_vec()))
+}
+
+fn bytes_concat(args: &[Value]) -> Result<Value> {
+    let mut result = Vec::new();
+    for arg in args {
+        result.extend_from_slice(expect_bytes("bytes-concat", arg)?);
+    }
+    Ok(Value::Bytes(result))
+}
+
+// Encoding
+
+fn bytes_to_hex(args: &[Value]) -> Result<Value> {
+    let bytes = expect_bytes("bytes->hex", &args[0])?;
+    Ok(Value::String(hex::encode(bytes)))
+}
+
+fn hex_to_bytes(args: &[Value]) -> Result<Value> {
+    let s = match &args[0] {
+        Value::String(s) => s,
+        _ => return Err(anyhow!("hex->bytes: expected string")),
+    };
+
+    hex::decode(s)
+        .map(Value::Bytes)
+        .map_err(|e| anyhow!("hex->bytes: {}", e))
+}
+
+fn bytes_to_base64(args: &[Value]) -> Result<Value> {
+    let bytes = expect_bytes("bytes->base64", &args[0])?;
+    Ok(Value::String(
+        base64::engine::general_purpose::STANDARD.encode(bytes),
+    ))
+}
+
+fn base64_to_bytes(args: &[Value]) -> Result<Value> {
+    let s = match &args[0] {
+        Value::String(s) => s,
+        _ => return Err(anyhow!("base64->bytes: expected string")),
+    };
+
+    base64::engine::general_purpose::STANDARD
+        .decode(s)
+        .map(Value::Bytes)
+        .map_err(|e| anyhow!("base64->bytes: {}", e))
+}
+
+// UTF-8 conversion
+
+fn string_to_bytes(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::String(s) => Ok(Value::Bytes(s.as_bytes().to_vec())),
+        _ => Err(anyhow!("string->bytes: expected string")),
+    }
+}
+
+fn bytes_to_string(args: &[Value]) -> Result<Value> {
+    let bytes = expect_bytes("bytes->string", &args[0])?;
+    String::from_utf8(bytes.to_vec())
+        .map(Value::String)
+        .map_err(|e| anyhow!("bytes->string: {}", e))
+}
+
+// Integer packing
+
+#[derive(Clone, Copy)]
+enum Endian {
+    Big,
+    Little,
+}
+
+fn expect_width(name: &str, value: &Value) -> Result<usize> {
+    match value {
+        Value::Integer(w @ (1 | 2 | 4 | 8)) => Ok(*w as usize),
+        _ => Err(anyhow!("{}: width must be 1, 2, 4 or 8", name)),
+    }
+}
+
+fn expect_endian(name: &str, value: &Value) -> Result<Endian> {
+    let endian = match value {
+        Value::Symbol(s) | Value::String(s) => s.as_str(),
+        _ => "",
+    };
+    match endian {
+        "big" => Ok(Endian::Big),
+        "little" => Ok(Endian::Little),
+        _ => Err(anyhow!("{}: endianness must be 'big or 'little", name)),
+    }
+}
+
+fn int_to_bytes(args: &[Value]) -> Result<Value> {
+    let n = match &args[0] {
+        Value::Integer(n) => *n,
+        _ => return Err(anyhow!("int->bytes: expected integer")),
+    };
+    let width = expect_width("int->bytes", &args[1])?;
+    let endian = expect_endian("int->bytes", &args[2])?;
+
+    // Accept anything representable as either a signed or unsigned value of this width
+    if width < 8 {
+        let bits = width as u32 * 8;
+        if n < -(1i64 << (bits - 1)) || n >= (1i64 << bits) {
+            return Err(anyhow!("int->bytes: {} does not fit in {} bytes", n, width));
+        }
+    }
+
+    let bytes = match endian {
+        Endian::Big => n.to_be_bytes()[8 - width..].to_vec(),
+        Endian::Little => n.to_le_bytes()[..width].to_vec(),
+    };
+    Ok(Value::Bytes(bytes))
+}
+
+/// Read the `width` bytes at the optional offset, most significant byte first
+fn read_int_bytes(name: &str, args: &[Value]) -> Result<(Vec<u8>, usize)> {
+    let bytes = expect_bytes(name, &args[0])?;
+    let width = expect_width(name, &args[1])?;
+    let endian = expect_endian(name, &args[2])?;
+    let offset = if args.len() > 3 {
+        expect_index(name, "offset", &args[3])?
+    } else {
+        0
+    };
+
+    let mut field = bytes
+        .get(offset..offset + width)
+        .ok_or_else(|| anyhow!("{}: not enough bytes at offset {}", name, offset))?
+        .to_vec();
+    if let Endian::Little = endian {
+        field.reverse();
+    }
+    Ok((field, width))
+}
+
+fn bytes_to_int(args: &[Value]) -> Result<Value> {
+    let (field, width) = read_int_bytes("bytes->int", args)?;
+
+    // Sign-extend into a full 8-byte buffer
+    let fill = if field[0] & 0x80 != 0 { 0xff } else { 0x00 };
+    let mut buf = [fill; 8];
+    buf[8 - width..].copy_from_slice(&field);
+    Ok(Value::Integer(i64::from_be_bytes(buf)))
+}
+
+fn bytes_to_uint(args: &[Value]) -> Result<Value> {
+    let (field, width) = read_int_bytes("bytes->uint", args)?;
+
+    let mut buf = [0; 8];
+    buf[8 - width..].copy_from_slice(&field);
+    let n = u64::from_be_bytes(buf);
+    // A u64 above i64::MAX is promoted like any other overflowing integer
+    Ok(i64::try_from(n).map_or_else(|_| Value::BigInt(BigInt::from(n)), Value::Integer))
+}
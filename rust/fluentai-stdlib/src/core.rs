@@ -7,6 +7,9 @@ use crate::registry::{StdlibFunction, StdlibRegistry};
 use crate::value::Value;
 use crate::vm_bridge::StdlibContext;
 use anyhow::{anyhow, Result};
+use fluentai_core::value::numeric::{self, ArithOp};
+use num_traits::Signed;
+use std::cmp::Ordering;
 
 /// Register all core functions
 pub fn register(registry: &mut StdlibRegistry) {
@@ -394,6 +397,8 @@ fn abs(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Integer(i) => Ok(Value::Integer(i.abs())),
         Value::Float(f) => Ok(Value::Float(f.abs())),
+        Value::BigInt(n) => Ok(Value::BigInt(n.abs())),
+        Value::Decimal(d) => Ok(Value::Decimal(d.abs())),
         _ => Err(anyhow!("abs: expected number")),
     }
 }
@@ -495,6 +500,9 @@ fn modulo(args: &[Value]) -> Result<Value> {
                 Ok(Value::Integer(a % b))
             }
         }
+        (a, b) if a.is_exact_number() && b.is_exact_number() => {
+            numeric::arith(ArithOp::Rem, a, b).map_err(|e| anyhow!("mod: {}", e))
+        }
         _ => Err(anyhow!("mod: expected integers")),
     }
 }
@@ -539,10 +547,7 @@ fn is_function(args: &[Value]) -> Result<Value> {
 }
 
 fn is_number(args: &[Value]) -> Result<Value> {
-    Ok(Value::Boolean(matches!(
-        &args[0],
-        Value::Integer(_) | Value::Float(_)
-    )))
+    Ok(Value::Boolean(args[0].is_number()))
 }
 
 // Comparison operations
@@ -553,7 +558,9 @@ fn less_than(args: &[Value]) -> Result<Value> {
         (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a < b)),
         (Value::Integer(a), Value::Float(b)) => Ok(Value::Boolean((*a as f64) < *b)),
         (Value::Float(a), Value::Integer(b)) => Ok(Value::Boolean(*a < (*b as f64))),
-        _ => Err(anyhow!("<: expected numbers")),
+        (a, b) => numeric::compare(a, b)
+            .map(|o| Value::Boolean(o.is_lt()))
+            .ok_or_else(|| anyhow!("<: expected numbers")),
     }
 }
 
@@ -563,7 +570,9 @@ fn greater_than(args: &[Value]) -> Result<Value> {
         (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a > b)),
         (Value::Integer(a), Value::Float(b)) => Ok(Value::Boolean(*a as f64 > *b)),
         (Value::Float(a), Value::Integer(b)) => Ok(Value::Boolean(*a > *b as f64)),
-        _ => Err(anyhow!(">: expected numbers")),
+        (a, b) => numeric::compare(a, b)
+            .map(|o| Value::Boolean(o.is_gt()))
+            .ok_or_else(|| anyhow!(">: expected numbers")),
     }
 }
 
@@ -573,7 +582,9 @@ fn less_equal(args: &[Value]) -> Result<Value> {
         (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a <= b)),
         (Value::Integer(a), Value::Float(b)) => Ok(Value::Boolean(*a as f64 <= *b)),
         (Value::Float(a), Value::Integer(b)) => Ok(Value::Boolean(*a <= *b as f64)),
-        _ => Err(anyhow!("<=: expected numbers")),
+        (a, b) => numeric::compare(a, b)
+            .map(|o| Value::Boolean(o.is_le()))
+            .ok_or_else(|| anyhow!("<=: expected numbers")),
     }
 }
 
@@ -583,7 +594,9 @@ fn greater_equal(args: &[Value]) -> Result<Value> {
         (Value::Float(a), Value::Float(b)) => Ok(Value::Boolean(a >= b)),
         (Value::Integer(a), Value::Float(b)) => Ok(Value::Boolean(*a as f64 >= *b)),
         (Value::Float(a), Value::Integer(b)) => Ok(Value::Boolean(*a >= *b as f64)),
-        _ => Err(anyhow!(">=: expected numbers")),
+        (a, b) => numeric::compare(a, b)
+            .map(|o| Value::Boolean(o.is_ge()))
+            .ok_or_else(|| anyhow!(">=: expected numbers")),
     }
 }
 
@@ -596,6 +609,9 @@ fn equal(args: &[Value]) -> Result<Value> {
         (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a == b)),
         (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a == b)),
         (Value::Nil, Value::Nil) => Ok(Value::Boolean(true)),
+        (a, b) if a.is_exact_number() && b.is_exact_number() => {
+            Ok(Value::Boolean(numeric::compare(a, b) == Some(Ordering::Equal)))
+        }
         _ => Ok(Value::Boolean(false)), // Different types are not equal
     }
 }
@@ -609,6 +625,9 @@ fn not_equal(args: &[Value]) -> Result<Value> {
         (Value::String(a), Value::String(b)) => Ok(Value::Boolean(a != b)),
         (Value::Boolean(a), Value::Boolean(b)) => Ok(Value::Boolean(a != b)),
         (Value::Nil, Value::Nil) => Ok(Value::Boolean(false)),
+        (a, b) if a.is_exact_number() && b.is_exact_number() => {
+            Ok(Value::Boolean(numeric::compare(a, b) != Some(Ordering::Equal)))
+        }
         _ => Ok(Value::Boolean(true)), // Different types are not equal
     }
 }
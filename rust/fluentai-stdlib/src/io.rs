@@ -38,6 +38,22 @@ pub fn register(registry: &mut StdlibRegistry) {
             vec![EffectType::IO],
             "Write content to file",
         ),
+        StdlibFunction::effectful_with_context(
+            "file-read-bytes",
+            file_read_bytes_ctx,
+            1,
+            Some(1),
+            vec![EffectType::IO],
+            "Read entire file contents as bytes",
+        ),
+        StdlibFunction::effectful_with_context(
+            "file-write-bytes",
+            file_write_bytes_ctx,
+            2,
+            Some(2),
+            vec![EffectType::IO],
+            "Write bytes to file",
+        ),
         StdlibFunction::effectful_with_context(
             "file-append",
             file_append_ctx,
@@ -134,6 +150,14 @@ fn file_write_ctx(context: &mut StdlibContext, args: &[Value]) -> Result<Value>
     perform_io_effect(context, "write_file", args)
 }
 
+fn file_read_bytes_ctx(context: &mut StdlibContext, args: &[Value]) -> Result<Value> {
+    perform_io_effect(context, "read_file_bytes", args)
+}
+
+fn file_write_bytes_ctx(context: &mut StdlibContext, args: &[Value]) -> Result<Value> {
+    perform_io_effect(context, "write_file_bytes", args)
+}
+
 fn file_append_ctx(context: &mut StdlibContext, args: &[Value]) -> Result<Value> {
     perform_io_effect(context, "append_file", args)
 }
@@ -39,6 +39,22 @@ pub trait IOHandler: Send + Sync {
     /// Handle file write operation
     fn file_write(&self, path: &str, content: &str) -> Result<()>;
 
+    /// Handle binary file read operation
+    ///
+    /// Defaults to reading the file as text.
+    fn file_read_bytes(&self, path: &str) -> Result<Vec<u8>> {
+        self.file_read(path).map(String::into_bytes)
+    }
+
+    /// Handle binary file write operation
+    ///
+    /// Defaults to writing the content as text, failing if it is not valid UTF-8.
+    fn file_write_bytes(&self, path: &str, content: &[u8]) -> Result<()> {
+        let text = std::str::from_utf8(content)
+            .map_err(|_| anyhow!("file-write-bytes: this I/O handler only writes UTF-8 text"))?;
+        self.file_write(path, text)
+    }
+
     /// Handle file append operation
     fn file_append(&self, path: &str, content: &str) -> Result<()>;
 
@@ -184,6 +200,61 @@ pub fn file_write_with_effects(args: &[Value]) -> Result<Value> {
     })
 }
 
+pub fn file_read_bytes_with_effects(args: &[Value]) -> Result<Value> {
+    if args.is_empty() {
+        return Err(anyhow!("file-read-bytes: expected 1 argument (path)"));
+    }
+
+    let path = match &args[0] {
+        Value::String(s) => s,
+        _ => return Err(anyhow!("file-read-bytes: expected string path")),
+    };
+
+    check_path_allowed(path)?;
+
+    with_io_context(|ctx| {
+        if let Some(handler) = &ctx.io_handler {
+            let content = handler.file_read_bytes(path)?;
+            Ok(Value::Bytes(content))
+        } else {
+            // Default implementation
+            let content = std::fs::read(path).map_err(|e| anyhow!("file-read-bytes: {}", e))?;
+            Ok(Value::Bytes(content))
+        }
+    })
+}
+
+pub fn file_write_bytes_with_effects(args: &[Value]) -> Result<Value> {
+    if args.len() < 2 {
+        return Err(anyhow!(
+            "file-write-bytes: expected 2 arguments (path, content)"
+        ));
+    }
+
+    let path = match &args[0] {
+        Value::String(s) => s,
+        _ => return Err(anyhow!("file-write-bytes: expected string path")),
+    };
+
+    let content = match &args[1] {
+        Value::Bytes(b) => b,
+        _ => return Err(anyhow!("file-write-bytes: expected bytes content")),
+    };
+
+    check_path_allowed(path)?;
+    check_write_allowed()?;
+
+    with_io_context(|ctx| {
+        if let Some(handler) = &ctx.io_handler {
+            handler.file_write_bytes(path, content)?;
+        } else {
+            // Default implementation
+            std::fs::write(path, content).map_err(|e| anyhow!("file-write-bytes: {}", e))?;
+        }
+        Ok(Value::Nil)
+    })
+}
+
 pub fn print_line_with_effects(args: &[Value]) -> Result<Value> {
     with_io_context(|ctx| {
         if !ctx.io_allowed {
@@ -452,6 +523,16 @@ impl IOHandler for LoggingIOHandler {
         Err(anyhow!("File operations not allowed in sandbox"))
     }
 
+    fn file_read_bytes(&self, path: &str) -> Result<Vec<u8>> {
+        self.log_operation(&format!("READ_BYTES: {}", path));
+        Err(anyhow!("File operations not allowed in sandbox"))
+    }
+
+    fn file_write_bytes(&self, path: &str, _content: &[u8]) -> Result<()> {
+        self.log_operation(&format!("WRITE_BYTES: {}", path));
+        Err(anyhow!("File operations not allowed in sandbox"))
+    }
+
     fn file_append(&self, path: &str, _content: &str) -> Result<()> {
         self.log_operation(&format!("APPEND: {}", path));
         Err(anyhow!("File operations not allowed in sandbox"))
@@ -5,6 +5,7 @@
 
 #![warn(missing_docs)]
 
+pub mod bytes;
 pub mod chars;
 pub mod collections;
 pub mod core;
@@ -38,6 +39,7 @@ pub fn init_stdlib() -> StdlibRegistry {
     core::register(&mut registry);
     strings::register(&mut registry);
     strings_extended::register(&mut registry);
+    bytes::register(&mut registry);
     chars::register(&mut registry);
     collections::register(&mut registry);
     math::register(&mut registry);
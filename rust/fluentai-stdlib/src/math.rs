@@ -1,8 +1,13 @@
 //! Mathematical functions
 
 use crate::registry::{StdlibFunction, StdlibRegistry};
-use crate::value::Value;
+use crate::value::{Value, ValueError};
 use anyhow::{anyhow, Result};
+use fluentai_core::value::numeric::{self, ArithOp};
+use fluentai_core::value::{BigInt, Decimal};
+use num_traits::{Signed, ToPrimitive, Zero};
+use rust_decimal::{MathematicalOps, RoundingStrategy};
+use std::cmp::Ordering;
 use std::f64::consts;
 
 /// Register all math functions
@@ -93,68 +98,89 @@ pub fn register(registry: &mut StdlibRegistry) {
 
 // Basic arithmetic
 
-fn add(args: &[Value]) -> Result<Value> {
-    let mut result = 0.0;
-    let mut all_ints = true;
-
-    for arg in args {
-        match arg {
-            Value::Integer(i) => result += *i as f64,
-            Value::Float(f) => {
-                result += f;
-                all_ints = false;
+/// Fold exact arguments with `op`, or return `None` if the arguments need the
+/// float fallback
+///
+/// A float mixed with a decimal or bigint is a type error rather than a lossy
+/// conversion, as in the VM's arithmetic opcodes.
+fn fold_exact(name: &str, op: ArithOp, init: Value, args: &[Value]) -> Option<Result<Value>> {
+    if !args.iter().all(Value::is_exact_number) {
+        return check_float_mix(name, args).err().map(Err);
+    }
+    Some(
+        args.iter()
+            .try_fold(init, |acc, arg| numeric::arith(op, &acc, arg))
+            .map_err(|e| anyhow!("{}: {}", name, e)),
+    )
+}
+
+/// Reject a float alongside a decimal or bigint; only integers may be widened
+/// to floats
+fn check_float_mix(name: &str, args: &[Value]) -> Result<()> {
+    if !args.iter().any(|arg| matches!(arg, Value::Float(_))) {
+        return Ok(());
+    }
+    match args
+        .iter()
+        .find(|arg| matches!(arg, Value::Decimal(_) | Value::BigInt(_)))
+    {
+        Some(exact) => Err(anyhow!(
+            "{}: {}",
+            name,
+            ValueError::TypeError {
+                expected: "float or integer",
+                actual: exact.type_name(),
             }
-            _ => return Err(anyhow!("+: expected number")),
-        }
+        )),
+        None => Ok(()),
     }
+}
 
-    if all_ints && result.fract() == 0.0 {
-        Ok(Value::Integer(result as i64))
-    } else {
-        Ok(Value::Float(result))
+fn add(args: &[Value]) -> Result<Value> {
+    if let Some(result) = fold_exact("+", ArithOp::Add, Value::Integer(0), args) {
+        return result;
     }
+
+    let mut result = 0.0;
+    for arg in args {
+        result += to_float(arg).map_err(|_| anyhow!("+: expected number"))?;
+    }
+    Ok(Value::Float(result))
 }
 
 fn subtract(args: &[Value]) -> Result<Value> {
     if args.len() == 1 {
         // Unary minus (negation)
         match &args[0] {
-            Value::Integer(n) => Ok(Value::Integer(-n)),
             Value::Float(f) => Ok(Value::Float(-f)),
+            n if n.is_exact_number() => numeric::negate(n).map_err(|e| anyhow!("-: {}", e)),
             _ => Err(anyhow!("-: expected number")),
         }
     } else {
         // Binary subtraction
         match (&args[0], &args[1]) {
-            (Value::Integer(a), Value::Integer(b)) => Ok(Value::Integer(a - b)),
-            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
-            (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 - b)),
-            (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a - *b as f64)),
+            (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                numeric::arith(ArithOp::Sub, a, b).map_err(|e| anyhow!("-: {}", e))
+            }
+            (a, b) if a.is_number() && b.is_number() => {
+                check_float_mix("-", args)?;
+                Ok(Value::Float(to_float(a)? - to_float(b)?))
+            }
             _ => Err(anyhow!("-: expected numbers")),
         }
     }
 }
 
 fn multiply(args: &[Value]) -> Result<Value> {
-    let mut result = 1.0;
-    let mut all_ints = true;
-
-    for arg in args {
-        match arg {
-            Value::Integer(i) => result *= *i as f64,
-            Value::Float(f) => {
-                result *= f;
-                all_ints = false;
-            }
-            _ => return Err(anyhow!("*: expected number")),
-        }
+    if let Some(result) = fold_exact("*", ArithOp::Mul, Value::Integer(1), args) {
+        return result;
     }
 
-    if all_ints && result.fract() == 0.0 {
-        Ok(Value::Integer(result as i64))
-    } else {
-        Ok(Value::Float(result))
+    let mut result = 1.0;
+    for arg in args {
+        result *= to_float(arg).map_err(|_| anyhow!("*: expected number"))?;
     }
+    Ok(Value::Float(result))
 }
 
 // Comparison functions
@@ -164,29 +190,24 @@ fn max(args: &[Value]) -> Result<Value> {
         return Err(anyhow!("max: expected at least one argument"));
     }
 
-    let mut result = match &args[0] {
-        Value::Integer(i) => *i as f64,
-        Value::Float(f) => *f,
-        _ => return Err(anyhow!("max: expected numbers")),
-    };
-    let mut is_float = matches!(&args[0], Value::Float(_));
-
-    for arg in &args[1..] {
-        match arg {
-            Value::Integer(i) => result = result.max(*i as f64),
-            Value::Float(f) => {
-                result = result.max(*f);
-                is_float = true;
+    // Exact numbers are compared exactly and returned unchanged
+    if args.iter().all(Value::is_exact_number) {
+        let best = args[1..].iter().fold(&args[0], |best, arg| {
+            if numeric::compare(arg, best) == Some(Ordering::Greater) {
+                arg
+            } else {
+                best
             }
-            _ => return Err(anyhow!("max: expected numbers")),
-        }
+        });
+        return Ok(best.clone());
     }
+    check_float_mix("max", args)?;
 
-    if is_float || result.fract() != 0.0 {
-        Ok(Value::Float(result))
-    } else {
-        Ok(Value::Integer(result as i64))
+    let mut result = f64::NAN;
+    for arg in args {
+        result = result.max(to_float(arg).map_err(|_| anyhow!("max: expected numbers"))?);
     }
+    Ok(Value::Float(result))
 }
 
 fn min(args: &[Value]) -> Result<Value> {
@@ -194,50 +215,40 @@ fn min(args: &[Value]) -> Result<Value> {
         return Err(anyhow!("min: expected at least one argument"));
     }
 
-    let mut result = match &args[0] {
-        Value::Integer(i) => *i as f64,
-        Value::Float(f) => *f,
-        _ => return Err(anyhow!("min: expected numbers")),
-    };
-    let mut is_float = matches!(&args[0], Value::Float(_));
-
-    for arg in &args[1..] {
-        match arg {
-            Value::Integer(i) => result = result.min(*i as f64),
-            Value::Float(f) => {
-                result = result.min(*f);
-                is_float = true;
+    // Exact numbers are compared exactly and returned unchanged
+    if args.iter().all(Value::is_exact_number) {
+        let best = args[1..].iter().fold(&args[0], |best, arg| {
+            if numeric::compare(arg, best) == Some(Ordering::Less) {
+                arg
+            } else {
+                best
             }
-            _ => return Err(anyhow!("min: expected numbers")),
-        }
+        });
+        return Ok(best.clone());
     }
+    check_float_mix("min", args)?;
 
-    if is_float || result.fract() != 0.0 {
-        Ok(Value::Float(result))
-    } else {
-        Ok(Value::Integer(result as i64))
+    let mut result = f64::NAN;
+    for arg in args {
+        result = result.min(to_float(arg).map_err(|_| anyhow!("min: expected numbers"))?);
     }
+    Ok(Value::Float(result))
 }
 
 fn divide(args: &[Value]) -> Result<Value> {
-    let divisor = match &args[1] {
-        Value::Integer(i) => *i as f64,
-        Value::Float(f) => *f,
-        _ => return Err(anyhow!("/: expected number")),
-    };
-
-    if divisor == 0.0 {
-        return Err(anyhow!("/: division by zero"));
-    }
-
     match (&args[0], &args[1]) {
-        (Value::Integer(a), Value::Integer(b)) => {
-            // Integer division for int/int
-            Ok(Value::Integer(a / b))
+        // Integer division for int/int, exact division for decimals
+        (a, b) if a.is_exact_number() && b.is_exact_number() => {
+            numeric::arith(ArithOp::Div, a, b).map_err(|e| anyhow!("/: {}", e))
+        }
+        (a, b) if a.is_number() && b.is_number() => {
+            check_float_mix("/", args)?;
+            let divisor = to_float(b)?;
+            if divisor == 0.0 {
+                return Err(anyhow!("/: division by zero"));
+            }
+            Ok(Value::Float(to_float(a)? / divisor))
         }
-        (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a / b)),
-        (Value::Integer(a), Value::Float(b)) => Ok(Value::Float(*a as f64 / b)),
-        (Value::Float(a), Value::Integer(b)) => Ok(Value::Float(a / *b as f64)),
         _ => Err(anyhow!("/: expected numbers")),
     }
 }
@@ -248,6 +259,8 @@ fn is_zero(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Integer(i) => Ok(Value::Boolean(*i == 0)),
         Value::Float(f) => Ok(Value::Boolean(*f == 0.0)),
+        Value::BigInt(n) => Ok(Value::Boolean(n.is_zero())),
+        Value::Decimal(d) => Ok(Value::Boolean(d.is_zero())),
         _ => Err(anyhow!("zero?: expected number")),
     }
 }
@@ -255,6 +268,7 @@ fn is_zero(args: &[Value]) -> Result<Value> {
 fn is_even(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Integer(i) => Ok(Value::Boolean(i % 2 == 0)),
+        Value::BigInt(n) => Ok(Value::Boolean((n % 2u8).is_zero())),
         _ => Err(anyhow!("even?: expected integer")),
     }
 }
@@ -262,6 +276,7 @@ fn is_even(args: &[Value]) -> Result<Value> {
 fn is_odd(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Integer(i) => Ok(Value::Boolean(i % 2 != 0)),
+        Value::BigInt(n) => Ok(Value::Boolean(!(n % 2u8).is_zero())),
         _ => Err(anyhow!("odd?: expected integer")),
     }
 }
@@ -269,6 +284,8 @@ fn is_odd(args: &[Value]) -> Result<Value> {
 fn sign(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::Integer(i) => Ok(Value::Integer(i.signum())),
+        Value::BigInt(n) => Ok(Value::Integer(n.signum().to_i64().unwrap_or(0))),
+        Value::Decimal(d) => Ok(Value::Integer(d.signum().to_i64().unwrap_or(0))),
         Value::Float(f) => {
             if f > &0.0 {
                 Ok(Value::Integer(1))
@@ -293,11 +310,7 @@ fn ln(args: &[Value]) -> Result<Value> {
 
 // Helper to convert Value to f64
 fn to_float(value: &Value) -> Result<f64> {
-    match value {
-        Value::Integer(i) => Ok(*i as f64),
-        Value::Float(f) => Ok(*f),
-        _ => Err(anyhow!("expected number")),
-    }
+    value.as_number().map_err(|_| anyhow!("expected number"))
 }
 
 // Helper to convert f64 result back to appropriate Value
@@ -407,14 +420,20 @@ fn log2(args: &[Value]) -> Result<Value> {
 fn pow(args: &[Value]) -> Result<Value> {
     match (&args[0], &args[1]) {
         (Value::Integer(base), Value::Integer(exp)) if *exp >= 0 => {
-            // For positive integer exponents, we can use integer arithmetic
-            let result = (*base as f64).powi(*exp as i32);
-            if result.fract() == 0.0 && result >= i64::MIN as f64 && result <= i64::MAX as f64 {
-                Ok(Value::Integer(result as i64))
-            } else {
-                Ok(Value::Float(result))
-            }
+            // Non-negative integer exponents are exact, promoting on overflow
+            let exp = u32::try_from(*exp).map_err(|_| anyhow!("pow: exponent too large"))?;
+            Ok(base
+                .checked_pow(exp)
+                .map_or_else(|| Value::BigInt(BigInt::from(*base).pow(exp)), Value::Integer))
         }
+        (Value::BigInt(base), Value::Integer(exp)) if *exp >= 0 => {
+            let exp = u32::try_from(*exp).map_err(|_| anyhow!("pow: exponent too large"))?;
+            Ok(Value::BigInt(base.pow(exp)))
+        }
+        (Value::Decimal(base), Value::Integer(exp)) => base
+            .checked_powi(*exp)
+            .map(Value::Decimal)
+            .ok_or_else(|| anyhow!("pow: decimal overflow")),
         _ => {
             // For all other cases, use floating point
             let base = to_float(&args[0])?;
@@ -425,41 +444,66 @@ fn pow(args: &[Value]) -> Result<Value> {
 }
 
 fn sqrt(args: &[Value]) -> Result<Value> {
-    let x = to_float(&args[0])?;
-    Ok(Value::Float(x.sqrt()))
+    match &args[0] {
+        Value::Decimal(d) => d
+            .sqrt()
+            .map(Value::Decimal)
+            .ok_or_else(|| anyhow!("sqrt: decimal argument must be non-negative")),
+        x => Ok(Value::Float(to_float(x)?.sqrt())),
+    }
 }
 
 // Rounding functions
 
 fn ceil(args: &[Value]) -> Result<Value> {
-    let x = to_float(&args[0])?;
-    Ok(Value::Float(x.ceil()))
+    match &args[0] {
+        Value::Decimal(d) => Ok(Value::Decimal(d.ceil())),
+        Value::BigInt(n) => Ok(Value::BigInt(n.clone())),
+        x => Ok(Value::Float(to_float(x)?.ceil())),
+    }
 }
 
 fn floor(args: &[Value]) -> Result<Value> {
-    let x = to_float(&args[0])?;
-    Ok(Value::Float(x.floor()))
+    match &args[0] {
+        Value::Decimal(d) => Ok(Value::Decimal(d.floor())),
+        Value::BigInt(n) => Ok(Value::BigInt(n.clone())),
+        x => Ok(Value::Float(to_float(x)?.floor())),
+    }
 }
 
 fn round(args: &[Value]) -> Result<Value> {
-    let x = to_float(&args[0])?;
-    Ok(Value::Float(x.round()))
+    match &args[0] {
+        Value::Decimal(d) => Ok(Value::Decimal(d.round_dp_with_strategy(0, RoundingStrategy::MidpointAwayFromZero))),
+        Value::BigInt(n) => Ok(Value::BigInt(n.clone())),
+        x => Ok(Value::Float(to_float(x)?.round())),
+    }
 }
 
 fn round_to(args: &[Value]) -> Result<Value> {
-    let x = to_float(&args[0])?;
     let places = match &args[1] {
         Value::Integer(i) => *i,
         _ => return Err(anyhow!("round-to: expected integer for decimal places")),
     };
 
+    if let Value::Decimal(d) = &args[0] {
+        let places = u32::try_from(places)
+            .map_err(|_| anyhow!("round-to: decimal places must be non-negative"))?;
+        return Ok(Value::Decimal(
+            d.round_dp_with_strategy(places, RoundingStrategy::MidpointAwayFromZero),
+        ));
+    }
+
+    let x = to_float(&args[0])?;
     let multiplier = 10.0_f64.powi(places as i32);
     Ok(Value::Float((x * multiplier).round() / multiplier))
 }
 
 fn trunc(args: &[Value]) -> Result<Value> {
-    let x = to_float(&args[0])?;
-    Ok(from_float(x.trunc()))
+    match &args[0] {
+        Value::Decimal(d) => Ok(Value::Decimal(d.trunc())),
+        Value::BigInt(n) => Ok(Value::BigInt(n.clone())),
+        x => Ok(from_float(to_float(x)?.trunc())),
+    }
 }
 
 // Constants
@@ -505,12 +549,9 @@ fn factorial(args: &[Value]) -> Result<Value> {
     }
 
     if n > 20 {
-        // Factorial grows very quickly, use float for large values
-        let mut result = 1.0;
-        for i in 2..=n {
-            result *= i as f64;
-        }
-        Ok(Value::Float(result))
+        // 21! no longer fits in an i64
+        let result = (2..=n).fold(BigInt::from(1), |acc, i| acc * i);
+        Ok(Value::BigInt(result))
     } else {
         let mut result = 1i64;
         for i in 2..=n {
@@ -561,7 +602,8 @@ fn lcm(args: &[Value]) -> Result<Value> {
             Value::Integer(g) => g,
             _ => unreachable!(),
         };
-        Ok(Value::Integer(a * b / gcd_val))
+        numeric::arith(ArithOp::Mul, &Value::Integer(a / gcd_val), &Value::Integer(b))
+            .map_err(|e| anyhow!("lcm: {}", e))
     }
 }
 
@@ -570,26 +612,16 @@ fn lcm(args: &[Value]) -> Result<Value> {
 fn sum(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::List(items) => {
-            let mut int_sum = 0i64;
-            let mut float_sum = 0.0;
-            let mut has_float = false;
-
-            for item in items {
-                match item {
-                    Value::Integer(i) => int_sum += i,
-                    Value::Float(f) => {
-                        has_float = true;
-                        float_sum += f;
-                    }
-                    _ => return Err(anyhow!("sum: list must contain only numbers")),
-                }
+            if let Some(result) = fold_exact("sum", ArithOp::Add, Value::Integer(0), items) {
+                return result;
             }
 
-            if has_float {
-                Ok(Value::Float(int_sum as f64 + float_sum))
-            } else {
-                Ok(Value::Integer(int_sum))
+            let mut total = 0.0;
+            for item in items {
+                total += to_float(item)
+                    .map_err(|_| anyhow!("sum: list must contain only numbers"))?;
             }
+            Ok(Value::Float(total))
         }
         _ => Err(anyhow!("sum: expected list")),
     }
@@ -598,26 +630,16 @@ fn sum(args: &[Value]) -> Result<Value> {
 fn product(args: &[Value]) -> Result<Value> {
     match &args[0] {
         Value::List(items) => {
-            let mut int_product = 1i64;
-            let mut float_product = 1.0;
-            let mut has_float = false;
-
-            for item in items {
-                match item {
-                    Value::Integer(i) => int_product *= i,
-                    Value::Float(f) => {
-                        has_float = true;
-                        float_product *= f;
-                    }
-                    _ => return Err(anyhow!("product: list must contain only numbers")),
-                }
+            if let Some(result) = fold_exact("product", ArithOp::Mul, Value::Integer(1), items) {
+                return result;
             }
 
-            if has_float {
-                Ok(Value::Float(int_product as f64 * float_product))
-            } else {
-                Ok(Value::Integer(int_product))
+            let mut total = 1.0;
+            for item in items {
+                total *= to_float(item)
+                    .map_err(|_| anyhow!("product: list must contain only numbers"))?;
             }
+            Ok(Value::Float(total))
         }
         _ => Err(anyhow!("product: expected list")),
     }
@@ -630,11 +652,13 @@ fn mean(args: &[Value]) -> Result<Value> {
                 return Err(anyhow!("mean: empty list"));
             }
 
-            let sum_val = sum(args)?;
-            match sum_val {
-                Value::Integer(i) => Ok(Value::Float(i as f64 / items.len() as f64)),
-                Value::Float(f) => Ok(Value::Float(f / items.len() as f64)),
-                _ => unreachable!(),
+            match sum(args)? {
+                // The mean of decimals stays exact
+                Value::Decimal(d) => d
+                    .checked_div(Decimal::from(items.len()))
+                    .map(Value::Decimal)
+                    .ok_or_else(|| anyhow!("mean: decimal overflow")),
+                total => Ok(Value::Float(to_float(&total)? / items.len() as f64)),
             }
         }
         _ => Err(anyhow!("mean: expected list")),
@@ -642,6 +666,21 @@ fn mean(args: &[Value]) -> Result<Value> {
 }
 
 fn clamp(args: &[Value]) -> Result<Value> {
+    if args.iter().all(Value::is_exact_number) {
+        if numeric::compare(&args[1], &args[2]) == Some(Ordering::Greater) {
+            return Err(anyhow!("clamp: min must be less than or equal to max"));
+        }
+        let clamped = if numeric::compare(&args[0], &args[1]) == Some(Ordering::Less) {
+            &args[1]
+        } else if numeric::compare(&args[0], &args[2]) == Some(Ordering::Greater) {
+            &args[2]
+        } else {
+            &args[0]
+        };
+        return Ok(clamped.clone());
+    }
+    check_float_mix("clamp", args)?;
+
     let value = to_float(&args[0])?;
     let min = to_float(&args[1])?;
     let max = to_float(&args[2])?;
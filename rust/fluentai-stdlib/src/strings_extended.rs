@@ -356,6 +356,13 @@ fn format_value(value: &Value, machine_readable: bool) -> String {
                 .collect();
             format!("{}({})", tag, formatted.join(", "))
         }
+        Value::Struct { name, fields } => {
+            let formatted: Vec<String> = fields
+                .iter()
+                .map(|(k, v)| format!("{}: {}", k, format_value(v, machine_readable)))
+                .collect();
+            format!("{} {{ {} }}", name, formatted.join(", "))
+        }
         _ => "<unprintable>".to_string(),
     }
 }
@@ -0,0 +1,163 @@
+//! Integration tests for byte string functions
+
+use fluentai_stdlib::{init_stdlib, value::Value};
+
+fn call(name: &str, args: &[Value]) -> anyhow::Result<Value> {
+    init_stdlib().get(name).unwrap().call(args)
+}
+
+fn bytes(b: &[u8]) -> Value {
+    Value::Bytes(b.to_vec())
+}
+
+#[test]
+fn test_bytes_inspection() {
+    assert_eq!(
+        call("bytes?", &[bytes(b"abc")]).unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        call("bytes?", &[Value::String("abc".to_string())]).unwrap(),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        call("bytes-length", &[bytes(b"abc")]).unwrap(),
+        Value::Integer(3)
+    );
+    assert_eq!(
+        call("bytes-ref", &[bytes(&[0x00, 0xff]), Value::Integer(1)]).unwrap(),
+        Value::Integer(255)
+    );
+    assert!(call("bytes-ref", &[bytes(b"a"), Value::Integer(1)]).is_err());
+}
+
+#[test]
+fn test_bytes_list_conversion() {
+    let list = Value::List(vec![Value::Integer(1), Value::Integer(255)]);
+    assert_eq!(
+        call("list->bytes", &[list.clone()]).unwrap(),
+        bytes(&[1, 255])
+    );
+    assert_eq!(call("bytes->list", &[bytes(&[1, 255])]).unwrap(), list);
+
+    let out_of_range = Value::List(vec![Value::Integer(256)]);
+    assert!(call("list->bytes", &[out_of_range]).is_err());
+}
+
+#[test]
+fn test_bytes_slice_and_concat() {
+    let data = bytes(b"GIF89a");
+    assert_eq!(
+        call(
+            "bytes-slice",
+            &[data.clone(), Value::Integer(0), Value::Integer(3)]
+        )
+        .unwrap(),
+        bytes(b"GIF")
+    );
+    assert_eq!(
+        call("bytes-slice", &[data.clone(), Value::Integer(3)]).unwrap(),
+        bytes(b"89a")
+    );
+    assert!(call("bytes-slice", &[data, Value::Integer(4), Value::Integer(2)]).is_err());
+
+    assert_eq!(
+        call("bytes-concat", &[bytes(b"ab"), bytes(b""), bytes(b"c")]).unwrap(),
+        bytes(b"abc")
+    );
+}
+
+#[test]
+fn test_hex_and_base64() {
+    let data = bytes(&[0xde, 0xad, 0xbe, 0xef]);
+    let hex = call("bytes->hex", &[data.clone()]).unwrap();
+    assert_eq!(hex, Value::String("deadbeef".to_string()));
+    assert_eq!(call("hex->bytes", &[hex]).unwrap(), data);
+    assert!(call("hex->bytes", &[Value::String("abc".to_string())]).is_err());
+
+    let encoded = call("bytes->base64", &[bytes(b"hello")]).unwrap();
+    assert_eq!(encoded, Value::String("aGVsbG8=".to_string()));
+    assert_eq!(call("base64->bytes", &[encoded]).unwrap(), bytes(b"hello"));
+}
+
+#[test]
+fn test_utf8_conversion() {
+    let encoded = call("string->bytes", &[Value::String("héllo".to_string())]).unwrap();
+    assert_eq!(encoded, bytes("héllo".as_bytes()));
+    assert_eq!(
+        call("bytes->string", &[encoded]).unwrap(),
+        Value::String("héllo".to_string())
+    );
+    assert!(call("bytes->string", &[bytes(&[0xff, 0xfe])]).is_err());
+}
+
+#[test]
+fn test_integer_packing() {
+    let big = Value::Symbol("big".to_string());
+    let little = Value::Symbol("little".to_string());
+
+    assert_eq!(
+        call(
+            "int->bytes",
+            &[Value::Integer(0x1234), Value::Integer(2), big.clone()]
+        )
+        .unwrap(),
+        bytes(&[0x12, 0x34])
+    );
+    assert_eq!(
+        call(
+            "int->bytes",
+            &[Value::Integer(-2), Value::Integer(4), little.clone()]
+        )
+        .unwrap(),
+        bytes(&[0xfe, 0xff, 0xff, 0xff])
+    );
+    assert!(call(
+        "int->bytes",
+        &[Value::Integer(256), Value::Integer(1), big.clone()]
+    )
+    .is_err());
+    assert!(call(
+        "int->bytes",
+        &[Value::Integer(1), Value::Integer(3), big.clone()]
+    )
+    .is_err());
+
+    let data = bytes(&[0x00, 0xff, 0xfe]);
+    assert_eq!(
+        call(
+            "bytes->int",
+            &[
+                data.clone(),
+                Value::Integer(2),
+                little.clone(),
+                Value::Integer(1)
+            ]
+        )
+        .unwrap(),
+        Value::Integer(-257)
+    );
+    assert_eq!(
+        call(
+            "bytes->uint",
+            &[
+                data.clone(),
+                Value::Integer(2),
+                big.clone(),
+                Value::Integer(1)
+            ]
+        )
+        .unwrap(),
+        Value::Integer(0xfffe)
+    );
+    assert!(call("bytes->int", &[data, Value::Integer(4), big.clone()]).is_err());
+
+    // Unsigned 64-bit values beyond i64::MAX are promoted to big integers
+    let max = bytes(&[0xff; 8]);
+    assert_eq!(
+        call("bytes->uint", &[max, Value::Integer(8), big])
+            .unwrap()
+            .to_string(),
+        u64::MAX.to_string()
+    );
+}
@@ -1,8 +1,80 @@
 //! Tests for io_effects module
 
-use fluentai_stdlib::io_effects::{self, set_io_context, IOEffectContext, LoggingIOHandler};
+use anyhow::{anyhow, Result};
+use fluentai_stdlib::io_effects::{
+    self, set_io_context, IOEffectContext, IOHandler, LoggingIOHandler,
+};
 use fluentai_stdlib::value::Value;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Handler that keeps text files in memory and implements only the required methods
+#[derive(Default)]
+struct MemoryIOHandler {
+    files: Mutex<HashMap<String, String>>,
+}
+
+impl IOHandler for MemoryIOHandler {
+    fn file_read(&self, path: &str) -> Result<String> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| anyhow!("no such file: {}", path))
+    }
+
+    fn file_write(&self, path: &str, content: &str) -> Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), content.to_string());
+        Ok(())
+    }
+
+    fn file_append(&self, path: &str, content: &str) -> Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .entry(path.to_string())
+            .or_default()
+            .push_str(content);
+        Ok(())
+    }
+
+    fn file_delete(&self, path: &str) -> Result<()> {
+        self.files.lock().unwrap().remove(path);
+        Ok(())
+    }
+
+    fn file_exists(&self, path: &str) -> Result<bool> {
+        Ok(self.files.lock().unwrap().contains_key(path))
+    }
+
+    fn dir_list(&self, _path: &str) -> Result<Vec<String>> {
+        Ok(self.files.lock().unwrap().keys().cloned().collect())
+    }
+
+    fn dir_create(&self, _path: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn current_directory(&self) -> Result<String> {
+        Ok("/".to_string())
+    }
+
+    fn read_line(&self) -> Result<String> {
+        Err(anyhow!("no input"))
+    }
+
+    fn print(&self, _content: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn print_line(&self, _content: &str) -> Result<()> {
+        Ok(())
+    }
+}
 
 #[test]
 fn test_print_with_effects() {
@@ -106,6 +178,41 @@ fn test_file_operations_with_logging_handler() {
     set_io_context(IOEffectContext::default());
 }
 
+#[test]
+fn test_bytes_operations_default_to_text_handler_methods() {
+    // Reset to default first
+    set_io_context(IOEffectContext::default());
+
+    let handler = Arc::new(MemoryIOHandler::default());
+    let ctx = IOEffectContext {
+        io_allowed: true,
+        allowed_paths: None,
+        read_only: false,
+        io_handler: Some(handler.clone()),
+    };
+    set_io_context(ctx);
+
+    let result = io_effects::file_write_bytes_with_effects(&[
+        Value::String("note.txt".to_string()),
+        Value::Bytes(b"hello".to_vec()),
+    ]);
+    assert_eq!(result.unwrap(), Value::Nil);
+    assert_eq!(handler.file_read("note.txt").unwrap(), "hello");
+
+    let result = io_effects::file_read_bytes_with_effects(&[Value::String("note.txt".to_string())]);
+    assert_eq!(result.unwrap(), Value::Bytes(b"hello".to_vec()));
+
+    // Content that is not text cannot go through the text methods
+    let result = io_effects::file_write_bytes_with_effects(&[
+        Value::String("image.png".to_string()),
+        Value::Bytes(vec![0x89, 0xff]),
+    ]);
+    assert!(result.unwrap_err().to_string().contains("UTF-8"));
+
+    // Reset context after test
+    set_io_context(IOEffectContext::default());
+}
+
 #[test]
 fn test_directory_operations_with_logging_handler() {
     // Reset to default first
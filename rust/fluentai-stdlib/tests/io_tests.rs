@@ -35,6 +35,31 @@ fn test_file_write_and_read() {
     assert_eq!(result.unwrap(), Value::String(content.to_string()));
 }
 
+#[test]
+fn test_file_write_and_read_bytes() {
+    let dir_path = test_dir();
+    let file_path = dir_path.join("test.bin");
+    let file_path_str = file_path.to_str().unwrap();
+
+    // Bytes that are not valid UTF-8 must survive the round trip
+    let content = vec![0x89, b'P', b'N', b'G', 0x00, 0xff];
+    let result = file_write_bytes_with_effects(&[
+        Value::String(file_path_str.to_string()),
+        Value::Bytes(content.clone()),
+    ]);
+    assert_eq!(result.unwrap(), Value::Nil);
+
+    let result = file_read_bytes_with_effects(&[Value::String(file_path_str.to_string())]);
+    assert_eq!(result.unwrap(), Value::Bytes(content));
+
+    // The binary writer only accepts bytes
+    let result = file_write_bytes_with_effects(&[
+        Value::String(file_path_str.to_string()),
+        Value::String("text".to_string()),
+    ]);
+    assert!(result.is_err());
+}
+
 #[test]
 fn test_file_append() {
     let dir_path = test_dir();
@@ -1,5 +1,6 @@
 //! Tests for math stdlib functions
 
+use fluentai_core::value::{BigInt, Decimal};
 use fluentai_stdlib::init_stdlib;
 use fluentai_stdlib::value::Value;
 
@@ -384,3 +385,45 @@ fn test_zero_predicate() {
         Value::Boolean(false)
     );
 }
+
+#[test]
+fn test_floats_do_not_mix_with_exact_numbers() {
+    let stdlib = init_stdlib();
+    let tenth = Value::Decimal(Decimal::new(1, 1));
+    let big = Value::BigInt(BigInt::from(i64::MAX) * 2);
+
+    let sum = stdlib.get("sum").unwrap();
+    let err = sum
+        .call(&[Value::List(vec![tenth.clone(), Value::Float(0.2)])])
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("got decimal"),
+        "unexpected error: {}",
+        err
+    );
+
+    for name in ["+", "-", "*", "/", "max", "min"] {
+        let f = stdlib.get(name).unwrap();
+        assert!(
+            f.call(&[big.clone(), Value::Float(1.5)]).is_err(),
+            "{}",
+            name
+        );
+        assert!(
+            f.call(&[Value::Float(1.5), tenth.clone()]).is_err(),
+            "{}",
+            name
+        );
+    }
+    let clamp = stdlib.get("clamp").unwrap();
+    assert!(clamp
+        .call(&[tenth, Value::Float(0.0), Value::Integer(1)])
+        .is_err());
+
+    // Integers still widen to floats
+    assert_eq!(
+        sum.call(&[Value::List(vec![Value::Integer(1), Value::Float(0.5)])])
+            .unwrap(),
+        Value::Float(1.5)
+    );
+}
@@ -2,6 +2,7 @@
 
 use crate::{
     environment::TypeEnvironment,
+    exhaustiveness::{check_matches, MatchIssue},
     inference::{TypeError, TypeInferencer},
     types::*,
 };
@@ -172,7 +173,23 @@ impl TypeChecker {
         }
 
         // Generate warnings
-        let warnings = self.generate_warnings(&types, graph);
+        let mut warnings = self.generate_warnings(&types, graph);
+
+        // Check match expressions for missing and unreachable arms
+        for issue in check_matches(graph) {
+            let location = self.get_location(issue.node());
+            match issue {
+                MatchIssue::NonExhaustive { missing, .. } => errors.push(TypeCheckError {
+                    location,
+                    error: TypeError::NonExhaustiveMatch(missing),
+                    context: "Add an arm for the missing case or a wildcard `_` arm".to_string(),
+                }),
+                MatchIssue::UnreachableArm { .. } => warnings.push(TypeCheckWarning {
+                    location,
+                    message: issue.to_string(),
+                }),
+            }
+        }
 
         let success = errors.is_empty();
 
@@ -391,6 +408,33 @@ mod tests {
         assert!(effect_errors.is_empty());
     }
 
+    #[test]
+    fn test_match_exhaustiveness() {
+        let result = check_code(
+            "private enum Light { Red, Amber, Green }\nmatch (Red) { Red => 0, Green => 2, _ => 1, Amber => 3 }",
+        );
+        assert!(result.success);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.message == "Match arm 4 is unreachable"));
+
+        let result = check_code(
+            "private enum Light { Red, Amber, Green }\nmatch (Red) { Red => 0, Green => 2 }",
+        );
+        assert!(!result.success);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| matches!(&e.error, TypeError::NonExhaustiveMatch(m) if m == "Amber")));
+    }
+
+    #[test]
+    fn test_refutable_let_pattern_type_checks() {
+        let result = check_code("{ let xs = [1, 2]; let [a, b] = xs; a + b }");
+        assert!(result.success, "{:?}", result.errors);
+    }
+
     #[test]
     fn test_warnings() {
         let result = check_code("(x) => x");
@@ -0,0 +1,611 @@
+//! Exhaustiveness and redundancy checking for `match` expressions
+//!
+//! Arms are checked with the usefulness algorithm over a pattern matrix. An arm
+//! is unreachable when it is not useful with respect to the arms above it, and a
+//! match is non-exhaustive when a wildcard is still useful after the last arm; the
+//! witness found for that wildcard is reported as the uncovered example.
+//!
+//! Guarded arms (and arms containing view patterns) are checked for reachability
+//! but never count towards covering later arms, since their condition may fail.
+
+use fluentai_core::ast::{Graph, Literal, Node, NodeId, Pattern, RangePattern};
+use rustc_hash::FxHashMap;
+use std::fmt;
+
+/// A problem found in a `match` expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchIssue {
+    /// No arm matches the example value
+    NonExhaustive {
+        /// The match node
+        node: NodeId,
+        /// A value no arm covers, e.g. `Rect(_, _)`
+        missing: String,
+    },
+    /// An arm that earlier arms already cover
+    UnreachableArm {
+        /// The match node
+        node: NodeId,
+        /// Zero-based index of the arm
+        arm: usize,
+    },
+}
+
+impl MatchIssue {
+    /// The match node the issue belongs to
+    pub fn node(&self) -> NodeId {
+        match self {
+            MatchIssue::NonExhaustive { node, .. } | MatchIssue::UnreachableArm { node, .. } => {
+                *node
+            }
+        }
+    }
+}
+
+impl fmt::Display for MatchIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchIssue::NonExhaustive { missing, .. } => {
+                write!(f, "Non-exhaustive match: {} not covered", missing)
+            }
+            MatchIssue::UnreachableArm { arm, .. } => {
+                write!(f, "Match arm {} is unreachable", arm + 1)
+            }
+        }
+    }
+}
+
+/// Check every `match` in a graph against the enums it declares
+pub fn check_matches(graph: &Graph) -> Vec<MatchIssue> {
+    ExhaustivenessChecker::new(graph).check_graph(graph)
+}
+
+/// A constructor heading a pattern column
+#[derive(Debug, Clone, PartialEq)]
+enum Ctor {
+    /// Enum variant, or `Cons`/`Nil` for lists
+    Variant(String),
+    /// Tuple of the given arity, the only constructor of its type
+    Tuple(usize),
+    Bool(bool),
+    /// Inclusive integer interval; a literal `n` is `Int(n, n)`
+    Int(i64, i64),
+    /// Any other literal, compared by equality and never exhaustive
+    Opaque(String),
+}
+
+impl Ctor {
+    /// Whether every value matched by `other` is matched by `self`
+    fn covers(&self, other: &Ctor) -> bool {
+        match (self, other) {
+            (Ctor::Int(lo, hi), Ctor::Int(other_lo, other_hi)) => lo <= other_lo && other_hi <= hi,
+            _ => self == other,
+        }
+    }
+}
+
+/// A pattern reduced to what matters for coverage
+#[derive(Debug, Clone)]
+enum Pat {
+    Wild,
+    Ctor(Ctor, Vec<Pat>),
+    Or(Vec<Pat>),
+}
+
+/// An example value built while searching for an uncovered case
+#[derive(Debug, Clone)]
+enum Witness {
+    Wild,
+    Ctor(Ctor, Vec<Witness>),
+}
+
+impl fmt::Display for Witness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Witness::Wild => write!(f, "_"),
+            Witness::Ctor(Ctor::Variant(name), args) if args.is_empty() => write!(f, "{}", name),
+            Witness::Ctor(Ctor::Variant(name), args) => {
+                let args = args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+                write!(f, "{}({})", name, args.join(", "))
+            }
+            Witness::Ctor(Ctor::Tuple(_), args) => {
+                let args = args.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+                write!(f, "({})", args.join(", "))
+            }
+            Witness::Ctor(Ctor::Bool(b), _) => write!(f, "{}", b),
+            // Show the value of the interval closest to zero
+            Witness::Ctor(Ctor::Int(lo, hi), _) => write!(f, "{}", 0.clamp(*lo, *hi)),
+            Witness::Ctor(Ctor::Opaque(text), _) => write!(f, "{}", text),
+        }
+    }
+}
+
+/// How a wildcard splits against the constructors in its column
+enum WildcardSplit {
+    /// The column names every constructor of its type
+    Complete(Vec<Ctor>),
+    /// Some values are not headed by any constructor in the column
+    Missing(Witness),
+}
+
+/// Checks `match` expressions against the enums declared in a graph
+pub struct ExhaustivenessChecker {
+    /// Variants of each enum with their field counts
+    enums: FxHashMap<String, Vec<(String, usize)>>,
+    /// Enum each variant belongs to
+    variant_enums: FxHashMap<String, String>,
+}
+
+impl ExhaustivenessChecker {
+    /// Create a checker that knows the enums declared in `graph`
+    pub fn new(graph: &Graph) -> Self {
+        let mut checker = Self {
+            enums: FxHashMap::default(),
+            variant_enums: FxHashMap::default(),
+        };
+        // Lists match as `Nil` or `Cons(head, tail)`
+        checker.add_enum(
+            "List",
+            vec![("Nil".to_string(), 0), ("Cons".to_string(), 2)],
+        );
+
+        for node in graph.nodes.values() {
+            if let Node::Enum { name, variants, .. } = node {
+                let variants = variants
+                    .iter()
+                    .map(|v| (v.name.clone(), v.fields.len()))
+                    .collect();
+                checker.add_enum(name, variants);
+            }
+        }
+        checker
+    }
+
+    fn add_enum(&mut self, name: &str, variants: Vec<(String, usize)>) {
+        for (variant, _) in &variants {
+            self.variant_enums.insert(variant.clone(), name.to_string());
+        }
+        self.enums.insert(name.to_string(), variants);
+    }
+
+    /// Check every `match` node in the graph, ordered by node id
+    ///
+    /// Matches desugared from let and parameter patterns are skipped: a refutable
+    /// pattern there raises at runtime instead.
+    pub fn check_graph(&self, graph: &Graph) -> Vec<MatchIssue> {
+        let mut matches: Vec<_> = graph
+            .nodes
+            .iter()
+            .filter_map(|(id, node)| match node {
+                Node::Match {
+                    branches,
+                    must_match: false,
+                    ..
+                } => Some((*id, branches)),
+                _ => None,
+            })
+            .collect();
+        matches.sort_by_key(|(id, _)| id.get());
+
+        matches
+            .into_iter()
+            .flat_map(|(id, branches)| {
+                let patterns: Vec<_> = branches.iter().map(|(pattern, _)| pattern).collect();
+                self.check_match(id, &patterns)
+            })
+            .collect()
+    }
+
+    /// Check the arms of a single match, in order
+    pub fn check_match(&self, node: NodeId, patterns: &[&Pattern]) -> Vec<MatchIssue> {
+        let mut issues = Vec::new();
+        let mut rows: Vec<Vec<Pat>> = Vec::new();
+
+        for (arm, pattern) in patterns.iter().enumerate() {
+            let mut conditional = false;
+            let pat = self.lower(pattern, &mut conditional);
+            if self.useful(&rows, std::slice::from_ref(&pat)).is_none() {
+                issues.push(MatchIssue::UnreachableArm { node, arm });
+            }
+            if !conditional {
+                rows.push(vec![pat]);
+            }
+        }
+
+        if let Some(mut witness) = self.useful(&rows, &[Pat::Wild]) {
+            issues.push(MatchIssue::NonExhaustive {
+                node,
+                missing: witness.remove(0).to_string(),
+            });
+        }
+        issues
+    }
+
+    /// Field count of a known variant
+    fn arity(&self, variant: &str) -> Option<usize> {
+        let variants = self.enums.get(self.variant_enums.get(variant)?)?;
+        variants
+            .iter()
+            .find(|(name, _)| name == variant)
+            .map(|(_, arity)| *arity)
+    }
+
+    /// Reduce a source pattern; `conditional` is set when it may fail for reasons
+    /// other than its shape (guards and view patterns)
+    fn lower(&self, pattern: &Pattern, conditional: &mut bool) -> Pat {
+        match pattern {
+            Pattern::Variable(_) | Pattern::Wildcard => Pat::Wild,
+            Pattern::Literal(Literal::Boolean(b)) => Pat::Ctor(Ctor::Bool(*b), vec![]),
+            Pattern::Literal(Literal::Integer(n)) => Pat::Ctor(Ctor::Int(*n, *n), vec![]),
+            Pattern::Literal(Literal::Nil) => Pat::Ctor(Ctor::Variant("Nil".to_string()), vec![]),
+            Pattern::Literal(other) => Pat::Ctor(Ctor::Opaque(other.to_string()), vec![]),
+            Pattern::Constructor { name, patterns } => {
+                let mut args: Vec<_> = patterns
+                    .iter()
+                    .map(|p| self.lower(p, conditional))
+                    .collect();
+                // Arity errors are reported by inference; pad or trim to the declaration
+                args.resize(self.arity(name).unwrap_or(args.len()), Pat::Wild);
+                Pat::Ctor(Ctor::Variant(name.clone()), args)
+            }
+            Pattern::Tuple(patterns) => Pat::Ctor(
+                Ctor::Tuple(patterns.len()),
+                patterns
+                    .iter()
+                    .map(|p| self.lower(p, conditional))
+                    .collect(),
+            ),
+            Pattern::Guard { pattern, .. } => {
+                *conditional = true;
+                self.lower(pattern, conditional)
+            }
+            Pattern::View { .. } => {
+                *conditional = true;
+                Pat::Wild
+            }
+            Pattern::As { pattern, .. } => self.lower(pattern, conditional),
+            Pattern::Or(alternatives) => Pat::Or(
+                alternatives
+                    .iter()
+                    .map(|p| self.lower(p, conditional))
+                    .collect(),
+            ),
+            Pattern::Range(range) => Pat::Ctor(Self::range_ctor(range), vec![]),
+        }
+    }
+
+    fn range_ctor(range: &RangePattern) -> Ctor {
+        match (&range.start, &range.end) {
+            (Literal::Integer(start), Literal::Integer(end)) => {
+                let last = if range.inclusive {
+                    Some(*end)
+                } else {
+                    end.checked_sub(1)
+                };
+                match last {
+                    Some(last) => Ctor::Int(*start, last),
+                    // An empty range matches nothing
+                    None => Ctor::Int(1, 0),
+                }
+            }
+            (start, end) => Ctor::Opaque(format!(
+                "{}{}{}",
+                start,
+                if range.inclusive { "..=" } else { ".." },
+                end
+            )),
+        }
+    }
+
+    /// Find a value matched by `q` but by none of `rows`, one witness per column
+    fn useful(&self, rows: &[Vec<Pat>], q: &[Pat]) -> Option<Vec<Witness>> {
+        let Some((head, rest)) = q.split_first() else {
+            return rows.is_empty().then(Vec::new);
+        };
+        let rows = expand_or_rows(rows);
+
+        match head {
+            Pat::Or(alternatives) => alternatives.iter().find_map(|alt| {
+                let mut q = vec![alt.clone()];
+                q.extend_from_slice(rest);
+                self.useful(&rows, &q)
+            }),
+            Pat::Ctor(ctor, args) => {
+                let heads = head_ctors(&rows);
+                split_ctor(ctor, &heads).into_iter().find_map(|k| {
+                    let mut q = args.clone();
+                    q.extend_from_slice(rest);
+                    self.useful(&specialize(&rows, &k, args.len()), &q)
+                        .map(|w| rebuild(k, args.len(), w))
+                })
+            }
+            Pat::Wild => match self.split_wildcard(&head_ctors(&rows)) {
+                WildcardSplit::Complete(ctors) => ctors.into_iter().find_map(|k| {
+                    let arity = match &k {
+                        Ctor::Variant(name) => self.arity(name).unwrap_or(0),
+                        Ctor::Tuple(arity) => *arity,
+                        _ => 0,
+                    };
+                    let mut q = vec![Pat::Wild; arity];
+                    q.extend_from_slice(rest);
+                    self.useful(&specialize(&rows, &k, arity), &q)
+                        .map(|w| rebuild(k, arity, w))
+                }),
+                WildcardSplit::Missing(example) => {
+                    let default: Vec<_> = rows
+                        .iter()
+                        .filter(|row| matches!(row[0], Pat::Wild))
+                        .map(|row| row[1..].to_vec())
+                        .collect();
+                    self.useful(&default, rest).map(|mut w| {
+                        w.insert(0, example);
+                        w
+                    })
+                }
+            },
+        }
+    }
+
+    fn split_wildcard(&self, heads: &[&Ctor]) -> WildcardSplit {
+        let named = |ctor: &Ctor| heads.iter().any(|h| h.covers(ctor));
+
+        match heads.first() {
+            Some(Ctor::Variant(name)) => {
+                let Some(variants) = self
+                    .variant_enums
+                    .get(name)
+                    .and_then(|enum_name| self.enums.get(enum_name))
+                else {
+                    return WildcardSplit::Missing(Witness::Wild);
+                };
+                for (variant, arity) in variants {
+                    let ctor = Ctor::Variant(variant.clone());
+                    if !named(&ctor) {
+                        return WildcardSplit::Missing(Witness::Ctor(
+                            ctor,
+                            vec![Witness::Wild; *arity],
+                        ));
+                    }
+                }
+                WildcardSplit::Complete(
+                    variants
+                        .iter()
+                        .map(|(variant, _)| Ctor::Variant(variant.clone()))
+                        .collect(),
+                )
+            }
+            Some(Ctor::Tuple(arity)) => WildcardSplit::Complete(vec![Ctor::Tuple(*arity)]),
+            Some(Ctor::Bool(_)) => {
+                for b in [true, false] {
+                    if !named(&Ctor::Bool(b)) {
+                        return WildcardSplit::Missing(Witness::Ctor(Ctor::Bool(b), vec![]));
+                    }
+                }
+                WildcardSplit::Complete(vec![Ctor::Bool(true), Ctor::Bool(false)])
+            }
+            Some(Ctor::Int(..)) => {
+                let segments = int_segments(heads);
+                match segments.iter().find(|s| !named(s)) {
+                    Some(gap) => WildcardSplit::Missing(Witness::Ctor(gap.clone(), vec![])),
+                    None => WildcardSplit::Complete(segments),
+                }
+            }
+            Some(Ctor::Opaque(_)) | None => WildcardSplit::Missing(Witness::Wild),
+        }
+    }
+}
+
+/// Replace rows headed by an or-pattern with one row per alternative
+fn expand_or_rows(rows: &[Vec<Pat>]) -> Vec<Vec<Pat>> {
+    fn push(row: Vec<Pat>, out: &mut Vec<Vec<Pat>>) {
+        match row.first() {
+            Some(Pat::Or(alternatives)) => {
+                for alt in alternatives {
+                    let mut expanded = row.clone();
+                    expanded[0] = alt.clone();
+                    push(expanded, out);
+                }
+            }
+            _ => out.push(row),
+        }
+    }
+
+    let mut out = Vec::new();
+    for row in rows {
+        push(row.clone(), &mut out);
+    }
+    out
+}
+
+fn head_ctors(rows: &[Vec<Pat>]) -> Vec<&Ctor> {
+    rows.iter()
+        .filter_map(|row| match row.first() {
+            Some(Pat::Ctor(ctor, _)) => Some(ctor),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Constructors `ctor` must be tried as; integer intervals are cut at every
+/// boundary in the column so each piece is either inside or outside a row
+fn split_ctor(ctor: &Ctor, heads: &[&Ctor]) -> Vec<Ctor> {
+    match ctor {
+        Ctor::Int(..) => {
+            let mut all = heads.to_vec();
+            all.push(ctor);
+            int_segments(&all)
+                .into_iter()
+                .filter(|segment| ctor.covers(segment))
+                .collect()
+        }
+        _ => vec![ctor.clone()],
+    }
+}
+
+/// Cut the full integer range at the boundaries of the given intervals
+fn int_segments(ctors: &[&Ctor]) -> Vec<Ctor> {
+    let mut bounds = vec![i64::MIN as i128, i64::MAX as i128 + 1];
+    for ctor in ctors {
+        if let Ctor::Int(lo, hi) = ctor {
+            if lo <= hi {
+                bounds.push(*lo as i128);
+                bounds.push(*hi as i128 + 1);
+            }
+        }
+    }
+    bounds.sort_unstable();
+    bounds.dedup();
+    bounds
+        .windows(2)
+        .map(|w| Ctor::Int(w[0] as i64, (w[1] - 1) as i64))
+        .collect()
+}
+
+/// Rows that match constructor `k`, with its fields replacing the first column
+fn specialize(rows: &[Vec<Pat>], k: &Ctor, arity: usize) -> Vec<Vec<Pat>> {
+    rows.iter()
+        .filter_map(|row| {
+            let (head, rest) = row.split_first()?;
+            let mut specialized = match head {
+                Pat::Ctor(ctor, args) if ctor.covers(k) => args.clone(),
+                Pat::Wild => Vec::new(),
+                _ => return None,
+            };
+            specialized.resize(arity, Pat::Wild);
+            specialized.extend_from_slice(rest);
+            Some(specialized)
+        })
+        .collect()
+}
+
+/// Fold the first `arity` witnesses back into constructor `k`
+fn rebuild(k: Ctor, arity: usize, mut witness: Vec<Witness>) -> Vec<Witness> {
+    let rest = witness.split_off(arity);
+    let mut rebuilt = vec![Witness::Ctor(k, witness)];
+    rebuilt.extend(rest);
+    rebuilt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluentai_parser::parse;
+
+    const SHAPE: &str = "private enum Shape { Circle(int), Rect(int, int), Empty }\n";
+
+    fn issues(code: &str) -> Vec<String> {
+        let graph = parse(code).unwrap();
+        check_matches(&graph)
+            .into_iter()
+            .map(|issue| issue.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_exhaustive_enum_match() {
+        let code = format!(
+            "{}match (Empty) {{ Circle(r) => r, Rect(w, h) => w * h, Empty => 0 }}",
+            SHAPE
+        );
+        assert!(issues(&code).is_empty());
+    }
+
+    #[test]
+    fn test_missing_variant_example() {
+        let code = format!("{}match (Empty) {{ Circle(r) => r, Empty => 0 }}", SHAPE);
+        assert_eq!(
+            issues(&code),
+            vec!["Non-exhaustive match: Rect(_, _) not covered"]
+        );
+    }
+
+    #[test]
+    fn test_nested_missing_case() {
+        let code = "match [1] { Cons(1, rest) => 1, Nil => 0 }";
+        assert_eq!(
+            issues(code),
+            vec!["Non-exhaustive match: Cons(0, _) not covered"]
+        );
+    }
+
+    #[test]
+    fn test_unreachable_arm_after_wildcard() {
+        let code = format!(
+            "{}match (Empty) {{ Circle(r) => r, _ => 0, Empty => 1 }}",
+            SHAPE
+        );
+        assert_eq!(issues(&code), vec!["Match arm 3 is unreachable"]);
+    }
+
+    #[test]
+    fn test_guarded_arm_does_not_cover() {
+        let code = format!(
+            "{}match (Empty) {{ Circle(r) when r > 0 => r, Rect(_, _) | Empty => 0 }}",
+            SHAPE
+        );
+        assert_eq!(
+            issues(&code),
+            vec!["Non-exhaustive match: Circle(_) not covered"]
+        );
+
+        let code = format!(
+            "{}match (Empty) {{ Circle(r) when r > 0 => r, Circle(r) => 0, Rect(_, _) | Empty => 0 }}",
+            SHAPE
+        );
+        assert!(issues(&code).is_empty());
+    }
+
+    #[test]
+    fn test_or_pattern_covers_alternatives() {
+        let code = format!(
+            "{}match (Empty) {{ Circle(_) | Rect(_, _) => 1, Empty => 0, Rect(1, 2) => 2 }}",
+            SHAPE
+        );
+        assert_eq!(issues(&code), vec!["Match arm 3 is unreachable"]);
+    }
+
+    #[test]
+    fn test_integer_ranges() {
+        let code = "match 5 { 0..10 => 1, 5..=7 => 2, _ => 3 }";
+        assert_eq!(issues(code), vec!["Match arm 2 is unreachable"]);
+
+        let code = "match 5 { 0..10 => 1, 10 => 2 }";
+        assert_eq!(issues(code), vec!["Non-exhaustive match: -1 not covered"]);
+    }
+
+    #[test]
+    fn test_boolean_match() {
+        assert!(issues("match true { true => 1, false => 0 }").is_empty());
+        assert_eq!(
+            issues("match true { true => 1 }"),
+            vec!["Non-exhaustive match: false not covered"]
+        );
+    }
+
+    #[test]
+    fn test_tuple_match() {
+        assert!(issues("match (true, 1) { (true, _) => 1, (false, _) => 2 }").is_empty());
+        assert_eq!(
+            issues("match (true, 1) { (true, _) => 1, (false, 0) => 2 }"),
+            vec!["Non-exhaustive match: (false, -1) not covered"]
+        );
+    }
+
+    #[test]
+    fn test_refutable_let_pattern_is_not_checked() {
+        assert!(issues("{ let xs = [1, 2]; let [a, b] = xs; a + b }").is_empty());
+        assert!(issues("{ let f = (Some(x)) => x; f(Some(1)) }").is_empty());
+    }
+
+    #[test]
+    fn test_literal_match_needs_wildcard() {
+        assert_eq!(
+            issues("match \"a\" { \"a\" => 1, \"b\" => 2 }"),
+            vec!["Non-exhaustive match: _ not covered"]
+        );
+        assert_eq!(
+            issues("match \"a\" { \"a\" => 1, \"a\" => 2, x => 3 }"),
+            vec!["Match arm 2 is unreachable"]
+        );
+    }
+}
@@ -2,12 +2,17 @@
 
 use crate::{
     environment::TypeEnvironment,
+    interface::ModuleInterface,
     types::*,
     unification::{Substitution, Unifier},
 };
 use anyhow::{anyhow, Result};
-use fluentai_core::ast::{Graph, Literal, Node, NodeId, Pattern};
-use rustc_hash::FxHashMap;
+use fluentai_core::ast::{
+    EffectOperation, EffectType, EnumVariant, Graph, ImportItem, Literal, Node, NodeId, Pattern, StructField,
+    TraitMethod, TypeExpr,
+};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::HashSet;
 
 /// Type inference engine
 pub struct TypeInferencer {
@@ -21,6 +26,42 @@ pub struct TypeInferencer {
     node_types: FxHashMap<NodeId, TypedValue>,
     /// Errors collected during inference
     errors: Vec<TypeError>,
+    /// Trait declarations: trait name -> method signatures
+    traits: FxHashMap<String, Vec<TraitMethod>>,
+    /// Trait method name -> declaring trait
+    trait_methods: FxHashMap<String, String>,
+    /// Implemented (type, trait) pairs
+    trait_impls: FxHashSet<(String, String)>,
+    /// Struct declarations by name
+    structs: FxHashMap<String, Vec<StructField>>,
+    /// Enum declarations by name
+    enums: FxHashMap<String, Vec<EnumVariant>>,
+    /// Enum variant name -> declaring enum
+    enum_variants: FxHashMap<String, String>,
+    /// Generic parameters of declared structs and enums
+    type_params: FxHashMap<String, Vec<String>>,
+    /// Type aliases: alias name -> aliased type
+    type_aliases: FxHashMap<String, TypeExpr>,
+    /// User-declared effects: effect name -> operation signatures
+    effect_decls: FxHashMap<String, Vec<EffectOperation>>,
+    /// Named types currently being expanded, to cut off recursive declarations
+    resolving_types: Vec<String>,
+    /// Interfaces of importable modules, keyed by import path
+    modules: FxHashMap<String, ModuleInterface>,
+    /// Names bound to a whole imported module -> its import path
+    module_aliases: FxHashMap<String, String>,
+    /// Functions whose bodies are being inferred, innermost last
+    effect_frames: Vec<EffectFrame>,
+    /// Result types of the enclosing loops, innermost last
+    loop_breaks: Vec<TypedValue>,
+}
+
+/// Effects and parameters of a function whose body is being inferred
+struct EffectFrame {
+    /// Effects the body performs
+    row: EffectRow,
+    /// Parameter types; row variables occurring in them keep nested rows open
+    params: Vec<TypedValue>,
 }
 
 /// Type errors that can occur during inference
@@ -63,6 +104,80 @@ pub enum TypeError {
     /// Effect constraint was violated
     #[error("Effect constraint violation: {0}")]
     EffectConstraintViolation(String),
+
+    /// Trait implementation refers to a trait that was never declared
+    #[error("Unknown trait: {0}")]
+    UnknownTrait(String),
+
+    /// Trait implementation does not define a method the trait declares
+    #[error("{type_name} as {trait_name} is missing method '{method}'")]
+    MissingTraitMethod {
+        /// The implementing type
+        type_name: String,
+        /// The trait being implemented
+        trait_name: String,
+        /// The method that was not defined
+        method: String,
+    },
+
+    /// Trait implementation defines a method the trait does not declare
+    #[error("Method '{method}' is not declared by trait {trait_name}")]
+    UnknownTraitMethod {
+        /// The trait being implemented
+        trait_name: String,
+        /// The extra method
+        method: String,
+    },
+
+    /// Trait method called on a type with no implementation of the trait
+    #[error("Type {type_name} does not implement trait {trait_name}")]
+    MissingTraitImpl {
+        /// The receiver type
+        type_name: String,
+        /// The trait declaring the method
+        trait_name: String,
+    },
+
+    /// Type annotation names a type that was never declared
+    #[error("Unknown type: {0}")]
+    UnknownType(String),
+
+    /// Type annotation names an effect that does not exist
+    #[error("Unknown effect: {0}")]
+    UnknownEffect(String),
+
+    /// Perform or handler names an operation its effect does not declare
+    #[error("Effect {effect} has no operation '{operation}'")]
+    UnknownEffectOperation {
+        /// The declared effect
+        effect: String,
+        /// The undeclared operation
+        operation: String,
+    },
+
+    /// Generic type applied to the wrong number of type arguments
+    #[error("Type {name} expects {expected} type argument(s), found {found}")]
+    TypeArgumentMismatch {
+        /// The generic type
+        name: String,
+        /// The number of type parameters it declares
+        expected: usize,
+        /// The number of type arguments supplied
+        found: usize,
+    },
+
+    /// Match expression has no arm for some value of the scrutinee
+    #[error("Non-exhaustive match: {0} not covered")]
+    NonExhaustiveMatch(String),
+
+    /// Import or qualified name refers to something the module does not export
+    #[error("Module {module} does not export '{name}'")]
+    UnknownExport {
+        /// The imported module
+        module: String,
+        /// The missing name
+        name: String,
+    },
 }
 
 impl TypeInferencer {
@@ -74,6 +189,20 @@ impl TypeInferencer {
             unifier: Unifier::new(),
             node_types: FxHashMap::default(),
             errors: Vec::new(),
+            traits: FxHashMap::default(),
+            trait_methods: FxHashMap::default(),
+            trait_impls: FxHashSet::default(),
+            structs: FxHashMap::default(),
+            enums: FxHashMap::default(),
+            type_params: FxHashMap::default(),
+            enum_variants: FxHashMap::default(),
+            type_aliases: FxHashMap::default(),
+            effect_decls: FxHashMap::default(),
+            resolving_types: Vec::new(),
+            modules: FxHashMap::default(),
+            module_aliases: FxHashMap::default(),
+            effect_frames: Vec::new(),
+            loop_breaks: Vec::new(),
         }
     }
 
@@ -85,13 +214,77 @@ impl TypeInferencer {
             unifier: Unifier::new(),
             node_types: FxHashMap::default(),
             errors: Vec::new(),
+            traits: FxHashMap::default(),
+            trait_methods: FxHashMap::default(),
+            trait_impls: FxHashSet::default(),
+            structs: FxHashMap::default(),
+            enums: FxHashMap::default(),
+            type_params: FxHashMap::default(),
+            enum_variants: FxHashMap::default(),
+            type_aliases: FxHashMap::default(),
+            effect_decls: FxHashMap::default(),
+            resolving_types: Vec::new(),
+            modules: FxHashMap::default(),
+            module_aliases: FxHashMap::default(),
+            effect_frames: Vec::new(),
+            loop_breaks: Vec::new(),
         }
     }
 
+    /// Make a module's interface available to `use` statements naming `module_path`
+    pub fn add_module_interface(&mut self, module_path: impl Into<String>, interface: ModuleInterface) {
+        self.modules.insert(module_path.into(), interface);
+    }
+
+    /// Infer a graph and return the types of the names it exports
+    pub fn infer_interface(&mut self, graph: &Graph) -> Result<ModuleInterface> {
+        self.infer_graph(graph)?;
+
+        let mut exports: Vec<(String, String)> = Vec::new();
+        let mut module_name = graph.graph_metadata.get("module_name").cloned();
+        for node in graph.nodes.values() {
+            match node {
+                Node::Export { export_list } => {
+                    for item in export_list {
+                        let exported = item.alias.clone().unwrap_or_else(|| item.name.clone());
+                        exports.push((item.name.clone(), exported));
+                    }
+                }
+                Node::Module { name, .. } => module_name = Some(name.clone()),
+                _ => {}
+            }
+        }
+        // A module's export list repeats the names of its export statements
+        for node in graph.nodes.values() {
+            if let Node::Module { exports: names, .. } = node {
+                for name in names {
+                    if !exports.iter().any(|(source, _)| source == name) {
+                        exports.push((name.clone(), name.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut interface = ModuleInterface::new(module_name.unwrap_or_else(|| "anonymous".to_string()));
+        for (source, exported) in exports {
+            match self.env.lookup(&source).cloned() {
+                Some(ty) => {
+                    interface.exports.insert(exported, self.subst.apply_type(&ty));
+                }
+                None => self.errors.push(TypeError::UnboundVariable(source)),
+            }
+        }
+        Ok(interface)
+    }
+
     /// Infer types for an entire graph
     pub fn infer_graph(&mut self, graph: &Graph) -> Result<FxHashMap<NodeId, TypedValue>> {
         self.node_types.clear();
         self.errors.clear();
+        self.module_aliases.clear();
+        self.effect_frames.clear();
+        self.collect_traits(graph);
+        self.collect_type_declarations(graph);
 
         // Infer type of root node
         if let Some(root_id) = graph.root_id {
@@ -120,7 +313,7 @@ impl TypeInferencer {
         let inferred_type = match node {
             Node::Literal(lit) => self.infer_literal(lit)?,
             Node::Variable { name } => self.infer_variable(name)?,
-            Node::Lambda { params, body } => self.infer_lambda(graph, params, *body)?,
+            Node::Lambda { params, body } => self.infer_lambda(graph, node_id, params, *body)?,
             Node::Application { function, args } => {
                 self.infer_application(graph, *function, args)?
             }
@@ -131,8 +324,18 @@ impl TypeInferencer {
                 then_branch,
                 else_branch,
             } => self.infer_if(graph, *condition, *then_branch, *else_branch)?,
+            Node::While { condition, body } => self.infer_while(graph, *condition, *body)?,
+            Node::For {
+                pattern,
+                iterable,
+                body,
+            } => self.infer_for(graph, pattern, *iterable, *body)?,
+            Node::Range { start, end, .. } => self.infer_range(graph, *start, *end)?,
+            Node::Break { value } => self.infer_break(graph, *value)?,
+            Node::Continue => self.env.fresh_type("T"),
             Node::List(elements) => self.infer_list(graph, elements)?,
-            Node::Match { expr, branches } => self.infer_match(graph, *expr, branches)?,
+            Node::Tuple(elements) => self.infer_tuple(graph, elements)?,
+            Node::Match { expr, branches, .. } => self.infer_match(graph, *expr, branches)?,
             Node::Effect {
                 effect_type,
                 operation,
@@ -141,31 +344,59 @@ impl TypeInferencer {
             Node::Async { body } => self.infer_async(graph, *body)?,
             Node::Await { expr } => self.infer_await(graph, *expr)?,
             Node::Spawn { expr } => self.infer_spawn(graph, *expr)?,
-            Node::Channel { .. } => self.infer_channel()?,
+            Node::Parallel { branches } => self.infer_parallel(graph, branches)?,
+            Node::Channel { capacity } => self.infer_channel(graph, *capacity)?,
             Node::Send { channel, value } => self.infer_send(graph, *channel, *value)?,
             Node::Receive { channel } => self.infer_receive(graph, *channel)?,
             Node::TrySend { channel, value } => self.infer_try_send(graph, *channel, *value)?,
             Node::TryReceive { channel } => self.infer_try_receive(graph, *channel)?,
             Node::Select { branches, default } => self.infer_select(graph, branches, default.as_ref())?,
-            // Module-related nodes - for now, return unit type
-            Node::Module { .. } => TypedValue::primitive(PrimitiveType::unit()),
-            Node::Import { .. } => TypedValue::primitive(PrimitiveType::unit()),
-            Node::Export { .. } => TypedValue::primitive(PrimitiveType::unit()),
-            Node::QualifiedVariable { .. } => {
-                // TODO: Implement proper module type inference
-                self.env.fresh_type("T")
+            // A module body binds its definitions; exports are read back by infer_interface
+            Node::Module { body, .. } => {
+                self.infer_node(graph, *body)?;
+                TypedValue::primitive(PrimitiveType::unit())
+            }
+            Node::Import {
+                module_path,
+                import_list,
+                import_all,
+            } => {
+                self.infer_import(module_path, import_list, *import_all);
+                TypedValue::primitive(PrimitiveType::unit())
             }
+            Node::Export { .. } => TypedValue::primitive(PrimitiveType::unit()),
+            Node::QualifiedVariable {
+                module_name,
+                variable_name,
+            } => match self.env.lookup(module_name).cloned() {
+                // `record.field` on a local binding parses as a qualified name
+                Some(object_type) => self.infer_field(object_type, variable_name),
+                None => match self.module_aliases.get(module_name).cloned() {
+                    Some(module_path) => self.imported_type(&module_path, variable_name),
+                    // Modules without a known interface stay untyped
+                    None => self.env.fresh_type("T"),
+                },
+            },
             Node::Contract { .. } => {
                 // Contracts are metadata, not runtime values
                 TypedValue::primitive(PrimitiveType::unit())
             }
-            Node::Handler { handlers: _, body } => {
-                // Handler has the same type as its body expression
-                self.infer_node(graph, *body)?
-            }
-            Node::Define { name: _, value } => {
+            Node::Handler { handlers, body } => self.infer_handler(graph, handlers, *body)?,
+            Node::Define { name, value } => {
+                // Type aliases were collected up front and have no runtime value to check
+                let is_alias = self.type_aliases.contains_key(name)
+                    && matches!(graph.get_node(*value), Some(Node::Variable { .. }))
+                    && Self::declared_type(graph, *value).is_some();
+                if !is_alias {
+                    // Bind the name first so recursive definitions can refer to themselves
+                    let binding = self.env.fresh_type("def");
+                    self.env.bind(name.clone(), binding.clone());
+                    let value_type = self.infer_node(graph, *value)?;
+                    self.expect_type(&binding, &value_type);
+                    let value_type = self.subst.apply_type(&value_type);
+                    self.env.bind(name.clone(), value_type);
+                }
                 // Define creates a binding and returns unit
-                self.infer_node(graph, *value)?;
                 TypedValue::primitive(PrimitiveType::unit())
             }
             Node::Begin { exprs } => {
@@ -239,19 +470,182 @@ impl TypeInferencer {
                 self.infer_node(graph, *value)?;
                 TypedValue::primitive(PrimitiveType::unit())
             }
+            Node::Struct { .. } => {
+                // Struct definitions are declarations, not runtime values
+                TypedValue::primitive(PrimitiveType::unit())
+            }
+            Node::StructConstruct { fields, .. } => {
+                let mut record = RecordType::new();
+                for (field_name, value) in fields {
+                    let field_type = self.infer_node(graph, *value)?;
+                    record = record.with_field(field_name.clone(), field_type);
+                }
+                TypedValue::record(record)
+            }
+            Node::FieldAccess { object, field } => {
+                let object_type = self.infer_node(graph, *object)?;
+                self.infer_field(object_type, field)
+            }
+            Node::StructUpdate { name, base, fields } => {
+                // The base must be the named struct, and each new value fits its field
+                let base_type = self.infer_node(graph, *base)?;
+                if self.structs.contains_key(name) {
+                    // A generic struct is instantiated with fresh parameters
+                    let mut vars = self.type_param_vars(name, vec![]);
+                    let struct_type = self.struct_type(name, &mut vars);
+                    self.expect_type(&struct_type, &base_type);
+                    for (field, value) in fields {
+                        let value_type = self.infer_node(graph, *value)?;
+                        let field_type = self.infer_field(struct_type.clone(), field);
+                        self.expect_type(&field_type, &value_type);
+                    }
+                    struct_type
+                } else {
+                    self.errors.push(TypeError::UnknownType(name.clone()));
+                    for (_, value) in fields {
+                        self.infer_node(graph, *value)?;
+                    }
+                    base_type
+                }
+            }
+            Node::Enum { .. } | Node::Trait { .. } | Node::EffectDef { .. } | Node::MacroDef { .. } => {
+                // Declarations, collected before inference starts
+                TypedValue::primitive(PrimitiveType::unit())
+            }
+            Node::Extern {
+                name,
+                params,
+                return_type,
+                effects,
+            } => {
+                // The host supplies the body, so the declared signature is the binding's type
+                let mut vars = FxHashMap::default();
+                let params = params
+                    .iter()
+                    .map(|(_, ty)| self.type_from_expr(ty, &mut vars))
+                    .collect();
+                let result = self.type_from_expr(return_type, &mut vars);
+                let effects = self.effects_from_names(effects);
+                self.env.bind(
+                    name.clone(),
+                    FunctionType::new(params, result).with_effects(effects),
+                );
+                TypedValue::primitive(PrimitiveType::unit())
+            }
+            Node::TraitImpl {
+                type_name,
+                trait_name,
+                methods,
+            } => self.infer_trait_impl(graph, type_name, trait_name, methods)?,
+            Node::MethodCall { method, args } => {
+                if self.trait_methods.contains_key(method) {
+                    self.infer_trait_method_call(graph, method, args)?
+                } else {
+                    // Only an impl of an unknown trait names the method, which is reported there
+                    for &arg in args {
+                        self.infer_node(graph, arg)?;
+                    }
+                    self.env.fresh_type("r")
+                }
+            }
+            // Already reported by the parser; anything goes in its place
+            Node::Error { .. } => self.env.fresh_type("T"),
+        };
+
+        // A declared annotation constrains the inferred type; lambdas check their own signature
+        let inferred_type = match Self::declared_type(graph, node_id) {
+            Some(annotation) if !Self::is_lambda_signature(node, annotation) => {
+                let expected = self.type_from_expr(annotation, &mut FxHashMap::default());
+                self.expect_type(&expected, &inferred_type);
+                self.subst.apply_type(&expected)
+            }
+            _ => inferred_type,
         };
 
+        // Effect and channel operations perform their effects in the enclosing function
+        if matches!(
+            node,
+            Node::Effect { .. }
+                | Node::Spawn { .. }
+                | Node::Parallel { .. }
+                | Node::Channel { .. }
+                | Node::Send { .. }
+                | Node::Receive { .. }
+                | Node::TrySend { .. }
+                | Node::TryReceive { .. }
+                | Node::Select { .. }
+        ) {
+            self.perform(&EffectRow::closed(inferred_type.effects.iter().copied()));
+        }
+
         // Store the inferred type
         self.node_types.insert(node_id, inferred_type.clone());
         Ok(inferred_type)
     }
 
+    /// Type of `field` read from a value of `object_type`
+    fn infer_field(&mut self, object_type: TypedValue, field: &str) -> TypedValue {
+        let object_type = self.subst.apply_type(&object_type);
+        match &object_type.inner {
+            TypedValueInner::Record(record) => match record.fields.get(field) {
+                Some(field_type) => field_type.clone(),
+                None => {
+                    self.errors.push(TypeError::TypeMismatch {
+                        expected: format!("record with field '{}'", field),
+                        found: object_type.to_string(),
+                    });
+                    self.env.fresh_type("T")
+                }
+            },
+            // The object's type is not known yet
+            _ => self.env.fresh_type("T"),
+        }
+    }
+
+    /// Type annotation written on a node, if any
+    fn declared_type(graph: &Graph, node_id: NodeId) -> Option<&TypeExpr> {
+        graph.get_metadata(node_id)?.type_annotation.as_ref()
+    }
+
+    /// Whether an annotation is a lambda's own parameter/return signature
+    fn is_lambda_signature(node: &Node, annotation: &TypeExpr) -> bool {
+        match (node, annotation) {
+            (Node::Lambda { params, .. }, TypeExpr::Function { params: declared, .. }) => {
+                params.len() == declared.len()
+            }
+            _ => false,
+        }
+    }
+
+    /// Unify a found type with the type an annotation requires, recording a mismatch
+    fn expect_type(&mut self, expected: &TypedValue, found: &TypedValue) -> bool {
+        let expected = self.subst.apply_type(expected);
+        let found = self.subst.apply_type(found);
+        match self.unifier.unify(&expected, &found) {
+            Ok(new_subst) => {
+                self.subst.compose(&new_subst);
+                true
+            }
+            Err(_) => {
+                self.errors.push(TypeError::TypeMismatch {
+                    expected: expected.to_string(),
+                    found: found.to_string(),
+                });
+                false
+            }
+        }
+    }
+
     /// Infer type of a literal
     fn infer_literal(&mut self, lit: &Literal) -> Result<TypedValue> {
         Ok(match lit {
             Literal::Integer(_) => TypedValue::primitive(PrimitiveType::int()),
             Literal::Float(_) => TypedValue::primitive(PrimitiveType::float()),
+            // Big integers are a representation of Int, not a separate type
+            Literal::BigInt(_) => TypedValue::primitive(PrimitiveType::int()),
+            Literal::Decimal(_) => TypedValue::primitive(PrimitiveType::decimal()),
             Literal::String(_) => TypedValue::primitive(PrimitiveType::string()),
+            Literal::Bytes(_) => TypedValue::primitive(PrimitiveType::bytes()),
             Literal::Symbol(_) => TypedValue::primitive(PrimitiveType::symbol()),
             Literal::Boolean(_) => TypedValue::primitive(PrimitiveType::bool()),
             Literal::Nil => TypedValue::primitive(PrimitiveType::unit()),
@@ -260,15 +654,14 @@ impl TypeInferencer {
 
     /// Infer type of a variable
     fn infer_variable(&mut self, name: &str) -> Result<TypedValue> {
-        // Check for built-in functions first
-        if let Some(builtin_type) = self.get_builtin_type(name) {
-            return Ok(builtin_type);
-        }
-
-        // Look up in environment
+        // Look up in environment; local bindings shadow built-ins
         if let Some(ty) = self.env.lookup(name) {
             // Instantiate type scheme (handle polymorphism)
             Ok(self.instantiate(ty.clone()))
+        } else if let Some(builtin_type) = self.get_builtin_type(name) {
+            Ok(builtin_type)
+        } else if let Some(enum_name) = self.enum_variants.get(name).cloned() {
+            Ok(self.variant_constructor_type(&enum_name, name))
         } else {
             self.errors
                 .push(TypeError::UnboundVariable(name.to_string()));
@@ -280,30 +673,435 @@ impl TypeInferencer {
     fn infer_lambda(
         &mut self,
         graph: &Graph,
+        node_id: NodeId,
         params: &[String],
         body: NodeId,
     ) -> Result<TypedValue> {
-        // Create fresh type variables for parameters
+        // Declared parameter and return types, with `_` for unannotated positions
+        let signature = match Self::declared_type(graph, node_id) {
+            Some(TypeExpr::Function {
+                params: declared,
+                result,
+                effects,
+            }) if declared.len() == params.len() => {
+                let mut vars = FxHashMap::default();
+                let declared: Vec<_> = declared
+                    .iter()
+                    .map(|ty| self.type_from_expr(ty, &mut vars))
+                    .collect();
+                let result = self.type_from_expr(result, &mut vars);
+                let effects = self.effects_from_names(effects);
+                Some((declared, result, effects))
+            }
+            Some(TypeExpr::Function { params: declared, .. }) => {
+                self.errors.push(TypeError::ArityMismatch {
+                    expected: declared.len(),
+                    found: params.len(),
+                });
+                None
+            }
+            _ => None,
+        };
+
+        // Declared effects are a closed row the body must stay within; otherwise they are inferred
+        let declared_row = match &signature {
+            Some((_, _, effects)) if !effects.is_empty() => {
+                Some(EffectRow::closed(effects.iter().copied()))
+            }
+            _ => None,
+        };
+
         let mut param_types = Vec::new();
 
         self.env.push_scope();
 
-        for param in params {
-            let param_type = self.env.fresh_type("t");
+        for (i, param) in params.iter().enumerate() {
+            let param_type = match &signature {
+                Some((declared, _, _)) => declared[i].clone(),
+                // Create fresh type variables for unannotated parameters
+                None => self.env.fresh_type("t"),
+            };
             param_types.push(param_type.clone());
             self.env.bind(param, param_type);
         }
 
+        let row = match &declared_row {
+            Some(row) => row.clone(),
+            None => EffectRow::open([], self.fresh_row_var()),
+        };
+        self.effect_frames.push(EffectFrame {
+            row,
+            params: param_types.clone(),
+        });
+
         // Infer body type
-        let body_type = self.infer_node(graph, body)?;
+        let body_type = self.infer_node(graph, body);
 
         self.env.pop_scope();
+        let frame = self.effect_frames.pop();
+
+        let body_type = body_type?;
+        let param_types: Vec<_> = param_types
+            .iter()
+            .map(|ty| self.subst.apply_type(ty))
+            .collect();
 
         // Create function type
-        Ok(TypedValue::function(FunctionType::new(
-            param_types,
-            body_type,
-        )))
+        let mut function = match signature {
+            Some((_, result, _)) => {
+                self.expect_type(&result, &body_type);
+                let result = self.subst.apply_type(&result);
+                FunctionType::new(param_types, result)
+            }
+            None => FunctionType::new(param_types, body_type),
+        };
+        let row = match (declared_row, frame) {
+            (Some(row), _) => row,
+            (None, Some(frame)) => self.close_row(&frame.row, &function),
+            (None, None) => EffectRow::pure(),
+        };
+        // Effects performed by the body belong to the arrow, not to the returned value
+        function.result.effects.retain(|effect| !row.effects.contains(effect));
+        Ok(TypedValue::function_with_row(function.with_effect_row(row)))
+    }
+
+    /// Drop the row variable of an inferred row unless the function's type or an enclosing
+    /// function's parameters mention it; only then can callers add effects through it
+    fn close_row(&self, row: &EffectRow, function: &FunctionType) -> EffectRow {
+        let mut row = self.subst.apply_row(row);
+        if let Some(tail) = &row.tail {
+            let mut vars = Vec::new();
+            let mut rows = Vec::new();
+            let enclosing = self.effect_frames.iter().flat_map(|frame| &frame.params);
+            for ty in function.params.iter().chain([&*function.result]).chain(enclosing) {
+                Self::collect_type_vars(&self.subst.apply_type(ty), &mut vars, &mut rows);
+            }
+            if !rows.contains(tail) {
+                row.tail = None;
+            }
+        }
+        row
+    }
+
+    /// Add the effects of `row` to the function whose body is being inferred
+    fn perform(&mut self, row: &EffectRow) {
+        let Some(frame) = self.effect_frames.last() else {
+            return;
+        };
+        let current = frame.row.clone();
+        // The callee's effects are a lower bound: the body may perform others as well
+        let mut performed = self.subst.apply_row(row);
+        if performed.tail.is_none() {
+            performed.tail = Some(self.fresh_row_var());
+        }
+        match self.unifier.unify_rows(&current, &performed) {
+            Ok(new_subst) => self.subst.compose(&new_subst),
+            Err(_) => {
+                let current = self.subst.apply_row(&current);
+                let mut extra: Vec<_> = performed
+                    .effects
+                    .difference(&current.effects)
+                    .map(|e| e.to_string())
+                    .collect();
+                extra.sort();
+                let declared = if current.effects.is_empty() {
+                    "Pure".to_string()
+                } else {
+                    let mut declared: Vec<_> =
+                        current.effects.iter().map(|e| e.to_string()).collect();
+                    declared.sort();
+                    declared.join(", ")
+                };
+                self.errors.push(TypeError::EffectConstraintViolation(format!(
+                    "{} performed in a function declared with({})",
+                    extra.join(", "),
+                    declared
+                )));
+            }
+        }
+    }
+
+    /// Generate a fresh effect row variable
+    fn fresh_row_var(&mut self) -> String {
+        self.env.fresh_type_var("e").name
+    }
+
+    /// Record struct, enum, effect and alias declarations so annotations can refer to them in any order
+    fn collect_type_declarations(&mut self, graph: &Graph) {
+        self.structs.clear();
+        self.enums.clear();
+        self.enum_variants.clear();
+        self.type_params.clear();
+        self.type_aliases.clear();
+        self.effect_decls.clear();
+
+        for node in graph.nodes.values() {
+            match node {
+                Node::Struct { name, type_params, fields, .. } => {
+                    self.structs.insert(name.clone(), fields.clone());
+                    self.type_params.insert(name.clone(), type_params.clone());
+                }
+                Node::Enum { name, type_params, variants, .. } => {
+                    self.type_params.insert(name.clone(), type_params.clone());
+                    for variant in variants {
+                        self.enum_variants.insert(variant.name.clone(), name.clone());
+                    }
+                    self.enums.insert(name.clone(), variants.clone());
+                }
+                Node::EffectDef { name, operations } => {
+                    self.effect_decls.insert(name.clone(), operations.clone());
+                }
+                Node::Define { name, value } => {
+                    // `type Alias = T` parses to a define of a variable annotated with T
+                    if let (Some(Node::Variable { .. }), Some(aliased)) =
+                        (graph.get_node(*value), Self::declared_type(graph, *value))
+                    {
+                        self.type_aliases.insert(name.clone(), aliased.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Convert a type annotation into a type; `vars` scopes type variables to one signature
+    fn type_from_expr(
+        &mut self,
+        expr: &TypeExpr,
+        vars: &mut FxHashMap<String, TypedValue>,
+    ) -> TypedValue {
+        match expr {
+            TypeExpr::Infer => self.env.fresh_type("t"),
+            TypeExpr::Variable(name) => self.type_variable(name, vars),
+            TypeExpr::Tuple(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|ty| self.type_from_expr(ty, vars))
+                    .collect();
+                TypedValue::tuple(TupleType::new(elements))
+            }
+            TypeExpr::Function {
+                params,
+                result,
+                effects,
+            } => {
+                let params = params
+                    .iter()
+                    .map(|ty| self.type_from_expr(ty, vars))
+                    .collect();
+                let result = self.type_from_expr(result, vars);
+                let effects = self.effects_from_names(effects);
+                FunctionType::new(params, result).with_effects(effects)
+            }
+            TypeExpr::Named { name, args } => self.named_type(name, args, vars),
+        }
+    }
+
+    /// Number of type arguments a named type takes
+    fn type_arity(&self, name: &str) -> usize {
+        // Declarations shadow the built-in constructors of the same name
+        match self.type_params.get(name) {
+            Some(params) => params.len(),
+            None => match name {
+                "List" | "Chan" | "Option" => 1,
+                "Map" | "Result" => 2,
+                _ => 0,
+            },
+        }
+    }
+
+    /// Resolve a named type: primitives, built-in constructors, structs, enums and aliases
+    fn named_type(
+        &mut self,
+        name: &str,
+        args: &[TypeExpr],
+        vars: &mut FxHashMap<String, TypedValue>,
+    ) -> TypedValue {
+        let expected_args = self.type_arity(name);
+        if args.len() != expected_args {
+            self.errors.push(TypeError::TypeArgumentMismatch {
+                name: name.to_string(),
+                expected: expected_args,
+                found: args.len(),
+            });
+        }
+        let mut args: Vec<_> = args
+            .iter()
+            .map(|ty| self.type_from_expr(ty, vars))
+            .collect();
+
+        match name {
+            "Int" | "int" => TypedValue::primitive(PrimitiveType::int()),
+            "Float" | "float" => TypedValue::primitive(PrimitiveType::float()),
+            "Decimal" | "decimal" => TypedValue::primitive(PrimitiveType::decimal()),
+            "String" | "string" => TypedValue::primitive(PrimitiveType::string()),
+            "Bytes" | "bytes" => TypedValue::primitive(PrimitiveType::bytes()),
+            "Bool" | "bool" => TypedValue::primitive(PrimitiveType::bool()),
+            "Symbol" => TypedValue::primitive(PrimitiveType::symbol()),
+            "Unit" => TypedValue::primitive(PrimitiveType::unit()),
+            "Any" | "any" => self.env.fresh_type("t"),
+            // The implementing type inside a trait signature, otherwise anything
+            "Self" => match vars.get("Self") {
+                Some(self_type) => self_type.clone(),
+                None => self.env.fresh_type("t"),
+            },
+            // Type parameters of the declaration being expanded
+            _ if args.is_empty() && vars.contains_key(name) => vars[name].clone(),
+            "List" => {
+                let element = match args.len() {
+                    1 => args.remove(0),
+                    _ => self.env.fresh_type("t"),
+                };
+                TypedValue::list(ListType::new(element))
+            }
+            "Chan" => {
+                let element = match args.len() {
+                    1 => args.remove(0),
+                    _ => self.env.fresh_type("t"),
+                };
+                TypedValue::channel(ChannelType::new(element))
+            }
+            // A recursive declaration refers back to itself; leave the inner reference open
+            _ if self.resolving_types.iter().any(|n| n == name) => self.env.fresh_type("t"),
+            _ if self.type_aliases.contains_key(name) => {
+                let aliased = self.type_aliases[name].clone();
+                self.resolving_types.push(name.to_string());
+                let ty = self.type_from_expr(&aliased, &mut FxHashMap::default());
+                self.resolving_types.pop();
+                ty
+            }
+            _ if self.structs.contains_key(name) => {
+                let mut params = self.type_param_vars(name, args);
+                self.struct_type(name, &mut params)
+            }
+            _ if self.enums.contains_key(name) => {
+                let mut params = self.type_param_vars(name, args);
+                self.enum_type(name, &mut params)
+            }
+            "Option" => {
+                let element = match args.len() {
+                    1 => args.remove(0),
+                    _ => self.env.fresh_type("t"),
+                };
+                TypedValue::variant(
+                    VariantType::new()
+                        .with_variant("Some", Some(element))
+                        .with_variant("None", None),
+                )
+            }
+            "Result" => {
+                let (ok, err) = match args.len() {
+                    2 => (args.remove(0), args.remove(0)),
+                    _ => (self.env.fresh_type("t"), self.env.fresh_type("e")),
+                };
+                TypedValue::variant(
+                    VariantType::new()
+                        .with_variant("Ok", Some(ok))
+                        .with_variant("Err", Some(err)),
+                )
+            }
+            // Maps have no structural type yet; their arguments are still checked above
+            "Map" => self.env.fresh_type("map"),
+            // Single capitals such as `T` are type variables
+            _ if name.len() == 1 && expected_args == 0 => self.type_variable(name, vars),
+            _ => {
+                self.errors.push(TypeError::UnknownType(name.to_string()));
+                self.env.fresh_type("t")
+            }
+        }
+    }
+
+    /// Bind a declaration's type parameters to the supplied arguments, or to fresh
+    /// variables where none were supplied
+    fn type_param_vars(&mut self, name: &str, args: Vec<TypedValue>) -> FxHashMap<String, TypedValue> {
+        let params = self.type_params.get(name).cloned().unwrap_or_default();
+        let mut args = args.into_iter();
+        params
+            .into_iter()
+            .map(|param| {
+                let ty = args.next().unwrap_or_else(|| self.env.fresh_type("t"));
+                (param, ty)
+            })
+            .collect()
+    }
+
+    /// The record type of a declared struct, with its type parameters bound by `params`
+    fn struct_type(&mut self, name: &str, params: &mut FxHashMap<String, TypedValue>) -> TypedValue {
+        let fields = self.structs[name].clone();
+        self.resolving_types.push(name.to_string());
+        let mut record = RecordType::new();
+        for field in &fields {
+            let field_type = self.type_from_expr(&field.field_type, params);
+            record = record.with_field(field.name.clone(), field_type);
+        }
+        self.resolving_types.pop();
+        TypedValue::record(record)
+    }
+
+    /// The variant type of a declared enum, with its type parameters bound by `params`
+    fn enum_type(&mut self, name: &str, params: &mut FxHashMap<String, TypedValue>) -> TypedValue {
+        let variants = self.enums[name].clone();
+        self.resolving_types.push(name.to_string());
+        let mut variant_type = VariantType::new();
+        for variant in &variants {
+            let mut fields: Vec<_> = variant
+                .fields
+                .iter()
+                .map(|ty| self.type_from_expr(ty, params))
+                .collect();
+            let payload = match fields.len() {
+                0 => None,
+                1 => Some(fields.remove(0)),
+                _ => Some(TypedValue::tuple(TupleType::new(fields))),
+            };
+            variant_type = variant_type.with_variant(variant.name.clone(), payload);
+        }
+        self.resolving_types.pop();
+        TypedValue::variant(variant_type)
+    }
+
+    /// Type of an enum variant used as a value: the enum itself, or a constructor for it
+    fn variant_constructor_type(&mut self, enum_name: &str, variant: &str) -> TypedValue {
+        let fields = self.enums[enum_name]
+            .iter()
+            .find(|v| v.name == variant)
+            .map(|v| v.fields.clone())
+            .unwrap_or_default();
+        let mut vars = self.type_param_vars(enum_name, vec![]);
+        let enum_type = self.enum_type(enum_name, &mut vars);
+        if fields.is_empty() {
+            return enum_type;
+        }
+        let params = fields
+            .iter()
+            .map(|ty| self.type_from_expr(ty, &mut vars))
+            .collect();
+        TypedValue::function_with_row(FunctionType::new(params, enum_type))
+    }
+
+    /// Look up or create the type variable for a name within one signature
+    fn type_variable(&mut self, name: &str, vars: &mut FxHashMap<String, TypedValue>) -> TypedValue {
+        if let Some(ty) = vars.get(name) {
+            return ty.clone();
+        }
+        let ty = self.env.fresh_type(name);
+        vars.insert(name.to_string(), ty.clone());
+        ty
+    }
+
+    /// Resolve the effect names of an effect row
+    fn effects_from_names(&mut self, names: &[String]) -> HashSet<EffectType> {
+        let mut effects = HashSet::new();
+        for name in names {
+            let declared = |name: &str| self.effect_decls.contains_key(name);
+            match EffectType::from_declared_name(name, declared) {
+                Ok(effect) if effect.is_builtin() || self.effect_decls.contains_key(name) => {
+                    effects.insert(effect);
+                }
+                _ => self.errors.push(TypeError::UnknownEffect(name.clone())),
+            }
+        }
+        effects
     }
 
     /// Infer type of function application
@@ -319,24 +1117,42 @@ impl TypeInferencer {
                 if name == "list" {
                     return self.infer_list(graph, args);
                 }
+                // `module.f(args)` parses as a method call with the module as receiver
+                if let Some(module_path) = self.module_receiver(graph, args) {
+                    let func_type = self.imported_type(&module_path, name);
+                    return self.apply_function(graph, func_type, &args[1..]);
+                }
             }
         }
 
         // Infer function type
         let func_type = self.infer_node(graph, function)?;
+        self.apply_function(graph, func_type, args)
+    }
+
+    /// Apply a function of type `func_type` to `args`
+    fn apply_function(
+        &mut self,
+        graph: &Graph,
+        func_type: TypedValue,
+        args: &[NodeId],
+    ) -> Result<TypedValue> {
 
         // Infer argument types
         let mut arg_types = Vec::new();
         for &arg in args {
-            arg_types.push(self.infer_node(graph, arg)?);
+            let arg_type = self.infer_node(graph, arg)?;
+            arg_types.push(self.open_function_row(arg_type));
         }
 
         // Create fresh result type
         let result_type = self.env.fresh_type("r");
+        let row = EffectRow::open([], self.fresh_row_var());
 
         // Create expected function type
-        let expected_func_type =
-            TypedValue::function(FunctionType::new(arg_types.clone(), result_type.clone()));
+        let expected_func_type = TypedValue::function_with_row(
+            FunctionType::new(arg_types.clone(), result_type.clone()).with_effect_row(row.clone()),
+        );
 
         // Unify with actual function type
         match self.unifier.unify(&func_type, &expected_func_type) {
@@ -344,7 +1160,10 @@ impl TypeInferencer {
                 self.subst.compose(&new_subst);
                 let mut result = self.subst.apply_type(&result_type);
                 // Preserve effects from the function
+                let row = self.subst.apply_row(&row);
                 result.effects.extend(func_type.effects.iter().copied());
+                result.effects.extend(row.effects.iter().copied());
+                self.perform(&row);
                 Ok(result)
             }
             Err(e) => {
@@ -357,6 +1176,313 @@ impl TypeInferencer {
         }
     }
 
+    /// A function passed as an argument may be used where more effects are allowed
+    fn open_function_row(&mut self, ty: TypedValue) -> TypedValue {
+        match &ty.inner {
+            TypedValueInner::Function(func) if func.effects.tail.is_none() => {
+                let mut row = func.effects.clone();
+                row.tail = Some(self.fresh_row_var());
+                TypedValue {
+                    inner: TypedValueInner::Function(func.clone().with_effect_row(row)),
+                    effects: ty.effects.clone(),
+                }
+            }
+            _ => ty,
+        }
+    }
+
+    /// Import path of a module named by the first argument of a method-style call
+    fn module_receiver(&self, graph: &Graph, args: &[NodeId]) -> Option<String> {
+        match graph.get_node(*args.first()?)? {
+            Node::Variable { name } if self.env.lookup(name).is_none() => {
+                self.module_aliases.get(name).cloned()
+            }
+            _ => None,
+        }
+    }
+
+    /// Bind the names a `use` statement brings into scope from a module with a known interface
+    fn infer_import(&mut self, module_path: &str, import_list: &[ImportItem], import_all: bool) {
+        let Some(interface) = self.modules.get(module_path).cloned() else {
+            return;
+        };
+
+        if import_all {
+            let names: Vec<_> = interface.exports.keys().cloned().collect();
+            for name in names {
+                let ty = self.imported_type(module_path, &name);
+                self.env.bind(name, ty);
+            }
+            return;
+        }
+
+        let module_name = module_path.rsplit(['/', ':']).next().unwrap_or(module_path);
+        for item in import_list {
+            let local = item.alias.clone().unwrap_or_else(|| item.name.clone());
+            // `use math;` and `use math as m;` bind the module itself
+            if item.name == module_name && interface.get(&item.name).is_none() {
+                self.module_aliases.insert(local, module_path.to_string());
+            } else {
+                let ty = self.imported_type(module_path, &item.name);
+                self.env.bind(local, ty);
+            }
+        }
+    }
+
+    /// Type of an exported name, with fresh type variables for each use
+    fn imported_type(&mut self, module_path: &str, name: &str) -> TypedValue {
+        let exported = self
+            .modules
+            .get(module_path)
+            .and_then(|interface| interface.get(name))
+            .cloned();
+        match exported {
+            Some(ty) => {
+                let mut vars = Vec::new();
+                let mut rows = Vec::new();
+                Self::collect_type_vars(&ty, &mut vars, &mut rows);
+                let mut fresh = Substitution::new();
+                for var in vars {
+                    let fresh_var = self.env.fresh_type("t");
+                    fresh.insert(var, fresh_var);
+                }
+                for row in rows {
+                    let fresh_row = EffectRow::open([], self.fresh_row_var());
+                    fresh.insert_row(row, fresh_row);
+                }
+                fresh.apply_type(&ty)
+            }
+            None => {
+                self.errors.push(TypeError::UnknownExport {
+                    module: module_path.to_string(),
+                    name: name.to_string(),
+                });
+                self.env.fresh_type("t")
+            }
+        }
+    }
+
+    /// Names of the type and effect row variables occurring in a type
+    fn collect_type_vars(ty: &TypedValue, vars: &mut Vec<String>, rows: &mut Vec<String>) {
+        match &ty.inner {
+            TypedValueInner::Variable(var) => {
+                if !vars.contains(&var.name) {
+                    vars.push(var.name.clone());
+                }
+            }
+            TypedValueInner::Function(func) => {
+                for param in &func.params {
+                    Self::collect_type_vars(param, vars, rows);
+                }
+                Self::collect_type_vars(&func.result, vars, rows);
+                if let Some(tail) = &func.effects.tail {
+                    if !rows.contains(tail) {
+                        rows.push(tail.clone());
+                    }
+                }
+            }
+            TypedValueInner::Tuple(tuple) => {
+                for element in &tuple.elements {
+                    Self::collect_type_vars(element, vars, rows);
+                }
+            }
+            TypedValueInner::List(list) => Self::collect_type_vars(&list.element_type, vars, rows),
+            TypedValueInner::Channel(channel) => {
+                Self::collect_type_vars(&channel.element_type, vars, rows)
+            }
+            TypedValueInner::Record(record) => {
+                for field in record.fields.values() {
+                    Self::collect_type_vars(field, vars, rows);
+                }
+            }
+            TypedValueInner::Variant(variant) => {
+                for payload in variant.variants.values().flatten() {
+                    Self::collect_type_vars(payload, vars, rows);
+                }
+            }
+            TypedValueInner::Effect(effect) => {
+                if let Some(payload) = &effect.payload_type {
+                    Self::collect_type_vars(payload, vars, rows);
+                }
+            }
+            TypedValueInner::Uncertain(uncertain) => {
+                Self::collect_type_vars(&uncertain.base_type, vars, rows)
+            }
+            TypedValueInner::Temporal(temporal) => {
+                Self::collect_type_vars(&temporal.base_type, vars, rows)
+            }
+            TypedValueInner::Primitive(_) => {}
+        }
+    }
+
+    /// Record trait declarations and impls so calls can be checked in any order
+    fn collect_traits(&mut self, graph: &Graph) {
+        self.traits.clear();
+        self.trait_methods.clear();
+        self.trait_impls.clear();
+
+        for node in graph.nodes.values() {
+            match node {
+                Node::Trait { name, methods } => {
+                    for method in methods {
+                        self.trait_methods.insert(method.name.clone(), name.clone());
+                    }
+                    self.traits.insert(name.clone(), methods.clone());
+                }
+                Node::TraitImpl {
+                    type_name,
+                    trait_name,
+                    ..
+                } => {
+                    self.trait_impls
+                        .insert((type_name.clone(), trait_name.clone()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Check a `Type as Trait` impl against the trait declaration
+    fn infer_trait_impl(
+        &mut self,
+        graph: &Graph,
+        type_name: &str,
+        trait_name: &str,
+        methods: &[(String, NodeId)],
+    ) -> Result<TypedValue> {
+        let mut declared = FxHashMap::default();
+        match self.traits.get(trait_name).cloned() {
+            None => self
+                .errors
+                .push(TypeError::UnknownTrait(trait_name.to_string())),
+            Some(signatures) => {
+                for signature in &signatures {
+                    if !methods.iter().any(|(name, _)| name == &signature.name) {
+                        self.errors.push(TypeError::MissingTraitMethod {
+                            type_name: type_name.to_string(),
+                            trait_name: trait_name.to_string(),
+                            method: signature.name.clone(),
+                        });
+                    }
+                }
+                for (name, lambda) in methods {
+                    match signatures.iter().find(|sig| &sig.name == name) {
+                        None => self.errors.push(TypeError::UnknownTraitMethod {
+                            trait_name: trait_name.to_string(),
+                            method: name.clone(),
+                        }),
+                        Some(signature) => {
+                            if let Some(Node::Lambda { params, .. }) = graph.get_node(*lambda) {
+                                if params.len() != signature.params.len() {
+                                    self.errors.push(TypeError::ArityMismatch {
+                                        expected: signature.params.len(),
+                                        found: params.len(),
+                                    });
+                                } else {
+                                    declared.insert(*lambda, signature.clone());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        // Methods of the right arity must fit the trait's signature for this type
+        let self_type = self.impl_type(type_name);
+        for (_, lambda) in methods {
+            let method_type = self.infer_node(graph, *lambda)?;
+            if let Some(signature) = declared.get(lambda) {
+                let tail = self.fresh_row_var();
+                let expected = self
+                    .trait_method_type(signature, &self_type)
+                    .with_effect_row(EffectRow::open([], tail));
+                self.expect_type(&TypedValue::function_with_row(expected), &method_type);
+            }
+        }
+
+        Ok(TypedValue::primitive(PrimitiveType::unit()))
+    }
+
+    /// Type of the values a `Type as Trait` impl is for
+    fn impl_type(&mut self, type_name: &str) -> TypedValue {
+        let args = vec![TypeExpr::Infer; self.type_arity(type_name)];
+        self.named_type(type_name, &args, &mut FxHashMap::default())
+    }
+
+    /// Signature of a trait method for `self_type`, which `self` and `Self` stand for;
+    /// other unannotated parameters and results are fresh type variables
+    fn trait_method_type(&mut self, signature: &TraitMethod, self_type: &TypedValue) -> FunctionType {
+        let mut vars = FxHashMap::default();
+        vars.insert("Self".to_string(), self_type.clone());
+        let params = signature
+            .params
+            .iter()
+            .map(|(name, ty)| match ty {
+                TypeExpr::Infer if name == "self" => self_type.clone(),
+                ty => self.type_from_expr(ty, &mut vars),
+            })
+            .collect();
+        let result = match &signature.return_type {
+            Some(ty) => self.type_from_expr(ty, &mut vars),
+            None => self.env.fresh_type("r"),
+        };
+        FunctionType::new(params, result)
+    }
+
+    /// Infer a call to a trait method, which dispatches on its receiver
+    fn infer_trait_method_call(
+        &mut self,
+        graph: &Graph,
+        method: &str,
+        args: &[NodeId],
+    ) -> Result<TypedValue> {
+        let trait_name = self.trait_methods[method].clone();
+        let signature = self.traits[&trait_name]
+            .iter()
+            .find(|sig| sig.name == method)
+            .cloned()
+            .expect("trait method index out of sync with trait declarations");
+
+        let mut arg_types = Vec::new();
+        for &arg in args {
+            arg_types.push(self.infer_node(graph, arg)?);
+        }
+
+        if args.len() != signature.params.len() {
+            self.errors.push(TypeError::ArityMismatch {
+                expected: signature.params.len(),
+                found: args.len(),
+            });
+            return Ok(self.env.fresh_type("r"));
+        }
+
+        // The receiver is `self`, so the signature is instantiated for its type
+        let receiver_type = match arg_types.first() {
+            Some(receiver) => self.subst.apply_type(receiver),
+            None => self.env.fresh_type("t"),
+        };
+
+        // Primitive receivers are known statically, so a missing impl is an error
+        if let TypedValueInner::Primitive(prim) = &receiver_type.inner {
+            if !self
+                .trait_impls
+                .contains(&(prim.name.clone(), trait_name.clone()))
+            {
+                self.errors.push(TypeError::MissingTraitImpl {
+                    type_name: prim.name.clone(),
+                    trait_name,
+                });
+            }
+        }
+
+        let expected = self.trait_method_type(&signature, &receiver_type);
+        for (param, arg) in expected.params.iter().zip(&arg_types) {
+            self.expect_type(param, arg);
+        }
+        Ok(*expected.result)
+    }
+
     /// Infer type of let expression
     fn infer_let(
         &mut self,
@@ -463,6 +1589,73 @@ impl TypeInferencer {
         }
     }
 
+    /// Infer type of while loop
+    fn infer_while(&mut self, graph: &Graph, condition: NodeId, body: NodeId) -> Result<TypedValue> {
+        let cond_type = self.infer_node(graph, condition)?;
+        self.expect_type(&TypedValue::primitive(PrimitiveType::bool()), &cond_type);
+        self.infer_loop_body(graph, body)
+    }
+
+    /// Infer type of for loop; the pattern binds each element of the iterable
+    fn infer_for(
+        &mut self,
+        graph: &Graph,
+        pattern: &Pattern,
+        iterable: NodeId,
+        body: NodeId,
+    ) -> Result<TypedValue> {
+        let iter_type = self.infer_node(graph, iterable)?;
+        let elem_type = self.env.fresh_type("a");
+        self.expect_type(&TypedValue::list(ListType::new(elem_type.clone())), &iter_type);
+
+        self.env.push_scope();
+        let elem_type = self.subst.apply_type(&elem_type);
+        let result = self
+            .check_pattern(graph, pattern, &elem_type)
+            .and_then(|_| self.infer_loop_body(graph, body));
+        self.env.pop_scope();
+        result
+    }
+
+    /// Infer the type of a loop from its body and the values it breaks with.
+    /// A loop that runs to completion produces nil, so like an `if` without an
+    /// `else` its break values must be nil as well.
+    fn infer_loop_body(&mut self, graph: &Graph, body: NodeId) -> Result<TypedValue> {
+        self.loop_breaks.push(TypedValue::primitive(PrimitiveType::unit()));
+        let body_type = self.infer_node(graph, body);
+        let loop_type = self.loop_breaks.pop();
+        body_type?;
+        Ok(loop_type
+            .map(|t| self.subst.apply_type(&t))
+            .unwrap_or_else(|| TypedValue::primitive(PrimitiveType::unit())))
+    }
+
+    /// Infer type of range; both bounds must be integers
+    fn infer_range(&mut self, graph: &Graph, start: NodeId, end: NodeId) -> Result<TypedValue> {
+        let int_type = TypedValue::primitive(PrimitiveType::int());
+        for bound in [start, end] {
+            let bound_type = self.infer_node(graph, bound)?;
+            self.expect_type(&int_type, &bound_type);
+        }
+        Ok(TypedValue::list(ListType::new(int_type)))
+    }
+
+    /// Infer type of break; its value becomes the result of the enclosing loop
+    fn infer_break(&mut self, graph: &Graph, value: Option<NodeId>) -> Result<TypedValue> {
+        let value_type = match value {
+            Some(value) => self.infer_node(graph, value)?,
+            None => TypedValue::primitive(PrimitiveType::unit()),
+        };
+        match self.loop_breaks.last().cloned() {
+            Some(expected) => {
+                self.expect_type(&expected, &value_type);
+            }
+            None => return Err(anyhow!("break outside of loop")),
+        }
+        // Control never continues past a break
+        Ok(self.env.fresh_type("T"))
+    }
+
     /// Infer type of list
     fn infer_list(&mut self, graph: &Graph, elements: &[NodeId]) -> Result<TypedValue> {
         if elements.is_empty() {
@@ -493,6 +1686,19 @@ impl TypeInferencer {
         }
     }
 
+    /// Infer type of tuple; unlike a list, each element keeps its own type
+    fn infer_tuple(&mut self, graph: &Graph, elements: &[NodeId]) -> Result<TypedValue> {
+        let mut element_types = Vec::with_capacity(elements.len());
+        for &elem_id in elements {
+            element_types.push(self.infer_node(graph, elem_id)?);
+        }
+        let element_types = element_types
+            .iter()
+            .map(|ty| self.subst.apply_type(ty))
+            .collect();
+        Ok(TypedValue::tuple(TupleType::new(element_types)))
+    }
+
     /// Infer type of pattern match
     fn infer_match(
         &mut self,
@@ -511,7 +1717,7 @@ impl TypeInferencer {
             self.env.push_scope();
 
             // Pattern matching adds bindings
-            self.check_pattern(pattern, &expr_type)?;
+            self.check_pattern(graph, pattern, &expr_type)?;
 
             // Infer body type
             let body_type = self.infer_node(graph, *body_id)?;
@@ -534,7 +1740,12 @@ impl TypeInferencer {
     }
 
     /// Check pattern and add bindings
-    fn check_pattern(&mut self, pattern: &Pattern, expected_type: &TypedValue) -> Result<()> {
+    fn check_pattern(
+        &mut self,
+        graph: &Graph,
+        pattern: &Pattern,
+        expected_type: &TypedValue,
+    ) -> Result<()> {
         match pattern {
             Pattern::Variable(name) => {
                 self.env.bind(name, expected_type.clone());
@@ -542,64 +1753,53 @@ impl TypeInferencer {
             }
             Pattern::Literal(lit) => {
                 let lit_type = self.infer_literal(lit)?;
-                match self.unifier.unify(&lit_type, expected_type) {
-                    Ok(new_subst) => {
-                        self.subst.compose(&new_subst);
-                        Ok(())
-                    }
-                    Err(_) => {
-                        self.errors.push(TypeError::PatternMatchFailure(format!(
-                            "Pattern type {} doesn't match expected {}",
-                            lit_type, expected_type
-                        )));
-                        Err(anyhow!("Pattern type mismatch"))
-                    }
-                }
+                self.unify_pattern_type(&lit_type, expected_type)
             }
-            Pattern::Constructor {
-                name: _,
-                patterns: _,
-            } => {
-                // TODO: Implement constructor pattern matching
-                self.errors.push(TypeError::PatternMatchFailure(
-                    "Constructor patterns not yet implemented".to_string(),
-                ));
-                Err(anyhow!("Constructor patterns not yet implemented"))
+            Pattern::Constructor { name, patterns } => {
+                self.check_constructor_pattern(graph, name, patterns, expected_type)
+            }
+            Pattern::Tuple(patterns) => {
+                let element_types: Vec<_> = patterns
+                    .iter()
+                    .map(|_| self.env.fresh_type("elem"))
+                    .collect();
+                let tuple = TypedValue::tuple(TupleType::new(element_types.clone()));
+                self.unify_pattern_type(&tuple, expected_type)?;
+                for (pattern, element_type) in patterns.iter().zip(&element_types) {
+                    let element_type = self.subst.apply_type(element_type);
+                    self.check_pattern(graph, pattern, &element_type)?;
+                }
+                Ok(())
             }
             Pattern::Wildcard => Ok(()),
-            Pattern::Guard {
-                pattern,
-                condition: _,
-            } => {
-                // For guard patterns, check the inner pattern
-                // The condition will be checked separately during evaluation
-                self.check_pattern(pattern, expected_type)
+            Pattern::Guard { pattern, condition } => {
+                // The guard sees the bindings of the pattern it guards
+                self.check_pattern(graph, pattern, expected_type)?;
+                let condition_type = self.infer_node(graph, *condition)?;
+                let bool_type = TypedValue::primitive(PrimitiveType::bool());
+                if !self.expect_type(&bool_type, &condition_type) {
+                    return Err(anyhow!("Guard condition must be Bool"));
+                }
+                Ok(())
             }
             Pattern::As { binding, pattern } => {
                 // For as-patterns, bind the name and check the inner pattern
                 self.env.bind(binding, expected_type.clone());
-                self.check_pattern(pattern, expected_type)
-            }
-            Pattern::Or(patterns) => {
-                // For or-patterns, check that all alternatives have the same type
-                for p in patterns {
-                    self.check_pattern(p, expected_type)?;
-                }
-                Ok(())
+                self.check_pattern(graph, pattern, expected_type)
             }
-            Pattern::Range(_) => {
-                // Range patterns only work with numeric types
+            Pattern::Or(patterns) => self.check_or_pattern(graph, patterns, expected_type),
+            Pattern::Range(range) => {
+                // Range patterns only work with numeric types, and both ends share one
+                let start_type = self.infer_literal(&range.start)?;
+                let end_type = self.infer_literal(&range.end)?;
+                self.unify_pattern_type(&start_type, expected_type)?;
+                self.unify_pattern_type(&end_type, expected_type)?;
+
+                let expected_type = self.subst.apply_type(expected_type);
                 match &expected_type.inner {
-                    TypedValueInner::Primitive(prim) => match prim.name.as_str() {
-                        "Int" | "Float" => Ok(()),
-                        _ => {
-                            self.errors.push(TypeError::PatternMatchFailure(format!(
-                                "Range patterns can only match numeric types, not {}",
-                                expected_type
-                            )));
-                            Err(anyhow!("Range pattern type mismatch"))
-                        }
-                    },
+                    TypedValueInner::Primitive(prim) if matches!(prim.name.as_str(), "Int" | "Float") => {
+                        Ok(())
+                    }
                     _ => {
                         self.errors.push(TypeError::PatternMatchFailure(format!(
                             "Range patterns can only match numeric types, not {}",
@@ -609,18 +1809,180 @@ impl TypeInferencer {
                     }
                 }
             }
-            Pattern::View {
-                function: _,
-                pattern,
-            } => {
-                // For view patterns, we would need to infer the function type
-                // and check that the pattern matches the function's return type
-                // For now, just check the inner pattern
-                self.check_pattern(pattern, expected_type)
+            Pattern::View { function, pattern } => {
+                // The view function maps the scrutinee to the value the inner pattern sees
+                let function_type = self.infer_node(graph, *function)?;
+                let view_type = self.env.fresh_type("view");
+                let expected_function = TypedValue::function_with_row(FunctionType::new(
+                    vec![expected_type.clone()],
+                    view_type.clone(),
+                ));
+                if !self.expect_type(&expected_function, &function_type) {
+                    return Err(anyhow!("View function does not accept the matched type"));
+                }
+                let view_type = self.subst.apply_type(&view_type);
+                self.check_pattern(graph, pattern, &view_type)
+            }
+        }
+    }
+
+    /// Unify the type a pattern matches with the scrutinee type
+    fn unify_pattern_type(&mut self, pattern_type: &TypedValue, expected_type: &TypedValue) -> Result<()> {
+        let pattern_type = self.subst.apply_type(pattern_type);
+        let expected_type = self.subst.apply_type(expected_type);
+        match self.unifier.unify(&pattern_type, &expected_type) {
+            Ok(new_subst) => {
+                self.subst.compose(&new_subst);
+                Ok(())
+            }
+            Err(_) => {
+                self.errors.push(TypeError::PatternMatchFailure(format!(
+                    "Pattern type {} doesn't match expected {}",
+                    pattern_type, expected_type
+                )));
+                Err(anyhow!("Pattern type mismatch"))
             }
         }
     }
 
+    /// Check a constructor pattern against an enum variant, or `Cons`/`Nil` against a list
+    fn check_constructor_pattern(
+        &mut self,
+        graph: &Graph,
+        name: &str,
+        patterns: &[Pattern],
+        expected_type: &TypedValue,
+    ) -> Result<()> {
+        let (constructed, field_types) = if let Some(enum_name) = self.enum_variants.get(name).cloned() {
+            let declared = self.enums[&enum_name]
+                .iter()
+                .find(|v| v.name == name)
+                .map_or(0, |v| v.fields.len());
+            let mut vars = self.type_param_vars(&enum_name, vec![]);
+            let enum_type = self.enum_type(&enum_name, &mut vars);
+            let payload = match &enum_type.inner {
+                TypedValueInner::Variant(variant) => variant.variants.get(name).cloned().flatten(),
+                _ => None,
+            };
+            // Several fields travel as one tuple payload
+            let field_types = match (declared, payload) {
+                (1, Some(payload)) => vec![payload],
+                (_, Some(TypedValue { inner: TypedValueInner::Tuple(tuple), .. })) => tuple.elements,
+                _ => vec![],
+            };
+            (enum_type, field_types)
+        } else if matches!(name, "Cons" | "cons") && patterns.len() == 2 {
+            let element = self.env.fresh_type("elem");
+            let list = TypedValue::list(ListType::new(element.clone()));
+            (list.clone(), vec![element, list])
+        } else if matches!(name, "Nil" | "nil") && patterns.is_empty() {
+            let element = self.env.fresh_type("elem");
+            (TypedValue::list(ListType::new(element)), vec![])
+        } else {
+            self.errors.push(TypeError::PatternMatchFailure(format!(
+                "Unknown constructor {}",
+                name
+            )));
+            return Err(anyhow!("Unknown constructor {}", name));
+        };
+
+        if field_types.len() != patterns.len() {
+            self.errors.push(TypeError::PatternMatchFailure(format!(
+                "Constructor {} expects {} field(s), found {}",
+                name,
+                field_types.len(),
+                patterns.len()
+            )));
+            return Err(anyhow!("Constructor pattern arity mismatch"));
+        }
+
+        self.unify_pattern_type(&constructed, expected_type)?;
+        for (pattern, field_type) in patterns.iter().zip(&field_types) {
+            let field_type = self.subst.apply_type(field_type);
+            self.check_pattern(graph, pattern, &field_type)?;
+        }
+        Ok(())
+    }
+
+    /// Check each alternative of an or-pattern; all of them must bind the same names at the same types
+    fn check_or_pattern(
+        &mut self,
+        graph: &Graph,
+        patterns: &[Pattern],
+        expected_type: &TypedValue,
+    ) -> Result<()> {
+        let mut bound: Option<(Vec<String>, Vec<TypedValue>)> = None;
+
+        for alternative in patterns {
+            let mut names = Vec::new();
+            Self::pattern_bindings(alternative, &mut names);
+            names.sort();
+            names.dedup();
+
+            self.env.push_scope();
+            let checked = self.check_pattern(graph, alternative, expected_type);
+            let types: Vec<_> = names
+                .iter()
+                .filter_map(|name| self.env.lookup(name).cloned())
+                .collect();
+            self.env.pop_scope();
+            checked?;
+
+            match &bound {
+                None => bound = Some((names, types)),
+                Some((first_names, _)) if *first_names != names => {
+                    self.errors.push(TypeError::PatternMatchFailure(format!(
+                        "Or-pattern alternatives bind different variables: {:?} and {:?}",
+                        first_names, names
+                    )));
+                    return Err(anyhow!("Or-pattern binding mismatch"));
+                }
+                Some((_, first_types)) => {
+                    for (first, other) in first_types.clone().iter().zip(&types) {
+                        if !self.expect_type(first, other) {
+                            return Err(anyhow!("Or-pattern binding type mismatch"));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some((names, types)) = bound {
+            for (name, ty) in names.iter().zip(&types) {
+                let ty = self.subst.apply_type(ty);
+                self.env.bind(name, ty);
+            }
+        }
+        Ok(())
+    }
+
+    /// Names a pattern binds
+    fn pattern_bindings(pattern: &Pattern, names: &mut Vec<String>) {
+        match pattern {
+            // `_` inside a constructor pattern is a wildcard, not a binding
+            Pattern::Variable(name) if name != "_" => names.push(name.clone()),
+            Pattern::Constructor { patterns, .. } | Pattern::Tuple(patterns) => {
+                for p in patterns {
+                    Self::pattern_bindings(p, names);
+                }
+            }
+            Pattern::Guard { pattern, .. } | Pattern::View { pattern, .. } => {
+                Self::pattern_bindings(pattern, names)
+            }
+            Pattern::As { binding, pattern } => {
+                names.push(binding.clone());
+                Self::pattern_bindings(pattern, names);
+            }
+            // Alternatives bind the same names; the first one speaks for all
+            Pattern::Or(patterns) => {
+                if let Some(first) = patterns.first() {
+                    Self::pattern_bindings(first, names);
+                }
+            }
+            Pattern::Variable(_) | Pattern::Literal(_) | Pattern::Wildcard | Pattern::Range(_) => {}
+        }
+    }
+
     /// Infer type of effect expression
     fn infer_effect(
         &mut self,
@@ -635,13 +1997,97 @@ impl TypeInferencer {
             arg_types.push(self.infer_node(graph, arg)?);
         }
 
-        // Get effect handler type
-        let effect_ty = self.get_effect_type(effect_type, operation, &arg_types)?;
+        let effect_ty = match effect_type {
+            EffectType::Custom(_) => match self.effect_operation(effect_type, operation) {
+                Some(signature) => {
+                    let expected = TypedValue::function_with_row(signature);
+                    let found = TypedValue::function_with_row(FunctionType::new(
+                        arg_types,
+                        self.env.fresh_type("effect"),
+                    ));
+                    self.expect_type(&expected, &found);
+                    match &self.subst.apply_type(&found).inner {
+                        TypedValueInner::Function(f) => (*f.result).clone(),
+                        _ => self.env.fresh_type("effect"),
+                    }
+                }
+                None => self.env.fresh_type("effect"),
+            },
+            _ => self.get_effect_type(effect_type, operation, &arg_types)?,
+        };
 
         // Add effect to the result
         Ok(effect_ty.add_effect(effect_type))
     }
 
+    /// Signature of an operation of a user-declared effect, reporting unknown effects and
+    /// operations; unannotated parameters and results are fresh type variables
+    fn effect_operation(&mut self, effect: EffectType, operation: &str) -> Option<FunctionType> {
+        let name = effect.to_string();
+        let Some(operations) = self.effect_decls.get(&name) else {
+            self.errors.push(TypeError::UnknownEffect(name));
+            return None;
+        };
+        let Some(declared) = operations.iter().find(|op| op.name == operation).cloned() else {
+            self.errors.push(TypeError::UnknownEffectOperation {
+                effect: name,
+                operation: operation.to_string(),
+            });
+            return None;
+        };
+        let mut vars = FxHashMap::default();
+        let params = declared
+            .params
+            .iter()
+            .map(|(_, ty)| self.type_from_expr(ty, &mut vars))
+            .collect();
+        let result = match &declared.return_type {
+            Some(ty) => self.type_from_expr(ty, &mut vars),
+            None => self.env.fresh_type("effect"),
+        };
+        Some(FunctionType::new(params, result))
+    }
+
+    /// Infer type of handle expression: the body's type without the effects it handles
+    fn infer_handler(
+        &mut self,
+        graph: &Graph,
+        handlers: &[(EffectType, Option<String>, NodeId)],
+        body: NodeId,
+    ) -> Result<TypedValue> {
+        let mut handled = HashSet::new();
+        for (effect, operation, handler_fn) in handlers {
+            handled.insert(*effect);
+            let handler_type = self.infer_node(graph, *handler_fn)?;
+            // Handlers of declared operations take its arguments and produce its result
+            if let (EffectType::Custom(_), Some(operation)) = (effect, operation) {
+                if let Some(signature) = self.effect_operation(*effect, operation) {
+                    let tail = self.fresh_row_var();
+                    let expected = signature.with_effect_row(EffectRow::open([], tail));
+                    self.expect_type(&TypedValue::function_with_row(expected), &handler_type);
+                }
+            }
+        }
+
+        // The body performs into its own row so the handled effects can be removed
+        let row = EffectRow::open([], self.fresh_row_var());
+        self.effect_frames.push(EffectFrame {
+            row,
+            params: Vec::new(),
+        });
+        let body_type = self.infer_node(graph, body);
+        let frame = self.effect_frames.pop();
+        let mut body_type = body_type?;
+
+        if let Some(frame) = frame {
+            let mut row = self.subst.apply_row(&frame.row);
+            row.effects.retain(|effect| !handled.contains(effect));
+            self.perform(&row);
+        }
+        body_type.effects.retain(|effect| !handled.contains(effect));
+        Ok(body_type)
+    }
+
     /// Infer type of async expression
     fn infer_async(&mut self, graph: &Graph, body: NodeId) -> Result<TypedValue> {
         let body_type = self.infer_node(graph, body)?;
@@ -666,21 +2112,46 @@ impl TypeInferencer {
         Ok(expr_type.add_effect(fluentai_core::ast::EffectType::Concurrent))
     }
 
+    /// Infer type of a parallel block: the list of its branch results,
+    /// which must all have the same type
+    fn infer_parallel(&mut self, graph: &Graph, branches: &[NodeId]) -> Result<TypedValue> {
+        let elem_type = self.env.fresh_type("T");
+        for branch in branches {
+            let branch_type = self.infer_node(graph, *branch)?;
+            self.expect_type(&elem_type, &branch_type);
+        }
+        let elem_type = self.subst.apply_type(&elem_type);
+        Ok(TypedValue::list(ListType::new(elem_type))
+            .add_effect(fluentai_core::ast::EffectType::Concurrent))
+    }
+
     /// Infer type of channel creation
-    fn infer_channel(&mut self) -> Result<TypedValue> {
-        // Channel[T] where T is a fresh type variable
+    fn infer_channel(&mut self, graph: &Graph, capacity: Option<NodeId>) -> Result<TypedValue> {
+        if let Some(capacity) = capacity {
+            let capacity_type = self.infer_node(graph, capacity)?;
+            self.expect_type(&TypedValue::primitive(PrimitiveType::int()), &capacity_type);
+        }
+
+        // Chan<T> where T is fixed by the first send, receive or annotation
         let elem_type = self.env.fresh_type("chan");
-        let channel_type =
-            TypedValue::variant(VariantType::new().with_variant("Channel", Some(elem_type)));
-        Ok(channel_type.add_effect(fluentai_core::ast::EffectType::Concurrent))
+        Ok(TypedValue::channel(ChannelType::new(elem_type))
+            .add_effect(fluentai_core::ast::EffectType::Concurrent))
+    }
+
+    /// Unify a channel operand with `Chan<T>` and return `T`
+    fn infer_channel_element(&mut self, graph: &Graph, channel: NodeId) -> Result<TypedValue> {
+        let channel_type = self.infer_node(graph, channel)?;
+        let elem_type = self.env.fresh_type("chan");
+        let expected = TypedValue::channel(ChannelType::new(elem_type.clone()));
+        self.expect_type(&expected, &channel_type);
+        Ok(self.subst.apply_type(&elem_type))
     }
 
     /// Infer type of send operation
     fn infer_send(&mut self, graph: &Graph, channel: NodeId, value: NodeId) -> Result<TypedValue> {
-        let _channel_type = self.infer_node(graph, channel)?;
-        let _value_type = self.infer_node(graph, value)?;
-
-        // TODO: Validate channel and value types match
+        let elem_type = self.infer_channel_element(graph, channel)?;
+        let value_type = self.infer_node(graph, value)?;
+        self.expect_type(&elem_type, &value_type);
 
         Ok(TypedValue::primitive(PrimitiveType::unit())
             .add_effect(fluentai_core::ast::EffectType::Concurrent))
@@ -688,21 +2159,16 @@ impl TypeInferencer {
 
     /// Infer type of receive operation
     fn infer_receive(&mut self, graph: &Graph, channel: NodeId) -> Result<TypedValue> {
-        let _channel_type = self.infer_node(graph, channel)?;
-
-        // Extract element type from channel
-        // TODO: Proper channel type extraction
-        let elem_type = self.env.fresh_type("recv");
-
+        let elem_type = self.infer_channel_element(graph, channel)?;
         Ok(elem_type.add_effect(fluentai_core::ast::EffectType::Concurrent))
     }
     
     /// Infer type of try-send operation
     fn infer_try_send(&mut self, graph: &Graph, channel: NodeId, value: NodeId) -> Result<TypedValue> {
-        let _channel_type = self.infer_node(graph, channel)?;
-        let _value_type = self.infer_node(graph, value)?;
-        
-        // TODO: Verify value type matches channel element type
+        let elem_type = self.infer_channel_element(graph, channel)?;
+        let value_type = self.infer_node(graph, value)?;
+        self.expect_type(&elem_type, &value_type);
+
         // Returns a boolean indicating success/failure
         Ok(TypedValue::primitive(PrimitiveType::bool())
             .add_effect(fluentai_core::ast::EffectType::Concurrent))
@@ -710,14 +2176,9 @@ impl TypeInferencer {
     
     /// Infer type of try-receive operation  
     fn infer_try_receive(&mut self, graph: &Graph, channel: NodeId) -> Result<TypedValue> {
-        let _channel_type = self.infer_node(graph, channel)?;
-        
-        // Extract element type from channel
-        // TODO: Proper channel type extraction
-        let elem_type = self.env.fresh_type("recv");
+        let elem_type = self.infer_channel_element(graph, channel)?;
         
         // Returns a list [bool, T] where bool indicates success
-        let _bool_type = TypedValue::primitive(PrimitiveType::bool());
         let list_type = TypedValue::list(ListType::new(elem_type));
         
         Ok(list_type.add_effect(fluentai_core::ast::EffectType::Concurrent))
@@ -774,12 +2235,12 @@ impl TypeInferencer {
         let string = || TypedValue::primitive(P::string());
         let unit = || TypedValue::primitive(P::unit());
 
-        let binary_int = || TypedValue::function(FunctionType::new(vec![int(), int()], int()));
+        let binary_int = || TypedValue::function_with_row(FunctionType::new(vec![int(), int()], int()));
 
         let binary_float =
-            || TypedValue::function(FunctionType::new(vec![float(), float()], float()));
+            || TypedValue::function_with_row(FunctionType::new(vec![float(), float()], float()));
 
-        let comparison = || TypedValue::function(FunctionType::new(vec![int(), int()], bool()));
+        let comparison = || TypedValue::function_with_row(FunctionType::new(vec![int(), int()], bool()));
 
         match name {
             // Arithmetic
@@ -804,15 +2265,15 @@ impl TypeInferencer {
             "!=" | "<>" => Some(comparison()),
 
             // Boolean
-            "and" => Some(TypedValue::function(FunctionType::new(
+            "and" => Some(TypedValue::function_with_row(FunctionType::new(
                 vec![bool(), bool()],
                 bool(),
             ))),
-            "or" => Some(TypedValue::function(FunctionType::new(
+            "or" => Some(TypedValue::function_with_row(FunctionType::new(
                 vec![bool(), bool()],
                 bool(),
             ))),
-            "not" => Some(TypedValue::function(FunctionType::new(
+            "not" => Some(TypedValue::function_with_row(FunctionType::new(
                 vec![bool()],
                 bool(),
             ))),
@@ -820,7 +2281,7 @@ impl TypeInferencer {
             // List operations
             "car" | "head" | "first" => {
                 let a = self.env.fresh_type("a");
-                Some(TypedValue::function(FunctionType::new(
+                Some(TypedValue::function_with_row(FunctionType::new(
                     vec![TypedValue::list(ListType::new(a.clone()))],
                     a,
                 )))
@@ -828,7 +2289,7 @@ impl TypeInferencer {
             "cdr" | "tail" | "rest" => {
                 let a = self.env.fresh_type("a");
                 let list_a = TypedValue::list(ListType::new(a));
-                Some(TypedValue::function(FunctionType::new(
+                Some(TypedValue::function_with_row(FunctionType::new(
                     vec![list_a.clone()],
                     list_a,
                 )))
@@ -836,48 +2297,94 @@ impl TypeInferencer {
             "cons" => {
                 let a = self.env.fresh_type("a");
                 let list_a = TypedValue::list(ListType::new(a.clone()));
-                Some(TypedValue::function(FunctionType::new(
+                Some(TypedValue::function_with_row(FunctionType::new(
                     vec![a, list_a.clone()],
                     list_a,
                 )))
             }
             "list-len" | "length" => {
                 let a = self.env.fresh_type("a");
-                Some(TypedValue::function(FunctionType::new(
+                Some(TypedValue::function_with_row(FunctionType::new(
                     vec![TypedValue::list(ListType::new(a))],
                     int(),
                 )))
             }
             "list-empty?" | "empty?" => {
                 let a = self.env.fresh_type("a");
-                Some(TypedValue::function(FunctionType::new(
+                Some(TypedValue::function_with_row(FunctionType::new(
                     vec![TypedValue::list(ListType::new(a))],
                     bool(),
                 )))
             }
 
             // String operations
-            "str-len" | "string-length" => Some(TypedValue::function(FunctionType::new(
+            "str-len" | "string-length" => Some(TypedValue::function_with_row(FunctionType::new(
                 vec![string()],
                 int(),
             ))),
-            "str-concat" | "string-append" => Some(TypedValue::function(FunctionType::new(
+            "str-concat" | "string-append" => Some(TypedValue::function_with_row(FunctionType::new(
                 vec![string(), string()],
                 string(),
             ))),
-            "str-upper" | "string-upcase" => Some(TypedValue::function(FunctionType::new(
+            "str-upper" | "string-upcase" => Some(TypedValue::function_with_row(FunctionType::new(
                 vec![string()],
                 string(),
             ))),
-            "str-lower" | "string-downcase" => Some(TypedValue::function(FunctionType::new(
+            "str-lower" | "string-downcase" => Some(TypedValue::function_with_row(FunctionType::new(
                 vec![string()],
                 string(),
             ))),
 
+            // Higher-order list operations perform the effects of the function they are given
+            "map" => {
+                let a = self.env.fresh_type("a");
+                let b = self.env.fresh_type("b");
+                let row = EffectRow::open([], self.fresh_row_var());
+                let f = FunctionType::new(vec![a.clone()], b.clone()).with_effect_row(row.clone());
+                Some(TypedValue::function_with_row(
+                    FunctionType::new(
+                        vec![
+                            TypedValue::function_with_row(f),
+                            TypedValue::list(ListType::new(a)),
+                        ],
+                        TypedValue::list(ListType::new(b)),
+                    )
+                    .with_effect_row(row),
+                ))
+            }
+            "filter" => {
+                let a = self.env.fresh_type("a");
+                let row = EffectRow::open([], self.fresh_row_var());
+                let f = FunctionType::new(vec![a.clone()], bool()).with_effect_row(row.clone());
+                let list_a = TypedValue::list(ListType::new(a));
+                Some(TypedValue::function_with_row(
+                    FunctionType::new(vec![TypedValue::function_with_row(f), list_a.clone()], list_a)
+                        .with_effect_row(row),
+                ))
+            }
+            "fold" => {
+                let a = self.env.fresh_type("a");
+                let acc = self.env.fresh_type("b");
+                let row = EffectRow::open([], self.fresh_row_var());
+                let f = FunctionType::new(vec![acc.clone(), a.clone()], acc.clone())
+                    .with_effect_row(row.clone());
+                Some(TypedValue::function_with_row(
+                    FunctionType::new(
+                        vec![
+                            TypedValue::function_with_row(f),
+                            acc.clone(),
+                            TypedValue::list(ListType::new(a)),
+                        ],
+                        acc,
+                    )
+                    .with_effect_row(row),
+                ))
+            }
+
             // IO operations
             "print" => Some(
-                TypedValue::function(FunctionType::new(vec![self.env.fresh_type("a")], unit()))
-                    .add_effect(fluentai_core::ast::EffectType::IO),
+                FunctionType::new(vec![self.env.fresh_type("a")], unit())
+                    .with_effects([fluentai_core::ast::EffectType::IO].into_iter().collect()),
             ),
 
             _ => None,
@@ -134,7 +134,6 @@ mod advanced_inference_tests {
     }
 
     #[test]
-    #[ignore = "channel() function not in default environment"]
     fn test_channel_types() {
         // Test channel creation
         let code = "channel()";
@@ -142,8 +141,7 @@ mod advanced_inference_tests {
         let result = infer_with_env(code, TypeEnvironment::new());
         assert!(result.is_ok());
         let ty = result.unwrap();
-        // Channel type should be a variant
-        assert_eq!(ty.kind(), TypeKind::Variant);
+        assert_eq!(ty.kind(), TypeKind::Channel);
     }
 
     #[test]
@@ -400,6 +398,18 @@ mod advanced_inference_tests {
             .contains(&fluentai_core::ast::EffectType::Concurrent));
     }
 
+    #[test]
+    fn test_parallel_inference() {
+        let ty = infer_with_env("parallel { 1 + 2; 3 * 4 }", TypeEnvironment::new()).unwrap();
+        assert_eq!(ty.to_string(), "[Int] ~{Concurrent}");
+
+        // Branches produce the elements of one list, so they must agree
+        let graph = parse(r#"parallel { 1; "two" }"#).unwrap();
+        let mut inferencer = TypeInferencer::new();
+        let result = inferencer.infer_graph(&graph);
+        assert!(result.is_err() || !inferencer.errors().is_empty());
+    }
+
     #[test]
     #[ignore = "send!/recv! macro syntax not yet supported by parser"]
     fn test_send_receive_inference() {
@@ -561,4 +571,834 @@ mod advanced_inference_tests {
         // Should fail due to unbound variable
         assert!(result.is_err() || !inferencer.errors().is_empty());
     }
+
+    fn infer_with_errors(code: &str) -> (Result<TypedValue>, Vec<TypeError>) {
+        let graph = parse(code).unwrap();
+        let mut inferencer = TypeInferencer::new();
+        let result = inferencer
+            .infer_graph(&graph)
+            .map(|types| types[&graph.root_id.unwrap()].clone());
+        (result, inferencer.errors().to_vec())
+    }
+
+    const SHOW: &str = "private trait Show { function show(self) -> string; function width(self, pad: int) -> int; }\n";
+
+    #[test]
+    fn test_trait_method_call_uses_declared_return_type() {
+        let code = format!(
+            "{}Int as Show {{ private function show(self) {{ \"n\" }} private function width(self, pad) {{ pad + 1 }} }}\n(42).show()",
+            SHOW
+        );
+        let (result, errors) = infer_with_errors(&code);
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "String");
+    }
+
+    #[test]
+    fn test_trait_impl_missing_method() {
+        let code = format!(
+            "{}Int as Show {{ private function show(self) {{ \"n\" }} }}",
+            SHOW
+        );
+        let (_, errors) = infer_with_errors(&code);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::MissingTraitMethod { method, .. } if method == "width"
+        )));
+    }
+
+    #[test]
+    fn test_trait_impl_extra_method_and_arity() {
+        let code = format!(
+            "{}Int as Show {{ private function show(self, x) {{ \"n\" }} private function width(self, pad) {{ pad }} private function extra(self) {{ 1 }} }}",
+            SHOW
+        );
+        let (_, errors) = infer_with_errors(&code);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::UnknownTraitMethod { method, .. } if method == "extra"
+        )));
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::ArityMismatch { expected: 1, found: 2 }
+        )));
+    }
+
+    #[test]
+    fn test_trait_impl_for_unknown_trait() {
+        let (_, errors) = infer_with_errors("Int as Missing { private function f(self) { 1 } }");
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::UnknownTrait(name) if name == "Missing")));
+    }
+
+    #[test]
+    fn test_trait_method_on_type_without_impl() {
+        let code = format!(
+            "{}Int as Show {{ private function show(self) {{ \"n\" }} private function width(self, pad) {{ pad }} }}\n\"text\".show()",
+            SHOW
+        );
+        let (_, errors) = infer_with_errors(&code);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::MissingTraitImpl { type_name, trait_name }
+                if type_name == "String" && trait_name == "Show"
+        )));
+    }
+
+    #[test]
+    fn test_trait_method_call_arity() {
+        let code = format!(
+            "{}Int as Show {{ private function show(self) {{ \"n\" }} private function width(self, pad) {{ pad }} }}\n(1).width()",
+            SHOW
+        );
+        let (_, errors) = infer_with_errors(&code);
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::ArityMismatch { expected: 2, found: 1 })));
+    }
+
+    #[test]
+    fn test_trait_impl_method_types_are_checked() {
+        let code = format!(
+            "{}Int as Show {{ private function show(self) {{ 1 }} private function width(self, pad) {{ pad + 1 }} }}",
+            SHOW
+        );
+        let (_, errors) = infer_with_errors(&code);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, found }
+                if expected.ends_with("String") && found.ends_with("Int")
+        )), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_trait_method_call_argument_types() {
+        let code = format!(
+            "{}Int as Show {{ private function show(self) {{ \"n\" }} private function width(self, pad) {{ pad }} }}\n(1).width(\"wide\")",
+            SHOW
+        );
+        let (_, errors) = infer_with_errors(&code);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, found } if expected == "Int" && found == "String"
+        )), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_trait_method_call_without_receiver() {
+        let code = format!(
+            "{}Int as Show {{ private function show(self) {{ \"n\" }} private function width(self, pad) {{ pad }} }}\n(1).show()",
+            SHOW
+        );
+        let mut graph = parse(&code).unwrap();
+        for node in graph.nodes.values_mut() {
+            if let fluentai_core::ast::Node::MethodCall { args, .. } = node {
+                args.clear();
+            }
+        }
+        let mut inferencer = TypeInferencer::new();
+        let _ = inferencer.infer_graph(&graph);
+        assert!(inferencer
+            .errors()
+            .iter()
+            .any(|e| matches!(e, TypeError::ArityMismatch { expected: 1, found: 0 })));
+    }
+
+    #[test]
+    fn test_annotated_function_signature() {
+        let (result, errors) = infer_with_errors(
+            "private function add(x: Int, y: int) -> Int { x + y }\nadd(1, 2)",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+    }
+
+    #[test]
+    fn test_return_annotation_is_enforced() {
+        let (_, errors) = infer_with_errors("private function f(x: Int) -> String { x + 1 }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, found } if expected == "String" && found == "Int"
+        )));
+    }
+
+    #[test]
+    fn test_parameter_annotation_is_enforced() {
+        let (result, errors) = infer_with_errors("private function f(s: String) -> Int { s + 1 }");
+        assert!(result.is_err() || !errors.is_empty());
+
+        let (result, errors) = infer_with_errors("private function f(x: Int) -> Int { x }\nf(\"a\")");
+        assert!(result.is_err() || !errors.is_empty());
+    }
+
+    #[test]
+    fn test_extern_declaration_types_calls() {
+        const SHA256: &str = "extern function sha256(data: String) -> String\n";
+
+        let (result, errors) = infer_with_errors(&format!("{}sha256(\"abc\")", SHA256));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "String");
+
+        let (result, errors) = infer_with_errors(&format!("{}sha256(42)", SHA256));
+        assert!(result.is_err() || !errors.is_empty());
+
+        let (result, errors) = infer_with_errors(&format!("{}sha256(\"a\", \"b\")", SHA256));
+        assert!(result.is_err() || !errors.is_empty());
+    }
+
+    #[test]
+    fn test_extern_declaration_effects() {
+        let (result, errors) =
+            infer_with_errors("extern function log_line(line: String) with(IO)\nlog_line(\"hi\")");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Unit ~{IO}");
+    }
+
+    #[test]
+    fn test_let_annotation_is_enforced() {
+        let (result, errors) = infer_with_errors("{ let x: Float = 1.5; x }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Float");
+
+        let (_, errors) = infer_with_errors("{ let x: String = 42; x }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, found } if expected == "String" && found == "Int"
+        )));
+    }
+
+    #[test]
+    fn test_generic_and_function_type_annotations() {
+        let (result, errors) = infer_with_errors(
+            "private function first(xs: List<T>) -> T { car(xs) }\nfirst(list(1, 2))",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let (result, errors) = infer_with_errors(
+            "private function apply(f: (Int) -> Bool, x: Int) -> Bool { f(x) }\napply((y) => y > 1, 2)",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Bool");
+
+        let (result, errors) = infer_with_errors(
+            "private function apply(f: (Int) -> Bool, x: Int) -> Bool { f(x) }\napply((y) => y + 1, 2)",
+        );
+        assert!(result.is_err() || !errors.is_empty());
+    }
+
+    #[test]
+    fn test_loops_produce_nil() {
+        let (result, errors) =
+            infer_with_errors("for x in [1, 2, 3] { if (x > 1) { break } else { nil } }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Unit");
+
+        // A loop can also finish without breaking, so its value may be nil
+        let (_, errors) = infer_with_errors("for x in [1, 2, 3] { if (x > 1) { break x } else { nil } }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, found } if expected == "Unit" && found == "Int"
+        )));
+    }
+
+    #[test]
+    fn test_builtin_type_constructor_annotations() {
+        let (_, errors) = infer_with_errors(
+            "private function keep(xs: List<Option<T>>) -> List<Option<T>> { xs }\nkeep(list())",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let (_, errors) = infer_with_errors(
+            "private function size(m: Map<String, Int>, r: Result<Int, String>) -> Int { 1 }",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let (_, errors) = infer_with_errors("{ let m: Map<String> = 1; m }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeArgumentMismatch { expected: 2, found: 1, .. }
+        )));
+    }
+
+    #[test]
+    fn test_generic_declaration_annotations() {
+        const BOX: &str = "private struct Box<T> { value: T }\n";
+        let (result, errors) =
+            infer_with_errors(&format!("{}{{ let b: Box<Int> = Box {{ value: 1 }}; b.value }}", BOX));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let (_, errors) =
+            infer_with_errors(&format!("{}{{ let b: Box<String> = Box {{ value: 1 }}; b }}", BOX));
+        assert!(errors.iter().any(|e| matches!(e, TypeError::TypeMismatch { .. })));
+
+        let (_, errors) = infer_with_errors(&format!("{}{{ let b: Box = Box {{ value: 1 }}; b }}", BOX));
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeArgumentMismatch { expected: 1, found: 0, .. }
+        )));
+
+        const MAYBE: &str = "private enum Maybe<T> { Just(T), Nothing }\n\
+            private function get(m: Maybe<Int>) -> Int { match m { Just(x) => x, Nothing => 0 } }\n";
+        let (result, errors) = infer_with_errors(&format!("{}get(Just(2))", MAYBE));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let (result, errors) = infer_with_errors(&format!("{}get(Just(\"two\"))", MAYBE));
+        assert!(result.is_err() || !errors.is_empty());
+    }
+
+    #[test]
+    fn test_struct_and_alias_annotations() {
+        let (result, errors) = infer_with_errors(
+            "private struct Point { x: int, y: int }\n{ let p: Point = Point { x: 1, y: 2 }; p.x }",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let (_, errors) = infer_with_errors(
+            "private struct Point { x: int, y: int }\n{ let p: Point = Point { x: 1, y: \"two\" }; p }",
+        );
+        assert!(errors.iter().any(|e| matches!(e, TypeError::TypeMismatch { .. })));
+
+        let (_, errors) = infer_with_errors("private type Count = Int\n{ let n: Count = \"many\"; n }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, .. } if expected == "Int"
+        )));
+    }
+
+    #[test]
+    fn test_struct_update_is_checked_against_definition() {
+        const POINT: &str = "private struct Point { x: int, y: int }\n";
+        let (result, errors) =
+            infer_with_errors(&format!("{}{{ let p = Point {{ x: 1, y: 2 }}; Point {{ x: 3, ..p }}.y }}", POINT));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let (_, errors) =
+            infer_with_errors(&format!("{}{{ let p = Point {{ x: 1, y: 2 }}; Point {{ z: 3, ..p }} }}", POINT));
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, .. } if expected == "record with field 'z'"
+        )));
+
+        let (_, errors) =
+            infer_with_errors(&format!("{}{{ let p = Point {{ x: 1, y: 2 }}; Point {{ x: \"three\", ..p }} }}", POINT));
+        assert!(errors.iter().any(|e| matches!(e, TypeError::TypeMismatch { .. })));
+
+        let (_, errors) = infer_with_errors(&format!("{}Point {{ x: 3, ..\"text\" }}", POINT));
+        assert!(errors.iter().any(|e| matches!(e, TypeError::TypeMismatch { .. })));
+
+        let (_, errors) = infer_with_errors("{ let p = 1; Missing { x: 3, ..p } }");
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::UnknownType(name) if name == "Missing")));
+    }
+
+    #[test]
+    fn test_unknown_types_and_effects_in_annotations() {
+        let (_, errors) = infer_with_errors("{ let x: Missing = 1; x }");
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::UnknownType(name) if name == "Missing")));
+
+        let (_, errors) = infer_with_errors("private function run(g: () -> Int with(Magic)) { g() }");
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::UnknownEffect(name) if name == "Magic")));
+
+        let (_, errors) = infer_with_errors("{ let xs: List<Int, Int> = list(1); xs }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeArgumentMismatch { expected: 1, found: 2, .. }
+        )));
+    }
+
+    const SHAPE: &str = "private enum Shape { Circle(int), Rect(int, int), Empty }\n";
+
+    #[test]
+    fn test_constructor_patterns_bind_variant_fields() {
+        let code = format!(
+            "{}private function area(s: Shape) -> int {{ match s {{ Circle(r) => r * r, Rect(w, h) => w * h, Empty => 0 }} }}\narea(Rect(2, 3))",
+            SHAPE
+        );
+        let (result, errors) = infer_with_errors(&code);
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+    }
+
+    #[test]
+    fn test_constructor_pattern_infers_scrutinee_type() {
+        // The scrutinee is an unannotated parameter; the patterns pin it to Shape
+        let code = format!(
+            "{}private function width(s) {{ match s {{ Rect(w, _) => w, _ => 0 }} }}\nwidth(Circle(1))",
+            SHAPE
+        );
+        let (result, errors) = infer_with_errors(&code);
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let code = format!(
+            "{}private function width(s) {{ match s {{ Rect(w, _) => w, _ => 0 }} }}\nwidth(5)",
+            SHAPE
+        );
+        let (result, errors) = infer_with_errors(&code);
+        assert!(result.is_err() || !errors.is_empty());
+    }
+
+    #[test]
+    fn test_constructor_pattern_errors() {
+        let arity = format!("{}match Circle(1) {{ Rect(w) => w, _ => 0 }}", SHAPE);
+        let (_, errors) = infer_with_errors(&arity);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::PatternMatchFailure(msg) if msg.contains("expects 2 field(s), found 1")
+        )));
+
+        let unknown = format!("{}match Circle(1) {{ Triangle(a) => a, _ => 0 }}", SHAPE);
+        let (_, errors) = infer_with_errors(&unknown);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::PatternMatchFailure(msg) if msg.contains("Unknown constructor Triangle")
+        )));
+
+        let wrong_type = format!("{}match 42 {{ Circle(r) => r, _ => 0 }}", SHAPE);
+        let (_, errors) = infer_with_errors(&wrong_type);
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::PatternMatchFailure(_))));
+    }
+
+    #[test]
+    fn test_list_constructor_patterns() {
+        let (result, errors) =
+            infer_with_errors("match list(1, 2) { Cons(head, tail) => head, Nil => 0 }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let (result, errors) =
+            infer_with_errors("match list(1, 2) { Cons(head, tail) => tail, Nil => list(3) }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "[Int]");
+    }
+
+    #[test]
+    fn test_or_and_as_patterns() {
+        let code = format!(
+            "{}match Rect(1, 2) {{ Circle(x) | Rect(x, _) => x, Empty => 0 }}",
+            SHAPE
+        );
+        let (result, errors) = infer_with_errors(&code);
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let code = format!("{}match (Empty) {{ Circle(x) | Empty => 1, _ => 0 }}", SHAPE);
+        let (_, errors) = infer_with_errors(&code);
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::PatternMatchFailure(msg) if msg.contains("bind different variables")
+        )));
+
+        let code = format!("{}match Circle(3) {{ Circle(r) as c => c, other => other }}", SHAPE);
+        let (result, errors) = infer_with_errors(&code);
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        let shape = result.unwrap().to_string();
+        assert!(shape.contains("Circle(Int)") && shape.contains("Empty"), "got {}", shape);
+    }
+
+    #[test]
+    fn test_range_and_guard_patterns() {
+        let (result, errors) =
+            infer_with_errors("match 5 { 1..10 => \"small\", n when n > 100 => \"huge\", _ => \"big\" }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "String");
+
+        let (_, errors) = infer_with_errors("match \"five\" { 1..10 => 1, _ => 0 }");
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::PatternMatchFailure(_))));
+
+        let (result, errors) = infer_with_errors("match 5 { n when n + 1 => 1, _ => 0 }");
+        assert!(result.is_err() || !errors.is_empty());
+    }
+
+    #[test]
+    fn test_view_pattern_matches_function_result() {
+        use fluentai_core::ast::{Graph, Literal, Node, Pattern, RangePattern};
+
+        fn view_match(scrutinee: Literal) -> (Result<TypedValue>, Vec<TypeError>) {
+            let mut graph = Graph::new();
+            let expr = graph.add_node(Node::Literal(scrutinee)).unwrap();
+            let function = graph
+                .add_node(Node::Variable { name: "str-len".to_string() })
+                .unwrap();
+            let short = graph.add_node(Node::Literal(Literal::Boolean(true))).unwrap();
+            let long = graph.add_node(Node::Literal(Literal::Boolean(false))).unwrap();
+            let pattern = Pattern::view(
+                function,
+                Pattern::Range(RangePattern {
+                    start: Literal::Integer(0),
+                    end: Literal::Integer(5),
+                    inclusive: false,
+                }),
+            );
+            let root = graph
+                .add_node(Node::Match {
+                    expr,
+                    branches: vec![(pattern, short), (Pattern::Wildcard, long)],
+                    must_match: false,
+                })
+                .unwrap();
+            graph.root_id = Some(root);
+
+            let mut inferencer = TypeInferencer::new();
+            let result = inferencer.infer_graph(&graph).map(|types| types[&root].clone());
+            (result, inferencer.errors().to_vec())
+        }
+
+        let (result, errors) = view_match(Literal::String("abc".to_string()));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Bool");
+
+        let (result, errors) = view_match(Literal::Integer(3));
+        assert!(result.is_err() || !errors.is_empty());
+    }
+
+    #[test]
+    fn test_channel_element_type_flows_from_send_to_receive() {
+        let (result, errors) =
+            infer_with_errors("{ let ch = channel(); ch.send(42); ch.receive() }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().inner, TypedValueInner::Primitive(PrimitiveType::int()));
+
+        let (result, errors) = infer_with_errors("{ let ch: Chan<String> = channel(10); ch }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert!(result.unwrap().to_string().starts_with("Chan<String>"));
+    }
+
+    #[test]
+    fn test_send_checks_channel_element_type() {
+        let (_, errors) =
+            infer_with_errors("{ let ch: Chan<String> = channel(); ch.send(42) }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, found } if expected == "String" && found == "Int"
+        )));
+
+        let (_, errors) =
+            infer_with_errors("{ let ch = channel(); ch.send(1); ch.send(\"two\") }");
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_receive_requires_channel() {
+        let (_, errors) = infer_with_errors("{ let ch = 5; ch.receive() }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { found, .. } if found == "Int"
+        )));
+
+        let (_, errors) = infer_with_errors("{ let ch = channel(\"big\"); ch }");
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_select_branches_share_channel_types() {
+        use fluentai_core::ast::{Graph, Literal, Node};
+
+        let mut graph = Graph::new();
+        let channel = graph.add_node(Node::Channel { capacity: None }).unwrap();
+        let int_value = graph.add_node(Node::Literal(Literal::Integer(1))).unwrap();
+        let send = graph
+            .add_node(Node::Send { channel, value: int_value })
+            .unwrap();
+        let receive = graph.add_node(Node::Receive { channel }).unwrap();
+        let string_value = graph
+            .add_node(Node::Literal(Literal::String("x".to_string())))
+            .unwrap();
+        let bad_send = graph
+            .add_node(Node::TrySend { channel, value: string_value })
+            .unwrap();
+        let handler = graph.add_node(Node::Literal(Literal::Boolean(true))).unwrap();
+        let root = graph
+            .add_node(Node::Select {
+                branches: vec![(send, handler), (receive, handler), (bad_send, handler)],
+                default: None,
+            })
+            .unwrap();
+        graph.root_id = Some(root);
+
+        let mut inferencer = TypeInferencer::new();
+        let types = inferencer.infer_graph(&graph).unwrap();
+        assert_eq!(
+            types[&receive].inner,
+            TypedValueInner::Primitive(PrimitiveType::int())
+        );
+        assert!(inferencer.errors().iter().any(|e| matches!(
+            e,
+            TypeError::TypeMismatch { expected, found } if expected == "Int" && found == "String"
+        )));
+    }
+
+    #[test]
+    fn test_infer_interface_collects_exports() {
+        let graph = parse(
+            "mod geometry {\n  private function area(w: Int, h: Int) -> Int { w * h }\n  private function id(x) { x }\n  export { area, id as identity };\n}",
+        )
+        .unwrap();
+        let mut inferencer = TypeInferencer::new();
+        let interface = inferencer.infer_interface(&graph).unwrap();
+
+        assert_eq!(interface.name, "geometry");
+        assert_eq!(interface.exports.len(), 2);
+        assert_eq!(interface.get("area").unwrap().kind(), TypeKind::Function);
+        assert!(interface.get("identity").is_some());
+    }
+
+    #[test]
+    fn test_imports_use_registered_module_interfaces() {
+        use crate::interface::ModuleInterface;
+
+        let int = || TypedValue::primitive(PrimitiveType::int());
+        let identity = TypedValue::variable(TypeVariable::new("a"));
+        let interface = ModuleInterface::new("math")
+            .with_export(
+                "add",
+                TypedValue::function(FunctionType::new(vec![int(), int()], int())),
+            )
+            .with_export(
+                "id",
+                TypedValue::function(FunctionType::new(vec![identity.clone()], identity)),
+            );
+        let infer = |code: &str| {
+            let graph = parse(code).unwrap();
+            let mut inferencer = TypeInferencer::new();
+            inferencer.add_module_interface("math", interface.clone());
+            let result = inferencer
+                .infer_graph(&graph)
+                .map(|types| types[&graph.root_id.unwrap()].clone());
+            (result, inferencer.errors().to_vec())
+        };
+
+        let (result, errors) = infer("use math::{add};\nadd(1, 2)");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        // Each use of a polymorphic export gets fresh type variables
+        let (result, errors) = infer("use math::*;\n[id(1), add(id(2), 3)]");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "[Int]");
+
+        let (result, errors) = infer("use math as m;\nm.add(true, 1)");
+        assert!(result.is_err() || !errors.is_empty());
+
+        let (_, errors) = infer("use math::{sub};\n1");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::UnknownExport { module, name } if module == "math" && name == "sub"
+        )));
+    }
+
+    #[test]
+    fn test_map_is_effect_polymorphic() {
+        let (result, errors) = infer_with_errors("map");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        let map = result.unwrap();
+        let TypedValueInner::Function(map) = &map.inner else {
+            panic!("map is not a function: {}", map);
+        };
+        let TypedValueInner::Function(f) = &map.params[0].inner else {
+            panic!("map's first parameter is not a function");
+        };
+        // `(a -e→ b, List<a>) -e→ List<b>`: map performs exactly what `f` performs
+        assert!(map.effects.effects.is_empty());
+        assert!(map.effects.tail.is_some());
+        assert_eq!(f.effects, map.effects);
+    }
+
+    #[test]
+    fn test_higher_order_calls_perform_argument_effects() {
+        let (result, errors) =
+            infer_with_errors("map((x) => { print(x); x }, list(1, 2))");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert!(result.unwrap().effects.contains(&fluentai_core::ast::EffectType::IO));
+
+        let (result, errors) = infer_with_errors("map((x) => x + 1, list(1, 2))");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "[Int]");
+
+        // A user-defined higher-order function forwards its argument's effects
+        let (result, errors) = infer_with_errors(
+            "{ let apply = (f, x) => f(x); apply((y) => { print(y); y }, 1) }",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert!(result.unwrap().effects.contains(&fluentai_core::ast::EffectType::IO));
+    }
+
+    #[test]
+    fn test_inferred_function_effect_rows() {
+        let (result, _) = infer_with_errors("(f, x) => f(x)");
+        let ty = result.unwrap();
+        let TypedValueInner::Function(apply) = &ty.inner else {
+            panic!("not a function: {}", ty);
+        };
+        let TypedValueInner::Function(f) = &apply.params[0].inner else {
+            panic!("first parameter is not a function: {}", ty);
+        };
+        assert!(apply.effects.tail.is_some());
+        assert_eq!(f.effects, apply.effects);
+
+        // Rows not tied to a parameter are closed
+        let (result, _) = infer_with_errors("(x) => { print(x); x }");
+        let ty = result.unwrap();
+        let TypedValueInner::Function(logged) = &ty.inner else {
+            panic!("not a function: {}", ty);
+        };
+        assert_eq!(
+            logged.effects,
+            EffectRow::closed([fluentai_core::ast::EffectType::IO])
+        );
+        assert_eq!(infer_with_errors("(x) => x + 1").0.unwrap().to_string(), "Int → Int");
+    }
+
+    #[test]
+    fn test_effectful_argument_rejected_where_pure_function_required() {
+        const APPLY_PURE: &str =
+            "private function apply_pure(f: (Int) -> Int, x: Int) -> Int { f(x) }\n";
+
+        let (result, errors) = infer_with_errors(&format!("{}apply_pure((y) => y + 1, 1)", APPLY_PURE));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "Int");
+
+        let (result, errors) = infer_with_errors(&format!(
+            "{}apply_pure((y) => {{ print(y); y }}, 1)",
+            APPLY_PURE
+        ));
+        assert!(result.is_err());
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::UnificationFailure(..))));
+
+        // A pure function is accepted where effects are allowed
+        let (_, errors) = infer_with_errors(
+            "private function run(f: (Int) -> Int with(IO), x: Int) -> Int { f(x) }\nrun((y) => y, 1)",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+    }
+
+    #[test]
+    fn test_declared_effects_bound_function_body() {
+        let (_, errors) =
+            infer_with_errors("private function log(x: Int) -> Int .with(IO) { print(x); x }");
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let (_, errors) =
+            infer_with_errors("private function bump(x: Int) -> Int .with(State) { print(x); x }");
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::EffectConstraintViolation(msg) if msg.contains("IO") && msg.contains("State")
+        )));
+    }
+
+    const LOGGER: &str =
+        "private effect Logger { function log(msg: string) -> int; function flush(); }\n";
+
+    #[test]
+    fn test_declared_effect_close_to_builtin_name() {
+        let (result, errors) = infer_with_errors(
+            "private effect Timer { function now() -> int; }\n\
+             private function tick() -> Int .with(Timer) { perform Timer.now() }\n\
+             tick()",
+        );
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert!(result
+            .unwrap()
+            .effects
+            .contains(&fluentai_core::ast::EffectType::custom("Timer")));
+    }
+
+    #[test]
+    fn test_perform_custom_effect_uses_declared_signature() {
+        let (result, errors) = infer_with_errors(&format!("{}perform Logger.log(\"hi\")", LOGGER));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        let ty = result.unwrap();
+        assert_eq!(ty.to_string(), "Int ~{Logger}");
+        assert!(ty.effects.contains(&fluentai_core::ast::EffectType::custom("Logger")));
+
+        let (_, errors) = infer_with_errors(&format!("{}perform Logger.log(1)", LOGGER));
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::TypeMismatch { .. })));
+
+        let (_, errors) = infer_with_errors(&format!("{}perform Logger.warn(\"x\")", LOGGER));
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::UnknownEffectOperation { effect, operation }
+                if effect == "Logger" && operation == "warn"
+        )));
+
+        let (_, errors) = infer_with_errors("perform Metrics.inc()");
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::UnknownEffect(name) if name == "Metrics")));
+    }
+
+    #[test]
+    fn test_custom_effects_in_function_rows() {
+        let (result, errors) =
+            infer_with_errors(&format!("{}(x) => perform Logger.log(x)", LOGGER));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "String -Logger→ Int");
+
+        let (_, errors) = infer_with_errors(&format!(
+            "{}private function f(x: String) -> Int .with(Logger) {{ perform Logger.log(x) }}",
+            LOGGER
+        ));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+        let (_, errors) = infer_with_errors(&format!(
+            "{}private function f(x: String) -> Int .with(IO) {{ perform Logger.log(x) }}",
+            LOGGER
+        ));
+        assert!(errors.iter().any(|e| matches!(
+            e,
+            TypeError::EffectConstraintViolation(msg) if msg.contains("Logger")
+        )));
+    }
+
+    #[test]
+    fn test_tuple_types() {
+        let (result, errors) = infer_with_errors(r#"(1, "a", true)"#);
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "(Int, String, Bool)");
+
+        let (result, errors) = infer_with_errors(r#"match (1, "a") { (n, s) => s }"#);
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "String");
+
+        let (_, errors) = infer_with_errors("match (1, 2) { (a, b, c) => a }");
+        assert!(!errors.is_empty());
+    }
+
+    #[test]
+    fn test_handle_removes_handled_effects() {
+        let (result, errors) = infer_with_errors(&format!(
+            "{}(x) => handle {{ perform Logger.log(x); print(x); 1 }} with {{ Logger.log(msg) => 0 }}",
+            LOGGER
+        ));
+        assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+        assert_eq!(result.unwrap().to_string(), "String -IO→ Int");
+
+        // Handlers take the operation's arguments and produce its result
+        let (_, errors) = infer_with_errors(&format!(
+            "{}handle {{ perform Logger.log(\"a\") }} with {{ Logger.log(msg) => \"done\" }}",
+            LOGGER
+        ));
+        assert!(errors
+            .iter()
+            .any(|e| matches!(e, TypeError::TypeMismatch { .. })));
+    }
 }
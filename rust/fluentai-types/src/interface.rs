@@ -0,0 +1,42 @@
+//! Module interfaces: the inferred types of a module's exports
+//!
+//! An interface is produced by [`TypeInferencer::infer_interface`] and registered
+//! with [`TypeInferencer::add_module_interface`] when checking modules that import
+//! it, so imported names are typed without re-inferring the exporting module.
+//!
+//! [`TypeInferencer::infer_interface`]: crate::inference::TypeInferencer::infer_interface
+//! [`TypeInferencer::add_module_interface`]: crate::inference::TypeInferencer::add_module_interface
+
+use crate::types::TypedValue;
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Exported names of a module and their inferred types
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModuleInterface {
+    /// Module name
+    pub name: String,
+    /// Exported name -> type
+    pub exports: FxHashMap<String, TypedValue>,
+}
+
+impl ModuleInterface {
+    /// Create an interface with no exports
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            exports: FxHashMap::default(),
+        }
+    }
+
+    /// Add an exported name
+    pub fn with_export(mut self, name: impl Into<String>, ty: TypedValue) -> Self {
+        self.exports.insert(name.into(), ty);
+        self
+    }
+
+    /// Type of an exported name
+    pub fn get(&self, name: &str) -> Option<&TypedValue> {
+        self.exports.get(name)
+    }
+}
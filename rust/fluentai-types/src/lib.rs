@@ -5,7 +5,8 @@
 //! - **Type Representation**: Basic types, ADTs, effect types, probabilistic and temporal types
 //! - **Type Inference**: Hindley-Milner type inference with let-polymorphism
 //! - **Type Checking**: Validation with readable error messages
-//! - **Effect Tracking**: Automatic tracking of side effects
+//! - **Effect Tracking**: Automatic tracking of side effects, with effect-row polymorphism
+//! - **Match Checking**: Exhaustiveness and unreachable-arm detection for `match`
 //!
 //! # Example
 //!
@@ -34,17 +35,22 @@
 
 pub mod checker;
 pub mod environment;
+pub mod exhaustiveness;
 pub mod inference;
+pub mod interface;
 pub mod types;
 pub mod unification;
 
 // Re-export main types
 pub use checker::{TypeCheckError, TypeCheckResult, TypeCheckWarning, TypeChecker};
 pub use environment::{TypeEnvironment, TypeEnvironmentBuilder};
+pub use exhaustiveness::{check_matches, ExhaustivenessChecker, MatchIssue};
 pub use inference::{TypeError, TypeInferencer};
+pub use interface::ModuleInterface;
 pub use types::{
-    EffectTypeWrapper, FunctionType, ListType, PrimitiveType, RecordType, TemporalType, TupleType,
-    TypeConstraint, TypeKind, TypeVariable, TypedValue, UncertainType, VariantType,
+    ChannelType, EffectRow, EffectTypeWrapper, FunctionType, ListType, PrimitiveType, RecordType,
+    TemporalType, TupleType, TypeConstraint, TypeKind, TypeVariable, TypedValue, UncertainType,
+    VariantType,
 };
 pub use unification::{Substitution, UnificationError, Unifier};
 
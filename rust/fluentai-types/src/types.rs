@@ -33,6 +33,8 @@ pub enum TypeKind {
     Uncertain,
     /// Types with temporal constraints
     Temporal,
+    /// Channels carrying values of one element type
+    Channel,
     /// Type variables for polymorphism
     TypeVariable,
 }
@@ -49,6 +51,7 @@ impl fmt::Display for TypeKind {
             TypeKind::Effect => write!(f, "Effect"),
             TypeKind::Uncertain => write!(f, "Uncertain"),
             TypeKind::Temporal => write!(f, "Temporal"),
+            TypeKind::Channel => write!(f, "Channel"),
             TypeKind::TypeVariable => write!(f, "TypeVariable"),
         }
     }
@@ -111,11 +114,21 @@ impl PrimitiveType {
         Self::new("Float")
     }
 
+    /// Create a Decimal primitive type
+    pub fn decimal() -> Self {
+        Self::new("Decimal")
+    }
+
     /// Create a String primitive type
     pub fn string() -> Self {
         Self::new("String")
     }
 
+    /// Create a Bytes primitive type
+    pub fn bytes() -> Self {
+        Self::new("Bytes")
+    }
+
     /// Create a Bool primitive type
     pub fn bool() -> Self {
         Self::new("Bool")
@@ -138,6 +151,64 @@ impl fmt::Display for PrimitiveType {
     }
 }
 
+/// Effects a function performs when called
+///
+/// A row lists known effects and may end in a row variable standing for the effects of
+/// functions it receives, so `map` can be typed `(a -e→ b, List<a>) -e→ List<b>`.
+/// A row without a variable is closed: the function performs exactly those effects.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EffectRow {
+    /// Known effects, never including `Pure`
+    pub effects: HashSet<EffectType>,
+    /// Row variable for the remaining effects, if the row is open
+    pub tail: Option<String>,
+}
+
+impl EffectRow {
+    /// The row of a function with no effects
+    pub fn pure() -> Self {
+        Self::default()
+    }
+
+    /// A row performing exactly `effects`
+    pub fn closed(effects: impl IntoIterator<Item = EffectType>) -> Self {
+        Self {
+            effects: effects
+                .into_iter()
+                .filter(|e| *e != EffectType::Pure)
+                .collect(),
+            tail: None,
+        }
+    }
+
+    /// A row performing `effects` plus whatever the row variable `tail` stands for
+    pub fn open(effects: impl IntoIterator<Item = EffectType>, tail: impl Into<String>) -> Self {
+        Self {
+            tail: Some(tail.into()),
+            ..Self::closed(effects)
+        }
+    }
+
+    /// Whether the row has no known effects and no row variable
+    pub fn is_pure(&self) -> bool {
+        self.effects.is_empty() && self.tail.is_none()
+    }
+}
+
+impl fmt::Display for EffectRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut effects: Vec<_> = self.effects.iter().map(|e| e.to_string()).collect();
+        effects.sort();
+        let effects = effects.join(", ");
+        match &self.tail {
+            None if self.effects.len() == 1 => write!(f, "{}", effects),
+            None => write!(f, "{{{}}}", effects),
+            Some(tail) if self.effects.is_empty() => write!(f, "{}", tail),
+            Some(tail) => write!(f, "{{{} | {}}}", effects, tail),
+        }
+    }
+}
+
 /// Function types with explicit effects
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct FunctionType {
@@ -147,6 +218,8 @@ pub struct FunctionType {
     pub result: Box<TypedValue>,
     /// Whether this function is pure (no side effects)
     pub is_pure: bool,
+    /// Effects performed when the function is called
+    pub effects: EffectRow,
 }
 
 impl FunctionType {
@@ -156,13 +229,23 @@ impl FunctionType {
             params,
             result: Box::new(result),
             is_pure: true,
+            effects: EffectRow::pure(),
         }
     }
 
+    /// Set the effects performed when the function is called
+    pub fn with_effect_row(mut self, effects: EffectRow) -> Self {
+        // A row variable alone only forwards the effects of the function's arguments
+        self.is_pure = effects.effects.is_empty();
+        self.effects = effects;
+        self
+    }
+
     /// Create a TypedValue from this function type with the given effects
     pub fn with_effects(mut self, effects: HashSet<EffectType>) -> TypedValue {
         self.is_pure =
             effects.is_empty() || (effects.len() == 1 && effects.contains(&EffectType::Pure));
+        self.effects = EffectRow::closed(effects.iter().copied());
 
         TypedValue {
             inner: TypedValueInner::Function(self),
@@ -176,10 +259,17 @@ impl fmt::Display for FunctionType {
         let params_str = self
             .params
             .iter()
-            .map(|p| p.to_string())
+            .map(|p| match p.inner {
+                TypedValueInner::Function(_) => format!("({})", p),
+                _ => p.to_string(),
+            })
             .collect::<Vec<_>>()
             .join(" → ");
-        write!(f, "{} → {}", params_str, self.result)
+        if self.effects.is_pure() {
+            write!(f, "{} → {}", params_str, self.result)
+        } else {
+            write!(f, "{} -{}→ {}", params_str, self.effects, self.result)
+        }
     }
 }
 
@@ -231,6 +321,28 @@ impl fmt::Display for ListType {
     }
 }
 
+/// Channel type carrying values of a single element type
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelType {
+    /// Type of values sent and received on the channel
+    pub element_type: Box<TypedValue>,
+}
+
+impl ChannelType {
+    /// Create a new channel type with the given element type
+    pub fn new(element_type: TypedValue) -> Self {
+        Self {
+            element_type: Box::new(element_type),
+        }
+    }
+}
+
+impl fmt::Display for ChannelType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Chan<{}>", self.element_type)
+    }
+}
+
 /// Record types with named fields
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RecordType {
@@ -485,6 +597,8 @@ pub enum TypedValueInner {
     Uncertain(UncertainType),
     /// Temporal type variant
     Temporal(TemporalType),
+    /// Channel type variant
+    Channel(ChannelType),
     /// Type variable variant
     Variable(TypeVariable),
 }
@@ -508,6 +622,9 @@ impl TypedValue {
     }
 
     /// Create a function typed value with inferred effects
+    ///
+    /// A function that is not pure is assumed to use state; see
+    /// [`TypedValue::function_with_row`] for effects taken from the effect row.
     pub fn function(func: FunctionType) -> Self {
         let mut effects = HashSet::new();
 
@@ -530,6 +647,30 @@ impl TypedValue {
         }
     }
 
+    /// Create a function typed value whose effects are those of its effect row
+    ///
+    /// Unlike [`TypedValue::function`], an effectful row adds exactly its own effects,
+    /// and a row that only forwards a row variable adds none.
+    pub fn function_with_row(func: FunctionType) -> Self {
+        let mut effects = HashSet::new();
+
+        // Collect effects from parameters
+        for param in &func.params {
+            effects.extend(param.effects.iter().copied());
+        }
+
+        // Collect effects from result
+        effects.extend(func.result.effects.iter().copied());
+
+        // Collect effects performed when called
+        effects.extend(func.effects.effects.iter().copied());
+
+        Self {
+            inner: TypedValueInner::Function(func),
+            effects,
+        }
+    }
+
     /// Create a tuple typed value with combined effects
     pub fn tuple(tuple: TupleType) -> Self {
         let mut effects = HashSet::new();
@@ -617,6 +758,14 @@ impl TypedValue {
         }
     }
 
+    /// Create a channel typed value
+    pub fn channel(channel: ChannelType) -> Self {
+        Self {
+            inner: TypedValueInner::Channel(channel),
+            effects: HashSet::new(),
+        }
+    }
+
     /// Create a type variable typed value
     pub fn variable(var: TypeVariable) -> Self {
         Self {
@@ -637,6 +786,7 @@ impl TypedValue {
             TypedValueInner::Effect(_) => TypeKind::Effect,
             TypedValueInner::Uncertain(_) => TypeKind::Uncertain,
             TypedValueInner::Temporal(_) => TypeKind::Temporal,
+            TypedValueInner::Channel(_) => TypeKind::Channel,
             TypedValueInner::Variable(_) => TypeKind::TypeVariable,
         }
     }
@@ -676,18 +826,23 @@ impl fmt::Display for TypedValue {
             TypedValueInner::Effect(e) => e.to_string(),
             TypedValueInner::Uncertain(u) => u.to_string(),
             TypedValueInner::Temporal(t) => t.to_string(),
+            TypedValueInner::Channel(c) => c.to_string(),
             TypedValueInner::Variable(v) => v.to_string(),
         };
 
-        if !self.is_pure() {
-            let effects_str = self
-                .effects
-                .iter()
-                .filter(|e| **e != EffectType::Pure)
-                .map(|e| e.to_string())
-                .collect::<Vec<_>>()
-                .join(", ");
-            write!(f, "{} ~{{{}}}", type_str, effects_str)
+        // Effects a function performs when called are already shown on its arrow
+        let shown = match &self.inner {
+            TypedValueInner::Function(func) => Some(&func.effects.effects),
+            _ => None,
+        };
+        let effects: Vec<_> = self
+            .effects
+            .iter()
+            .filter(|e| **e != EffectType::Pure && !shown.is_some_and(|s| s.contains(e)))
+            .map(|e| e.to_string())
+            .collect();
+        if !effects.is_empty() {
+            write!(f, "{} ~{{{}}}", type_str, effects.join(", "))
         } else {
             write!(f, "{}", type_str)
         }
@@ -743,6 +898,31 @@ mod tests {
         assert_eq!(typed_func.to_string(), "String → Unit ~{IO}");
     }
 
+    #[test]
+    fn test_function_effect_defaults() {
+        let string = || TypedValue::primitive(PrimitiveType::string());
+        let row = EffectRow::open([EffectType::IO], "e");
+
+        // The plain constructor keeps treating an impure function as stateful
+        let typed_func = TypedValue::function(
+            FunctionType::new(vec![string()], string()).with_effect_row(row.clone()),
+        );
+        assert!(typed_func.effects.contains(&EffectType::State));
+        assert!(!typed_func.effects.contains(&EffectType::IO));
+
+        let typed_func = TypedValue::function_with_row(
+            FunctionType::new(vec![string()], string()).with_effect_row(row),
+        );
+        assert!(!typed_func.effects.contains(&EffectType::State));
+        assert!(typed_func.effects.contains(&EffectType::IO));
+
+        // Forwarding a row variable alone is not an effect of its own
+        let typed_func = TypedValue::function_with_row(
+            FunctionType::new(vec![string()], string()).with_effect_row(EffectRow::open([], "e")),
+        );
+        assert!(typed_func.is_pure());
+    }
+
     #[test]
     fn test_type_variables() {
         let type_var = TypeVariable::new("T").with_constraint(TypeConstraint::Numeric);
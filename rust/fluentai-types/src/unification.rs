@@ -2,11 +2,13 @@
 
 use crate::types::*;
 use rustc_hash::FxHashMap;
+use std::collections::HashSet;
 
-/// Substitution mapping type variables to types
+/// Substitution mapping type variables to types and row variables to effect rows
 #[derive(Debug, Clone)]
 pub struct Substitution {
     mapping: FxHashMap<String, TypedValue>,
+    rows: FxHashMap<String, EffectRow>,
 }
 
 impl Substitution {
@@ -14,6 +16,7 @@ impl Substitution {
     pub fn new() -> Self {
         Self {
             mapping: FxHashMap::default(),
+            rows: FxHashMap::default(),
         }
     }
 
@@ -22,8 +25,32 @@ impl Substitution {
         self.mapping.insert(var, ty);
     }
 
+    /// Add a mapping from row variable to effect row
+    pub fn insert_row(&mut self, var: String, row: EffectRow) {
+        self.rows.insert(var, row);
+    }
+
+    /// Apply substitution to an effect row
+    pub fn apply_row(&self, row: &EffectRow) -> EffectRow {
+        match row.tail.as_ref().and_then(|tail| self.rows.get(tail)) {
+            Some(rest) => {
+                let mut applied = self.apply_row(rest);
+                applied.effects.extend(row.effects.iter().copied());
+                applied
+            }
+            None => row.clone(),
+        }
+    }
+
     /// Apply substitution to a type
     pub fn apply_type(&self, ty: &TypedValue) -> TypedValue {
+        // Effects recorded on the type itself survive rebuilding its structure
+        let mut applied = self.apply_inner(ty);
+        applied.effects.extend(ty.effects.iter().copied());
+        applied
+    }
+
+    fn apply_inner(&self, ty: &TypedValue) -> TypedValue {
         match &ty.inner {
             TypedValueInner::Variable(var) => {
                 if let Some(substituted) = self.mapping.get(&var.name) {
@@ -36,11 +63,10 @@ impl Substitution {
             TypedValueInner::Function(func) => {
                 let params = func.params.iter().map(|p| self.apply_type(p)).collect();
                 let result = self.apply_type(&func.result);
-                TypedValue::function(FunctionType {
-                    params,
-                    result: Box::new(result),
-                    is_pure: func.is_pure,
-                })
+                TypedValue::function_with_row(
+                    FunctionType::new(params, result)
+                        .with_effect_row(self.apply_row(&func.effects)),
+                )
             }
             TypedValueInner::Tuple(tuple) => {
                 let elements = tuple.elements.iter().map(|e| self.apply_type(e)).collect();
@@ -52,6 +78,10 @@ impl Substitution {
                     element_type: Box::new(element_type),
                 })
             }
+            TypedValueInner::Channel(channel) => {
+                let element_type = self.apply_type(&channel.element_type);
+                TypedValue::channel(ChannelType::new(element_type))
+            }
             TypedValueInner::Record(record) => {
                 let fields = record
                     .fields
@@ -106,6 +136,9 @@ impl Substitution {
         for value in self.mapping.values_mut() {
             *value = other.apply_type(value);
         }
+        for row in self.rows.values_mut() {
+            *row = other.apply_row(row);
+        }
 
         // Add mappings from other that aren't in self
         for (var, ty) in &other.mapping {
@@ -113,11 +146,16 @@ impl Substitution {
                 self.mapping.insert(var.clone(), ty.clone());
             }
         }
+        for (var, row) in &other.rows {
+            if !self.rows.contains_key(var) {
+                self.rows.insert(var.clone(), row.clone());
+            }
+        }
     }
 
     /// Check if a variable occurs in the substitution
     pub fn contains_var(&self, var: &str) -> bool {
-        self.mapping.contains_key(var)
+        self.mapping.contains_key(var) || self.rows.contains_key(var)
     }
 }
 
@@ -163,12 +201,18 @@ pub enum UnificationError {
     /// Variant types don't match
     #[error("Variant mismatch")]
     VariantMismatch,
+
+    /// Function effect rows don't match
+    #[error("Cannot unify effects {0} with {1}")]
+    EffectMismatch(String, String),
 }
 
 /// Type unifier
 pub struct Unifier {
     /// Current substitution
     subst: Substitution,
+    /// Counter for row variables introduced while unifying two open rows
+    row_counter: usize,
 }
 
 impl Unifier {
@@ -176,6 +220,7 @@ impl Unifier {
     pub fn new() -> Self {
         Self {
             subst: Substitution::new(),
+            row_counter: 0,
         }
     }
 
@@ -202,6 +247,13 @@ impl Unifier {
                 }
             }
 
+            // A variable trivially unifies with itself
+            (TypedValueInner::Variable(v1), TypedValueInner::Variable(v2))
+                if v1.name == v2.name =>
+            {
+                Ok(Substitution::new())
+            }
+
             // Variable unifies with anything (with occurs check)
             (TypedValueInner::Variable(v), _) => {
                 if self.occurs_check(&v.name, &t2) {
@@ -251,6 +303,10 @@ impl Unifier {
                 let result_subst2 = self.unify(&f1.result, &f2.result)?;
                 result_subst.compose(&result_subst2);
 
+                // Unify effect rows
+                let row_subst = self.unify_rows(&f1.effects, &f2.effects)?;
+                result_subst.compose(&row_subst);
+
                 Ok(result_subst)
             }
 
@@ -278,6 +334,11 @@ impl Unifier {
                 self.unify(&l1.element_type, &l2.element_type)
             }
 
+            // Channel types
+            (TypedValueInner::Channel(c1), TypedValueInner::Channel(c2)) => {
+                self.unify(&c1.element_type, &c2.element_type)
+            }
+
             // Record types
             (TypedValueInner::Record(r1), TypedValueInner::Record(r2)) => {
                 // Check that all fields match
@@ -366,6 +427,51 @@ impl Unifier {
         }
     }
 
+    /// Unify two effect rows, binding row variables to the effects the other row adds
+    pub fn unify_rows(
+        &mut self,
+        r1: &EffectRow,
+        r2: &EffectRow,
+    ) -> Result<Substitution, UnificationError> {
+        let r1 = self.subst.apply_row(r1);
+        let r2 = self.subst.apply_row(r2);
+        let only1: HashSet<_> = r1.effects.difference(&r2.effects).copied().collect();
+        let only2: HashSet<_> = r2.effects.difference(&r1.effects).copied().collect();
+        let mismatch = || UnificationError::EffectMismatch(r1.to_string(), r2.to_string());
+
+        let mut subst = Substitution::new();
+        match (&r1.tail, &r2.tail) {
+            (None, None) if only1.is_empty() && only2.is_empty() => {}
+            (Some(v1), None) if only1.is_empty() => {
+                subst.insert_row(v1.clone(), EffectRow::closed(only2));
+            }
+            (None, Some(v2)) if only2.is_empty() => {
+                subst.insert_row(v2.clone(), EffectRow::closed(only1));
+            }
+            (Some(v1), Some(v2)) if v1 == v2 => {
+                if !only1.is_empty() || !only2.is_empty() {
+                    return Err(mismatch());
+                }
+            }
+            (Some(v1), Some(v2)) if only1.is_empty() => {
+                subst.insert_row(v1.clone(), EffectRow::open(only2, v2.clone()));
+            }
+            (Some(v1), Some(v2)) if only2.is_empty() => {
+                subst.insert_row(v2.clone(), EffectRow::open(only1, v1.clone()));
+            }
+            (Some(v1), Some(v2)) => {
+                // Each row gains the other's effects and both share what is left
+                let rest = format!("ρ{}", self.row_counter);
+                self.row_counter += 1;
+                subst.insert_row(v1.clone(), EffectRow::open(only2, rest.clone()));
+                subst.insert_row(v2.clone(), EffectRow::open(only1, rest));
+            }
+            _ => return Err(mismatch()),
+        }
+        self.subst.compose(&subst);
+        Ok(subst)
+    }
+
     /// Occurs check - ensure variable doesn't occur in type
     fn occurs_check(&self, var: &str, ty: &TypedValue) -> bool {
         match &ty.inner {
@@ -376,6 +482,7 @@ impl Unifier {
             }
             TypedValueInner::Tuple(t) => t.elements.iter().any(|e| self.occurs_check(var, e)),
             TypedValueInner::List(l) => self.occurs_check(var, &l.element_type),
+            TypedValueInner::Channel(c) => self.occurs_check(var, &c.element_type),
             TypedValueInner::Record(r) => r.fields.values().any(|f| self.occurs_check(var, f)),
             TypedValueInner::Variant(v) => v
                 .variants
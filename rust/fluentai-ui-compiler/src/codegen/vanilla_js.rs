@@ -83,7 +83,14 @@ impl<'a> VanillaJsCompiler<'a> {
             Literal::Boolean(b) => b.to_string(),
             Literal::Integer(i) => i.to_string(),
             Literal::Float(f) => f.to_string(),
+            Literal::BigInt(n) => format!("{}n", n),
+            // JavaScript has no exact decimal type
+            Literal::Decimal(d) => d.to_string(),
             Literal::String(s) => format!("\"{}\"", s.replace("\"", "\\\"")),
+            Literal::Bytes(b) => format!(
+                "new Uint8Array([{}])",
+                b.iter().map(|byte| byte.to_string()).collect::<Vec<_>>().join(", ")
+            ),
             Literal::Symbol(s) => format!("Symbol.for(\"{}\")", s),
         })
     }
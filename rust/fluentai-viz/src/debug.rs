@@ -122,7 +122,10 @@ pub fn serialize_value(value: &Value) -> String {
         Value::Boolean(b) => b.to_string(),
         Value::Integer(i) => i.to_string(),
         Value::Float(f) => f.to_string(),
+        Value::BigInt(n) => n.to_string(),
+        Value::Decimal(d) => d.to_string(),
         Value::String(s) => format!("\"{}\"", s),
+        Value::Bytes(b) => fluentai_core::value::format_bytes(b),
         Value::List(items) => {
             let items_str: Vec<String> = items
                 .iter()
@@ -132,6 +135,10 @@ pub fn serialize_value(value: &Value) -> String {
             let suffix = if items.len() > 5 { ", ..." } else { "" };
             format!("[{}{}]", items_str.join(", "), suffix)
         }
+        Value::Tuple(items) => {
+            let items_str: Vec<String> = items.iter().map(serialize_value).collect();
+            format!("({})", items_str.join(", "))
+        }
         Value::Function { chunk_id, .. } => {
             format!("<function:chunk_{}>", chunk_id)
         }
@@ -139,6 +146,15 @@ pub fn serialize_value(value: &Value) -> String {
             let values_str: Vec<String> = values.iter().map(serialize_value).collect();
             format!("{}({})", tag, values_str.join(", "))
         }
+        Value::Struct { name, fields } => {
+            let fields_str: Vec<String> = fields
+                .iter()
+                .take(5)
+                .map(|(k, v)| format!("{}: {}", k, serialize_value(v)))
+                .collect();
+            let suffix = if fields.len() > 5 { ", ..." } else { "" };
+            format!("{} {{ {}{} }}", name, fields_str.join(", "), suffix)
+        }
         Value::Cell(_) => "<cell>".to_string(),
         Value::Module { name, .. } => format!("<module:{}>", name),
         Value::Map(map) => {
@@ -189,14 +189,18 @@ impl ASTLayouter {
             } => {
                 vec![*condition, *then_branch, *else_branch]
             }
+            Node::While { condition, body } => vec![*condition, *body],
+            Node::For { iterable, body, .. } => vec![*iterable, *body],
+            Node::Range { start, end, .. } => vec![*start, *end],
+            Node::Break { value } => value.iter().copied().collect(),
             Node::Application { function, args } => {
                 let mut children = vec![*function];
                 children.extend(args);
                 children
             }
-            Node::List(items) => items.clone(),
+            Node::List(items) | Node::Tuple(items) => items.clone(),
             Node::Effect { args, .. } => args.clone(),
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 let mut children = vec![*expr];
                 children.extend(branches.iter().map(|(_, body)| *body));
                 children
@@ -205,6 +209,7 @@ impl ASTLayouter {
             Node::Async { body } => vec![*body],
             Node::Await { expr } => vec![*expr],
             Node::Spawn { expr } => vec![*expr],
+            Node::Parallel { branches } => branches.clone(),
             Node::Send { channel, value } => vec![*channel, *value],
             Node::Receive { channel } => vec![*channel],
             Node::TrySend { channel, value } => vec![*channel, *value],
@@ -221,6 +226,15 @@ impl ASTLayouter {
                 children.extend(invariants);
                 children
             }
+            Node::StructConstruct { fields, .. } => fields.iter().map(|(_, v)| *v).collect(),
+            Node::FieldAccess { object, .. } => vec![*object],
+            Node::StructUpdate { base, fields, .. } => {
+                let mut children = vec![*base];
+                children.extend(fields.iter().map(|(_, v)| *v));
+                children
+            }
+            Node::TraitImpl { methods, .. } => methods.iter().map(|(_, m)| *m).collect(),
+            Node::MethodCall { args, .. } => args.clone(),
             _ => vec![],
         }
     }
@@ -234,6 +248,13 @@ impl ASTLayouter {
             Node::Let { .. } => "let".to_string(),
             Node::Letrec { .. } => "letrec".to_string(),
             Node::If { .. } => "if".to_string(),
+            Node::While { .. } => "while".to_string(),
+            Node::For { .. } => "for".to_string(),
+            Node::Range { inclusive, .. } => {
+                if *inclusive { "..=" } else { ".." }.to_string()
+            }
+            Node::Break { .. } => "break".to_string(),
+            Node::Continue => "continue".to_string(),
             Node::Application { .. } => "apply".to_string(),
             Node::Effect {
                 effect_type,
@@ -243,6 +264,7 @@ impl ASTLayouter {
                 format!("{}:{}", effect_type, operation)
             }
             Node::List(_) => "list".to_string(),
+            Node::Tuple(_) => "tuple".to_string(),
             Node::Match { .. } => "match".to_string(),
             Node::Module { name, .. } => format!("module {}", name),
             Node::Import { module_path, .. } => format!("import {}", module_path),
@@ -256,6 +278,7 @@ impl ASTLayouter {
             Node::Async { .. } => "async".to_string(),
             Node::Await { .. } => "await".to_string(),
             Node::Spawn { .. } => "spawn".to_string(),
+            Node::Parallel { .. } => "parallel".to_string(),
             Node::Channel { .. } => "channel".to_string(),
             Node::Send { .. } => "send!".to_string(),
             Node::Receive { .. } => "recv!".to_string(),
@@ -280,6 +303,18 @@ impl ASTLayouter {
             Node::PromiseRace { .. } => "promise-race".to_string(),
             Node::Timeout { .. } => "with-timeout".to_string(),
             Node::Assignment { .. } => "=".to_string(),
+            Node::Struct { name, .. } => format!("struct {}", name),
+            Node::StructConstruct { name, .. } => format!("{} {{}}", name),
+            Node::FieldAccess { field, .. } => format!(".{}", field),
+            Node::StructUpdate { .. } => "struct-update".to_string(),
+            Node::Enum { name, .. } => format!("enum {}", name),
+            Node::Trait { name, .. } => format!("trait {}", name),
+            Node::TraitImpl { type_name, trait_name, .. } => format!("{} as {}", type_name, trait_name),
+            Node::MethodCall { method, .. } => format!(".{}", method),
+            Node::EffectDef { name, .. } => format!("effect {}", name),
+            Node::Extern { name, .. } => format!("extern {}", name),
+            Node::MacroDef { name, .. } => format!("macro {}", name),
+            Node::Error { .. } => "error".to_string(),
         }
     }
 
@@ -292,9 +327,15 @@ impl ASTLayouter {
             Node::Let { .. } => "let",
             Node::Letrec { .. } => "letrec",
             Node::If { .. } => "if",
+            Node::While { .. } => "while",
+            Node::For { .. } => "for",
+            Node::Range { .. } => "range",
+            Node::Break { .. } => "break",
+            Node::Continue => "continue",
             Node::Application { .. } => "application",
             Node::Effect { .. } => "effect",
             Node::List(_) => "list",
+            Node::Tuple(_) => "tuple",
             Node::Match { .. } => "match",
             Node::Module { .. } => "module",
             Node::Import { .. } => "import",
@@ -303,6 +344,7 @@ impl ASTLayouter {
             Node::Async { .. } => "async",
             Node::Await { .. } => "await",
             Node::Spawn { .. } => "spawn",
+            Node::Parallel { .. } => "parallel",
             Node::Channel { .. } => "channel",
             Node::Send { .. } => "send",
             Node::Receive { .. } => "receive",
@@ -324,6 +366,18 @@ impl ASTLayouter {
             Node::PromiseRace { .. } => "promise-race",
             Node::Timeout { .. } => "timeout",
             Node::Assignment { .. } => "assignment",
+            Node::Struct { .. } => "struct",
+            Node::StructConstruct { .. } => "struct-construct",
+            Node::FieldAccess { .. } => "field-access",
+            Node::StructUpdate { .. } => "struct-update",
+            Node::Enum { .. } => "enum",
+            Node::Trait { .. } => "trait",
+            Node::TraitImpl { .. } => "trait-impl",
+            Node::MethodCall { .. } => "method-call",
+            Node::EffectDef { .. } => "effect-def",
+            Node::Extern { .. } => "extern",
+            Node::MacroDef { .. } => "macro-def",
+            Node::Error { .. } => "error",
         }
         .to_string()
     }
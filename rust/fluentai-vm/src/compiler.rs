@@ -1,6 +1,6 @@
 //! Compiler from AST to bytecode
 
-use fluentai_bytecode::{Bytecode, BytecodeChunk, Instruction, Opcode};
+use fluentai_bytecode::{Bytecode, BytecodeChunk, ExternDecl, Instruction, Opcode};
 use crate::compiler_builtins::BuiltinResult;
 use crate::free_var_analysis::FreeVarAnalyzer;
 use fluentai_bytecode::source_map::{SourceLocation, SourceMap, ModuleSourceMap};
@@ -15,6 +15,22 @@ use std::collections::{HashMap, HashSet};
 const MAKECLOSURE_CHUNK_ID_SHIFT: u32 = 16;
 const MAKECLOSURE_CAPTURE_COUNT_MASK: u32 = 0xFFFF;
 
+/// Bit masks for MakeStruct instruction packing
+const MAKESTRUCT_LAYOUT_SHIFT: u32 = 16;
+const MAKESTRUCT_FIELD_COUNT_MASK: u32 = 0xFFFF;
+
+/// Bit masks for DefineMethod / CallMethod instruction packing
+const METHOD_NAME_SHIFT: u32 = 16;
+const METHOD_LOW_MASK: u32 = 0xFFFF;
+
+/// Pack two operands into one instruction argument, or `None` if either does not fit
+fn pack_arg(high: u32, shift: u32, low: usize, low_mask: u32) -> Option<u32> {
+    if high > u32::MAX >> shift || low > low_mask as usize {
+        return None;
+    }
+    Some((high << shift) | low as u32)
+}
+
 /// Compiler options
 #[derive(Debug, Clone)]
 pub struct CompilerOptions {
@@ -41,6 +57,7 @@ pub struct Compiler {
     stack_depth: usize,                    // Track current stack depth
     scope_bases: Vec<usize>,               // Base stack position for each scope
     cell_vars: Vec<HashSet<String>>,       // Variables that are cells (for letrec)
+    captured_cells: HashSet<String>,       // Captured variables whose defining binding is a cell
     options: CompilerOptions,
     // Tail call optimization tracking
     in_tail_position: bool, // Whether we're compiling in tail position
@@ -48,6 +65,156 @@ pub struct Compiler {
     // Source mapping
     current_node_id: Option<NodeId>, // Current AST node being compiled
     source_filename: Option<String>, // Optional source filename
+    node_locations: HashMap<NodeId, SourceLocation>, // Source spans of the nodes being compiled
+    // Struct definitions: name -> field names in declaration order
+    struct_layouts: HashMap<String, Vec<String>>,
+    // Actor state parameter of the handler being compiled (field writes to it call Become)
+    actor_state_var: Option<String>,
+    pending_actor_state_var: Option<String>, // Set by compile_actor for the next lambda
+    // Enum definitions: name -> variant tags (impls on an enum apply to each tag)
+    enum_variants: HashMap<String, Vec<String>>,
+    // Enclosing loops, innermost last (targets for break and continue)
+    loops: Vec<LoopContext>,
+    // Error and effect handlers open in the function being compiled, innermost last
+    open_handlers: Vec<OpenHandler>,
+}
+
+/// A handler a break or continue must close before leaving its block
+#[derive(Debug, Clone, Copy)]
+enum OpenHandler {
+    /// A try block; `installed` is false inside its catch branches, where the
+    /// error handler has already been popped but the finally block is pending
+    Try {
+        installed: bool,
+        finally: Option<NodeId>,
+    },
+    /// The body of a `handle` expression
+    Effect,
+}
+
+/// Jump targets of a loop being compiled
+struct LoopContext {
+    /// Chunk the loop is compiled into; break and continue cannot cross functions
+    chunk: usize,
+    /// Stack depth a break unwinds to before leaving its value
+    break_depth: usize,
+    /// Stack depth a continue unwinds to
+    continue_depth: usize,
+    /// Number of open handlers outside the loop
+    handler_depth: usize,
+    /// Jumps to patch to the instruction after the loop
+    break_jumps: Vec<usize>,
+    /// Jumps to patch to the start of the next iteration
+    continue_jumps: Vec<usize>,
+}
+
+/// One step from a matched value to a part of it
+#[derive(Debug, Clone, Copy)]
+enum PatternStep {
+    /// Head of a non-empty list
+    Head,
+    /// Tail of a non-empty list
+    Tail,
+    /// Field N of a tagged value
+    Field(usize),
+    /// Element N of a tuple
+    Element(usize),
+    /// Result of applying a view function
+    View(NodeId),
+}
+
+impl PatternStep {
+    /// Steps to the parts of a constructor pattern; `Cons` takes lists apart
+    fn for_constructor(name: &str, arity: usize) -> Vec<PatternStep> {
+        if is_cons_pattern(name, arity) {
+            vec![PatternStep::Head, PatternStep::Tail]
+        } else {
+            (0..arity).map(PatternStep::Field).collect()
+        }
+    }
+}
+
+fn is_cons_pattern(name: &str, arity: usize) -> bool {
+    (name == "cons" || name == "Cons") && arity == 2
+}
+
+fn is_nil_pattern(name: &str, arity: usize) -> bool {
+    (name == "nil" || name == "Nil") && arity == 0
+}
+
+/// Whether a pattern matches every value, so no test has to be compiled for it
+fn is_irrefutable(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::Wildcard | Pattern::Variable(_) => true,
+        Pattern::As { pattern, .. } | Pattern::View { pattern, .. } => is_irrefutable(pattern),
+        Pattern::Or(patterns) => patterns.iter().any(is_irrefutable),
+        Pattern::Tuple(patterns) => patterns.iter().all(is_irrefutable),
+        Pattern::Literal(_)
+        | Pattern::Constructor { .. }
+        | Pattern::Range(_)
+        | Pattern::Guard { .. } => false,
+    }
+}
+
+/// Guard conditions and view functions inside a pattern
+fn pattern_expressions(pattern: &Pattern) -> Vec<NodeId> {
+    match pattern {
+        Pattern::Guard { pattern, condition } => {
+            let mut nodes = pattern_expressions(pattern);
+            nodes.push(*condition);
+            nodes
+        }
+        Pattern::View { function, pattern } => {
+            let mut nodes = vec![*function];
+            nodes.extend(pattern_expressions(pattern));
+            nodes
+        }
+        Pattern::As { pattern, .. } => pattern_expressions(pattern),
+        Pattern::Constructor { patterns, .. } | Pattern::Tuple(patterns) | Pattern::Or(patterns) => {
+            patterns.iter().flat_map(pattern_expressions).collect()
+        }
+        Pattern::Wildcard | Pattern::Variable(_) | Pattern::Literal(_) | Pattern::Range(_) => {
+            Vec::new()
+        }
+    }
+}
+
+/// Steps from the value matched by `pattern` to the part bound to `name`
+fn binding_path(pattern: &Pattern, name: &str) -> Result<Option<Vec<PatternStep>>> {
+    match pattern {
+        Pattern::Variable(bound) => Ok((bound == name).then(Vec::new)),
+        Pattern::As { binding, .. } if binding == name => Ok(Some(Vec::new())),
+        Pattern::As { pattern, .. } | Pattern::Guard { pattern, .. } => {
+            binding_path(pattern, name)
+        }
+        Pattern::View { function, pattern } => Ok(binding_path(pattern, name)?.map(|mut path| {
+            path.insert(0, PatternStep::View(*function));
+            path
+        })),
+        Pattern::Constructor { name: ctor, patterns } => {
+            let steps = PatternStep::for_constructor(ctor, patterns.len());
+            for (part, step) in patterns.iter().zip(steps) {
+                if let Some(mut path) = binding_path(part, name)? {
+                    path.insert(0, step);
+                    return Ok(Some(path));
+                }
+            }
+            Ok(None)
+        }
+        Pattern::Tuple(patterns) => {
+            for (i, part) in patterns.iter().enumerate() {
+                if let Some(mut path) = binding_path(part, name)? {
+                    path.insert(0, PatternStep::Element(i));
+                    return Ok(Some(path));
+                }
+            }
+            Ok(None)
+        }
+        Pattern::Or(_) if pattern.bound_variables().iter().any(|bound| bound == name) => Err(
+            anyhow!("Or-patterns binding '{}' cannot be nested in another or-pattern", name),
+        ),
+        Pattern::Or(_) | Pattern::Wildcard | Pattern::Literal(_) | Pattern::Range(_) => Ok(None),
+    }
 }
 
 /// Helper struct to hold error handler information during try/catch/finally compilation
@@ -77,11 +244,19 @@ impl Compiler {
             stack_depth: 0,
             scope_bases: vec![0],
             cell_vars: vec![HashSet::new()],
+            captured_cells: HashSet::new(),
             options,
             in_tail_position: false,
             current_function: None,
             current_node_id: None,
             source_filename: None,
+            node_locations: HashMap::new(),
+            struct_layouts: HashMap::new(),
+            actor_state_var: None,
+            pending_actor_state_var: None,
+            enum_variants: HashMap::new(),
+            loops: Vec::new(),
+            open_handlers: Vec::new(),
         }
     }
 
@@ -108,6 +283,35 @@ impl Compiler {
             .root_id
             .ok_or_else(|| anyhow!("AST graph has no root node"))?;
         
+        if self.options.debug_info {
+            self.collect_node_locations(&optimized_graph, graph);
+        }
+        
+        // Register struct layouts up front so constructions compiled before
+        // the definition (e.g. inside earlier functions) use declaration order
+        // Enum variants are registered the same way
+        for node in optimized_graph.nodes.values() {
+            match node {
+                Node::Struct { name, fields, .. } => {
+                    self.struct_layouts.insert(
+                        name.clone(),
+                        fields.iter().map(|f| f.name.clone()).collect(),
+                    );
+                }
+                Node::Enum { name, variants, .. } => {
+                    self.enum_variants.insert(
+                        name.clone(),
+                        variants.iter().map(|v| v.name.clone()).collect(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        
+        // Externs come from the source graph so that a declaration the
+        // optimizer dropped as unused is still checked at load time
+        self.collect_externs(graph)?;
+        
         // Verify initial state
         self.verify_stack_invariants();
         
@@ -122,6 +326,30 @@ impl Compiler {
         Ok(self.bytecode)
     }
 
+    /// Record every `extern` declaration for the loader to bind
+    fn collect_externs(&mut self, graph: &ASTGraph) -> Result<()> {
+        for node in graph.nodes.values() {
+            if let Node::Extern { name, params, .. } = node {
+                match self.bytecode.externs.iter().find(|decl| decl.name == *name) {
+                    Some(decl) if decl.arity != params.len() => {
+                        return Err(anyhow!(
+                            "Conflicting declarations of extern function '{}'",
+                            name
+                        ));
+                    }
+                    Some(_) => {}
+                    None => self.bytecode.externs.push(ExternDecl {
+                        name: name.clone(),
+                        arity: params.len(),
+                    }),
+                }
+            }
+        }
+        // Node order is not stable, so keep the declarations in a predictable order
+        self.bytecode.externs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(())
+    }
+
     /// Create a new chunk and return its ID
     fn create_chunk(&mut self) -> Result<usize> {
         let chunk = BytecodeChunk::new(None);
@@ -244,9 +472,38 @@ impl Compiler {
             } => {
                 self.compile_if(graph, *condition, *then_branch, *else_branch)?;
             }
+            Node::While { condition, body } => {
+                self.compile_while(graph, *condition, *body)?;
+            }
+            Node::For {
+                pattern,
+                iterable,
+                body,
+            } => {
+                self.compile_for(graph, pattern, *iterable, *body)?;
+            }
+            Node::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                self.compile_range(graph, *start, *end, *inclusive)?;
+            }
+            Node::Break { value } => {
+                self.compile_break(graph, *value)?;
+            }
+            Node::Continue => {
+                self.compile_continue(graph)?;
+            }
             Node::List(items) => {
                 self.compile_list(graph, items)?;
             }
+            Node::Tuple(items) => {
+                for &item in items {
+                    self.compile_node(graph, item)?;
+                }
+                self.emit(Instruction::with_arg(Opcode::MakeTuple, items.len() as u32));
+            }
             Node::Effect {
                 effect_type,
                 operation,
@@ -263,6 +520,9 @@ impl Compiler {
             Node::Spawn { expr } => {
                 self.compile_spawn(graph, *expr)?;
             }
+            Node::Parallel { branches } => {
+                self.compile_parallel(graph, branches)?;
+            }
             Node::Channel { capacity } => {
                 // If capacity is provided, compile it and push onto stack
                 if let Some(cap_expr) = capacity {
@@ -321,8 +581,8 @@ impl Compiler {
             Node::Timeout { duration, promise, default } => {
                 self.compile_timeout(graph, *duration, *promise, default.as_ref().copied())?;
             }
-            Node::Match { expr, branches } => {
-                self.compile_match(graph, *expr, branches)?;
+            Node::Match { expr, branches, must_match } => {
+                self.compile_match(graph, *expr, branches, *must_match)?;
             }
             Node::Module {
                 name,
@@ -347,7 +607,13 @@ impl Compiler {
                 module_name,
                 variable_name,
             } => {
-                self.compile_qualified_variable(module_name, variable_name)?;
+                if self.is_local_variable(module_name) {
+                    // `record.field` where `record` is a local binding
+                    self.compile_variable(module_name)?;
+                    self.compile_get_field(variable_name);
+                } else {
+                    self.compile_qualified_variable(module_name, variable_name)?;
+                }
             }
             Node::Contract { .. } => {
                 // Contracts are metadata and don't generate bytecode directly
@@ -385,6 +651,40 @@ impl Compiler {
             Node::Assignment { target, value } => {
                 self.compile_assignment(graph, *target, *value)?;
             }
+            Node::Struct { .. } => {
+                // Layouts are registered before compilation starts;
+                // the definition itself has no runtime representation
+                self.emit(Instruction::new(Opcode::PushNil));
+            }
+            Node::StructConstruct { name, fields } => {
+                self.compile_struct_construct(graph, name, fields)?;
+            }
+            Node::FieldAccess { object, field } => {
+                self.compile_node(graph, *object)?;
+                self.compile_get_field(field);
+            }
+            Node::StructUpdate { name, base, fields } => {
+                self.compile_struct_update(graph, name, *base, fields)?;
+            }
+            Node::Enum { .. } | Node::Trait { .. } | Node::EffectDef { .. } | Node::Extern { .. } => {
+                // Variants, method names and externs are registered before compilation starts
+                self.emit(Instruction::new(Opcode::PushNil));
+            }
+            Node::TraitImpl { type_name, methods, .. } => {
+                self.compile_trait_impl(graph, type_name, methods)?;
+            }
+            Node::MethodCall { method, args } => {
+                self.compile_method_call(graph, method, args)?;
+            }
+            Node::MacroDef { name, .. } => {
+                return Err(anyhow!(
+                    "Macro '{}' must be expanded before compilation",
+                    name
+                ));
+            }
+            Node::Error { message } => {
+                return Err(anyhow!("Cannot compile source with syntax errors: {}", message));
+            }
         }
 
         // Restore previous node
@@ -418,10 +718,22 @@ impl Compiler {
                 let idx = self.add_constant(Value::Float(*f));
                 self.emit(Instruction::with_arg(Opcode::Push, idx));
             }
+            Literal::BigInt(n) => {
+                let idx = self.add_constant(Value::BigInt(n.clone()));
+                self.emit(Instruction::with_arg(Opcode::Push, idx));
+            }
+            Literal::Decimal(d) => {
+                let idx = self.add_constant(Value::Decimal(*d));
+                self.emit(Instruction::with_arg(Opcode::Push, idx));
+            }
             Literal::String(s) => {
                 let idx = self.add_constant(Value::String(s.clone()));
                 self.emit(Instruction::with_arg(Opcode::Push, idx));
             }
+            Literal::Bytes(b) => {
+                let idx = self.add_constant(Value::Bytes(b.clone()));
+                self.emit(Instruction::with_arg(Opcode::Push, idx));
+            }
             Literal::Symbol(s) => {
                 let idx = self.add_constant(Value::Symbol(s.clone()));
                 self.emit(Instruction::with_arg(Opcode::Push, idx));
@@ -491,18 +803,8 @@ impl Compiler {
                     capture_idx as u32,
                 ));
 
-                // Check if this captured variable is a cell
-                // This is tricky - we need to know if the captured variable was a cell
-                // For now, we'll check if the name exists in any parent scope's cell_vars
-                let mut is_cell = false;
-                for parent_cells in &self.cell_vars {
-                    if parent_cells.contains(name) {
-                        is_cell = true;
-                        break;
-                    }
-                }
-
-                if is_cell {
+                // Captured cells are dereferenced on use
+                if self.captured_cells.contains(name) {
                     self.emit(Instruction::new(Opcode::CellGet));
                 }
 
@@ -521,6 +823,20 @@ impl Compiler {
         graph: &ASTGraph,
         func: NodeId,
         args: &[NodeId],
+    ) -> Result<()> {
+        // Only the call itself can be in tail position, never its operands
+        let saved_tail = std::mem::replace(&mut self.in_tail_position, false);
+        let result = self.compile_call(graph, func, args, saved_tail);
+        self.in_tail_position = saved_tail;
+        result
+    }
+
+    fn compile_call(
+        &mut self,
+        graph: &ASTGraph,
+        func: NodeId,
+        args: &[NodeId],
+        in_tail_position: bool,
     ) -> Result<()> {
         // Check if it's a built-in function
         if let Some(node) = graph.nodes.get(&func) {
@@ -637,7 +953,7 @@ impl Compiler {
         }
 
         // Check if this is a tail call
-        let is_tail_call = self.in_tail_position
+        let is_tail_call = in_tail_position
             && self.current_function.is_some()
             && if let Some(Node::Variable { name }) = graph.nodes.get(&func) {
                 self.current_function.as_ref() == Some(name)
@@ -664,13 +980,19 @@ impl Compiler {
         // Find free variables - variables used in body but not defined as parameters
         let free_vars = self.find_free_variables(graph, body, params)?;
 
-        // Emit code to push captured values onto stack
+        // Emit code to push captured values onto stack, noting which are cells
+        // in the scope that defines them
+        let mut captured_cells = HashSet::new();
         for var in &free_vars {
             self.compile_captured_variable(var)?;
+            if self.is_cell_binding(var)? {
+                captured_cells.insert(var.clone());
+            }
         }
 
         // Create a new chunk for the lambda
-        let lambda_chunk = BytecodeChunk::new(Some("lambda".to_string()));
+        let mut lambda_chunk = BytecodeChunk::new(Some("lambda".to_string()));
+        lambda_chunk.arity = params.len();
         let chunk_id = self.bytecode.add_chunk(lambda_chunk);
 
         // Save current context
@@ -680,8 +1002,14 @@ impl Compiler {
         let saved_stack_depth = self.stack_depth;
         let saved_scope_bases = self.scope_bases.clone();
         let saved_cell_vars = self.cell_vars.clone();
+        let saved_captured_cells = std::mem::replace(&mut self.captured_cells, captured_cells);
+        let saved_open_handlers = std::mem::take(&mut self.open_handlers);
         let saved_function = self.current_function.clone();
         let _saved_tail = self.in_tail_position;
+        let saved_actor_state_var = std::mem::replace(
+            &mut self.actor_state_var,
+            self.pending_actor_state_var.take(),
+        );
 
         // Switch to lambda chunk
         self.current_chunk = chunk_id;
@@ -708,17 +1036,8 @@ impl Compiler {
         }
 
         // Add captured variables to captured map
-        // Also track which captured variables are cells
         for (i, var) in free_vars.iter().enumerate() {
             self.captured[0].insert(var.clone(), i);
-
-            // Check if this variable is a cell in any parent scope
-            for parent_cells in &saved_cell_vars {
-                if parent_cells.contains(var) {
-                    self.cell_vars[0].insert(var.clone());
-                    break;
-                }
-            }
         }
 
         // Compile body in tail position
@@ -753,8 +1072,11 @@ impl Compiler {
             "Stack depth not restored after lambda compilation"
         );
         self.cell_vars = saved_cell_vars;
+        self.captured_cells = saved_captured_cells;
+        self.open_handlers = saved_open_handlers;
         self.current_function = saved_function;
         self.in_tail_position = _saved_tail;
+        self.actor_state_var = saved_actor_state_var;
 
         // Push function value with captures
         if free_vars.is_empty() {
@@ -890,56 +1212,131 @@ impl Compiler {
     }
     
     fn compile_assignment(&mut self, graph: &ASTGraph, target: NodeId, value: NodeId) -> Result<()> {
-        // For now, we only support simple variable assignments
-        // TODO: In the future, support field access (e.g., self.count)
-        
-        
         let target_node = graph
             .get_node(target)
             .ok_or_else(|| anyhow!("Invalid target node in assignment: {:?}", target))?;
         
         match target_node {
-            Node::Variable { name } => {
+            Node::Variable { .. } | Node::QualifiedVariable { .. } | Node::FieldAccess { .. } => {
+                let saved_tail = self.in_tail_position;
+                self.in_tail_position = false;
+                
                 // Compile the value expression
                 self.compile_node(graph, value)?;
                 
                 // Duplicate the value on the stack so we can return it
                 self.emit(Instruction::new(Opcode::Dup));
-                // Note: emit() already adjusts stack_depth for Dup
                 
-                // Look up in locals
-                let mut found_local = false;
-                for (scope_idx, scope) in self.locals.iter().enumerate().rev() {
-                    if let Some(&rel_pos) = scope.get(name) {
-                        // The position stored is relative to the scope base
-                        let abs_pos = self.get_scope_base(scope_idx)? + rel_pos;
-                        
-                        // Store in local variable (consumes one copy)
-                        self.emit(Instruction::with_arg(Opcode::Store, abs_pos as u32));
-                        // Stack depth is now managed by emit()
-                        found_local = true;
-                        break;
+                // Write one copy into the target (consumes it)
+                self.compile_store_place(graph, target)?;
+                
+                // Field writes to an actor's state also update the actor
+                if let Some(root) = self.assignment_root(graph, target) {
+                    let is_actor_state = !matches!(target_node, Node::Variable { .. })
+                        && self.actor_state_var.as_deref() == Some(root.as_str());
+                    if is_actor_state {
+                        self.compile_variable(&root)?;
+                        self.emit(Instruction::new(Opcode::Become));
+                        self.emit(Instruction::new(Opcode::Pop));
                     }
                 }
                 
-                if !found_local {
-                    // If not local, store as global (consumes one copy)
-                    let idx = self.add_constant(Value::String(name.clone()));
-                    self.emit(Instruction::with_arg(Opcode::StoreGlobal, idx));
-                    // Stack depth is now managed by emit()
-                }
+                self.in_tail_position = saved_tail;
                 
                 // The duplicated value remains on the stack as the result
                 // Assignment now returns the assigned value
                 Ok(())
             }
-            Node::Application { function, args } if args.len() == 2 => {
-                // This might be a field access like (get obj field)
-                // For now, we'll error out
-                // TODO: Implement field assignment
-                Err(anyhow!("Field assignment not yet implemented"))
+            _ => Err(anyhow!(
+                "Invalid assignment target: must be a variable or a field"
+            )),
+        }
+    }
+    
+    /// Store the value on top of the stack into an assignment target.
+    ///
+    /// Records are immutable values, so `obj.field := v` builds an updated
+    /// copy with `SetField` and writes it back to `obj`, recursively for
+    /// nested targets such as `a.b.c := v`.
+    fn compile_store_place(&mut self, graph: &ASTGraph, target: NodeId) -> Result<()> {
+        let target_node = graph
+            .get_node(target)
+            .ok_or_else(|| anyhow!("Invalid target node in assignment: {:?}", target))?;
+        
+        match target_node {
+            Node::Variable { name } => self.compile_store_variable(name),
+            Node::QualifiedVariable {
+                module_name,
+                variable_name,
+            } => {
+                // [value] -> [record, value] -> [updated record]
+                self.compile_variable(module_name)?;
+                self.emit(Instruction::new(Opcode::Swap));
+                let field_idx = self.add_constant(Value::String(variable_name.clone()));
+                self.emit(Instruction::with_arg(Opcode::SetField, field_idx));
+                self.compile_store_variable(module_name)
+            }
+            Node::FieldAccess { object, field } => {
+                self.compile_node(graph, *object)?;
+                self.emit(Instruction::new(Opcode::Swap));
+                let field_idx = self.add_constant(Value::String(field.clone()));
+                self.emit(Instruction::with_arg(Opcode::SetField, field_idx));
+                self.compile_store_place(graph, *object)
+            }
+            _ => Err(anyhow!(
+                "Invalid assignment target: must be a variable or a field"
+            )),
+        }
+    }
+    
+    /// Store the value on top of the stack into a variable (consumes it)
+    fn compile_store_variable(&mut self, name: &str) -> Result<()> {
+        // Look up in locals
+        for (scope_idx, scope) in self.locals.iter().enumerate().rev() {
+            if let Some(&rel_pos) = scope.get(name) {
+                // The position stored is relative to the scope base
+                let abs_pos = self.get_scope_base(scope_idx)? + rel_pos;
+                
+                if self.is_cell_var(scope_idx, name)? {
+                    // [value] -> [value, cell] -> [cell, value] -> CellSet -> nil
+                    self.emit(Instruction::with_arg(Opcode::Load, abs_pos as u32));
+                    self.emit(Instruction::new(Opcode::Swap));
+                    self.emit(Instruction::new(Opcode::CellSet));
+                    self.emit(Instruction::new(Opcode::Pop));
+                } else {
+                    self.emit(Instruction::with_arg(Opcode::UpdateLocal, abs_pos as u32));
+                }
+                return Ok(());
+            }
+        }
+        
+        // Captured cells can be written through; plain captures are copies
+        for scope in self.captured.iter().rev() {
+            if let Some(&capture_idx) = scope.get(name) {
+                if !self.captured_cells.contains(name) {
+                    return Err(anyhow!("Cannot assign to captured variable '{}'", name));
+                }
+                self.emit(Instruction::with_arg(Opcode::LoadCaptured, capture_idx as u32));
+                self.emit(Instruction::new(Opcode::Swap));
+                self.emit(Instruction::new(Opcode::CellSet));
+                self.emit(Instruction::new(Opcode::Pop));
+                return Ok(());
             }
-            _ => Err(anyhow!("Invalid assignment target: must be a variable"))
+        }
+        
+        // If not local, store as global (consumes one copy)
+        let idx = self.add_constant(Value::String(name.to_string()));
+        self.emit(Instruction::with_arg(Opcode::StoreGlobal, idx));
+        Ok(())
+    }
+    
+    /// The variable an assignment target ultimately writes to
+    fn assignment_root(&self, graph: &ASTGraph, target: NodeId) -> Option<String> {
+        match graph.get_node(target)? {
+            Node::Variable { name } => Some(name.clone()),
+            Node::QualifiedVariable { module_name, .. } => Some(module_name.clone()),
+            Node::FieldAccess { object, .. } => self.assignment_root(graph, *object),
+            _ => None,
         }
     }
 
@@ -1033,6 +1430,7 @@ impl Compiler {
 
         // Jump to else if false
         let jump_to_else = self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0));
+        let branch_depth = self.stack_depth;
 
         // Compile then branch (preserves tail position)
         self.compile_node(graph, then_branch)?;
@@ -1044,6 +1442,9 @@ impl Compiler {
         let else_start = self.current_offset();
         self.patch_jump(jump_to_else, else_start);
 
+        // Only one branch runs, so the else branch starts from the same depth
+        self.stack_depth = branch_depth;
+
         // Compile else branch (preserves tail position)
         self.compile_node(graph, else_branch)?;
 
@@ -1054,103 +1455,539 @@ impl Compiler {
         Ok(())
     }
 
-    fn compile_list(&mut self, graph: &ASTGraph, items: &[NodeId]) -> Result<()> {
-        // Compile all items
-        for &item in items {
-            self.compile_node(graph, item)?;
+    fn compile_while(&mut self, graph: &ASTGraph, condition: NodeId, body: NodeId) -> Result<()> {
+        let saved_tail = self.in_tail_position;
+        self.in_tail_position = false;
+        let base = self.stack_depth;
+
+        let loop_start = self.emit(Instruction::new(Opcode::LoopStart));
+        self.compile_node(graph, condition)?;
+        let exit_jump = self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0));
+
+        self.loops.push(LoopContext {
+            chunk: self.current_chunk,
+            break_depth: base,
+            continue_depth: base,
+            handler_depth: self.open_handlers.len(),
+            break_jumps: Vec::new(),
+            continue_jumps: Vec::new(),
+        });
+        self.compile_node(graph, body)?;
+        self.emit(Instruction::new(Opcode::Pop));
+        let context = self.loops.pop().expect("loop context pushed above");
+
+        let next_iteration = self.current_offset();
+        for jump in context.continue_jumps {
+            self.patch_jump(jump, next_iteration);
         }
+        self.emit(Instruction::with_arg(Opcode::LoopEnd, loop_start as u32));
 
-        // Create list
-        self.emit(Instruction::with_arg(Opcode::MakeList, items.len() as u32));
+        // Running off the end of the loop produces nil; break jumps past it with its value
+        let exit = self.current_offset();
+        self.patch_jump(exit_jump, exit);
+        self.emit(Instruction::new(Opcode::PushNil));
+        let end = self.current_offset();
+        for jump in context.break_jumps {
+            self.patch_jump(jump, end);
+        }
 
+        self.in_tail_position = saved_tail;
         Ok(())
     }
 
+    /// Compile a for loop. Two hidden stack slots hold the iteration state:
+    /// the counter and end bound for a range, or the list and an index otherwise.
+    fn compile_for(
+        &mut self,
+        graph: &ASTGraph,
+        pattern: &Pattern,
+        iterable: NodeId,
+        body: NodeId,
+    ) -> Result<()> {
+        let saved_tail = self.in_tail_position;
+        self.in_tail_position = false;
+        let base = self.stack_depth;
+
+        let range = match graph.get_node(iterable) {
+            Some(Node::Range {
+                start,
+                end,
+                inclusive,
+            }) => Some((*start, *end, *inclusive)),
+            _ => None,
+        };
+        let (cursor_slot, limit_slot) = match range {
+            Some((start, end, _)) => {
+                self.compile_node(graph, start)?;
+                self.compile_node(graph, end)?;
+                (base, base + 1)
+            }
+            None => {
+                self.compile_node(graph, iterable)?;
+                self.emit(Instruction::new(Opcode::PushInt0));
+                (base + 1, base)
+            }
+        };
 
-    pub(crate) fn emit(&mut self, instruction: Instruction) -> usize {
-        let initial_depth = self.stack_depth;
-        
-        // Get the stack effect for this instruction
-        let effect = stack_effect(&instruction);
-        
-        // Validate we have enough values on the stack
-        debug_assert!(
-            self.stack_depth >= effect.pop,
-            "Instruction {:?} requires {} values on stack, but stack depth is only {}",
-            instruction.opcode,
-            effect.pop,
-            self.stack_depth
-        );
-        
-        // Apply the stack effect
-        self.stack_depth = effect.apply(self.stack_depth);
-        
-        // Additional validation for specific instructions
-        match instruction.opcode {
-            // For PopN, we need special handling because it keeps the top value
-            Opcode::PopN => {
-                // PopN pops N values from beneath the top value, keeping the top
-                // So the actual requirement is N+1 values on the initial stack
-                debug_assert!(
-                    initial_depth >= instruction.arg as usize + 1,
-                    "PopN({}) requires at least {} values on stack, but depth was {}",
-                    instruction.arg,
-                    instruction.arg as usize + 1,
-                    initial_depth
-                );
+        let loop_start = self.emit(Instruction::new(Opcode::LoopStart));
+        self.emit(Instruction::with_arg(Opcode::Load, cursor_slot as u32));
+        self.emit(Instruction::with_arg(Opcode::Load, limit_slot as u32));
+        match range {
+            Some((_, _, true)) => {
+                self.emit(Instruction::new(Opcode::Le));
             }
-            _ => {}
-        }
-        
-        // Verify stack depth never goes negative (saturating operations should prevent this)
-        debug_assert!(
-            self.stack_depth <= 10000, // Reasonable upper bound
-            "Stack depth {} seems unreasonably large - possible underflow",
-            self.stack_depth
-        );
-        
-        // For instructions that should produce values, verify stack grew
-        if effect.push > 0 && effect.pop == 0 {
-            debug_assert!(
-                self.stack_depth > initial_depth,
-                "Instruction {:?} should increase stack depth",
-                instruction.opcode
-            );
-        }
-        
-        let offset = self.bytecode.chunks[self.current_chunk].add_instruction(instruction);
-        
-        // Record source location for this instruction if we have a current node
-        if self.options.debug_info {
-            if let Some(node_id) = self.current_node_id {
-                self.record_source_location(offset, node_id);
+            Some((_, _, false)) => {
+                self.emit(Instruction::new(Opcode::Lt));
+            }
+            None => {
+                self.emit(Instruction::new(Opcode::ListLen));
+                self.emit(Instruction::new(Opcode::Lt));
             }
         }
-        
-        offset
-    }
+        let exit_jump = self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0));
 
-    fn add_constant(&mut self, value: Value) -> u32 {
-        self.bytecode.chunks[self.current_chunk].add_constant(value)
-    }
+        // Push the current element
+        if range.is_none() {
+            self.emit(Instruction::with_arg(Opcode::Load, limit_slot as u32));
+        }
+        self.emit(Instruction::with_arg(Opcode::Load, cursor_slot as u32));
+        if range.is_none() {
+            self.emit(Instruction::new(Opcode::ListGet));
+        }
 
-    fn current_offset(&self) -> usize {
-        self.bytecode.chunks[self.current_chunk].instructions.len()
-    }
+        self.loops.push(LoopContext {
+            chunk: self.current_chunk,
+            break_depth: base + 2,
+            continue_depth: base + 2,
+            handler_depth: self.open_handlers.len(),
+            break_jumps: Vec::new(),
+            continue_jumps: Vec::new(),
+        });
+        self.compile_loop_iteration(graph, pattern, body)?;
+        let context = self.loops.pop().expect("loop context pushed above");
+
+        let next_iteration = self.current_offset();
+        for jump in context.continue_jumps {
+            self.patch_jump(jump, next_iteration);
+        }
+        self.emit(Instruction::with_arg(Opcode::Load, cursor_slot as u32));
+        self.emit(Instruction::new(Opcode::PushInt1));
+        self.emit(Instruction::new(Opcode::Add));
+        self.emit(Instruction::with_arg(Opcode::UpdateLocal, cursor_slot as u32));
+        self.emit(Instruction::with_arg(Opcode::LoopEnd, loop_start as u32));
+
+        let exit = self.current_offset();
+        self.patch_jump(exit_jump, exit);
+        self.emit(Instruction::new(Opcode::PushNil));
+        let end = self.current_offset();
+        for jump in context.break_jumps {
+            self.patch_jump(jump, end);
+        }
+        // Drop the iteration state beneath the result
+        self.emit(Instruction::with_arg(Opcode::PopN, 2));
 
-    fn patch_jump(&mut self, offset: usize, target: usize) {
-        self.bytecode.chunks[self.current_chunk].patch_jump(offset, target);
+        self.in_tail_position = saved_tail;
+        Ok(())
     }
 
-    fn compile_effect(
+    /// Bind the element on top of the stack to a for loop's pattern and run
+    /// the body, leaving the stack as it was before the element was pushed.
+    /// An element that does not match the pattern raises a match error, as a
+    /// refutable `let` does.
+    fn compile_loop_iteration(
         &mut self,
         graph: &ASTGraph,
-        effect_type: fluentai_core::ast::EffectType,
-        operation: &str,
-        args: &[NodeId],
+        pattern: &Pattern,
+        body: NodeId,
+    ) -> Result<()> {
+        let iteration_depth = self.stack_depth - 1;
+        let mismatch_jump = if self.compile_pattern_check(graph, pattern)? {
+            Some(self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)))
+        } else {
+            None
+        };
+
+        self.push_pattern_scope(graph, pattern, iteration_depth)?;
+        self.compile_node(graph, body)?;
+        self.exit_scope();
+
+        // Discard the body's value along with the element and anything bound from it
+        while self.stack_depth > iteration_depth {
+            self.emit(Instruction::new(Opcode::Pop));
+        }
+
+        if let Some(mismatch_jump) = mismatch_jump {
+            let done_jump = self.emit(Instruction::with_arg(Opcode::Jump, 0));
+            let mismatch = self.current_offset();
+            self.patch_jump(mismatch_jump, mismatch);
+            // Only the unmatched element is left on the mismatch path
+            self.stack_depth = iteration_depth + 1;
+            self.emit(Instruction::new(Opcode::MatchError));
+            let done = self.current_offset();
+            self.patch_jump(done_jump, done);
+        }
+        Ok(())
+    }
+
+    /// The innermost loop, if it belongs to the function being compiled
+    fn current_loop(&self, keyword: &str) -> Result<&LoopContext> {
+        self.loops
+            .last()
+            .filter(|context| context.chunk == self.current_chunk)
+            .ok_or_else(|| anyhow!("'{}' outside of a loop", keyword))
+    }
+
+    fn compile_break(&mut self, graph: &ASTGraph, value: Option<NodeId>) -> Result<()> {
+        let context = self.current_loop("break")?;
+        let (break_depth, handler_depth) = (context.break_depth, context.handler_depth);
+        let depth = self.stack_depth;
+
+        let saved_tail = self.in_tail_position;
+        self.in_tail_position = false;
+        match value {
+            Some(value) => self.compile_node(graph, value)?,
+            None => {
+                self.emit(Instruction::new(Opcode::PushNil));
+            }
+        }
+        self.close_handlers(graph, handler_depth)?;
+        self.in_tail_position = saved_tail;
+
+        // Unwind everything the loop body has pushed, keeping the value on top
+        let unwind = self.stack_depth - 1 - break_depth;
+        if unwind > 0 {
+            self.emit(Instruction::with_arg(Opcode::PopN, unwind as u32));
+        }
+        let jump = self.emit(Instruction::with_arg(Opcode::Jump, 0));
+        if let Some(context) = self.loops.last_mut() {
+            context.break_jumps.push(jump);
+        }
+
+        // Control does not continue past the jump; keep the depth of an expression
+        self.stack_depth = depth + 1;
+        Ok(())
+    }
+
+    fn compile_continue(&mut self, graph: &ASTGraph) -> Result<()> {
+        let context = self.current_loop("continue")?;
+        let (continue_depth, handler_depth) = (context.continue_depth, context.handler_depth);
+        let depth = self.stack_depth;
+
+        let saved_tail = std::mem::replace(&mut self.in_tail_position, false);
+        self.close_handlers(graph, handler_depth)?;
+        self.in_tail_position = saved_tail;
+
+        for _ in continue_depth..depth {
+            self.emit(Instruction::new(Opcode::Pop));
+        }
+        let jump = self.emit(Instruction::with_arg(Opcode::Jump, 0));
+        if let Some(context) = self.loops.last_mut() {
+            context.continue_jumps.push(jump);
+        }
+
+        self.stack_depth = depth + 1;
+        Ok(())
+    }
+
+    /// Close the handlers opened since `handler_depth`, innermost first, before
+    /// a jump out of them. Pending finally blocks run inline and leave the
+    /// stack unchanged.
+    fn close_handlers(&mut self, graph: &ASTGraph, handler_depth: usize) -> Result<()> {
+        for index in (handler_depth..self.open_handlers.len()).rev() {
+            match self.open_handlers[index] {
+                OpenHandler::Try { installed, finally } => {
+                    if installed {
+                        self.emit(Instruction::new(Opcode::PopHandler));
+                    }
+                    if let Some(finally) = finally {
+                        // The finally block runs outside its own try
+                        let inner = self.open_handlers.split_off(index);
+                        self.compile_node(graph, finally)?;
+                        self.open_handlers.extend(inner);
+                        self.emit(Instruction::new(Opcode::Pop));
+                    }
+                }
+                OpenHandler::Effect => {
+                    self.emit(Instruction::new(Opcode::UninstallHandler));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A range outside a for loop evaluates to a list via the `range` builtin
+    fn compile_range(
+        &mut self,
+        graph: &ASTGraph,
+        start: NodeId,
+        end: NodeId,
+        inclusive: bool,
+    ) -> Result<()> {
+        let saved_tail = self.in_tail_position;
+        self.in_tail_position = false;
+        self.compile_node(graph, start)?;
+        self.compile_node(graph, end)?;
+        if inclusive {
+            self.emit(Instruction::new(Opcode::PushInt1));
+            self.emit(Instruction::new(Opcode::Add));
+        }
+        self.in_tail_position = saved_tail;
+
+        let idx = self.add_constant(Value::String("range".to_string()));
+        self.emit(Instruction::with_arg(Opcode::LoadGlobal, idx));
+        self.emit(Instruction::with_arg(Opcode::Call, 2));
+        Ok(())
+    }
+
+    fn compile_list(&mut self, graph: &ASTGraph, items: &[NodeId]) -> Result<()> {
+        // Compile all items
+        for &item in items {
+            self.compile_node(graph, item)?;
+        }
+
+        // Create list
+        self.emit(Instruction::with_arg(Opcode::MakeList, items.len() as u32));
+
+        Ok(())
+    }
+
+
+    fn compile_struct_construct(
+        &mut self,
+        graph: &ASTGraph,
+        name: &str,
+        fields: &[(String, NodeId)],
+    ) -> Result<()> {
+        // Use the declared field order when the definition is known, so that
+        // values built with fields in any order have the same layout
+        let field_names = match self.struct_layouts.get(name) {
+            Some(layout) => {
+                for (field, _) in fields {
+                    if !layout.contains(field) {
+                        return Err(anyhow!("Struct {} has no field '{}'", name, field));
+                    }
+                }
+                for field in layout {
+                    if !fields.iter().any(|(f, _)| f == field) {
+                        return Err(anyhow!("Missing field '{}' in {} construction", field, name));
+                    }
+                }
+                layout.clone()
+            }
+            None => fields.iter().map(|(f, _)| f.clone()).collect(),
+        };
+
+        let saved_tail = self.in_tail_position;
+        self.in_tail_position = false;
+        for field in &field_names {
+            let (_, value) = fields
+                .iter()
+                .find(|(f, _)| f == field)
+                .ok_or_else(|| anyhow!("Missing field '{}' in {} construction", field, name))?;
+            self.compile_node(graph, *value)?;
+        }
+        self.in_tail_position = saved_tail;
+
+        let mut layout = Vec::with_capacity(field_names.len() + 1);
+        layout.push(Value::String(name.to_string()));
+        layout.extend(field_names.into_iter().map(Value::String));
+        let field_count = layout.len() - 1;
+        let layout_idx = self.add_constant(Value::List(layout));
+
+        let packed = pack_arg(
+            layout_idx,
+            MAKESTRUCT_LAYOUT_SHIFT,
+            field_count,
+            MAKESTRUCT_FIELD_COUNT_MASK,
+        )
+        .ok_or_else(|| anyhow!("Struct {} has too many fields or constants", name))?;
+        self.emit(Instruction::with_arg(Opcode::MakeStruct, packed));
+
+        Ok(())
+    }
+
+    fn compile_struct_update(
+        &mut self,
+        graph: &ASTGraph,
+        name: &str,
+        base: NodeId,
+        fields: &[(String, NodeId)],
+    ) -> Result<()> {
+        if let Some(layout) = self.struct_layouts.get(name) {
+            if let Some((field, _)) = fields.iter().find(|(f, _)| !layout.contains(f)) {
+                return Err(anyhow!("Struct {} has no field '{}'", name, field));
+            }
+        }
+
+        let saved_tail = self.in_tail_position;
+        self.in_tail_position = false;
+
+        // Each SetField consumes the struct and the new value and pushes a
+        // fresh copy, so the base value itself is never mutated
+        self.compile_node(graph, base)?;
+        for (field, value) in fields {
+            self.compile_node(graph, *value)?;
+            let field_idx = self.add_constant(Value::String(field.clone()));
+            self.emit(Instruction::with_arg(Opcode::SetField, field_idx));
+        }
+
+        self.in_tail_position = saved_tail;
+        Ok(())
+    }
+
+    fn compile_get_field(&mut self, field: &str) {
+        let field_idx = self.add_constant(Value::String(field.to_string()));
+        self.emit(Instruction::with_arg(Opcode::GetField, field_idx));
+    }
+
+    /// Compile `Type as Trait { ... }`: register each method for the type
+    ///
+    /// An impl on an enum is registered under the enum name, together with its
+    /// variant tags so that values of any variant dispatch to it.
+    fn compile_trait_impl(
+        &mut self,
+        graph: &ASTGraph,
+        type_name: &str,
+        methods: &[(String, NodeId)],
     ) -> Result<()> {
-        // Push effect type as string
-        let effect_str = format!("{:?}", effect_type);
+        let receiver_type = match self.enum_variants.get(type_name) {
+            Some(variants) => {
+                let mut layout = Vec::with_capacity(variants.len() + 1);
+                layout.push(Value::String(type_name.to_string()));
+                layout.extend(variants.iter().cloned().map(Value::String));
+                Value::List(layout)
+            }
+            None => Value::String(type_name.to_string()),
+        };
+        let type_idx = self.add_constant(receiver_type);
+
+        for (method, lambda) in methods {
+            // Method bodies are not self-tail-calls: calls by name dispatch
+            let saved_function = self.current_function.take();
+            self.compile_node(graph, *lambda)?;
+            self.current_function = saved_function;
+
+            let method_idx = self.add_constant(Value::String(method.clone()));
+            let packed = pack_arg(type_idx, METHOD_NAME_SHIFT, method_idx as usize, METHOD_LOW_MASK)
+                .ok_or_else(|| anyhow!("Too many constants to register {}.{}", type_name, method))?;
+            self.emit(Instruction::with_arg(Opcode::DefineMethod, packed));
+        }
+
+        // The impl itself evaluates to nil
+        self.emit(Instruction::new(Opcode::PushNil));
+        Ok(())
+    }
+
+    /// Compile `receiver.method(args)`, dispatching on the runtime type of the receiver
+    fn compile_method_call(&mut self, graph: &ASTGraph, method: &str, args: &[NodeId]) -> Result<()> {
+        if args.is_empty() {
+            return Err(anyhow!("Method '{}' called without a receiver", method));
+        }
+
+        let saved_tail = self.in_tail_position;
+        self.in_tail_position = false;
+        for &arg in args {
+            self.compile_node(graph, arg)?;
+        }
+        self.in_tail_position = saved_tail;
+
+        let method_idx = self.add_constant(Value::String(method.to_string()));
+        let packed = pack_arg(method_idx, METHOD_NAME_SHIFT, args.len(), METHOD_LOW_MASK)
+            .ok_or_else(|| anyhow!("Too many arguments or constants in call to method '{}'", method))?;
+        self.emit(Instruction::with_arg(Opcode::CallMethod, packed));
+        Ok(())
+    }
+
+    /// Whether a name resolves to a local or captured variable in the current scope
+    fn is_local_variable(&self, name: &str) -> bool {
+        self.locals.iter().any(|scope| scope.contains_key(name))
+            || self.captured.iter().any(|scope| scope.contains_key(name))
+    }
+
+    pub(crate) fn emit(&mut self, instruction: Instruction) -> usize {
+        let initial_depth = self.stack_depth;
+        
+        // Get the stack effect for this instruction
+        let effect = stack_effect(&instruction);
+        
+        // Validate we have enough values on the stack
+        debug_assert!(
+            self.stack_depth >= effect.pop,
+            "Instruction {:?} requires {} values on stack, but stack depth is only {}",
+            instruction.opcode,
+            effect.pop,
+            self.stack_depth
+        );
+        
+        // Apply the stack effect
+        self.stack_depth = effect.apply(self.stack_depth);
+        
+        // Additional validation for specific instructions
+        match instruction.opcode {
+            // For PopN, we need special handling because it keeps the top value
+            Opcode::PopN => {
+                // PopN pops N values from beneath the top value, keeping the top
+                // So the actual requirement is N+1 values on the initial stack
+                debug_assert!(
+                    initial_depth >= instruction.arg as usize + 1,
+                    "PopN({}) requires at least {} values on stack, but depth was {}",
+                    instruction.arg,
+                    instruction.arg as usize + 1,
+                    initial_depth
+                );
+            }
+            _ => {}
+        }
+        
+        // Verify stack depth never goes negative (saturating operations should prevent this)
+        debug_assert!(
+            self.stack_depth <= 10000, // Reasonable upper bound
+            "Stack depth {} seems unreasonably large - possible underflow",
+            self.stack_depth
+        );
+        
+        // For instructions that should produce values, verify stack grew
+        if effect.push > 0 && effect.pop == 0 {
+            debug_assert!(
+                self.stack_depth > initial_depth,
+                "Instruction {:?} should increase stack depth",
+                instruction.opcode
+            );
+        }
+        
+        let offset = self.bytecode.chunks[self.current_chunk].add_instruction(instruction);
+        
+        // Record source location for this instruction if we have a current node
+        if self.options.debug_info {
+            if let Some(node_id) = self.current_node_id {
+                self.record_source_location(offset, node_id);
+            }
+        }
+        
+        offset
+    }
+
+    fn add_constant(&mut self, value: Value) -> u32 {
+        self.bytecode.chunks[self.current_chunk].add_constant(value)
+    }
+
+    fn current_offset(&self) -> usize {
+        self.bytecode.chunks[self.current_chunk].instructions.len()
+    }
+
+    fn patch_jump(&mut self, offset: usize, target: usize) {
+        self.bytecode.chunks[self.current_chunk].patch_jump(offset, target);
+    }
+
+    fn compile_effect(
+        &mut self,
+        graph: &ASTGraph,
+        effect_type: fluentai_core::ast::EffectType,
+        operation: &str,
+        args: &[NodeId],
+    ) -> Result<()> {
+        // Push effect type by name, matching the names handlers are installed under
+        let effect_str = effect_type.to_string();
         let idx = self.add_constant(Value::String(effect_str));
         self.emit(Instruction::with_arg(Opcode::PushConst, idx));
 
@@ -1218,6 +2055,15 @@ impl Compiler {
         Ok(())
     }
 
+    fn compile_parallel(&mut self, graph: &ASTGraph, branches: &[NodeId]) -> Result<()> {
+        // Each branch becomes a closure that runs in its own task
+        for branch in branches {
+            self.compile_lambda(graph, &[], *branch)?;
+        }
+        self.emit(Instruction::with_arg(Opcode::Parallel, branches.len() as u32));
+        Ok(())
+    }
+
     fn compile_send(&mut self, graph: &ASTGraph, channel: NodeId, value: NodeId) -> Result<()> {
         // Compile channel and value
         self.compile_node(graph, channel)?;
@@ -1329,8 +2175,13 @@ impl Compiler {
         // Compile initial state
         self.compile_node(graph, initial_state)?;
         
-        // Compile handler function  
+        // Compile handler function, remembering its state parameter so that
+        // field writes like `state.count := n` update the actor
+        if let Some(Node::Lambda { params, .. }) = graph.get_node(handler) {
+            self.pending_actor_state_var = params.first().cloned();
+        }
         self.compile_node(graph, handler)?;
+        self.pending_actor_state_var = None;
         
         // Create actor
         self.emit(Instruction::new(Opcode::CreateActor));
@@ -1369,63 +2220,8 @@ impl Compiler {
         // The ActorReceive opcode will put the current message on the stack
         self.emit(Instruction::new(Opcode::ActorReceive));
         
-        // Now we have the message on the stack, compile it like a match expression
-        // We'll compile pattern matching as a series of if-else chains
-        let mut jump_to_ends = Vec::new();
-        
-        for (i, (pattern, handler)) in patterns.iter().enumerate() {
-            let is_last = i == patterns.len() - 1;
-            
-            // Compile pattern test
-            let (bindings, always_matches) = self.compile_pattern_test(graph, pattern)?;
-            
-            // Handle the test result
-            let jump_to_next = if always_matches {
-                // Pattern always matches - no jump needed
-                None
-            } else {
-                // We have a boolean on the stack from the pattern test
-                // Jump to next branch if false
-                Some(self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)))
-            };
-            
-            // Pattern matched - the matched value is still on the stack
-            
-            // Apply bindings in reverse order
-            for (name, _) in bindings.iter().rev() {
-                let idx = self.add_local(name.to_string())?;
-                self.emit(Instruction::with_arg(Opcode::Store, idx as u32));
-            }
-            
-            // Pop the matched value (we've extracted what we need)
-            self.emit(Instruction::new(Opcode::Pop));
-            
-            // Compile the handler body
-            self.compile_node(graph, *handler)?;
-            
-            // Jump to end
-            let jump_to_end = self.emit(Instruction::with_arg(Opcode::Jump, 0));
-            jump_to_ends.push(jump_to_end);
-            
-            // Patch the jump to next branch (if any)
-            if let Some(jump_idx) = jump_to_next {
-                let next_branch_start = self.bytecode.chunks[self.current_chunk].instructions.len();
-                self.bytecode.chunks[self.current_chunk].patch_jump(jump_idx, next_branch_start);
-            }
-            
-            // If this is the last branch and it doesn't always match, we need a default case
-            if is_last && !always_matches {
-                // No pattern matched - pop the message and return nil
-                self.emit(Instruction::new(Opcode::Pop));
-                self.emit(Instruction::new(Opcode::PushNil));
-            }
-        }
-        
-        // Patch all jumps to end
-        let end_pos = self.bytecode.chunks[self.current_chunk].instructions.len();
-        for jump_idx in jump_to_ends {
-            self.bytecode.chunks[self.current_chunk].patch_jump(jump_idx, end_pos);
-        }
+        // Match the message like a match expression, producing nil when nothing matches
+        self.compile_match_branches(graph, patterns, false)?;
         
         // TODO: Handle timeout if provided
         if timeout.is_some() {
@@ -1535,12 +2331,11 @@ impl Compiler {
                     let before_handler_depth = self.stack_depth;
                     self.compile_node(graph, *handler)?;
                     
-                    // After handler compilation, we should have exactly one value on stack
-                    // (the handler result)
+                    // After handler compilation, the handler result sits above the error parameter
                     debug_assert!(
-                        self.stack_depth == before_handler_depth,
-                        "Handler should consume error parameter and produce result: expected depth {}, got {}",
-                        before_handler_depth,
+                        self.stack_depth == before_handler_depth + 1,
+                        "Handler should produce one result above the error parameter: expected depth {}, got {}",
+                        before_handler_depth + 1,
                         self.stack_depth
                     );
                     
@@ -1552,12 +2347,8 @@ impl Compiler {
                     // Pop scope
                     self.pop_scope();
                     
-                    // Reset stack depth to handler depth (the result replaced the error)
-                    self.stack_depth = handler_stack_depth;
-                    debug_assert!(
-                        self.stack_depth == handler_stack_depth,
-                        "Stack depth should be restored to handler depth after catch"
-                    );
+                    // The result replaced the error just above the handler depth
+                    self.stack_depth = handler_stack_depth + 1;
                 }
                 _ => {
                     // For other patterns, just compile the handler (not fully supported yet)
@@ -1659,14 +2450,18 @@ impl Compiler {
         let (push_handler_idx, push_finally_idx) = self.setup_error_handlers(finally)?;
         
         // Compile try body and get jump after successful completion
+        self.open_handlers.push(OpenHandler::Try { installed: true, finally });
         let jump_after_body = self.compile_try_body(graph, body)?;
+        self.open_handlers.pop();
         
         // Set up catch handler position and patch PushHandler
         self.setup_catch_handlers(push_handler_idx)?;
         
         // Compile catch branches with pattern matching
         // Pass the handler stack depth so we know where the error will be placed
+        self.open_handlers.push(OpenHandler::Try { installed: false, finally });
         self.compile_catch_branches(graph, catch_branches, handler_stack_depth)?;
+        self.open_handlers.pop();
         
         // Set up jump to finally after catch
         let jump_after_catch = self.setup_finally_jumps(finally)?;
@@ -1788,7 +2583,13 @@ impl Compiler {
         if self.options.optimization_level != OptimizationLevel::None {
             // Use the enhanced analyzer for more accurate results when optimizing
             let mut analyzer = FreeVarAnalyzer::new();
-            analyzer.analyze_with_params(graph, node_id, params)
+            let mut free_vars = analyzer.analyze_with_params(graph, node_id, params)?;
+            // A qualified receiver only needs capturing when it is a local record,
+            // module names are resolved at runtime
+            free_vars.retain(|name| {
+                !analyzer.is_qualified_receiver(name) || self.is_local_variable(name)
+            });
+            Ok(free_vars)
         } else {
             // Use the existing implementation for non-optimized builds
             let mut free_vars = HashSet::new();
@@ -1858,6 +2659,27 @@ impl Compiler {
                 }
                 self.collect_free_variables(graph, *body, free_vars, &mut new_bound)?;
             }
+            Node::Letrec { bindings, body } => {
+                // Letrec bindings can all reference each other
+                let mut new_bound = bound_vars.clone();
+                for (name, _) in bindings {
+                    new_bound.insert(name.clone());
+                }
+                for (_, value) in bindings {
+                    self.collect_free_variables(graph, *value, free_vars, &mut new_bound)?;
+                }
+                self.collect_free_variables(graph, *body, free_vars, &mut new_bound)?;
+            }
+            Node::Begin { exprs } => {
+                for expr in exprs {
+                    self.collect_free_variables(graph, *expr, free_vars, bound_vars)?;
+                }
+            }
+            Node::Assignment { target, value } => {
+                // The assigned variable must be captured to be written through
+                self.collect_free_variables(graph, *target, free_vars, bound_vars)?;
+                self.collect_free_variables(graph, *value, free_vars, bound_vars)?;
+            }
             Node::Application { function, args } => {
                 self.collect_free_variables(graph, *function, free_vars, bound_vars)?;
                 for arg in args {
@@ -1873,7 +2695,39 @@ impl Compiler {
                 self.collect_free_variables(graph, *then_branch, free_vars, bound_vars)?;
                 self.collect_free_variables(graph, *else_branch, free_vars, bound_vars)?;
             }
-            Node::List(items) => {
+            Node::While { condition, body } => {
+                self.collect_free_variables(graph, *condition, free_vars, bound_vars)?;
+                self.collect_free_variables(graph, *body, free_vars, bound_vars)?;
+            }
+            Node::For {
+                pattern,
+                iterable,
+                body,
+            } => {
+                self.collect_free_variables(graph, *iterable, free_vars, bound_vars)?;
+                let mut new_bound = bound_vars.clone();
+                new_bound.extend(pattern.bound_variables());
+                self.collect_free_variables(graph, *body, free_vars, &mut new_bound)?;
+            }
+            Node::Match { expr, branches, .. } => {
+                self.collect_free_variables(graph, *expr, free_vars, bound_vars)?;
+                for (pattern, body) in branches {
+                    let mut new_bound = bound_vars.clone();
+                    new_bound.extend(pattern.bound_variables());
+                    for node in pattern_expressions(pattern) {
+                        self.collect_free_variables(graph, node, free_vars, &mut new_bound)?;
+                    }
+                    self.collect_free_variables(graph, *body, free_vars, &mut new_bound)?;
+                }
+            }
+            Node::Range { start, end, .. } => {
+                self.collect_free_variables(graph, *start, free_vars, bound_vars)?;
+                self.collect_free_variables(graph, *end, free_vars, bound_vars)?;
+            }
+            Node::Break { value: Some(value) } => {
+                self.collect_free_variables(graph, *value, free_vars, bound_vars)?;
+            }
+            Node::List(items) | Node::Tuple(items) => {
                 for item in items {
                     self.collect_free_variables(graph, *item, free_vars, bound_vars)?;
                 }
@@ -1892,6 +2746,11 @@ impl Compiler {
             Node::Spawn { expr } => {
                 self.collect_free_variables(graph, *expr, free_vars, bound_vars)?;
             }
+            Node::Parallel { branches } => {
+                for branch in branches {
+                    self.collect_free_variables(graph, *branch, free_vars, bound_vars)?;
+                }
+            }
             Node::Send { channel, value } => {
                 self.collect_free_variables(graph, *channel, free_vars, bound_vars)?;
                 self.collect_free_variables(graph, *value, free_vars, bound_vars)?;
@@ -1962,6 +2821,31 @@ impl Compiler {
                     self.collect_free_variables(graph, *def, free_vars, bound_vars)?;
                 }
             }
+            Node::QualifiedVariable { module_name, .. } => {
+                // `record.field` reads the record from a local binding
+                if !bound_vars.contains(module_name) && self.is_local_variable(module_name) {
+                    free_vars.insert(module_name.clone());
+                }
+            }
+            Node::StructConstruct { fields, .. } => {
+                for (_, value) in fields {
+                    self.collect_free_variables(graph, *value, free_vars, bound_vars)?;
+                }
+            }
+            Node::FieldAccess { object, .. } => {
+                self.collect_free_variables(graph, *object, free_vars, bound_vars)?;
+            }
+            Node::StructUpdate { base, fields, .. } => {
+                self.collect_free_variables(graph, *base, free_vars, bound_vars)?;
+                for (_, value) in fields {
+                    self.collect_free_variables(graph, *value, free_vars, bound_vars)?;
+                }
+            }
+            Node::MethodCall { args, .. } => {
+                for arg in args {
+                    self.collect_free_variables(graph, *arg, free_vars, bound_vars)?;
+                }
+            }
             _ => {} // Literals, Channel, etc. have no variables
         }
 
@@ -2012,6 +2896,7 @@ impl Compiler {
         graph: &ASTGraph,
         expr: NodeId,
         branches: &[(Pattern, NodeId)],
+        must_match: bool,
     ) -> Result<()> {
         // Validate that we have at least one branch
         if branches.is_empty() {
@@ -2019,564 +2904,457 @@ impl Compiler {
         }
 
         // Compile the expression to match
+        let saved_tail = self.in_tail_position;
+        self.in_tail_position = false;
         self.compile_node(graph, expr)?;
+        self.in_tail_position = saved_tail;
 
-        // We'll compile pattern matching as a series of if-else chains
-        // Key insight: for literal patterns, we need to preserve the value being matched
-        // across multiple tests. We'll use a different strategy:
-        // 1. Keep the value on stack throughout
-        // 2. For literal patterns, dup the value, push literal, compare
-        // 3. Only consume the value when we find a match
+        self.compile_match_branches(graph, branches, must_match)
+    }
 
+    /// Match the value on top of the stack against each branch in turn,
+    /// replacing it with the value of the first branch that matches.
+    /// When no branch matches, either raise a match error or produce nil.
+    fn compile_match_branches(
+        &mut self,
+        graph: &ASTGraph,
+        branches: &[(Pattern, NodeId)],
+        raise_on_no_match: bool,
+    ) -> Result<()> {
+        let subject_depth = self.stack_depth;
+        let subject_slot = subject_depth - 1;
         let mut jump_to_ends = Vec::new();
+        let mut exhaustive = false;
 
-        for (i, (pattern, body)) in branches.iter().enumerate() {
-            let is_last = i == branches.len() - 1;
+        for (pattern, body) in branches {
+            // Every branch starts with just the subject on the stack
+            self.stack_depth = subject_depth;
 
-            // Compile pattern test
-            // This will leave a boolean on the stack for conditional patterns
-            // For always-matching patterns (variable/wildcard), no test is generated
-            let (bindings, always_matches) = self.compile_pattern_test(graph, pattern)?;
-
-            // Handle the test result
-            let jump_to_next = if always_matches {
-                // Pattern always matches - no jump needed
-                None
-            } else {
-                // We have a boolean on the stack from the pattern test
-                // Jump to next branch if false
+            let saved_tail = self.in_tail_position;
+            self.in_tail_position = false;
+            let jump_to_next = if self.compile_pattern_check(graph, pattern)? {
                 Some(self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)))
-            };
-
-            // At this point, pattern matched (or always matches)
-            // The matched value is still on the stack
-
-            // Check for special cons pattern case
-            let is_cons_pattern = if let Pattern::Constructor { name, patterns } = pattern {
-                (name == "cons" || name == "Cons")
-                    && patterns.len() == 2
-                    && matches!(&patterns[0], Pattern::Variable(_))
-                    && matches!(&patterns[1], Pattern::Variable(_))
             } else {
-                false
+                None
             };
+            let bound = self.push_pattern_scope(graph, pattern, subject_slot)?;
+            self.in_tail_position = saved_tail;
 
-            // Create new scope for pattern bindings
-            if !bindings.is_empty() {
-                if is_cons_pattern {
-                    // Special handling for cons pattern
-                    // At this point, JumpIfNot has already consumed the boolean (if there was one)
-                    // The matched value (list) is on top of stack at position stack_depth-1
-
-                    // Save the position where the list currently is
-                    let list_position = self.stack_depth - 1;
-
-                    // We need to duplicate the list first before extracting head/tail
-                    self.emit(Instruction::new(Opcode::Dup)); // [..., list] -> [..., list, list]
-                    // Stack depth is now managed by emit()
-
-                    // Extract head from the duplicated list
-                    self.emit(Instruction::new(Opcode::ListHead)); // [..., list, list] -> [..., list, head]
-                    
-                    // Now we need to get the tail from the original list
-                    // First swap to get the original list on top
-                    self.emit(Instruction::new(Opcode::Swap)); // [..., list, head] -> [..., head, list]
-                    
-                    // Extract tail
-                    self.emit(Instruction::new(Opcode::ListTail)); // [..., head, list] -> [..., head, tail]
-
-                    // Stack now has [head, tail] where the list used to be
-                    // head is at list_position, tail is at list_position + 1
-
-                    // Set up scope for bindings
-                    self.locals.push(HashMap::new());
-                    self.captured.push(HashMap::new());
-                    self.scope_bases.push(list_position); // Base of our new scope
-                    self.cell_vars.push(HashSet::new());
-                    let scope_idx = self.locals.len() - 1;
-
-                    // Bind head and tail with their positions relative to scope base
-                    // After all operations, head is at list_position and tail at list_position + 1
-                    // But we store them as 0 and 1 relative to the scope base
-                    if let Pattern::Constructor { patterns, .. } = pattern {
-                        if let Pattern::Variable(head_name) = &patterns[0] {
-                            // Store position relative to scope base
-                            self.locals[scope_idx].insert(head_name.clone(), 0);
-                        }
-                        if let Pattern::Variable(tail_name) = &patterns[1] {
-                            // Store position relative to scope base  
-                            self.locals[scope_idx].insert(tail_name.clone(), 1);
-                        }
-                    }
-                } else {
-                    // Regular pattern binding
-                    // After the pattern test and potential JumpIfNot, the matched value is on top of stack
-                    // The bindings contain absolute stack positions, but we need to adjust them
-                    // to be relative to our scope base
-
-                    // The matched value is at stack_depth - 1
-                    let value_pos = self.stack_depth - 1;
-
-                    self.locals.push(HashMap::new());
-                    self.captured.push(HashMap::new());
-                    self.scope_bases.push(value_pos);
-                    self.cell_vars.push(HashSet::new());
-                    let scope_idx = self.locals.len() - 1;
-
-                    // Add pattern bindings
-                    // For simple patterns like (as x 42), the binding position should be 0
-                    // relative to the scope base (which is the value position)
-                    for (name, abs_pos) in &bindings {
-                        // Convert absolute position to relative position
-                        let rel_pos = if *abs_pos >= value_pos {
-                            *abs_pos - value_pos
-                        } else {
-                            // This shouldn't happen, but handle it gracefully
-                            0
-                        };
-                        self.locals[scope_idx].insert(name.clone(), rel_pos);
-                    }
-                }
-            } else {
-                // No bindings - pop the matched value
-                self.emit(Instruction::new(Opcode::Pop));
-            }
-
-            // Compile branch body
+            // The branch body keeps the tail position of the match
             self.compile_node(graph, *body)?;
+            self.exit_scope();
 
-            // Clean up pattern bindings scope
-            if !bindings.is_empty() {
-                self.locals.pop();
-                self.captured.pop();
-                self.scope_bases.pop();
-                self.cell_vars.pop();
+            // Drop the subject and the bound parts, keeping the result
+            self.emit(Instruction::with_arg(Opcode::PopN, bound as u32 + 1));
 
-                // Note: For cons patterns, we leave the extracted values on the stack
-                // They will be cleaned up when the function returns
-                // This is simpler than trying to juggle the stack to preserve the result
-            }
-
-            // Jump to end (skip other branches and fallback)
-            // We need to jump unless this is the last branch AND it always matches
-            // (in which case there's no fallback code to skip)
-            if !is_last || !always_matches {
-                jump_to_ends.push(self.emit(Instruction::with_arg(Opcode::Jump, 0)));
-            }
-
-            // Patch jump to next branch
-            if let Some(jump_offset) = jump_to_next {
-                let next_branch = self.current_offset();
-                self.patch_jump(jump_offset, next_branch);
-
-                // JumpIfNot already consumed the boolean
-                // The value remains on stack for the next pattern test
+            match jump_to_next {
+                Some(jump) => {
+                    jump_to_ends.push(self.emit(Instruction::with_arg(Opcode::Jump, 0)));
+                    let next_branch = self.current_offset();
+                    self.patch_jump(jump, next_branch);
+                }
+                None => {
+                    // Later branches can never be reached
+                    exhaustive = true;
+                    break;
+                }
             }
         }
 
-        // If we get here and no pattern matched, we need to handle the error case
-        // Check if the last pattern was always-matching
-        let last_always_matches = if let Some((pattern, _)) = branches.last() {
-            match pattern {
-                Pattern::Wildcard | Pattern::Variable(_) => true,
-                Pattern::As { pattern, .. } => {
-                    // Check if the inner pattern always matches
-                    matches!(pattern.as_ref(), Pattern::Wildcard | Pattern::Variable(_))
-                }
-                _ => false,
+        if !exhaustive {
+            self.stack_depth = subject_depth;
+            if raise_on_no_match {
+                self.emit(Instruction::new(Opcode::MatchError));
+            } else {
+                self.emit(Instruction::new(Opcode::Pop));
+                self.emit(Instruction::new(Opcode::PushNil));
             }
-        } else {
-            false
-        };
-
-        if !last_always_matches && !branches.is_empty() {
-            // No pattern matched - this is a runtime error
-            // For now, push nil as a fallback
-            self.emit(Instruction::new(Opcode::Pop)); // Pop the unmatched value
-            self.emit(Instruction::new(Opcode::PushNil));
         }
 
-        // Patch all jumps to end (after fallback code)
         let end = self.current_offset();
-        for jump_offset in jump_to_ends {
-            self.patch_jump(jump_offset, end);
+        for jump in jump_to_ends {
+            self.patch_jump(jump, end);
         }
-
+        // The result takes the place of the subject
+        self.stack_depth = subject_depth;
         Ok(())
     }
 
-    fn compile_pattern_test(
+    /// Open a scope binding the names in a pattern that matched the value at
+    /// `slot`. Parts of the value that names bind to are pushed onto the stack;
+    /// returns how many were pushed. The caller closes the scope with `exit_scope`.
+    fn push_pattern_scope(
         &mut self,
         graph: &ASTGraph,
         pattern: &Pattern,
-    ) -> Result<(Vec<(String, usize)>, bool)> {
+        slot: usize,
+    ) -> Result<usize> {
+        let start_depth = self.stack_depth;
         let mut bindings = Vec::new();
+        self.compile_pattern_bindings(graph, pattern, slot, &mut Vec::new(), &mut bindings)?;
+
+        self.locals.push(HashMap::new());
+        self.captured.push(HashMap::new());
+        self.scope_bases.push(slot);
+        self.cell_vars.push(HashSet::new());
+        let scope_idx = self.locals.len() - 1;
+        for (name, pos) in bindings {
+            self.locals[scope_idx].insert(name, pos - slot);
+        }
+
+        Ok(self.stack_depth - start_depth)
+    }
+
+    /// Test whether the value on top of the stack matches a pattern.
+    /// Returns false without emitting anything when the pattern always matches;
+    /// otherwise leaves the value with a boolean above it.
+    fn compile_pattern_check(&mut self, graph: &ASTGraph, pattern: &Pattern) -> Result<bool> {
+        let value_depth = self.stack_depth;
 
         match pattern {
-            Pattern::Wildcard => {
-                // Always matches, no test needed
-                // Value remains on stack
-                Ok((bindings, true))
-            }
-            Pattern::Variable(name) => {
-                // Always matches, bind the value
-                let pos = self.stack_depth - 1; // Value is on top of stack
-                bindings.push((name.clone(), pos));
-                Ok((bindings, true))
-            }
+            Pattern::Wildcard | Pattern::Variable(_) => Ok(false),
+            Pattern::As { pattern, .. } => self.compile_pattern_check(graph, pattern),
             Pattern::Literal(lit) => {
-                // Duplicate the value for comparison
                 self.emit(Instruction::new(Opcode::Dup));
-                // Push literal and compare
                 self.compile_literal(lit)?;
                 self.emit(Instruction::new(Opcode::Eq));
-                // Stack now has: [value, bool]
-                // The boolean will be consumed by JumpIfNot
-                // The original value remains for the next test or for the body
-                Ok((bindings, false))
+                Ok(true)
+            }
+            Pattern::Range(range) => {
+                // Check lower bound, short-circuiting when it fails
+                self.emit(Instruction::new(Opcode::Dup));
+                self.compile_literal(&range.start)?;
+                self.emit(Instruction::new(Opcode::Ge));
+                let lower_fail_jump = self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0));
+
+                // Check upper bound
+                self.emit(Instruction::new(Opcode::Dup));
+                self.compile_literal(&range.end)?;
+                if range.inclusive {
+                    self.emit(Instruction::new(Opcode::Le));
+                } else {
+                    self.emit(Instruction::new(Opcode::Lt));
+                }
+                let end_jump = self.emit(Instruction::with_arg(Opcode::Jump, 0));
+
+                let lower_fail = self.current_offset();
+                self.patch_jump(lower_fail_jump, lower_fail);
+                self.stack_depth = value_depth;
+                self.emit(Instruction::new(Opcode::PushFalse));
+
+                let end = self.current_offset();
+                self.patch_jump(end_jump, end);
+                Ok(true)
+            }
+            Pattern::Tuple(patterns) => {
+                // IsTuple leaves the value beneath its result
+                self.emit(Instruction::with_arg(Opcode::IsTuple, patterns.len() as u32));
+                let steps = (0..patterns.len()).map(PatternStep::Element);
+                self.compile_pattern_parts_check(graph, patterns, steps, value_depth)?;
+                Ok(true)
             }
             Pattern::Constructor { name, patterns } => {
-                // Special handling for list patterns
-                if (name == "cons" || name == "Cons")
-                    && patterns.len() == 2
-                    && matches!(&patterns[0], Pattern::Variable(_))
-                    && matches!(&patterns[1], Pattern::Variable(_))
-                {
-                    // Special case: cons with two variable patterns
-                    // First check if the list is non-empty
-                    self.emit(Instruction::new(Opcode::Dup)); // [..., list, list]
-                    self.emit(Instruction::new(Opcode::ListEmpty)); // [..., list, bool]
-                    self.emit(Instruction::new(Opcode::Not)); // [..., list, !bool]
-
-                    // This is handled differently in compile_match
-                    if let (Pattern::Variable(head_name), Pattern::Variable(tail_name)) =
-                        (&patterns[0], &patterns[1])
-                    {
-                        // Just return the bindings, compile_match will handle extraction
-                        bindings.push((head_name.clone(), 0)); // placeholder positions
-                        bindings.push((tail_name.clone(), 1));
-                    }
-                    Ok((bindings, false)) // false = test was generated
-                } else if (name == "cons" || name == "Cons") && patterns.len() == 2 {
-                    // Handle cons pattern on lists
-                    // Stack before: [..., list]
-
-                    // First check if list is empty
-                    self.emit(Instruction::new(Opcode::Dup)); // [..., list, list]
-                    self.emit(Instruction::new(Opcode::ListEmpty)); // [..., list, bool]
-
-                    // If empty, fail - the test result (bool) will be left on stack
-                    // for JumpIfNot in compile_match
-                    // But we need to invert it because empty = fail
-                    self.emit(Instruction::new(Opcode::Not)); // [..., list, !bool]
-                    Ok((bindings, false))
-                } else if (name == "nil" || name == "Nil") && patterns.is_empty() {
-                    // Handle nil pattern on lists
+                // Check the shape of the value first
+                if is_nil_pattern(name, patterns.len()) {
+                    self.emit(Instruction::new(Opcode::Dup));
+                    self.emit(Instruction::new(Opcode::ListEmpty));
+                    return Ok(true);
+                } else if is_cons_pattern(name, patterns.len()) {
                     self.emit(Instruction::new(Opcode::Dup));
                     self.emit(Instruction::new(Opcode::ListEmpty));
-                    Ok((bindings, false))
+                    self.emit(Instruction::new(Opcode::Not));
                 } else {
-                    // Regular tagged value pattern
-                    self.emit(Instruction::new(Opcode::Dup)); // Duplicate the value for tag check
+                    // IsTagged leaves the value beneath its result
                     let tag_idx = self.add_constant(Value::String(name.clone()));
                     self.emit(Instruction::with_arg(Opcode::IsTagged, tag_idx));
-
-                    // If not the right tag, jump to next pattern
-                    let fail_jump = self.bytecode.chunks[self.current_chunk].instructions.len();
-                    self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)); // Will patch later
-
-                    // Extract and test sub-patterns
-                    for (i, sub_pattern) in patterns.iter().enumerate() {
-                        // Duplicate the tagged value
-                        self.emit(Instruction::new(Opcode::Dup));
-                        // Get the field
-                        self.emit(Instruction::with_arg(Opcode::GetTaggedField, i as u32));
-                        // Test the sub-pattern
-                        let (sub_bindings, _) = self.compile_pattern_test(graph, sub_pattern)?;
-                        bindings.extend(sub_bindings);
-
-                        // If sub-pattern failed, clean up stack and fail
-                        let _sub_fail_jump =
-                            self.bytecode.chunks[self.current_chunk].instructions.len();
-                        self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)); // Will patch later
-
-                        // Pop the test result (keep the value for next field or body)
-                        self.emit(Instruction::new(Opcode::Pop));
-                    }
-
-                    // All sub-patterns matched, push true
-                    self.emit(Instruction::new(Opcode::PushTrue));
-
-                    // Jump over the failure case
-                    let success_jump = self.bytecode.chunks[self.current_chunk].instructions.len();
-                    self.emit(Instruction::with_arg(Opcode::Jump, 0)); // Will patch later
-
-                    // Patch the fail jump to here
-                    let fail_target = self.bytecode.chunks[self.current_chunk].instructions.len();
-                    self.bytecode.chunks[self.current_chunk].patch_jump(fail_jump, fail_target);
-
-                    // Also patch sub-pattern fail jumps
-                    for i in 0..patterns.len() {
-                        let sub_fail_jump = fail_jump + 2 + (i * 4) + 3; // Calculate the position
-                        self.bytecode.chunks[self.current_chunk]
-                            .patch_jump(sub_fail_jump, fail_target);
-                    }
-
-                    // Failure case: push false
-                    self.emit(Instruction::new(Opcode::PushFalse));
-
-                    // Patch success jump
-                    let end_target = self.bytecode.chunks[self.current_chunk].instructions.len();
-                    self.bytecode.chunks[self.current_chunk].patch_jump(success_jump, end_target);
-
-                    Ok((bindings, false))
                 }
+
+                let steps = PatternStep::for_constructor(name, patterns.len());
+                self.compile_pattern_parts_check(graph, patterns, steps, value_depth)?;
+                Ok(true)
             }
             Pattern::Guard { pattern, condition } => {
-                // First compile the inner pattern test
-                let (inner_bindings, always_matches) = self.compile_pattern_test(graph, pattern)?;
-
-                if !always_matches {
-                    // If the pattern doesn't always match, we already have a test result on stack
-                    // Need to check it before evaluating the guard
-                    let pattern_fail_jump =
-                        self.bytecode.chunks[self.current_chunk].instructions.len();
-                    self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)); // Will patch later
-
-                    // Pattern matched, now evaluate guard condition
-                    // Create temporary scope with pattern bindings for guard compilation
-                    self.locals.push(HashMap::new());
-                    self.captured.push(HashMap::new());
-                    self.scope_bases.push(self.stack_depth - 1); // Value is on stack
-                    self.cell_vars.push(HashSet::new());
-                    let scope_idx = self.locals.len() - 1;
-
-                    // Add pattern bindings to scope
-                    for (name, pos) in &inner_bindings {
-                        self.locals[scope_idx].insert(name.clone(), *pos);
-                    }
-
-                    // Compile guard condition with bindings in scope
-                    self.compile_node(graph, *condition)?;
-
-                    // Remove temporary scope
-                    self.locals.pop();
-                    self.captured.pop();
-                    self.scope_bases.pop();
-                    self.cell_vars.pop();
-
-                    // Jump to end
-                    let end_jump = self.bytecode.chunks[self.current_chunk].instructions.len();
-                    self.emit(Instruction::with_arg(Opcode::Jump, 0)); // Will patch later
-
-                    // Pattern failed, push false
-                    let pattern_fail_target =
-                        self.bytecode.chunks[self.current_chunk].instructions.len();
-                    self.bytecode.chunks[self.current_chunk]
-                        .patch_jump(pattern_fail_jump, pattern_fail_target);
-                    self.emit(Instruction::new(Opcode::PushFalse));
-
-                    // Patch end jump
-                    let end_target = self.bytecode.chunks[self.current_chunk].instructions.len();
-                    self.bytecode.chunks[self.current_chunk].patch_jump(end_jump, end_target);
+                let fail_jump = if self.compile_pattern_check(graph, pattern)? {
+                    Some(self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)))
                 } else {
-                    // Pattern always matches, just evaluate the guard
-                    // Create temporary scope with pattern bindings for guard compilation
-                    self.locals.push(HashMap::new());
-                    self.captured.push(HashMap::new());
-                    self.scope_bases.push(self.stack_depth - 1); // Value is on stack
-                    self.cell_vars.push(HashSet::new());
-                    let scope_idx = self.locals.len() - 1;
-
-                    // Add pattern bindings to scope
-                    for (name, pos) in &inner_bindings {
-                        self.locals[scope_idx].insert(name.clone(), *pos);
-                    }
-
-                    // Compile guard condition with bindings in scope
-                    self.compile_node(graph, *condition)?;
+                    None
+                };
 
-                    // Remove temporary scope
-                    self.locals.pop();
-                    self.captured.pop();
-                    self.scope_bases.pop();
-                    self.cell_vars.pop();
+                // Evaluate the condition with the pattern's bindings in scope
+                let bound = self.push_pattern_scope(graph, pattern, value_depth - 1)?;
+                self.compile_node(graph, *condition)?;
+                self.exit_scope();
+                if bound > 0 {
+                    self.emit(Instruction::with_arg(Opcode::PopN, bound as u32));
                 }
 
-                let bindings_mut = inner_bindings;
-                Ok((bindings_mut, false))
-            }
-            Pattern::As { binding, pattern } => {
-                // As-pattern: bind the value, then test the inner pattern
-                // The value is on top of stack
-                let value_pos = self.stack_depth - 1;
-
-                // Add the as-binding first
-                bindings.push((binding.clone(), value_pos));
-
-                // Compile the inner pattern
-                let (inner_bindings, always_matches) = self.compile_pattern_test(graph, pattern)?;
-
-                // Add any inner bindings
-                bindings.extend(inner_bindings);
-
-                Ok((bindings, always_matches))
+                if let Some(fail_jump) = fail_jump {
+                    let end_jump = self.emit(Instruction::with_arg(Opcode::Jump, 0));
+                    let fail = self.current_offset();
+                    self.patch_jump(fail_jump, fail);
+                    self.stack_depth = value_depth;
+                    self.emit(Instruction::new(Opcode::PushFalse));
+                    let end = self.current_offset();
+                    self.patch_jump(end_jump, end);
+                }
+                Ok(true)
             }
             Pattern::Or(patterns) => {
-                // Or-pattern: try each pattern in sequence until one matches
-                if patterns.is_empty() {
-                    // Empty or-pattern never matches
-                    self.emit(Instruction::new(Opcode::PushFalse));
-                    return Ok((bindings, false));
+                if patterns.iter().any(is_irrefutable) {
+                    return Ok(false);
                 }
 
+                // Try each alternative until one matches
                 let mut jumps_to_success = Vec::new();
-
-                for (i, pat) in patterns.iter().enumerate() {
-                    // The stack should have [value] at this point
-                    // compile_pattern_test expects the value on top of stack
-
-                    let (pat_bindings, always_matches) = self.compile_pattern_test(graph, pat)?;
-
-                    if always_matches {
-                        // This pattern always matches, no need to check others
-                        bindings.extend(pat_bindings);
-                        // Push true for the or-pattern result
-                        self.emit(Instruction::new(Opcode::PushTrue));
-                        return Ok((bindings, false));
-                    }
-
-                    // Pattern test left stack as: [value, bool]
-                    // We need to check the bool and potentially continue
-
-                    if i < patterns.len() - 1 {
-                        // Not the last pattern - need to preserve value for next iteration
-                        // Stack: [value, bool]
-                        // Duplicate the bool for the jump test
-                        self.emit(Instruction::new(Opcode::Dup)); // [value, bool, bool]
-
-                        // If true, jump to success
-                        let jump_to_success =
-                            self.bytecode.chunks[self.current_chunk].instructions.len();
-                        self.emit(Instruction::with_arg(Opcode::JumpIf, 0)); // Will patch later
-                        jumps_to_success.push(jump_to_success);
-                        // JumpIf consumed one bool, stack: [value, bool]
-
-                        // Test failed, pop the false result
-                        self.emit(Instruction::new(Opcode::Pop)); // [value]
-                                                                  // Ready for next iteration
-                    } else {
-                        // Last pattern - the bool result stays on stack
-                        // If true, jump to success
-                        let jump_to_success =
-                            self.bytecode.chunks[self.current_chunk].instructions.len();
-                        self.emit(Instruction::with_arg(Opcode::JumpIf, 0)); // Will patch later
-                        jumps_to_success.push(jump_to_success);
-                        // Stack after jump: [value] (JumpIf consumed the bool)
-                    }
-
-                    // Note: We can't use bindings from or-patterns as they may not all bind the same variables
+                for alternative in patterns {
+                    self.compile_pattern_check(graph, alternative)?;
+                    jumps_to_success.push(self.emit(Instruction::with_arg(Opcode::JumpIf, 0)));
                 }
+                self.emit(Instruction::new(Opcode::PushFalse));
+                let end_jump = self.emit(Instruction::with_arg(Opcode::Jump, 0));
 
-                // After all patterns tried and failed
-                // For the last pattern:
-                // - If it matched, we jumped to success
-                // - If it didn't match, the stack is [value] (JumpIf consumed the false)
-                // We need to push false as the or-pattern result
-                self.emit(Instruction::new(Opcode::PushFalse)); // [value, false]
-
-                // Jump to end to skip success case
-                let jump_to_end = self.bytecode.chunks[self.current_chunk].instructions.len();
-                self.emit(Instruction::with_arg(Opcode::Jump, 0)); // Will patch later
-
-                // Success target: when we jump here, stack is [value]
-                let success_target = self.bytecode.chunks[self.current_chunk].instructions.len();
+                let success = self.current_offset();
                 for jump in jumps_to_success {
-                    self.bytecode.chunks[self.current_chunk].patch_jump(jump, success_target);
+                    self.patch_jump(jump, success);
                 }
-                // Push true as the or-pattern result
-                self.emit(Instruction::new(Opcode::PushTrue)); // [value, true]
-
-                // Patch jump to end
-                let end_target = self.bytecode.chunks[self.current_chunk].instructions.len();
-                self.bytecode.chunks[self.current_chunk].patch_jump(jump_to_end, end_target);
+                self.stack_depth = value_depth;
+                self.emit(Instruction::new(Opcode::PushTrue));
 
-                // Stack now has: [value, bool] where bool is the or-pattern result
-                Ok((bindings, false))
+                let end = self.current_offset();
+                self.patch_jump(end_jump, end);
+                Ok(true)
             }
-            Pattern::Range(range) => {
-                // Range pattern: check if value is within range
+            Pattern::View { function, pattern } => {
+                if is_irrefutable(pattern) {
+                    return Ok(false);
+                }
 
-                // Duplicate value for comparison
+                // Match the inner pattern against the function's result
                 self.emit(Instruction::new(Opcode::Dup));
+                self.compile_pattern_step(graph, PatternStep::View(*function))?;
+                self.compile_pattern_check(graph, pattern)?;
+                self.emit(Instruction::new(Opcode::Swap));
+                self.emit(Instruction::new(Opcode::Pop));
+                Ok(true)
+            }
+        }
+    }
 
-                // Check lower bound
-                self.compile_literal(&range.start)?;
-                self.emit(Instruction::new(Opcode::Ge)); // value >= start
-
-                // Short-circuit if lower bound fails
-                let lower_fail_jump = self.bytecode.chunks[self.current_chunk].instructions.len();
-                self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)); // Will patch later
+    /// With the value and the result of its shape test on the stack, check each
+    /// part that can fail, giving up at the first mismatch
+    fn compile_pattern_parts_check(
+        &mut self,
+        graph: &ASTGraph,
+        patterns: &[Pattern],
+        steps: impl IntoIterator<Item = PatternStep>,
+        value_depth: usize,
+    ) -> Result<()> {
+        let parts: Vec<_> = patterns
+            .iter()
+            .zip(steps)
+            .filter(|(part, _)| !is_irrefutable(part))
+            .collect();
+        if parts.is_empty() {
+            return Ok(());
+        }
 
-                // Check upper bound
-                self.emit(Instruction::new(Opcode::Dup)); // Duplicate value again
-                self.compile_literal(&range.end)?;
-                if range.inclusive {
-                    self.emit(Instruction::new(Opcode::Le)); // value <= end
-                } else {
-                    self.emit(Instruction::new(Opcode::Lt)); // value < end
-                }
+        let mut fail_jumps = vec![self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0))];
+        for (part, step) in parts {
+            self.emit(Instruction::new(Opcode::Dup));
+            self.compile_pattern_step(graph, step)?;
+            self.compile_pattern_check(graph, part)?;
+            // Drop the part, keeping its result
+            self.emit(Instruction::new(Opcode::Swap));
+            self.emit(Instruction::new(Opcode::Pop));
+            fail_jumps.push(self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)));
+        }
+        self.emit(Instruction::new(Opcode::PushTrue));
+        let end_jump = self.emit(Instruction::with_arg(Opcode::Jump, 0));
 
-                // Jump to end
-                let end_jump = self.bytecode.chunks[self.current_chunk].instructions.len();
-                self.emit(Instruction::with_arg(Opcode::Jump, 0)); // Will patch later
+        let fail = self.current_offset();
+        for jump in fail_jumps {
+            self.patch_jump(jump, fail);
+        }
+        self.stack_depth = value_depth;
+        self.emit(Instruction::new(Opcode::PushFalse));
 
-                // Lower bound failed, push false
-                let lower_fail_target = self.bytecode.chunks[self.current_chunk].instructions.len();
-                self.bytecode.chunks[self.current_chunk]
-                    .patch_jump(lower_fail_jump, lower_fail_target);
-                self.emit(Instruction::new(Opcode::PushFalse));
+        let end = self.current_offset();
+        self.patch_jump(end_jump, end);
+        Ok(())
+    }
 
-                // Patch end jump
-                let end_target = self.bytecode.chunks[self.current_chunk].instructions.len();
-                self.bytecode.chunks[self.current_chunk].patch_jump(end_jump, end_target);
+    /// Replace the value on top of the stack with the part a step leads to
+    fn compile_pattern_step(&mut self, graph: &ASTGraph, step: PatternStep) -> Result<()> {
+        match step {
+            PatternStep::Head => {
+                self.emit(Instruction::new(Opcode::ListHead));
+            }
+            PatternStep::Tail => {
+                self.emit(Instruction::new(Opcode::ListTail));
+            }
+            PatternStep::Field(index) => {
+                self.emit(Instruction::with_arg(Opcode::GetTaggedField, index as u32));
+            }
+            PatternStep::Element(index) => {
+                self.emit(Instruction::with_arg(Opcode::TupleGet, index as u32));
+            }
+            PatternStep::View(function) => {
+                // Call takes the argument beneath the function
+                self.compile_node(graph, function)?;
+                self.emit(Instruction::with_arg(Opcode::Call, 1));
+            }
+        }
+        Ok(())
+    }
 
-                Ok((bindings, false))
+    /// Collect the stack slot of each name bound by a pattern that matched the
+    /// value at `slot`, pushing the parts of the value that `path` leads to.
+    fn compile_pattern_bindings(
+        &mut self,
+        graph: &ASTGraph,
+        pattern: &Pattern,
+        slot: usize,
+        path: &mut Vec<PatternStep>,
+        bindings: &mut Vec<(String, usize)>,
+    ) -> Result<()> {
+        match pattern {
+            Pattern::Variable(name) => {
+                let pos = self.compile_pattern_part(graph, slot, path)?;
+                bindings.push((name.clone(), pos));
+            }
+            Pattern::As { binding, pattern } => {
+                let pos = self.compile_pattern_part(graph, slot, path)?;
+                bindings.push((binding.clone(), pos));
+                self.compile_pattern_bindings(graph, pattern, slot, path, bindings)?;
+            }
+            Pattern::Guard { pattern, .. } => {
+                self.compile_pattern_bindings(graph, pattern, slot, path, bindings)?;
             }
             Pattern::View { function, pattern } => {
-                // View pattern: apply function then match
-                // Stack: [..., value]
-
-                // Duplicate the value for function application
-                self.emit(Instruction::new(Opcode::Dup)); // [..., value, value]
+                path.push(PatternStep::View(*function));
+                self.compile_pattern_bindings(graph, pattern, slot, path, bindings)?;
+                path.pop();
+            }
+            Pattern::Constructor { name, patterns } => {
+                let steps = PatternStep::for_constructor(name, patterns.len());
+                for (part, step) in patterns.iter().zip(steps) {
+                    path.push(step);
+                    self.compile_pattern_bindings(graph, part, slot, path, bindings)?;
+                    path.pop();
+                }
+            }
+            Pattern::Tuple(patterns) => {
+                for (i, part) in patterns.iter().enumerate() {
+                    path.push(PatternStep::Element(i));
+                    self.compile_pattern_bindings(graph, part, slot, path, bindings)?;
+                    path.pop();
+                }
+            }
+            Pattern::Or(patterns) => {
+                let names = pattern.bound_variables();
+                if !names.is_empty() {
+                    self.compile_or_pattern_bindings(graph, patterns, &names, slot, path, bindings)?;
+                }
+            }
+            Pattern::Wildcard | Pattern::Literal(_) | Pattern::Range(_) => {}
+        }
+        Ok(())
+    }
 
-                // Compile the function
-                self.compile_node(graph, *function)?; // [..., value, value, func]
+    /// Bind the names of an or-pattern from whichever alternative matched.
+    /// Each alternative pushes its parts in the order of `names`.
+    fn compile_or_pattern_bindings(
+        &mut self,
+        graph: &ASTGraph,
+        alternatives: &[Pattern],
+        names: &[String],
+        slot: usize,
+        path: &[PatternStep],
+        bindings: &mut Vec<(String, usize)>,
+    ) -> Result<()> {
+        let start_depth = self.stack_depth;
+        let mut jump_to_ends = Vec::new();
 
-                // Call expects: function on top, then arguments below
-                // Current stack: [..., original_value, dup_value, func]
-                // The Call instruction will:
-                // 1. Pop func
-                // 2. Pop dup_value as the argument
-                // 3. Push the result
-                // This leaves [..., original_value, result] which is what we want!
-                self.emit(Instruction::with_arg(Opcode::Call, 1)); // [..., value, result]
+        for (i, alternative) in alternatives.iter().enumerate() {
+            self.stack_depth = start_depth;
 
-                // Now match the result against the inner pattern
-                let (inner_bindings, always_matches) = self.compile_pattern_test(graph, pattern)?;
+            // The pattern as a whole matched, so the last alternative needs no test
+            let jump_to_next = if i + 1 < alternatives.len() {
+                self.load_slot(slot);
+                for &step in path {
+                    self.compile_pattern_step(graph, step)?;
+                }
+                if self.compile_pattern_check(graph, alternative)? {
+                    self.emit(Instruction::new(Opcode::Swap));
+                    self.emit(Instruction::new(Opcode::Pop));
+                    Some(self.emit(Instruction::with_arg(Opcode::JumpIfNot, 0)))
+                } else {
+                    self.emit(Instruction::new(Opcode::Pop));
+                    None
+                }
+            } else {
+                None
+            };
 
-                // If pattern test left a boolean on stack, we need to handle it
-                if !always_matches {
-                    // Stack: [..., value, bool]
-                    // Swap so value stays on top for potential binding
-                    self.emit(Instruction::new(Opcode::Swap)); // [..., bool, value]
-                    self.emit(Instruction::new(Opcode::Swap)); // [..., value, bool]
+            for name in names {
+                let mut name_path = path.to_vec();
+                name_path.extend(binding_path(alternative, name)?.ok_or_else(|| {
+                    anyhow!("Every alternative of an or-pattern must bind '{}'", name)
+                })?);
+                self.load_slot(slot);
+                for step in name_path {
+                    self.compile_pattern_step(graph, step)?;
                 }
+            }
 
-                bindings.extend(inner_bindings);
-                Ok((bindings, always_matches))
+            match jump_to_next {
+                Some(jump) => {
+                    jump_to_ends.push(self.emit(Instruction::with_arg(Opcode::Jump, 0)));
+                    let next = self.current_offset();
+                    self.patch_jump(jump, next);
+                }
+                None => break,
             }
         }
+
+        let end = self.current_offset();
+        for jump in jump_to_ends {
+            self.patch_jump(jump, end);
+        }
+        self.stack_depth = start_depth + names.len();
+        for (i, name) in names.iter().enumerate() {
+            bindings.push((name.clone(), start_depth + i));
+        }
+        Ok(())
+    }
+
+    /// Push the part of the value at `slot` that `path` leads to, returning its
+    /// slot. The value itself is used in place when the path is empty.
+    fn compile_pattern_part(
+        &mut self,
+        graph: &ASTGraph,
+        slot: usize,
+        path: &[PatternStep],
+    ) -> Result<usize> {
+        if path.is_empty() {
+            return Ok(slot);
+        }
+        self.load_slot(slot);
+        for &step in path {
+            self.compile_pattern_step(graph, step)?;
+        }
+        Ok(self.stack_depth - 1)
+    }
+
+    /// Push a copy of the value at a frame-relative stack slot
+    fn load_slot(&mut self, slot: usize) {
+        match slot {
+            0 => self.emit(Instruction::new(Opcode::LoadLocal0)),
+            1 => self.emit(Instruction::new(Opcode::LoadLocal1)),
+            2 => self.emit(Instruction::new(Opcode::LoadLocal2)),
+            3 => self.emit(Instruction::new(Opcode::LoadLocal3)),
+            _ => self.emit(Instruction::with_arg(Opcode::Load, slot as u32)),
+        };
     }
 
     // Module compilation methods
@@ -2690,20 +3468,8 @@ impl Compiler {
 
         // Compile handler functions
         for (effect_type, op_filter, handler_fn) in handlers {
-            // Push effect type - use the same case as VM expects
-            let effect_str = match effect_type {
-                fluentai_core::ast::EffectType::IO => "IO",
-                fluentai_core::ast::EffectType::State => "State",
-                fluentai_core::ast::EffectType::Error => "Error",
-                fluentai_core::ast::EffectType::Time => "Time",
-                fluentai_core::ast::EffectType::Network => "Network",
-                fluentai_core::ast::EffectType::Random => "Random",
-                fluentai_core::ast::EffectType::Dom => "Dom",
-                fluentai_core::ast::EffectType::Async => "Async",
-                fluentai_core::ast::EffectType::Concurrent => "Concurrent",
-                fluentai_core::ast::EffectType::Pure => "Pure",
-            }
-            .to_string();
+            // Push effect type by name; user-declared effects use their declared name
+            let effect_str = effect_type.to_string();
             let idx = self.add_constant(Value::String(effect_str));
             self.emit(Instruction::with_arg(Opcode::PushConst, idx));
             // emit() already updates stack_depth
@@ -2736,7 +3502,9 @@ impl Compiler {
         // emit() will handle the stack depth adjustment
 
         // Compile body - the body will produce a value on the stack
+        self.open_handlers.push(OpenHandler::Effect);
         self.compile_node(graph, body)?;
+        self.open_handlers.pop();
 
         // Uninstall handler (preserving the body's result)
         self.emit(Instruction::new(Opcode::UninstallHandler));
@@ -2802,6 +3570,17 @@ impl Compiler {
     /// # Errors
     /// Returns an error if scope_idx is out of bounds.
     #[inline]
+    /// Whether a name resolves to a cell, looked up in the scope that binds it
+    fn is_cell_binding(&self, name: &str) -> Result<bool> {
+        for (scope_idx, scope) in self.locals.iter().enumerate().rev() {
+            if scope.contains_key(name) {
+                return self.is_cell_var(scope_idx, name);
+            }
+        }
+        Ok(self.captured.iter().any(|scope| scope.contains_key(name))
+            && self.captured_cells.contains(name))
+    }
+
     fn is_cell_var(&self, scope_idx: usize, name: &str) -> Result<bool> {
         self.cell_vars.get(scope_idx)
             .map(|cells| cells.contains(name))
@@ -3210,18 +3989,34 @@ impl Compiler {
             
             // Add location mapping
             if let Some(source_map) = &mut chunk.source_map {
-                // For now, we don't have actual line/column info from AST nodes
-                // This would need to be added during parsing
-                // Use node ID's internal value as a placeholder for start/end positions
-                let node_id_value = node_id.0.get() as usize;
-                source_map.add_instruction_location(instruction_offset, SourceLocation {
-                    start: node_id_value,
-                    end: node_id_value,
-                    line: None,
-                    column: None,
-                });
+                if let Some(location) = self.node_locations.get(&node_id) {
+                    source_map.add_instruction_location(instruction_offset, *location);
+                }
                 source_map.add_instruction_node(instruction_offset, node_id);
             }
         }
     }
+
+    /// Resolve the source spans of the nodes to compile
+    ///
+    /// Spans come from the (possibly optimized) graph being compiled; line and
+    /// column numbers come from the line index of the parsed source.
+    fn collect_node_locations(&mut self, graph: &ASTGraph, source: &ASTGraph) {
+        let line_index = graph.line_index.as_ref().or(source.line_index.as_ref());
+        self.node_locations = graph
+            .metadata
+            .iter()
+            .filter_map(|(id, metadata)| {
+                let (start, end) = metadata.span?;
+                let location = match line_index {
+                    Some(index) => {
+                        let position = index.line_col(start);
+                        SourceLocation::with_line_col(start, end, position.line, position.column)
+                    }
+                    None => SourceLocation::new(start, end),
+                };
+                Some((*id, location))
+            })
+            .collect();
+    }
 }
@@ -478,6 +478,11 @@ impl ConcurrentGc {
                     self.scan_value(v);
                 }
             }
+            Value::Struct { fields, .. } => {
+                for (_, v) in fields {
+                    self.scan_value(v);
+                }
+            }
             Value::Module { exports, .. } => {
                 for (_, v) in exports {
                     self.scan_value(v);
@@ -537,6 +542,11 @@ impl ConcurrentGc {
                     self.scan_value_concurrent(v, gray_queue, guard);
                 }
             }
+            Value::Struct { fields, .. } => {
+                for (_, v) in fields {
+                    self.scan_value_concurrent(v, gray_queue, guard);
+                }
+            }
             Value::Module { exports, .. } => {
                 for (_, v) in exports {
                     self.scan_value_concurrent(v, gray_queue, guard);
@@ -690,9 +700,13 @@ impl ConcurrentGc {
             Value::Boolean(_) => 8,
             Value::Integer(_) => 16,
             Value::Float(_) => 16,
+            Value::BigInt(n) => 32 + (n.bits() as usize).div_ceil(8),
+            Value::Decimal(_) => 24,
             Value::String(s) => 24 + s.len(),
+            Value::Bytes(b) => 24 + b.len(),
             Value::Symbol(s) => 24 + s.len(),
             Value::List(items) => 24 + items.len() * 8,
+            Value::Tuple(items) => 24 + items.len() * 8,
             Value::Procedure(_) => 48, // Arc + fields
             Value::Vector(items) => 24 + items.len() * 8,
             Value::Map(m) => 32 + m.len() * 16,
@@ -703,6 +717,7 @@ impl ConcurrentGc {
             Value::Channel(_) => 16,
             Value::Cell(_) => 16,
             Value::Tagged { values, .. } => 32 + values.len() * 8,
+            Value::Struct { fields, .. } => 32 + fields.len() * 24,
             Value::Module { exports, .. } => 48 + exports.len() * 16,
             Value::GcHandle(_) => 16,
             Value::Actor(_) => 16, // Same as other ID-based values
@@ -392,9 +392,13 @@ pub fn value_type_name(value: &Value) -> &'static str {
         Value::Boolean(_) => "bool",
         Value::Integer(_) => "int",
         Value::Float(_) => "float",
+        Value::BigInt(_) => "bigint",
+        Value::Decimal(_) => "decimal",
         Value::String(_) => "string",
+        Value::Bytes(_) => "bytes",
         Value::Symbol(_) => "symbol",
         Value::List(_) => "list",
+        Value::Tuple(_) => "tuple",
         Value::Procedure(_) => "procedure",
         Value::Vector(_) => "vector",
         Value::Map(_) => "map",
@@ -405,6 +409,7 @@ pub fn value_type_name(value: &Value) -> &'static str {
         Value::Channel(_) => "channel",
         Value::Cell(_) => "cell",
         Value::Tagged { .. } => "tagged",
+        Value::Struct { .. } => "struct",
         Value::Module { .. } => "module",
         Value::GcHandle(_) => "gc-handle",
         Value::Actor(_) => "actor",
@@ -43,6 +43,8 @@ pub struct FreeVarAnalyzer {
     analyses: FxHashMap<NodeId, NodeAnalysis>,
     /// Counter for generating unique scope IDs
     next_scope_id: usize,
+    /// Names used as the receiver of a qualified reference (`name.field`)
+    qualified_receivers: FxHashSet<String>,
 }
 
 impl FreeVarAnalyzer {
@@ -50,6 +52,7 @@ impl FreeVarAnalyzer {
         Self {
             analyses: FxHashMap::default(),
             next_scope_id: 0,
+            qualified_receivers: FxHashSet::default(),
         }
     }
     
@@ -74,6 +77,13 @@ impl FreeVarAnalyzer {
             .unwrap_or(false)
     }
     
+    /// Check if a name was used as the receiver of a qualified reference.
+    /// Such a name is either a module or a local record, which only the
+    /// compiler can tell apart.
+    pub fn is_qualified_receiver(&self, var_name: &str) -> bool {
+        self.qualified_receivers.contains(var_name)
+    }
+    
     /// Get the free variables for a lambda node
     pub fn get_lambda_free_vars(&self, node_id: NodeId) -> Vec<String> {
         self.analyses.get(&node_id)
@@ -242,7 +252,7 @@ impl FreeVarAnalyzer {
                 self.merge_analysis(&mut analysis, else_analysis);
             }
             
-            Node::Match { expr, branches } => {
+            Node::Match { expr, branches, .. } => {
                 // Analyze match expression
                 let expr_analysis = self.analyze_node(graph, *expr, scope_id, bound_vars)?;
                 self.merge_analysis(&mut analysis, expr_analysis);
@@ -257,7 +267,40 @@ impl FreeVarAnalyzer {
                 }
             }
             
-            Node::List(items) => {
+            Node::While { condition, body } => {
+                let cond_analysis = self.analyze_node(graph, *condition, scope_id, bound_vars)?;
+                self.merge_analysis(&mut analysis, cond_analysis);
+                
+                let body_analysis = self.analyze_node(graph, *body, scope_id, bound_vars)?;
+                self.merge_analysis(&mut analysis, body_analysis);
+            }
+            
+            Node::For { pattern, iterable, body } => {
+                let iter_analysis = self.analyze_node(graph, *iterable, scope_id, bound_vars)?;
+                self.merge_analysis(&mut analysis, iter_analysis);
+                
+                // The pattern binds each element in the body
+                let mut loop_bound = bound_vars.clone();
+                self.collect_pattern_bindings(pattern, &mut loop_bound);
+                
+                let body_analysis = self.analyze_node(graph, *body, scope_id, &loop_bound)?;
+                self.merge_analysis(&mut analysis, body_analysis);
+            }
+            
+            Node::Range { start, end, .. } => {
+                let start_analysis = self.analyze_node(graph, *start, scope_id, bound_vars)?;
+                self.merge_analysis(&mut analysis, start_analysis);
+                
+                let end_analysis = self.analyze_node(graph, *end, scope_id, bound_vars)?;
+                self.merge_analysis(&mut analysis, end_analysis);
+            }
+            
+            Node::Break { value: Some(value) } => {
+                let value_analysis = self.analyze_node(graph, *value, scope_id, bound_vars)?;
+                self.merge_analysis(&mut analysis, value_analysis);
+            }
+            
+            Node::List(items) | Node::Parallel { branches: items } => {
                 for &item in items {
                     let item_analysis = self.analyze_node(graph, item, scope_id, bound_vars)?;
                     self.merge_analysis(&mut analysis, item_analysis);
@@ -293,6 +336,55 @@ impl FreeVarAnalyzer {
                 }
             }
             
+            Node::QualifiedVariable { module_name, .. } => {
+                if !bound_vars.contains(module_name) {
+                    self.qualified_receivers.insert(module_name.clone());
+                    let info = analysis.var_usage.entry(module_name.clone()).or_insert(VarInfo {
+                        is_free: true,
+                        is_captured: false,
+                        usage_scopes: FxHashSet::default(),
+                        reference_count: 0,
+                    });
+                    info.usage_scopes.insert(scope_id);
+                    info.reference_count += 1;
+                }
+            }
+            
+            Node::StructConstruct { fields, .. } => {
+                for (_, value) in fields {
+                    let value_analysis = self.analyze_node(graph, *value, scope_id, bound_vars)?;
+                    self.merge_analysis(&mut analysis, value_analysis);
+                }
+            }
+            
+            Node::FieldAccess { object, .. } => {
+                let object_analysis = self.analyze_node(graph, *object, scope_id, bound_vars)?;
+                self.merge_analysis(&mut analysis, object_analysis);
+            }
+            
+            Node::StructUpdate { base, fields, .. } => {
+                let base_analysis = self.analyze_node(graph, *base, scope_id, bound_vars)?;
+                self.merge_analysis(&mut analysis, base_analysis);
+                for (_, value) in fields {
+                    let value_analysis = self.analyze_node(graph, *value, scope_id, bound_vars)?;
+                    self.merge_analysis(&mut analysis, value_analysis);
+                }
+            }
+            
+            Node::TraitImpl { methods, .. } => {
+                for (_, method) in methods {
+                    let method_analysis = self.analyze_node(graph, *method, scope_id, bound_vars)?;
+                    self.merge_analysis(&mut analysis, method_analysis);
+                }
+            }
+            
+            Node::MethodCall { args, .. } => {
+                for &arg in args {
+                    let arg_analysis = self.analyze_node(graph, arg, scope_id, bound_vars)?;
+                    self.merge_analysis(&mut analysis, arg_analysis);
+                }
+            }
+            
             // Literals and other leaf nodes have no variables
             Node::Literal(_) | Node::Channel { .. } => {}
             
@@ -334,7 +426,7 @@ impl FreeVarAnalyzer {
                 bindings.insert(binding.clone());
                 self.collect_pattern_bindings(pattern, bindings);
             }
-            Pattern::Constructor { patterns, .. } => {
+            Pattern::Constructor { patterns, .. } | Pattern::Tuple(patterns) => {
                 for p in patterns {
                     self.collect_pattern_bindings(p, bindings);
                 }
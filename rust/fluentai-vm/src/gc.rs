@@ -346,6 +346,11 @@ impl GarbageCollector {
                     self.mark_value_children(v)?;
                 }
             }
+            Value::Struct { fields, .. } => {
+                for (_, v) in fields {
+                    self.mark_value_children(v)?;
+                }
+            }
             Value::Module { exports, .. } => {
                 for (_, v) in exports {
                     self.mark_value_children(v)?;
@@ -3,14 +3,19 @@
 //! This module provides integration between the VM and the JIT compiler,
 //! automatically compiling frequently executed functions to native code.
 
-use fluentai_bytecode::Bytecode;
+use fluentai_bytecode::{Bytecode, Instruction};
 use crate::error::{VMError, VMResult};
-use fluentai_core::ast::{NodeId, UsageStatistics};
+use crate::vm::{VMState, VM};
+use fluentai_core::ast::UsageStatistics;
 use fluentai_core::value::Value;
 #[cfg(feature = "jit")]
-use fluentai_jit::JitCompiler;
+use fluentai_jit::{
+    feedback::{profiled_operands, TypeFeedback},
+    runtime::JitCallback,
+    CompiledFunction, JitCompiler,
+};
 use rustc_hash::FxHashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, PoisonError};
 
 /// Threshold for JIT compilation
 pub struct JitConfig {
@@ -20,6 +25,8 @@ pub struct JitConfig {
     pub time_threshold: u64,
     /// Maximum number of JIT-compiled functions to keep in memory
     pub max_compiled_functions: usize,
+    /// Number of deoptimizations after which a function stays in the interpreter
+    pub max_deoptimizations: u32,
     /// Enable JIT compilation
     pub enabled: bool,
 }
@@ -30,6 +37,7 @@ impl Default for JitConfig {
             call_threshold: 50,  // Compile after 50 calls
             time_threshold: 1_000_000,  // Or after 1ms total execution time
             max_compiled_functions: 1000,
+            max_deoptimizations: 10,
             enabled: cfg!(target_arch = "x86_64"),  // Only enable on x86_64
         }
     }
@@ -37,12 +45,18 @@ impl Default for JitConfig {
 
 /// Manages JIT compilation for the VM
 pub struct JitManager {
-    /// JIT compiler instance
-    compiler: Option<JitCompiler>,
+    /// JIT compiler instance, locked so that the VM can be shared between threads
+    compiler: Option<Mutex<JitCompiler>>,
     /// Configuration
     config: JitConfig,
     /// Map from chunk_id to whether it's been JIT compiled
     compiled_chunks: FxHashMap<usize, bool>,
+    /// Operand types seen by the interpreter, used to specialize compiled code
+    feedback: TypeFeedback,
+    /// Number of times each chunk's compiled code has deoptimized
+    deoptimizations: FxHashMap<usize, u32>,
+    /// Code compiled ahead of time, used instead of the JIT compiler's
+    native_functions: FxHashMap<usize, CompiledFunction>,
     /// Statistics about JIT compilation
     stats: JitStats,
 }
@@ -53,12 +67,14 @@ pub struct JitStats {
     pub compilation_failures: usize,
     pub total_compilation_time_ms: f64,
     pub jit_execution_count: u64,
+    /// Number of times compiled code fell back to the interpreter
+    pub deoptimization_count: u64,
 }
 
 impl JitManager {
     pub fn new(config: JitConfig) -> Self {
         let compiler = if config.enabled {
-            JitCompiler::new().ok()
+            JitCompiler::new().ok().map(Mutex::new)
         } else {
             None
         };
@@ -67,6 +83,9 @@ impl JitManager {
             compiler,
             config,
             compiled_chunks: FxHashMap::default(),
+            feedback: TypeFeedback::new(),
+            deoptimizations: FxHashMap::default(),
+            native_functions: FxHashMap::default(),
             stats: JitStats::default(),
         }
     }
@@ -83,8 +102,9 @@ impl JitManager {
         }
         
         // Check if it meets the thresholds
-        stats.call_count >= self.config.call_threshold ||
-        stats.total_time >= self.config.time_threshold
+        let total_time = stats.execution_count * stats.avg_execution_time_ns;
+        stats.execution_count >= self.config.call_threshold ||
+        total_time >= self.config.time_threshold
     }
     
     /// Attempt to JIT compile a chunk
@@ -99,9 +119,9 @@ impl JitManager {
         
         let start_time = std::time::Instant::now();
         
-        match self.compiler.as_mut() {
+        match self.compiler.as_mut().map(unlocked) {
             Some(compiler) => {
-                match compiler.compile(bytecode, chunk_id) {
+                match compiler.compile_with_feedback(bytecode, chunk_id, &self.feedback) {
                     Ok(_) => {
                         self.compiled_chunks.insert(chunk_id, true);
                         self.stats.functions_compiled += 1;
@@ -123,12 +143,17 @@ impl JitManager {
         }
     }
     
-    /// Execute a JIT-compiled function if available
-    pub fn execute_if_compiled(
-        &mut self,
-        chunk_id: usize,
-        bytecode: &Arc<Bytecode>,
-    ) -> Option<VMResult<Value>> {
+    /// Get a JIT-compiled function taking `arg_count` arguments if one is available,
+    /// counting it as a JIT execution
+    pub fn compiled_function(&mut self, chunk_id: usize, arg_count: usize) -> Option<CompiledFunction> {
+        if let Some(func) = self.native_functions.get(&chunk_id) {
+            if func.arity != arg_count {
+                return None;
+            }
+            self.stats.jit_execution_count += 1;
+            return Some(func.clone());
+        }
+        
         if !self.config.enabled {
             return None;
         }
@@ -138,27 +163,57 @@ impl JitManager {
             return None;
         }
         
-        match self.compiler.as_mut() {
-            Some(compiler) => {
-                // Get the compiled function from cache
-                match compiler.compile(bytecode, chunk_id) {
-                    Ok(compiled_func) => {
-                        // Execute the JIT-compiled function
-                        match compiler.execute(compiled_func) {
-                            Ok(result) => {
-                                self.stats.jit_execution_count += 1;
-                                Some(Ok(result))
-                            }
-                            Err(e) => Some(Err(VMError::Runtime {
-                                message: format!("JIT execution error: {}", e),
-                                stack_trace: None,
-                            })),
-                        }
-                    }
-                    Err(_) => None, // Fall back to interpreter
-                }
+        let func = unlocked(self.compiler.as_mut()?).get_compiled(chunk_id)?;
+        if func.arity != arg_count {
+            return None;
+        }
+        let func = func.clone();
+        self.stats.jit_execution_count += 1;
+        Some(func)
+    }
+    
+    /// Use code compiled ahead of time for a chunk instead of compiling it
+    pub fn register_native(&mut self, chunk_id: usize, func: CompiledFunction) {
+        self.compiled_chunks.insert(chunk_id, true);
+        self.native_functions.insert(chunk_id, func);
+    }
+    
+    /// Check if a chunk has code compiled ahead of time
+    pub fn has_native(&self, chunk_id: usize) -> bool {
+        self.native_functions.contains_key(&chunk_id)
+    }
+    
+    /// Record the operand types of an instruction about to be interpreted
+    ///
+    /// `stack` is the VM stack; the instruction's operands are on top of it.
+    pub fn record_operands(&mut self, chunk_id: usize, ip: usize, instruction: &Instruction, stack: &[Value]) {
+        if !self.is_enabled() {
+            return;
+        }
+        if let Some(count) = profiled_operands(instruction.opcode) {
+            if let Some(start) = stack.len().checked_sub(count) {
+                self.feedback.record(chunk_id, ip, &stack[start..]);
             }
-            None => None,
+        }
+    }
+    
+    /// Discard a chunk's compiled code after a failed type guard
+    ///
+    /// The chunk is compiled again with the updated feedback once it is hot, unless it
+    /// has deoptimized too often, in which case it stays in the interpreter.
+    pub fn deoptimized(&mut self, chunk_id: usize) {
+        self.stats.deoptimization_count += 1;
+        self.native_functions.remove(&chunk_id);
+        let count = self.deoptimizations.entry(chunk_id).or_insert(0);
+        *count += 1;
+        
+        if *count >= self.config.max_deoptimizations {
+            self.compiled_chunks.insert(chunk_id, false);
+        } else {
+            self.compiled_chunks.remove(&chunk_id);
+        }
+        if let Some(compiler) = &mut self.compiler {
+            unlocked(compiler).invalidate(chunk_id);
         }
     }
     
@@ -170,7 +225,7 @@ impl JitManager {
     /// Clear the JIT cache
     pub fn clear_cache(&mut self) {
         if let Some(compiler) = &mut self.compiler {
-            compiler.clear_cache();
+            unlocked(compiler).clear_cache();
         }
         self.compiled_chunks.clear();
     }
@@ -179,4 +234,94 @@ impl JitManager {
     pub fn is_enabled(&self) -> bool {
         self.config.enabled && self.compiler.is_some()
     }
-}
\ No newline at end of file
+}
+
+/// Get the compiler out of its lock, which `&mut` access makes unnecessary to take
+fn unlocked(compiler: &mut Mutex<JitCompiler>) -> &mut JitCompiler {
+    compiler.get_mut().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Lets JIT-compiled code call bytecode functions on the VM that is running it
+pub struct VMJitBridge<'a> {
+    vm: &'a mut VM,
+}
+
+impl<'a> VMJitBridge<'a> {
+    /// Create a bridge to the given VM
+    pub fn new(vm: &'a mut VM) -> Self {
+        Self { vm }
+    }
+}
+
+impl JitCallback for VMJitBridge<'_> {
+    fn call_function(&mut self, func: &Value, args: &[Value]) -> anyhow::Result<Value> {
+        // Compiled callees, including recursive calls, stay in native code
+        if let Value::Function { chunk_id, env } = func {
+            if let Some(result) = self.vm.try_jit_execute(*chunk_id, args, env)? {
+                return Ok(result);
+            }
+        }
+
+        // Push function and arguments to stack
+        self.vm.push(func.clone())?;
+        for arg in args {
+            self.vm.push(arg.clone())?;
+        }
+
+        // Run the call to completion; it may re-enter JIT code
+        self.vm.call_value(args.len())?;
+
+        Ok(self.vm.pop()?)
+    }
+
+    fn execute_instruction(
+        &mut self,
+        chunk_id: usize,
+        instruction: &Instruction,
+        operands: &[Value],
+    ) -> anyhow::Result<Value> {
+        let base = self.vm.stack_len();
+        for operand in operands {
+            self.vm.push(operand.clone())?;
+        }
+
+        let state = self.vm.execute_instruction(instruction, chunk_id)?;
+        if !matches!(state, VMState::Continue) {
+            return Err(VMError::RuntimeError {
+                message: format!("{:?} cannot run from JIT code", instruction.opcode),
+                stack_trace: None,
+            }
+            .into());
+        }
+
+        // The result is the top value the instruction left behind, if any
+        let result = if self.vm.stack_len() > base {
+            self.vm.pop()?
+        } else {
+            Value::Nil
+        };
+        while self.vm.stack_len() > base {
+            self.vm.pop()?;
+        }
+        Ok(result)
+    }
+
+    fn constant(&mut self, chunk_id: usize, index: usize) -> anyhow::Result<Value> {
+        Ok(self.vm.get_constant(chunk_id, index)?.clone())
+    }
+
+    fn is_truthy(&mut self, value: &Value) -> bool {
+        self.vm.is_truthy(value)
+    }
+}
+
+/// Recover the VM error behind a failed JIT call, if there was one
+pub(crate) fn jit_error(error: anyhow::Error) -> VMError {
+    match error.downcast::<VMError>() {
+        Ok(e) => e,
+        Err(e) => VMError::RuntimeError {
+            message: format!("JIT execution error: {}", e),
+            stack_trace: None,
+        },
+    }
+}
@@ -28,13 +28,14 @@ pub mod stdlib_bridge;
 pub mod typed_stack;
 pub mod unboxed;
 pub mod usage_tracker;
+pub mod verifier;
 pub mod vm;
 pub mod vm_builder;
 pub mod async_vm;
 pub mod cow_globals;
 
 pub use builder::{VMBuilder as VMBuilderLegacy, VMConfig};
-pub use fluentai_bytecode::{Bytecode, BytecodeChunk, Instruction, Opcode};
+pub use fluentai_bytecode::{Bytecode, BytecodeChunk, ExternDecl, Instruction, Opcode};
 pub use compiler::{Compiler, CompilerOptions};
 pub use concurrent::{BoundedQueue, LockFreeQueue, LockFreeStack, WorkStealingDeque};
 pub use concurrent_gc::{ConcurrentGc, ConcurrentGcConfig};
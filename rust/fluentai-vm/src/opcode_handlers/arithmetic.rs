@@ -4,9 +4,31 @@ use fluentai_bytecode::{Instruction, Opcode};
 use crate::error::{value_type_name, VMError, VMResult};
 use crate::safety::checked_ops;
 use crate::vm::{VM, VMState};
-use fluentai_core::value::Value;
+use fluentai_core::value::numeric::{self, ArithOp};
+use fluentai_core::value::{Value, ValueError};
 use super::OpcodeHandler;
 
+/// Map an exact-arithmetic failure onto the VM's error type
+fn numeric_error(operation: &str, err: ValueError) -> VMError {
+    match err {
+        ValueError::DivisionByZero => VMError::DivisionByZero {
+            location: None,
+            stack_trace: None,
+        },
+        err => VMError::ArithmeticError {
+            operation: operation.to_string(),
+            message: err.to_string(),
+        },
+    }
+}
+
+/// Generic arithmetic on two integers, big integers or decimals.
+///
+/// Unlike the `*Int` opcodes, `i64` overflow promotes to a big integer.
+fn exact_op(op: ArithOp, operation: &str, a: &Value, b: &Value) -> VMResult<Value> {
+    numeric::arith(op, a, b).map_err(|e| numeric_error(operation, e))
+}
+
 pub struct ArithmeticHandler;
 
 impl OpcodeHandler for ArithmeticHandler {
@@ -16,18 +38,14 @@ impl OpcodeHandler for ArithmeticHandler {
         match instruction.opcode {
             // Basic arithmetic
             Add => vm.binary_op(|a, b| match (a, b) {
-                (Value::Integer(x), Value::Integer(y)) => checked_ops::add_i64(x, y)
-                    .map(Value::Integer)
-                    .map_err(|_| VMError::IntegerOverflow {
-                        operation: "add".to_string(),
-                        operands: (x, y),
-                        stack_trace: None,
-                    }),
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    exact_op(ArithOp::Add, "add", &a, &b)
+                }
                 (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x + y)),
                 (Value::String(x), Value::String(y)) => Ok(Value::String(x + &y)),
                 (a, b) => Err(VMError::TypeError {
                     operation: "add".to_string(),
-                    expected: "int/float/string".to_string(),
+                    expected: "number/string".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -35,17 +53,13 @@ impl OpcodeHandler for ArithmeticHandler {
             })?,
             
             Sub => vm.binary_op(|a, b| match (a, b) {
-                (Value::Integer(x), Value::Integer(y)) => checked_ops::sub_i64(x, y)
-                    .map(Value::Integer)
-                    .map_err(|_| VMError::IntegerOverflow {
-                        operation: "sub".to_string(),
-                        operands: (x, y),
-                        stack_trace: None,
-                    }),
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    exact_op(ArithOp::Sub, "sub", &a, &b)
+                }
                 (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x - y)),
                 (a, b) => Err(VMError::TypeError {
                     operation: "sub".to_string(),
-                    expected: "int/float".to_string(),
+                    expected: "number".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -53,17 +67,13 @@ impl OpcodeHandler for ArithmeticHandler {
             })?,
             
             Mul => vm.binary_op(|a, b| match (a, b) {
-                (Value::Integer(x), Value::Integer(y)) => checked_ops::mul_i64(x, y)
-                    .map(Value::Integer)
-                    .map_err(|_| VMError::IntegerOverflow {
-                        operation: "mul".to_string(),
-                        operands: (x, y),
-                        stack_trace: None,
-                    }),
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    exact_op(ArithOp::Mul, "mul", &a, &b)
+                }
                 (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x * y)),
                 (a, b) => Err(VMError::TypeError {
                     operation: "mul".to_string(),
-                    expected: "int/float".to_string(),
+                    expected: "number".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -71,26 +81,13 @@ impl OpcodeHandler for ArithmeticHandler {
             })?,
             
             Div => vm.binary_op(|a, b| match (a, b) {
-                (Value::Integer(x), Value::Integer(y)) => {
-                    checked_ops::div_i64(x, y).map(Value::Integer).map_err(|_| {
-                        if y == 0 {
-                            VMError::DivisionByZero {
-                                location: None,
-                                stack_trace: None,
-                            }
-                        } else {
-                            VMError::IntegerOverflow {
-                                operation: "div".to_string(),
-                                operands: (x, y),
-                                stack_trace: None,
-                            }
-                        }
-                    })
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    exact_op(ArithOp::Div, "div", &a, &b)
                 }
                 (Value::Float(x), Value::Float(y)) => Ok(Value::Float(x / y)),
                 (a, b) => Err(VMError::TypeError {
                     operation: "div".to_string(),
-                    expected: "int/float".to_string(),
+                    expected: "number".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -98,25 +95,12 @@ impl OpcodeHandler for ArithmeticHandler {
             })?,
             
             Mod => vm.binary_op(|a, b| match (a, b) {
-                (Value::Integer(x), Value::Integer(y)) => {
-                    checked_ops::mod_i64(x, y).map(Value::Integer).map_err(|_| {
-                        if y == 0 {
-                            VMError::DivisionByZero {
-                                location: None,
-                                stack_trace: None,
-                            }
-                        } else {
-                            VMError::IntegerOverflow {
-                                operation: "mod".to_string(),
-                                operands: (x, y),
-                                stack_trace: None,
-                            }
-                        }
-                    })
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    exact_op(ArithOp::Rem, "mod", &a, &b)
                 }
                 (a, b) => Err(VMError::TypeError {
                     operation: "mod".to_string(),
-                    expected: "int".to_string(),
+                    expected: "int/bigint/decimal".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -126,20 +110,15 @@ impl OpcodeHandler for ArithmeticHandler {
             Neg => {
                 let value = vm.pop()?;
                 match value {
-                    Value::Integer(x) => {
-                        let negated =
-                            checked_ops::neg_i64(x).map_err(|_| VMError::IntegerOverflow {
-                                operation: "neg".to_string(),
-                                operands: (x, 0),
-                                stack_trace: None,
-                            })?;
-                        vm.push(Value::Integer(negated))?
+                    v if v.is_exact_number() => {
+                        let negated = numeric::negate(&v).map_err(|e| numeric_error("neg", e))?;
+                        vm.push(negated)?
                     }
                     Value::Float(x) => vm.push(Value::Float(-x))?,
                     v => {
                         return Err(VMError::TypeError {
                             operation: "neg".to_string(),
-                            expected: "int/float".to_string(),
+                            expected: "number".to_string(),
                             got: value_type_name(&v).to_string(),
                             location: None,
                             stack_trace: None,
@@ -148,7 +127,7 @@ impl OpcodeHandler for ArithmeticHandler {
                 }
             }
             
-            // Type-specialized arithmetic
+            // Type-specialized arithmetic: fixed-width, overflow is an error
             AddInt => vm.binary_int_op(|x, y| {
                 checked_ops::add_i64(x, y).map_err(|_| VMError::IntegerOverflow {
                     operation: "add_int".to_string(),
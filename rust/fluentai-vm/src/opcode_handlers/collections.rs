@@ -1,4 +1,4 @@
-//! Collection operations handler (Lists and Maps)
+//! Collection operations handler (Lists, Tuples, Maps and Structs)
 
 use fluentai_bytecode::{Instruction, Opcode};
 use crate::error::{VMError, VMResult};
@@ -6,10 +6,14 @@ use crate::vm::{VM, VMState};
 use fluentai_core::value::Value;
 use super::OpcodeHandler;
 
+/// Bit masks for MakeStruct instruction unpacking (must match compiler)
+const MAKESTRUCT_LAYOUT_SHIFT: u32 = 16;
+const MAKESTRUCT_FIELD_COUNT_MASK: u32 = 0xFFFF;
+
 pub struct CollectionsHandler;
 
 impl OpcodeHandler for CollectionsHandler {
-    fn execute(&mut self, vm: &mut VM, instruction: &Instruction, _chunk_id: usize) -> VMResult<VMState> {
+    fn execute(&mut self, vm: &mut VM, instruction: &Instruction, chunk_id: usize) -> VMResult<VMState> {
         use Opcode::*;
         
         match instruction.opcode {
@@ -257,6 +261,137 @@ impl OpcodeHandler for CollectionsHandler {
                 }
             }
             
+            // Tuple operations
+            MakeTuple => {
+                let count = instruction.arg as usize;
+                let mut items = Vec::with_capacity(count);
+                for _ in 0..count {
+                    items.push(vm.pop()?);
+                }
+                items.reverse();
+                vm.push(Value::Tuple(items))?;
+            }
+            
+            TupleGet => {
+                let index = instruction.arg as usize;
+                let tuple = vm.pop()?;
+                
+                match &tuple {
+                    Value::Tuple(items) => match items.get(index) {
+                        Some(item) => vm.push(item.clone())?,
+                        None => {
+                            return Err(VMError::RuntimeError {
+                                message: format!("Tuple index out of bounds: {} (arity: {})", index, items.len()),
+                                stack_trace: None,
+                            });
+                        }
+                    },
+                    _ => {
+                        return Err(VMError::TypeError {
+                            operation: "tuple_get".to_string(),
+                            expected: "tuple".to_string(),
+                            got: vm.value_type_name(&tuple).to_string(),
+                            location: None,
+                            stack_trace: None,
+                        });
+                    }
+                }
+            }
+            
+            IsTuple => {
+                let arity = instruction.arg as usize;
+                let is_match = matches!(vm.peek(0)?, Value::Tuple(items) if items.len() == arity);
+                vm.push(Value::Boolean(is_match))?;
+            }
+            
+            // Struct operations
+            MakeStruct => {
+                // Upper 16 bits: constant index of the layout list [name, field1, field2, ...]
+                // Lower 16 bits: number of field values on the stack
+                let layout_idx = (instruction.arg >> MAKESTRUCT_LAYOUT_SHIFT) as usize;
+                let field_count = (instruction.arg & MAKESTRUCT_FIELD_COUNT_MASK) as usize;
+                let layout = match vm.get_constant(chunk_id, layout_idx)? {
+                    Value::List(items) if items.len() == field_count + 1 => items.clone(),
+                    _ => {
+                        return Err(VMError::InvalidConstantIndex {
+                            index: layout_idx as u32,
+                            max_index: vm.bytecode().chunks[chunk_id].constants.len(),
+                            stack_trace: None,
+                        });
+                    }
+                };
+                
+                let mut values = Vec::with_capacity(field_count);
+                for _ in 0..field_count {
+                    values.push(vm.pop()?);
+                }
+                values.reverse();
+                
+                let mut names = layout.into_iter().map(|v| match v {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                });
+                let name = names.next().unwrap_or_default();
+                let fields = names.zip(values).collect();
+                vm.push(Value::Struct { name, fields })?;
+            }
+            
+            GetField => {
+                let field = vm.get_constant_string_at(chunk_id, instruction.arg as usize)?;
+                let object = vm.pop()?;
+                
+                match &object {
+                    Value::Struct { .. } => {
+                        let value = object.get_field(&field).map_err(|e| VMError::RuntimeError {
+                            message: e.to_string(),
+                            stack_trace: None,
+                        })?;
+                        vm.push(value.clone())?;
+                    }
+                    Value::Map(m) => {
+                        vm.push(m.get(&field).cloned().unwrap_or(Value::Nil))?;
+                    }
+                    _ => {
+                        return Err(VMError::TypeError {
+                            operation: format!("get_field .{}", field),
+                            expected: "struct or map".to_string(),
+                            got: vm.value_type_name(&object).to_string(),
+                            location: None,
+                            stack_trace: None,
+                        });
+                    }
+                }
+            }
+            
+            SetField => {
+                let field = vm.get_constant_string_at(chunk_id, instruction.arg as usize)?;
+                let value = vm.pop()?;
+                let object = vm.pop()?;
+                
+                match object {
+                    Value::Struct { .. } => {
+                        let updated = object.with_field(&field, value).map_err(|e| VMError::RuntimeError {
+                            message: e.to_string(),
+                            stack_trace: None,
+                        })?;
+                        vm.push(updated)?;
+                    }
+                    Value::Map(mut m) => {
+                        m.insert(field, value);
+                        vm.push(Value::Map(m))?;
+                    }
+                    object => {
+                        return Err(VMError::TypeError {
+                            operation: format!("set_field .{}", field),
+                            expected: "struct or map".to_string(),
+                            got: vm.value_type_name(&object).to_string(),
+                            location: None,
+                            stack_trace: None,
+                        });
+                    }
+                }
+            }
+            
             _ => unreachable!("CollectionsHandler received non-collection opcode"),
         }
         
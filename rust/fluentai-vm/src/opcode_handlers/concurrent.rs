@@ -277,6 +277,17 @@ impl OpcodeHandler for ConcurrentHandler {
                 vm.push(Value::Nil)?;
             }
             
+            Parallel => {
+                let count = instruction.arg as usize;
+                let mut branches = Vec::with_capacity(count);
+                for _ in 0..count {
+                    branches.push(vm.pop()?);
+                }
+                branches.reverse();
+                let results = vm.run_parallel(branches)?;
+                vm.push(results)?;
+            }
+            
             _ => unreachable!("ConcurrentHandler received non-concurrent opcode"),
         }
         
@@ -2,11 +2,15 @@
 
 use fluentai_bytecode::{Instruction, Opcode};
 use crate::error::{VMError, VMResult};
-use crate::vm::{VM, VMState, CallFrame};
+use crate::vm::{method_receiver_type, VM, VMState, CallFrame};
 use fluentai_core::value::Value;
 use std::time::Instant;
 use super::OpcodeHandler;
 
+/// Bit unpacking for DefineMethod and CallMethod (must match compiler)
+const METHOD_NAME_SHIFT: u32 = 16;
+const METHOD_LOW_MASK: u32 = 0xFFFF;
+
 pub struct ControlFlowHandler;
 
 impl OpcodeHandler for ControlFlowHandler {
@@ -49,10 +53,10 @@ impl OpcodeHandler for ControlFlowHandler {
                 
                 match &func {
                     Value::Function { chunk_id, env } => {
-                        // Try JIT compilation if conditions are met
-                        if vm.should_jit_compile(*chunk_id) && env.is_empty() {
-                            // Attempt JIT execution for functions without captures
-                            if let Some(result) = vm.try_jit_execute(*chunk_id)? {
+                        // Run code compiled ahead of time, or try JIT compilation if conditions are met
+                        if vm.has_native_code(*chunk_id) || vm.should_jit_compile(*chunk_id) {
+                            // Closures pass their captured values as the environment
+                            if let Some(result) = vm.try_jit_execute(*chunk_id, &args, env)? {
                                 vm.push(result)?;
                                 return Ok(VMState::Continue);
                             }
@@ -106,6 +110,75 @@ impl OpcodeHandler for ControlFlowHandler {
                 }
             }
             
+            // Register a trait method implementation for a type
+            DefineMethod => {
+                // The type is a name, or for an enum a list [enum name, variant tags...]
+                let type_idx = (instruction.arg >> METHOD_NAME_SHIFT) as usize;
+                let type_name = match vm.get_constant(_chunk_id, type_idx)? {
+                    Value::String(name) => name.clone(),
+                    Value::List(layout) if !layout.is_empty() => {
+                        let mut names = layout.iter().map(|v| match v {
+                            Value::String(s) => s.clone(),
+                            other => other.to_string(),
+                        });
+                        let enum_name = names.next().unwrap_or_default();
+                        vm.define_enum_variants(&enum_name, names.collect());
+                        enum_name
+                    }
+                    _ => {
+                        return Err(VMError::InvalidConstantIndex {
+                            index: type_idx as u32,
+                            max_index: vm.bytecode().chunks[_chunk_id].constants.len(),
+                            stack_trace: None,
+                        });
+                    }
+                };
+                let method = vm.get_constant_string_at(
+                    _chunk_id,
+                    (instruction.arg & METHOD_LOW_MASK) as usize,
+                )?;
+                let func = vm.pop()?;
+                vm.define_method(type_name, method, func);
+            }
+            
+            // Trait method call: dispatch on the runtime type of the receiver
+            CallMethod => {
+                let method = vm.get_constant_string_at(
+                    _chunk_id,
+                    (instruction.arg >> METHOD_NAME_SHIFT) as usize,
+                )?;
+                let arg_count = (instruction.arg & METHOD_LOW_MASK) as usize;
+                if arg_count == 0 {
+                    return Err(VMError::RuntimeError {
+                        message: format!("Method '{}' called without a receiver", method),
+                        stack_trace: None,
+                    });
+                }
+                let receiver = vm.peek(arg_count - 1)?.clone();
+                
+                // Fall back to a plain function of the same name
+                let func = match vm.lookup_method(&receiver, &method) {
+                    Some(func) => func.clone(),
+                    None => match vm.get_global(&method) {
+                        Some(func) => func.clone(),
+                        None => {
+                            return Err(VMError::RuntimeError {
+                                message: format!(
+                                    "No implementation of method '{}' for type {}",
+                                    method,
+                                    method_receiver_type(&receiver)
+                                ),
+                                stack_trace: None,
+                            })
+                        }
+                    },
+                };
+                
+                vm.push(func)?;
+                let call = Instruction::with_arg(Call, arg_count as u32);
+                return self.execute(vm, &call, _chunk_id);
+            }
+            
             // Tail call optimization
             TailCall => {
                 let arg_count = instruction.arg as usize;
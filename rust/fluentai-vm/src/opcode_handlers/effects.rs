@@ -1,7 +1,7 @@
 //! Effect and handler operations
 
 use fluentai_bytecode::{Instruction, Opcode};
-use crate::error::{VMError, VMResult};
+use crate::error::{value_type_name, VMError, VMResult};
 use crate::vm::{VM, VMState};
 use fluentai_core::value::Value;
 use super::OpcodeHandler;
@@ -9,61 +9,46 @@ use super::OpcodeHandler;
 pub struct EffectsHandler;
 
 impl OpcodeHandler for EffectsHandler {
-    fn execute(&mut self, vm: &mut VM, instruction: &Instruction, chunk_id: usize) -> VMResult<VMState> {
+    fn execute(&mut self, vm: &mut VM, instruction: &Instruction, _chunk_id: usize) -> VMResult<VMState> {
         use Opcode::*;
         
         match instruction.opcode {
-            // Perform an effect
-            Effect => {
-                let operation_idx = instruction.arg as usize;
-                let operation = vm.get_constant_string_at(chunk_id, operation_idx)?;
-                
-                // Handle the effect
-                vm.perform_effect(operation)?;
-            }
-            
-            EffectAsync => {
-                let operation_idx = instruction.arg as usize;
-                let operation = vm.get_constant_string_at(chunk_id, operation_idx)?;
+            // Perform an effect: pops the effect type, operation and `arg` arguments
+            Effect | EffectAsync | Perform => {
+                let arg_count = instruction.arg as usize;
+                let mut args = Vec::with_capacity(arg_count);
+                for _ in 0..arg_count {
+                    args.push(vm.pop()?);
+                }
+                args.reverse();
                 
-                // Handle the async effect (simplified for now)
-                vm.perform_effect(operation)?;
-            }
-            
-            Perform => {
-                let operation_idx = instruction.arg as usize;
-                let operation = vm.get_constant_string_at(chunk_id, operation_idx)?;
+                let operation = pop_string(vm, "effect", "string for operation name")?;
+                let effect = pop_string(vm, "effect", "string for effect type")?;
                 
-                // Handle the effect
-                vm.perform_effect(operation)?;
+                let result = vm.perform_effect(effect, operation, args)?;
+                vm.push(result)?;
             }
             
-            // Create effect handler
+            // Create effect handler table from `arg` (effect type, operation filter, function) triples
             MakeHandler => {
                 let handler_count = instruction.arg as usize;
                 let mut handlers = Vec::with_capacity(handler_count);
                 
                 // Pop handlers in reverse order
                 for _ in 0..handler_count {
-                    handlers.push(vm.pop()?);
+                    let handler_fn = vm.pop()?;
+                    let op_filter = vm.pop()?;
+                    let effect_type = vm.pop()?;
+                    handlers.push(Value::List(vec![effect_type, op_filter, handler_fn]));
                 }
                 handlers.reverse();
                 
-                // For now, just push a placeholder
-                vm.push(Value::Nil)?;
+                vm.push(Value::List(handlers))?;
             }
             
-            // Install effect handler
+            // Install effect handler table
             InstallHandler => {
-                let handler_count = instruction.arg as usize;
-                let mut handlers = Vec::with_capacity(handler_count);
-                
-                // Pop handlers in reverse order
-                for _ in 0..handler_count {
-                    handlers.push(vm.pop()?);
-                }
-                handlers.reverse();
-                
+                let handlers = vm.pop()?;
                 vm.install_effect_handlers(handlers)?;
             }
             
@@ -151,4 +136,18 @@ impl OpcodeHandler for EffectsHandler {
         
         Ok(VMState::Continue)
     }
-}
\ No newline at end of file
+}
+
+/// Pop a string operand of an effect instruction
+fn pop_string(vm: &mut VM, operation: &str, expected: &str) -> VMResult<String> {
+    match vm.pop()? {
+        Value::String(s) => Ok(s),
+        v => Err(VMError::TypeError {
+            operation: operation.to_string(),
+            expected: expected.to_string(),
+            got: value_type_name(&v).to_string(),
+            location: None,
+            stack_trace: None,
+        }),
+    }
+}
@@ -3,7 +3,7 @@
 use fluentai_bytecode::{Instruction, Opcode};
 use crate::error::{value_type_name, VMError, VMResult};
 use crate::vm::{VM, VMState};
-use fluentai_core::value::Value;
+use fluentai_core::value::{numeric, Value};
 use super::OpcodeHandler;
 
 pub struct LogicalHandler;
@@ -20,9 +20,12 @@ impl OpcodeHandler for LogicalHandler {
             Lt => vm.binary_op(|a, b| match (a, b) {
                 (Value::Integer(x), Value::Integer(y)) => Ok(Value::Boolean(x < y)),
                 (Value::Float(x), Value::Float(y)) => Ok(Value::Boolean(x < y)),
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    Ok(Value::Boolean(numeric::compare(&a, &b).is_some_and(|o| o.is_lt())))
+                }
                 (a, b) => Err(VMError::TypeError {
                     operation: "lt".to_string(),
-                    expected: "int/float".to_string(),
+                    expected: "number".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -32,9 +35,12 @@ impl OpcodeHandler for LogicalHandler {
             Le => vm.binary_op(|a, b| match (a, b) {
                 (Value::Integer(x), Value::Integer(y)) => Ok(Value::Boolean(x <= y)),
                 (Value::Float(x), Value::Float(y)) => Ok(Value::Boolean(x <= y)),
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    Ok(Value::Boolean(numeric::compare(&a, &b).is_some_and(|o| o.is_le())))
+                }
                 (a, b) => Err(VMError::TypeError {
                     operation: "le".to_string(),
-                    expected: "int/float".to_string(),
+                    expected: "number".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -44,9 +50,12 @@ impl OpcodeHandler for LogicalHandler {
             Gt => vm.binary_op(|a, b| match (a, b) {
                 (Value::Integer(x), Value::Integer(y)) => Ok(Value::Boolean(x > y)),
                 (Value::Float(x), Value::Float(y)) => Ok(Value::Boolean(x > y)),
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    Ok(Value::Boolean(numeric::compare(&a, &b).is_some_and(|o| o.is_gt())))
+                }
                 (a, b) => Err(VMError::TypeError {
                     operation: "gt".to_string(),
-                    expected: "int/float".to_string(),
+                    expected: "number".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -56,9 +65,12 @@ impl OpcodeHandler for LogicalHandler {
             Ge => vm.binary_op(|a, b| match (a, b) {
                 (Value::Integer(x), Value::Integer(y)) => Ok(Value::Boolean(x >= y)),
                 (Value::Float(x), Value::Float(y)) => Ok(Value::Boolean(x >= y)),
+                (a, b) if a.is_exact_number() && b.is_exact_number() => {
+                    Ok(Value::Boolean(numeric::compare(&a, &b).is_some_and(|o| o.is_ge())))
+                }
                 (a, b) => Err(VMError::TypeError {
                     operation: "ge".to_string(),
-                    expected: "int/float".to_string(),
+                    expected: "number".to_string(),
                     got: format!("{} and {}", value_type_name(&a), value_type_name(&b)),
                     location: None,
                     stack_trace: None,
@@ -31,6 +31,8 @@ pub enum Capability {
     UnlimitedMemory,
     /// Can import modules
     ModuleImport { modules: Vec<String> },
+    /// Can bind the named host functions through `extern` declarations
+    HostFunction { names: Vec<String> },
     /// Can perform unsafe operations
     Unsafe,
     /// Can access global mutable state
@@ -315,6 +317,21 @@ impl SecurityContext {
         Ok(())
     }
 
+    /// Check if an `extern` declaration may bind the named host function
+    pub fn check_extern(&self, name: &str) -> Result<()> {
+        let cap = Capability::HostFunction {
+            names: vec![name.to_string()],
+        };
+        let wildcard = Capability::HostFunction {
+            names: vec!["*".to_string()],
+        };
+        if !self.has_capability(&cap) && !self.has_capability(&wildcard) {
+            return Err(anyhow!("No capability to call host function '{}'", name));
+        }
+
+        Ok(())
+    }
+
     /// Terminate execution
     pub fn terminate(&self) {
         self.terminated.store(true, Ordering::Relaxed);
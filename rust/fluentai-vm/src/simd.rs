@@ -7,6 +7,8 @@ use anyhow::{anyhow, Result};
 use fluentai_core::value::Value;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use std::arch::x86_64::*;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use std::mem;
 
 /// SIMD operations for numeric arrays
 pub struct SimdOps;
@@ -8,6 +8,12 @@ use fluentai_bytecode::{Instruction, Opcode};
 /// Bit masks for MakeClosure instruction unpacking (must match compiler and VM)
 const MAKECLOSURE_CAPTURE_COUNT_MASK: u32 = 0xFFFF;
 
+/// Bit mask for MakeStruct instruction unpacking (must match compiler and VM)
+const MAKESTRUCT_FIELD_COUNT_MASK: u32 = 0xFFFF;
+
+/// Bit mask for CallMethod instruction unpacking (must match compiler and VM)
+const CALLMETHOD_ARG_COUNT_MASK: u32 = 0xFFFF;
+
 /// Describes how an instruction affects the stack
 #[derive(Debug, Clone, Copy)]
 pub struct StackEffect {
@@ -59,17 +65,19 @@ pub fn stack_effect(instruction: &Instruction) -> StackEffect {
         
         // Load operations
         Load | LoadGlobal | LoadCaptured | LoadLocal0 
-        | LoadLocal1 | LoadLocal2 | LoadLocal3 => StackEffect::new(0, 1),
+        | LoadLocal1 | LoadLocal2 | LoadLocal3 
+        | LoadLocal | LoadUpvalue => StackEffect::new(0, 1),
         
         // Store operations
         Store => StackEffect::new(1, 0),
-        StoreGlobal => StackEffect::new(1, 0),
+        StoreGlobal | DefineGlobal => StackEffect::new(1, 0),
         UpdateLocal => StackEffect::new(1, 0),
+        StoreLocal | StoreUpvalue => StackEffect::new(1, 1), // Stores the top value without popping it
         
         // Binary operations (consume 2, produce 1)
         Add | Sub | Mul | Div | Mod 
         | Eq | Ne | Lt | Le | Gt | Ge 
-        | And | Or | StrConcat | ListCons => StackEffect::new(2, 1),
+        | And | Or | StrConcat | ListCons | ListGet => StackEffect::new(2, 1),
         
         // Unary operations (consume 1, produce 1)
         Not | Neg | ListHead | ListTail | ListEmpty | ListLen => StackEffect::new(1, 1),
@@ -103,17 +111,38 @@ pub fn stack_effect(instruction: &Instruction) -> StackEffect {
             StackEffect::new(count, 1)
         }
         
+        ListSet => StackEffect::new(3, 1), // Consumes list, index and value, produces list
+        
+        // Map operations
+        MakeMap => {
+            let count = instruction.arg as usize;
+            StackEffect::new(2 * count, 1) // Consumes a key and a value per entry
+        }
+        MapGet => StackEffect::new(2, 1), // Consumes map and key, produces value
+        MapSet => StackEffect::new(3, 1), // Consumes map, key and value, produces map
+        
+        // Tuple operations
+        MakeTuple => {
+            let count = instruction.arg as usize;
+            StackEffect::new(count, 1)
+        }
+        TupleGet => StackEffect::new(1, 1), // Consumes tuple, produces element
+        IsTuple => StackEffect::new(1, 2), // Produces value and boolean
+        
         // Tagged values
         MakeTagged => {
             let count = instruction.arg as usize;
             StackEffect::new(count + 1, 1) // +1 for the tag
         }
         IsTagged => StackEffect::new(1, 2), // Produces value and boolean
+        MatchError => StackEffect::new(1, 0), // Consumes the unmatched value and raises
         
         // Cell operations
         MakeCell => StackEffect::new(1, 1), // Consumes value, produces cell
         CellGet => StackEffect::new(1, 1), // Consumes cell, produces value
         CellSet => StackEffect::new(2, 1), // Consumes cell and value, produces nil
+        LoadCell => StackEffect::new(1, 1), // Consumes cell, produces value
+        StoreCell => StackEffect::new(2, 0), // Consumes cell and value
         
         // GC operations
         GcAlloc => StackEffect::new(1, 1), // Consumes value, produces handle
@@ -122,11 +151,12 @@ pub fn stack_effect(instruction: &Instruction) -> StackEffect {
         GcCollect => StackEffect::new(0, 1), // No args, produces nil
         
         // Control flow
-        Jump | JumpIf => StackEffect::new(0, 0), // No stack effect
-        JumpIfNot => StackEffect::new(1, 0), // Consumes condition
+        Jump => StackEffect::new(0, 0), // No stack effect
+        JumpIf | JumpIfNot => StackEffect::new(1, 0), // Consumes condition
         
         // Channel operations
-        Channel => StackEffect::new(0, 1), // Creates channel
+        Channel | MakeChannel => StackEffect::new(0, 1), // Creates channel
+        ChannelWithCapacity => StackEffect::new(1, 1), // Consumes capacity, produces channel
         Send => StackEffect::new(2, 1), // Consumes channel and value, produces nil
         Receive => StackEffect::new(1, 1), // Consumes channel, produces value
         TrySend => StackEffect::new(2, 1), // Consumes channel and value, produces bool
@@ -140,6 +170,7 @@ pub fn stack_effect(instruction: &Instruction) -> StackEffect {
         }
         
         // Actor operations
+        MakeActor => StackEffect::new(2, 1), // Consumes state and handler, produces actor
         ActorSend => StackEffect::new(2, 1), // Consumes actor and message, produces nil
         
         // Handler operations
@@ -164,14 +195,18 @@ pub fn stack_effect(instruction: &Instruction) -> StackEffect {
         Become => StackEffect::new(1, 1), // Consumes new state, produces nil
         
         // Effect operations
-        Effect => {
+        Effect | Perform => {
             // Consumes effect type, operation, and N args; produces result
             let arg_count = instruction.arg as usize;
             StackEffect::new(2 + arg_count, 1)
         }
         
+        Resume => StackEffect::new(1, 0), // Consumes the resumption value
+        
         // Additional operations
         MakeFuture => StackEffect::new(1, 1), // Consumes function, produces future
+        Spawn => StackEffect::new(1, 1), // Consumes function, produces promise
+        Await => StackEffect::new(1, 1), // Consumes promise, produces result
         
         // Qualified name loading
         LoadQualified => StackEffect::new(0, 1), // Pushes value
@@ -194,6 +229,23 @@ pub fn stack_effect(instruction: &Instruction) -> StackEffect {
         GetTag => StackEffect::new(1, 1), // Consumes tagged value, produces tag
         GetTaggedField => StackEffect::new(1, 1), // Consumes tagged value, produces field
         
+        // Struct operations
+        MakeStruct => {
+            // MakeStruct consumes one value per field and produces 1 struct
+            let field_count = (instruction.arg & MAKESTRUCT_FIELD_COUNT_MASK) as usize;
+            StackEffect::new(field_count, 1)
+        }
+        GetField => StackEffect::new(1, 1), // Consumes struct, produces field value
+        SetField => StackEffect::new(2, 1), // Consumes struct and value, produces updated struct
+        
+        // Trait operations
+        DefineMethod => StackEffect::new(1, 0), // Consumes method implementation
+        CallMethod => {
+            // Like Call, but the function is resolved from the receiver (first arg)
+            let arg_count = (instruction.arg & CALLMETHOD_ARG_COUNT_MASK) as usize;
+            StackEffect::new(arg_count, 1)
+        }
+        
         // Module operations
         LoadModule => StackEffect::new(0, 1), // Pushes module
         ImportBinding => StackEffect::new(1, 0), // Consumes module name
@@ -209,6 +261,10 @@ pub fn stack_effect(instruction: &Instruction) -> StackEffect {
         PromiseAll => StackEffect::new(1, 1), // Consumes list, produces promise
         PromiseRace => StackEffect::new(1, 1), // Consumes list, produces promise
         WithTimeout => StackEffect::new(2, 1), // Consumes promise and timeout, produces promise
+        Parallel => {
+            let count = instruction.arg as usize;
+            StackEffect::new(count, 1) // Consumes branch closures, produces result list
+        }
         
         // Actor model operations
         CreateActor => StackEffect::new(2, 1), // Consumes state and handler, produces actor
@@ -219,18 +275,12 @@ pub fn stack_effect(instruction: &Instruction) -> StackEffect {
         
         // Error handling
         Try | Catch | Finally | EndFinally => StackEffect::new(0, 0), // Control flow
+        TryStart | TryStartWithFinally | TryEnd 
+        | FinallyStart | FinallyEnd => StackEffect::new(0, 0), // Control flow
         PushFinally => StackEffect::new(0, 0), // No immediate stack effect
         
         // Special
         Nop => StackEffect::new(0, 0), // No operation
-        
-        // Catch-all for any missing opcodes (should not happen)
-        _ => {
-            panic!(
-                "Unknown opcode in stack_effect: {:?}. This is a bug - all opcodes should have defined stack effects.",
-                instruction.opcode
-            );
-        }
     }
 }
 
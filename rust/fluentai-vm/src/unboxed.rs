@@ -1,7 +1,7 @@
 //! Unboxed value representation for high-performance numeric operations
 
 use crate::safety::{ChannelId, PromiseId};
-use fluentai_core::value::Value;
+use fluentai_core::value::{format_bytes, BigInt, Decimal, Value};
 use rustc_hash::FxHashMap;
 use std::fmt;
 
@@ -30,8 +30,12 @@ pub enum UnboxedValue {
 #[derive(Debug, Clone)]
 pub enum BoxedValue {
     String(String),
+    Bytes(Vec<u8>),
+    BigInt(BigInt),
+    Decimal(Decimal),
     Symbol(String),
     List(Vec<UnboxedValue>),
+    Tuple(Vec<UnboxedValue>),
     Closure {
         chunk_id: usize,
         captured_env: Vec<UnboxedValue>,
@@ -53,6 +57,10 @@ pub enum BoxedValue {
         tag: String,
         values: Vec<UnboxedValue>,
     },
+    Struct {
+        name: String,
+        fields: Vec<(String, UnboxedValue)>,
+    },
     Module {
         name: String,
         exports: FxHashMap<String, UnboxedValue>,
@@ -77,11 +85,18 @@ impl UnboxedValue {
             Value::Integer(i) => UnboxedValue::Int(i),
             Value::Float(f) => UnboxedValue::Float(f),
             Value::String(s) => UnboxedValue::Boxed(Box::new(BoxedValue::String(s))),
+            Value::Bytes(b) => UnboxedValue::Boxed(Box::new(BoxedValue::Bytes(b))),
+            Value::BigInt(n) => UnboxedValue::Boxed(Box::new(BoxedValue::BigInt(n))),
+            Value::Decimal(d) => UnboxedValue::Boxed(Box::new(BoxedValue::Decimal(d))),
             Value::Symbol(s) => UnboxedValue::Boxed(Box::new(BoxedValue::Symbol(s))),
             Value::List(items) => {
                 let unboxed_items = items.into_iter().map(UnboxedValue::from_value).collect();
                 UnboxedValue::Boxed(Box::new(BoxedValue::List(unboxed_items)))
             }
+            Value::Tuple(items) => {
+                let unboxed_items = items.into_iter().map(UnboxedValue::from_value).collect();
+                UnboxedValue::Boxed(Box::new(BoxedValue::Tuple(unboxed_items)))
+            }
             Value::Procedure(_) => {
                 // Procedures are not yet supported in unboxed representation
                 UnboxedValue::Boxed(Box::new(BoxedValue::String("<procedure>".to_string())))
@@ -128,6 +143,16 @@ impl UnboxedValue {
                     values: unboxed_values,
                 }))
             }
+            Value::Struct { name, fields } => {
+                let unboxed_fields = fields
+                    .into_iter()
+                    .map(|(k, v)| (k, UnboxedValue::from_value(v)))
+                    .collect();
+                UnboxedValue::Boxed(Box::new(BoxedValue::Struct {
+                    name,
+                    fields: unboxed_fields,
+                }))
+            }
             Value::Module { name, exports } => {
                 let unboxed_exports = exports
                     .into_iter()
@@ -160,11 +185,18 @@ impl UnboxedValue {
             UnboxedValue::Float(f) => Value::Float(f),
             UnboxedValue::Boxed(boxed) => match *boxed {
                 BoxedValue::String(s) => Value::String(s),
+                BoxedValue::Bytes(b) => Value::Bytes(b),
+                BoxedValue::BigInt(n) => Value::BigInt(n),
+                BoxedValue::Decimal(d) => Value::Decimal(d),
                 BoxedValue::Symbol(s) => Value::Symbol(s),
                 BoxedValue::List(items) => {
                     let values = items.into_iter().map(|v| v.to_value()).collect();
                     Value::List(values)
                 }
+                BoxedValue::Tuple(items) => {
+                    let values = items.into_iter().map(|v| v.to_value()).collect();
+                    Value::Tuple(values)
+                }
                 BoxedValue::Closure {
                     chunk_id,
                     captured_env,
@@ -187,6 +219,10 @@ impl UnboxedValue {
                     let vals = values.into_iter().map(|v| v.to_value()).collect();
                     Value::Tagged { tag, values: vals }
                 }
+                BoxedValue::Struct { name, fields } => {
+                    let fields = fields.into_iter().map(|(k, v)| (k, v.to_value())).collect();
+                    Value::Struct { name, fields }
+                }
                 BoxedValue::Module { name, exports } => {
                     let exps = exports
                         .into_iter()
@@ -227,6 +263,9 @@ impl fmt::Display for UnboxedValue {
             UnboxedValue::Float(fl) => write!(f, "{}", fl),
             UnboxedValue::Boxed(boxed) => match &**boxed {
                 BoxedValue::String(s) => write!(f, "\"{}\"", s),
+                BoxedValue::Bytes(b) => write!(f, "{}", format_bytes(b)),
+                BoxedValue::BigInt(n) => write!(f, "{}", n),
+                BoxedValue::Decimal(d) => write!(f, "{}", d),
                 BoxedValue::Symbol(s) => write!(f, "{}", s),
                 BoxedValue::List(items) => {
                     write!(f, "[")?;
@@ -238,6 +277,19 @@ impl fmt::Display for UnboxedValue {
                     }
                     write!(f, "]")
                 }
+                BoxedValue::Tuple(items) => {
+                    write!(f, "(")?;
+                    for (i, item) in items.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ", ")?;
+                        }
+                        write!(f, "{}", item)?;
+                    }
+                    if items.len() == 1 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, ")")
+                }
                 BoxedValue::Closure {
                     chunk_id,
                     param_count,
@@ -259,6 +311,16 @@ impl fmt::Display for UnboxedValue {
                     }
                     write!(f, ")")
                 }
+                BoxedValue::Struct { name, fields } => {
+                    write!(f, "{} {{", name)?;
+                    for (i, (field, value)) in fields.iter().enumerate() {
+                        if i > 0 {
+                            write!(f, ",")?;
+                        }
+                        write!(f, " {}: {}", field, value)?;
+                    }
+                    write!(f, " }}")
+                }
                 BoxedValue::Module { name, exports } => {
                     write!(f, "<module {} with {} exports>", name, exports.len())
                 }
@@ -0,0 +1,253 @@
+//! Bytecode verification
+//!
+//! Bytecode that was not produced by the compiler in the same process, such as a
+//! module loaded from a `.fabc` file, is checked before the VM runs it. The
+//! verifier rejects jumps outside their chunk, references to missing constants
+//! or chunks, method calls without a receiver, and instruction sequences that
+//! pop more values than the stack holds. Stack depths follow every control-flow path using the effects from
+//! [`crate::stack_effect`], and paths that meet at an instruction must agree on
+//! the depth there.
+
+use crate::stack_effect::stack_effect;
+use fluentai_bytecode::{Bytecode, BytecodeChunk, Instruction, Opcode};
+use fluentai_core::value::Value;
+use thiserror::Error;
+
+/// Bit unpacking for instructions with two 16-bit operands (must match compiler and VM)
+const HIGH_SHIFT: u32 = 16;
+const LOW_MASK: u32 = 0xFFFF;
+
+/// A reason bytecode was rejected by [`verify`]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum VerifyError {
+    /// The entry chunk does not exist
+    #[error("main chunk {main} does not exist ({chunks} chunks)")]
+    MissingMainChunk { main: usize, chunks: usize },
+
+    /// A jump or handler target lies outside the chunk
+    #[error("chunk {chunk}, instruction {offset}: jump target {target} is outside the chunk ({len} instructions)")]
+    InvalidJumpTarget {
+        chunk: usize,
+        offset: usize,
+        target: usize,
+        len: usize,
+    },
+
+    /// An operand refers to a constant the chunk does not have
+    #[error("chunk {chunk}, instruction {offset}: constant index {index} is out of range ({len} constants)")]
+    InvalidConstantIndex {
+        chunk: usize,
+        offset: usize,
+        index: usize,
+        len: usize,
+    },
+
+    /// An operand or function constant refers to a chunk that does not exist
+    #[error("chunk {chunk}, instruction {offset}: reference to missing chunk {target}")]
+    InvalidChunkReference {
+        chunk: usize,
+        offset: usize,
+        target: usize,
+    },
+
+    /// A method call has no receiver to resolve the method from
+    #[error("chunk {chunk}, instruction {offset}: CallMethod passes no receiver")]
+    MissingReceiver { chunk: usize, offset: usize },
+
+    /// An instruction pops more values than the stack holds
+    #[error("chunk {chunk}, instruction {offset}: {opcode:?} needs {needed} stack values but only {depth} are available")]
+    StackUnderflow {
+        chunk: usize,
+        offset: usize,
+        opcode: Opcode,
+        needed: usize,
+        depth: usize,
+    },
+
+    /// Two paths reach an instruction with different stack depths
+    #[error("chunk {chunk}, instruction {offset}: reached with stack depth {expected} on one path and {found} on another")]
+    InconsistentStackDepth {
+        chunk: usize,
+        offset: usize,
+        expected: usize,
+        found: usize,
+    },
+
+    /// Execution can continue past the last instruction of a chunk
+    #[error("chunk {chunk}: execution runs past the last instruction")]
+    MissingReturn { chunk: usize },
+}
+
+/// Check that `bytecode` is safe to hand to the VM
+pub fn verify(bytecode: &Bytecode) -> Result<(), VerifyError> {
+    if bytecode.main_chunk >= bytecode.chunks.len() {
+        return Err(VerifyError::MissingMainChunk {
+            main: bytecode.main_chunk,
+            chunks: bytecode.chunks.len(),
+        });
+    }
+
+    for (chunk_id, chunk) in bytecode.chunks.iter().enumerate() {
+        for (offset, instruction) in chunk.instructions.iter().enumerate() {
+            check_operands(bytecode, chunk_id, chunk, offset, instruction)?;
+        }
+        for (offset, constant) in chunk.constants.iter().enumerate() {
+            if let Value::Function {
+                chunk_id: target, ..
+            } = constant
+            {
+                if *target >= bytecode.chunks.len() {
+                    return Err(VerifyError::InvalidChunkReference {
+                        chunk: chunk_id,
+                        offset,
+                        target: *target,
+                    });
+                }
+            }
+        }
+        check_stack(chunk_id, chunk)?;
+    }
+    Ok(())
+}
+
+/// Check the jump targets, constant indices and chunk references in an instruction's operand
+fn check_operands(
+    bytecode: &Bytecode,
+    chunk_id: usize,
+    chunk: &BytecodeChunk,
+    offset: usize,
+    instruction: &Instruction,
+) -> Result<(), VerifyError> {
+    use Opcode::*;
+
+    let arg = instruction.arg as usize;
+    let high = (instruction.arg >> HIGH_SHIFT) as usize;
+    let low = (instruction.arg & LOW_MASK) as usize;
+
+    let jump = |target: usize| {
+        if target < chunk.instructions.len() {
+            Ok(())
+        } else {
+            Err(VerifyError::InvalidJumpTarget {
+                chunk: chunk_id,
+                offset,
+                target,
+                len: chunk.instructions.len(),
+            })
+        }
+    };
+    let constant = |index: usize| {
+        if index < chunk.constants.len() {
+            Ok(())
+        } else {
+            Err(VerifyError::InvalidConstantIndex {
+                chunk: chunk_id,
+                offset,
+                index,
+                len: chunk.constants.len(),
+            })
+        }
+    };
+    let function = |target: usize| {
+        if target < bytecode.chunks.len() {
+            Ok(())
+        } else {
+            Err(VerifyError::InvalidChunkReference {
+                chunk: chunk_id,
+                offset,
+                target,
+            })
+        }
+    };
+
+    match instruction.opcode {
+        Jump | JumpIf | JumpIfNot | LoopEnd | Try | TryStart | PushHandler | PushFinally => {
+            jump(arg)
+        }
+        TryStartWithFinally => jump(high).and(jump(low)),
+        Push | PushConst | LoadGlobal | StoreGlobal | DefineGlobal | GetField | SetField
+        | LoadModule | ImportAll | BeginModule | ExportBinding => constant(arg),
+        MakeStruct => constant(high),
+        // The receiver is the first argument, so a method call passes at least one
+        CallMethod if low == 0 => Err(VerifyError::MissingReceiver {
+            chunk: chunk_id,
+            offset,
+        }),
+        CallMethod => constant(high),
+        DefineMethod | ImportBinding | LoadQualified => constant(high).and(constant(low)),
+        MakeFunc => function(arg),
+        MakeClosure => function(high),
+        _ => Ok(()),
+    }
+}
+
+/// Instructions after which control never reaches the next instruction
+fn ends_flow(opcode: Opcode) -> bool {
+    matches!(
+        opcode,
+        Opcode::Jump
+            | Opcode::LoopEnd
+            | Opcode::Return
+            | Opcode::TailReturn
+            | Opcode::Throw
+            | Opcode::MatchError
+            | Opcode::Halt
+    )
+}
+
+/// Follow every path through the chunk, checking stack depths
+fn check_stack(chunk_id: usize, chunk: &BytecodeChunk) -> Result<(), VerifyError> {
+    let len = chunk.instructions.len();
+    let mut depths: Vec<Option<usize>> = vec![None; len];
+    // A called chunk starts with its arguments on the stack
+    let mut pending = vec![(0, chunk.arity)];
+
+    while let Some((offset, depth)) = pending.pop() {
+        if offset >= len {
+            return Err(VerifyError::MissingReturn { chunk: chunk_id });
+        }
+        match depths[offset] {
+            Some(expected) if expected == depth => continue,
+            Some(expected) => {
+                return Err(VerifyError::InconsistentStackDepth {
+                    chunk: chunk_id,
+                    offset,
+                    expected,
+                    found: depth,
+                })
+            }
+            None => depths[offset] = Some(depth),
+        }
+
+        let instruction = &chunk.instructions[offset];
+        let effect = stack_effect(instruction);
+        if depth < effect.pop {
+            return Err(VerifyError::StackUnderflow {
+                chunk: chunk_id,
+                offset,
+                opcode: instruction.opcode,
+                needed: effect.pop,
+                depth,
+            });
+        }
+        let next = effect.apply(depth);
+
+        match instruction.opcode {
+            Opcode::Jump | Opcode::JumpIf | Opcode::JumpIfNot | Opcode::LoopEnd => {
+                pending.push((instruction.arg as usize, next));
+            }
+            // A thrown error unwinds to the depth at handler installation and pushes the error
+            Opcode::Try | Opcode::TryStart | Opcode::PushHandler => {
+                pending.push((instruction.arg as usize, depth + 1));
+            }
+            Opcode::TryStartWithFinally => {
+                pending.push(((instruction.arg >> HIGH_SHIFT) as usize, depth + 1));
+            }
+            _ => {}
+        }
+        if !ends_flow(instruction.opcode) {
+            pending.push((offset + 1, next));
+        }
+    }
+    Ok(())
+}
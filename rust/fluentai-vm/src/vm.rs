@@ -1,20 +1,23 @@
 //! High-performance stack-based virtual machine
-use  fluentai_bytecode::{Bytecode, Instruction, Opcode};
+use  fluentai_bytecode::{Bytecode, ExternDecl, Instruction, Opcode};
 use  crate::cow_globals::CowGlobals;
 use  crate::debug::{DebugConfig, StepMode, VMDebugEvent};
 use  crate::error::{value_type_name, StackFrame, StackTrace, VMError, VMResult};
 use  crate::gc::{GarbageCollector, GcConfig, GcScope};
 #[cfg(feature = "jit")]
-use  crate::jit_integration::{JitConfig, JitManager};
+use  crate::jit_integration::{jit_error, JitConfig, JitManager, VMJitBridge};
+#[cfg(feature = "jit")]
+use  fluentai_jit::runtime::JitOutcome;
 use  crate::safety::{checked_ops, ActorId, ChannelId, IdGenerator, PromiseId, ResourceLimits};
 use  crate::security::{SecurityManager, SecurityPolicy};
 use  fluentai_core::ast::{NodeId, UsageStatistics};
-use  fluentai_core::value::Value;
-use  fluentai_effects::{runtime::EffectRuntime, EffectContext};
+use  fluentai_core::value::{numeric, BigInt, Value};
+use  fluentai_effects::{runtime::EffectRuntime, EffectContext, EffectType};
 use  fluentai_modules::{ModuleLoader, ModuleResolver};
 use  fluentai_stdlib::value::Value as StdlibValue;
 use  fluentai_stdlib::{init_stdlib, StdlibRegistry};
 use  rustc_hash::FxHashMap;
+use  std::sync::atomic::{AtomicBool, Ordering};
 use  std::sync::{Arc, RwLock};
 use  std::time::Instant;
 use  tokio::sync::{mpsc, oneshot};
@@ -168,11 +171,35 @@ pub struct VM {
     current_actor: Option<ActorId>,
     // Current message being processed by actor (for ActorReceive opcode)
     current_actor_message: Option<Value>,
+    // State set via Become by the handler currently running
+    pending_actor_state: Option<Value>,
+    // Trait method implementations keyed by (type name, method name)
+    methods: FxHashMap<(String, String), Value>,
+    // Variant tag -> enums with trait impls that declare it
+    variant_enums: FxHashMap<String, Vec<String>>,
+    // Cancellation flags of the enclosing parallel blocks, innermost last; one is set
+    // when a sibling branch of that block has failed
+    cancelled: Vec<Arc<AtomicBool>>,
     // JIT compilation manager
     #[cfg(feature = "jit")]
     jit_manager: JitManager,
 }
 
+/// Type name used to dispatch trait methods on a receiver value
+pub fn method_receiver_type(value: &Value) -> &str {
+    match value {
+        Value::Struct { name, .. } => name,
+        Value::Tagged { tag, .. } => tag,
+        Value::Integer(_) => "Int",
+        Value::Float(_) => "Float",
+        Value::String(_) => "String",
+        Value::Boolean(_) => "Bool",
+        Value::List(_) => "List",
+        Value::Map(_) => "Map",
+        other => value_type_name(other),
+    }
+}
+
 /// Handler frame for tracking active effect handlers
 #[derive(Clone)]
 pub struct HandlerFrame {
@@ -246,6 +273,10 @@ impl VM {
             finally_states: Vec::new(),
             current_actor: None,
             current_actor_message: None,
+            pending_actor_state: None,
+            methods: FxHashMap::default(),
+            variant_enums: FxHashMap::default(),
+            cancelled: Vec::new(),
             #[cfg(feature = "jit")]
             jit_manager: JitManager::new(JitConfig::default()),
         }
@@ -261,6 +292,8 @@ impl VM {
         self.stack.clear();
         self.call_stack.clear();
         self.globals.clear();
+        self.methods.clear();
+        self.variant_enums.clear();
         self.promises.clear();
         self.channels.clear();
         self.cells.clear();
@@ -450,7 +483,9 @@ impl VM {
             start_time: self.usage_tracker.as_ref().map(|_| Instant::now()),
         });
 
-        let result = self.run_inner();
+        let result = self
+            .run_inner()
+            .map_err(|error| self.attach_source_location(error));
 
         // Track error if one occurred
         if result.is_err() {
@@ -523,7 +558,7 @@ impl VM {
 
                 // Check specific instruction security requirements
                 match &instruction.opcode {
-                    Opcode::Call => {
+                    Opcode::Call | Opcode::CallMethod => {
                         // Check call depth
                         if self.call_stack.len() >= self.resource_limits.max_call_depth {
                             return Err(VMError::CallStackOverflow {
@@ -537,6 +572,13 @@ impl VM {
                 }
             }
 
+            // A cancelled branch stops at its next call or backward jump
+            if !self.cancelled.is_empty() && Self::is_cancellation_point(&instruction, ip) {
+                self.check_cancelled()?;
+            }
+
+            self.record_type_feedback(chunk_id, ip, &instruction);
+
             // Increment IP before execution (may be modified by jumps)
             self.call_stack.last_mut().unwrap().ip += 1;
             self.instruction_count += 1;
@@ -672,7 +714,8 @@ impl VM {
                 
                 // Control flow operations - dispatched to ControlFlowHandler
                 Jump | JumpIf | JumpIfNot | Call | TailCall | 
-                Return | TailReturn | LoopStart | LoopEnd | Halt => {
+                Return | TailReturn | LoopStart | LoopEnd | Halt |
+                CallMethod | DefineMethod => {
                     return control_flow_handler.execute(self, instruction, chunk_id);
                 }
                 
@@ -689,7 +732,9 @@ impl VM {
                 // Collection operations - dispatched to CollectionsHandler
                 MakeList | ListGet | ListSet | ListHead | ListTail |
                 ListCons | ListLen | ListEmpty |
-                MakeMap | MapGet | MapSet => {
+                MakeTuple | TupleGet | IsTuple |
+                MakeMap | MapGet | MapSet |
+                MakeStruct | GetField | SetField => {
                     return collections_handler.execute(self, instruction, chunk_id);
                 }
                 
@@ -697,7 +742,7 @@ impl VM {
                 Spawn | Await | Channel | ChannelWithCapacity | MakeChannel |
                 Send | Receive | CreateActor | MakeActor | ActorSend |
                 ActorReceive | Become | TrySend | TryReceive | Select |
-                PromiseNew | PromiseAll | PromiseRace | WithTimeout => {
+                PromiseNew | PromiseAll | PromiseRace | WithTimeout | Parallel => {
                     return concurrent_handler.execute(self, instruction, chunk_id);
                 }
                 
@@ -989,6 +1034,14 @@ impl VM {
                     self.push(Value::Boolean(is_match))?;
                 }
                 
+                MatchError => {
+                    let value = self.pop()?;
+                    return Err(VMError::RuntimeError {
+                        message: format!("Match error: no pattern matches {}", value),
+                        stack_trace: None,
+                    });
+                }
+                
                 LoadModule => {
                     let module_name = self.get_constant_string(instruction.arg)?;
                     self.load_module(&module_name)?;
@@ -1241,6 +1294,8 @@ impl VM {
         self.push(result)
     }
 
+    /// Apply a fixed-width integer operation; `op` reports overflow as an error
+    /// rather than promoting to a big integer like the generic opcodes do
     pub fn binary_int_op<F>(&mut self, op: F) -> VMResult<()>
     where
         F: FnOnce(i64, i64) -> VMResult<i64>,
@@ -1310,9 +1365,13 @@ impl VM {
             (Value::Boolean(x), Value::Boolean(y)) => x == y,
             (Value::Integer(x), Value::Integer(y)) => x == y,
             (Value::Float(x), Value::Float(y)) => x == y,
+            (x, y) if x.is_exact_number() && y.is_exact_number() => {
+                numeric::compare(x, y) == Some(std::cmp::Ordering::Equal)
+            }
             (Value::String(x), Value::String(y)) => x == y,
+            (Value::Bytes(x), Value::Bytes(y)) => x == y,
             (Value::Symbol(x), Value::Symbol(y)) => x == y,
-            (Value::List(x), Value::List(y)) => {
+            (Value::List(x), Value::List(y)) | (Value::Tuple(x), Value::Tuple(y)) => {
                 x.len() == y.len() && x.iter().zip(y).all(|(a, b)| self.values_equal(a, b))
             }
             (Value::Vector(x), Value::Vector(y)) => {
@@ -1340,6 +1399,23 @@ impl VM {
                         .zip(vals2)
                         .all(|(a, b)| self.values_equal(a, b))
             }
+            (
+                Value::Struct {
+                    name: name1,
+                    fields: fields1,
+                },
+                Value::Struct {
+                    name: name2,
+                    fields: fields2,
+                },
+            ) => {
+                name1 == name2
+                    && fields1.len() == fields2.len()
+                    && fields1
+                        .iter()
+                        .zip(fields2)
+                        .all(|((k1, a), (k2, b))| k1 == k2 && self.values_equal(a, b))
+            }
             (Value::Module { name: n1, .. }, Value::Module { name: n2, .. }) => n1 == n2,
             (Value::Promise(x), Value::Promise(y)) => x == y,
             (Value::Channel(x), Value::Channel(y)) => x == y,
@@ -1354,9 +1430,13 @@ impl VM {
             Value::Boolean(b) => *b,
             Value::Integer(i) => *i != 0,
             Value::Float(f) => *f != 0.0,
+            Value::BigInt(n) => *n != BigInt::ZERO,
+            Value::Decimal(d) => !d.is_zero(),
             Value::String(s) => !s.is_empty(),
+            Value::Bytes(b) => !b.is_empty(),
             Value::Symbol(_) => true,
             Value::List(l) => !l.is_empty(),
+            Value::Tuple(_) => true,
             Value::Procedure(_) => true,
             Value::Vector(v) => !v.is_empty(),
             Value::Map(m) => !m.is_empty(),
@@ -1366,6 +1446,7 @@ impl VM {
             Value::Channel(_) => true,
             Value::Cell(_) => true,
             Value::Tagged { .. } => true,
+            Value::Struct { .. } => true,
             Value::Module { .. } => true,
             Value::GcHandle(_) => true,
             Value::Actor(_) => true,
@@ -1446,52 +1527,7 @@ impl VM {
                 });
 
                 // Continue execution until this call returns
-                let initial_call_depth = self.call_stack.len();
-                while self.call_stack.len() >= initial_call_depth {
-                    let frame = self
-                        .call_stack
-                        .last()
-                        .ok_or_else(|| VMError::StackUnderflow {
-                            operation: "get_current_frame".to_string(),
-                            stack_size: self.call_stack.len(),
-                            stack_trace: None,
-                        })?;
-                    let chunk_id = frame.chunk_id;
-                    let ip = frame.ip;
-
-                    if ip >= self.bytecode.chunks[chunk_id].instructions.len() {
-                        return Err(VMError::InvalidJumpTarget {
-                            target: ip,
-                            chunk_size: self.bytecode.chunks[chunk_id].instructions.len(),
-                            stack_trace: None,
-                        });
-                    }
-
-                    let instruction = self.bytecode.chunks[chunk_id].instructions[ip].clone();
-                    self.call_stack.last_mut().unwrap().ip += 1;
-
-                    match self.execute_instruction(&instruction, chunk_id)? {
-                        VMState::Continue => {}
-                        VMState::Return => {
-                            if self.call_stack.len() == initial_call_depth {
-                                // This is our call returning
-                                self.call_stack.pop();
-                                break;
-                            } else {
-                                // Inner call returning
-                                self.call_stack.pop();
-                            }
-                        }
-                        VMState::Halt => {
-                            return Err(VMError::RuntimeError {
-                                message: "Unexpected halt in function call".to_string(),
-                                stack_trace: Some(self.build_stack_trace()),
-                            });
-                        }
-                    }
-                }
-
-                Ok(())
+                self.run_until_return()
             }
             Value::NativeFunction {
                 function, arity, ..
@@ -1528,6 +1564,57 @@ impl VM {
         }
     }
 
+    /// Interpret the top call frame until it returns, leaving its result on the stack
+    fn run_until_return(&mut self) -> VMResult<()> {
+        let initial_call_depth = self.call_stack.len();
+        while self.call_stack.len() >= initial_call_depth {
+            let frame = self
+                .call_stack
+                .last()
+                .ok_or_else(|| VMError::StackUnderflow {
+                    operation: "get_current_frame".to_string(),
+                    stack_size: self.call_stack.len(),
+                    stack_trace: None,
+                })?;
+            let chunk_id = frame.chunk_id;
+            let ip = frame.ip;
+
+            if ip >= self.bytecode.chunks[chunk_id].instructions.len() {
+                return Err(VMError::InvalidJumpTarget {
+                    target: ip,
+                    chunk_size: self.bytecode.chunks[chunk_id].instructions.len(),
+                    stack_trace: None,
+                });
+            }
+
+            let instruction = self.bytecode.chunks[chunk_id].instructions[ip].clone();
+            self.record_type_feedback(chunk_id, ip, &instruction);
+            self.call_stack.last_mut().unwrap().ip += 1;
+
+            match self.execute_instruction(&instruction, chunk_id)? {
+                VMState::Continue => {}
+                VMState::Return => {
+                    if self.call_stack.len() == initial_call_depth {
+                        // This is our call returning
+                        self.call_stack.pop();
+                        break;
+                    } else {
+                        // Inner call returning
+                        self.call_stack.pop();
+                    }
+                }
+                VMState::Halt => {
+                    return Err(VMError::RuntimeError {
+                        message: "Unexpected halt in function call".to_string(),
+                        stack_trace: Some(self.build_stack_trace()),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn call_handler_function(&mut self, handler: Value, args: Vec<Value>) -> VMResult<Value> {
         // Call a handler function with the given arguments
         match handler {
@@ -1657,6 +1744,9 @@ impl VM {
             Value::Boolean(b) => fluentai_core::value::Value::Boolean(*b),
             Value::Integer(i) => fluentai_core::value::Value::Integer(*i),
             Value::Float(f) => fluentai_core::value::Value::Float(*f),
+            Value::BigInt(n) => fluentai_core::value::Value::BigInt(n.clone()),
+            Value::Decimal(d) => fluentai_core::value::Value::Decimal(*d),
+            Value::Bytes(b) => fluentai_core::value::Value::Bytes(b.clone()),
             Value::String(s) => fluentai_core::value::Value::String(s.clone()),
             Value::Symbol(s) => fluentai_core::value::Value::Symbol(s.clone()),
             Value::List(items) => fluentai_core::value::Value::List(
@@ -1665,6 +1755,12 @@ impl VM {
                     .map(|v| self.vm_value_to_core_value(v))
                     .collect(),
             ),
+            Value::Tuple(items) => fluentai_core::value::Value::Tuple(
+                items
+                    .iter()
+                    .map(|v| self.vm_value_to_core_value(v))
+                    .collect(),
+            ),
             Value::Procedure(proc) => fluentai_core::value::Value::Procedure(proc.clone()),
             Value::Vector(items) => fluentai_core::value::Value::Vector(
                 items
@@ -1702,6 +1798,13 @@ impl VM {
                     .map(|v| self.vm_value_to_core_value(v))
                     .collect(),
             },
+            Value::Struct { name, fields } => fluentai_core::value::Value::Struct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(k, v)| (k.clone(), self.vm_value_to_core_value(v)))
+                    .collect(),
+            },
             Value::Module { name, exports } => {
                 // Convert to a map representation
                 let mut map = FxHashMap::default();
@@ -1734,6 +1837,9 @@ impl VM {
             fluentai_core::value::Value::Boolean(b) => Value::Boolean(*b),
             fluentai_core::value::Value::Integer(i) => Value::Integer(*i),
             fluentai_core::value::Value::Float(f) => Value::Float(*f),
+            fluentai_core::value::Value::BigInt(n) => Value::BigInt(n.clone()),
+            fluentai_core::value::Value::Decimal(d) => Value::Decimal(*d),
+            fluentai_core::value::Value::Bytes(b) => Value::Bytes(b.clone()),
             fluentai_core::value::Value::String(s) => Value::String(s.clone()),
             fluentai_core::value::Value::List(items) => Value::List(
                 items
@@ -1741,6 +1847,12 @@ impl VM {
                     .map(|v| self.core_value_to_vm_value(v))
                     .collect(),
             ),
+            fluentai_core::value::Value::Tuple(items) => Value::Tuple(
+                items
+                    .iter()
+                    .map(|v| self.core_value_to_vm_value(v))
+                    .collect(),
+            ),
             fluentai_core::value::Value::Map(map) => {
                 let mut vm_map = FxHashMap::default();
                 for (k, v) in map.iter() {
@@ -1763,6 +1875,13 @@ impl VM {
                     .map(|v| self.core_value_to_vm_value(v))
                     .collect(),
             },
+            fluentai_core::value::Value::Struct { name, fields } => Value::Struct {
+                name: name.clone(),
+                fields: fields
+                    .iter()
+                    .map(|(k, v)| (k.clone(), self.core_value_to_vm_value(v)))
+                    .collect(),
+            },
             fluentai_core::value::Value::Symbol(s) => {
                 // Preserve symbols as symbols
                 Value::Symbol(s.clone())
@@ -1938,6 +2057,93 @@ impl VM {
         self.globals.get(name)
     }
 
+    /// Check that the security policy, if one is set, lets the program call the
+    /// named host function
+    pub fn check_host_function(&self, name: &str) -> VMResult<()> {
+        if let Some(ref security) = self.security_manager {
+            security.context.check_extern(name)?;
+        }
+        Ok(())
+    }
+
+    /// Bind the program's `extern` declarations to host functions
+    ///
+    /// `resolve` supplies the implementation of each declaration. Every declaration
+    /// must be granted by the security policy, if one is set, and resolve to a native
+    /// function of the declared arity, so the program never starts with a dangling
+    /// foreign call.
+    pub fn link_externs(
+        &mut self,
+        mut resolve: impl FnMut(&ExternDecl) -> Option<Value>,
+    ) -> VMResult<()> {
+        for decl in self.bytecode.externs.clone() {
+            self.check_host_function(&decl.name)?;
+
+            let function = resolve(&decl).ok_or_else(|| VMError::RuntimeError {
+                message: format!("Unresolved extern function '{}'", decl.name),
+                stack_trace: None,
+            })?;
+            match &function {
+                Value::NativeFunction { arity, .. } if *arity == decl.arity => {}
+                Value::NativeFunction { arity, .. } => {
+                    return Err(VMError::RuntimeError {
+                        message: format!(
+                            "Extern function '{}' is declared with {} parameters but the host function takes {}",
+                            decl.name, decl.arity, arity
+                        ),
+                        stack_trace: None,
+                    });
+                }
+                other => {
+                    return Err(VMError::TypeError {
+                        operation: format!("extern function '{}'", decl.name),
+                        expected: "native function".to_string(),
+                        got: value_type_name(other).to_string(),
+                        location: None,
+                        stack_trace: None,
+                    });
+                }
+            }
+
+            self.set_global(decl.name, function);
+        }
+        Ok(())
+    }
+
+    /// Register a trait method implementation for a type
+    pub fn define_method(&mut self, type_name: String, method: String, func: Value) {
+        self.methods.insert((type_name, method), func);
+    }
+
+    /// Record the variant tags of an enum, so impls on the enum apply to them
+    pub fn define_enum_variants(&mut self, enum_name: &str, variants: Vec<String>) {
+        for variant in variants {
+            let enums = self.variant_enums.entry(variant).or_default();
+            if !enums.iter().any(|name| name == enum_name) {
+                enums.push(enum_name.to_string());
+            }
+        }
+    }
+
+    /// Look up the trait method implementation for a receiver value
+    ///
+    /// Structs dispatch on their struct name, tagged values on their tag and then
+    /// their enum, and primitives on their type name (`Int`, `Float`, `String`, ...).
+    pub fn lookup_method(&self, receiver: &Value, method: &str) -> Option<&Value> {
+        let type_name = method_receiver_type(receiver);
+        if let Some(func) = self.methods.get(&(type_name.to_string(), method.to_string())) {
+            return Some(func);
+        }
+        match receiver {
+            Value::Tagged { tag, .. } => self
+                .variant_enums
+                .get(tag)?
+                .iter()
+                .find_map(|enum_name| self.methods.get(&(enum_name.clone(), method.to_string()))),
+            _ => None,
+        }
+    }
+
     /// Build a stack trace from current call stack
     pub fn build_stack_trace(&self) -> StackTrace {
         let mut trace = StackTrace::new();
@@ -1951,20 +2157,7 @@ impl VM {
                 .unwrap_or_else(|| format!("<anonymous:{}>", frame.chunk_id));
 
             // Get source location from source map if available
-            let location = self
-                .bytecode
-                .chunks
-                .get(frame.chunk_id)
-                .and_then(|chunk| chunk.source_map.as_ref())
-                .and_then(|source_map| source_map.get_location(frame.ip))
-                .map(|src_loc| crate::error::SourceLocation {
-                    file: self.bytecode.chunks.get(frame.chunk_id)
-                        .and_then(|chunk| chunk.source_map.as_ref())
-                        .and_then(|sm| sm.filename.clone()),
-                    line: src_loc.line.unwrap_or(0) as usize,
-                    column: src_loc.column.unwrap_or(0) as usize,
-                    function: Some(function_name.clone()),
-                });
+            let location = self.frame_source_location(frame);
 
             trace.push_frame(StackFrame {
                 function_name,
@@ -2003,7 +2196,11 @@ impl VM {
             _ => {}
         }
         
-        // Add source location if not already present and applicable
+        self.attach_source_location(error)
+    }
+
+    /// Fill in the source location of an error from the current instruction
+    fn attach_source_location(&self, mut error: VMError) -> VMError {
         if let Some(frame) = self.call_stack.last() {
             match &mut error {
                 VMError::TypeError { location, .. } |
@@ -2011,21 +2208,7 @@ impl VM {
                 VMError::InvalidOpcode { location, .. } |
                 VMError::UnknownIdentifier { location, .. } => {
                     if location.is_none() {
-                        *location = self
-                            .bytecode
-                            .chunks
-                            .get(frame.chunk_id)
-                            .and_then(|chunk| chunk.source_map.as_ref())
-                            .and_then(|source_map| source_map.get_location(frame.ip))
-                            .map(|src_loc| crate::error::SourceLocation {
-                                file: self.bytecode.chunks.get(frame.chunk_id)
-                                    .and_then(|chunk| chunk.source_map.as_ref())
-                                    .and_then(|sm| sm.filename.clone()),
-                                line: src_loc.line.unwrap_or(0) as usize,
-                                column: src_loc.column.unwrap_or(0) as usize,
-                                function: self.bytecode.chunks.get(frame.chunk_id)
-                                    .and_then(|chunk| chunk.name.clone()),
-                            });
+                        *location = self.frame_source_location(frame);
                     }
                 }
                 _ => {}
@@ -2035,6 +2218,20 @@ impl VM {
         error
     }
 
+    /// Source location of the instruction a frame is executing
+    fn frame_source_location(&self, frame: &CallFrame) -> Option<crate::error::SourceLocation> {
+        let chunk = self.bytecode.chunks.get(frame.chunk_id)?;
+        let source_map = chunk.source_map.as_ref()?;
+        // The instruction pointer is advanced before an instruction executes
+        let src_loc = source_map.get_location(frame.ip.saturating_sub(1))?;
+        Some(crate::error::SourceLocation {
+            file: source_map.filename.clone(),
+            line: src_loc.line.unwrap_or(0) as usize,
+            column: src_loc.column.unwrap_or(0) as usize,
+            function: chunk.name.clone(),
+        })
+    }
+
     /// Set resource limits
     pub fn set_resource_limits(&mut self, limits: ResourceLimits) {
         self.resource_limits = limits;
@@ -2139,9 +2336,29 @@ impl VM {
         false
     }
     
+    /// Check if a chunk has code compiled ahead of time, which runs in place of
+    /// interpreting it whether or not it is hot
+    #[cfg(feature = "jit")]
+    pub fn has_native_code(&self, chunk_id: usize) -> bool {
+        self.jit_manager.has_native(chunk_id)
+    }
+    
+    /// Check if a chunk has code compiled ahead of time, which needs the `jit` feature
+    #[cfg(not(feature = "jit"))]
+    pub fn has_native_code(&self, _chunk_id: usize) -> bool {
+        false
+    }
+    
     /// Try to execute a function using JIT compilation
+    ///
+    /// Returns `None` when the chunk is not compiled, so the caller should interpret it.
     #[cfg(feature = "jit")]
-    pub fn try_jit_execute(&mut self, chunk_id: usize) -> VMResult<Option<Value>> {
+    pub fn try_jit_execute(
+        &mut self,
+        chunk_id: usize,
+        args: &[Value],
+        env: &[Value],
+    ) -> VMResult<Option<Value>> {
         // First attempt compilation if needed
         if let Some(tracker) = &self.usage_tracker {
             if let Ok(tracker_guard) = tracker.read() {
@@ -2155,18 +2372,87 @@ impl VM {
         }
         
         // Try to execute the JIT-compiled version
-        match self.jit_manager.execute_if_compiled(chunk_id, &self.bytecode) {
-            Some(Ok(value)) => Ok(Some(value)),
-            Some(Err(_)) => Ok(None), // Fall back to interpreter
-            None => Ok(None),
+        let func = match self.jit_manager.compiled_function(chunk_id, args.len()) {
+            Some(func) => func,
+            None => return Ok(None),
+        };
+        
+        // Calls from the compiled code to bytecode functions come back to this VM
+        let mut bridge = VMJitBridge::new(self);
+        let deopt = match func.call_or_deoptimize(&mut bridge, args, env).map_err(jit_error)? {
+            JitOutcome::Returned(value) => return Ok(Some(value)),
+            JitOutcome::Deoptimized(deopt) => deopt,
+        };
+        
+        // A type guard failed: rebuild the frame and interpret the rest of the call
+        self.jit_manager.deoptimized(chunk_id);
+        let stack_base = self.stack.len();
+        for value in deopt.stack {
+            self.push(value)?;
         }
+        self.call_stack.push(CallFrame {
+            chunk_id,
+            ip: deopt.ip,
+            stack_base,
+            env: env.to_vec(),
+            start_time: if self.usage_tracker.is_some() {
+                Some(Instant::now())
+            } else {
+                None
+            },
+        });
+        self.run_until_return()?;
+        self.pop().map(Some)
     }
     
     #[cfg(not(feature = "jit"))]
-    pub fn try_jit_execute(&mut self, _chunk_id: usize) -> VMResult<Option<Value>> {
+    pub fn try_jit_execute(
+        &mut self,
+        _chunk_id: usize,
+        _args: &[Value],
+        _env: &[Value],
+    ) -> VMResult<Option<Value>> {
         Ok(None)
     }
     
+    /// Run a chunk with code compiled ahead of time instead of interpreting it
+    ///
+    /// # Safety
+    ///
+    /// `code` must be a chunk of this VM's bytecode compiled to an object file by
+    /// `fluentai_jit::object`, and must stay loaded for the life of the VM.
+    #[cfg(feature = "jit")]
+    pub unsafe fn register_native_function(&mut self, chunk_id: usize, code: *const u8) -> VMResult<()> {
+        let arity = self
+            .bytecode
+            .chunks
+            .get(chunk_id)
+            .ok_or_else(|| VMError::RuntimeError {
+                message: format!("Native code for unknown chunk {}", chunk_id),
+                stack_trace: None,
+            })?
+            .arity;
+        let func = fluentai_jit::CompiledFunction::from_code(code, arity).map_err(jit_error)?;
+        self.jit_manager.register_native(chunk_id, func);
+        Ok(())
+    }
+    
+    /// Record operand types at an instruction for specializing JIT-compiled code
+    #[cfg(feature = "jit")]
+    fn record_type_feedback(&mut self, chunk_id: usize, ip: usize, instruction: &Instruction) {
+        self.jit_manager
+            .record_operands(chunk_id, ip, instruction, &self.stack);
+    }
+    
+    #[cfg(not(feature = "jit"))]
+    fn record_type_feedback(&mut self, _chunk_id: usize, _ip: usize, _instruction: &Instruction) {}
+    
+    /// Replace the JIT configuration, discarding any compiled code
+    #[cfg(feature = "jit")]
+    pub fn set_jit_config(&mut self, config: JitConfig) {
+        self.jit_manager = JitManager::new(config);
+    }
+    
     /// Get JIT compilation statistics
     #[cfg(feature = "jit")]
     pub fn jit_stats(&self) -> &crate::jit_integration::JitStats {
@@ -2278,30 +2564,11 @@ impl VM {
                 // Store the receiver
                 self.promises.insert(promise_id, rx);
                 
-                // Clone shared resources
-                let bytecode = Arc::clone(&self.bytecode);
-                let stdlib = self.stdlib.clone();
-                let effect_runtime = Arc::clone(&self.effect_runtime);
-                let effect_context = Arc::clone(&self.effect_context);
-                let globals = self.globals.clone(); // COW clone
+                let build_task_vm = self.task_vm_builder(chunk_id, env);
                 
                 // Spawn the task
                 tokio::spawn(async move {
-                    // Create a new VM with shared bytecode
-                    let mut task_vm = VM::with_shared_bytecode(bytecode);
-                    task_vm.stdlib = stdlib;
-                    task_vm.effect_runtime = effect_runtime;
-                    task_vm.effect_context = effect_context;
-                    task_vm.globals = globals;
-                    
-                    // Set up the call frame for the function
-                    task_vm.call_stack.push(CallFrame {
-                        chunk_id,
-                        ip: 0,
-                        stack_base: 0,
-                        env,
-                        start_time: None,
-                    });
+                    let mut task_vm = build_task_vm();
                     
                     // Run the function
                     let result = task_vm.run_inner();
@@ -2324,6 +2591,143 @@ impl VM {
         }
     }
     
+    /// Whether a parallel branch checks for cancellation before `instruction` at `ip`.
+    /// Only calls and backward jumps can keep a branch running indefinitely.
+    fn is_cancellation_point(instruction: &Instruction, ip: usize) -> bool {
+        match instruction.opcode {
+            Opcode::Call | Opcode::TailCall | Opcode::CallMethod | Opcode::LoopEnd => true,
+            Opcode::Jump | Opcode::JumpIf | Opcode::JumpIfNot => instruction.arg as usize <= ip,
+            _ => false,
+        }
+    }
+    
+    /// Fail if this VM runs a branch of a parallel block, at any depth of nesting,
+    /// that has been cancelled
+    fn check_cancelled(&self) -> VMResult<()> {
+        if self.cancelled.iter().any(|flag| flag.load(Ordering::Relaxed)) {
+            return Err(VMError::AsyncError {
+                message: "parallel branch cancelled".to_string(),
+                stack_trace: None,
+            });
+        }
+        Ok(())
+    }
+    
+    /// Run each branch closure of a parallel block in its own task and return
+    /// the list of their results in branch order. The first branch to fail
+    /// cancels the others, and its error is returned once they have stopped.
+    /// Cancelling an enclosing parallel block cancels the branches too.
+    pub fn run_parallel(&mut self, branches: Vec<Value>) -> VMResult<Value> {
+        let handle = self.effect_runtime.try_handle().ok_or_else(|| VMError::AsyncError {
+            message: "parallel block requires an async runtime".to_string(),
+            stack_trace: None,
+        })?;
+        
+        let branch_count = branches.len();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let mut flags = self.cancelled.clone();
+        flags.push(Arc::clone(&cancelled));
+        let (tx, rx) = std::sync::mpsc::channel();
+        
+        for (index, branch) in branches.into_iter().enumerate() {
+            let (chunk_id, env) = match branch {
+                Value::Function { chunk_id, env } => (chunk_id, env),
+                other => {
+                    cancelled.store(true, Ordering::Relaxed);
+                    return Err(VMError::TypeError {
+                        operation: "parallel".to_string(),
+                        expected: "function".to_string(),
+                        got: value_type_name(&other).to_string(),
+                        location: None,
+                        stack_trace: None,
+                    });
+                }
+            };
+            let build_task_vm = self.task_vm_builder(chunk_id, env);
+            let flags = flags.clone();
+            // Branches join before the block returns, so they run inside the
+            // dynamic extent of the enclosing effect handlers
+            let handlers = self.handler_stack.clone();
+            let tx = tx.clone();
+            
+            // Branches run synchronous VM code, so they go on the blocking pool
+            handle.spawn_blocking(move || {
+                let mut task_vm = build_task_vm();
+                task_vm.cancelled = flags;
+                task_vm.handler_stack = handlers;
+                let result = task_vm.run_inner();
+                let _ = tx.send((index, result));
+            });
+        }
+        drop(tx);
+        
+        let mut results = vec![Value::Nil; branch_count];
+        let mut completed = 0;
+        let mut failure = None;
+        for (index, result) in rx {
+            completed += 1;
+            match result {
+                Ok(value) => results[index] = value,
+                Err(error) => {
+                    if failure.is_none() {
+                        cancelled.store(true, Ordering::Relaxed);
+                        failure = Some(error);
+                    }
+                }
+            }
+        }
+        
+        if let Some(error) = failure {
+            return Err(error);
+        }
+        if completed < branch_count {
+            return Err(VMError::AsyncError {
+                message: "parallel branch terminated without a result".to_string(),
+                stack_trace: None,
+            });
+        }
+        Ok(Value::List(results))
+    }
+    
+    /// Capture what a task needs to call `chunk_id` in a VM of its own that
+    /// shares this VM's bytecode, stdlib, effects, globals and trait methods.
+    /// The task runs under this VM's security manager, so it draws on the same
+    /// instruction budget, and its call depth counts from this VM's.
+    fn task_vm_builder(&self, chunk_id: usize, env: Vec<Value>) -> impl FnOnce() -> VM + Send + 'static {
+        let bytecode = Arc::clone(&self.bytecode);
+        let stdlib = self.stdlib.clone();
+        let effect_runtime = Arc::clone(&self.effect_runtime);
+        let effect_context = Arc::clone(&self.effect_context);
+        let globals = self.globals.clone(); // COW clone
+        let methods = self.methods.clone();
+        let security_manager = self.security_manager.clone();
+        let mut resource_limits = self.resource_limits.clone();
+        resource_limits.max_call_depth = resource_limits
+            .max_call_depth
+            .saturating_sub(self.call_stack.len());
+        
+        move || {
+            let mut task_vm = VM::with_shared_bytecode(bytecode);
+            task_vm.stdlib = stdlib;
+            task_vm.effect_runtime = effect_runtime;
+            task_vm.effect_context = effect_context;
+            task_vm.globals = globals;
+            task_vm.methods = methods;
+            task_vm.security_manager = security_manager;
+            task_vm.resource_limits = resource_limits;
+            
+            // Set up the call frame for the function
+            task_vm.call_stack.push(CallFrame {
+                chunk_id,
+                ip: 0,
+                stack_base: 0,
+                env,
+                start_time: None,
+            });
+            task_vm
+        }
+    }
+    
     pub fn create_actor(&mut self, initial_state: Value, handler: Value) -> VMResult<ActorId> {
         // Validate handler is a function
         match &handler {
@@ -2386,26 +2790,16 @@ impl VM {
         self.current_actor = Some(actor_id);
         
         // Try to receive a message from the mailbox
+        let mut result = Ok(());
         while let Ok(message) = actor.mailbox.try_recv() {
-            // Set the current message for ActorReceive opcode
-            self.current_actor_message = Some(message.clone());
-            
-            // Call the handler function with (state, message)
-            let handler = actor.handler.clone();
-            
-            // Push handler, state, and message onto stack
-            self.push(handler)?;
-            self.push(actor.state.clone())?;
-            self.push(message)?;
-            
-            // Call the handler with 2 arguments
-            self.call_value(2)?;
+            result = self.handle_actor_message(&mut actor, message);
             
-            // The result is the new state
-            actor.state = self.pop()?;
-            
-            // Clear the current message
+            // Clear the current message, and any state a failed handler set with Become
             self.current_actor_message = None;
+            self.pending_actor_state = None;
+            if result.is_err() {
+                break;
+            }
         }
         
         // Clear actor context
@@ -2413,6 +2807,25 @@ impl VM {
         
         // Put the actor back
         self.actors.insert(actor_id, actor);
+        result
+    }
+    
+    /// Run an actor's handler on one message and update its state
+    fn handle_actor_message(&mut self, actor: &mut Actor, message: Value) -> VMResult<()> {
+        // Set the current message for ActorReceive opcode
+        self.current_actor_message = Some(message.clone());
+        
+        // Push handler, state, and message onto stack
+        self.push(actor.handler.clone())?;
+        self.push(actor.state.clone())?;
+        self.push(message)?;
+        
+        // Call the handler with 2 arguments
+        self.call_value(2)?;
+        
+        // The result is the new state, unless the handler used Become
+        let result = self.pop()?;
+        actor.state = self.pending_actor_state.take().unwrap_or(result);
         Ok(())
     }
     
@@ -2435,6 +2848,11 @@ impl VM {
         if let Some(actor) = self.actors.get_mut(&actor_id) {
             actor.state = new_state;
             Ok(())
+        } else if self.current_actor == Some(actor_id) {
+            // The actor is checked out while its handler runs;
+            // the state is applied once the handler returns
+            self.pending_actor_state = Some(new_state);
+            Ok(())
         } else {
             Err(VMError::UnknownIdentifier {
                 name: format!("actor:{}", actor_id.0),
@@ -2444,6 +2862,11 @@ impl VM {
         }
     }
     
+    /// Get the current state of an actor
+    pub fn get_actor_state(&self, actor_id: ActorId) -> Option<&Value> {
+        self.actors.get(&actor_id).map(|actor| &actor.state)
+    }
+    
     /// Get the current actor message (for ActorReceive opcode)
     pub fn get_current_actor_message(&self) -> Option<Value> {
         self.current_actor_message.clone()
@@ -2598,8 +3021,42 @@ impl VM {
     }
     
     // Tail call support
+
+    /// Call `func` in place of the current frame, so tail recursion runs in constant space
+    ///
+    /// Compiled code for the callee runs instead if there is any. Values that are not
+    /// bytecode functions are called normally, leaving their result on the stack.
     pub fn setup_tail_call(&mut self, func: Value, args: Vec<Value>) -> VMResult<()> {
-        // Implementation would reuse current call frame
+        let (chunk_id, env) = match func {
+            Value::Function { chunk_id, env } => (chunk_id, env),
+            func => {
+                let arg_count = args.len();
+                self.push(func)?;
+                for arg in args {
+                    self.push(arg)?;
+                }
+                return self.call_value(arg_count);
+            }
+        };
+
+        if self.has_native_code(chunk_id) || self.should_jit_compile(chunk_id) {
+            if let Some(result) = self.try_jit_execute(chunk_id, &args, &env)? {
+                return self.push(result);
+            }
+        }
+
+        let frame = self.call_stack.last_mut().ok_or_else(|| VMError::RuntimeError {
+            message: "Tail call outside of a function".to_string(),
+            stack_trace: None,
+        })?;
+        frame.chunk_id = chunk_id;
+        frame.ip = 0;
+        frame.env = env;
+        let stack_base = frame.stack_base;
+        self.stack.truncate(stack_base);
+        for arg in args {
+            self.push(arg)?;
+        }
         Ok(())
     }
     
@@ -2613,19 +3070,138 @@ impl VM {
     
     
     // Effect operations
-    pub fn perform_effect(&mut self, operation: String) -> VMResult<()> {
-        // Simplified implementation
-        Ok(())
+
+    /// Perform `effect.operation(args)`. The innermost installed handler for the operation
+    /// runs with the arguments (handlers for a whole effect also receive the operation name
+    /// first) and its result is the value of the perform; with no handler installed the
+    /// effect context's handler for the effect type runs instead.
+    pub fn perform_effect(
+        &mut self,
+        effect: String,
+        operation: String,
+        args: Vec<Value>,
+    ) -> VMResult<Value> {
+        let found = self.handler_stack.iter().enumerate().rev().find_map(|(depth, frame)| {
+            if let Some(handler) = frame.handlers.get(&(effect.clone(), Some(operation.clone()))) {
+                Some((depth, handler.clone(), false))
+            } else {
+                frame
+                    .handlers
+                    .get(&(effect.clone(), None))
+                    .map(|handler| (depth, handler.clone(), true))
+            }
+        });
+
+        match found {
+            Some((depth, handler, pass_operation)) => {
+                let mut handler_args = Vec::with_capacity(args.len() + 1);
+                if pass_operation {
+                    handler_args.push(Value::String(operation));
+                }
+                handler_args.extend(args);
+
+                // The handler runs outside its own frame, so it can perform the effect it handles
+                let inner = self.handler_stack.split_off(depth);
+                self.push(handler)?;
+                let arg_count = handler_args.len();
+                for arg in handler_args {
+                    self.push(arg)?;
+                }
+                let result = self.call_value(arg_count);
+                self.handler_stack.extend(inner);
+                result?;
+                self.pop()
+            }
+            None => {
+                let core_args: Vec<fluentai_core::value::Value> = args
+                    .iter()
+                    .map(|v| self.vm_value_to_core_value(v))
+                    .collect();
+                // The name was resolved when the program was parsed, so a name that is
+                // not built in is a declared effect, however close to a built-in one
+                let effect_type = EffectType::from_declared_name(&effect, |_| true).map_err(|e| VMError::RuntimeError {
+                    message: format!("Effect error: {}", e),
+                    stack_trace: Some(self.build_stack_trace()),
+                })?;
+                let result = self
+                    .effect_context
+                    .perform_sync(effect_type, &operation, &core_args)
+                    .map_err(|e| VMError::RuntimeError {
+                        message: format!("Effect error: {}", e),
+                        stack_trace: Some(self.build_stack_trace()),
+                    })?;
+                Ok(self.core_value_to_vm_value(&result))
+            }
+        }
     }
-    
-    pub fn install_effect_handlers(&mut self, handlers: Vec<Value>) -> VMResult<()> {
-        // Simplified implementation
+
+    /// Install a handler table created by `MakeHandler` for the dynamic extent of a body
+    pub fn install_effect_handlers(&mut self, table: Value) -> VMResult<()> {
+        let entries = match table {
+            Value::List(entries) => entries,
+            v => {
+                return Err(VMError::TypeError {
+                    operation: "install_handler".to_string(),
+                    expected: "handler table".to_string(),
+                    got: value_type_name(&v).to_string(),
+                    location: None,
+                    stack_trace: None,
+                })
+            }
+        };
+
+        let mut handlers = FxHashMap::default();
+        for entry in entries {
+            match entry {
+                Value::List(mut triple) if triple.len() == 3 => {
+                    let handler = triple.pop().unwrap_or(Value::Nil);
+                    let operation = match triple.pop() {
+                        Some(Value::String(op)) => Some(op),
+                        _ => None,
+                    };
+                    let effect = match triple.pop() {
+                        Some(Value::String(effect)) => effect,
+                        v => {
+                            return Err(VMError::TypeError {
+                                operation: "install_handler".to_string(),
+                                expected: "string for effect type".to_string(),
+                                got: v.as_ref().map_or("nil", value_type_name).to_string(),
+                                location: None,
+                                stack_trace: None,
+                            })
+                        }
+                    };
+                    handlers.insert((effect, operation), handler);
+                }
+                v => {
+                    return Err(VMError::TypeError {
+                        operation: "install_handler".to_string(),
+                        expected: "handler entry".to_string(),
+                        got: value_type_name(&v).to_string(),
+                        location: None,
+                        stack_trace: None,
+                    })
+                }
+            }
+        }
+
+        self.handler_stack.push(HandlerFrame {
+            handlers,
+            _return_ip: self.call_stack.last().map_or(0, |frame| frame.ip),
+            _stack_depth: self.stack.len(),
+        });
         Ok(())
     }
-    
+
+    /// Remove the innermost installed handler table
     pub fn uninstall_effect_handler(&mut self) -> VMResult<()> {
-        // Simplified implementation
-        Ok(())
+        match self.handler_stack.pop() {
+            Some(_) => Ok(()),
+            None => Err(VMError::RuntimeError {
+                message: "No handler to uninstall".to_string(),
+                stack_trace: Some(self.build_stack_trace()),
+            }),
+        }
     }
     
     pub fn resume_from_handler(&mut self, value: Value) -> VMResult<()> {
@@ -0,0 +1,38 @@
+//! Tests for byte-string literals in the VM
+
+mod common;
+
+use common::run;
+use fluentai_core::value::Value;
+
+#[test]
+fn test_byte_string_literal() {
+    assert_eq!(
+        run(r#"b"\x89PNG\r\n""#).unwrap(),
+        Value::Bytes(vec![0x89, b'P', b'N', b'G', b'\r', b'\n'])
+    );
+    assert_eq!(run(r#"b"""#).unwrap(), Value::Bytes(vec![]));
+}
+
+#[test]
+fn test_byte_string_equality() {
+    assert_eq!(run(r#"b"abc" == b"abc""#).unwrap(), Value::Boolean(true));
+    assert_eq!(run(r#"b"abc" == "abc""#).unwrap(), Value::Boolean(false));
+}
+
+#[test]
+fn test_byte_string_pattern() {
+    let code = r#"
+match(b"\x7fELF") {
+    b"\x7fELF" => "elf",
+    _ => "unknown"
+}
+"#;
+    assert_eq!(run(code).unwrap(), Value::String("elf".to_string()));
+}
+
+#[test]
+fn test_byte_string_display() {
+    let value = run(r#"b"a\"\x00""#).unwrap();
+    assert_eq!(value.to_string(), r#"b"a\"\x00""#);
+}
@@ -0,0 +1,35 @@
+//! Helpers shared by the integration tests that run FLC source end to end
+//!
+//! Each test binary uses only some of these.
+#![allow(dead_code)]
+
+use fluentai_core::value::Value;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    VM,
+};
+
+/// Parse, compile at `optimization_level` and run `code`, reporting any stage's
+/// error as a string
+pub fn run_with(code: &str, optimization_level: OptimizationLevel) -> Result<Value, String> {
+    let graph = fluentai_parser::parse(code).map_err(|e| e.to_string())?;
+    let options = CompilerOptions {
+        optimization_level,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .compile(&graph)
+        .map_err(|e| e.to_string())?;
+    VM::new(bytecode).run().map_err(|e| e.to_string())
+}
+
+/// Run `code` without optimization
+pub fn run(code: &str) -> Result<Value, String> {
+    run_with(code, OptimizationLevel::None)
+}
+
+/// Wrap statements in a function body so their `let` bindings scope over the rest of the block
+pub fn run_block(block: &str) -> Result<Value, String> {
+    run(&format!("private function main() {{ {} }}\nmain()", block))
+}
@@ -587,6 +587,7 @@ fn test_compile_match_expression() -> Result<()> {
                 (Pattern::Literal(Literal::Integer(42)), found),
                 (Pattern::Wildcard, not_found),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -1075,6 +1075,7 @@ fn test_compile_pattern_matching() -> Result<()> {
                 ),
                 (Pattern::Wildcard, zero),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -1114,6 +1115,7 @@ fn test_compile_pattern_matching_nil() -> Result<()> {
                 ),
                 (Pattern::Wildcard, not_empty_str),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -1167,6 +1169,7 @@ fn test_compile_pattern_matching_tail() -> Result<()> {
                 ),
                 (Pattern::Wildcard, empty_list),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -1232,6 +1235,7 @@ fn test_compile_pattern_matching_nested() -> Result<()> {
                 ),
                 (Pattern::Wildcard, zero),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -1287,6 +1291,7 @@ fn test_compile_pattern_matching_with_optimization() -> Result<()> {
                 ),
                 (Pattern::Wildcard, zero),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -1333,6 +1338,7 @@ fn test_compile_pattern_matching_with_optimization() -> Result<()> {
                 ),
                 (Pattern::Wildcard, not_empty_str),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -1385,6 +1391,7 @@ fn test_compile_pattern_matching_with_optimization() -> Result<()> {
                 ), // Return the expected tail
                 (Pattern::Wildcard, empty_list),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -0,0 +1,140 @@
+//! Tests for user-declared effects, handle blocks and effect-context dispatch
+
+use fluentai_core::value::Value;
+use fluentai_effects::{EffectHandler, EffectResult, EffectType};
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    VM,
+};
+use std::sync::Arc;
+
+fn compile(code: &str) -> VM {
+    let graph = fluentai_parser::parse(code).unwrap();
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options).compile(&graph).unwrap();
+    VM::new(bytecode)
+}
+
+fn run(code: &str) -> Result<Value, String> {
+    compile(code).run().map_err(|e| e.to_string())
+}
+
+const LOGGER: &str = r#"
+private effect Logger {
+    function log(msg: string) -> int;
+    function level() -> string;
+}
+"#;
+
+#[test]
+fn test_handle_custom_effect_operation() {
+    let code = format!(
+        "{}\nhandle {{ perform Logger.log(\"hi\") + 1 }} with {{ Logger.log(msg) => 41 }}",
+        LOGGER
+    );
+    assert_eq!(run(&code).unwrap(), Value::Integer(42));
+}
+
+#[test]
+fn test_handler_receives_arguments() {
+    let code = format!(
+        "{}\nhandle {{ perform Logger.log(\"abc\") }} with {{ Logger.log(msg) => msg + \"!\" }}",
+        LOGGER
+    );
+    assert_eq!(run(&code).unwrap(), Value::String("abc!".to_string()));
+}
+
+#[test]
+fn test_handler_scope_is_dynamic() {
+    let code = format!(
+        r#"{}
+private function emit() {{ perform Logger.level() }}
+private function main() {{
+    handle {{ emit() + "/" + emit() }} with {{ Logger.level() => "debug" }}
+}}
+main()"#,
+        LOGGER
+    );
+    assert_eq!(run(&code).unwrap(), Value::String("debug/debug".to_string()));
+}
+
+#[test]
+fn test_innermost_handler_wins_and_handlers_run_outside_their_scope() {
+    let code = format!(
+        r#"{}
+handle {{
+    handle {{ perform Logger.level() }} with {{ Logger.level() => "inner+" + perform Logger.level() }}
+}} with {{ Logger.level() => "outer" }}"#,
+        LOGGER
+    );
+    assert_eq!(run(&code).unwrap(), Value::String("inner+outer".to_string()));
+}
+
+#[test]
+fn test_unhandled_custom_effect_is_runtime_error() {
+    let code = format!("{}\nperform Logger.log(\"hi\")", LOGGER);
+    let err = run(&code).unwrap_err();
+    assert!(
+        err.contains("No handler registered for effect type Logger"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn test_declared_effects_close_to_builtin_names() {
+    // `Timer` and `Stats` are one edit from `Time` and `State`, but declared here
+    let code = r#"
+private effect Timer { function now() -> int; }
+private effect Stats { function count() -> int; }
+handle {
+    perform Timer.now() + perform Stats.count()
+} with {
+    Timer.now() => 40,
+    Stats.count() => 2
+}
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(42));
+
+    // Undeclared, it is still taken for a misspelling
+    let err = fluentai_parser::parse("perform Timer.now()").unwrap_err().to_string();
+    assert!(err.contains("did you mean Time?"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_unhandled_declared_effect_close_to_builtin_name() {
+    let code = "private effect Timer { function now() -> int; }\nperform Timer.now()";
+    let err = run(code).unwrap_err();
+    assert!(
+        err.contains("No handler registered for effect type Timer"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+struct Metrics;
+
+impl EffectHandler for Metrics {
+    fn effect_type(&self) -> EffectType {
+        EffectType::custom("Metrics")
+    }
+
+    fn handle_sync(&self, operation: &str, args: &[Value]) -> EffectResult {
+        match (operation, args) {
+            ("double", [Value::Integer(n)]) => Ok(Value::Integer(n * 2)),
+            _ => Ok(Value::Nil),
+        }
+    }
+}
+
+#[test]
+fn test_custom_effect_dispatches_to_registered_handler() {
+    let code = "private effect Metrics { function double(n: int) -> int; }\nperform Metrics.double(21)";
+    let mut vm = compile(code);
+    vm.get_effect_context().register_handler(Arc::new(Metrics));
+    assert_eq!(vm.run().unwrap(), Value::Integer(42));
+}
@@ -43,6 +43,7 @@ fn test_debug_compilation_issue() -> Result<()> {
                 ),
                 (Pattern::Wildcard, zero),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -116,3 +116,17 @@ x() + y()
 
     assert_eq!(result, Value::Integer(30));
 }
+
+#[test]
+fn test_define_function_tail_recursive() {
+    // Tail calls reuse the caller's frame, so deep tail recursion does not grow the stack
+    let code = r#"
+private function count(n, acc) {
+    if (n == 0) { acc } else { count(n - 1, acc + 1) }
+}
+count(100000, 0)
+    "#;
+
+    let result = compile_and_run(code).unwrap();
+    assert_eq!(result, Value::Integer(100000));
+}
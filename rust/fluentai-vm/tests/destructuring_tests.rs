@@ -0,0 +1,183 @@
+//! Tests for pattern destructuring in `let`, function parameters and `for` loops
+
+mod common;
+
+use common::{run, run_block, run_with};
+use fluentai_core::value::Value;
+use fluentai_optimizer::OptimizationLevel;
+
+fn ints(values: &[i64]) -> Value {
+    Value::List(values.iter().map(|&n| Value::Integer(n)).collect())
+}
+
+#[test]
+fn test_let_list_pattern() {
+    let code = "let [a, b, c] = [1, 2, 3]; a * 100 + b * 10 + c";
+    assert_eq!(run_block(code).unwrap(), Value::Integer(123));
+}
+
+#[test]
+fn test_let_list_pattern_with_rest() {
+    let code = "let [first, ...rest] = [1, 2, 3]; [first, rest]";
+    assert_eq!(
+        run_block(code).unwrap(),
+        Value::List(vec![Value::Integer(1), ints(&[2, 3])])
+    );
+}
+
+#[test]
+fn test_let_constructor_pattern() {
+    let code = r#"
+let Pair(left, right) = Pair(3, 4);
+left * right
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(12));
+}
+
+#[test]
+fn test_let_nested_pattern() {
+    let code = r#"
+let Some([x, _, z]) = Some([1, 2, 3]);
+let total = x + z;
+total
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(4));
+}
+
+#[test]
+fn test_let_as_pattern() {
+    let code = "let [head, ..._] as all = [5, 6]; [head, all]";
+    assert_eq!(
+        run_block(code).unwrap(),
+        Value::List(vec![Value::Integer(5), ints(&[5, 6])])
+    );
+}
+
+#[test]
+fn test_let_record_destructuring() {
+    let code = r#"
+private struct Point { x: Int, y: Int }
+private function main() {
+    let {x, y} = Point { x: 3, y: 4 };
+    x * y
+}
+main()
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(12));
+}
+
+#[test]
+fn test_let_expression_with_pattern() {
+    let code = "let [a, b] = [20, 22]; a + b";
+    assert_eq!(run(code).unwrap(), Value::Integer(42));
+}
+
+#[test]
+fn test_refutable_let_raises_match_error() {
+    let err = run_block("let [a, b] = [1, 2, 3]; a + b").unwrap_err();
+    assert!(err.contains("Match error"), "unexpected error: {}", err);
+
+    let err = run_block("let Some(x) = Fail(0); x").unwrap_err();
+    assert!(err.contains("Match error"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_refutable_for_pattern_raises_match_error() {
+    let err = run_block("let s = 0; for [a, b] in [[1, 2], [3]] { s := s + a }; s").unwrap_err();
+    assert!(err.contains("Match error"), "unexpected error: {}", err);
+
+    let err = run_with(
+        "for Some(x) in [Some(1), Fail(2)] { x }",
+        OptimizationLevel::Standard,
+    )
+    .unwrap_err();
+    assert!(err.contains("Match error"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_refutable_let_raises_when_optimized() {
+    let err = run_with("let [a, b] = [1, 2, 3]; a + b", OptimizationLevel::Standard).unwrap_err();
+    assert!(err.contains("Match error"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_match_without_matching_branch_produces_nil() {
+    assert_eq!(run("match 3 { 1 => \"one\", 2 => \"two\" }").unwrap(), Value::Nil);
+    assert_eq!(run("match([1, 2, 3]) { [a, b] => a + b }").unwrap(), Value::Nil);
+}
+
+#[test]
+fn test_function_parameter_patterns() {
+    let code = r#"
+private function add_pair(Pair(a, b), [c, ..._]) { a + b + c }
+add_pair(Pair(1, 2), [3, 4])
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(6));
+}
+
+#[test]
+fn test_lambda_parameter_patterns() {
+    let code = r#"
+private function main() {
+    let offset = 100;
+    let f = ([x, y]) => x + y + offset;
+    f([1, 2])
+}
+main()
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(103));
+}
+
+#[test]
+fn test_refutable_parameter_raises_match_error() {
+    let code = "private function head_of([x, ..._]) { x }\nhead_of([])";
+    let err = run(code).unwrap_err();
+    assert!(err.contains("Match error"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_for_with_list_pattern() {
+    let code = r#"
+let total = 0;
+for [a, b] in [[1, 2], [3, 4]] { total := total + a * b };
+total
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(14));
+}
+
+#[test]
+fn test_match_nested_patterns() {
+    let code = r#"
+private function describe(value) {
+    match value {
+        Some([0, ...rest]) => 1000 + length(rest),
+        Some([x, y]) when (x > y) => x - y,
+        Some(_) => 0,
+        Fail(code) => 0 - code
+    }
+}
+[describe(Some([0, 7, 8])), describe(Some([9, 4])), describe(Some([1, 2])), describe(Fail(3))]
+"#;
+    assert_eq!(run(code).unwrap(), ints(&[1002, 5, 0, -3]));
+}
+
+#[test]
+fn test_or_pattern_bindings() {
+    let code = r#"
+private function other(pair) {
+    match pair {
+        Pair(a, 0) | Pair(0, a) => a,
+        _ => -1
+    }
+}
+[other(Pair(5, 0)), other(Pair(0, 6)), other(Pair(1, 2))]
+"#;
+    assert_eq!(run(code).unwrap(), ints(&[5, 6, -1]));
+}
+
+#[test]
+fn test_destructuring_with_default_optimization() {
+    let code = "let [a, ...rest] = [1, 2, 3]; a + length(rest)";
+    assert_eq!(run_with(code, OptimizationLevel::Standard).unwrap(), Value::Integer(3));
+}
+
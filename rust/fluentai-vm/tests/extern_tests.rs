@@ -0,0 +1,114 @@
+//! Tests for `extern` foreign function declarations
+
+use fluentai_core::value::Value;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    Capability, ExternDecl, SecurityManager, SecurityPolicy, VM,
+};
+use std::sync::Arc;
+
+fn compile_with(code: &str, optimization_level: OptimizationLevel) -> Result<VM, String> {
+    let graph = fluentai_parser::parse(code).map_err(|e| e.to_string())?;
+    let options = CompilerOptions {
+        optimization_level,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .compile(&graph)
+        .map_err(|e| e.to_string())?;
+    Ok(VM::new(bytecode))
+}
+
+fn compile(code: &str) -> Result<VM, String> {
+    compile_with(code, OptimizationLevel::None)
+}
+
+/// Resolve `shout` to a native function upper-casing its argument, and nothing else
+fn host(decl: &ExternDecl) -> Option<Value> {
+    (decl.name == "shout").then(|| Value::NativeFunction {
+        name: "shout".to_string(),
+        arity: 1,
+        function: Arc::new(|args| match &args[0] {
+            Value::String(s) => Ok(Value::String(s.to_uppercase())),
+            _ => Ok(Value::Nil),
+        }),
+    })
+}
+
+#[test]
+fn test_extern_declarations_are_recorded_in_bytecode() {
+    let code = r#"
+extern function shout(text: String) -> String
+extern function add(a: Int, b: Int) -> Int
+nil
+"#;
+    let vm = compile(code).unwrap();
+    assert_eq!(
+        vm.bytecode().externs,
+        vec![
+            ExternDecl { name: "add".to_string(), arity: 2 },
+            ExternDecl { name: "shout".to_string(), arity: 1 },
+        ]
+    );
+}
+
+#[test]
+fn test_linked_extern_is_callable() {
+    let code = r#"
+extern function shout(text: String) -> String
+private function greet(name) { shout(name) }
+greet("world")
+"#;
+    let mut vm = compile(code).unwrap();
+    vm.link_externs(host).unwrap();
+    assert_eq!(vm.run().unwrap(), Value::String("WORLD".to_string()));
+}
+
+#[test]
+fn test_extern_with_default_optimization() {
+    let code = "extern function shout(text: String) -> String\nshout(\"hi\")";
+    let mut vm = compile_with(code, OptimizationLevel::Standard).unwrap();
+    vm.link_externs(host).unwrap();
+    assert_eq!(vm.run().unwrap(), Value::String("HI".to_string()));
+}
+
+#[test]
+fn test_unresolved_extern_fails_to_link() {
+    let mut vm = compile("extern function missing(x: Int) -> Int\nnil").unwrap();
+    let err = vm.link_externs(host).unwrap_err().to_string();
+    assert!(err.contains("Unresolved extern function 'missing'"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_extern_arity_must_match_host_function() {
+    let mut vm = compile("extern function shout(a: String, b: String) -> String\nnil").unwrap();
+    let err = vm.link_externs(host).unwrap_err().to_string();
+    assert!(err.contains("declared with 2 parameters"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_conflicting_extern_declarations() {
+    let code = "extern function f(x: Int) -> Int\nextern function f(x: Int, y: Int) -> Int\nnil";
+    let err = compile(code).err().unwrap();
+    assert!(err.contains("Conflicting declarations"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_extern_requires_capability_under_security_policy() {
+    let code = "extern function shout(text: String) -> String\nshout(\"hi\")";
+
+    let mut vm = compile(code).unwrap();
+    vm.with_security_policy(SecurityPolicy::sandbox());
+    let err = vm.link_externs(host).unwrap_err().to_string();
+    assert!(err.contains("No capability"), "unexpected error: {}", err);
+
+    let manager = SecurityManager::sandbox();
+    manager.context.grant_capability(Capability::HostFunction {
+        names: vec!["shout".to_string()],
+    });
+    let mut vm = compile(code).unwrap();
+    vm.set_security_manager(Arc::new(manager));
+    vm.link_externs(host).unwrap();
+    assert_eq!(vm.run().unwrap(), Value::String("HI".to_string()));
+}
@@ -0,0 +1,219 @@
+//! Tests for field assignment on records and actor state
+
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    safety::ActorId,
+    Value, VM,
+};
+
+fn compile_with(code: &str, optimization_level: OptimizationLevel) -> VM {
+    let graph = fluentai_parser::parse(code).expect("Parse failed");
+    let options = CompilerOptions {
+        optimization_level,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .compile(&graph)
+        .expect("Compile failed");
+    VM::new(bytecode)
+}
+
+fn run(code: &str) -> Value {
+    compile_with(code, OptimizationLevel::None)
+        .run()
+        .expect("Run failed")
+}
+
+#[test]
+fn test_field_assignment_on_local_record() {
+    let code = r#"
+private struct Point { x: int, y: int }
+private function main() {
+    let p = Point { x: 1, y: 2 };
+    p.x := 10;
+    p.x + p.y
+}
+main()
+    "#;
+
+    assert_eq!(run(code), Value::Integer(12));
+}
+
+#[test]
+fn test_field_assignment_returns_assigned_value() {
+    let code = r#"
+private struct Point { x: int, y: int }
+private function main() {
+    let p = Point { x: 1, y: 2 };
+    5 + (p.y := 20)
+}
+main()
+    "#;
+
+    assert_eq!(run(code), Value::Integer(25));
+}
+
+#[test]
+fn test_nested_field_assignment() {
+    let code = r#"
+private struct Point { x: int, y: int }
+private struct Line { start: Point, end: Point }
+private function main() {
+    let line = Line { start: Point { x: 0, y: 0 }, end: Point { x: 1, y: 1 } };
+    line.start.x := 7;
+    line
+}
+main()
+    "#;
+
+    match run(code) {
+        Value::Struct { fields, .. } => {
+            let start = &fields[0].1;
+            assert_eq!(start.get_field("x").unwrap(), &Value::Integer(7));
+            assert_eq!(start.get_field("y").unwrap(), &Value::Integer(0));
+        }
+        other => panic!("Expected struct, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_field_assignment_does_not_alias() {
+    let code = r#"
+private struct Point { x: int, y: int }
+private function main() {
+    let p = Point { x: 1, y: 2 };
+    let q = p;
+    p.x := 10;
+    q.x
+}
+main()
+    "#;
+
+    assert_eq!(run(code), Value::Integer(1));
+}
+
+#[test]
+fn test_field_assignment_unknown_field_error() {
+    let code = r#"
+private struct Point { x: int, y: int }
+private function main() {
+    let p = Point { x: 1, y: 2 };
+    p.z := 3
+}
+main()
+    "#;
+
+    let result = compile_with(code, OptimizationLevel::None).run();
+    assert!(result.unwrap_err().to_string().contains("no field 'z'"));
+}
+
+#[test]
+fn test_actor_handler_updates_state_field() {
+    let code = r#"
+private struct Tally { count: int, last: int }
+private actor Counter {
+    state: Tally = Tally { count: 0, last: 0 };
+    private handle message(msg: int) {
+        self.count := self.count + 1;
+        self.last := msg;
+        "ok"
+    }
+}
+Counter
+    "#;
+
+    let mut vm = compile_with(code, OptimizationLevel::None);
+    let actor_id = match vm.run().expect("Run failed") {
+        Value::Actor(id) => ActorId(id),
+        other => panic!("Expected actor, got {:?}", other),
+    };
+
+    vm.send_to_actor(actor_id, Value::Integer(5)).unwrap();
+    vm.send_to_actor(actor_id, Value::Integer(9)).unwrap();
+    vm.process_all_actor_messages().unwrap();
+
+    let state = vm.get_actor_state(actor_id).expect("actor state");
+    assert_eq!(state.get_field("count").unwrap(), &Value::Integer(2));
+    assert_eq!(state.get_field("last").unwrap(), &Value::Integer(9));
+}
+
+#[test]
+fn test_failed_actor_handler_discards_its_state_change() {
+    // Message 0 updates the state and then fails, so the update must not survive
+    let code = r#"
+private struct Tally { count: int }
+private actor Counter {
+    state: Tally = Tally { count: 0 };
+    private handle message(msg: int) {
+        if (msg == 0) {
+            (self.count := 100) / msg
+        } else {
+            self
+        }
+    }
+}
+Counter
+    "#;
+
+    let mut vm = compile_with(code, OptimizationLevel::None);
+    let actor_id = match vm.run().expect("Run failed") {
+        Value::Actor(id) => ActorId(id),
+        other => panic!("Expected actor, got {:?}", other),
+    };
+
+    vm.send_to_actor(actor_id, Value::Integer(0)).unwrap();
+    assert!(vm.process_all_actor_messages().is_err());
+
+    vm.send_to_actor(actor_id, Value::Integer(5)).unwrap();
+    vm.process_all_actor_messages().unwrap();
+    let state = vm.get_actor_state(actor_id).expect("actor state");
+    assert_eq!(state.get_field("count").unwrap(), &Value::Integer(0));
+}
+
+#[test]
+fn test_assignment_to_captured_variable_is_rejected() {
+    let code = r#"
+private function main() {
+    let x = 1;
+    let set = () => { x := 2 };
+    set();
+    x
+}
+main()
+    "#;
+
+    let graph = fluentai_parser::parse(code).expect("Parse failed");
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    let err = Compiler::with_options(options).compile(&graph).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Cannot assign to captured variable 'x'"));
+}
+
+#[test]
+fn test_assignment_through_captured_letrec_cell() {
+    let code = r#"
+private function main() {
+    (let rec x = 1; let set = () => { x := 5 }; { set(); x })
+}
+main()
+    "#;
+
+    assert_eq!(run(code), Value::Integer(5));
+}
+
+#[test]
+fn test_capture_of_plain_binding_shadowing_a_cell() {
+    let code = r#"
+private function main() {
+    (let rec x = 1; let x = 7; let get = () => x; get())
+}
+main()
+    "#;
+
+    assert_eq!(run(code), Value::Integer(7));
+}
@@ -0,0 +1,140 @@
+//! Tests for JIT code specialized on interpreter type feedback, and deoptimization
+#![cfg(all(feature = "jit", target_arch = "x86_64"))]
+
+use fluentai_core::ast::NodeId;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_parser::parse;
+use fluentai_vm::{jit_integration::JitConfig, Compiler, CompilerOptions, Value, VM};
+use std::num::NonZeroU32;
+
+/// Compile without optimization and JIT-compile the given chunks after their first call
+fn jit_vm(source: &str, hot_chunks: &[usize]) -> VM {
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .compile(&parse(source).unwrap())
+        .unwrap();
+
+    let mut vm = VM::new(bytecode);
+    vm.set_jit_config(JitConfig {
+        call_threshold: 1,
+        ..Default::default()
+    });
+    vm.enable_usage_tracking();
+    for &chunk_id in hot_chunks {
+        let node_id = NodeId(NonZeroU32::new(chunk_id as u32).unwrap());
+        vm.register_chunk_mapping(chunk_id, node_id);
+    }
+    vm
+}
+
+#[test]
+fn test_float_feedback_compiles_float_code() {
+    // The interpreted first call sees floats, so the compiled code multiplies floats
+    let source = r#"
+private function scale(x) { x * 2.5 }
+scale(2.0) + scale(4.0)"#;
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Float(15.0));
+
+    let stats = vm.jit_stats();
+    assert_eq!(stats.jit_execution_count, 1);
+    assert_eq!(stats.deoptimization_count, 0);
+}
+
+#[test]
+fn test_failed_int_guard_resumes_in_interpreter() {
+    // `add` is compiled for integers, then called with floats
+    let source = r#"
+private function add(a, b) { a + b }
+[add(add(1, 2), 4), add(0.5, 2.0)]"#;
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(
+        vm.run().unwrap(),
+        Value::List(vec![Value::Integer(7), Value::Float(2.5)])
+    );
+
+    let stats = vm.jit_stats();
+    assert_eq!(stats.jit_execution_count, 2);
+    assert_eq!(stats.deoptimization_count, 1);
+}
+
+#[test]
+fn test_deoptimized_function_is_recompiled_with_new_feedback() {
+    // After deoptimizing, the addition has seen both integers and floats, so the
+    // recompiled code handles either without another deoptimization
+    let source = r#"
+private function add(a, b) { a + b }
+[add(1, 2), add(3, 4), add(0.5, 2.0), add(5, 6), add(1.5, 1.0)]"#;
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(
+        vm.run().unwrap(),
+        Value::List(vec![
+            Value::Integer(3),
+            Value::Integer(7),
+            Value::Float(2.5),
+            Value::Integer(11),
+            Value::Float(2.5),
+        ])
+    );
+
+    let stats = vm.jit_stats();
+    assert_eq!(stats.functions_compiled, 2);
+    assert_eq!(stats.deoptimization_count, 1);
+}
+
+#[test]
+fn test_integer_overflow_deoptimizes() {
+    // 2^59 fits a tagged integer, but doubling it does not
+    let source = r#"
+private function double(x) { x + x }
+double(1) + double(576460752303423488)"#;
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(1152921504606846978));
+    assert_eq!(vm.jit_stats().deoptimization_count, 1);
+}
+
+#[test]
+fn test_division_by_zero_is_raised_by_the_interpreter() {
+    let source = r#"
+private function div(a, b) { a / b }
+div(6, 3) + div(1, 0)"#;
+    let mut vm = jit_vm(source, &[1]);
+    assert!(vm.run().is_err());
+    assert_eq!(vm.jit_stats().deoptimization_count, 1);
+}
+
+#[test]
+fn test_integers_too_big_to_tag_stay_exact() {
+    // 2^61 does not fit a tagged integer, so it is boxed in compiled code and
+    // arithmetic on it deoptimizes
+    let source = r#"
+private function same(x) { x }
+private function add(a, b) { a + b }
+private function equal(a, b) { a == b }
+private function quot(a, b) { a / b }
+[same(1), same(2305843009213693952), add(1, 2), add(2305843009213693952, 1),
+ equal(1, 1), equal(2305843009213693952, 2305843009213693952), equal(2305843009213693952, 0),
+ quot(6, 3), quot(0 - 1152921504606846976, 0 - 1)]"#;
+    let mut vm = jit_vm(source, &[1, 2, 3, 4]);
+    assert_eq!(
+        vm.run().unwrap(),
+        Value::List(vec![
+            Value::Integer(1),
+            Value::Integer(2305843009213693952),
+            Value::Integer(3),
+            Value::Integer(2305843009213693953),
+            Value::Boolean(true),
+            Value::Boolean(true),
+            Value::Boolean(false),
+            Value::Integer(2),
+            Value::Integer(1152921504606846976),
+        ])
+    );
+
+    let stats = vm.jit_stats();
+    assert_eq!(stats.jit_execution_count, 5);
+    assert_eq!(stats.deoptimization_count, 2);
+}
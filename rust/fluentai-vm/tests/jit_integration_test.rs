@@ -1,119 +1,126 @@
 //! Tests for JIT compilation integration with the VM
+#![cfg(all(feature = "jit", target_arch = "x86_64"))]
 
-#[cfg(all(feature = "jit", target_arch = "x86_64"))]
-use fluentai_core::ast::{Graph, Literal, Node, NodeId};
-#[cfg(all(feature = "jit", target_arch = "x86_64"))]
+use fluentai_core::ast::NodeId;
+use fluentai_optimizer::OptimizationLevel;
 use fluentai_parser::parse;
-#[cfg(all(feature = "jit", target_arch = "x86_64"))]
-use fluentai_vm::{Bytecode, Compiler, VM, Value};
-#[cfg(all(feature = "jit", target_arch = "x86_64"))]
+use fluentai_vm::{jit_integration::JitConfig, Compiler, CompilerOptions, Value, VM};
 use std::num::NonZeroU32;
 
-#[test]
-#[cfg(all(feature = "jit", target_arch = "x86_64"))]
-fn test_jit_hot_path_compilation() {
-    // Create a simple recursive factorial function
-    let source = r#"
-        (define (factorial n)
-            (if (<= n 1)
-                1
-                (* n (factorial (- n 1)))))
-    "#;
-    
-    let ast = parse(source).unwrap();
-    let compiler = Compiler::new();
-    let bytecode = compiler.compile(&ast).unwrap();
-    
+/// Compile `source` and JIT-compile chunk 1 once it has been called `call_threshold` times
+fn jit_vm(source: &str, call_threshold: u64) -> VM {
+    jit_vm_with_chunks(source, call_threshold, &[1])
+}
+
+/// Like [`jit_vm`], but JIT-compile the given chunks; the others stay interpreted
+fn jit_vm_with_chunks(source: &str, call_threshold: u64, chunks: &[usize]) -> VM {
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .compile(&parse(source).unwrap())
+        .unwrap();
+
     let mut vm = VM::new(bytecode);
-    
-    // Enable usage tracking
+    vm.set_jit_config(JitConfig {
+        call_threshold,
+        ..Default::default()
+    });
     vm.enable_usage_tracking();
-    
-    // Register chunk mappings for tracking
-    let node_id = NodeId(NonZeroU32::new(1).unwrap());
-    vm.register_chunk_mapping(1, node_id); // Assuming factorial is chunk 1
-    
-    // Call factorial multiple times to trigger JIT compilation
-    for i in 1..100 {
-        // Create AST for factorial call
-        let mut graph = Graph::new();
-        let n = graph.add_node(Node::Literal(Literal::Integer(5)));
-        let fact_sym = graph.add_node(Node::Variable { name: "factorial".to_string() });
-        let call = graph.add_node(Node::Application {
-            function: fact_sym,
-            args: vec![n],
-        });
-        graph.root = Some(call);
-        
-        // Compile and run
-        let call_bytecode = compiler.compile(&graph).unwrap();
-        let mut call_vm = VM::new(call_bytecode);
-        
-        // Copy globals from main VM
-        if let Ok(globals) = vm.get_globals() {
-            call_vm.set_globals(globals);
-        }
-        
-        let result = call_vm.run().unwrap();
-        assert_eq!(result, Value::Integer(120)); // 5! = 120
-        
-        // After 50 calls, check if JIT compilation happened
-        if i == 51 {
-            let stats = vm.jit_stats();
-            assert!(stats.functions_compiled > 0, "Expected JIT compilation after 50 calls");
-        }
+    for &chunk_id in chunks {
+        vm.register_chunk_mapping(chunk_id, NodeId(NonZeroU32::new(chunk_id as u32).unwrap()));
     }
-    
-    // Verify JIT statistics
-    let final_stats = vm.jit_stats();
-    println!("JIT Stats: {:?}", final_stats);
-    assert!(final_stats.functions_compiled > 0);
-    assert_eq!(final_stats.compilation_failures, 0);
+    vm
+}
+
+#[test]
+fn test_jit_hot_path_compilation() {
+    // The first calls are interpreted, later ones run the compiled code
+    let source = r#"
+private function square(x) { x * x }
+[square(1), square(2), square(3), square(4), square(5), square(6), square(7), square(8)]"#;
+    let mut vm = jit_vm(source, 5);
+    assert_eq!(
+        vm.run().unwrap(),
+        Value::List((1..=8).map(|x| Value::Integer(x * x)).collect())
+    );
+
+    let stats = vm.jit_stats();
+    assert_eq!(stats.functions_compiled, 1);
+    assert!(
+        stats.jit_execution_count > 0,
+        "Expected compiled code to run"
+    );
+    assert!(
+        stats.jit_execution_count < 8,
+        "Expected early calls to be interpreted"
+    );
+    assert_eq!(stats.compilation_failures, 0);
 }
 
 #[test]
-#[cfg(all(feature = "jit", target_arch = "x86_64"))]
 fn test_jit_simple_arithmetic() {
-    // Create a simple function that does arithmetic
     let source = r#"
-        (define (compute x y)
-            (+ (* x x) (* y y)))
-    "#;
-    
-    let ast = parse(source).unwrap();
-    let compiler = Compiler::new();
-    let bytecode = compiler.compile(&ast).unwrap();
-    
-    let mut vm = VM::new(bytecode);
-    vm.enable_usage_tracking();
-    
-    // Register chunk mapping
-    let node_id = NodeId(NonZeroU32::new(1).unwrap());
-    vm.register_chunk_mapping(1, node_id);
-    
-    // Call the function enough times to trigger JIT
-    for _ in 0..60 {
-        let mut graph = Graph::new();
-        let x = graph.add_node(Node::Literal(Literal::Integer(3)));
-        let y = graph.add_node(Node::Literal(Literal::Integer(4)));
-        let compute = graph.add_node(Node::Variable { name: "compute".to_string() });
-        let call = graph.add_node(Node::Application {
-            function: compute,
-            args: vec![x, y],
-        });
-        graph.root = Some(call);
-        
-        let call_bytecode = compiler.compile(&graph).unwrap();
-        let mut call_vm = VM::new(call_bytecode);
-        
-        if let Ok(globals) = vm.get_globals() {
-            call_vm.set_globals(globals);
-        }
-        
-        let result = call_vm.run().unwrap();
-        assert_eq!(result, Value::Integer(25)); // 3*3 + 4*4 = 9 + 16 = 25
-    }
-    
+private function compute(x, y) { x * x + y * y }
+[compute(3, 4), compute(3, 4), compute(5, 12)]"#;
+    let mut vm = jit_vm(source, 1);
+    assert_eq!(
+        vm.run().unwrap(),
+        Value::List(vec![
+            Value::Integer(25),
+            Value::Integer(25),
+            Value::Integer(169),
+        ])
+    );
+
     let stats = vm.jit_stats();
-    assert!(stats.functions_compiled > 0, "Expected JIT compilation");
-}
\ No newline at end of file
+    assert_eq!(stats.functions_compiled, 1);
+    assert_eq!(stats.jit_execution_count, 2);
+}
+
+#[test]
+fn test_jit_returns_booleans() {
+    let source = "{ let lt = (a, b) => a < b; [lt(1, 2), lt(3, 2), lt(5, 6)] }";
+    let mut vm = jit_vm(source, 1);
+    assert_eq!(
+        vm.run().unwrap(),
+        Value::List(vec![
+            Value::Boolean(true),
+            Value::Boolean(false),
+            Value::Boolean(true),
+        ])
+    );
+    assert_eq!(vm.jit_stats().jit_execution_count, 2);
+}
+
+#[test]
+fn test_jit_branches_on_interpreted_callee_results() {
+    // Only the caller is compiled, so false and nil come back from the interpreter
+    for falsy in ["false", "nil"] {
+        let source = format!(
+            "{{ let no = (x) => {}; let g = (x) => if (no(x)) {{ 1 }} else {{ 2 }}; [g(1), g(2), g(3)] }}",
+            falsy
+        );
+        let mut vm = jit_vm_with_chunks(&source, 1, &[2]);
+        assert_eq!(
+            vm.run().unwrap(),
+            Value::List(vec![Value::Integer(2); 3]),
+            "callee returning {}",
+            falsy
+        );
+        assert_eq!(vm.jit_stats().jit_execution_count, 2);
+    }
+}
+
+#[test]
+fn test_jit_branches_like_the_interpreter() {
+    // Empty lists and zero are falsy in the VM
+    let source = "{ let f = (x) => if (x) { 1 } else { 2 }; [f([]), f([1]), f([]), f(0), f(3), f(nil), f(true)] }";
+    let mut vm = jit_vm(source, 1);
+    assert_eq!(
+        vm.run().unwrap(),
+        Value::List([2, 1, 2, 2, 1, 2, 1].into_iter().map(Value::Integer).collect())
+    );
+    assert_eq!(vm.jit_stats().jit_execution_count, 6);
+}
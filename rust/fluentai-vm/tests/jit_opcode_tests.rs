@@ -0,0 +1,180 @@
+//! Tests for JIT-compiled functions using globals, cells, tagged values, effects and tail calls
+#![cfg(all(feature = "jit", target_arch = "x86_64"))]
+
+use fluentai_core::ast::NodeId;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_parser::parse;
+use fluentai_vm::{jit_integration::JitConfig, Compiler, CompilerOptions, Value, VM};
+use std::num::NonZeroU32;
+
+/// Compile `source` without optimization
+fn compile(source: &str) -> VM {
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .compile(&parse(source).unwrap())
+        .unwrap();
+    VM::new(bytecode)
+}
+
+/// Run `source` in the interpreter only
+fn interpret(source: &str) -> Value {
+    let mut vm = compile(source);
+    vm.set_jit_config(JitConfig {
+        enabled: false,
+        ..Default::default()
+    });
+    vm.run().unwrap()
+}
+
+/// Compile without optimization and JIT-compile the given chunks after their first call
+fn jit_vm(source: &str, hot_chunks: &[usize]) -> VM {
+    let mut vm = compile(source);
+    vm.set_jit_config(JitConfig {
+        call_threshold: 1,
+        ..Default::default()
+    });
+    vm.enable_usage_tracking();
+    for &chunk_id in hot_chunks {
+        let node_id = NodeId(NonZeroU32::new(chunk_id as u32).unwrap());
+        vm.register_chunk_mapping(chunk_id, node_id);
+    }
+    vm
+}
+
+#[test]
+fn test_compiled_function_loads_globals() {
+    // `quad` (chunk 2) looks up `double` with LoadGlobal through the interpreter stub
+    let source = r#"
+private function double(x) { x * 2 }
+private function quad(x) { double(double(x)) }
+quad(1) + quad(2)"#;
+    let mut vm = jit_vm(source, &[2]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(12));
+
+    let stats = vm.jit_stats();
+    assert_eq!(stats.functions_compiled, 1);
+    assert_eq!(stats.compilation_failures, 0);
+    assert_eq!(stats.jit_execution_count, 1);
+}
+
+#[test]
+fn test_compiled_tail_calls() {
+    // The first call takes the base case in the interpreter; the second recurses in
+    // compiled code, with each tail call jumping back to the start of the chunk
+    let source = r#"
+private function sum(n, acc) { if (n == 0) { acc } else { sum(n - 1, acc + n) } }
+sum(0, 7) + sum(100, 0)"#;
+    assert_eq!(interpret(source), Value::Integer(5057));
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(5057));
+    assert_eq!(vm.jit_stats().jit_execution_count, 1);
+}
+
+#[test]
+fn test_deep_tail_recursion() {
+    let source = r#"
+private function down(n) { if (n == 0) { 0 } else { down(n - 1) } }
+down(0) + down(200000)"#;
+    assert_eq!(interpret(source), Value::Integer(0));
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(0));
+    assert_eq!(vm.jit_stats().jit_execution_count, 1);
+
+    // A closure that tail calls itself through a captured cell loops as well
+    let source = "let rec count = (n, acc) => if (n == 0) { acc } else { count(n - 1, acc + 1) }; count(0, 0) + count(100000, 0)";
+    assert_eq!(interpret(source), Value::Integer(100000));
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(100000));
+    assert_eq!(vm.jit_stats().jit_execution_count, 1);
+}
+
+#[test]
+fn test_compiled_recursion_through_cells() {
+    // `fact` reaches itself through a captured `let rec` cell
+    let source =
+        "let rec fact = (n) => if (n == 0) { 1 } else { n * fact(n - 1) }; fact(0) + fact(5)";
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(121));
+    assert_eq!(vm.jit_stats().compilation_failures, 0);
+}
+
+#[test]
+fn test_compiled_pattern_matching_on_tagged_values() {
+    let source = "{ let unwrap = (o) => match(o) { Some(x) => x, None => 0 }; unwrap(Some(1)) + unwrap(Some(41)) + unwrap(None()) }";
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(42));
+    assert_eq!(vm.jit_stats().jit_execution_count, 2);
+
+    // A failed let pattern raises from compiled code
+    let source =
+        "{ let unwrap = (o) => { let Some(x) = o; x }; unwrap(Some(1)) + unwrap(Other(2)) }";
+    let mut vm = jit_vm(source, &[1]);
+    let err = vm.run().unwrap_err();
+    assert!(err.to_string().contains("Match error"), "{}", err);
+}
+
+#[test]
+fn test_compiled_branches_on_interpreted_tests() {
+    // IsTuple runs in the interpreter stub, so its booleans come back from the VM
+    let source =
+        "{ let f = (t) => match(t) { (a, b) => a + b, _ => 100 }; [f((2, 3)), f(5), f((4, 5))] }";
+    let expected = Value::List(vec![Value::Integer(5), Value::Integer(100), Value::Integer(9)]);
+    assert_eq!(interpret(source), expected);
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), expected);
+    assert_eq!(vm.jit_stats().jit_execution_count, 2);
+}
+
+#[test]
+fn test_compiled_list_operations() {
+    // List opcodes run in the interpreter stub; each function is compiled after its first call
+    let source = r#"
+private function front(xs) { head(xs) }
+private function pair(n) { [n, n] }
+private function behind(xs) { tail(cons(0, xs)) }
+private function size(xs) { match(xs) { [] => 0, _ => length(xs) } }
+[front([7, 8]), front([9]), pair(1), pair(2), behind([2, 3]), behind([]), size([]), size([1, 2, 3])]"#;
+    let list = |items: &[i64]| Value::List(items.iter().map(|&n| Value::Integer(n)).collect());
+    let expected = Value::List(vec![
+        Value::Integer(7),
+        Value::Integer(9),
+        list(&[1, 1]),
+        list(&[2, 2]),
+        list(&[2, 3]),
+        list(&[]),
+        Value::Integer(0),
+        Value::Integer(3),
+    ]);
+    assert_eq!(interpret(source), expected);
+    let mut vm = jit_vm(source, &[1, 2, 3, 4]);
+    assert_eq!(vm.run().unwrap(), expected);
+
+    let stats = vm.jit_stats();
+    assert_eq!(stats.compilation_failures, 0);
+    assert_eq!(stats.jit_execution_count, 4);
+
+    // Errors are raised by the interpreter, as they are for interpreted code
+    let mut vm = jit_vm(
+        "private function front(xs) { head(xs) }\nfront([1]) + front([])",
+        &[1],
+    );
+    let err = vm.run().unwrap_err();
+    assert!(err.to_string().contains("empty list"), "{}", err);
+}
+
+#[test]
+fn test_compiled_function_performs_effects() {
+    // `log_twice` (chunk 1) performs through the handler installed by the interpreter
+    let source = r#"
+private effect Logger {
+    function log(msg: int) -> int;
+}
+private function log_twice(x) { perform Logger.log(x) + perform Logger.log(x) }
+handle { log_twice(1) + log_twice(2) } with { Logger.log(msg) => msg * 10 }"#;
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(60));
+    assert_eq!(vm.jit_stats().jit_execution_count, 1);
+}
@@ -0,0 +1,65 @@
+//! Tests for calls between interpreted and JIT-compiled functions
+#![cfg(all(feature = "jit", target_arch = "x86_64"))]
+
+use fluentai_core::ast::NodeId;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_parser::parse;
+use fluentai_vm::{jit_integration::JitConfig, Compiler, CompilerOptions, Value, VM};
+use std::num::NonZeroU32;
+
+/// Compile without optimization and JIT-compile the given chunks after their first call
+fn jit_vm(source: &str, hot_chunks: &[usize]) -> VM {
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .compile(&parse(source).unwrap())
+        .unwrap();
+
+    let mut vm = VM::new(bytecode);
+    vm.set_jit_config(JitConfig {
+        call_threshold: 1,
+        ..Default::default()
+    });
+    vm.enable_usage_tracking();
+    for &chunk_id in hot_chunks {
+        let node_id = NodeId(NonZeroU32::new(chunk_id as u32).unwrap());
+        vm.register_chunk_mapping(chunk_id, node_id);
+    }
+    vm
+}
+
+#[test]
+fn test_interpreter_calls_jit_closure() {
+    // Chunk 1 is the closure; its second call runs the compiled code with `y` captured
+    let mut vm = jit_vm(
+        "{ let y = 10; let add = (x) => x + y; add(1) + add(2) }",
+        &[1],
+    );
+    assert_eq!(vm.run().unwrap(), Value::Integer(23));
+
+    let stats = vm.jit_stats();
+    assert_eq!(stats.functions_compiled, 1);
+    assert_eq!(stats.jit_execution_count, 1);
+}
+
+#[test]
+fn test_jit_calls_back_into_vm() {
+    // Only `twice` (chunk 1) is compiled; the `inc` closure it calls stays interpreted
+    let source =
+        "{ let twice = (f, x) => f(f(x)); let inc = (x) => x + 1; twice(inc, 1) + twice(inc, 10) }";
+    let mut vm = jit_vm(source, &[1]);
+    assert_eq!(vm.run().unwrap(), Value::Integer(15));
+    assert_eq!(vm.jit_stats().jit_execution_count, 1);
+}
+
+#[test]
+fn test_vm_errors_propagate_through_jit() {
+    // The second call to `apply` is compiled and calls a closure that fails in the VM
+    let source = "{ let apply = (f) => f(); apply(() => 1) + apply(() => 1 / 0) }";
+    let mut vm = jit_vm(source, &[1]);
+    let err = vm.run().unwrap_err();
+    assert!(err.to_string().contains("zero"), "{}", err);
+    assert_eq!(vm.jit_stats().jit_execution_count, 1);
+}
@@ -0,0 +1,282 @@
+//! Tests for native `while`/`for` loops with `break` and `continue`
+
+mod common;
+
+use common::{run, run_block, run_with};
+use fluentai_core::value::Value;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    Opcode,
+};
+
+/// How many times an opcode appears in the compiled main chunk
+fn count_opcode(code: &str, opcode: Opcode) -> usize {
+    let graph = fluentai_parser::parse(code).unwrap();
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options).compile(&graph).unwrap();
+    bytecode.chunks[bytecode.main_chunk]
+        .instructions
+        .iter()
+        .filter(|instruction| instruction.opcode == opcode)
+        .count()
+}
+
+#[test]
+fn test_while_loop_with_assignment() {
+    let code = r#"
+let i = 0;
+let total = 0;
+while (i < 5) {
+    total := total + i;
+    i := i + 1
+};
+total
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(10));
+}
+
+#[test]
+fn test_while_loop_evaluates_to_nil() {
+    let code = "let i = 0; while (i < 3) { i := i + 1 }";
+    assert_eq!(run_block(code).unwrap(), Value::Nil);
+}
+
+#[test]
+fn test_break_with_value() {
+    let code = r#"
+let i = 0;
+while (true) {
+    if (i * i > 50) { break i } else { nil };
+    i := i + 1
+}
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(8));
+}
+
+#[test]
+fn test_break_inside_let() {
+    let code = r#"
+let i = 0;
+while (true) {
+    let doubled = i * 2;
+    if (doubled > 6) { break doubled } else { nil };
+    i := i + 1
+}
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(8));
+}
+
+#[test]
+fn test_continue_skips_rest_of_body() {
+    let code = r#"
+let total = 0;
+for x in [1, 2, 3, 4, 5, 6] {
+    if (x % 2 == 0) { continue } else { nil };
+    total := total + x
+};
+total
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(9));
+}
+
+#[test]
+fn test_for_over_list() {
+    let code = r#"
+let total = 0;
+for x in [10, 20, 30] { total := total + x };
+total
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(60));
+}
+
+#[test]
+fn test_for_over_exclusive_range() {
+    let code = "let total = 0; for i in 0..5 { total := total + i }; total";
+    assert_eq!(run_block(code).unwrap(), Value::Integer(10));
+}
+
+#[test]
+fn test_for_over_inclusive_range() {
+    let code = "let total = 0; for i in 1..=5 { total := total + i }; total";
+    assert_eq!(run_block(code).unwrap(), Value::Integer(15));
+}
+
+#[test]
+fn test_for_with_destructuring_pattern() {
+    let code = r#"
+let total = 0;
+for Cons(head, rest) in [[1, 2], [3, 4], [5, 6]] { total := total + head };
+total
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(9));
+}
+
+#[test]
+fn test_for_stops_at_an_element_that_does_not_match() {
+    let code = r#"
+let count = 0;
+for 0 in [0, 0, 1, 0] { count := count + 1 };
+count
+"#;
+    let err = run_block(code).unwrap_err();
+    assert!(err.contains("no pattern matches 1"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_break_from_for_loop() {
+    let code = "for x in [3, 7, 12, 5] { if (x > 10) { break x } else { nil } }";
+    assert_eq!(run(code).unwrap(), Value::Integer(12));
+}
+
+#[test]
+fn test_nested_loops_break_innermost() {
+    let code = r#"
+let total = 0;
+for i in 0..3 {
+    for j in 0..10 {
+        if (j > i) { break } else { nil };
+        total := total + 1
+    }
+};
+total
+"#;
+    assert_eq!(run_block(code).unwrap(), Value::Integer(6));
+}
+
+#[test]
+fn test_loops_inside_functions() {
+    let code = r#"
+private function sum_to(n) {
+    let total = 0;
+    for i in 1..=n { total := total + i };
+    total
+}
+sum_to(10)
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(55));
+}
+
+#[test]
+fn test_loop_with_default_optimization() {
+    let code = r#"
+let i = 0;
+while (i < 10) {
+    if (i > 2) { break i * 10 } else { nil };
+    i := i + 1
+}
+"#;
+    let value = run_with(code, OptimizationLevel::Standard).unwrap();
+    assert_eq!(value, Value::Integer(30));
+}
+
+#[test]
+fn test_loops_with_basic_optimization() {
+    let basic = |code| run_with(code, OptimizationLevel::Basic);
+    assert_eq!(basic("while (false) { 1 }").unwrap(), Value::Nil);
+    assert_eq!(basic("for x in [1, 2, 3] { x }").unwrap(), Value::Nil);
+    assert_eq!(
+        basic("for i in 0..10 { if (i == 4) { break i * 2 } else { nil } }").unwrap(),
+        Value::Integer(8)
+    );
+    let code = r#"
+private function sum_to(n) {
+    let total = 0;
+    for i in 1..=n { total := total + i };
+    total
+}
+sum_to(10)
+"#;
+    assert_eq!(basic(code).unwrap(), Value::Integer(55));
+}
+
+#[test]
+fn test_break_outside_of_loop_is_an_error() {
+    let err = run("break 1").unwrap_err();
+    assert!(err.contains("outside of a loop"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_continue_does_not_cross_function_boundary() {
+    let code = "while (true) { (() => { continue })() }";
+    let err = run(code).unwrap_err();
+    assert!(err.contains("outside of a loop"), "unexpected error: {}", err);
+}
+
+
+#[test]
+fn test_break_inside_try_catch_pops_the_handler() {
+    let code = "while (true) { try { break 1 } catch (e) { 2 } }";
+    // One PopHandler on the try body's normal exit and one before the break
+    assert_eq!(count_opcode(code, Opcode::PushHandler), 1);
+    assert_eq!(count_opcode(code, Opcode::PopHandler), 2);
+    assert_eq!(run(code).unwrap(), Value::Integer(1));
+}
+
+#[test]
+fn test_break_inside_catch_does_not_pop_the_handler_again() {
+    let code = "while (true) { try { 1 } catch (e) { break 2 } }";
+    // The catch runs after the handler is popped, so only the try body pops it
+    assert_eq!(count_opcode(code, Opcode::PopHandler), 1);
+}
+
+#[test]
+fn test_break_inside_try_runs_finally() {
+    let code = r#"
+let runs = 0;
+let result = while (true) {
+    try { break 7 } catch (e) { 0 } finally { runs := runs + 1 }
+};
+[result, runs]
+"#;
+    assert_eq!(
+        run_block(code).unwrap(),
+        Value::List(vec![Value::Integer(7), Value::Integer(1)])
+    );
+}
+
+#[test]
+fn test_continue_inside_try_runs_finally_each_iteration() {
+    let code = r#"
+let total = 0;
+let cleanups = 0;
+for x in [1, 2, 3, 4] {
+    try {
+        if (x % 2 == 0) { continue } else { nil };
+        total := total + x
+    } catch (e) {
+        nil
+    } finally {
+        cleanups := cleanups + 1
+    }
+};
+[total, cleanups]
+"#;
+    assert_eq!(
+        run_block(code).unwrap(),
+        Value::List(vec![Value::Integer(4), Value::Integer(4)])
+    );
+    // One PopHandler on the try body's normal exit and one before the continue
+    assert_eq!(count_opcode(code, Opcode::PopHandler), 2);
+}
+
+#[test]
+fn test_break_out_of_handle_uninstalls_the_handler() {
+    let code = r#"
+private effect Probe {
+    function level() -> string;
+}
+private function main() {
+    let seen = while (true) {
+        handle { break perform Probe.level() } with { Probe.level() => "handled" }
+    };
+    seen + "/" + perform Probe.level()
+}
+main()
+"#;
+    let err = run(code).unwrap_err();
+    assert!(err.contains("Probe"), "unexpected error: {}", err);
+}
@@ -0,0 +1,110 @@
+//! Tests for user-defined macros expanded before compilation
+
+use fluentai_core::value::Value;
+use fluentai_metaprogramming::expand_macros;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    VM,
+};
+
+fn run(code: &str) -> Result<Value, String> {
+    let mut graph = fluentai_parser::parse(code).map_err(|e| e.to_string())?;
+    expand_macros(&mut graph).map_err(|e| e.to_string())?;
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .compile(&graph)
+        .map_err(|e| e.to_string())?;
+    VM::new(bytecode).run().map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_macro_substitutes_arguments() {
+    let code = "macro square(x) { x * x }\nsquare(3 + 4)";
+    assert_eq!(run(code).unwrap(), Value::Integer(49));
+}
+
+#[test]
+fn test_macro_arguments_are_not_evaluated_eagerly() {
+    let code = r#"
+macro my_unless(cond, body) { if (cond) { "skipped" } else { body } }
+my_unless(true, 1 / 0)
+"#;
+    assert_eq!(run(code).unwrap(), Value::String("skipped".to_string()));
+}
+
+#[test]
+fn test_macro_bindings_do_not_capture_caller_variables() {
+    let code = r#"
+macro add_ten(x) { let tmp = 10; tmp + x }
+private function main() {
+    let tmp = 1;
+    add_ten(tmp)
+}
+main()
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(11));
+}
+
+#[test]
+fn test_macro_parameter_names_a_binding() {
+    let code = r#"
+macro with_value(name, value, body) { let name = value; body }
+with_value(n, 20, n + 1)
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(21));
+}
+
+#[test]
+fn test_macros_expand_inside_other_macros() {
+    let code = r#"
+macro double(x) { x + x }
+macro quadruple(x) { double(double(x)) }
+quadruple(5)
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(20));
+}
+
+#[test]
+fn test_macro_arity_is_checked() {
+    let code = "macro square(x) { x * x }\nsquare(1, 2)";
+    let err = run(code).unwrap_err();
+    assert!(err.contains("expects 1 arguments"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_unexpanded_macro_is_a_compile_error() {
+    let graph = fluentai_parser::parse("macro id(x) { x }\nid(1)").unwrap();
+    let err = Compiler::new().compile(&graph).unwrap_err();
+    assert!(err.to_string().contains("must be expanded"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_macro_free_names_refer_to_the_definition_scope() {
+    let code = r#"
+private function helper() { 1 }
+macro m() { helper() }
+{ let helper = () => 99; m() }
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(1));
+
+    // The caller's binding still names its own value, in the call's arguments too
+    let code = r#"
+private function helper() { 1 }
+macro both(f) { f() + helper() }
+{ let helper = () => 99; both(helper) + helper() }
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(199));
+}
+
+#[test]
+fn test_dollar_in_template_string_is_kept() {
+    let code = r#"
+macro price(x) { "$" + x }
+price("5")
+"#;
+    assert_eq!(run(code).unwrap(), Value::String("$5".to_string()));
+}
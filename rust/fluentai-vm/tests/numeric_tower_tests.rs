@@ -0,0 +1,130 @@
+//! Tests for big integers, exact decimals and overflow promotion
+
+mod common;
+
+use common::{run, run_with};
+use fluentai_core::value::{BigInt, Decimal, Value};
+use fluentai_optimizer::OptimizationLevel;
+use std::str::FromStr;
+
+fn decimal(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+fn big(s: &str) -> BigInt {
+    BigInt::from_str(s).unwrap()
+}
+
+#[test]
+fn test_bigint_literal() {
+    match run("123456789012345678901234567890n").unwrap() {
+        Value::BigInt(n) => assert_eq!(n, big("123456789012345678901234567890")),
+        other => panic!("expected bigint, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_decimal_literal() {
+    match run("19.99d").unwrap() {
+        Value::Decimal(d) => assert_eq!(d, decimal("19.99")),
+        other => panic!("expected decimal, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_addition_overflow_promotes_to_bigint() {
+    match run("9223372036854775807 + 1").unwrap() {
+        Value::BigInt(n) => assert_eq!(n, big("9223372036854775808")),
+        other => panic!("expected bigint, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_multiplication_overflow_promotes_to_bigint() {
+    // 20! still fits in an i64; multiplying on up to 25! does not
+    let code = "2432902008176640000 * 21 * 22 * 23 * 24 * 25";
+    match run(code).unwrap() {
+        Value::BigInt(n) => assert_eq!(n, big("15511210043330985984000000")),
+        other => panic!("expected bigint, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_constant_folding_leaves_overflow_to_runtime() {
+    let result = run_with("9223372036854775807 * 2", OptimizationLevel::Standard).unwrap();
+    assert_eq!(result, Value::BigInt(big("18446744073709551614")));
+}
+
+#[test]
+fn test_small_results_stay_integers() {
+    assert!(matches!(run("40 + 2").unwrap(), Value::Integer(42)));
+}
+
+#[test]
+fn test_bigint_mixed_with_integer() {
+    assert_eq!(run("10n * 3 - 5").unwrap(), Value::BigInt(BigInt::from(25)));
+    assert_eq!(run("7n / 2").unwrap(), Value::BigInt(BigInt::from(3)));
+    assert_eq!(run("7n % 2").unwrap(), Value::BigInt(BigInt::from(1)));
+}
+
+#[test]
+fn test_decimal_arithmetic_is_exact() {
+    assert_eq!(run("0.1d + 0.2d").unwrap(), Value::Decimal(decimal("0.3")));
+    assert_eq!(run("0.1d + 0.2d == 0.3d").unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn test_decimal_mixed_with_integer() {
+    assert_eq!(run("19.99d * 3").unwrap(), Value::Decimal(decimal("59.97")));
+    assert_eq!(run("10d / 4").unwrap(), Value::Decimal(decimal("2.5")));
+}
+
+#[test]
+fn test_decimal_division_by_zero() {
+    let err = run("1.5d / 0").unwrap_err();
+    assert!(
+        err.contains("Division by zero"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn test_decimal_mixed_with_float_is_rejected() {
+    assert!(run("1.5d + 1.5").is_err());
+}
+
+#[test]
+fn test_exact_comparisons() {
+    assert_eq!(
+        run("100000000000000000000n > 5").unwrap(),
+        Value::Boolean(true)
+    );
+    assert_eq!(run("2.50d <= 2.5d").unwrap(), Value::Boolean(true));
+    assert_eq!(run("1.99d < 2").unwrap(), Value::Boolean(true));
+    assert_eq!(run("2.00d == 2").unwrap(), Value::Boolean(true));
+}
+
+#[test]
+fn test_decimal_literal_pattern() {
+    let code = r#"
+match(0.50d) {
+    0.5d => "half",
+    _ => "other"
+}
+"#;
+    assert_eq!(run(code).unwrap(), Value::String("half".to_string()));
+}
+
+#[test]
+fn test_portfolio_value_is_exact() {
+    let code = r#"
+private function main() {
+    let price = 178.25d;
+    let shares = 50;
+    price * shares + 142.30d * 10 + 195.96d * 25
+}
+main()
+"#;
+    assert_eq!(run(code).unwrap(), Value::Decimal(decimal("15234.50")));
+}
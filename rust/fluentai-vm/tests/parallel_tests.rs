@@ -0,0 +1,168 @@
+//! Tests for structured `parallel { ... }` blocks
+
+mod common;
+
+use common::{run, run_with};
+use fluentai_core::value::Value;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    safety::ResourceLimits,
+    security::SecurityPolicy,
+    VM,
+};
+
+fn compile(code: &str) -> VM {
+    let graph = fluentai_parser::parse(code).unwrap();
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        ..Default::default()
+    };
+    VM::new(Compiler::with_options(options).compile(&graph).unwrap())
+}
+
+#[test]
+fn test_parallel_returns_results_in_branch_order() {
+    let code = r#"parallel { 1 + 1; 2 * 3; "done" }"#;
+    assert_eq!(
+        run(code).unwrap(),
+        Value::List(vec![
+            Value::Integer(2),
+            Value::Integer(6),
+            Value::String("done".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_parallel_empty_block() {
+    assert_eq!(run("parallel { }").unwrap(), Value::List(vec![]));
+}
+
+#[test]
+fn test_parallel_branches_capture_locals() {
+    let code = r#"
+private function main() {
+    let base = 10;
+    parallel { base + 1; base + 2 }
+}
+main()
+"#;
+    assert_eq!(
+        run(code).unwrap(),
+        Value::List(vec![Value::Integer(11), Value::Integer(12)])
+    );
+}
+
+#[test]
+fn test_parallel_branches_call_functions() {
+    let code = r#"
+private function sum_to(n) {
+    let total = 0;
+    for i in 1..=n { total := total + i };
+    total
+}
+parallel { sum_to(10); sum_to(100) }
+"#;
+    assert_eq!(
+        run(code).unwrap(),
+        Value::List(vec![Value::Integer(55), Value::Integer(5050)])
+    );
+}
+
+#[test]
+fn test_parallel_failure_cancels_other_branches() {
+    // Without cancellation the looping branch would never finish
+    let code = "parallel { while (true) { nil }; 1 / 0 }";
+    let err = run(code).unwrap_err();
+    assert!(err.contains("Division by zero"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_parallel_failure_cancels_nested_branches() {
+    // The outer branch waits on a nested block whose branch would loop forever
+    let code = "parallel { parallel { while (true) { nil }; 2 }; 1 / 0 }";
+    let err = run(code).unwrap_err();
+    assert!(err.contains("Division by zero"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_parallel_failure_cancels_branch_waiting_to_receive() {
+    let code = r#"
+private function wait_for(ch) {
+    let value = ch.receive();
+    if (value == nil) { wait_for(ch) } else { value }
+}
+parallel { wait_for(channel()); 1 / 0 }
+"#;
+    let err = run(code).unwrap_err();
+    assert!(err.contains("Division by zero"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_parallel_with_default_optimization() {
+    let code = "parallel { 20 + 1; 40 + 2 }";
+    assert_eq!(
+        run_with(code, OptimizationLevel::Standard).unwrap(),
+        Value::List(vec![Value::Integer(21), Value::Integer(42)])
+    );
+}
+
+#[test]
+fn test_parallel_branches_see_enclosing_effect_handlers() {
+    let code = r#"
+private effect Probe {
+    function level() -> string;
+}
+handle {
+    parallel { perform Probe.level(); "plain" }
+} with { Probe.level() => "handled" }
+"#;
+    assert_eq!(
+        run(code).unwrap(),
+        Value::List(vec![
+            Value::String("handled".to_string()),
+            Value::String("plain".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_parallel_branches_share_the_instruction_limit() {
+    // Without the sandbox the branch would loop forever
+    let mut vm = compile("parallel { while (true) { nil } }");
+    vm.with_security_policy(SecurityPolicy {
+        max_instructions: 10_000,
+        ..SecurityPolicy::sandbox()
+    });
+    let err = vm.run().unwrap_err().to_string();
+    assert!(err.contains("Instruction limit exceeded"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_parallel_branches_count_call_depth_from_the_caller() {
+    let code = r#"
+private function down(n) {
+    if (n == 0) { 0 } else { down(n - 1) + 1 }
+}
+private function keep(value) { value }
+private function nest(n) {
+    if (n == 0) { parallel { down(15) } } else { keep(nest(n - 1)) }
+}
+nest(15)
+"#;
+    assert_eq!(
+        run(code).unwrap(),
+        Value::List(vec![Value::Integer(15)])
+    );
+
+    // Each stays within the limit on its own, but not stacked on the caller's frames
+    let mut vm = compile(code);
+    vm.with_security_policy(SecurityPolicy::default());
+    vm.set_resource_limits(ResourceLimits {
+        max_call_depth: 25,
+        ..ResourceLimits::default()
+    });
+    let err = vm.run().unwrap_err().to_string();
+    assert!(err.to_lowercase().contains("call stack"), "unexpected error: {}", err);
+}
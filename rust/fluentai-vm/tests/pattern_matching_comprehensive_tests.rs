@@ -53,6 +53,7 @@ fn test_match_integer_literal() -> Result<()> {
                 (Pattern::Literal(Literal::Integer(42)), result1),
                 (Pattern::Wildcard, result2),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -86,6 +87,7 @@ fn test_match_string_literal() -> Result<()> {
                 ),
                 (Pattern::Wildcard, result2),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -117,6 +119,7 @@ fn test_match_boolean_literals() -> Result<()> {
                 (Pattern::Literal(Literal::Boolean(true)), true_result),
                 (Pattern::Literal(Literal::Boolean(false)), false_result),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_true);
@@ -143,6 +146,7 @@ fn test_match_boolean_literals() -> Result<()> {
                 (Pattern::Literal(Literal::Boolean(true)), true_result),
                 (Pattern::Literal(Literal::Boolean(false)), false_result),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_false);
@@ -171,6 +175,7 @@ fn test_match_variable_binding() -> Result<()> {
         .add_node(Node::Match {
             expr: value,
             branches: vec![(Pattern::Variable("x".to_string()), x_var)],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -209,6 +214,7 @@ fn test_match_empty_list_with_nil() -> Result<()> {
                 ),
                 (Pattern::Wildcard, non_empty_result),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -266,6 +272,7 @@ fn test_match_non_empty_list_with_cons() -> Result<()> {
                     empty_result,
                 ),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -323,6 +330,7 @@ fn test_match_list_tail() -> Result<()> {
                     empty_result,
                 ),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -372,6 +380,7 @@ fn test_match_single_element_list() -> Result<()> {
                     xs_var,
                 ), // Return the tail
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -398,6 +407,7 @@ fn test_wildcard_pattern() -> Result<()> {
         .add_node(Node::Match {
             expr: value,
             branches: vec![(Pattern::Wildcard, default_result)],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -428,6 +438,7 @@ fn test_wildcard_with_other_patterns() -> Result<()> {
                 (Pattern::Literal(Literal::Integer(42)), specific_result),
                 (Pattern::Wildcard, default_result),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -468,6 +479,7 @@ fn test_multiple_pattern_branches() -> Result<()> {
                 (Pattern::Literal(Literal::Integer(3)), result3),
                 (Pattern::Wildcard, default),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -518,6 +530,7 @@ fn test_pattern_matching_with_standard_optimization() -> Result<()> {
                 ),
                 (Pattern::Wildcard, default),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -563,6 +576,7 @@ fn test_pattern_matching_with_aggressive_optimization() -> Result<()> {
                     non_empty,
                 ),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -585,6 +599,7 @@ fn test_match_with_no_branches_should_error() {
         .add_node(Node::Match {
             expr: value,
             branches: vec![], // No branches
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -635,6 +650,7 @@ fn test_match_list_with_wrong_pattern_order() -> Result<()> {
                     cons_result,
                 ),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
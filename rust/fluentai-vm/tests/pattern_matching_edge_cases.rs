@@ -72,6 +72,7 @@ fn test_match_deeply_nested_lists() -> Result<()> {
                 ),
                 (Pattern::Wildcard, default),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -122,6 +123,7 @@ fn test_match_list_of_different_types() -> Result<()> {
                 },
                 x_var,
             )],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -169,6 +171,7 @@ fn test_match_very_long_list() -> Result<()> {
                     xs_var,
                 ), // Return tail
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -224,6 +227,7 @@ fn test_match_with_shadowed_variables() -> Result<()> {
                 },
                 x_var,
             )],
+            must_match: false,
         })
         .expect("Failed to add node");
 
@@ -280,6 +284,7 @@ fn test_match_nil_before_cons() -> Result<()> {
                     cons_result,
                 ),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_empty);
@@ -324,6 +329,7 @@ fn test_match_nil_before_cons() -> Result<()> {
                     cons_result,
                 ),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_non_empty);
@@ -403,6 +409,7 @@ fn test_match_multiple_variable_bindings() -> Result<()> {
                 },
                 sum,
             )],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -427,6 +434,7 @@ fn test_match_multiple_variable_bindings() -> Result<()> {
                 },
                 x_only,
             )],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_simple);
@@ -467,6 +475,7 @@ fn test_match_with_nil_fallthrough() -> Result<()> {
                 ),
                 (Pattern::Wildcard, default_result),
             ],
+            must_match: false,
         })
         .expect("Failed to add node");
     graph.root_id = Some(match_node);
@@ -0,0 +1,65 @@
+//! Tests that compiled bytecode and runtime errors point at source locations
+
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    VMError, VM,
+};
+
+fn compile(code: &str) -> VM {
+    let graph = fluentai_parser::parse(code).unwrap();
+    let options = CompilerOptions {
+        optimization_level: OptimizationLevel::None,
+        debug_info: true,
+        ..Default::default()
+    };
+    let bytecode = Compiler::with_options(options)
+        .with_source_filename("test.flc".to_string())
+        .compile(&graph)
+        .unwrap();
+    VM::new(bytecode)
+}
+
+#[test]
+fn test_source_map_has_line_and_column() {
+    let vm = compile("let x = 1;\nx + 2");
+    let bytecode = vm.bytecode();
+    let chunk = &bytecode.chunks[bytecode.main_chunk];
+    let source_map = chunk.source_map.as_ref().expect("debug info requested");
+
+    let lines: Vec<u32> = (0..chunk.instructions.len())
+        .filter_map(|offset| source_map.get_location(offset))
+        .map(|location| location.line.expect("location without a line"))
+        .collect();
+    assert!(!lines.is_empty());
+    assert!(lines.contains(&1));
+    assert!(lines.contains(&2));
+}
+
+#[test]
+fn test_runtime_error_reports_source_location() {
+    let mut vm = compile("private function divide(a, b) {\n    a / b\n}\ndivide(1, 0)");
+    match vm.run() {
+        Err(VMError::DivisionByZero {
+            location: Some(location),
+            ..
+        }) => {
+            assert_eq!(location.file.as_deref(), Some("test.flc"));
+            assert_eq!((location.line, location.column), (2, 5));
+        }
+        other => panic!("expected division by zero with a location, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_stack_trace_locations() {
+    let mut vm = compile("private function fail(x) {\n    x / 0\n}\n\nfail(1)");
+    let _ = vm.run();
+    let trace = vm.build_stack_trace();
+    let lines: Vec<usize> = trace
+        .frames
+        .iter()
+        .filter_map(|frame| frame.location.as_ref().map(|loc| loc.line))
+        .collect();
+    assert_eq!(lines, vec![5, 2]);
+}
@@ -0,0 +1,179 @@
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    Value, VM,
+};
+
+fn compile_and_run_with(
+    code: &str,
+    optimization_level: OptimizationLevel,
+) -> Result<Value, Box<dyn std::error::Error>> {
+    let graph = fluentai_parser::parse(code)?;
+    let options = CompilerOptions {
+        optimization_level,
+        ..Default::default()
+    };
+    let compiler = Compiler::with_options(options);
+    let bytecode = compiler.compile(&graph)?;
+    let mut vm = VM::new(bytecode);
+    Ok(vm.run()?)
+}
+
+fn compile_and_run(code: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    compile_and_run_with(code, OptimizationLevel::None)
+}
+
+#[test]
+fn test_struct_construction() {
+    let code = r#"
+private struct Point { x: int, y: int }
+Point { x: 1, y: 2 }
+    "#;
+
+    let result = compile_and_run(code).unwrap();
+    assert_eq!(
+        result,
+        Value::Struct {
+            name: "Point".to_string(),
+            fields: vec![
+                ("x".to_string(), Value::Integer(1)),
+                ("y".to_string(), Value::Integer(2)),
+            ],
+        }
+    );
+}
+
+#[test]
+fn test_struct_fields_stored_in_declaration_order() {
+    let code = r#"
+private struct Point { x: int, y: int }
+Point { y: 2, x: 1 }
+    "#;
+
+    let result = compile_and_run(code).unwrap();
+    match result {
+        Value::Struct { fields, .. } => {
+            let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, vec!["x", "y"]);
+        }
+        other => panic!("Expected struct, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_struct_field_access() {
+    let code = r#"
+private struct Point { x: int, y: int }
+let p = Point { x: 3, y: 4 };
+p.x * p.x + p.y * p.y
+    "#;
+
+    let result = compile_and_run(code).unwrap();
+    assert_eq!(result, Value::Integer(25));
+}
+
+#[test]
+fn test_struct_field_access_optimized() {
+    let code = r#"
+private struct Point { x: int, y: int }
+let p = Point { x: 3, y: 4 };
+p.x + p.y
+    "#;
+
+    let result = compile_and_run_with(code, OptimizationLevel::Standard).unwrap();
+    assert_eq!(result, Value::Integer(7));
+}
+
+#[test]
+fn test_nested_field_access() {
+    let code = r#"
+private struct Point { x: int, y: int }
+private struct Line { start: Point, end: Point }
+let line = Line { start: Point { x: 1, y: 2 }, end: Point { x: 5, y: 7 } };
+line.end.y - line.start.y
+    "#;
+
+    let result = compile_and_run(code).unwrap();
+    assert_eq!(result, Value::Integer(5));
+}
+
+#[test]
+fn test_field_access_in_function() {
+    let code = r#"
+private struct Point { x: int, y: int }
+private function sum(p) {
+    p.x + p.y
+}
+sum(Point { x: 10, y: 20 })
+    "#;
+
+    let result = compile_and_run(code).unwrap();
+    assert_eq!(result, Value::Integer(30));
+}
+
+#[test]
+fn test_struct_update() {
+    let code = r#"
+private struct Point { x: int, y: int }
+let p = Point { x: 1, y: 2 };
+let q = Point { x: 10, ..p };
+q.x + q.y + p.x
+    "#;
+
+    let result = compile_and_run(code).unwrap();
+    assert_eq!(result, Value::Integer(13));
+}
+
+#[test]
+fn test_struct_missing_field_error() {
+    let code = r#"
+private struct Point { x: int, y: int }
+Point { x: 1 }
+    "#;
+
+    let result = compile_and_run(code);
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Missing field 'y'"));
+}
+
+#[test]
+fn test_struct_unknown_field_error() {
+    let code = r#"
+private struct Point { x: int, y: int }
+Point { x: 1, y: 2, z: 3 }
+    "#;
+
+    let result = compile_and_run(code);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("no field 'z'"));
+}
+
+#[test]
+fn test_struct_update_unknown_field_error() {
+    let code = r#"
+private struct Point { x: int, y: int }
+let p = Point { x: 1, y: 2 };
+Point { z: 10, ..p }
+    "#;
+
+    let result = compile_and_run(code);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("no field 'z'"));
+}
+
+#[test]
+fn test_struct_layout_beyond_packed_range_error() {
+    // The layout constant's index must fit in the upper half of MakeStruct's argument
+    let strings: Vec<String> = (0..70_000).map(|i| format!("\"s{}\";", i)).collect();
+    let code = format!(
+        "private struct Point {{ x: int, y: int }}\n{}\nPoint {{ x: 1, y: 2 }}",
+        strings.join("\n")
+    );
+
+    let result = compile_and_run(&code);
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("too many fields or constants"));
+}
@@ -0,0 +1,149 @@
+//! Tests for trait declarations, impls and method dispatch
+
+mod common;
+
+use common::run;
+use fluentai_bytecode::{Bytecode, BytecodeChunk, Instruction, Opcode};
+use fluentai_vm::{compiler::Compiler, Value, VM};
+
+const SHAPES: &str = r#"
+private struct Circle { r: int }
+private struct Rect { w: int, h: int }
+private trait Area {
+    function area(self) -> int;
+    function scaled(self, k: int) -> int;
+}
+Circle as Area {
+    private function area(self) { 3 * self.r * self.r }
+    private function scaled(self, k) { k * self.area() }
+}
+Rect as Area {
+    private function area(self) { self.w * self.h }
+    private function scaled(self, k) { k * self.area() }
+}
+"#;
+
+#[test]
+fn test_method_dispatch_on_struct_type() {
+    let code = format!(
+        "{}\nprivate function main() {{ Circle {{ r: 2 }}.area() + Rect {{ w: 3, h: 4 }}.area() }}\nmain()",
+        SHAPES
+    );
+    assert_eq!(run(&code).unwrap(), Value::Integer(24));
+}
+
+#[test]
+fn test_method_with_arguments_and_self_call() {
+    let code = format!(
+        "{}\nprivate function main() {{ let r = Rect {{ w: 2, h: 5 }}; r.scaled(3) }}\nmain()",
+        SHAPES
+    );
+    assert_eq!(run(&code).unwrap(), Value::Integer(30));
+}
+
+#[test]
+fn test_method_dispatch_on_mixed_list_elements() {
+    let code = format!(
+        "{}\nprivate function main() {{ let s = [Circle {{ r: 1 }}, Rect {{ w: 2, h: 2 }}, Circle {{ r: 2 }}]; s.head().area() + s.tail().head().area() + s.tail().tail().head().area() }}\nmain()",
+        SHAPES
+    );
+    assert_eq!(run(&code).unwrap(), Value::Integer(19));
+}
+
+#[test]
+fn test_method_dispatch_on_enum_tags() {
+    let code = r#"
+private enum Shape { Square(int), Triangle(int, int) }
+private trait Describe { function sides(self) -> int; }
+Shape as Describe {
+    private function sides(self) {
+        match self {
+            Square(_) => 4,
+            Triangle(_, _) => 3
+        }
+    }
+}
+Square(2).sides() * 10 + Triangle(1, 2).sides()
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(43));
+}
+
+#[test]
+fn test_method_dispatch_on_primitive() {
+    let code = r#"
+private trait Double { function double(self); }
+Int as Double { private function double(self) { self * 2 } }
+String as Double { private function double(self) { self + self } }
+[21.double(), "ab".double()]
+"#;
+    assert_eq!(
+        run(code).unwrap(),
+        Value::List(vec![Value::Integer(42), Value::String("abab".to_string())])
+    );
+}
+
+#[test]
+fn test_missing_impl_is_runtime_error() {
+    let code = format!("{}\n42.area()", SHAPES);
+    let err = run(&code).unwrap_err();
+    assert!(
+        err.contains("No implementation of method 'area' for type Int"),
+        "unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn test_method_dispatch_with_optimization() {
+    let code = format!("{}\nRect {{ w: 3, h: 4 }}.scaled(2)", SHAPES);
+    let graph = fluentai_parser::parse(&code).unwrap();
+    let bytecode = Compiler::new().compile(&graph).unwrap();
+    assert_eq!(VM::new(bytecode).run().unwrap(), Value::Integer(24));
+}
+
+#[test]
+fn test_direct_call_with_method_name_is_not_dispatched() {
+    // Only `x.length()` dispatches; `length(xs)` is still the list builtin
+    let code = r#"
+private struct Stack { size: int }
+private trait Sized { function length(self) -> int; }
+Stack as Sized { private function length(self) { self.size } }
+[Stack { size: 7 }.length(), length([1, 2, 3])]
+"#;
+    assert_eq!(
+        run(code).unwrap(),
+        Value::List(vec![Value::Integer(7), Value::Integer(3)])
+    );
+}
+
+#[test]
+fn test_variant_impl_takes_precedence_over_enum_impl() {
+    let code = r#"
+private enum Shape { Square(int), Triangle(int) }
+private trait Named { function name(self) -> string; }
+Square as Named { private function name(self) { "square" } }
+Shape as Named { private function name(self) { "shape" } }
+[Square(1).name(), Triangle(1).name()]
+"#;
+    assert_eq!(
+        run(code).unwrap(),
+        Value::List(vec![
+            Value::String("square".to_string()),
+            Value::String("shape".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn test_call_method_without_receiver_is_error() {
+    let mut chunk = BytecodeChunk::new(Some("test".to_string()));
+    let method = chunk.add_constant(Value::String("area".to_string()));
+    chunk.add_instruction(Instruction::with_arg(Opcode::CallMethod, method << 16));
+    chunk.add_instruction(Instruction::new(Opcode::Halt));
+    let mut bytecode = Bytecode::new();
+    bytecode.add_chunk(chunk);
+    bytecode.main_chunk = 0;
+
+    let err = VM::new(bytecode).run().unwrap_err().to_string();
+    assert!(err.contains("without a receiver"), "unexpected error: {}", err);
+}
@@ -0,0 +1,117 @@
+//! Tests for tuple expressions and tuple patterns
+
+mod common;
+
+use common::{run, run_block, run_with};
+use fluentai_core::value::Value;
+use fluentai_optimizer::OptimizationLevel;
+
+#[test]
+fn test_tuple_expression() {
+    assert_eq!(
+        run(r#"(1, "two", 3.0)"#).unwrap(),
+        Value::Tuple(vec![
+            Value::Integer(1),
+            Value::String("two".to_string()),
+            Value::Float(3.0)
+        ])
+    );
+}
+
+#[test]
+fn test_parentheses_without_comma_are_grouping() {
+    assert_eq!(run("(1 + 2) * 3").unwrap(), Value::Integer(9));
+    assert_eq!(
+        run("(42,)").unwrap(),
+        Value::Tuple(vec![Value::Integer(42)])
+    );
+}
+
+#[test]
+fn test_tuple_is_not_a_list() {
+    assert_eq!(run("(1, 2) == (1, 2)").unwrap(), Value::Boolean(true));
+    assert_eq!(run("(1, 2) == (2, 1)").unwrap(), Value::Boolean(false));
+    assert_ne!(
+        run("(1, 2)").unwrap(),
+        Value::List(vec![Value::Integer(1), Value::Integer(2)])
+    );
+}
+
+#[test]
+fn test_tuple_match() {
+    let code = r#"
+match((0, "origin")) {
+    (0, name) => name,
+    (_, _) => "elsewhere"
+}
+"#;
+    assert_eq!(run(code).unwrap(), Value::String("origin".to_string()));
+}
+
+#[test]
+fn test_tuple_pattern_checks_arity() {
+    let code = r#"
+match((1, 2, 3)) {
+    (a, b) => "pair",
+    (a, b, c) => "triple",
+    _ => "other"
+}
+"#;
+    assert_eq!(run(code).unwrap(), Value::String("triple".to_string()));
+}
+
+#[test]
+fn test_tuple_pattern_does_not_match_list() {
+    let code = r#"
+match([1, 2]) {
+    (a, b) => "tuple",
+    _ => "list"
+}
+"#;
+    assert_eq!(run(code).unwrap(), Value::String("list".to_string()));
+}
+
+#[test]
+fn test_let_tuple_destructuring() {
+    let code = "let (q, r) = (17 / 5, 17 % 5); q * 10 + r";
+    assert_eq!(run_block(code).unwrap(), Value::Integer(32));
+}
+
+#[test]
+fn test_function_returning_tuple() {
+    let code = r#"
+private function min_max(a, b) {
+    if (a < b) { (a, b) } else { (b, a) }
+}
+private function main() {
+    let (lo, hi) = min_max(9, 4);
+    hi - lo
+}
+main()
+"#;
+    assert_eq!(run(code).unwrap(), Value::Integer(5));
+}
+
+#[test]
+fn test_lambda_tuple_parameter() {
+    let code = "let swap = ((a, b)) => (b, a); swap((1, 2))";
+    assert_eq!(
+        run_block(code).unwrap(),
+        Value::Tuple(vec![Value::Integer(2), Value::Integer(1)])
+    );
+}
+
+#[test]
+fn test_nested_tuples_with_optimization() {
+    let code = "match(((1, 2), 3)) { ((a, b), c) => a + b + c }";
+    assert_eq!(
+        run_with(code, OptimizationLevel::Standard).unwrap(),
+        Value::Integer(6)
+    );
+}
+
+#[test]
+fn test_tuple_display() {
+    assert_eq!(run(r#"(1, "a")"#).unwrap().to_string(), r#"(1, "a")"#);
+    assert_eq!(run("(1,)").unwrap().to_string(), "(1,)");
+}
@@ -0,0 +1,306 @@
+//! Tests for `.fabc` round trips and the bytecode verifier
+
+use fluentai_bytecode::{Bytecode, BytecodeChunk, Instruction, Opcode};
+use fluentai_core::value::Value;
+use fluentai_optimizer::OptimizationLevel;
+use fluentai_vm::{
+    compiler::{Compiler, CompilerOptions},
+    verifier::{verify, VerifyError},
+    VM,
+};
+
+fn compile(code: &str, optimization_level: OptimizationLevel) -> Bytecode {
+    let graph = fluentai_parser::parse(code).unwrap();
+    let options = CompilerOptions {
+        optimization_level,
+        ..Default::default()
+    };
+    Compiler::with_options(options).compile(&graph).expect(code)
+}
+
+/// Build a single-chunk program from raw instructions
+fn program(instructions: Vec<Instruction>, constants: Vec<Value>) -> Bytecode {
+    let mut chunk = BytecodeChunk::new(Some("main".to_string()));
+    chunk.instructions = instructions;
+    chunk.constants = constants;
+    let mut bytecode = Bytecode::new();
+    bytecode.add_chunk(chunk);
+    bytecode
+}
+
+const PROGRAMS: &[&str] = &[
+    "1 + 2 * 3",
+    r#"let greet = (name) => "hi " + name; greet("bob")"#,
+    "let x = 10; let add = (y) => x + y; add(5)",
+    "match([1, 2, 3]) { [first, ...rest] => first, _ => 0 }",
+    "match((1, 2)) { (a, 0) => a, (a, b) => a + b }",
+    "if (3 > 2) { 1.5 } else { 2.5 }",
+    "let total = 0; for x in [1, 2, 3] { total = total + x }; total",
+    "12345678901234567890n + 1",
+    "let i = 0; while (i < 10) { i := i + 1; if (i < 3) { continue } else { nil }; break i * 10 }",
+    "parallel { 1 + 1; 2 * 3; parallel { 4; 5 } }",
+    r#"private effect Probe { function level() -> string; }
+handle { perform Probe.level() + "!" } with { Probe.level() => "debug" }"#,
+    "let (q, r) = (17 / 5, 17 % 5); q * 10 + r",
+    "let pair = (1, (2, 3)); match(pair) { (a, (b, c)) => a + b + c, _ => 0 }",
+];
+
+/// Programs the optimizer cannot rewrite yet (try blocks, actors and function
+/// bodies with local bindings), checked as the compiler emits them
+const UNOPTIMIZED_PROGRAMS: &[&str] = &[
+    "try { 1 } catch (e) { 2 } finally { 3 }",
+    "try { 10 / 2 } catch (e) { e }",
+    r#"private function main() {
+    let i = 0;
+    while (true) {
+        i := i + 1;
+        let square = i * i;
+        if (square < 9) { continue } else { nil };
+        break square + 1
+    }
+}
+main()"#,
+    r#"private function main() {
+    let total = 0;
+    for (a, b) in [(1, 2), (3, 4), (5, 6)] {
+        if (a == 5) { break } else { nil };
+        total := total + a * b
+    };
+    total
+}
+main()"#,
+    r#"private function main() {
+    let n = 0;
+    while (n < 5) {
+        n := n + 1;
+        try {
+            if (n == 2) { continue } else { nil };
+            if (n == 4) { break n } else { nil }
+        } catch (e) {
+            0
+        } finally {
+            nil
+        }
+    }
+}
+main()"#,
+    r#"private effect Probe { function level() -> string; }
+private function main() {
+    let seen = while (true) { handle { break perform Probe.level() } with { Probe.level() => "x" } };
+    seen
+}
+main()"#,
+    r#"private struct Tally { count: int, last: int }
+private actor Counter {
+    state: Tally = Tally { count: 0, last: 0 };
+    private handle message(msg: int) {
+        self.count := self.count + 1;
+        self.last := msg;
+        "ok"
+    }
+}
+Counter"#,
+    r#"private struct Point { x: int, y: int }
+private function main() {
+    let p = Point { x: 1, y: 2 };
+    let q = Point { x: 10, ..p };
+    q.x + q.y
+}
+main()"#,
+];
+
+/// Each program with every optimization level it supports
+fn cases() -> impl Iterator<Item = (&'static str, OptimizationLevel)> {
+    let optimized = PROGRAMS.iter().flat_map(|code| {
+        [OptimizationLevel::None, OptimizationLevel::Standard]
+            .into_iter()
+            .map(move |level| (*code, level))
+    });
+    let unoptimized = UNOPTIMIZED_PROGRAMS
+        .iter()
+        .map(|code| (*code, OptimizationLevel::None));
+    optimized.chain(unoptimized)
+}
+
+#[test]
+fn test_compiled_programs_verify() {
+    for (code, level) in cases() {
+        let bytecode = compile(code, level);
+        if let Err(e) = verify(&bytecode) {
+            panic!("{} failed verification at {:?}: {}", code, level, e);
+        }
+    }
+}
+
+#[test]
+fn test_round_trip_runs_the_same() {
+    for (code, level) in cases() {
+        let bytecode = compile(code, level);
+        let expected = VM::new(bytecode.clone()).run().map_err(|e| e.to_string());
+
+        let loaded = Bytecode::from_bytes(&bytecode.to_bytes().unwrap()).unwrap();
+        verify(&loaded).unwrap();
+        let actual = VM::new(loaded).run().map_err(|e| e.to_string());
+        assert_eq!(actual, expected, "{} at {:?}", code, level);
+    }
+}
+
+#[test]
+fn test_rejects_jump_outside_chunk() {
+    let bytecode = program(
+        vec![
+            Instruction::with_arg(Opcode::Jump, 7),
+            Instruction::new(Opcode::Halt),
+        ],
+        vec![],
+    );
+    assert_eq!(
+        verify(&bytecode),
+        Err(VerifyError::InvalidJumpTarget {
+            chunk: 0,
+            offset: 0,
+            target: 7,
+            len: 2
+        })
+    );
+}
+
+#[test]
+fn test_rejects_missing_constants_and_chunks() {
+    let bytecode = program(
+        vec![
+            Instruction::with_arg(Opcode::PushConst, 1),
+            Instruction::new(Opcode::Halt),
+        ],
+        vec![Value::Integer(1)],
+    );
+    assert!(matches!(
+        verify(&bytecode),
+        Err(VerifyError::InvalidConstantIndex {
+            index: 1,
+            len: 1,
+            ..
+        })
+    ));
+
+    // MakeClosure keeps the chunk id in the upper 16 bits
+    let bytecode = program(
+        vec![
+            Instruction::with_arg(Opcode::MakeClosure, 3 << 16),
+            Instruction::new(Opcode::Halt),
+        ],
+        vec![],
+    );
+    assert!(matches!(
+        verify(&bytecode),
+        Err(VerifyError::InvalidChunkReference { target: 3, .. })
+    ));
+
+    let bytecode = program(
+        vec![Instruction::new(Opcode::Halt)],
+        vec![Value::Function {
+            chunk_id: 9,
+            env: vec![],
+        }],
+    );
+    assert!(matches!(
+        verify(&bytecode),
+        Err(VerifyError::InvalidChunkReference { target: 9, .. })
+    ));
+}
+
+#[test]
+fn test_rejects_method_call_without_receiver() {
+    // CallMethod keeps the method name constant in the upper 16 bits and the argument count below
+    let bytecode = program(
+        vec![
+            Instruction::with_arg(Opcode::CallMethod, 0),
+            Instruction::new(Opcode::Halt),
+        ],
+        vec![Value::String("show".to_string())],
+    );
+    assert_eq!(
+        verify(&bytecode),
+        Err(VerifyError::MissingReceiver {
+            chunk: 0,
+            offset: 0
+        })
+    );
+
+    let bytecode = program(
+        vec![
+            Instruction::new(Opcode::PushInt1),
+            Instruction::with_arg(Opcode::CallMethod, 1),
+            Instruction::new(Opcode::Halt),
+        ],
+        vec![Value::String("show".to_string())],
+    );
+    assert_eq!(verify(&bytecode), Ok(()));
+}
+
+#[test]
+fn test_rejects_stack_underflow() {
+    let bytecode = program(
+        vec![
+            Instruction::new(Opcode::PushInt1),
+            Instruction::new(Opcode::Add),
+            Instruction::new(Opcode::Halt),
+        ],
+        vec![],
+    );
+    assert_eq!(
+        verify(&bytecode),
+        Err(VerifyError::StackUnderflow {
+            chunk: 0,
+            offset: 1,
+            opcode: Opcode::Add,
+            needed: 2,
+            depth: 1
+        })
+    );
+
+    // A chunk's arguments are on the stack when it starts
+    let mut bytecode = program(
+        vec![
+            Instruction::new(Opcode::Add),
+            Instruction::new(Opcode::Return),
+        ],
+        vec![],
+    );
+    bytecode.chunks[0].arity = 2;
+    assert_eq!(verify(&bytecode), Ok(()));
+}
+
+#[test]
+fn test_rejects_inconsistent_branches() {
+    // The fall-through path pushes a value the jump path does not
+    let bytecode = program(
+        vec![
+            Instruction::new(Opcode::PushTrue),
+            Instruction::with_arg(Opcode::JumpIfNot, 3),
+            Instruction::new(Opcode::PushInt1),
+            Instruction::new(Opcode::Halt),
+        ],
+        vec![],
+    );
+    assert!(matches!(
+        verify(&bytecode),
+        Err(VerifyError::InconsistentStackDepth { offset: 3, .. })
+    ));
+}
+
+#[test]
+fn test_rejects_running_off_the_end() {
+    let bytecode = program(vec![Instruction::new(Opcode::PushNil)], vec![]);
+    assert_eq!(
+        verify(&bytecode),
+        Err(VerifyError::MissingReturn { chunk: 0 })
+    );
+
+    let mut bytecode = program(vec![Instruction::new(Opcode::Halt)], vec![]);
+    bytecode.main_chunk = 1;
+    assert!(matches!(
+        verify(&bytecode),
+        Err(VerifyError::MissingMainChunk { main: 1, chunks: 1 })
+    ));
+}
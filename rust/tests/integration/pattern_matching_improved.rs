@@ -45,6 +45,7 @@ fn simple_value_matching() -> Result<()> {
             (Pattern::Literal(Literal::Integer(3)), r3),
             (Pattern::Wildcard, default),
         ],
+        must_match: false,
     })?;
     println!("    Required: Multiple Pattern::Literal constructions");
     println!("    Required: Manual Pattern::Wildcard for default");
@@ -110,6 +111,7 @@ fn list_pattern_matching() -> Result<()> {
                 ],
             }, head_var),
         ],
+        must_match: false,
     })?;
     println!("    Required: Manual Pattern::Constructor creation");
     println!("    Required: String allocation for pattern names");
@@ -36,6 +36,7 @@ fn main() {
             (Pattern::Literal(Literal::Integer(42)), x_var),  // Branch references x_var
             (Pattern::Wildcard, lit_1),
         ],
+        must_match: false,
     }).expect("Failed to add node");
     println!("  - Created match node with ID: {}", match_node.0.get());
     